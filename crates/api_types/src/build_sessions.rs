@@ -0,0 +1,191 @@
+use common::hash::HexBytes;
+use serde::{Deserialize, Serialize};
+
+/// Build session creation response.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BuildSessionCreateResponse {
+    /// Build session identifier.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_database_identifier")
+    )]
+    pub id: i64,
+}
+
+/// A single build session log entry.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LogEntry {
+    /// Log entry identifier.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_database_identifier")
+    )]
+    pub id: i64,
+
+    /// Log entry text value.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_log_entry")
+    )]
+    pub text: String,
+
+    /// Count of identical consecutive log entries collapsed into this one.
+    ///
+    /// Servers that do not collapse log entries omit this value.
+    #[serde(default = "default_log_count")]
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_log_repeat_count")
+    )]
+    pub count: i64,
+}
+
+/// Default log entry repetition count, used with servers that do not collapse log entries.
+fn default_log_count() -> i64 {
+    1
+}
+
+/// Build session log entries response.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BuildSessionLogsResponse {
+    /// Log entries.
+    pub logs: Vec<LogEntry>,
+
+    /// Cursor of the next page, if there are more log entries available.
+    ///
+    /// Only returned if the `after` query parameter was provided.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_log_position")
+    )]
+    pub next_cursor: Option<i64>,
+}
+
+/// Builder signature of build session artifacts.
+///
+/// Signed message is a concatenation of the code hash and the JSON metadata hash.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ArtifactSignature {
+    /// Identifier of the builder signing key.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_signing_key_id")
+    )]
+    pub key_id: String,
+
+    /// sr25519 public key of the builder signing key.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_signing_public_key")
+    )]
+    pub public_key: HexBytes<32>,
+
+    /// sr25519 signature of build artifacts.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_artifact_signature")
+    )]
+    pub signature: HexBytes<64>,
+}
+
+/// Current builder signing key response.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SigningKeyResponse {
+    /// Signing key identifier.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_signing_key_id")
+    )]
+    pub key_id: String,
+
+    /// sr25519 public key used to verify artifact signatures.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_signing_public_key")
+    )]
+    pub public_key: HexBytes<32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use common::hash::HexBytes;
+    use serde_json::json;
+
+    use super::{
+        ArtifactSignature, BuildSessionCreateResponse, BuildSessionLogsResponse, LogEntry,
+    };
+
+    #[test]
+    fn create_round_trip() {
+        let value = json!({ "id": 1 });
+
+        let response: BuildSessionCreateResponse = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!(response, BuildSessionCreateResponse { id: 1 });
+        assert_eq!(serde_json::to_value(response).unwrap(), value);
+    }
+
+    #[test]
+    fn logs_round_trip() {
+        let value = json!({
+            "logs": [{ "id": 1, "text": "Compiling\n", "count": 2 }],
+            "next_cursor": 1,
+        });
+
+        let response: BuildSessionLogsResponse = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!(
+            response,
+            BuildSessionLogsResponse {
+                logs: vec![LogEntry {
+                    id: 1,
+                    text: String::from("Compiling\n"),
+                    count: 2,
+                }],
+                next_cursor: Some(1),
+            }
+        );
+        assert_eq!(serde_json::to_value(response).unwrap(), value);
+    }
+
+    #[test]
+    fn logs_defaults() {
+        let response: BuildSessionLogsResponse =
+            serde_json::from_value(json!({ "logs": [{ "id": 1, "text": "" }] })).unwrap();
+
+        assert_eq!(response.logs[0].count, 1);
+        assert_eq!(response.next_cursor, None);
+
+        assert_eq!(
+            serde_json::to_value(response).unwrap(),
+            json!({ "logs": [{ "id": 1, "text": "", "count": 1 }] })
+        );
+    }
+
+    #[test]
+    fn signature_round_trip() {
+        let value = json!({
+            "key_id": "0102030405060708",
+            "public_key": "01".repeat(32),
+            "signature": "02".repeat(64),
+        });
+
+        let signature: ArtifactSignature = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!(
+            signature,
+            ArtifactSignature {
+                key_id: String::from("0102030405060708"),
+                public_key: HexBytes([1; 32]),
+                signature: HexBytes([2; 64]),
+            }
+        );
+        assert_eq!(serde_json::to_value(signature).unwrap(), value);
+    }
+}
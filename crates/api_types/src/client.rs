@@ -0,0 +1,113 @@
+use std::fmt;
+
+use reqwest::{RequestBuilder, StatusCode};
+use serde::Deserialize;
+
+use crate::{
+    build_sessions::{ArtifactSignature, BuildSessionLogsResponse},
+    contracts::ContractData,
+};
+
+/// Subset of the build session details response, that contains the artifact signature.
+#[derive(Deserialize)]
+struct SignedBuildSessionDetails {
+    /// Builder signature of build artifacts.
+    #[serde(default)]
+    signature: Option<ArtifactSignature>,
+}
+
+/// Typed API server client.
+///
+/// A single [`reqwest::Client`] is reused for all requests, which allows
+/// to reuse connections and TLS sessions between them.
+#[derive(Clone)]
+pub struct Client {
+    /// Underlying HTTP client.
+    client: reqwest::Client,
+
+    /// API server base URL.
+    server_path: String,
+
+    /// Authentication token passed with each request, if any.
+    token: Option<String>,
+}
+
+impl Client {
+    /// Create new [`Client`] using the provided API server base URL
+    /// and an optional authentication token.
+    pub fn new(server_path: impl Into<String>, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            server_path: server_path.into(),
+            token,
+        }
+    }
+
+    /// Get details of a contract deployed at the provided address.
+    ///
+    /// Returns [`None`] if the contract is unknown to the API server.
+    pub async fn contract_details(
+        &self,
+        address: &str,
+    ) -> Result<Option<ContractData>, reqwest::Error> {
+        let response = self.get(&format!("contracts/{address}")).send().await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        response.error_for_status()?.json().await.map(Some)
+    }
+
+    /// Get logs of a build session identified by either numeric identifier or code hash.
+    ///
+    /// Only log entries with identifiers greater than `position` are returned.
+    pub async fn build_session_logs(
+        &self,
+        id: impl fmt::Display,
+        position: i64,
+    ) -> Result<BuildSessionLogsResponse, reqwest::Error> {
+        self.get(&format!("buildSessions/logs/{id}"))
+            .query(&[("position", position)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Get the builder signature of artifacts of the latest build session
+    /// with the provided hex-encoded code hash.
+    ///
+    /// Returns [`None`] if the build session is unknown to the API server
+    /// or if its artifacts were not signed.
+    pub async fn artifact_signature(
+        &self,
+        code_hash: &str,
+    ) -> Result<Option<ArtifactSignature>, reqwest::Error> {
+        let response = self
+            .get(&format!("buildSessions/details/{code_hash}"))
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(response
+            .error_for_status()?
+            .json::<SignedBuildSessionDetails>()
+            .await?
+            .signature)
+    }
+
+    /// Create a GET request to the provided API server route.
+    fn get(&self, route: &str) -> RequestBuilder {
+        let request = self.client.get(format!("{}/{route}", self.server_path));
+
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
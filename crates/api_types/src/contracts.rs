@@ -0,0 +1,101 @@
+use common::hash::Hash32;
+use serde::{Deserialize, Serialize};
+
+/// Contract details response.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ContractData {
+    /// Name of the related node.
+    #[cfg_attr(feature = "schema", schemars(example = "crate::schema::example_node"))]
+    pub node: String,
+
+    /// Identifier of the related node.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_database_identifier")
+    )]
+    pub node_id: i64,
+
+    /// Human-readable network name of the related node.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_node_display_name")
+    )]
+    pub node_display_name: Option<String>,
+
+    /// Related code hash.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_hex_hash")
+    )]
+    pub code_hash: Hash32,
+
+    /// Contract owner.
+    ///
+    /// This field is only available is the contract
+    /// was discovered after the initial activation of an event server.
+    ///
+    /// Address is encoded using the SS58 prefix of the related node.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_account")
+    )]
+    pub owner: Option<String>,
+
+    /// Timestamp of a block in which the contract instantiation was discovered.
+    ///
+    /// This field is only available if the contract
+    /// was discovered after the initial activation of an event server.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_timestamp")
+    )]
+    pub first_seen_at: Option<i64>,
+
+    /// Number of a block in which the contract instantiation was discovered.
+    ///
+    /// This field is only available if the contract
+    /// was discovered after block numbers were recorded by an event server.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_block_number")
+    )]
+    pub first_seen_block: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use common::hash::Hash32;
+    use serde_json::json;
+
+    use super::ContractData;
+
+    #[test]
+    fn round_trip() {
+        let value = json!({
+            "node": "alephzero",
+            "node_id": 1,
+            "node_display_name": "Aleph Zero",
+            "code_hash": "00".repeat(32),
+            "owner": null,
+            "first_seen_at": 1672531200,
+            "first_seen_block": null,
+        });
+
+        let data: ContractData = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!(
+            data,
+            ContractData {
+                node: String::from("alephzero"),
+                node_id: 1,
+                node_display_name: Some(String::from("Aleph Zero")),
+                code_hash: Hash32::from([0; 32]),
+                owner: None,
+                first_seen_at: Some(1672531200),
+                first_seen_block: None,
+            }
+        );
+        assert_eq!(serde_json::to_value(data).unwrap(), value);
+    }
+}
@@ -0,0 +1,33 @@
+//! # API server request and response types
+//!
+//! This crate contains JSON request and response bodies shared between the API server
+//! and its Rust clients, such as the `patron` CLI. Since both sides use the same definitions,
+//! incompatible changes to the API become compilation errors instead of runtime failures.
+//!
+//! Types are grouped by the API server route groups they are used in.
+//!
+//! ## Features
+//!
+//! * `schema` - derives [`schemars::JsonSchema`] for all types, which is used by the API server
+//!   to generate OpenAPI documentation.
+//! * `client` - enables the [`client`] module with a typed API client over [`reqwest`].
+
+#![deny(missing_docs)]
+#![deny(clippy::missing_docs_in_private_items)]
+
+/// Build session routes.
+pub mod build_sessions;
+
+/// Typed API server client.
+#[cfg(feature = "client")]
+pub mod client;
+
+/// Smart contract routes.
+pub mod contracts;
+
+/// Source code routes.
+pub mod source_code;
+
+/// OpenAPI documentation examples.
+#[cfg(feature = "schema")]
+mod schema;
@@ -0,0 +1,66 @@
+use common::hash::{Hash32, HexBytes};
+
+/// Example database identifier.
+pub(crate) fn example_database_identifier() -> i64 {
+    1
+}
+
+/// Example code hash.
+pub(crate) fn example_hex_hash() -> Hash32 {
+    Hash32::from([200; 32])
+}
+
+/// Example signing key identifier.
+pub(crate) fn example_signing_key_id() -> String {
+    String::from("1f9c2e4b7a0d3c58")
+}
+
+/// Example sr25519 public key.
+pub(crate) fn example_signing_public_key() -> HexBytes<32> {
+    HexBytes([212; 32])
+}
+
+/// Example sr25519 artifact signature.
+pub(crate) fn example_artifact_signature() -> HexBytes<64> {
+    HexBytes([100; 64])
+}
+
+/// Example log entry text.
+pub(crate) fn example_log_entry() -> String {
+    String::from("Compiling futures-util v0.3.28")
+}
+
+/// Example log entry repetition count.
+pub(crate) fn example_log_repeat_count() -> i64 {
+    1
+}
+
+/// Example log position.
+pub(crate) fn example_log_position() -> Option<i64> {
+    Some(40)
+}
+
+/// Example node name.
+pub(crate) fn example_node() -> String {
+    String::from("alephzero")
+}
+
+/// Example human-readable network name.
+pub(crate) fn example_node_display_name() -> Option<String> {
+    Some(String::from("Aleph Zero"))
+}
+
+/// Example SS58-encoded account address.
+pub(crate) fn example_account() -> String {
+    String::from("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY")
+}
+
+/// Example UNIX timestamp.
+pub(crate) fn example_timestamp() -> i64 {
+    1672531200
+}
+
+/// Example block number.
+pub(crate) fn example_block_number() -> Option<i64> {
+    Some(4200000)
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Source code identifier response.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SourceCodeUploadResponse {
+    /// Source code identifier.
+    #[cfg_attr(
+        feature = "schema",
+        schemars(example = "crate::schema::example_database_identifier")
+    )]
+    pub id: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::SourceCodeUploadResponse;
+
+    #[test]
+    fn round_trip() {
+        let value = json!({ "id": 1 });
+
+        let response: SourceCodeUploadResponse = serde_json::from_value(value.clone()).unwrap();
+
+        assert_eq!(response, SourceCodeUploadResponse { id: 1 });
+        assert_eq!(serde_json::to_value(response).unwrap(), value);
+    }
+}
@@ -0,0 +1,4 @@
+#[ink::contract]
+mod flipper {
+    pub value: bool,
+}
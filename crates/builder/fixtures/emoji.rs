@@ -0,0 +1,6 @@
+// 🦀 ink! «contract»
+mod flipper {
+    /// ✨ Flips the value.
+    #[ink(message)]
+    pub fn é_ï_flip() {}
+}
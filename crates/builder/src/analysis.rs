@@ -0,0 +1,368 @@
+use std::{sync::Arc, time::Duration};
+
+use db::{
+    build_session, diagnostic, file, ActiveValue, ColumnTrait, DatabaseConnection,
+    DatabaseTransaction, DbErr, EntityTrait, QueryFilter, QuerySelect, TransactionErrorExt,
+    TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+use ink_analyzer::{Diagnostic, Severity};
+use tokio::{
+    sync::{mpsc::UnboundedReceiver, OwnedSemaphorePermit, Semaphore},
+    task::JoinError,
+};
+use tracing::{error, instrument, warn};
+
+use crate::line_index::LineIndex;
+
+/// Maximum count of files analyzed simultaneously.
+const MAX_CONCURRENT_ANALYSES: usize = 4;
+
+/// Diagnostic message stored if the analysis did not finish in time.
+const TIMEOUT_MESSAGE: &str = "ink-analyzer did not finish in time, diagnostics are unavailable";
+
+/// Errors that may occur during the source code analysis.
+#[derive(Debug, Display, Error, From)]
+pub(crate) enum AnalysisError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Unable to spawn ink-analyzer task.
+    #[display(fmt = "unable to spawn ink-analyzer task")]
+    Spawn(JoinError),
+}
+
+/// Start source code analysis process.
+///
+/// Received build session identifiers are analyzed in the background,
+/// with at most [`MAX_CONCURRENT_ANALYSES`] files analyzed at the same time.
+///
+/// [`Future`] returned from this function should be
+/// spawned as a background process.
+///
+/// [`Future`]: std::future::Future
+pub(crate) async fn analyze(
+    db: Arc<DatabaseConnection>,
+    mut receiver: UnboundedReceiver<i64>,
+    timeout: Duration,
+) {
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_ANALYSES));
+
+    while let Some(build_session_id) = receiver.recv().await {
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore is never closed");
+
+        let db = db.clone();
+
+        tokio::spawn(async move {
+            let result = analyze_build_session(&db, build_session_id, timeout, permit, |text| {
+                ink_analyzer::Analysis::new(text).diagnostics()
+            })
+            .await;
+
+            if let Err(e) = result {
+                error!(%e, %build_session_id, "unable to analyze source code");
+            }
+        });
+    }
+}
+
+/// Get identifiers of build sessions, which are still waiting for their source code analysis.
+///
+/// Such build sessions may remain after the builder restart.
+pub(crate) async fn pending_build_sessions(db: &DatabaseConnection) -> Result<Vec<i64>, DbErr> {
+    build_session::Entity::find()
+        .select_only()
+        .column(build_session::Column::Id)
+        .filter(build_session::Column::DiagnosticsPending.eq(true))
+        .into_tuple::<i64>()
+        .all(db)
+        .await
+}
+
+/// Analyze `lib.rs` file of the provided build session and store found diagnostics.
+///
+/// The provided semaphore `permit` is released only after the `analyzer` returns,
+/// even if the analysis timed out, to keep the count of analysis threads bounded.
+#[instrument(skip(db, permit, analyzer), err)]
+async fn analyze_build_session<F>(
+    db: &DatabaseConnection,
+    build_session_id: i64,
+    timeout: Duration,
+    permit: OwnedSemaphorePermit,
+    analyzer: F,
+) -> Result<(), AnalysisError>
+where
+    F: FnOnce(&str) -> Vec<Diagnostic> + Send + 'static,
+{
+    let Some(source_code_id) = build_session::Entity::find_by_id(build_session_id)
+        .select_only()
+        .column(build_session::Column::SourceCodeId)
+        .into_tuple::<i64>()
+        .one(db)
+        .await?
+    else {
+        return Ok(());
+    };
+
+    let lib_rs = file::Entity::find()
+        .select_only()
+        .columns([file::Column::Id, file::Column::Text])
+        .filter(file::Column::SourceCodeId.eq(source_code_id))
+        .filter(file::Column::Name.eq("lib.rs"))
+        .into_tuple::<(i64, String)>()
+        .one(db)
+        .await?;
+
+    let analysis = match lib_rs {
+        Some((file_id, text)) => {
+            let task = tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                let diagnostics = analyzer(&text);
+                (text, diagnostics)
+            });
+
+            let output = match tokio::time::timeout(timeout, task).await {
+                Ok(output) => Some(output?),
+                Err(_) => {
+                    warn!("ink-analyzer timed out");
+                    None
+                }
+            };
+
+            Some((file_id, output))
+        }
+        None => None,
+    };
+
+    db.transaction::<_, _, DbErr>(|txn| {
+        Box::pin(async move {
+            if let Some((file_id, output)) = analysis {
+                store_diagnostics(txn, build_session_id, file_id, output).await?;
+            }
+
+            build_session::Entity::update_many()
+                .filter(build_session::Column::Id.eq(build_session_id))
+                .col_expr(build_session::Column::DiagnosticsPending, false.into())
+                .exec(txn)
+                .await?;
+
+            Ok(())
+        })
+    })
+    .await
+    .into_raw_result()?;
+
+    Ok(())
+}
+
+/// Store diagnostics of a single file, alongside with the analyzed file text.
+///
+/// Line and column numbers of diagnostics are computed from the analyzed text.
+/// If the analysis timed out, a single warning is stored instead.
+async fn store_diagnostics(
+    txn: &DatabaseTransaction,
+    build_session_id: i64,
+    file_id: i64,
+    analysis: Option<(String, Vec<Diagnostic>)>,
+) -> Result<(), DbErr> {
+    let models = match analysis {
+        Some((text, diagnostics)) => {
+            let index = LineIndex::new(&text);
+
+            diagnostics
+                .into_iter()
+                .map(|raw_diagnostic| {
+                    let start = u32::from(raw_diagnostic.range.start());
+                    let end = u32::from(raw_diagnostic.range.end());
+
+                    let start_position = index.position(start as usize);
+                    let end_position = index.position(end as usize);
+
+                    diagnostic::ActiveModel {
+                        build_session_id: ActiveValue::Set(build_session_id),
+                        file_id: ActiveValue::Set(file_id),
+                        level: ActiveValue::Set(match raw_diagnostic.severity {
+                            Severity::Warning => diagnostic::Level::Warning,
+                            Severity::Error => diagnostic::Level::Error,
+                        }),
+                        start: ActiveValue::Set(start as i64),
+                        end: ActiveValue::Set(end as i64),
+                        start_line: ActiveValue::Set(Some(start_position.line)),
+                        start_col: ActiveValue::Set(Some(start_position.column)),
+                        end_line: ActiveValue::Set(Some(end_position.line)),
+                        end_col: ActiveValue::Set(Some(end_position.column)),
+                        message: ActiveValue::Set(raw_diagnostic.message),
+                        ..Default::default()
+                    }
+                })
+                .collect::<Vec<_>>()
+        }
+        None => vec![diagnostic::ActiveModel {
+            build_session_id: ActiveValue::Set(build_session_id),
+            file_id: ActiveValue::Set(file_id),
+            level: ActiveValue::Set(diagnostic::Level::Warning),
+            start: ActiveValue::Set(0),
+            end: ActiveValue::Set(0),
+            start_line: ActiveValue::Set(Some(1)),
+            start_col: ActiveValue::Set(Some(1)),
+            end_line: ActiveValue::Set(Some(1)),
+            end_col: ActiveValue::Set(Some(1)),
+            message: ActiveValue::Set(String::from(TIMEOUT_MESSAGE)),
+            ..Default::default()
+        }],
+    };
+
+    if !models.is_empty() {
+        diagnostic::Entity::insert_many(models)
+            .exec_without_returning(txn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use db::{
+        build_session, diagnostic, file, source_code, user, ActiveValue, DatabaseConnection,
+        EntityTrait,
+    };
+    use tokio::sync::Semaphore;
+
+    use crate::testing::create_database;
+
+    use super::{analyze_build_session, pending_build_sessions, TIMEOUT_MESSAGE};
+
+    /// Create a build session with pending diagnostics and a `lib.rs` file.
+    async fn create_build_session(db: &DatabaseConnection) -> i64 {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        file::Entity::insert(file::ActiveModel {
+            source_code_id: ActiveValue::Set(source_code_id),
+            name: ActiveValue::Set(String::from("lib.rs")),
+            text: ActiveValue::Set(String::from("#[ink::contract]\nmod contract {}")),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert file");
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::Completed),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            diagnostics_pending: ActiveValue::Set(true),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert build session")
+        .id
+    }
+
+    #[tokio::test]
+    async fn completed() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        assert_eq!(
+            pending_build_sessions(&db).await.unwrap(),
+            [build_session_id]
+        );
+
+        let semaphore = Arc::new(Semaphore::new(1));
+
+        analyze_build_session(
+            &db,
+            build_session_id,
+            Duration::from_secs(60),
+            semaphore.clone().acquire_owned().await.unwrap(),
+            |text| ink_analyzer::Analysis::new(text).diagnostics(),
+        )
+        .await
+        .expect("unable to analyze build session");
+
+        let model = build_session::Entity::find_by_id(build_session_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(!model.diagnostics_pending);
+        assert!(pending_build_sessions(&db).await.unwrap().is_empty());
+        assert_eq!(semaphore.available_permits(), 1);
+
+        let diagnostics = diagnostic::Entity::find().all(&db).await.unwrap();
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.message != TIMEOUT_MESSAGE));
+        assert!(diagnostics.iter().all(|diagnostic| {
+            diagnostic.start_line.is_some()
+                && diagnostic.start_col.is_some()
+                && diagnostic.end_line.is_some()
+                && diagnostic.end_col.is_some()
+        }));
+    }
+
+    #[tokio::test]
+    async fn timed_out() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        let semaphore = Arc::new(Semaphore::new(1));
+
+        analyze_build_session(
+            &db,
+            build_session_id,
+            Duration::from_millis(20),
+            semaphore.clone().acquire_owned().await.unwrap(),
+            |_| {
+                std::thread::sleep(Duration::from_millis(200));
+                Vec::new()
+            },
+        )
+        .await
+        .expect("unable to analyze build session");
+
+        let model = build_session::Entity::find_by_id(build_session_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(!model.diagnostics_pending);
+
+        let diagnostics = diagnostic::Entity::find().all(&db).await.unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, diagnostic::Level::Warning);
+        assert_eq!(diagnostics[0].message, TIMEOUT_MESSAGE);
+
+        // Permit is held by the analysis thread, which is still running.
+        assert_eq!(semaphore.available_permits(), 0);
+
+        tokio::time::timeout(Duration::from_secs(1), semaphore.acquire())
+            .await
+            .expect("analysis permit was not released")
+            .unwrap();
+    }
+}
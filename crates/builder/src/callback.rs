@@ -0,0 +1,288 @@
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    config,
+    hash::Hash32,
+    signature::{sign_timestamped, unix_timestamp, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+};
+use db::build_session;
+use derive_more::{Display, Error, From};
+use serde_json::json;
+use tracing::{error, info};
+
+/// Timeout for a single completion callback request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors that may occur during the completion callback client creation.
+#[derive(Debug, Display, Error, From)]
+pub enum CallbackError {
+    /// Unable to create an HTTP client.
+    Client(reqwest::Error),
+
+    /// Completion callback URL was configured without a shared secret.
+    #[display(fmt = "completion callback URL requires a callback secret")]
+    MissingSecret,
+}
+
+/// Final status of a committed build session.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Completion {
+    /// Build session identifier.
+    pub(crate) build_session_id: i64,
+
+    /// Final build session status.
+    pub(crate) status: build_session::Status,
+
+    /// Code hash of the built WASM blob, if the build session completed successfully.
+    pub(crate) code_hash: Option<Hash32>,
+}
+
+impl Completion {
+    /// Serialize the current value into a JSON request body.
+    fn body(&self) -> Vec<u8> {
+        json!({
+            "build_session_id": self.build_session_id,
+            "status": self.status,
+            "code_hash": self.code_hash,
+        })
+        .to_string()
+        .into_bytes()
+    }
+}
+
+/// API server client, that notifies it about build session completion.
+pub(crate) struct CompletionCallback {
+    /// Inner HTTP client.
+    client: reqwest::Client,
+
+    /// Completion callback URL.
+    url: String,
+
+    /// Secret used to sign the request body.
+    secret: String,
+}
+
+impl CompletionCallback {
+    /// Create new [`CompletionCallback`] from the builder configuration.
+    ///
+    /// Returns [`None`] if no completion callback URL is configured.
+    pub(crate) fn from_config(config: &config::Builder) -> Result<Option<Self>, CallbackError> {
+        let Some(url) = &config.completion_callback_url else {
+            return Ok(None);
+        };
+
+        let secret = config
+            .callback_secret
+            .clone()
+            .ok_or(CallbackError::MissingSecret)?;
+
+        Ok(Some(Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()?,
+            url: url.clone(),
+            secret,
+        }))
+    }
+
+    /// Notify the API server about the provided build session completion.
+    ///
+    /// Any errors are only logged, since the build session status is already committed
+    /// at this point. [`Future`] returned from this function is meant to be spawned
+    /// in the background.
+    ///
+    /// [`Future`]: std::future::Future
+    pub(crate) async fn notify(self: Arc<Self>, completion: Completion) {
+        let body = completion.body();
+        let timestamp = unix_timestamp();
+
+        let result = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(
+                SIGNATURE_HEADER,
+                sign_timestamped(self.secret.as_bytes(), timestamp, &body),
+            )
+            .body(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        match result {
+            Ok(_) => info!(
+                build_session_id = completion.build_session_id,
+                "completion callback sent"
+            ),
+            Err(e) => error!(
+                %e,
+                build_session_id = completion.build_session_id,
+                "unable to send completion callback"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+    use common::{
+        config::{Builder, VolumeBackend, VolumeDriver},
+        hash::Hash32,
+        signature::{timestamp_is_fresh, verify_timestamped, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+    };
+    use db::build_session;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::{CallbackError, Completion, CompletionCallback};
+
+    fn builder_config(url: Option<&str>, secret: Option<&str>) -> Builder {
+        Builder {
+            images_path: PathBuf::from("/tmp/images"),
+            api_server_url: String::from("http://localhost:3000"),
+            worker_count: 1,
+            max_build_duration: 3600,
+            shutdown_grace_seconds: 60,
+            analysis_timeout: 30,
+            token_max_age: 86400,
+            wasm_size_limit: 1024,
+            polkavm_size_limit: 1024,
+            metadata_size_limit: 1024,
+            max_log_bytes: 1024,
+            memory_limit: 1024,
+            memory_swap_limit: 1024,
+            cpu_quota: None,
+            pids_limit: 768,
+            blkio_weight: None,
+            volume_size: String::from("1G"),
+            volume_backend: VolumeBackend::Ext4Image,
+            docker_endpoint: None,
+            docker_tls: None,
+            volume_driver: VolumeDriver::Docker,
+            image_digests: HashMap::new(),
+            completion_callback_url: url.map(String::from),
+            callback_secret: secret.map(String::from),
+            hostname: None,
+            signing_key_seed: None,
+            registry: None,
+        }
+    }
+
+    fn completion() -> Completion {
+        Completion {
+            build_session_id: 1,
+            status: build_session::Status::Completed,
+            code_hash: Some(Hash32::from([1; 32])),
+        }
+    }
+
+    /// Find a header value inside of the provided HTTP request head.
+    fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+        head.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+
+    /// Accept a single HTTP request, returning its head and body.
+    async fn accept(listener: TcpListener) -> (String, Vec<u8>) {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+
+        let (head, body_len) = loop {
+            let len = stream.read(&mut buf).await.unwrap();
+            assert_ne!(len, 0, "connection closed before request head was received");
+            request.extend_from_slice(&buf[..len]);
+
+            if let Some(position) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                let head = String::from_utf8(request[..position].to_vec()).unwrap();
+                request.drain(..position + 4);
+
+                let body_len = header(&head, "content-length").unwrap().parse().unwrap();
+
+                break (head, body_len);
+            }
+        };
+
+        while request.len() < body_len {
+            let len = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..len]);
+        }
+
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+            .await
+            .unwrap();
+
+        (head, request)
+    }
+
+    #[test]
+    fn configuration() {
+        let url = Some("http://localhost:3000/internal/buildSessions/notify");
+
+        let callback = CompletionCallback::from_config(&builder_config(None, None)).unwrap();
+        assert!(callback.is_none());
+
+        let callback = CompletionCallback::from_config(&builder_config(url, Some("secret")));
+        assert!(callback.unwrap().is_some());
+
+        let callback = CompletionCallback::from_config(&builder_config(url, None));
+        assert!(matches!(callback, Err(CallbackError::MissingSecret)));
+    }
+
+    #[tokio::test]
+    async fn signed_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/notify", listener.local_addr().unwrap());
+
+        let callback = CompletionCallback::from_config(&builder_config(Some(&url), Some("secret")))
+            .unwrap()
+            .unwrap();
+
+        let (_, (head, body)) =
+            tokio::join!(Arc::new(callback).notify(completion()), accept(listener));
+
+        let signature = header(&head, SIGNATURE_HEADER).unwrap();
+        let timestamp = header(&head, TIMESTAMP_HEADER).unwrap().parse().unwrap();
+
+        assert!(head.starts_with("POST /notify HTTP/1.1"));
+        assert!(verify_timestamped(b"secret", timestamp, &body, signature));
+        assert!(timestamp_is_fresh(timestamp, 60));
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            serde_json::json!({
+                "build_session_id": 1,
+                "status": "completed",
+                "code_hash": "01".repeat(32),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn unreachable_server() {
+        // Bind and drop a listener to get an address without any server listening on it.
+        let address = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let callback = CompletionCallback::from_config(&builder_config(
+            Some(&format!("http://{address}/notify")),
+            Some("secret"),
+        ))
+        .unwrap()
+        .unwrap();
+
+        // Failures are only logged, without affecting the caller.
+        Arc::new(callback).notify(completion()).await;
+    }
+}
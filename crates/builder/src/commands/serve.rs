@@ -1,57 +1,473 @@
-use std::sync::Arc;
+use std::{fs, future::Future, sync::Arc, time::Duration};
 
-use bollard::{errors::Error, Docker};
-use common::config;
-use db::{DatabaseConnection, DbErr};
+use bollard::{errors::Error, Docker, API_DEFAULT_VERSION};
+use common::{
+    artifact_signature::SigningKey,
+    config::{self, DockerTls},
+};
+use db::{
+    sea_query::OnConflict, signing_key, ActiveValue, DatabaseConnection, DbErr, EntityTrait,
+    OffsetDateTime, PrimitiveDateTime,
+};
 use derive_more::{Display, Error, From};
-use futures_util::{stream::FuturesUnordered, FutureExt, StreamExt};
-use tokio::sync::mpsc;
-use tracing::{info, instrument};
+use futures_util::{pin_mut, stream::FuturesUnordered, FutureExt, StreamExt};
+use tokio::{sync::mpsc, time::timeout};
+use tracing::{error, info, instrument, warn};
 
-use crate::{log_collector, process::worker};
+use crate::{
+    analysis,
+    callback::{CallbackError, CompletionCallback},
+    log_collector,
+    process::worker,
+    shutdown::Shutdown,
+    tokens, webhooks,
+};
+
+/// Docker client timeout, in seconds.
+const DOCKER_TIMEOUT: u64 = 120;
+
+/// Path to the hostname of the current UTS namespace, which matches the value
+/// returned by `gethostname`.
+const HOSTNAME_PATH: &str = "/proc/sys/kernel/hostname";
+
+/// Hostname recorded if it is neither configured nor available from the system.
+const UNKNOWN_HOSTNAME: &str = "unknown";
 
 /// `serve` command errors.
 #[derive(Display, Debug, From, Error)]
 pub enum ServeError {
     /// Database-related error.
     DbErr(DbErr),
+
+    /// Docker-related error.
+    Docker(Error),
+
+    /// Unable to create the completion callback client.
+    Callback(CallbackError),
+
+    /// Configured Docker endpoint is neither a Unix socket path nor a `tcp://` URL.
+    #[display(fmt = "unsupported docker endpoint format")]
+    InvalidDockerEndpoint,
+
+    /// TLS configuration was provided for a non-TCP Docker endpoint.
+    #[display(fmt = "docker TLS configuration requires a tcp:// endpoint")]
+    TlsWithoutTcp,
+
+    /// Configured artifact signing key seed is invalid.
+    #[display(fmt = "invalid artifact signing key seed")]
+    InvalidSigningKey,
+}
+
+/// Docker daemon connection method.
+#[derive(Debug, PartialEq, Eq)]
+enum DockerConnection<'a> {
+    /// Default local Docker socket.
+    Default,
+
+    /// Unix socket with the provided path.
+    Socket(&'a str),
+
+    /// Plain HTTP connection to the provided address.
+    Http(&'a str),
+
+    /// HTTPS connection to the provided address using client certificates.
+    Ssl(&'a str, &'a DockerTls),
+}
+
+impl<'a> DockerConnection<'a> {
+    /// Select Docker daemon connection method from the builder configuration.
+    fn from_config(config: &'a config::Builder) -> Result<Self, ServeError> {
+        let Some(endpoint) = config.docker_endpoint.as_deref() else {
+            return match config.docker_tls {
+                Some(_) => Err(ServeError::TlsWithoutTcp),
+                None => Ok(Self::Default),
+            };
+        };
+
+        if endpoint.starts_with("tcp://") {
+            return Ok(match &config.docker_tls {
+                Some(tls) => Self::Ssl(endpoint, tls),
+                None => Self::Http(endpoint),
+            });
+        }
+
+        let path = endpoint
+            .strip_prefix("unix://")
+            .or_else(|| endpoint.starts_with('/').then_some(endpoint))
+            .ok_or(ServeError::InvalidDockerEndpoint)?;
+
+        if config.docker_tls.is_some() {
+            return Err(ServeError::TlsWithoutTcp);
+        }
+
+        Ok(Self::Socket(path))
+    }
+
+    /// Create a Docker client using the current connection method.
+    fn connect(self) -> Result<Docker, Error> {
+        match self {
+            Self::Default => Docker::connect_with_socket_defaults(),
+            Self::Socket(path) => {
+                Docker::connect_with_socket(path, DOCKER_TIMEOUT, API_DEFAULT_VERSION)
+            }
+            Self::Http(address) => {
+                Docker::connect_with_http(address, DOCKER_TIMEOUT, API_DEFAULT_VERSION)
+            }
+            Self::Ssl(address, tls) => Docker::connect_with_ssl(
+                address,
+                &tls.key,
+                &tls.cert,
+                &tls.ca,
+                DOCKER_TIMEOUT,
+                API_DEFAULT_VERSION,
+            ),
+        }
+    }
 }
 
 /// Spawn build session workers to handle new build sessions.
+///
+/// On SIGTERM or Ctrl-C, workers stop accepting new build sessions and in-flight ones
+/// are given [`shutdown_grace_seconds`] to complete, after which their containers are removed
+/// and build sessions are returned to the queue.
+///
+/// [`shutdown_grace_seconds`]: config::Builder::shutdown_grace_seconds
 #[instrument(skip_all, err)]
 pub async fn serve(
     builder_config: config::Builder,
     storage_config: config::Storage,
     supported_cargo_contract_versions: Vec<String>,
     database: DatabaseConnection,
-) -> Result<(), Error> {
-    let builder_config = Arc::new(builder_config);
-    let storage_config = Arc::new(storage_config);
-    let supported_cargo_contract_versions = Arc::new(supported_cargo_contract_versions);
-    let docker = Arc::new(Docker::connect_with_socket_defaults()?);
+) -> Result<(), ServeError> {
+    let docker = DockerConnection::from_config(&builder_config)?.connect()?;
+    let callback = CompletionCallback::from_config(&builder_config)?.map(Arc::new);
+
     let database = Arc::new(database);
 
+    let hostname = builder_config
+        .hostname
+        .clone()
+        .unwrap_or_else(system_hostname);
+
+    info!(%hostname, "using builder hostname");
+
+    let signing_key = match &builder_config.signing_key_seed {
+        Some(seed) => {
+            let key = SigningKey::from_seed(seed).map_err(|_| ServeError::InvalidSigningKey)?;
+            register_signing_key(&database, &key).await?;
+
+            info!(key_id = %key.key_id(), "signing build artifacts");
+
+            Some(key)
+        }
+        None => None,
+    };
+
     info!("spawning log collector");
-    let (sender, receiver) = mpsc::unbounded_channel();
-    tokio::spawn(log_collector::collect_logs(database.clone(), receiver));
+    let (log_sender, receiver) = mpsc::unbounded_channel();
+    let log_collector = tokio::spawn(log_collector::collect_logs(
+        database.clone(),
+        receiver,
+        builder_config.max_log_bytes,
+    ));
+
+    info!("spawning webhook delivery process");
+    let (webhook_sender, webhook_receiver) = mpsc::unbounded_channel();
+    tokio::spawn(webhooks::deliver(database.clone(), webhook_receiver));
+
+    info!("spawning source code analysis process");
+    let (analysis_sender, analysis_receiver) = mpsc::unbounded_channel();
+    tokio::spawn(analysis::analyze(
+        database.clone(),
+        analysis_receiver,
+        Duration::from_secs(builder_config.analysis_timeout),
+    ));
+
+    info!("spawning build session tokens sweeping process");
+    tokio::spawn(tokens::sweep(
+        database.clone(),
+        Duration::from_secs(builder_config.token_max_age),
+    ));
+
+    // Resume analysis of build sessions left unanalyzed after the previous shutdown.
+    for build_session_id in analysis::pending_build_sessions(&database).await? {
+        let _ = analysis_sender.send(build_session_id);
+    }
+
+    let context = Arc::new(worker::WorkerContext {
+        builder_config,
+        storage_config,
+        supported_cargo_contract_versions,
+        runtime: docker,
+        db: database,
+        log_sender,
+        webhook_sender,
+        analysis_sender,
+        callback,
+        shutdown: Shutdown::default(),
+        hostname,
+        signing_key,
+    });
+
+    context.shutdown.listen();
 
     info!("started build session processing");
 
-    (0..builder_config.worker_count)
-        .map(|_| {
-            tokio::spawn(worker::spawn(
-                builder_config.clone(),
-                storage_config.clone(),
-                supported_cargo_contract_versions.clone(),
-                docker.clone(),
-                database.clone(),
-                sender.clone(),
-            ))
-            .map(|_| ())
-        })
+    let workers = (0..context.builder_config.worker_count)
+        .map(|_| tokio::spawn(worker::spawn(context.clone())).map(|_| ()))
         .collect::<FuturesUnordered<_>>()
-        .collect::<()>()
-        .await;
+        .collect::<()>();
+
+    drain(
+        workers,
+        &context.shutdown,
+        Duration::from_secs(context.builder_config.shutdown_grace_seconds),
+    )
+    .await;
+
+    // Dropping the last log sender lets the log collector exit after flushing buffered entries.
+    drop(context);
+
+    info!("flushing collected logs");
+    if let Err(e) = log_collector.await {
+        error!(%e, "log collector failed");
+    }
+
+    info!("shutdown completed");
+
+    Ok(())
+}
+
+/// Register the public key of the provided signing key, so that clients are able
+/// to verify artifact signatures created with it.
+///
+/// Registration time of already known keys is updated, which makes the provided key current.
+async fn register_signing_key(db: &DatabaseConnection, key: &SigningKey) -> Result<(), DbErr> {
+    let now = OffsetDateTime::now_utc();
+
+    signing_key::Entity::insert(signing_key::ActiveModel {
+        id: ActiveValue::Set(key.key_id()),
+        public_key: ActiveValue::Set(key.public_key().0.to_vec()),
+        registered_at: ActiveValue::Set(PrimitiveDateTime::new(now.date(), now.time())),
+    })
+    .on_conflict(
+        OnConflict::column(signing_key::Column::Id)
+            .update_column(signing_key::Column::RegisteredAt)
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
 
     Ok(())
 }
+
+/// Get the hostname of the current system.
+fn system_hostname() -> String {
+    fs::read_to_string(HOSTNAME_PATH)
+        .ok()
+        .map(|hostname| hostname.trim().to_owned())
+        .filter(|hostname| !hostname.is_empty())
+        .unwrap_or_else(|| String::from(UNKNOWN_HOSTNAME))
+}
+
+/// Wait for the provided workers to exit after the shutdown signal is triggered.
+///
+/// If workers do not exit within the provided grace period, the shutdown is forced,
+/// which interrupts in-flight build sessions.
+async fn drain<F>(workers: F, shutdown: &Shutdown, grace: Duration)
+where
+    F: Future<Output = ()>,
+{
+    pin_mut!(workers);
+
+    tokio::select! {
+        _ = &mut workers => return,
+        _ = shutdown.triggered() => {},
+    }
+
+    if timeout(grace, &mut workers).await.is_err() {
+        warn!("shutdown grace period elapsed, interrupting in-flight build sessions");
+        shutdown.force();
+        workers.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+    use common::{
+        artifact_signature::SigningKey,
+        config::{Builder, DockerTls, VolumeBackend, VolumeDriver},
+    };
+    use db::{signing_key, EntityTrait};
+    use tokio::time::timeout;
+
+    use crate::{shutdown::Shutdown, testing::create_database};
+
+    use super::{drain, register_signing_key, DockerConnection, ServeError};
+
+    fn builder_config(docker_endpoint: Option<&str>, docker_tls: Option<DockerTls>) -> Builder {
+        Builder {
+            images_path: PathBuf::from("/tmp/images"),
+            api_server_url: String::from("http://localhost:3000"),
+            worker_count: 1,
+            max_build_duration: 3600,
+            shutdown_grace_seconds: 60,
+            analysis_timeout: 30,
+            token_max_age: 86400,
+            wasm_size_limit: 1024,
+            polkavm_size_limit: 1024,
+            metadata_size_limit: 1024,
+            max_log_bytes: 1024,
+            memory_limit: 1024,
+            memory_swap_limit: 1024,
+            cpu_quota: None,
+            pids_limit: 768,
+            blkio_weight: None,
+            volume_size: String::from("1G"),
+            volume_backend: VolumeBackend::Ext4Image,
+            docker_endpoint: docker_endpoint.map(String::from),
+            docker_tls,
+            volume_driver: VolumeDriver::Docker,
+            image_digests: HashMap::new(),
+            completion_callback_url: None,
+            callback_secret: None,
+            hostname: None,
+            signing_key_seed: None,
+            registry: None,
+        }
+    }
+
+    fn tls() -> DockerTls {
+        DockerTls {
+            key: PathBuf::from("key.pem"),
+            cert: PathBuf::from("cert.pem"),
+            ca: PathBuf::from("ca.pem"),
+        }
+    }
+
+    #[test]
+    fn default_socket() {
+        let config = builder_config(None, None);
+
+        assert_eq!(
+            DockerConnection::from_config(&config).unwrap(),
+            DockerConnection::Default
+        );
+    }
+
+    #[test]
+    fn unix_socket() {
+        for endpoint in [
+            "/run/user/1000/podman/podman.sock",
+            "unix:///run/user/1000/podman/podman.sock",
+        ] {
+            let config = builder_config(Some(endpoint), None);
+
+            assert_eq!(
+                DockerConnection::from_config(&config).unwrap(),
+                DockerConnection::Socket("/run/user/1000/podman/podman.sock")
+            );
+        }
+    }
+
+    #[test]
+    fn tcp() {
+        let config = builder_config(Some("tcp://127.0.0.1:2375"), None);
+
+        assert_eq!(
+            DockerConnection::from_config(&config).unwrap(),
+            DockerConnection::Http("tcp://127.0.0.1:2375")
+        );
+
+        let config = builder_config(Some("tcp://127.0.0.1:2376"), Some(tls()));
+
+        assert_eq!(
+            DockerConnection::from_config(&config).unwrap(),
+            DockerConnection::Ssl("tcp://127.0.0.1:2376", &tls())
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let config = builder_config(Some("http://127.0.0.1:2375"), None);
+        assert!(matches!(
+            DockerConnection::from_config(&config),
+            Err(ServeError::InvalidDockerEndpoint)
+        ));
+
+        for endpoint in [None, Some("/var/run/docker.sock")] {
+            let config = builder_config(endpoint, Some(tls()));
+            assert!(matches!(
+                DockerConnection::from_config(&config),
+                Err(ServeError::TlsWithoutTcp)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn graceful_drain() {
+        let shutdown = Shutdown::default();
+        shutdown.trigger();
+
+        // Worker finishing its in-flight build session.
+        let worker = tokio::time::sleep(Duration::from_millis(20));
+
+        timeout(
+            Duration::from_secs(1),
+            drain(worker, &shutdown, Duration::from_secs(60)),
+        )
+        .await
+        .expect("workers were not drained");
+
+        assert!(timeout(Duration::from_millis(20), shutdown.forced())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn forced_drain() {
+        let shutdown = Shutdown::default();
+
+        // Worker stuck on a build session, which exits only after the shutdown is forced.
+        let worker = {
+            let shutdown = shutdown.clone();
+            async move { shutdown.forced().await }
+        };
+
+        tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                shutdown.trigger();
+            }
+        });
+
+        timeout(
+            Duration::from_secs(1),
+            drain(worker, &shutdown, Duration::from_millis(20)),
+        )
+        .await
+        .expect("workers were not interrupted");
+    }
+
+    #[tokio::test]
+    async fn signing_key_registration() {
+        let db = create_database().await;
+        let key = SigningKey::from_seed(
+            "0x0101010101010101010101010101010101010101010101010101010101010101",
+        )
+        .unwrap();
+
+        // Restarted builders register the same key again.
+        register_signing_key(&db, &key).await.unwrap();
+        register_signing_key(&db, &key).await.unwrap();
+
+        let keys = signing_key::Entity::find().all(&db).await.unwrap();
+
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].id, key.key_id());
+        assert_eq!(keys[0].public_key, key.public_key().0);
+    }
+}
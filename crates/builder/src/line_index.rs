@@ -0,0 +1,126 @@
+use std::iter;
+
+/// Line and column numbers of a single text position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct LineColumn {
+    /// Line number, starting from 1.
+    pub(crate) line: i64,
+
+    /// Column number in characters, starting from 1.
+    pub(crate) column: i64,
+}
+
+/// Index of line start offsets within a single text, used to convert
+/// byte offsets into line and column numbers.
+///
+/// Lines are separated by either LF or CRLF line endings, while columns
+/// are counted in Unicode scalar values instead of bytes.
+pub(crate) struct LineIndex<'a> {
+    /// Indexed text.
+    text: &'a str,
+
+    /// Byte offsets of each line start.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Create new [`LineIndex`] of the provided text.
+    pub(crate) fn new(text: &'a str) -> Self {
+        let line_starts = iter::once(0)
+            .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+
+        Self { text, line_starts }
+    }
+
+    /// Convert the provided byte offset into line and column numbers.
+    ///
+    /// Offsets outside of the text are clamped to its end, while offsets inside
+    /// of multi-byte characters are moved to the start of these characters.
+    pub(crate) fn position(&self, offset: usize) -> LineColumn {
+        let mut offset = offset.min(self.text.len());
+
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let line_text = &self.text[self.line_starts[line]..offset];
+
+        // Carriage return of a CRLF line ending is a part of the line break.
+        let line_text = line_text.strip_suffix('\r').unwrap_or(line_text);
+
+        LineColumn {
+            line: line as i64 + 1,
+            column: line_text.chars().count() as i64 + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineColumn, LineIndex};
+
+    /// Source code with multi-byte characters.
+    const EMOJI: &str = include_str!("../fixtures/emoji.rs");
+
+    /// Source code with Windows line endings.
+    const CRLF: &str = include_str!("../fixtures/crlf.rs");
+
+    /// Get line and column numbers of the first occurrence of the provided pattern.
+    fn position_of(index: &LineIndex, text: &str, pattern: &str) -> (i64, i64) {
+        let LineColumn { line, column } = index.position(text.find(pattern).unwrap());
+
+        (line, column)
+    }
+
+    #[test]
+    fn multi_byte_characters() {
+        let index = LineIndex::new(EMOJI);
+
+        assert_eq!(position_of(&index, EMOJI, "🦀"), (1, 4));
+        assert_eq!(position_of(&index, EMOJI, "ink!"), (1, 6));
+        assert_eq!(position_of(&index, EMOJI, "mod"), (2, 1));
+        assert_eq!(position_of(&index, EMOJI, "flip()"), (5, 16));
+
+        // Offsets inside of a multi-byte character point to the character itself.
+        let crab = EMOJI.find('🦀').unwrap();
+        assert_eq!(index.position(crab + 2), index.position(crab));
+    }
+
+    #[test]
+    fn windows_line_endings() {
+        assert!(CRLF.contains("\r\n"), "fixture must use CRLF line endings");
+
+        let index = LineIndex::new(CRLF);
+
+        assert_eq!(position_of(&index, CRLF, "mod"), (2, 1));
+        assert_eq!(position_of(&index, CRLF, "value"), (3, 9));
+
+        // Both parts of a line ending are positioned at the end of the line.
+        let line_end = CRLF.find('\r').unwrap();
+        assert_eq!(
+            index.position(line_end),
+            LineColumn {
+                line: 1,
+                column: 17
+            }
+        );
+        assert_eq!(index.position(line_end + 1), index.position(line_end));
+        assert_eq!(
+            index.position(line_end + 2),
+            LineColumn { line: 2, column: 1 }
+        );
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let index = LineIndex::new("first\nsecond");
+
+        assert_eq!(index.position(100), LineColumn { line: 2, column: 7 });
+        assert_eq!(
+            LineIndex::new("").position(0),
+            LineColumn { line: 1, column: 1 }
+        );
+    }
+}
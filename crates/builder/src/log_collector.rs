@@ -1,8 +1,16 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use db::{log, ActiveModelTrait, DatabaseConnection};
+use common::{build_logs::TRUNCATION_MARKER, hash};
+use db::{
+    log, sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter,
+};
 use tokio::sync::mpsc::UnboundedReceiver;
-use tracing::error;
+use tracing::{error, warn};
 
 /// A single log entry passed from the build session process.
 pub(crate) struct LogEntry {
@@ -16,8 +24,130 @@ pub(crate) struct LogEntry {
     pub(crate) text: String,
 }
 
+/// Max period between identical consecutive log entries,
+/// during which they are collapsed into a single stored log entry.
+const REPEAT_WINDOW: Duration = Duration::from_secs(5);
+
+/// Decision made by the [`LogBudget`] about a single log entry.
+#[derive(Debug, PartialEq, Eq)]
+enum Admission {
+    /// Log entry fits into the build session budget.
+    Accept,
+
+    /// Log entry exceeds the build session budget for the first time,
+    /// and the truncation marker should be stored instead.
+    Truncate,
+
+    /// Build session budget was already exceeded.
+    Drop,
+}
+
+/// Per-build session log size accounting.
+///
+/// Budgets are kept for the lifetime of the log collector,
+/// which takes a few bytes per each processed build session.
+struct LogBudget {
+    /// Max total size of log entries of a single build session, in bytes.
+    max_bytes: usize,
+
+    /// Total size of received log entries, keyed by build session identifiers.
+    used: HashMap<i64, usize>,
+}
+
+impl LogBudget {
+    /// Create new [`LogBudget`] with the provided per-build session limit.
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            used: HashMap::new(),
+        }
+    }
+
+    /// Account a log entry of the provided size.
+    fn admit(&mut self, build_session_id: i64, len: usize) -> Admission {
+        let used = self.used.entry(build_session_id).or_default();
+
+        if *used > self.max_bytes {
+            return Admission::Drop;
+        }
+
+        *used = used.saturating_add(len);
+
+        if *used > self.max_bytes {
+            Admission::Truncate
+        } else {
+            Admission::Accept
+        }
+    }
+}
+
+/// Last stored log entry of a single build session.
+struct LastEntry {
+    /// Stored log entry identifier.
+    id: i64,
+
+    /// Hash of the log entry text.
+    hash: [u8; 32],
+
+    /// Time of the last received log entry with the same text.
+    received_at: Instant,
+}
+
+/// Per-build session tracking of identical consecutive log entries.
+///
+/// Only text hashes are kept for the lifetime of the log collector,
+/// which takes a few bytes per each processed build session.
+#[derive(Default)]
+struct Repeats {
+    /// Last stored log entries, keyed by build session identifiers.
+    last: HashMap<i64, LastEntry>,
+}
+
+impl Repeats {
+    /// Get the identifier of a stored log entry, which the provided text repeats.
+    ///
+    /// Text is considered repeated only if it is identical to the last stored log entry
+    /// of the same build session, and was received within the [`REPEAT_WINDOW`].
+    fn repeated(&mut self, build_session_id: i64, text: &str, now: Instant) -> Option<i64> {
+        let last = self.last.get_mut(&build_session_id)?;
+
+        if last.hash != hash::blake2(text.as_bytes())
+            || now.saturating_duration_since(last.received_at) > REPEAT_WINDOW
+        {
+            return None;
+        }
+
+        last.received_at = now;
+
+        Some(last.id)
+    }
+
+    /// Record a newly stored log entry of a build session.
+    fn record(&mut self, build_session_id: i64, id: i64, text: &str, now: Instant) {
+        self.last.insert(
+            build_session_id,
+            LastEntry {
+                id,
+                hash: hash::blake2(text.as_bytes()),
+                received_at: now,
+            },
+        );
+    }
+}
+
 /// Start log collection process.
 ///
+/// Log entries of a single build session are stored until their total size
+/// exceeds `max_bytes`, after which a single [`TRUNCATION_MARKER`] entry is stored.
+///
+/// Build session rows are kept locked by workers for the whole build,
+/// thus the truncation is recorded only by the stored marker entry,
+/// instead of a build session column that would block the log collection.
+///
+/// Identical consecutive log entries received within the [`REPEAT_WINDOW`]
+/// are collapsed into a single stored log entry with an increased repeat count.
+/// Such repeated log entries are not accounted in the build session budget.
+///
 /// [`Future`] returned from this function should be
 /// spawned as a background process.
 ///
@@ -25,18 +155,230 @@ pub(crate) struct LogEntry {
 pub(crate) async fn collect_logs(
     db: Arc<DatabaseConnection>,
     mut receiver: UnboundedReceiver<LogEntry>,
+    max_bytes: usize,
 ) {
+    let mut budget = LogBudget::new(max_bytes);
+    let mut repeats = Repeats::default();
+
     while let Some(log_entry) = receiver.recv().await {
-        let insert = log::ActiveModel {
-            build_session_id: db::ActiveValue::Set(log_entry.build_session_id),
-            text: db::ActiveValue::Set(log_entry.text),
+        if let Err(e) = store_entry(&db, &mut budget, &mut repeats, log_entry).await {
+            error!(%e, "unable to insert log entry")
+        }
+    }
+}
+
+/// Store a single log entry, if it fits into the build session budget.
+///
+/// Log entries that repeat the last stored log entry increase its repeat count instead.
+async fn store_entry(
+    db: &DatabaseConnection,
+    budget: &mut LogBudget,
+    repeats: &mut Repeats,
+    log_entry: LogEntry,
+) -> Result<(), DbErr> {
+    let build_session_id = log_entry.build_session_id;
+    let now = Instant::now();
+
+    if let Some(id) = repeats.repeated(build_session_id, &log_entry.text, now) {
+        log::Entity::update_many()
+            .filter(log::Column::Id.eq(id))
+            .col_expr(
+                log::Column::RepeatCount,
+                Expr::col(log::Column::RepeatCount).add(1),
+            )
+            .exec(db)
+            .await?;
+
+        return Ok(());
+    }
+
+    let text = match budget.admit(build_session_id, log_entry.text.len()) {
+        Admission::Accept => log_entry.text,
+        Admission::Truncate => {
+            warn!(%build_session_id, "build session log size limit exceeded");
+
+            String::from(TRUNCATION_MARKER)
+        }
+        Admission::Drop => return Ok(()),
+    };
+
+    let model = log::ActiveModel {
+        build_session_id: db::ActiveValue::Set(build_session_id),
+        text: db::ActiveValue::Set(text),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    repeats.record(build_session_id, model.id, &model.text, now);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use common::build_logs::TRUNCATION_MARKER;
+    use db::{
+        build_session, log, source_code, user, ActiveValue, DatabaseConnection, EntityTrait,
+        QueryOrder,
+    };
+
+    use crate::testing::create_database;
+
+    use super::{store_entry, Admission, LogBudget, LogEntry, Repeats, REPEAT_WINDOW};
+
+    async fn create_build_session(db: &DatabaseConnection) -> i64 {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::New),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
             ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert build session")
+        .id
+    }
+
+    #[test]
+    fn budget_accounting() {
+        let mut budget = LogBudget::new(10);
+
+        assert_eq!(budget.admit(1, 4), Admission::Accept);
+        assert_eq!(budget.admit(1, 6), Admission::Accept);
+        assert_eq!(budget.admit(2, 10), Admission::Accept);
+        assert_eq!(budget.admit(1, 1), Admission::Truncate);
+        assert_eq!(budget.admit(1, 0), Admission::Drop);
+        assert_eq!(budget.admit(2, 0), Admission::Accept);
+        assert_eq!(budget.admit(2, usize::MAX), Admission::Truncate);
+        assert_eq!(budget.admit(2, 1), Admission::Drop);
+    }
+
+    #[tokio::test]
+    async fn truncation_marker() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        let mut budget = LogBudget::new(8);
+        let mut repeats = Repeats::default();
+
+        for text in ["first\n", "second\n", "third\n"] {
+            store_entry(
+                &db,
+                &mut budget,
+                &mut repeats,
+                LogEntry {
+                    build_session_id,
+                    text: String::from(text),
+                },
+            )
+            .await
+            .expect("unable to store log entry");
         }
-        .insert(&*db)
-        .await;
 
-        if let Err(e) = insert {
-            error!(%e, "unable to insert log entry")
+        let logs = log::Entity::find()
+            .order_by_asc(log::Column::Id)
+            .all(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|log| log.text)
+            .collect::<Vec<_>>();
+
+        assert_eq!(logs, ["first\n", TRUNCATION_MARKER]);
+    }
+
+    #[test]
+    fn repeat_window() {
+        let mut repeats = Repeats::default();
+        let now = Instant::now();
+
+        assert_eq!(repeats.repeated(1, "text", now), None);
+
+        repeats.record(1, 10, "text", now);
+
+        assert_eq!(repeats.repeated(1, "text", now), Some(10));
+        assert_eq!(repeats.repeated(1, "other", now), None);
+        assert_eq!(repeats.repeated(2, "text", now), None);
+        assert_eq!(
+            repeats.repeated(1, "text", now + REPEAT_WINDOW / 2),
+            Some(10)
+        );
+        assert_eq!(repeats.repeated(1, "text", now + REPEAT_WINDOW), Some(10));
+        assert_eq!(repeats.repeated(1, "text", now + REPEAT_WINDOW * 3), None);
+    }
+
+    #[tokio::test]
+    async fn repeated_entries() {
+        let db = create_database().await;
+        let first_build_session_id = create_build_session(&db).await;
+        let second_build_session_id = create_build_session(&db).await;
+
+        let mut budget = LogBudget::new(1024);
+        let mut repeats = Repeats::default();
+
+        let stream = [
+            (first_build_session_id, "Downloading\n"),
+            (first_build_session_id, "Downloading\n"),
+            (second_build_session_id, "Downloading\n"),
+            (first_build_session_id, "Downloading\n"),
+            (first_build_session_id, "Compiling\n"),
+            (first_build_session_id, "Downloading\n"),
+            (first_build_session_id, "Downloading\n"),
+        ];
+
+        for (build_session_id, text) in stream {
+            store_entry(
+                &db,
+                &mut budget,
+                &mut repeats,
+                LogEntry {
+                    build_session_id,
+                    text: String::from(text),
+                },
+            )
+            .await
+            .expect("unable to store log entry");
         }
+
+        let logs = log::Entity::find()
+            .order_by_asc(log::Column::Id)
+            .all(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|log| (log.build_session_id, log.text, log.repeat_count))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            logs,
+            [
+                (first_build_session_id, String::from("Downloading\n"), 3),
+                (second_build_session_id, String::from("Downloading\n"), 1),
+                (first_build_session_id, String::from("Compiling\n"), 1),
+                (first_build_session_id, String::from("Downloading\n"), 2),
+            ]
+        );
+
+        // Repeated log entries are not accounted in the build session budget.
+        assert_eq!(budget.used[&first_build_session_id], 34);
     }
 }
@@ -1,7 +1,7 @@
 //! # Smart contract builder
 //!
 //! Smart contract builder process is responsible for managing
-//! Docker containers that build the smart contract WASM blobs
+//! Docker containers that build the smart contract WASM or PolkaVM blobs
 //! in an isolated and reproducible manner.
 //!
 //! # CLI subcommands
@@ -27,28 +27,81 @@
 //! [`container`]: process::container
 //! [`worker`]: process::worker
 //!
+//! # Source code analysis
+//!
+//! Source code of each build session is analyzed using ink-analyzer after the build
+//! session is finished, in a separate bounded pool of background tasks.
+//! This way, slow analysis doesn't hold the build session queue locked.
+//!
+//! See [`analysis`] for more details.
+//!
+//! Project manifests are checked for the most common build failure causes
+//! right after the source code is unarchived, see [`manifest`] for more details.
+//!
 //! # Log collector
 //!
 //! To provide users with information about whats happening during the build process
 //! we spawn the log collector process, which ingests logs from all running build processes.
+//! Total size of stored logs is limited for each build session, with any excessive output dropped.
 //!
 //! See [`log_collector`] for more details.
+//!
+//! # Webhooks
+//!
+//! After the final build session status is committed, user-defined webhooks
+//! are notified from a separate background process, which retries failed requests.
+//!
+//! See [`webhooks`] for more details.
+//!
+//! If configured, the API server is notified about each build session completion as well,
+//! which is done with a single signed request. See [`callback`] for more details.
+//!
+//! # Build session tokens
+//!
+//! Build session tokens are removed as soon as their build sessions are finished.
+//! Tokens of build sessions that never finished are periodically removed
+//! after the configured age. See [`tokens`] for more details.
 
 #![deny(missing_docs)]
 #![deny(clippy::missing_docs_in_private_items)]
 
+/// Background source code analysis.
+mod analysis;
+
+/// API server completion callback client.
+mod callback;
+
 /// CLI configuration and available subcommands.
 mod cli;
 
 /// Subcommand implementations.
 mod commands;
 
+/// Conversion of byte offsets into line and column numbers.
+mod line_index;
+
 /// Log collector implementation.
 mod log_collector;
 
+/// Project manifest checks.
+mod manifest;
+
 /// Build process instantiation and management.
 mod process;
 
+/// Graceful shutdown coordination.
+mod shutdown;
+
+/// Expired build session tokens removal.
+mod tokens;
+
+/// Webhook delivery implementation.
+mod webhooks;
+
+/// Testing utilities.
+#[cfg(test)]
+mod testing;
+
 use clap::Parser;
 use cli::{Cli, Command};
 use common::{config::Config, logging};
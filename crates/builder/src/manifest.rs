@@ -0,0 +1,413 @@
+use std::{collections::HashMap, ops::Range};
+
+use common::paths;
+use db::{
+    build_session::ProcessedBuildSession, diagnostic, file, ActiveValue, ColumnTrait,
+    DatabaseTransaction, DbErr, EntityTrait, QueryFilter, QuerySelect,
+};
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::line_index::LineIndex;
+
+/// Major versions of ink! supported by each major version of cargo-contract.
+const COMPATIBLE_INK_VERSIONS: &[(u64, &[u64])] =
+    &[(1, &[3]), (2, &[4]), (3, &[4]), (4, &[4, 5]), (5, &[5])];
+
+/// Parsed parts of the project manifest, which are checked before the build.
+#[derive(Deserialize)]
+struct Manifest {
+    /// Library target configuration.
+    lib: Option<Spanned<Lib>>,
+
+    /// Project dependencies.
+    dependencies: Option<Spanned<HashMap<String, Spanned<Dependency>>>>,
+}
+
+/// Library target configuration.
+#[derive(Deserialize)]
+struct Lib {
+    /// Crate types produced by the library target.
+    #[serde(rename = "crate-type")]
+    crate_type: Option<Spanned<Vec<String>>>,
+}
+
+/// A single project dependency.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Dependency {
+    /// Dependency specified with a version requirement only.
+    Simple(String),
+
+    /// Dependency specified with a table.
+    Detailed {
+        /// Dependency version requirement, if any.
+        version: Option<String>,
+    },
+}
+
+/// A single issue found in the project manifest.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Issue {
+    /// Issue severity level.
+    pub(crate) level: diagnostic::Level,
+
+    /// Byte range of the manifest part that caused the issue.
+    pub(crate) range: Range<usize>,
+
+    /// Issue message.
+    pub(crate) message: String,
+}
+
+impl Issue {
+    /// Create new error-level [`Issue`].
+    fn error(range: Range<usize>, message: &str) -> Self {
+        Self {
+            level: diagnostic::Level::Error,
+            range,
+            message: String::from(message),
+        }
+    }
+}
+
+/// Check the provided project manifest text for the most common build failure causes.
+///
+/// Manifest is expected to declare the `cdylib` crate type for its library target and
+/// to depend on an ink! version compatible with the provided cargo-contract version.
+pub(crate) fn check(text: &str, cargo_contract_version: &str) -> Vec<Issue> {
+    let manifest: Manifest = match toml::from_str(text) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            return vec![Issue::error(
+                e.span().unwrap_or(0..0),
+                &format!("unable to parse Cargo.toml: {}", e.message()),
+            )]
+        }
+    };
+
+    let mut issues = Vec::new();
+
+    let (lib_range, crate_type) = match &manifest.lib {
+        Some(lib) => (lib.span(), lib.get_ref().crate_type.as_ref()),
+        None => (0..0, None),
+    };
+
+    match crate_type {
+        Some(crate_type) if !crate_type.get_ref().iter().any(|ty| ty == "cdylib") => {
+            issues.push(Issue::error(
+                crate_type.span(),
+                "`[lib] crate-type` must contain \"cdylib\"",
+            ));
+        }
+        Some(_) => {}
+        None => issues.push(Issue {
+            level: diagnostic::Level::Warning,
+            range: lib_range,
+            message: String::from("`[lib] crate-type` is missing, add `crate-type = [\"cdylib\"]`"),
+        }),
+    }
+
+    let (dependencies_range, ink) = match &manifest.dependencies {
+        Some(dependencies) => (dependencies.span(), dependencies.get_ref().get("ink")),
+        None => (0..0, None),
+    };
+
+    match ink {
+        Some(ink) => {
+            let requirement = match ink.get_ref() {
+                Dependency::Simple(version) => Some(version),
+                Dependency::Detailed { version } => version.as_ref(),
+            };
+
+            let compatible = COMPATIBLE_INK_VERSIONS
+                .iter()
+                .find(|(major, _)| Some(*major) == major_version(cargo_contract_version))
+                .map(|(_, compatible)| *compatible);
+
+            // Version requirements inherited from workspaces or missing altogether are not checked.
+            if let (Some(requirement), Some(compatible)) = (requirement, compatible) {
+                let major = major_version(requirement);
+
+                if matches!(major, Some(major) if !compatible.contains(&major)) {
+                    issues.push(Issue::error(
+                        ink.span(),
+                        &format!(
+                            "ink! {requirement} is not supported by cargo-contract \
+                            {cargo_contract_version}"
+                        ),
+                    ));
+                }
+            }
+        }
+        None => issues.push(Issue::error(
+            dependencies_range,
+            "`ink` dependency is missing",
+        )),
+    }
+
+    issues
+}
+
+/// Get the major version of the provided version or version requirement.
+fn major_version(version: &str) -> Option<u64> {
+    let version = version.trim_start_matches(|c: char| "=^~<> ".contains(c));
+    let end = version
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(version.len());
+
+    version[..end].parse().ok()
+}
+
+/// Check the manifest of the provided build session project and store found issues
+/// as diagnostics of the manifest file.
+///
+/// Build sessions without an uploaded manifest file, or with a project directory outside
+/// of the project root, are skipped.
+pub(crate) async fn store_diagnostics(
+    txn: &DatabaseTransaction,
+    build_session: &ProcessedBuildSession,
+) -> Result<(), DbErr> {
+    let project_directory = build_session
+        .project_directory
+        .as_deref()
+        .unwrap_or_default();
+
+    let name = match paths::normalize_project_dir(project_directory).as_deref() {
+        Some("") => String::from("Cargo.toml"),
+        Some(project_directory) => format!("{project_directory}/Cargo.toml"),
+        None => return Ok(()),
+    };
+
+    let Some((file_id, text)) = file::Entity::find()
+        .select_only()
+        .columns([file::Column::Id, file::Column::Text])
+        .filter(file::Column::SourceCodeId.eq(build_session.source_code_id))
+        .filter(file::Column::Name.eq(name))
+        .into_tuple::<(i64, String)>()
+        .one(txn)
+        .await?
+    else {
+        return Ok(());
+    };
+
+    let index = LineIndex::new(&text);
+
+    let models = check(&text, &build_session.cargo_contract_version)
+        .into_iter()
+        .map(|issue| {
+            let start = index.position(issue.range.start);
+            let end = index.position(issue.range.end);
+
+            diagnostic::ActiveModel {
+                build_session_id: ActiveValue::Set(build_session.id),
+                file_id: ActiveValue::Set(file_id),
+                level: ActiveValue::Set(issue.level),
+                start: ActiveValue::Set(issue.range.start as i64),
+                end: ActiveValue::Set(issue.range.end as i64),
+                start_line: ActiveValue::Set(Some(start.line)),
+                start_col: ActiveValue::Set(Some(start.column)),
+                end_line: ActiveValue::Set(Some(end.line)),
+                end_col: ActiveValue::Set(Some(end.column)),
+                message: ActiveValue::Set(issue.message),
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !models.is_empty() {
+        diagnostic::Entity::insert_many(models)
+            .exec_without_returning(txn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        build_session::{self, ProcessedBuildSession},
+        diagnostic, file, source_code, user, ActiveValue, DatabaseConnection, EntityTrait,
+        TransactionTrait,
+    };
+
+    use crate::testing::create_database;
+
+    use super::{check, major_version, store_diagnostics};
+
+    /// Valid ink! 4 project manifest.
+    const VALID: &str = r#"
+[package]
+name = "flipper"
+version = "0.1.0"
+
+[dependencies]
+ink = { version = "4.2.0", default-features = false }
+
+[lib]
+path = "lib.rs"
+crate-type = ["cdylib"]
+"#;
+
+    /// Get messages of issues found in the provided manifest.
+    fn messages(text: &str, cargo_contract_version: &str) -> Vec<String> {
+        check(text, cargo_contract_version)
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect()
+    }
+
+    #[test]
+    fn valid_manifest() {
+        assert!(check(VALID, "3.0.0").is_empty());
+        assert!(check(VALID, "4.0.0").is_empty());
+        assert!(check(
+            &VALID.replace(
+                "{ version = \"4.2.0\", default-features = false }",
+                "{ workspace = true }"
+            ),
+            "3.0.0"
+        )
+        .is_empty());
+        assert!(check(&VALID.replace("\"4.2.0\"", "\"^4\""), "3.0.0").is_empty());
+    }
+
+    #[test]
+    fn broken_manifests() {
+        assert_eq!(
+            messages(&VALID.replace("crate-type = [\"cdylib\"]", ""), "3.0.0"),
+            ["`[lib] crate-type` is missing, add `crate-type = [\"cdylib\"]`"]
+        );
+        assert_eq!(
+            messages(&VALID.replace("\"cdylib\"", "\"rlib\""), "3.0.0"),
+            ["`[lib] crate-type` must contain \"cdylib\""]
+        );
+        assert_eq!(
+            messages(&VALID.replace("ink =", "scale ="), "3.0.0"),
+            ["`ink` dependency is missing"]
+        );
+        assert_eq!(
+            messages(&VALID.replace("4.2.0", "5.0.0"), "3.0.0"),
+            ["ink! 5.0.0 is not supported by cargo-contract 3.0.0"]
+        );
+        assert_eq!(
+            messages("[package", "3.0.0").len(),
+            1,
+            "parse errors are reported as a single issue"
+        );
+        assert!(messages("[package", "3.0.0")[0].starts_with("unable to parse Cargo.toml"));
+    }
+
+    #[test]
+    fn issue_ranges() {
+        let text = VALID.replace("\"cdylib\"", "\"rlib\"");
+        let issues = check(&text, "3.0.0");
+
+        assert_eq!(&text[issues[0].range.clone()], "[\"rlib\"]");
+    }
+
+    #[test]
+    fn major_versions() {
+        assert_eq!(major_version("4.2.0"), Some(4));
+        assert_eq!(major_version("=5.0.0-rc"), Some(5));
+        assert_eq!(major_version("^4"), Some(4));
+        assert_eq!(major_version("*"), None);
+    }
+
+    /// Create a build session with the provided manifest file.
+    async fn create_build_session(
+        db: &DatabaseConnection,
+        name: &str,
+        manifest: &str,
+    ) -> ProcessedBuildSession {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        file::Entity::insert(file::ActiveModel {
+            source_code_id: ActiveValue::Set(source_code_id),
+            name: ActiveValue::Set(String::from(name)),
+            text: ActiveValue::Set(String::from(manifest)),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert file");
+
+        let id = build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::New),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            project_directory: ActiveValue::Set(Some(String::from("contracts/flipper/"))),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert build session")
+        .id;
+
+        ProcessedBuildSession {
+            id,
+            source_code_id,
+            cargo_contract_version: String::from("3.0.0"),
+            project_directory: Some(String::from("contracts/flipper/")),
+        }
+    }
+
+    #[tokio::test]
+    async fn stored_diagnostics() {
+        let db = create_database().await;
+
+        let build_session = create_build_session(
+            &db,
+            "contracts/flipper/Cargo.toml",
+            &VALID.replace("ink =", "scale ="),
+        )
+        .await;
+
+        let txn = db.begin().await.unwrap();
+        store_diagnostics(&txn, &build_session)
+            .await
+            .expect("unable to store diagnostics");
+        txn.commit().await.unwrap();
+
+        let diagnostics = diagnostic::Entity::find().all(&db).await.unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].build_session_id, build_session.id);
+        assert_eq!(diagnostics[0].level, diagnostic::Level::Error);
+        assert_eq!(diagnostics[0].message, "`ink` dependency is missing");
+        assert!(diagnostics[0].start_line.is_some());
+    }
+
+    #[tokio::test]
+    async fn valid_manifest_diagnostics() {
+        let db = create_database().await;
+
+        let build_session = create_build_session(&db, "contracts/flipper/Cargo.toml", VALID).await;
+
+        let txn = db.begin().await.unwrap();
+        store_diagnostics(&txn, &build_session)
+            .await
+            .expect("unable to store diagnostics");
+        txn.commit().await.unwrap();
+
+        assert!(diagnostic::Entity::find()
+            .all(&db)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
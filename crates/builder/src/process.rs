@@ -1,6 +1,15 @@
+/// Build artifacts selection.
+pub(crate) mod artifacts;
+
 /// Container instantiation and removal.
 pub(crate) mod container;
 
+/// Container runtime abstraction.
+pub(crate) mod runtime;
+
+/// Streaming file extraction from `tar` archives.
+pub(crate) mod extract;
+
 /// Volume management.
 pub(crate) mod volume;
 
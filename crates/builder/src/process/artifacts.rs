@@ -0,0 +1,209 @@
+use std::path::Path;
+
+use db::build_session::ArtifactKind;
+use derive_more::{Display, Error};
+
+/// Root directory of the build session volume inside of containers.
+const ROOT_DIRECTORY: &str = "/contract";
+
+/// Supported kinds of contract blobs.
+const ARTIFACT_KINDS: [ArtifactKind; 2] = [ArtifactKind::Wasm, ArtifactKind::Polkavm];
+
+/// Errors that may occur during build artifacts selection.
+#[derive(Debug, Display, Error, PartialEq, Eq)]
+pub enum ArtifactsError {
+    /// No contract blob and JSON metadata pairs were produced.
+    #[display(fmt = "build artifacts not found")]
+    NotFound,
+
+    /// Multiple contracts were built, and none of them matches the project directory.
+    #[display(fmt = "unable to select build artifacts of multiple contracts")]
+    Ambiguous,
+}
+
+/// Paths to the build artifacts of a single contract.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Artifacts<'a> {
+    /// Contract crate name.
+    pub contract_name: &'a str,
+
+    /// Kind of the contract blob.
+    pub kind: ArtifactKind,
+
+    /// Path to the contract blob.
+    pub code_path: &'a str,
+
+    /// Path to the JSON metadata.
+    pub metadata_path: &'a str,
+}
+
+/// Select build artifacts of the contract located in the provided project directory.
+///
+/// `listing` contains paths of all `.wasm`, `.polkavm` and `.json` files found inside
+/// of `target/ink` directories, one path per line. Artifact pairs are found by matching
+/// file stems of the files inside of the same directory, and the kind of the contract blob
+/// is detected from its file extension.
+///
+/// If multiple contracts were built, artifacts are selected either by their location
+/// inside of the project directory, or by matching the contract crate name with the
+/// project directory name.
+pub fn select<'a>(listing: &'a str, project_path: &str) -> Result<Artifacts<'a>, ArtifactsError> {
+    let paths = listing
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut candidates = paths
+        .iter()
+        .filter_map(|&code_path| {
+            let (kind, contract_name) = ARTIFACT_KINDS.into_iter().find_map(|kind| {
+                let contract_name = code_path
+                    .strip_suffix(kind.extension())?
+                    .strip_suffix('.')?;
+                Some((kind, contract_name))
+            })?;
+
+            let metadata_path = paths
+                .iter()
+                .copied()
+                .find(|path| path.strip_suffix(".json") == Some(contract_name))?;
+
+            Some(Artifacts {
+                contract_name: file_name(contract_name),
+                kind,
+                code_path,
+                metadata_path,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if candidates.len() > 1 && project_path != ROOT_DIRECTORY {
+        let project_target = format!("{project_path}/target/ink/");
+        let project_name = crate_name(file_name(project_path));
+
+        candidates.retain(|artifacts| {
+            artifacts.code_path.starts_with(&project_target)
+                || crate_name(artifacts.contract_name) == project_name
+        });
+    }
+
+    match candidates.len() {
+        0 => Err(ArtifactsError::NotFound),
+        1 => Ok(candidates.remove(0)),
+        _ => Err(ArtifactsError::Ambiguous),
+    }
+}
+
+/// Get the last component of the provided path.
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path)
+}
+
+/// Normalize the provided name, since crate names may use either dashes or underscores.
+fn crate_name(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use db::build_session::ArtifactKind;
+
+    use super::{select, Artifacts, ArtifactsError};
+
+    #[test]
+    fn single_contract() {
+        let listing = "/contract/target/ink/flipper.wasm\n/contract/target/ink/flipper.json\n";
+
+        assert_eq!(
+            select(listing, "/contract"),
+            Ok(Artifacts {
+                contract_name: "flipper",
+                kind: ArtifactKind::Wasm,
+                code_path: "/contract/target/ink/flipper.wasm",
+                metadata_path: "/contract/target/ink/flipper.json",
+            })
+        );
+    }
+
+    #[test]
+    fn polkavm_contract() {
+        let listing = "/contract/target/ink/flipper.polkavm\n/contract/target/ink/flipper.json\n";
+
+        assert_eq!(
+            select(listing, "/contract"),
+            Ok(Artifacts {
+                contract_name: "flipper",
+                kind: ArtifactKind::Polkavm,
+                code_path: "/contract/target/ink/flipper.polkavm",
+                metadata_path: "/contract/target/ink/flipper.json",
+            })
+        );
+    }
+
+    #[test]
+    fn multiple_contracts() {
+        let listing = [
+            "/contract/target/ink/erc20/erc20.json",
+            "/contract/target/ink/erc20/erc20.wasm",
+            "/contract/target/ink/my-flipper/my_flipper.json",
+            "/contract/target/ink/my-flipper/my_flipper.wasm",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            select(&listing, "/contract/contracts/my-flipper"),
+            Ok(Artifacts {
+                contract_name: "my_flipper",
+                kind: ArtifactKind::Wasm,
+                code_path: "/contract/target/ink/my-flipper/my_flipper.wasm",
+                metadata_path: "/contract/target/ink/my-flipper/my_flipper.json",
+            })
+        );
+        assert_eq!(
+            select(&listing, "/contract/contracts/erc20")
+                .unwrap()
+                .contract_name,
+            "erc20"
+        );
+        assert_eq!(
+            select(&listing, "/contract"),
+            Err(ArtifactsError::Ambiguous)
+        );
+        assert_eq!(
+            select(&listing, "/contract/contracts/unknown"),
+            Err(ArtifactsError::NotFound)
+        );
+    }
+
+    #[test]
+    fn project_target_directory() {
+        let listing = [
+            "/contract/target/ink/erc20.json",
+            "/contract/target/ink/erc20.wasm",
+            "/contract/token/target/ink/token_impl.json",
+            "/contract/token/target/ink/token_impl.wasm",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            select(&listing, "/contract/token").unwrap().contract_name,
+            "token_impl"
+        );
+    }
+
+    #[test]
+    fn missing_artifacts() {
+        for listing in [
+            "",
+            "/contract/target/ink/flipper.wasm",
+            "/contract/target/ink/flipper.json\n/contract/target/ink/other.wasm",
+            "/contract/target/ink/flipper.json\n/contract/target/ink/flipperpolkavm",
+        ] {
+            assert_eq!(select(listing, "/contract"), Err(ArtifactsError::NotFound));
+        }
+    }
+}
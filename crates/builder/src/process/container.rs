@@ -1,28 +1,42 @@
-use std::{
-    collections::HashMap,
-    fmt,
-    io::{self, Cursor, Read, Write},
-};
+use std::{collections::HashMap, fmt};
 
 use bollard::{
-    container::{
-        AttachContainerOptions, Config, CreateContainerOptions, DownloadFromContainerOptions,
-        LogOutput, RemoveContainerOptions,
-    },
+    auth::DockerCredentials,
+    container::{Config, LogOutput},
     errors::Error,
-    image::{CreateImageOptions, ListImagesOptions},
     service::{
         ContainerWaitResponse, HostConfig, Mount, MountTypeEnum, MountVolumeOptions,
         MountVolumeOptionsDriverConfig,
     },
-    Docker,
 };
-use common::config;
+use common::config::{self, VolumeDriver};
 use derive_more::{Display, Error, From};
 use futures_util::{Stream, TryStreamExt};
-use tracing::info;
+use tracing::{error, info};
+
+use crate::process::{
+    extract::{ExtractError, FileExtractor},
+    runtime::ContainerRuntime,
+    volume::{Volume, VolumeError, VolumeSource},
+};
+
+/// Errors that may occur during container creation process.
+#[derive(Debug, Display, Error, From)]
+pub enum ContainerCreateError {
+    /// Docker-related error.
+    Docker(Error),
 
-use crate::process::volume::{Volume, VolumeError};
+    /// Unable to pull the image from the registry.
+    #[from(ignore)]
+    #[display(fmt = "unable to pull image {}: {}", reference, source)]
+    ImagePull {
+        /// Image reference that was attempted to be pulled.
+        reference: String,
+
+        /// Registry error.
+        source: Error,
+    },
+}
 
 /// Errors that may occur during container removal process.
 #[derive(Debug, Display, Error, From)]
@@ -40,17 +54,18 @@ pub enum DownloadFromContainerError {
     /// Docker-related error.
     Docker(Error),
 
-    /// IO-related error.
-    Io(io::Error),
+    /// Unable to extract the requested file from the downloaded archive.
+    Extract(ExtractError),
+}
+
+/// Path to the build artifacts listing, produced by the [`Image::Move`] stage.
+const ARTIFACTS_LISTING_PATH: &str = "/contract/.artifacts";
 
-    /// Unable to fill the byte buffer with the requested file.
-    #[display(fmt = "file size limit exceeded")]
-    FileSizeLimitExceeded,
+/// Max build artifacts listing size, in bytes.
+const ARTIFACTS_LISTING_SIZE_LIMIT: usize = 64 * 1024;
 
-    /// The requested file was not found.
-    #[display(fmt = "file not found")]
-    FileNotFound,
-}
+/// Repository of the build image in Docker registry.
+const BUILD_IMAGE_REPOSITORY: &str = "paritytech/contracts-verifiable";
 
 /// Supported container images.
 pub enum Image<'a> {
@@ -61,9 +76,17 @@ pub enum Image<'a> {
     Build {
         /// `cargo-contract` version to use during image download process.
         version: &'a str,
+
+        /// Pinned image digest in the `sha256:<hex>` format.
+        ///
+        /// If provided, the image is referenced by its digest instead of the mutable version tag.
+        digest: Option<&'a str>,
+
+        /// Registry mirror prefix, prepended to the image repository name.
+        mirror: Option<&'a str>,
     },
 
-    /// Artifact rename image, produced using Nix.
+    /// Artifact listing image, produced using Nix.
     Move,
 }
 
@@ -71,87 +94,97 @@ impl<'a> fmt::Display for Image<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Image::Unarchive => write!(f, "stage-unarchive"),
-            Image::Build { version } => write!(f, "paritytech/contracts-verifiable:{version}"),
+            Image::Build {
+                digest: Some(digest),
+                mirror,
+                ..
+            } => write!(f, "{}@{digest}", build_image_repository(*mirror)),
+            Image::Build {
+                version,
+                digest: None,
+                mirror,
+            } => write!(f, "{}:{version}", build_image_repository(*mirror)),
             Image::Move => write!(f, "stage-move"),
         }
     }
 }
 
-/// A single running Docker container instance.
+/// A single running container instance.
 pub struct Container {
     /// Docker-specific container identifier.
     id: String,
 
     /// Related volume.
     volume: Volume,
+
+    /// Name of the Docker volume created specifically for this container.
+    named_volume: Option<String>,
+
+    /// Digest of the build image used by this container.
+    image_digest: Option<String>,
 }
 
 impl Container {
-    /// Spawn new Docker container with the provided configuration.
-    pub async fn new(
+    /// Spawn new container with the provided configuration.
+    pub async fn new<R: ContainerRuntime>(
         config: &config::Builder,
-        client: &Docker,
+        client: &R,
         volume: Volume,
         name: &str,
         image: Image<'_>,
         env: Option<Vec<&str>>,
         working_dir: Option<&str>,
-    ) -> Result<Self, (Error, Volume)> {
-        // Attempt to isolate container as much as possible.
-        //
-        // The provided container configuration should protect
-        // the build process from using any unnecessary capabilities,
-        // stop the container in case if too many processes are spawned
-        // (this may occur during archive unpacking).
-        let host_config = HostConfig {
-            cap_add: Some(vec![String::from("DAC_OVERRIDE")]),
-            cap_drop: Some(vec![String::from("ALL")]),
-            memory: Some(config.memory_limit),
-            memory_swap: Some(config.memory_swap_limit),
-            // Mount the passed volume as a home directory of a root user.
-            mounts: Some(vec![Mount {
-                target: Some(String::from("/contract")),
-                typ: Some(MountTypeEnum::VOLUME),
-                volume_options: Some(MountVolumeOptions {
-                    driver_config: Some(MountVolumeOptionsDriverConfig {
-                        name: Some(String::from("local")),
-                        options: Some(HashMap::from([
-                            (String::from("device"), volume.device().to_string()),
-                            (String::from("type"), String::from("ext4")),
-                        ])),
-                    }),
-                    ..Default::default()
-                }),
-                ..Default::default()
-            }]),
-            pids_limit: Some(768),
-            security_opt: Some(vec![String::from("no-new-privileges")]),
-            ..Default::default()
-        };
-
+    ) -> Result<Self, (ContainerCreateError, Volume)> {
         let image_str = image.to_string();
 
-        let cmd = if let Image::Build { .. } = image {
-            if let Err(err) = Self::ensure_image_exists(client, &image_str).await {
+        let (cmd, image_digest) = if let Image::Build { digest, mirror, .. } = image {
+            let credentials = config.registry.as_ref().and_then(registry_credentials);
+
+            if let Err(err) = Self::ensure_image_exists(client, &image_str, credentials).await {
                 return Err((err, volume));
             }
 
-            Some(vec!["build", "--release"])
+            let image_digest = match digest {
+                Some(digest) => Some(String::from(digest)),
+                None => {
+                    let repository = build_image_repository(mirror);
+
+                    match Self::resolve_digest(client, &image_str, &repository).await {
+                        Ok(digest) => digest,
+                        Err(err) => return Err((err.into(), volume)),
+                    }
+                }
+            };
+
+            (Some(vec!["build", "--release"]), image_digest)
+        } else {
+            (None, None)
+        };
+
+        let source = volume.source();
+
+        let named_volume = if let (VolumeSource::Device(device), VolumeDriver::Podman) =
+            (&source, config.volume_driver)
+        {
+            if let Err(err) = client.create_volume(name, device).await {
+                return Err((err.into(), volume));
+            }
+
+            Some(String::from(name))
         } else {
             None
         };
 
-        let container = match client
+        let mount = mount(source, config.volume_driver, name);
+
+        let id = match client
             .create_container(
-                Some(CreateContainerOptions {
-                    name,
-                    platform: Some("linux/amd64"),
-                }),
+                name,
                 Config {
                     image: Some(&*image_str),
                     cmd,
                     env,
-                    host_config: Some(host_config),
+                    host_config: Some(host_config(config, mount)),
                     attach_stdout: Some(true),
                     attach_stderr: Some(true),
                     working_dir,
@@ -160,155 +193,552 @@ impl Container {
             )
             .await
         {
-            Ok(container) => container,
-            Err(err) => return Err((err, volume)),
+            Ok(id) => id,
+            Err(err) => {
+                Self::remove_named_volume(client, named_volume.as_deref()).await;
+                return Err((err.into(), volume));
+            }
         };
 
-        if let Err(err) = client.start_container::<String>(&container.id, None).await {
-            return Err((err, volume));
+        if let Err(err) = client.start_container(&id).await {
+            Self::remove_named_volume(client, named_volume.as_deref()).await;
+            return Err((err.into(), volume));
         }
 
         Ok(Self {
-            id: container.id,
+            id,
             volume,
+            named_volume,
+            image_digest,
         })
     }
 
-    /// Get a [`Stream`] of logs from the current Docker container.
-    pub async fn logs(
+    /// Get the digest of the build image used by this container.
+    ///
+    /// [`None`] for non-build images, or if the digest could not be resolved.
+    pub fn image_digest(&self) -> Option<&str> {
+        self.image_digest.as_deref()
+    }
+
+    /// Remove the provided named volume, if any, after a failed container instantiation.
+    async fn remove_named_volume<R: ContainerRuntime>(client: &R, name: Option<&str>) {
+        if let Some(name) = name {
+            if let Err(e) = client.remove_volume(name).await {
+                error!(%e, %name, "unable to remove named volume");
+            }
+        }
+    }
+
+    /// Get a [`Stream`] of logs from the current container.
+    pub async fn logs<R: ContainerRuntime>(
         &self,
-        client: &Docker,
+        client: &R,
     ) -> Result<impl Stream<Item = Result<LogOutput, Error>>, Error> {
-        let raw = client
-            .attach_container::<String>(
-                &self.id,
-                Some(AttachContainerOptions {
-                    stdout: Some(true),
-                    stderr: Some(true),
-                    stream: Some(true),
-                    logs: Some(true),
-                    ..Default::default()
-                }),
-            )
-            .await?;
-
-        Ok(raw.output)
+        client.attach_container(&self.id).await
     }
 
-    /// Get WASM blob of an ink! smart contract from the container's filesystem.
+    /// Get the listing of build artifacts from the container's filesystem.
     ///
-    /// Provided `buf` slice can be used to limit the WASM blob size.
-    pub async fn wasm_file<'a>(
+    /// Listing contains paths of all `.wasm`, `.polkavm` and `.json` files inside
+    /// of `target/ink` directories, one path per line.
+    pub async fn artifacts_listing<R: ContainerRuntime>(
         &self,
-        client: &Docker,
-        buf: &'a mut [u8],
-    ) -> Result<&'a [u8], DownloadFromContainerError> {
-        self.download_from_container_to_buf(client, "/contract/target/ink/main.wasm", buf)
+        client: &R,
+    ) -> Result<Vec<u8>, DownloadFromContainerError> {
+        self.download_file(client, ARTIFACTS_LISTING_PATH, ARTIFACTS_LISTING_SIZE_LIMIT)
             .await
     }
 
+    /// Get WASM or PolkaVM blob of an ink! smart contract from the container's filesystem.
+    ///
+    /// Provided `limit` value can be used to limit the contract blob size.
+    pub async fn code_file<R: ContainerRuntime>(
+        &self,
+        client: &R,
+        path: &str,
+        limit: usize,
+    ) -> Result<Vec<u8>, DownloadFromContainerError> {
+        self.download_file(client, path, limit).await
+    }
+
     /// Get JSON metadata of an ink! smart contract from the container's filesystem.
     ///
-    /// Provided `buf` slice can be used to limit the JSON metadata size.
-    pub async fn metadata_file<'a>(
+    /// Provided `limit` value can be used to limit the JSON metadata size.
+    pub async fn metadata_file<R: ContainerRuntime>(
         &self,
-        client: &Docker,
-        buf: &'a mut [u8],
-    ) -> Result<&'a [u8], DownloadFromContainerError> {
-        self.download_from_container_to_buf(client, "/contract/target/ink/main.json", buf)
-            .await
+        client: &R,
+        path: &str,
+        limit: usize,
+    ) -> Result<Vec<u8>, DownloadFromContainerError> {
+        self.download_file(client, path, limit).await
     }
 
-    /// Get a [`Stream`] of the current Docker container process events.
-    pub fn events(
+    /// Get a [`Stream`] of the current container process events.
+    pub fn events<'a, R: ContainerRuntime>(
         &self,
-        client: &Docker,
-    ) -> impl Stream<Item = Result<ContainerWaitResponse, Error>> {
-        client.wait_container::<String>(&self.id, None)
+        client: &'a R,
+    ) -> impl Stream<Item = Result<ContainerWaitResponse, Error>> + 'a {
+        client.wait_container(&self.id)
     }
 
-    /// Remove the current Docker container and retrieve the inner [`Volume`] value.
-    pub async fn remove(self, client: &Docker) -> Result<Volume, ContainerRemoveError> {
-        client
-            .remove_container(
-                &self.id,
-                Some(RemoveContainerOptions {
-                    v: true,
-                    force: true,
-                    ..Default::default()
-                }),
-            )
-            .await?;
+    /// Remove the current container and retrieve the inner [`Volume`] value.
+    pub async fn remove<R: ContainerRuntime>(
+        self,
+        client: &R,
+    ) -> Result<Volume, ContainerRemoveError> {
+        client.remove_container(&self.id).await?;
+
+        if let Some(name) = &self.named_volume {
+            client.remove_volume(name).await?;
+        }
 
         Ok(self.volume)
     }
 
     /// Ensure that the image with the provided name exists.
     ///
-    /// If it doesn't, an attempt to pull it from Docker registry will be made.
-    pub async fn ensure_image_exists(client: &Docker, image: &str) -> Result<(), Error> {
-        let list = client
-            .list_images(Some(ListImagesOptions {
-                filters: HashMap::from([("reference", vec![image])]),
-                ..Default::default()
-            }))
-            .await?;
-
-        if list.is_empty() {
+    /// If it doesn't, an attempt to pull it from Docker registry
+    /// using the provided credentials will be made.
+    pub async fn ensure_image_exists<R: ContainerRuntime>(
+        client: &R,
+        image: &str,
+        credentials: Option<DockerCredentials>,
+    ) -> Result<(), ContainerCreateError> {
+        if client.list_images(image).await?.is_empty() {
             info!(%image, "downloading missing docker image");
 
             client
-                .create_image(
-                    Some(CreateImageOptions {
-                        from_image: image,
-                        ..Default::default()
-                    }),
-                    None,
-                    None,
-                )
-                .map_ok(|_| ())
-                .try_collect::<()>()
-                .await?;
+                .create_image(image, credentials)
+                .await
+                .map_err(|source| ContainerCreateError::ImagePull {
+                    reference: String::from(image),
+                    source,
+                })?;
         }
 
         Ok(())
     }
 
-    /// Download a file from the container's filesystem to the provided buffer.
-    ///
-    /// Since Docker wraps downloaded files into a `tar` archive, we re-use the same buffer
-    /// to unarchive the downloaded file.
+    /// Resolve the registry digest of the provided local build image
+    /// pulled from the provided repository.
+    async fn resolve_digest<R: ContainerRuntime>(
+        client: &R,
+        image: &str,
+        repository: &str,
+    ) -> Result<Option<String>, Error> {
+        let repo_digests = client.repository_digests(image).await?;
+
+        Ok(repository_digest(&repo_digests, repository).map(String::from))
+    }
+
+    /// Download a file from the container's filesystem.
     ///
-    /// To ensure that you access only the file's bytes (and not the `tar` archive's bytes)
-    /// you can use the slice returned from this function.
-    async fn download_from_container_to_buf<'a>(
+    /// Since Docker wraps downloaded files into a `tar` archive, the archive is parsed
+    /// while being downloaded, and the download is aborted as soon as
+    /// the file size exceeds the provided `limit`.
+    async fn download_file<R: ContainerRuntime>(
         &self,
-        client: &Docker,
+        client: &R,
         path: &str,
-        buf: &'a mut [u8],
-    ) -> Result<&'a [u8], DownloadFromContainerError> {
-        let mut cursor = Cursor::new(buf);
+        limit: usize,
+    ) -> Result<Vec<u8>, DownloadFromContainerError> {
+        let mut extractor = FileExtractor::new(limit);
 
-        let mut stream =
-            client.download_from_container(&self.id, Some(DownloadFromContainerOptions { path }));
+        let mut stream = client.download_from_container(&self.id, path);
 
         while let Some(chunk) = stream.try_next().await? {
-            cursor
-                .write(&chunk)
-                .map_err(|_| DownloadFromContainerError::FileSizeLimitExceeded)?;
+            extractor.push(&chunk)?;
+        }
+
+        Ok(extractor.finish()?)
+    }
+}
+
+/// Get the build image repository name, prefixed with the provided registry mirror, if any.
+fn build_image_repository(mirror: Option<&str>) -> String {
+    match mirror {
+        Some(mirror) => format!("{}/{BUILD_IMAGE_REPOSITORY}", mirror.trim_end_matches('/')),
+        None => String::from(BUILD_IMAGE_REPOSITORY),
+    }
+}
+
+/// Get credentials used to pull build images from the provided registry.
+///
+/// Returns [`None`] if neither username nor identity token are configured,
+/// in which case images are pulled anonymously.
+fn registry_credentials(registry: &config::Registry) -> Option<DockerCredentials> {
+    if registry.username.is_none() && registry.identity_token.is_none() {
+        return None;
+    }
+
+    Some(DockerCredentials {
+        username: registry.username.clone(),
+        password: registry.password.clone(),
+        identitytoken: registry.identity_token.clone(),
+        serveraddress: registry
+            .mirror
+            .as_deref()
+            .and_then(|mirror| mirror.split('/').next())
+            .map(String::from),
+        ..Default::default()
+    })
+}
+
+/// Find the digest of the provided repository among image repository digests.
+///
+/// Repository digests are reported in the `repository@sha256:<hex>` format,
+/// with Podman additionally prefixing repository names with the registry domain.
+fn repository_digest<'a>(repo_digests: &'a [String], repository: &str) -> Option<&'a str> {
+    repo_digests.iter().find_map(|repo_digest| {
+        repo_digest
+            .strip_prefix("docker.io/")
+            .unwrap_or(repo_digest)
+            .strip_prefix(repository)?
+            .strip_prefix('@')
+    })
+}
+
+/// Create a [`HostConfig`] for a container with the provided volume mount.
+fn host_config(config: &config::Builder, mount: Mount) -> HostConfig {
+    // Attempt to isolate container as much as possible.
+    //
+    // The provided container configuration should protect
+    // the build process from using any unnecessary capabilities,
+    // stop the container in case if too many processes are spawned
+    // (this may occur during archive unpacking).
+    HostConfig {
+        cap_add: Some(vec![String::from("DAC_OVERRIDE")]),
+        cap_drop: Some(vec![String::from("ALL")]),
+        memory: Some(config.memory_limit),
+        memory_swap: Some(config.memory_swap_limit),
+        nano_cpus: config.cpu_quota.map(|cpus| (cpus * 1_000_000_000.0) as i64),
+        blkio_weight: config.blkio_weight,
+        mounts: Some(vec![mount]),
+        pids_limit: Some(config.pids_limit),
+        security_opt: Some(vec![String::from("no-new-privileges")]),
+        ..Default::default()
+    }
+}
+
+/// Create a [`Mount`] specification for the provided volume source.
+///
+/// Volume is mounted as a home directory of a root user.
+fn mount(source: VolumeSource, driver: VolumeDriver, name: &str) -> Mount {
+    let mount = match (source, driver) {
+        (VolumeSource::Device(device), VolumeDriver::Docker) => Mount {
+            typ: Some(MountTypeEnum::VOLUME),
+            volume_options: Some(MountVolumeOptions {
+                driver_config: Some(MountVolumeOptionsDriverConfig {
+                    name: Some(String::from("local")),
+                    options: Some(HashMap::from([
+                        (String::from("device"), device.to_string()),
+                        (String::from("type"), String::from("ext4")),
+                    ])),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        // Podman volumes are created before the container itself.
+        (VolumeSource::Device(_), VolumeDriver::Podman) => Mount {
+            typ: Some(MountTypeEnum::VOLUME),
+            source: Some(String::from(name)),
+            ..Default::default()
+        },
+        (VolumeSource::Directory(path), _) => Mount {
+            typ: Some(MountTypeEnum::BIND),
+            source: Some(path.display().to_string()),
+            ..Default::default()
+        },
+    };
+
+    Mount {
+        target: Some(String::from("/contract")),
+        ..mount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+    };
+
+    use bollard::service::{Mount, MountTypeEnum};
+    use common::config::{Builder, Registry, VolumeBackend, VolumeDriver};
+
+    use super::{
+        build_image_repository, host_config, mount, registry_credentials, repository_digest,
+        Container, ContainerCreateError, Image,
+    };
+    use crate::{process::volume::VolumeSource, testing::FakeRuntime};
+
+    fn builder_config() -> Builder {
+        Builder {
+            images_path: PathBuf::from("/tmp/images"),
+            api_server_url: String::from("http://localhost:3000"),
+            worker_count: 1,
+            max_build_duration: 3600,
+            shutdown_grace_seconds: 60,
+            analysis_timeout: 30,
+            token_max_age: 86400,
+            wasm_size_limit: 1024,
+            polkavm_size_limit: 1024,
+            metadata_size_limit: 1024,
+            max_log_bytes: 1024,
+            memory_limit: 1024,
+            memory_swap_limit: 2048,
+            cpu_quota: None,
+            pids_limit: 768,
+            blkio_weight: None,
+            volume_size: String::from("1G"),
+            volume_backend: VolumeBackend::Ext4Image,
+            docker_endpoint: None,
+            docker_tls: None,
+            volume_driver: VolumeDriver::Docker,
+            image_digests: HashMap::new(),
+            completion_callback_url: None,
+            callback_secret: None,
+            hostname: None,
+            signing_key_seed: None,
+            registry: None,
         }
+    }
+
+    fn registry(username: Option<&str>, identity_token: Option<&str>) -> Registry {
+        Registry {
+            mirror: Some(String::from("registry.example.com/dockerhub")),
+            username: username.map(String::from),
+            password: username.map(|_| String::from("password")),
+            identity_token: identity_token.map(String::from),
+        }
+    }
 
-        let position = cursor.position() as usize;
+    fn directory_mount() -> Mount {
+        mount(
+            VolumeSource::Directory(Path::new("/tmp/images/.tmp1234")),
+            VolumeDriver::Docker,
+            "build-session-1",
+        )
+    }
+
+    #[test]
+    fn image_references() {
+        assert_eq!(Image::Unarchive.to_string(), "stage-unarchive");
+        assert_eq!(Image::Move.to_string(), "stage-move");
+        assert_eq!(
+            Image::Build {
+                version: "3.0.0",
+                digest: None,
+                mirror: None,
+            }
+            .to_string(),
+            "paritytech/contracts-verifiable:3.0.0"
+        );
+        assert_eq!(
+            Image::Build {
+                version: "3.0.0",
+                digest: Some("sha256:abcdef"),
+                mirror: None,
+            }
+            .to_string(),
+            "paritytech/contracts-verifiable@sha256:abcdef"
+        );
+    }
+
+    #[test]
+    fn mirrored_image_references() {
+        assert_eq!(
+            Image::Build {
+                version: "3.0.0",
+                digest: None,
+                mirror: Some("registry.example.com/dockerhub"),
+            }
+            .to_string(),
+            "registry.example.com/dockerhub/paritytech/contracts-verifiable:3.0.0"
+        );
+        assert_eq!(
+            Image::Build {
+                version: "3.0.0",
+                digest: Some("sha256:abcdef"),
+                mirror: Some("registry.example.com/"),
+            }
+            .to_string(),
+            "registry.example.com/paritytech/contracts-verifiable@sha256:abcdef"
+        );
+        assert_eq!(
+            build_image_repository(Some("registry.example.com")),
+            "registry.example.com/paritytech/contracts-verifiable"
+        );
+    }
+
+    #[test]
+    fn anonymous_registry() {
+        assert!(registry_credentials(&registry(None, None)).is_none());
+    }
+
+    #[tokio::test]
+    async fn registry_credentials_plumbing() {
+        let image = "registry.example.com/dockerhub/paritytech/contracts-verifiable:3.0.0";
+        let runtime = FakeRuntime::default();
+
+        Container::ensure_image_exists(
+            &runtime,
+            image,
+            registry_credentials(&registry(Some("user"), None)),
+        )
+        .await
+        .unwrap();
+
+        // Local images are not pulled again.
+        Container::ensure_image_exists(&runtime, image, None)
+            .await
+            .unwrap();
+
+        let pulls = runtime.pulls();
+        assert_eq!(pulls.len(), 1);
+        assert_eq!(pulls[0].0, image);
+
+        let credentials = pulls[0].1.as_ref().unwrap();
+        assert_eq!(credentials.username.as_deref(), Some("user"));
+        assert_eq!(credentials.password.as_deref(), Some("password"));
+        assert_eq!(credentials.identitytoken, None);
+        assert_eq!(
+            credentials.serveraddress.as_deref(),
+            Some("registry.example.com")
+        );
+
+        let credentials = registry_credentials(&registry(None, Some("token"))).unwrap();
+        assert_eq!(credentials.username, None);
+        assert_eq!(credentials.identitytoken.as_deref(), Some("token"));
+    }
+
+    #[tokio::test]
+    async fn image_pull_failure() {
+        let image = "registry.example.com/paritytech/contracts-verifiable:3.0.0";
+        let runtime = FakeRuntime::default().with_failing_pulls();
+
+        let err = Container::ensure_image_exists(&runtime, image, None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            &err,
+            ContainerCreateError::ImagePull { reference, .. } if reference == image
+        ));
+        assert!(err
+            .to_string()
+            .starts_with(&format!("unable to pull image {image}: ")));
+    }
+
+    #[test]
+    fn resolved_repository_digest() {
+        let repo_digests = vec![
+            String::from("library/other@sha256:000000"),
+            String::from("paritytech/contracts-verifiable-fork@sha256:111111"),
+            String::from("paritytech/contracts-verifiable@sha256:abcdef"),
+        ];
+
+        assert_eq!(
+            repository_digest(&repo_digests, "paritytech/contracts-verifiable"),
+            Some("sha256:abcdef")
+        );
+        assert_eq!(
+            repository_digest(&repo_digests[..2], "paritytech/contracts-verifiable"),
+            None
+        );
+
+        // Podman reports fully qualified repository names.
+        assert_eq!(
+            repository_digest(
+                &[String::from(
+                    "docker.io/paritytech/contracts-verifiable@sha256:abcdef"
+                )],
+                "paritytech/contracts-verifiable"
+            ),
+            Some("sha256:abcdef")
+        );
+    }
+
+    #[test]
+    fn default_host_config() {
+        let host_config = host_config(&builder_config(), directory_mount());
+
+        assert_eq!(host_config.memory, Some(1024));
+        assert_eq!(host_config.memory_swap, Some(2048));
+        assert_eq!(host_config.pids_limit, Some(768));
+        assert_eq!(host_config.nano_cpus, None);
+        assert_eq!(host_config.blkio_weight, None);
+        assert_eq!(host_config.mounts, Some(vec![directory_mount()]));
+    }
 
-        // Re-use the same buffer to store both archived and unarchived files.
-        let (archive, file_buf) = cursor.into_inner().split_at_mut(position);
+    #[test]
+    fn configured_host_config() {
+        let config = Builder {
+            cpu_quota: Some(1.5),
+            pids_limit: 128,
+            blkio_weight: Some(500),
+            ..builder_config()
+        };
+
+        let host_config = host_config(&config, directory_mount());
+
+        assert_eq!(host_config.nano_cpus, Some(1_500_000_000));
+        assert_eq!(host_config.pids_limit, Some(128));
+        assert_eq!(host_config.blkio_weight, Some(500));
+    }
 
-        let file_size = tar::Archive::new(&*archive)
-            .entries()?
-            .next()
-            .ok_or(DownloadFromContainerError::FileNotFound)??
-            .read(file_buf)?;
+    #[test]
+    fn ext4_image_docker() {
+        let mount = mount(
+            VolumeSource::Device("/dev/loop0"),
+            VolumeDriver::Docker,
+            "build-session-1",
+        );
+
+        assert_eq!(mount.target.as_deref(), Some("/contract"));
+        assert_eq!(mount.typ, Some(MountTypeEnum::VOLUME));
+        assert_eq!(mount.source, None);
+
+        let driver_config = mount.volume_options.unwrap().driver_config.unwrap();
+        assert_eq!(driver_config.name.as_deref(), Some("local"));
+        assert_eq!(
+            driver_config.options.unwrap(),
+            HashMap::from([
+                (String::from("device"), String::from("/dev/loop0")),
+                (String::from("type"), String::from("ext4")),
+            ])
+        );
+    }
+
+    #[test]
+    fn ext4_image_podman() {
+        let mount = mount(
+            VolumeSource::Device("/dev/loop0"),
+            VolumeDriver::Podman,
+            "build-session-1",
+        );
+
+        assert_eq!(mount.target.as_deref(), Some("/contract"));
+        assert_eq!(mount.typ, Some(MountTypeEnum::VOLUME));
+        assert_eq!(mount.source.as_deref(), Some("build-session-1"));
+        assert!(mount.volume_options.is_none());
+    }
 
-        Ok(&file_buf[..file_size])
+    #[test]
+    fn directory() {
+        for driver in [VolumeDriver::Docker, VolumeDriver::Podman] {
+            let mount = mount(
+                VolumeSource::Directory(Path::new("/tmp/images/.tmp1234")),
+                driver,
+                "build-session-1",
+            );
+
+            assert_eq!(mount.target.as_deref(), Some("/contract"));
+            assert_eq!(mount.typ, Some(MountTypeEnum::BIND));
+            assert_eq!(mount.source.as_deref(), Some("/tmp/images/.tmp1234"));
+            assert!(mount.volume_options.is_none());
+        }
     }
 }
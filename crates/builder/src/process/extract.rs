@@ -0,0 +1,270 @@
+use std::io;
+
+use derive_more::{Display, Error, From};
+
+/// Size of a single `tar` archive block.
+const BLOCK_SIZE: usize = 512;
+
+/// Errors that may occur during file extraction.
+#[derive(Debug, Display, Error, From)]
+pub enum ExtractError {
+    /// Unable to parse the `tar` archive.
+    Io(io::Error),
+
+    /// Size of the archived file exceeds the provided limit.
+    #[display(fmt = "file size limit exceeded")]
+    FileSizeLimitExceeded,
+
+    /// The archive does not contain any files.
+    #[display(fmt = "file not found")]
+    FileNotFound,
+}
+
+/// Current state of the [`FileExtractor`].
+enum State {
+    /// Collecting the next header block.
+    Header,
+
+    /// Skipping the data of a non-file entry (e.g. PAX extended headers).
+    Skip {
+        /// Count of bytes left to skip, including the block padding.
+        remaining: usize,
+    },
+
+    /// Collecting the archived file.
+    File {
+        /// Count of file bytes left to read.
+        remaining: usize,
+    },
+
+    /// The archived file was read completely.
+    Done,
+
+    /// The archive ended without any regular files.
+    End,
+}
+
+/// Incremental `tar` archive parser, that extracts the first regular file
+/// from the archive passed in chunks.
+///
+/// Size limit is enforced as soon as the file header is parsed,
+/// thus no memory is allocated for files that exceed the limit.
+pub struct FileExtractor {
+    /// Maximum file size, in bytes.
+    limit: usize,
+
+    /// Current parser state.
+    state: State,
+
+    /// Partially received header block.
+    header: Vec<u8>,
+
+    /// Extracted file contents.
+    file: Vec<u8>,
+}
+
+impl FileExtractor {
+    /// Create new [`FileExtractor`] with the provided file size limit.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            state: State::Header,
+            header: Vec::with_capacity(BLOCK_SIZE),
+            file: Vec::new(),
+        }
+    }
+
+    /// Feed the next chunk of the `tar` archive to the parser.
+    pub fn push(&mut self, mut chunk: &[u8]) -> Result<(), ExtractError> {
+        while !chunk.is_empty() {
+            match &mut self.state {
+                State::Header => {
+                    let len = chunk.len().min(BLOCK_SIZE - self.header.len());
+                    self.header.extend_from_slice(&chunk[..len]);
+                    chunk = &chunk[len..];
+
+                    if self.header.len() == BLOCK_SIZE {
+                        self.parse_header()?;
+                    }
+                }
+                State::Skip { remaining } => {
+                    let len = chunk.len().min(*remaining);
+                    *remaining -= len;
+                    chunk = &chunk[len..];
+
+                    if *remaining == 0 {
+                        self.state = State::Header;
+                    }
+                }
+                State::File { remaining } => {
+                    let len = chunk.len().min(*remaining);
+                    self.file.extend_from_slice(&chunk[..len]);
+                    *remaining -= len;
+                    chunk = &chunk[len..];
+
+                    if *remaining == 0 {
+                        self.state = State::Done;
+                    }
+                }
+                State::Done | State::End => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finish the extraction process, returning the archived file contents.
+    pub fn finish(self) -> Result<Vec<u8>, ExtractError> {
+        match self.state {
+            State::Done => Ok(self.file),
+            State::End => Err(ExtractError::FileNotFound),
+            State::Header if self.header.is_empty() => Err(ExtractError::FileNotFound),
+            State::Header | State::Skip { .. } | State::File { .. } => Err(unexpected_eof().into()),
+        }
+    }
+
+    /// Parse the collected header block and switch to the next state.
+    fn parse_header(&mut self) -> Result<(), ExtractError> {
+        // An empty block marks the end of an archive.
+        if self.header.iter().all(|byte| *byte == 0) {
+            self.header.clear();
+            self.state = State::End;
+            return Ok(());
+        }
+
+        let header = tar::Header::from_byte_slice(&self.header);
+
+        let checksum = self.header[..148]
+            .iter()
+            .chain(&self.header[156..])
+            .fold(8 * u32::from(b' '), |sum, byte| sum + u32::from(*byte));
+
+        if checksum != header.cksum()? {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "archive header checksum mismatch",
+            )
+            .into());
+        }
+
+        let size = usize::try_from(header.entry_size()?)
+            .map_err(|_| ExtractError::FileSizeLimitExceeded)?;
+
+        self.state = if header.entry_type().is_file() {
+            if size > self.limit {
+                return Err(ExtractError::FileSizeLimitExceeded);
+            }
+
+            self.file.reserve_exact(size);
+
+            if size == 0 {
+                State::Done
+            } else {
+                State::File { remaining: size }
+            }
+        } else {
+            let padded = size
+                .checked_add(BLOCK_SIZE - 1)
+                .ok_or(ExtractError::FileSizeLimitExceeded)?
+                / BLOCK_SIZE
+                * BLOCK_SIZE;
+
+            if padded == 0 {
+                State::Header
+            } else {
+                State::Skip { remaining: padded }
+            }
+        };
+
+        self.header.clear();
+
+        Ok(())
+    }
+}
+
+/// Create an [`io::Error`] for archives that end unexpectedly.
+fn unexpected_eof() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of archive")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExtractError, FileExtractor};
+
+    /// Create a synthetic `tar` archive with the provided file contents.
+    fn archive(contents: &[u8]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+
+        // Long paths are stored using additional entries, which have to be skipped.
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        builder
+            .append_data(&mut header, "a".repeat(128) + "/main.wasm", contents)
+            .unwrap();
+
+        builder.into_inner().unwrap()
+    }
+
+    /// Extract a file from the provided archive passing it in chunks of the provided size.
+    fn extract(archive: &[u8], chunk_size: usize, limit: usize) -> Result<Vec<u8>, ExtractError> {
+        let mut extractor = FileExtractor::new(limit);
+
+        for chunk in archive.chunks(chunk_size) {
+            extractor.push(chunk)?;
+        }
+
+        extractor.finish()
+    }
+
+    #[test]
+    fn below_and_at_limit() {
+        let contents = (0..1500).map(|i| i as u8).collect::<Vec<_>>();
+        let archive = archive(&contents);
+
+        for chunk_size in [1, 7, 512, archive.len()] {
+            for limit in [contents.len(), contents.len() + 1] {
+                let file = extract(&archive, chunk_size, limit).unwrap();
+
+                assert_eq!(file, contents);
+                assert_eq!(file.capacity(), contents.len());
+            }
+        }
+    }
+
+    #[test]
+    fn above_limit() {
+        let contents = vec![1; 1500];
+        let archive = archive(&contents);
+
+        for chunk_size in [1, 512, archive.len()] {
+            assert!(matches!(
+                extract(&archive, chunk_size, contents.len() - 1),
+                Err(ExtractError::FileSizeLimitExceeded)
+            ));
+        }
+    }
+
+    #[test]
+    fn empty_file() {
+        assert_eq!(extract(&archive(&[]), 512, 0).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn truncated_archive() {
+        let archive = archive(&[1; 1500]);
+
+        assert!(matches!(
+            extract(&archive[..2000], 512, 2048),
+            Err(ExtractError::Io(_))
+        ));
+        assert!(matches!(
+            extract(&[], 512, 2048),
+            Err(ExtractError::FileNotFound)
+        ));
+        assert!(matches!(
+            extract(&[0; 1024], 512, 2048),
+            Err(ExtractError::FileNotFound)
+        ));
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use bollard::{
+    auth::DockerCredentials,
+    container::{
+        AttachContainerOptions, Config, CreateContainerOptions, DownloadFromContainerOptions,
+        LogOutput, RemoveContainerOptions,
+    },
+    errors::Error,
+    image::{CreateImageOptions, ListImagesOptions},
+    service::ContainerWaitResponse,
+    volume::CreateVolumeOptions,
+    Docker,
+};
+use bytes::Bytes;
+use futures_util::{stream::BoxStream, StreamExt, TryStreamExt};
+
+/// Container runtime operations used during the build session processing.
+///
+/// This trait is implemented for the [`Docker`] client, which is also
+/// used to communicate with Podman, and for an in-memory runtime used in tests.
+#[async_trait]
+pub trait ContainerRuntime: Send + Sync {
+    /// List identifiers of local images that match the provided image reference.
+    async fn list_images(&self, reference: &str) -> Result<Vec<String>, Error>;
+
+    /// Pull the provided image from the registry, authenticating with the provided credentials.
+    async fn create_image(
+        &self,
+        image: &str,
+        credentials: Option<DockerCredentials>,
+    ) -> Result<(), Error>;
+
+    /// Get repository digests of the provided local image.
+    async fn repository_digests(&self, image: &str) -> Result<Vec<String>, Error>;
+
+    /// Create a named volume backed by the provided ext4-formatted block device.
+    async fn create_volume(&self, name: &str, device: &str) -> Result<(), Error>;
+
+    /// Remove the provided named volume.
+    async fn remove_volume(&self, name: &str) -> Result<(), Error>;
+
+    /// Create a new container, returning its identifier.
+    async fn create_container(&self, name: &str, config: Config<&str>) -> Result<String, Error>;
+
+    /// Start the provided container.
+    async fn start_container(&self, id: &str) -> Result<(), Error>;
+
+    /// Attach to the standard output and error streams of the provided container.
+    async fn attach_container(
+        &self,
+        id: &str,
+    ) -> Result<BoxStream<'static, Result<LogOutput, Error>>, Error>;
+
+    /// Wait for the provided container to exit.
+    fn wait_container(&self, id: &str) -> BoxStream<'_, Result<ContainerWaitResponse, Error>>;
+
+    /// Forcefully remove the provided container alongside with its anonymous volumes.
+    async fn remove_container(&self, id: &str) -> Result<(), Error>;
+
+    /// Download a `tar` archive with the provided file from the container's filesystem.
+    fn download_from_container(&self, id: &str, path: &str) -> BoxStream<'_, Result<Bytes, Error>>;
+}
+
+#[async_trait]
+impl ContainerRuntime for Docker {
+    async fn list_images(&self, reference: &str) -> Result<Vec<String>, Error> {
+        let list = Docker::list_images(
+            self,
+            Some(ListImagesOptions {
+                filters: HashMap::from([("reference", vec![reference])]),
+                ..Default::default()
+            }),
+        )
+        .await?;
+
+        Ok(list.into_iter().map(|image| image.id).collect())
+    }
+
+    async fn create_image(
+        &self,
+        image: &str,
+        credentials: Option<DockerCredentials>,
+    ) -> Result<(), Error> {
+        Docker::create_image(
+            self,
+            Some(CreateImageOptions {
+                from_image: image,
+                ..Default::default()
+            }),
+            None,
+            credentials,
+        )
+        .map_ok(|_| ())
+        .try_collect::<()>()
+        .await
+    }
+
+    async fn repository_digests(&self, image: &str) -> Result<Vec<String>, Error> {
+        let inspect = self.inspect_image(image).await?;
+
+        Ok(inspect.repo_digests.unwrap_or_default())
+    }
+
+    async fn create_volume(&self, name: &str, device: &str) -> Result<(), Error> {
+        Docker::create_volume(
+            self,
+            CreateVolumeOptions {
+                name,
+                driver: "local",
+                driver_opts: HashMap::from([("device", device), ("type", "ext4")]),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_volume(&self, name: &str) -> Result<(), Error> {
+        Docker::remove_volume(self, name, None).await
+    }
+
+    async fn create_container(&self, name: &str, config: Config<&str>) -> Result<String, Error> {
+        let container = Docker::create_container(
+            self,
+            Some(CreateContainerOptions {
+                name,
+                platform: Some("linux/amd64"),
+            }),
+            config,
+        )
+        .await?;
+
+        Ok(container.id)
+    }
+
+    async fn start_container(&self, id: &str) -> Result<(), Error> {
+        Docker::start_container::<String>(self, id, None).await
+    }
+
+    async fn attach_container(
+        &self,
+        id: &str,
+    ) -> Result<BoxStream<'static, Result<LogOutput, Error>>, Error> {
+        let raw = Docker::attach_container::<String>(
+            self,
+            id,
+            Some(AttachContainerOptions {
+                stdout: Some(true),
+                stderr: Some(true),
+                stream: Some(true),
+                logs: Some(true),
+                ..Default::default()
+            }),
+        )
+        .await?;
+
+        Ok(raw.output)
+    }
+
+    fn wait_container(&self, id: &str) -> BoxStream<'_, Result<ContainerWaitResponse, Error>> {
+        Docker::wait_container::<String>(self, id, None).boxed()
+    }
+
+    async fn remove_container(&self, id: &str) -> Result<(), Error> {
+        Docker::remove_container(
+            self,
+            id,
+            Some(RemoveContainerOptions {
+                v: true,
+                force: true,
+                ..Default::default()
+            }),
+        )
+        .await
+    }
+
+    fn download_from_container(&self, id: &str, path: &str) -> BoxStream<'_, Result<Bytes, Error>> {
+        let options = DownloadFromContainerOptions {
+            path: String::from(path),
+        };
+
+        Docker::download_from_container(self, id, Some(options)).boxed()
+    }
+}
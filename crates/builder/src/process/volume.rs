@@ -1,6 +1,16 @@
+//! # Volume backends
+//!
+//! Volumes can be created using one of the following backends,
+//! depending on the [configuration](common::config::Builder):
+//!
+//! * ext4 image, which is the default one and the only backend that enforces volume size
+//!   on the filesystem level without additional privileges;
+//! * bind directory, which is just a temporary directory inside the images path;
+//! * tmpfs, which mounts a size-limited tmpfs inside a temporary directory.
+//!
 //! # Creation process
 //!
-//! To create such a volume, we first create a [temporary file]
+//! To create an ext4 image volume, we first create a [temporary file]
 //! inside a directory specified in the [configuration](common::config::Builder).
 //!
 //! After that, we resize it to the required container volume size with `fallocate`
@@ -12,21 +22,29 @@
 //! Generated loop device path is passed to Docker container for mounting purposes
 //! during container instantiation later.
 //!
+//! Other backends create a [temporary directory] instead, which is bind-mounted
+//! into containers. For the tmpfs backend, a tmpfs of the configured size
+//! is mounted into the temporary directory using `mount`.
+//!
 //! # Removal process
 //!
 //! After the container finished its build process, volumes are meant to be deleted,
 //! since they are created for a single build session.
 //!
-//! To delete a volume, `udisksctl` is used to remove the previously created
+//! To delete an ext4 image volume, `udisksctl` is used to remove the previously created
 //! loop device. After the loop device is removed, we simply remove the temporary
 //! file created to handle the filesystem itself.
 //!
+//! Directory-based volumes are removed recursively, with tmpfs being unmounted beforehand.
+//!
 //! [temporary file]: tempfile::NamedTempFile
+//! [temporary directory]: tempfile::TempDir
 
 use std::{io, path::Path, process::Stdio, str};
 
+use common::config::VolumeBackend;
 use derive_more::{Display, Error, From};
-use tempfile::NamedTempFile;
+use tempfile::{NamedTempFile, TempDir};
 use tokio::process::Command;
 
 /// [`Volume`]-related errors.
@@ -46,23 +64,83 @@ pub enum VolumeError {
     /// Unable to create loop device using `udisksctl`.
     #[display(fmt = "unable to create the device with udisks")]
     Udisks,
+
+    /// Unable to mount or unmount tmpfs.
+    #[display(fmt = "unable to mount or unmount tmpfs")]
+    Tmpfs,
+}
+
+/// Source of the volume data, used to mount the volume into containers.
+#[derive(Debug, PartialEq, Eq)]
+pub enum VolumeSource<'a> {
+    /// Block device with an ext4 filesystem.
+    Device(&'a str),
+
+    /// Host directory, which has to be bind-mounted.
+    Directory(&'a Path),
 }
 
 /// Isolated container volume.
-pub struct Volume {
-    /// Loop device path.
-    device: String,
+pub enum Volume {
+    /// ext4 image attached as a loop device.
+    Ext4Image {
+        /// Loop device path.
+        device: String,
+
+        /// ext4-formatted temporary file.
+        file: NamedTempFile,
+    },
 
-    /// ext4-formatted temporary file.
-    file: NamedTempFile,
+    /// Plain temporary directory.
+    Bind {
+        /// Temporary directory path.
+        dir: TempDir,
+    },
+
+    /// Temporary directory with a mounted tmpfs.
+    Tmpfs {
+        /// Temporary directory path.
+        dir: TempDir,
+    },
 }
 
 impl Volume {
-    /// Create new [`Volume`] inside the provided `path` with the provided `size`.
+    /// Create new [`Volume`] using the provided `backend` inside the provided `path`
+    /// with the provided `size`.
     ///
     /// `size` value must be formatted in a way that is compatible with `fallocate`'s
     /// `-l` flag. See `fallocate(1)` man page for more information.
-    pub async fn new(path: &Path, size: &str) -> Result<Self, VolumeError> {
+    pub async fn new(backend: VolumeBackend, path: &Path, size: &str) -> Result<Self, VolumeError> {
+        match backend {
+            VolumeBackend::Ext4Image => Self::new_ext4_image(path, size).await,
+            VolumeBackend::Bind => Ok(Self::Bind {
+                dir: TempDir::new_in(path)?,
+            }),
+            VolumeBackend::Tmpfs => {
+                let dir = TempDir::new_in(path)?;
+
+                let mount = Command::new("mount")
+                    .args(["-t", "tmpfs", "-o"])
+                    .arg(format!("size={size}"))
+                    .arg("tmpfs")
+                    .arg(dir.path())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()?
+                    .wait()
+                    .await?;
+
+                if !mount.success() {
+                    return Err(VolumeError::Tmpfs);
+                }
+
+                Ok(Self::Tmpfs { dir })
+            }
+        }
+    }
+
+    /// Create new ext4 image [`Volume`].
+    async fn new_ext4_image(path: &Path, size: &str) -> Result<Self, VolumeError> {
         let file = NamedTempFile::new_in(path)?;
 
         let fallocate = Command::new("fallocate")
@@ -107,30 +185,53 @@ impl Volume {
             .ok_or(VolumeError::Udisks)?
             .to_string();
 
-        Ok(Self { device, file })
+        Ok(Self::Ext4Image { device, file })
     }
 
-    /// Get underlying loop device path.
-    pub fn device(&self) -> &str {
-        &self.device
+    /// Get underlying volume data source.
+    pub fn source(&self) -> VolumeSource<'_> {
+        match self {
+            Self::Ext4Image { device, .. } => VolumeSource::Device(device),
+            Self::Bind { dir } | Self::Tmpfs { dir } => VolumeSource::Directory(dir.path()),
+        }
     }
 
     /// Close the current volume.
     pub async fn close(self) -> Result<(), VolumeError> {
-        let loop_device_removal = Command::new("udisksctl")
-            .args(["loop-delete", "--no-user-interaction", "-b"])
-            .arg(self.device)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .spawn()?
-            .wait()
-            .await?;
+        match self {
+            Self::Ext4Image { device, file } => {
+                let loop_device_removal = Command::new("udisksctl")
+                    .args(["loop-delete", "--no-user-interaction", "-b"])
+                    .arg(device)
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()?
+                    .wait()
+                    .await?;
 
-        if !loop_device_removal.success() {
-            return Err(VolumeError::Udisks);
-        }
+                if !loop_device_removal.success() {
+                    return Err(VolumeError::Udisks);
+                }
 
-        self.file.close()?;
+                file.close()?;
+            }
+            Self::Bind { dir } => dir.close()?,
+            Self::Tmpfs { dir } => {
+                let unmount = Command::new("umount")
+                    .arg(dir.path())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()?
+                    .wait()
+                    .await?;
+
+                if !unmount.success() {
+                    return Err(VolumeError::Tmpfs);
+                }
+
+                dir.close()?;
+            }
+        }
 
         Ok(())
     }
@@ -144,3 +245,36 @@ impl Volume {
             .strip_suffix('.')
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use common::config::VolumeBackend;
+    use tempfile::TempDir;
+
+    use super::{Volume, VolumeSource};
+
+    #[tokio::test]
+    async fn bind_directory_cleanup() {
+        let images_path = TempDir::new().unwrap();
+
+        let volume = Volume::new(VolumeBackend::Bind, images_path.path(), "1G")
+            .await
+            .expect("unable to create volume");
+
+        let VolumeSource::Directory(path) = volume.source() else {
+            panic!("bind volume must be backed by a directory");
+        };
+
+        let path = path.to_path_buf();
+        assert!(path.starts_with(images_path.path()));
+
+        // Emulate build artifacts left by containers.
+        std::fs::create_dir_all(path.join("target/ink")).unwrap();
+        std::fs::write(path.join("target/ink/main.wasm"), [0; 16]).unwrap();
+
+        volume.close().await.expect("unable to close volume");
+
+        assert!(!path.exists());
+        assert!(images_path.path().exists());
+    }
+}
@@ -1,29 +1,35 @@
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{future::Future, path::PathBuf, sync::Arc, time::Duration};
 
-use bollard::Docker;
-use common::{config, hash, s3};
+use common::{artifact_signature::SigningKey, config, hash, paths, s3};
 use db::{
-    build_session::{self, ProcessedBuildSession},
-    build_session_token, code, diagnostic, file,
+    build_session::{self, ArtifactKind, ProcessedBuildSession},
+    build_session_stage::{self, Stage},
+    build_session_token, code,
     sea_query::{LockBehavior, LockType, OnConflict},
-    source_code, ActiveValue, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr,
-    EntityTrait, QueryFilter, QuerySelect, TransactionErrorExt, TransactionTrait,
+    selector, source_code, ActiveValue, ColumnTrait, DatabaseConnection, DatabaseTransaction,
+    DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionErrorExt,
+    TransactionTrait,
 };
 use derive_more::{Display, Error, From};
 use futures_util::{pin_mut, StreamExt, TryFutureExt};
-use ink_analyzer::Severity;
 use itertools::Itertools;
-use normalize_path::NormalizePath;
-use tokio::{sync::mpsc::UnboundedSender, task::JoinError, time::timeout};
-use tracing::{debug, error, instrument};
+use tokio::{
+    sync::mpsc::UnboundedSender,
+    time::{timeout, Instant},
+};
+use tracing::{debug, error, field, info_span, instrument, warn, Instrument};
 
 use crate::{
+    callback::{Completion, CompletionCallback},
     log_collector::LogEntry,
-    process::{container::Container, volume::Volume},
+    manifest,
+    process::{container::Container, runtime::ContainerRuntime, volume::Volume},
+    shutdown::Shutdown,
 };
 
 use super::{
-    container::{ContainerRemoveError, DownloadFromContainerError, Image},
+    artifacts::{self, ArtifactsError},
+    container::{ContainerCreateError, ContainerRemoveError, DownloadFromContainerError, Image},
     volume::VolumeError,
 };
 
@@ -38,6 +44,49 @@ const UPDATE_PERIOD: Duration = Duration::from_secs(5);
 pub(crate) enum WorkerError {
     /// Database-related error.
     DatabaseError(DbErr),
+
+    /// In-flight build session was interrupted by a forced shutdown.
+    #[display(fmt = "build session was interrupted by shutdown")]
+    Interrupted,
+}
+
+/// State shared between all build session workers.
+pub(crate) struct WorkerContext<R> {
+    /// Builder component configuration.
+    pub(crate) builder_config: config::Builder,
+
+    /// AWS S3 storage configuration.
+    pub(crate) storage_config: config::Storage,
+
+    /// Supported `cargo-contract` versions.
+    pub(crate) supported_cargo_contract_versions: Vec<String>,
+
+    /// Container runtime client.
+    pub(crate) runtime: R,
+
+    /// Database connection.
+    pub(crate) db: Arc<DatabaseConnection>,
+
+    /// Log collector channel.
+    pub(crate) log_sender: UnboundedSender<LogEntry>,
+
+    /// Webhook delivery channel.
+    pub(crate) webhook_sender: UnboundedSender<i64>,
+
+    /// Source code analysis channel.
+    pub(crate) analysis_sender: UnboundedSender<i64>,
+
+    /// API server completion callback client, if configured.
+    pub(crate) callback: Option<Arc<CompletionCallback>>,
+
+    /// Graceful shutdown signal.
+    pub(crate) shutdown: Shutdown,
+
+    /// Hostname of the current builder instance, recorded for each handled build session.
+    pub(crate) hostname: String,
+
+    /// Key used to sign artifacts of completed build sessions, if configured.
+    pub(crate) signing_key: Option<SigningKey>,
 }
 
 /// Spawn a worker that will handle incoming build sessions.
@@ -46,124 +95,485 @@ pub(crate) enum WorkerError {
 /// as it handles new build sessions in a loop, while also attempting to recover
 /// from any occuring errors.
 ///
+/// The worker exits after the shutdown signal is triggered, finishing
+/// the in-flight build session first.
+///
 /// [`Future`]: std::future::Future
 #[instrument(skip_all)]
-pub(crate) async fn spawn(
-    builder_config: Arc<config::Builder>,
-    storage_config: Arc<config::Storage>,
-    supported_cargo_contract_versions: Arc<Vec<String>>,
-    docker: Arc<Docker>,
-    db: Arc<DatabaseConnection>,
-    log_sender: UnboundedSender<LogEntry>,
-) {
-    loop {
-        let outcome = db
-            .transaction::<_, _, WorkerError>(|txn| {
-                let builder_config = builder_config.clone();
-                let storage_config = storage_config.clone();
-                let supported_cargo_contract_versions = supported_cargo_contract_versions.clone();
-                let docker = docker.clone();
-                let log_sender = log_sender.clone();
-
-                Box::pin(async move {
-                    let mut session_query = build_session::Entity::find()
-                        .select_only()
-                        .columns([
-                            build_session::Column::Id,
-                            build_session::Column::SourceCodeId,
-                            build_session::Column::CargoContractVersion,
-                            build_session::Column::ProjectDirectory,
-                        ])
-                        .filter(build_session::Column::Status.eq(build_session::Status::New));
-
-                    // Skip any locked build sessions to handle the build session
-                    // table as a queue.
-                    QuerySelect::query(&mut session_query)
-                        .lock_with_behavior(LockType::NoKeyUpdate, LockBehavior::SkipLocked);
-
-                    if let Some(build_session) = session_query
-                        .into_model::<build_session::ProcessedBuildSession>()
-                        .one(txn)
-                        .await?
-                    {
-                        let mut wasm_buf = vec![0; builder_config.wasm_size_limit];
-                        let mut metadata_buf = vec![0; builder_config.metadata_size_limit];
-
-                        let val = |wasm_buf, metadata_buf| async {
-                            Instance::new(
-                                &build_session,
-                                &builder_config,
-                                &docker,
-                                &storage_config,
-                                txn,
+pub(crate) async fn spawn<R: ContainerRuntime + 'static>(context: Arc<WorkerContext<R>>) {
+    run_loop(&context.shutdown, UPDATE_PERIOD, || process_next(&context)).await
+}
+
+/// Run worker iterations until the shutdown signal is triggered.
+///
+/// Each iteration returns `true` if a build session was handled. Otherwise, the next iteration
+/// is delayed by the provided `poll_period`, unless the shutdown signal is triggered earlier.
+async fn run_loop<F, Fut>(shutdown: &Shutdown, poll_period: Duration, mut iteration: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    while !shutdown.is_triggered() {
+        if !iteration().await {
+            tokio::select! {
+                _ = tokio::time::sleep(poll_period) => {},
+                _ = shutdown.triggered() => {},
+            }
+        }
+    }
+}
+
+/// Lock the oldest build session that is not yet handled by any other worker.
+///
+/// The returned build session stays locked until the provided transaction is finished.
+async fn claim_next(txn: &DatabaseTransaction) -> Result<Option<ProcessedBuildSession>, DbErr> {
+    let mut session_query = build_session::Entity::find()
+        .select_only()
+        .columns([
+            build_session::Column::Id,
+            build_session::Column::SourceCodeId,
+            build_session::Column::CargoContractVersion,
+            build_session::Column::ProjectDirectory,
+        ])
+        .filter(build_session::Column::Status.eq(build_session::Status::New))
+        .order_by_asc(build_session::Column::Id);
+
+    // Skip any locked build sessions to handle the build session
+    // table as a queue.
+    QuerySelect::query(&mut session_query)
+        .lock_with_behavior(LockType::NoKeyUpdate, LockBehavior::SkipLocked);
+
+    session_query
+        .into_model::<ProcessedBuildSession>()
+        .one(txn)
+        .await
+}
+
+/// Record the claimed build session as handled by the builder instance with the provided hostname.
+///
+/// Source code is analyzed separately after the transaction is committed,
+/// thus diagnostics are marked as pending as well.
+async fn start_session(
+    txn: &DatabaseTransaction,
+    build_session_id: i64,
+    hostname: &str,
+) -> Result<(), DbErr> {
+    build_session::Entity::update_many()
+        .filter(build_session::Column::Id.eq(build_session_id))
+        .col_expr(build_session::Column::DiagnosticsPending, true.into())
+        .col_expr(build_session::Column::BuilderHostname, hostname.into())
+        .exec(txn)
+        .await?;
+
+    Ok(())
+}
+
+/// Handle the next available build session.
+///
+/// Returns `false` if there were no build sessions to handle.
+///
+/// Build sessions interrupted by a forced shutdown are rolled back,
+/// which returns them to the `New` status to be handled again after restart.
+async fn process_next<R: ContainerRuntime + 'static>(context: &Arc<WorkerContext<R>>) -> bool {
+    let outcome = context
+        .db
+        .transaction::<_, _, WorkerError>(|txn| {
+            let context = context.clone();
+
+            Box::pin(async move {
+                if let Some(build_session) = claim_next(txn).await? {
+                    start_session(txn, build_session.id, &context.hostname).await?;
+
+                    let mut durations = StageDurations::default();
+                    let mut image_digest = None;
+                    let mut build_image = None;
+
+                    let stages = StageReporter {
+                        db: &context.db,
+                        log_sender: &context.log_sender,
+                        build_session_id: build_session.id,
+                    };
+
+                    let outcome = async {
+                        let instance = Instance::new(
+                            &build_session,
+                            &context.builder_config,
+                            &context.runtime,
+                            &context.storage_config,
+                            &context.shutdown,
+                            txn,
+                        );
+
+                        let instance = stages
+                            .run(
+                                Stage::Unarchiving,
+                                &mut durations.unarchive,
+                                instance.unarchive(context.log_sender.clone()),
+                            )
+                            .await?;
+
+                        let instance = stages
+                            .run(
+                                Stage::Building,
+                                &mut durations.build,
+                                instance.build(
+                                    context.log_sender.clone(),
+                                    &context.supported_cargo_contract_versions,
+                                    &mut image_digest,
+                                    &mut build_image,
+                                ),
+                            )
+                            .await?;
+
+                        stages
+                            .run(
+                                Stage::Extracting,
+                                &mut durations.move_files,
+                                instance.get_files(),
                             )
-                            .unarchive()
-                            .await?
-                            .build(log_sender, &supported_cargo_contract_versions)
-                            .await?
-                            .get_files(wasm_buf, metadata_buf)
                             .await
-                        };
-
-                        match val(&mut wasm_buf, &mut metadata_buf).await {
-                            Ok((wasm, metadata)) => {
-                                let code_hash = hash::blake2(wasm);
-
-                                build_session::Entity::update_many()
-                                    .filter(build_session::Column::Id.eq(build_session.id))
-                                    .col_expr(
-                                        build_session::Column::Status,
-                                        build_session::Status::Completed.into(),
-                                    )
-                                    .col_expr(
-                                        build_session::Column::CodeHash,
-                                        (&code_hash[..]).into(),
-                                    )
-                                    .col_expr(build_session::Column::Metadata, metadata.into())
-                                    .exec(txn)
-                                    .await?;
-
-                                code::Entity::insert(code::ActiveModel {
-                                    hash: ActiveValue::Set(code_hash.to_vec()),
-                                    code: ActiveValue::Set(wasm.to_vec()),
-                                })
-                                .on_conflict(
-                                    OnConflict::column(code::Column::Hash)
-                                        .do_nothing()
-                                        .to_owned(),
-                                )
-                                .exec_without_returning(txn)
-                                .await?;
-                            }
-                            Err(_) => {
-                                build_session::Entity::update_many()
-                                    .filter(build_session::Column::Id.eq(build_session.id))
-                                    .col_expr(
-                                        build_session::Column::Status,
-                                        build_session::Status::Failed.into(),
-                                    )
-                                    .exec(txn)
-                                    .await?;
-                            }
-                        }
-
-                        Ok(false)
-                    } else {
-                        Ok(true)
                     }
-                })
+                    .await;
+
+                    // Rollback the transaction to leave the interrupted build session unchanged.
+                    if let Err(SessionError::Interrupted) = outcome {
+                        stages.clear().await;
+                        return Err(WorkerError::Interrupted);
+                    }
+
+                    let completion = finish_session(
+                        txn,
+                        build_session.id,
+                        outcome.ok(),
+                        &durations,
+                        image_digest.as_deref(),
+                        build_image.as_deref(),
+                        context.signing_key.as_ref(),
+                    )
+                    .await?;
+
+                    Ok(Some(completion))
+                } else {
+                    Ok(None)
+                }
             })
-            .await
-            .into_raw_result();
+        })
+        .await
+        .into_raw_result();
+
+    match outcome {
+        // Webhooks, source code analysis and the API server are handled only after
+        // the final build session status was committed.
+        Ok(Some(completion)) => {
+            if let Err(e) = context.webhook_sender.send(completion.build_session_id) {
+                error!(%e, "unable to enqueue webhook delivery")
+            }
+
+            if let Err(e) = context.analysis_sender.send(completion.build_session_id) {
+                error!(%e, "unable to enqueue source code analysis")
+            }
+
+            if let Some(callback) = &context.callback {
+                tokio::spawn(callback.clone().notify(completion));
+            }
+
+            true
+        }
+        Ok(None) => false,
+        Err(WorkerError::Interrupted) => {
+            warn!("build session was interrupted by shutdown and returned to the queue");
+            true
+        }
+        Err(error) => {
+            error!(%error, "worker error");
+            true
+        }
+    }
+}
+
+/// Durations of build session stages, in milliseconds.
+#[derive(Default)]
+struct StageDurations {
+    /// Source code unarchiving stage duration.
+    unarchive: Option<i64>,
+
+    /// Contract build stage duration.
+    build: Option<i64>,
+
+    /// Build artifacts retrieval stage duration.
+    move_files: Option<i64>,
+}
+
+/// Run a single build session stage inside of a separate [`tracing`] span,
+/// storing its elapsed time in the provided `duration` value.
+async fn timed<F: Future>(stage: &'static str, duration: &mut Option<i64>, future: F) -> F::Output {
+    let span = info_span!("stage", stage, elapsed_ms = field::Empty);
+    let start = Instant::now();
+
+    let output = future.instrument(span.clone()).await;
+
+    let elapsed_ms = start.elapsed().as_millis() as i64;
+    span.record("elapsed_ms", elapsed_ms);
+    span.in_scope(|| debug!(elapsed_ms, "stage finished"));
+
+    *duration = Some(elapsed_ms);
+
+    output
+}
+
+/// Build session stage reporter.
+///
+/// Stages are stored using the shared database connection instead of the worker transaction,
+/// since the build session row stays locked until the build session is finished.
+///
+/// Failed stages are explained to users with a separate log entry, so that build session
+/// logs are never empty for failed build sessions.
+struct StageReporter<'a> {
+    /// Database connection.
+    db: &'a DatabaseConnection,
+
+    /// Log entry sender used to report stage failures.
+    log_sender: &'a UnboundedSender<LogEntry>,
+
+    /// Processed build session identifier.
+    build_session_id: i64,
+}
+
+impl StageReporter<'_> {
+    /// Enter the provided build session stage and run it using [`timed`].
+    async fn run<T, F: Future<Output = Result<T, SessionError>>>(
+        &self,
+        stage: Stage,
+        duration: &mut Option<i64>,
+        future: F,
+    ) -> F::Output {
+        self.enter(stage).await;
+
+        let name = match stage {
+            Stage::Unarchiving => "unarchive",
+            Stage::Building => "build",
+            Stage::Extracting => "move",
+        };
+
+        let output = timed(name, duration, future).await;
+
+        match &output {
+            // Interrupted build sessions are returned to the queue and are not failed.
+            Ok(_) | Err(SessionError::Interrupted) => {}
+            Err(error) => self.report_failure(stage, error),
+        }
+
+        output
+    }
+
+    /// Send a log entry, which explains the failure of the provided stage.
+    fn report_failure(&self, stage: Stage, error: &SessionError) {
+        let result = self.log_sender.send(LogEntry {
+            build_session_id: self.build_session_id,
+            text: failure_message(stage, error),
+        });
+
+        if let Err(e) = result {
+            error!(%e, "unable to send log entry")
+        }
+    }
+
+    /// Store the provided stage as the current one.
+    ///
+    /// Any errors are only logged, since stages are not required to process build sessions.
+    async fn enter(&self, stage: Stage) {
+        let result = build_session_stage::Entity::insert(build_session_stage::ActiveModel {
+            build_session_id: ActiveValue::Set(self.build_session_id),
+            stage: ActiveValue::Set(stage),
+        })
+        .on_conflict(
+            OnConflict::column(build_session_stage::Column::BuildSessionId)
+                .update_column(build_session_stage::Column::Stage)
+                .to_owned(),
+        )
+        .exec_without_returning(self.db)
+        .await;
+
+        if let Err(e) = result {
+            warn!(%e, ?stage, "unable to store build session stage");
+        }
+    }
+
+    /// Remove the current stage of a build session that was returned to the queue.
+    async fn clear(&self) {
+        let result = build_session_stage::Entity::delete_by_id(self.build_session_id)
+            .exec(self.db)
+            .await;
 
-        match outcome {
-            Ok(empty) if empty => tokio::time::sleep(UPDATE_PERIOD).await,
-            Err(error) => error!(%error, "worker error"),
-            _ => {}
+        if let Err(e) = result {
+            warn!(%e, "unable to remove build session stage");
         }
     }
 }
 
+/// Create a user-facing explanation of the provided stage failure.
+///
+/// Errors caused by the deployment environment are not described in detail,
+/// since their messages may contain internal information.
+fn failure_message(stage: Stage, error: &SessionError) -> String {
+    let stage = match stage {
+        Stage::Unarchiving => "unarchiving",
+        Stage::Building => "building",
+        Stage::Extracting => "extracting",
+    };
+
+    let reason = match error {
+        SessionError::DownloadFromContainerError(_)
+        | SessionError::ArtifactsError(_)
+        | SessionError::ContainerExited(_)
+        | SessionError::TimedOut
+        | SessionError::UnsupportedCargoContractVersion
+        | SessionError::InvalidProjectDirectory => error.to_string(),
+        _ => String::from("internal builder error"),
+    };
+
+    format!("\nBuild session failed during the {stage} stage: {reason}\n")
+}
+
+/// Build artifacts retrieved from the container.
+struct BuildArtifacts {
+    /// Contract WASM or PolkaVM blob.
+    code: Vec<u8>,
+
+    /// Kind of the contract blob.
+    kind: ArtifactKind,
+
+    /// Contract JSON metadata.
+    metadata: Vec<u8>,
+
+    /// Contract crate name.
+    contract_name: String,
+}
+
+/// Store the final build session status alongside with its stage durations
+/// and the digest and reference of the build image used.
+///
+/// Build artifacts are expected to be [`Some`] only if the build session finished successfully,
+/// in which case message selectors are extracted from the contract metadata and, if a signing key
+/// is provided, the code hash and the JSON metadata hash are signed with it.
+/// The current build session stage and build session tokens are removed, since
+/// the build session is no longer processed.
+async fn finish_session(
+    txn: &DatabaseTransaction,
+    build_session_id: i64,
+    artifacts: Option<BuildArtifacts>,
+    durations: &StageDurations,
+    image_digest: Option<&str>,
+    build_image: Option<&str>,
+    signing_key: Option<&SigningKey>,
+) -> Result<Completion, DbErr> {
+    build_session_stage::Entity::delete_by_id(build_session_id)
+        .exec(txn)
+        .await?;
+
+    build_session_token::Entity::delete_many()
+        .filter(build_session_token::Column::BuildSessionId.eq(build_session_id))
+        .exec(txn)
+        .await?;
+
+    let update = build_session::Entity::update_many()
+        .filter(build_session::Column::Id.eq(build_session_id))
+        .col_expr(build_session::Column::ImageDigest, image_digest.into())
+        .col_expr(build_session::Column::BuildImage, build_image.into())
+        .col_expr(
+            build_session::Column::UnarchiveDuration,
+            durations.unarchive.into(),
+        )
+        .col_expr(build_session::Column::BuildDuration, durations.build.into())
+        .col_expr(
+            build_session::Column::MoveDuration,
+            durations.move_files.into(),
+        );
+
+    match artifacts {
+        Some(BuildArtifacts {
+            code,
+            kind,
+            metadata,
+            contract_name,
+        }) => {
+            let code_hash = code_hash(kind, &code);
+
+            code::Entity::insert(code::ActiveModel {
+                hash: ActiveValue::Set(code_hash.to_vec()),
+                code: ActiveValue::Set(code),
+            })
+            .on_conflict(
+                OnConflict::column(code::Column::Hash)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(txn)
+            .await?;
+
+            selector::store(txn, &code_hash, &metadata).await?;
+
+            let metadata_hash = hash::blake2(&metadata);
+
+            let (signature, signing_key_id) = match signing_key {
+                Some(key) => (
+                    Some(key.sign(&code_hash, &metadata_hash).0.to_vec()),
+                    Some(key.key_id()),
+                ),
+                None => (None, None),
+            };
+
+            update
+                .col_expr(
+                    build_session::Column::Status,
+                    build_session::Status::Completed.into(),
+                )
+                .col_expr(build_session::Column::CodeHash, (&code_hash[..]).into())
+                .col_expr(build_session::Column::ArtifactKind, kind.into())
+                .col_expr(build_session::Column::Metadata, metadata.into())
+                .col_expr(
+                    build_session::Column::MetadataHash,
+                    (&metadata_hash[..]).into(),
+                )
+                .col_expr(build_session::Column::ContractName, contract_name.into())
+                .col_expr(build_session::Column::Signature, signature.into())
+                .col_expr(build_session::Column::SigningKeyId, signing_key_id.into())
+                .exec(txn)
+                .await?;
+
+            Ok(Completion {
+                build_session_id,
+                status: build_session::Status::Completed,
+                code_hash: Some(code_hash.into()),
+            })
+        }
+        None => {
+            update
+                .col_expr(
+                    build_session::Column::Status,
+                    build_session::Status::Failed.into(),
+                )
+                .exec(txn)
+                .await?;
+
+            Ok(Completion {
+                build_session_id,
+                status: build_session::Status::Failed,
+                code_hash: None,
+            })
+        }
+    }
+}
+
+/// Compute the code hash of a contract blob with the algorithm used by chains
+/// that accept contract blobs of the provided kind.
+fn code_hash(kind: ArtifactKind, code: &[u8]) -> [u8; 32] {
+    match kind {
+        ArtifactKind::Wasm => hash::blake2(code),
+        ArtifactKind::Polkavm => hash::keccak256(code),
+    }
+}
+
 /// Build session errors, which are constrained down to a single container
 /// and are usually caused by an incorrect user input.
 #[derive(Debug, Display, Error, From)]
@@ -180,12 +590,18 @@ enum SessionError {
     /// Volume-related error.
     VolumeError(VolumeError),
 
+    /// Unable to create the container.
+    ContainerCreateError(ContainerCreateError),
+
     /// Unable to remove the container.
     ContainerRemoveError(ContainerRemoveError),
 
     /// Unable to download files from the container.
     DownloadFromContainerError(DownloadFromContainerError),
 
+    /// Unable to select build artifacts of the current contract.
+    ArtifactsError(ArtifactsError),
+
     /// Unable to acquire a [build session token](db::build_session_token)
     #[display(fmt = "missing build session token")]
     MissingBuildSessionToken,
@@ -202,43 +618,51 @@ enum SessionError {
     #[display(fmt = "container timed out")]
     TimedOut,
 
-    /// Unable to spawn ink-analyzer task.
-    #[display(fmt = "unable to spawn ink-analyzer task")]
-    InkAnalyzerSpawn(JoinError),
+    /// Container was removed due to a forced shutdown.
+    #[display(fmt = "container interrupted by shutdown")]
+    Interrupted,
 
     /// Unsupported cargo-contract version.
     #[display(fmt = "unsupported cargo-contract version")]
     UnsupportedCargoContractVersion,
+
+    /// Project directory resolves to a directory outside of the project root.
+    #[display(fmt = "project directory is outside of the project root")]
+    InvalidProjectDirectory,
 }
 
 /// Archived build session instance.
-struct Instance<'a> {
+struct Instance<'a, R> {
     /// Inner build session database record.
     build_session: &'a ProcessedBuildSession,
     /// Builder component configuration.
     builder_config: &'a config::Builder,
-    /// Docker RPC client.
-    docker: &'a Docker,
+    /// Container runtime client.
+    runtime: &'a R,
     /// AWS S3 storage configuration.
     storage_config: &'a config::Storage,
+    /// Graceful shutdown signal.
+    shutdown: &'a Shutdown,
     /// Current database transaction.
     txn: &'a DatabaseTransaction,
 }
 
-impl<'a> Instance<'a> {
+impl<'a, R: ContainerRuntime> Instance<'a, R> {
     /// Create new build session [`Instance`].
     fn new(
         build_session: &'a ProcessedBuildSession,
         builder_config: &'a config::Builder,
-        docker: &'a Docker,
+        runtime: &'a R,
         storage_config: &'a config::Storage,
+        shutdown: &'a Shutdown,
         txn: &'a DatabaseTransaction,
     ) -> Self {
         Instance {
             build_session,
             builder_config,
-            docker,
+            runtime,
             storage_config,
+            shutdown,
             txn,
         }
     }
@@ -246,8 +670,15 @@ impl<'a> Instance<'a> {
     /// Unarchive user-provided files using a separately launched container instance.
     ///
     /// This method returns [`UnarchivedInstance`], which can be used to start the build process itself.
-    #[instrument(skip(self), fields(id = %self.build_session.id), err(level = "info"))]
-    async fn unarchive(self) -> Result<UnarchivedInstance<'a>, SessionError> {
+    ///
+    /// Logs of the unarchiving container are sent using the provided `log_sender`,
+    /// so that invalid archives are visible to users. Uploaded project manifest
+    /// is checked afterwards, with any found issues stored as diagnostics.
+    #[instrument(skip(self, log_sender), fields(id = %self.build_session.id), err(level = "info"))]
+    async fn unarchive(
+        self,
+        log_sender: UnboundedSender<LogEntry>,
+    ) -> Result<UnarchivedInstance<'a, R>, SessionError> {
         let archive_hash = source_code::Entity::find_by_id(self.build_session.source_code_id)
             .select_only()
             .column(source_code::Column::ArchiveHash)
@@ -270,46 +701,10 @@ impl<'a> Instance<'a> {
             .get_source_code(&archive_hash)
             .await?;
 
-        debug!("running ink-analyzer on lib.rs file");
-
-        let lib_rs = file::Entity::find()
-            .select_only()
-            .columns([file::Column::Id, file::Column::Text])
-            .filter(file::Column::SourceCodeId.eq(self.build_session.source_code_id))
-            .filter(file::Column::Name.eq("lib.rs"))
-            .into_tuple::<(i64, String)>()
-            .one(self.txn)
-            .await?;
-
-        if let Some((file_id, text)) = lib_rs {
-            let diagnostics = tokio::task::spawn_blocking(move || {
-                ink_analyzer::Analysis::new(&text).diagnostics()
-            })
-            .await?;
-
-            if !diagnostics.is_empty() {
-                diagnostic::Entity::insert_many(diagnostics.into_iter().map(|raw_diagnostic| {
-                    diagnostic::ActiveModel {
-                        build_session_id: ActiveValue::Set(self.build_session.id),
-                        file_id: ActiveValue::Set(file_id),
-                        level: ActiveValue::Set(match raw_diagnostic.severity {
-                            Severity::Warning => diagnostic::Level::Warning,
-                            Severity::Error => diagnostic::Level::Error,
-                        }),
-                        start: ActiveValue::Set(u32::from(raw_diagnostic.range.start()) as i64),
-                        end: ActiveValue::Set(u32::from(raw_diagnostic.range.end()) as i64),
-                        message: ActiveValue::Set(raw_diagnostic.message),
-                        ..Default::default()
-                    }
-                }))
-                .exec_without_returning(self.txn)
-                .await?;
-            }
-        }
-
         debug!("creating new volume for build session");
 
         let volume = Volume::new(
+            self.builder_config.volume_backend,
             &self.builder_config.images_path,
             &self.builder_config.volume_size,
         )
@@ -319,7 +714,7 @@ impl<'a> Instance<'a> {
 
         let container = match Container::new(
             self.builder_config,
-            self.docker,
+            self.runtime,
             volume,
             &format!("unarchive-{}", self.build_session.id),
             Image::Unarchive,
@@ -339,39 +734,60 @@ impl<'a> Instance<'a> {
             }
         };
 
-        let volume = wait_and_remove(container, self.docker, self.builder_config).await?;
+        let volume = handle_session(
+            log_sender,
+            self.build_session.id,
+            container,
+            self.runtime,
+            self.builder_config,
+            self.shutdown,
+        )
+        .await?;
 
         debug!("unarchiving process completed successfully");
 
+        if let Err(err) = manifest::store_diagnostics(self.txn, self.build_session).await {
+            volume.close().await?;
+            return Err(err.into());
+        }
+
         Ok(UnarchivedInstance {
             build_session: self.build_session,
             builder_config: self.builder_config,
-            docker: self.docker,
+            runtime: self.runtime,
+            shutdown: self.shutdown,
             volume,
         })
     }
 }
 
 /// Build session instance with unarchived user files.
-struct UnarchivedInstance<'a> {
+struct UnarchivedInstance<'a, R> {
     /// Inner build session database record.
     build_session: &'a ProcessedBuildSession,
     /// Builder component configuration.
     builder_config: &'a config::Builder,
-    /// Docker RPC client.
-    docker: &'a Docker,
+    /// Container runtime client.
+    runtime: &'a R,
+    /// Graceful shutdown signal.
+    shutdown: &'a Shutdown,
     /// Inner volume with unarchived source code.
     volume: Volume,
 }
 
-impl<'a> UnarchivedInstance<'a> {
+impl<'a, R: ContainerRuntime> UnarchivedInstance<'a, R> {
     /// Start build process for the current build session instance.
-    #[instrument(skip(self, log_sender, supported_cargo_contract_versions), fields(id = %self.build_session.id), err(level = "info"))]
+    ///
+    /// Digest and reference of the build image are stored in the provided `image_digest`
+    /// and `build_image` values as soon as the build container is started.
+    #[instrument(skip(self, log_sender, supported_cargo_contract_versions, image_digest, build_image), fields(id = %self.build_session.id), err(level = "info"))]
     pub async fn build(
         self,
         log_sender: UnboundedSender<LogEntry>,
         supported_cargo_contract_versions: &[String],
-    ) -> Result<BuiltInstance<'a>, SessionError> {
+        image_digest: &mut Option<String>,
+        build_image: &mut Option<String>,
+    ) -> Result<BuiltInstance<'a, R>, SessionError> {
         debug!("spawning container for building purposes");
 
         if !supported_cargo_contract_versions.contains(&self.build_session.cargo_contract_version) {
@@ -399,18 +815,34 @@ impl<'a> UnarchivedInstance<'a> {
             return Err(SessionError::UnsupportedCargoContractVersion);
         }
 
-        let normalized_path =
+        let Some(normalized_path) =
             normalize_working_dir(self.build_session.project_directory.as_deref())
-                .display()
-                .to_string();
+        else {
+            self.volume.close().await?;
+            return Err(SessionError::InvalidProjectDirectory);
+        };
+
+        let normalized_path = normalized_path.display().to_string();
+
+        let mirror = self
+            .builder_config
+            .registry
+            .as_ref()
+            .and_then(|registry| registry.mirror.as_deref());
 
         let container = match Container::new(
             self.builder_config,
-            self.docker,
+            self.runtime,
             self.volume,
             &format!("build-session-{}", self.build_session.id),
             Image::Build {
                 version: &self.build_session.cargo_contract_version,
+                digest: self
+                    .builder_config
+                    .image_digests
+                    .get(&self.build_session.cargo_contract_version)
+                    .map(String::as_str),
+                mirror,
             },
             None,
             Some(&normalized_path),
@@ -424,12 +856,23 @@ impl<'a> UnarchivedInstance<'a> {
             }
         };
 
+        *image_digest = container.image_digest().map(String::from);
+        *build_image = Some(
+            Image::Build {
+                version: &self.build_session.cargo_contract_version,
+                digest: container.image_digest(),
+                mirror,
+            }
+            .to_string(),
+        );
+
         let volume = handle_session(
             log_sender,
             self.build_session.id,
             container,
-            self.docker,
+            self.runtime,
             self.builder_config,
+            self.shutdown,
         )
         .await?;
 
@@ -438,43 +881,42 @@ impl<'a> UnarchivedInstance<'a> {
         Ok(BuiltInstance {
             build_session: self.build_session,
             builder_config: self.builder_config,
-            docker: self.docker,
+            runtime: self.runtime,
+            shutdown: self.shutdown,
             volume,
             normalized_path,
         })
     }
 }
 
-/// Build session with WASM and metadata artifacts available
-struct BuiltInstance<'a> {
+/// Build session with contract blob and metadata artifacts available
+struct BuiltInstance<'a, R> {
     /// Inner build session database record.
     build_session: &'a ProcessedBuildSession,
     /// Builder component configuration.
     builder_config: &'a config::Builder,
-    /// Docker RPC client.
-    docker: &'a Docker,
+    /// Container runtime client.
+    runtime: &'a R,
+    /// Graceful shutdown signal.
+    shutdown: &'a Shutdown,
     /// Inner volume with unarchived source code.
     volume: Volume,
     /// Normalized project directory path value.
     normalized_path: String,
 }
 
-impl<'a> BuiltInstance<'a> {
-    /// Rename artifacts files and write them into the provided buffers.
+impl<'a, R: ContainerRuntime> BuiltInstance<'a, R> {
+    /// List artifacts files of the built contracts and download the ones
+    /// that belong to the current project directory from the container.
     ///
-    /// This methods returns an [`Err`] if the provided buffers are insufficient in size to write
-    /// build artifacts.
-    #[instrument(skip(self, wasm_buf, metadata_buf), fields(id = %self.build_session.id), err(level = "info"))]
-    async fn get_files<'b>(
-        self,
-        wasm_buf: &'b mut [u8],
-        metadata_buf: &'b mut [u8],
-    ) -> Result<(&'b [u8], &'b [u8]), SessionError> {
-        debug!("spawning container for file rename purposes");
+    /// This methods returns an [`Err`] if build artifacts exceed the configured size limits.
+    #[instrument(skip(self), fields(id = %self.build_session.id), err(level = "info"))]
+    async fn get_files(self) -> Result<BuildArtifacts, SessionError> {
+        debug!("spawning container for artifacts listing purposes");
 
         let container = match Container::new(
             self.builder_config,
-            self.docker,
+            self.runtime,
             self.volume,
             &format!("move-{}", self.build_session.id),
             Image::Move,
@@ -490,23 +932,48 @@ impl<'a> BuiltInstance<'a> {
             }
         };
 
-        let outcome = wait(&container, self.docker, self.builder_config)
+        let outcome = wait(&container, self.runtime, self.builder_config, self.shutdown)
             .and_then(|_| async {
-                let wasm = container.wasm_file(self.docker, wasm_buf).await?;
+                let listing = container.artifacts_listing(self.runtime).await?;
+                let listing = String::from_utf8_lossy(&listing);
+
+                let artifacts = artifacts::select(&listing, &self.normalized_path)?;
+
+                let code_size_limit = match artifacts.kind {
+                    ArtifactKind::Wasm => self.builder_config.wasm_size_limit,
+                    ArtifactKind::Polkavm => self.builder_config.polkavm_size_limit,
+                };
+
+                let code = container
+                    .code_file(self.runtime, artifacts.code_path, code_size_limit)
+                    .await?;
 
-                let metadata = container.metadata_file(self.docker, metadata_buf).await?;
+                let metadata = container
+                    .metadata_file(
+                        self.runtime,
+                        artifacts.metadata_path,
+                        self.builder_config.metadata_size_limit,
+                    )
+                    .await?;
 
                 debug!(
-                    wasm_size = %wasm.len(),
+                    contract_name = %artifacts.contract_name,
+                    kind = ?artifacts.kind,
+                    code_size = %code.len(),
                     metadata_size = %metadata.len(),
-                    "retrieved WASM blob and JSON metadata successfully"
+                    "retrieved contract blob and JSON metadata successfully"
                 );
 
-                Ok((wasm, metadata))
+                Ok(BuildArtifacts {
+                    code,
+                    kind: artifacts.kind,
+                    metadata,
+                    contract_name: String::from(artifacts.contract_name),
+                })
             })
             .await;
 
-        container.remove(self.docker).await?.close().await?;
+        container.remove(self.runtime).await?.close().await?;
 
         outcome
     }
@@ -514,19 +981,25 @@ impl<'a> BuiltInstance<'a> {
 
 /// Wait for the provided [`Container`] to finish running.
 ///
-/// This function returns an [`Err`] if container returns non-zero exit code.
-async fn wait(
+/// This function returns an [`Err`] if container returns non-zero exit code,
+/// or if the shutdown is forced before the container finishes running.
+async fn wait<R: ContainerRuntime>(
     container: &Container,
-    docker: &Docker,
+    runtime: &R,
     builder_config: &config::Builder,
+    shutdown: &Shutdown,
 ) -> Result<(), SessionError> {
-    match timeout(
+    let event = timeout(
         Duration::from_secs(builder_config.max_build_duration),
-        container.events(docker).next(),
-    )
-    .await
-    .map_err(|_| SessionError::TimedOut)?
-    {
+        container.events(runtime).next(),
+    );
+
+    let event = tokio::select! {
+        event = event => event.map_err(|_| SessionError::TimedOut)?,
+        _ = shutdown.forced() => return Err(SessionError::Interrupted),
+    };
+
+    match event {
         Some(Ok(_)) | None => Ok(()),
         Some(Err(bollard::errors::Error::DockerContainerWaitError { code, .. })) => {
             Err(SessionError::ContainerExited(code))
@@ -538,14 +1011,15 @@ async fn wait(
 /// Wait for the provided [`Container`] to finish running and automatically delete it afterwards.
 ///
 /// If an error occurs during the deletion process, this function will automatically attempt to close the backing [`Volume`].
-async fn wait_and_remove(
+async fn wait_and_remove<R: ContainerRuntime>(
     container: Container,
-    docker: &Docker,
+    runtime: &R,
     builder_config: &config::Builder,
+    shutdown: &Shutdown,
 ) -> Result<Volume, SessionError> {
-    let outcome = wait(&container, docker, builder_config).await;
+    let outcome = wait(&container, runtime, builder_config, shutdown).await;
 
-    let volume = container.remove(docker).await?;
+    let volume = container.remove(runtime).await?;
 
     if let Err(err) = outcome {
         volume.close().await?;
@@ -555,30 +1029,35 @@ async fn wait_and_remove(
     }
 }
 
-/// Handle a single build session.
+/// Wait for the provided [`Container`] to finish running, while sending its logs
+/// using the provided `log_sender`.
 ///
-/// Returns the backing volume with WASM and metadata artifacts, [`SessionError`] otherwise.
-async fn handle_session<'a>(
+/// Returns the backing volume of the container, [`SessionError`] otherwise.
+async fn handle_session<R: ContainerRuntime>(
     log_sender: UnboundedSender<LogEntry>,
     build_session_id: i64,
     container: Container,
-    docker: &Docker,
+    runtime: &R,
     builder_config: &config::Builder,
+    shutdown: &Shutdown,
 ) -> Result<Volume, SessionError> {
     let logs = tokio_stream::StreamExt::chunks_timeout(
-        container.logs(docker).await?,
+        container.logs(runtime).await?,
         10,
         Duration::from_secs(3),
     );
 
     pin_mut!(logs);
 
-    let wait_future = wait_and_remove(container, docker, builder_config);
+    let wait_future = wait_and_remove(container, runtime, builder_config, shutdown);
 
     pin_mut!(wait_future);
 
     loop {
+        // Logs are polled first to send the remaining output of exited containers.
         tokio::select! {
+            biased;
+
             Some(chunk) = logs.next() => {
                 let text = strip_ansi_escapes::strip_str(
                     chunk.into_iter()
@@ -604,12 +1083,953 @@ async fn handle_session<'a>(
 }
 
 /// Convert user-supplied `project_directory` path into a normalized [`PathBuf`] value.
-fn normalize_working_dir(project_directory: Option<&str>) -> PathBuf {
+///
+/// Returns [`None`] if the provided path resolves to a directory outside of `/contract`.
+fn normalize_working_dir(project_directory: Option<&str>) -> Option<PathBuf> {
     let mut path = PathBuf::from("/contract");
 
     if let Some(project_directory) = project_directory {
-        path.push(project_directory);
+        path.push(paths::normalize_project_dir(project_directory)?);
+    }
+
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use common::{
+        artifact_signature::{self, SigningKey},
+        build_logs::TRUNCATION_MARKER,
+        config::{self, VolumeBackend, VolumeDriver},
+        hash::{self, HexBytes},
+    };
+    use db::{
+        build_session::{self, ArtifactKind, ProcessedBuildSession},
+        build_session_stage::{self, Stage},
+        build_session_token, code, log,
+        sea_orm::DbBackend,
+        selector, source_code, user, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection,
+        EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+    };
+    use tempfile::TempDir;
+    use tokio::{sync::mpsc, time::timeout};
+
+    use crate::{
+        callback::Completion,
+        log_collector::{collect_logs, LogEntry},
+        shutdown::Shutdown,
+        testing::{create_database, FakeContainer, FakeRuntime, IMAGE_DIGEST},
+    };
+
+    use super::{
+        claim_next, failure_message, finish_session, run_loop, start_session, timed,
+        BuildArtifacts, Instance, SessionError, StageDurations, StageReporter,
+    };
+
+    /// Reference of the build image used by test build sessions.
+    const BUILD_IMAGE: &str = "paritytech/contracts-verifiable:3.0.0";
+
+    /// Artifact signing key seed used in tests.
+    const SIGNING_KEY_SEED: &str =
+        "0x0101010101010101010101010101010101010101010101010101010101010101";
+
+    async fn create_build_session(db: &DatabaseConnection) -> i64 {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::New),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert build session")
+        .id
+    }
+
+    #[tokio::test]
+    async fn concurrent_claims() {
+        let db = create_database().await;
+
+        // Row locks are only supported by PostgreSQL.
+        if db.get_database_backend() != DbBackend::Postgres {
+            return;
+        }
+
+        let first = create_build_session(&db).await;
+        let second = create_build_session(&db).await;
+
+        let first_txn = db.begin().await.unwrap();
+        let second_txn = db.begin().await.unwrap();
+        let third_txn = db.begin().await.unwrap();
+
+        let (first_claim, second_claim) =
+            tokio::join!(claim_next(&first_txn), claim_next(&second_txn));
+
+        let mut claimed = [first_claim, second_claim].map(|claim| claim.unwrap().unwrap().id);
+        claimed.sort();
+        assert_eq!(claimed, [first, second]);
+
+        assert!(claim_next(&third_txn).await.unwrap().is_none());
+
+        // Rolled back build sessions are returned to the queue.
+        first_txn.rollback().await.unwrap();
+        second_txn.rollback().await.unwrap();
+
+        assert_eq!(
+            claim_next(&third_txn)
+                .await
+                .unwrap()
+                .map(|session| session.id),
+            Some(first)
+        );
+    }
+
+    #[tokio::test]
+    async fn truncated_logs_of_claimed_session() {
+        let db = create_database().await;
+
+        // Row locks are only supported by PostgreSQL.
+        if db.get_database_backend() != DbBackend::Postgres {
+            return;
+        }
+
+        let build_session_id = create_build_session(&db).await;
+
+        let txn = db.begin().await.unwrap();
+        let build_session = claim_next(&txn).await.unwrap().unwrap();
+        assert_eq!(build_session.id, build_session_id);
+
+        let db = Arc::new(db);
+        let (log_sender, log_receiver) = mpsc::unbounded_channel();
+        tokio::spawn(collect_logs(db.clone(), log_receiver, 8));
+
+        for text in ["first\n", "second\n"] {
+            log_sender
+                .send(LogEntry {
+                    build_session_id,
+                    text: String::from(text),
+                })
+                .unwrap();
+        }
+
+        // Log size limit is enforced while the build session row is still locked.
+        timeout(Duration::from_secs(5), async {
+            loop {
+                let marker = log::Entity::find()
+                    .filter(log::Column::BuildSessionId.eq(build_session_id))
+                    .filter(log::Column::Text.eq(TRUNCATION_MARKER))
+                    .one(&*db)
+                    .await
+                    .unwrap();
+
+                if marker.is_some() {
+                    break;
+                }
+
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("truncation marker was not stored");
+
+        txn.rollback().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn started_session_hostname() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        let txn = db.begin().await.unwrap();
+        let build_session = claim_next(&txn).await.unwrap().unwrap();
+        assert_eq!(build_session.id, build_session_id);
+
+        start_session(&txn, build_session.id, "builder-1")
+            .await
+            .expect("unable to start build session");
+        txn.commit().await.unwrap();
+
+        let model = build_session::Entity::find_by_id(build_session_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(model.builder_hostname.as_deref(), Some("builder-1"));
+        assert!(model.diagnostics_pending);
     }
 
-    path.normalize()
+    #[tokio::test]
+    async fn completed_stage_durations() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        let mut durations = StageDurations::default();
+
+        let outcome: Result<_, ()> = async {
+            timed(
+                "unarchive",
+                &mut durations.unarchive,
+                tokio::time::sleep(Duration::from_millis(20)),
+            )
+            .await;
+
+            timed("build", &mut durations.build, async { Ok::<_, ()>(()) }).await?;
+
+            timed("move", &mut durations.move_files, async {
+                Ok(BuildArtifacts {
+                    code: vec![1, 2, 3],
+                    kind: ArtifactKind::Wasm,
+                    metadata: b"{}".to_vec(),
+                    contract_name: String::from("flipper"),
+                })
+            })
+            .await
+        }
+        .await;
+
+        let txn = db.begin().await.unwrap();
+        let completion = finish_session(
+            &txn,
+            build_session_id,
+            outcome.ok(),
+            &durations,
+            Some(IMAGE_DIGEST),
+            Some(BUILD_IMAGE),
+            None,
+        )
+        .await
+        .expect("unable to finish build session");
+        txn.commit().await.unwrap();
+
+        let model = build_session::Entity::find_by_id(build_session_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(model.status, build_session::Status::Completed);
+        assert_eq!(model.image_digest.as_deref(), Some(IMAGE_DIGEST));
+        assert_eq!(model.build_image.as_deref(), Some(BUILD_IMAGE));
+        assert_eq!(model.signature, None);
+        assert!(model.unarchive_duration.unwrap() >= 20);
+        assert!(model.build_duration.is_some());
+        assert!(model.move_duration.is_some());
+        assert_eq!(model.metadata.as_deref(), Some(&b"{}"[..]));
+        assert_eq!(model.metadata_hash, Some(hash::blake2(b"{}").to_vec()));
+        assert_eq!(model.contract_name.as_deref(), Some("flipper"));
+        assert_eq!(model.artifact_kind, ArtifactKind::Wasm);
+        assert_eq!(model.code_hash, Some(hash::blake2(&[1, 2, 3]).to_vec()));
+        assert_eq!(completion.status, build_session::Status::Completed);
+        assert_eq!(
+            completion.code_hash.map(|hash| hash.0.to_vec()),
+            model.code_hash
+        );
+
+        let code = code::Entity::find_by_id(model.code_hash.unwrap())
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(code.code, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn completed_polkavm() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+        let signing_key = SigningKey::from_seed(SIGNING_KEY_SEED).unwrap();
+
+        let txn = db.begin().await.unwrap();
+        let completion = finish_session(
+            &txn,
+            build_session_id,
+            Some(BuildArtifacts {
+                code: POLKAVM_FIXTURE.to_vec(),
+                kind: ArtifactKind::Polkavm,
+                metadata: b"{}".to_vec(),
+                contract_name: String::from("flipper"),
+            }),
+            &StageDurations::default(),
+            Some(IMAGE_DIGEST),
+            None,
+            Some(&signing_key),
+        )
+        .await
+        .expect("unable to finish build session");
+        txn.commit().await.unwrap();
+
+        let model = build_session::Entity::find_by_id(build_session_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let code_hash = hash::keccak256(POLKAVM_FIXTURE).to_vec();
+
+        assert_eq!(model.signing_key_id, Some(signing_key.key_id()));
+        assert!(artifact_signature::verify(
+            &signing_key.public_key(),
+            &HexBytes::try_from(&model.signature.unwrap()[..]).unwrap(),
+            &code_hash,
+            &hash::blake2(b"{}"),
+        ));
+
+        assert_eq!(model.artifact_kind, ArtifactKind::Polkavm);
+        assert_eq!(model.code_hash.as_ref(), Some(&code_hash));
+        assert_eq!(
+            completion.code_hash.map(|hash| hash.0.to_vec()),
+            Some(code_hash.clone())
+        );
+
+        let code = code::Entity::find_by_id(code_hash)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(code.code, POLKAVM_FIXTURE);
+    }
+
+    #[tokio::test]
+    async fn completed_selectors() {
+        let db = create_database().await;
+
+        let metadata = br#"{"spec":{"messages":[
+            {"label":"flip","selector":"0x633aa551","mutates":true},
+            {"label":"get","selector":"0x2f865bd9","mutates":false}
+        ]}}"#;
+
+        // Both build sessions produce the same code, thus selectors are stored only once.
+        for _ in 0..2 {
+            let build_session_id = create_build_session(&db).await;
+
+            let txn = db.begin().await.unwrap();
+            finish_session(
+                &txn,
+                build_session_id,
+                Some(BuildArtifacts {
+                    code: vec![1, 2, 3],
+                    kind: ArtifactKind::Wasm,
+                    metadata: metadata.to_vec(),
+                    contract_name: String::from("flipper"),
+                }),
+                &StageDurations::default(),
+                None,
+                None,
+                None,
+            )
+            .await
+            .expect("unable to finish build session");
+            txn.commit().await.unwrap();
+        }
+
+        let selectors = selector::Entity::find()
+            .order_by_asc(selector::Column::Id)
+            .all(&db)
+            .await
+            .unwrap();
+
+        assert_eq!(selectors.len(), 2);
+        assert_eq!(selectors[0].selector, [0x63, 0x3a, 0xa5, 0x51]);
+        assert_eq!(selectors[0].message_name, "flip");
+        assert!(selectors[0].mutates);
+        assert_eq!(selectors[1].selector, [0x2f, 0x86, 0x5b, 0xd9]);
+        assert_eq!(selectors[1].message_name, "get");
+        assert!(!selectors[1].mutates);
+        assert_eq!(selectors[0].code_hash, selectors[1].code_hash);
+    }
+
+    #[tokio::test]
+    async fn failed_stage_durations() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        let mut durations = StageDurations::default();
+
+        let outcome: Result<BuildArtifacts, ()> = async {
+            timed("unarchive", &mut durations.unarchive, async {
+                Ok::<_, ()>(())
+            })
+            .await?;
+
+            timed("build", &mut durations.build, async { Err::<(), _>(()) }).await?;
+
+            timed("move", &mut durations.move_files, async {
+                Ok(BuildArtifacts {
+                    code: Vec::new(),
+                    kind: ArtifactKind::Wasm,
+                    metadata: Vec::new(),
+                    contract_name: String::from("flipper"),
+                })
+            })
+            .await
+        }
+        .await;
+
+        let txn = db.begin().await.unwrap();
+        let completion = finish_session(
+            &txn,
+            build_session_id,
+            outcome.ok(),
+            &durations,
+            Some(IMAGE_DIGEST),
+            Some(BUILD_IMAGE),
+            None,
+        )
+        .await
+        .expect("unable to finish build session");
+        txn.commit().await.unwrap();
+
+        let model = build_session::Entity::find_by_id(build_session_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(model.status, build_session::Status::Failed);
+        assert_eq!(model.image_digest.as_deref(), Some(IMAGE_DIGEST));
+        assert_eq!(model.build_image.as_deref(), Some(BUILD_IMAGE));
+        assert!(model.unarchive_duration.is_some());
+        assert!(model.build_duration.is_some());
+        assert_eq!(model.move_duration, None);
+        assert_eq!(model.code_hash, None);
+        assert_eq!(model.contract_name, None);
+        assert_eq!(
+            completion,
+            Completion {
+                build_session_id,
+                status: build_session::Status::Failed,
+                code_hash: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn finished_session_token_removed() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        let source_code_id = build_session::Entity::find_by_id(build_session_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap()
+            .source_code_id;
+
+        build_session_token::Entity::insert(build_session_token::ActiveModel {
+            token: ActiveValue::Set(build_session_token::generate_token()),
+            source_code_id: ActiveValue::Set(source_code_id),
+            build_session_id: ActiveValue::Set(build_session_id),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert build session token");
+
+        let txn = db.begin().await.unwrap();
+        finish_session(
+            &txn,
+            build_session_id,
+            None,
+            &StageDurations::default(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .expect("unable to finish build session");
+        txn.commit().await.unwrap();
+
+        assert!(build_session_token::Entity::find()
+            .all(&db)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Get the current stage of the provided build session.
+    async fn current_stage(db: &DatabaseConnection, build_session_id: i64) -> Option<Stage> {
+        build_session_stage::Entity::find_by_id(build_session_id)
+            .select_only()
+            .column(build_session_stage::Column::Stage)
+            .into_tuple::<Stage>()
+            .one(db)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn stages_progress_monotonically() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        let (log_sender, mut log_receiver) = mpsc::unbounded_channel();
+
+        let stages = StageReporter {
+            db: &db,
+            log_sender: &log_sender,
+            build_session_id,
+        };
+
+        let mut durations = StageDurations::default();
+        let mut observed = Vec::new();
+
+        assert_eq!(current_stage(&db, build_session_id).await, None);
+
+        let outcome = async {
+            stages
+                .run(Stage::Unarchiving, &mut durations.unarchive, async {
+                    observed.push(current_stage(&db, build_session_id).await);
+                    Ok(())
+                })
+                .await?;
+
+            stages
+                .run(Stage::Building, &mut durations.build, async {
+                    observed.push(current_stage(&db, build_session_id).await);
+                    Ok(())
+                })
+                .await?;
+
+            stages
+                .run(Stage::Extracting, &mut durations.move_files, async {
+                    observed.push(current_stage(&db, build_session_id).await);
+                    Ok(BuildArtifacts {
+                        code: vec![1, 2, 3],
+                        kind: ArtifactKind::Wasm,
+                        metadata: b"{}".to_vec(),
+                        contract_name: String::from("flipper"),
+                    })
+                })
+                .await
+        }
+        .await;
+
+        assert_eq!(
+            observed,
+            [
+                Some(Stage::Unarchiving),
+                Some(Stage::Building),
+                Some(Stage::Extracting)
+            ]
+        );
+        assert!(observed.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let txn = db.begin().await.unwrap();
+        finish_session(
+            &txn,
+            build_session_id,
+            outcome.ok(),
+            &durations,
+            None,
+            None,
+            None,
+        )
+        .await
+        .expect("unable to finish build session");
+        txn.commit().await.unwrap();
+
+        assert_eq!(current_stage(&db, build_session_id).await, None);
+        assert!(durations.move_files.is_some());
+        assert!(log_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_stage_reported() {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        let (log_sender, mut log_receiver) = mpsc::unbounded_channel();
+
+        let stages = StageReporter {
+            db: &db,
+            log_sender: &log_sender,
+            build_session_id,
+        };
+
+        let mut durations = StageDurations::default();
+
+        let outcome = stages
+            .run(Stage::Unarchiving, &mut durations.unarchive, async {
+                Err::<(), _>(SessionError::ContainerExited(1))
+            })
+            .await;
+
+        assert!(matches!(outcome, Err(SessionError::ContainerExited(1))));
+
+        let entry = log_receiver.try_recv().expect("failure was not reported");
+        assert_eq!(entry.build_session_id, build_session_id);
+        assert_eq!(
+            entry.text,
+            concat!(
+                "\nBuild session failed during the unarchiving stage: ",
+                "container exited with status code 1\n"
+            )
+        );
+
+        let outcome = stages
+            .run(Stage::Building, &mut durations.build, async {
+                Err::<(), _>(SessionError::Interrupted)
+            })
+            .await;
+
+        assert!(matches!(outcome, Err(SessionError::Interrupted)));
+        assert!(log_receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn internal_failure_message() {
+        assert_eq!(
+            failure_message(Stage::Extracting, &SessionError::MissingSourceCode),
+            "\nBuild session failed during the extracting stage: internal builder error\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn shutdown_during_iteration() {
+        let shutdown = Shutdown::default();
+        let iterations = AtomicUsize::new(0);
+
+        let (shutdown_ref, iterations_ref) = (&shutdown, &iterations);
+
+        // Shutdown signal received while handling a build session.
+        timeout(
+            Duration::from_secs(1),
+            run_loop(&shutdown, Duration::from_secs(3600), move || async move {
+                iterations_ref.fetch_add(1, Ordering::SeqCst);
+                shutdown_ref.trigger();
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                true
+            }),
+        )
+        .await
+        .expect("worker loop did not exit");
+
+        assert_eq!(iterations.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn shutdown_while_idle() {
+        let shutdown = Shutdown::default();
+        let iterations = AtomicUsize::new(0);
+
+        let iterations_ref = &iterations;
+
+        tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                shutdown.trigger();
+            }
+        });
+
+        // Poll period is long enough to ensure that the loop is woken up by the signal.
+        timeout(
+            Duration::from_secs(1),
+            run_loop(&shutdown, Duration::from_secs(3600), move || async move {
+                iterations_ref.fetch_add(1, Ordering::SeqCst);
+                false
+            }),
+        )
+        .await
+        .expect("worker loop did not exit");
+
+        assert_eq!(iterations.load(Ordering::SeqCst), 1);
+    }
+
+    /// Create a builder configuration, which stores build session volumes
+    /// inside of the provided directory.
+    fn builder_config(images_path: &Path, max_build_duration: u64) -> config::Builder {
+        config::Builder {
+            images_path: PathBuf::from(images_path),
+            api_server_url: String::from("http://localhost:3000"),
+            worker_count: 1,
+            max_build_duration,
+            shutdown_grace_seconds: 60,
+            analysis_timeout: 30,
+            token_max_age: 86400,
+            wasm_size_limit: 1024,
+            polkavm_size_limit: 1024,
+            metadata_size_limit: 1024,
+            max_log_bytes: 1024,
+            memory_limit: 1024,
+            memory_swap_limit: 2048,
+            cpu_quota: None,
+            pids_limit: 768,
+            blkio_weight: None,
+            volume_size: String::from("1G"),
+            volume_backend: VolumeBackend::Bind,
+            docker_endpoint: None,
+            docker_tls: None,
+            volume_driver: VolumeDriver::Docker,
+            image_digests: HashMap::new(),
+            completion_callback_url: None,
+            callback_secret: None,
+            hostname: None,
+            signing_key_seed: None,
+            registry: None,
+        }
+    }
+
+    /// Run all stages of a new build session using the provided container runtime.
+    ///
+    /// Returns the build session outcome, alongside with the resolved image digest,
+    /// build image reference and texts of the sent log entries.
+    async fn run_stages(
+        runtime: &FakeRuntime,
+        max_build_duration: u64,
+        supported_cargo_contract_versions: &[String],
+    ) -> (
+        Result<BuildArtifacts, SessionError>,
+        Option<String>,
+        Option<String>,
+        Vec<String>,
+    ) {
+        let db = create_database().await;
+        let build_session_id = create_build_session(&db).await;
+
+        let source_code_id = build_session::Entity::find_by_id(build_session_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap()
+            .source_code_id;
+
+        build_session_token::Entity::insert(build_session_token::ActiveModel {
+            token: ActiveValue::Set(build_session_token::generate_token()),
+            source_code_id: ActiveValue::Set(source_code_id),
+            build_session_id: ActiveValue::Set(build_session_id),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert build session token");
+
+        let images_path = TempDir::new().unwrap();
+        let builder_config = builder_config(images_path.path(), max_build_duration);
+        let storage_config = config::Storage {
+            access_key_id: String::from("access-key"),
+            secret_access_key: String::from("secret-key"),
+            region: String::from("us-east-1"),
+            endpoint_url: String::from("http://localhost:9000"),
+            source_code_bucket: String::from("source-code"),
+            checksums: true,
+        };
+        let shutdown = Shutdown::default();
+
+        let txn = db.begin().await.unwrap();
+        let build_session = ProcessedBuildSession {
+            id: build_session_id,
+            source_code_id,
+            cargo_contract_version: String::from("3.0.0"),
+            project_directory: None,
+        };
+
+        let (log_sender, mut log_receiver) = mpsc::unbounded_channel::<LogEntry>();
+        let mut image_digest = None;
+        let mut build_image = None;
+
+        let outcome = async {
+            Instance::new(
+                &build_session,
+                &builder_config,
+                runtime,
+                &storage_config,
+                &shutdown,
+                &txn,
+            )
+            .unarchive(log_sender.clone())
+            .await?
+            .build(
+                log_sender,
+                supported_cargo_contract_versions,
+                &mut image_digest,
+                &mut build_image,
+            )
+            .await?
+            .get_files()
+            .await
+        }
+        .await;
+
+        let mut logs = Vec::new();
+        while let Ok(entry) = log_receiver.try_recv() {
+            assert_eq!(entry.build_session_id, build_session_id);
+            logs.push(entry.text);
+        }
+
+        (outcome, image_digest, build_image, logs)
+    }
+
+    /// WASM blob fixture.
+    const WASM_FIXTURE: &[u8] = include_bytes!("../../fixtures/flipper.wasm");
+
+    /// PolkaVM blob fixture.
+    const POLKAVM_FIXTURE: &[u8] = include_bytes!("../../fixtures/flipper.polkavm");
+
+    /// Create a runtime, in which all stage containers finish successfully,
+    /// and build containers are scripted with the provided behavior.
+    fn fake_runtime(build: FakeContainer) -> FakeRuntime {
+        fake_runtime_with_artifact(build, ArtifactKind::Wasm)
+    }
+
+    /// Create a runtime, in which all stage containers finish successfully,
+    /// and the move container lists a contract blob of the provided kind.
+    fn fake_runtime_with_artifact(build: FakeContainer, kind: ArtifactKind) -> FakeRuntime {
+        let (code_path, code) = match kind {
+            ArtifactKind::Wasm => ("/contract/target/ink/flipper.wasm", WASM_FIXTURE),
+            ArtifactKind::Polkavm => ("/contract/target/ink/flipper.polkavm", POLKAVM_FIXTURE),
+        };
+
+        let files = HashMap::from([
+            (
+                String::from("/contract/.artifacts"),
+                format!("{code_path}\n/contract/target/ink/flipper.json\n").into_bytes(),
+            ),
+            (String::from(code_path), code.to_vec()),
+            (
+                String::from("/contract/target/ink/flipper.json"),
+                b"{}".to_vec(),
+            ),
+        ]);
+
+        FakeRuntime::default()
+            .with_container("stage-unarchive", FakeContainer::default())
+            .with_container(BUILD_IMAGE, build)
+            .with_container(
+                "stage-move",
+                FakeContainer {
+                    files,
+                    ..Default::default()
+                },
+            )
+    }
+
+    #[tokio::test]
+    async fn fake_runtime_completed() {
+        let runtime = fake_runtime(FakeContainer::default());
+
+        let (outcome, image_digest, build_image, _) =
+            run_stages(&runtime, 60, &[String::from("3.0.0")]).await;
+
+        let artifacts = outcome.expect("build session failed");
+        assert_eq!(artifacts.contract_name, "flipper");
+        assert_eq!(artifacts.kind, ArtifactKind::Wasm);
+        assert_eq!(artifacts.code, WASM_FIXTURE);
+        assert_eq!(artifacts.metadata, b"{}");
+        assert_eq!(image_digest.as_deref(), Some(IMAGE_DIGEST));
+        assert_eq!(
+            build_image,
+            Some(format!("paritytech/contracts-verifiable@{IMAGE_DIGEST}"))
+        );
+        assert_eq!(runtime.container_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn fake_runtime_polkavm() {
+        let runtime = fake_runtime_with_artifact(FakeContainer::default(), ArtifactKind::Polkavm);
+
+        let (outcome, _, _, _) = run_stages(&runtime, 60, &[String::from("3.0.0")]).await;
+
+        let artifacts = outcome.expect("build session failed");
+        assert_eq!(artifacts.kind, ArtifactKind::Polkavm);
+        assert_eq!(artifacts.code, POLKAVM_FIXTURE);
+        assert_eq!(runtime.container_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn fake_runtime_container_exited() {
+        let runtime = fake_runtime(FakeContainer {
+            exit_code: 101,
+            ..Default::default()
+        });
+
+        let (outcome, _, _, _) = run_stages(&runtime, 60, &[String::from("3.0.0")]).await;
+
+        assert!(matches!(outcome, Err(SessionError::ContainerExited(101))));
+        assert_eq!(runtime.container_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn fake_runtime_unarchive_failed() {
+        let runtime = fake_runtime(FakeContainer::default()).with_container(
+            "stage-unarchive",
+            FakeContainer {
+                exit_code: 9,
+                logs: vec![String::from("unzip: cannot find zipfile directory\n")],
+                ..Default::default()
+            },
+        );
+
+        let (outcome, image_digest, build_image, logs) =
+            run_stages(&runtime, 60, &[String::from("3.0.0")]).await;
+
+        assert!(matches!(outcome, Err(SessionError::ContainerExited(9))));
+        assert_eq!(image_digest, None);
+        assert_eq!(build_image, None);
+        assert_eq!(logs, ["unzip: cannot find zipfile directory\n"]);
+        assert_eq!(runtime.container_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn fake_runtime_timed_out() {
+        let runtime = fake_runtime(FakeContainer {
+            hangs: true,
+            ..Default::default()
+        });
+
+        let (outcome, _, _, _) = run_stages(&runtime, 1, &[String::from("3.0.0")]).await;
+
+        assert!(matches!(outcome, Err(SessionError::TimedOut)));
+        assert_eq!(runtime.container_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn fake_runtime_unsupported_version() {
+        let runtime = fake_runtime(FakeContainer::default());
+
+        let (outcome, image_digest, build_image, logs) =
+            run_stages(&runtime, 60, &[String::from("4.0.0")]).await;
+
+        assert!(matches!(
+            outcome,
+            Err(SessionError::UnsupportedCargoContractVersion)
+        ));
+        assert_eq!(image_digest, None);
+        assert_eq!(build_image, None);
+        assert_eq!(
+            logs,
+            [
+                "Provided cargo-contract version is not supported.\n",
+                "Consider using version 4.0.0"
+            ]
+        );
+        assert_eq!(runtime.container_count(), 0);
+    }
 }
@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tracing::info;
+
+/// Current builder shutdown phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    /// Workers are handling new build sessions.
+    Running,
+
+    /// Workers must finish in-flight build sessions without accepting new ones.
+    Stopping,
+
+    /// In-flight build sessions must be interrupted as soon as possible.
+    Forced,
+}
+
+/// Graceful shutdown signal, shared between build session workers.
+///
+/// Workers are expected to check [`Shutdown::is_triggered`] between
+/// processed build sessions and stop as soon as the signal is triggered.
+/// Running containers are interrupted only after the shutdown is forced.
+#[derive(Clone)]
+pub(crate) struct Shutdown(Arc<watch::Sender<Phase>>);
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(Phase::Running).0))
+    }
+}
+
+impl Shutdown {
+    /// Trigger the shutdown signal.
+    pub(crate) fn trigger(&self) {
+        self.advance(Phase::Stopping);
+    }
+
+    /// Force the shutdown, interrupting in-flight build sessions.
+    pub(crate) fn force(&self) {
+        self.advance(Phase::Forced);
+    }
+
+    /// Check if the shutdown signal was triggered.
+    pub(crate) fn is_triggered(&self) -> bool {
+        *self.0.borrow() >= Phase::Stopping
+    }
+
+    /// Wait until the shutdown signal is triggered.
+    pub(crate) async fn triggered(&self) {
+        self.wait_for(Phase::Stopping).await
+    }
+
+    /// Wait until the shutdown is forced.
+    pub(crate) async fn forced(&self) {
+        self.wait_for(Phase::Forced).await
+    }
+
+    /// Spawn a background task that triggers the shutdown signal
+    /// on SIGTERM or Ctrl-C.
+    pub(crate) fn listen(&self) {
+        let shutdown = self.clone();
+
+        tokio::spawn(async move {
+            wait_for_signal().await;
+            info!("shutdown signal received, finishing in-flight build sessions");
+            shutdown.trigger();
+        });
+    }
+
+    /// Move to the provided phase, unless a later one was already reached.
+    fn advance(&self, phase: Phase) {
+        self.0.send_if_modified(|current| {
+            let modified = *current < phase;
+            *current = (*current).max(phase);
+            modified
+        });
+    }
+
+    /// Wait until the provided phase is reached.
+    async fn wait_for(&self, phase: Phase) {
+        // The sender is owned by the current value, thus the channel can't be closed.
+        let _ = self
+            .0
+            .subscribe()
+            .wait_for(|current| *current >= phase)
+            .await;
+    }
+}
+
+/// Wait for either SIGTERM or Ctrl-C.
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate()).expect("unable to install SIGTERM handler");
+
+    tokio::select! {
+        _ = terminate.recv() => {},
+        _ = tokio::signal::ctrl_c() => {},
+    }
+}
+
+/// Wait for Ctrl-C.
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::timeout;
+
+    use super::Shutdown;
+
+    #[tokio::test]
+    async fn phases() {
+        let shutdown = Shutdown::default();
+
+        assert!(!shutdown.is_triggered());
+        assert!(timeout(Duration::from_millis(20), shutdown.triggered())
+            .await
+            .is_err());
+
+        shutdown.trigger();
+
+        assert!(shutdown.is_triggered());
+        shutdown.triggered().await;
+        assert!(timeout(Duration::from_millis(20), shutdown.forced())
+            .await
+            .is_err());
+
+        shutdown.force();
+        shutdown.forced().await;
+
+        // Triggering the signal again must not cancel the forced shutdown.
+        shutdown.trigger();
+        shutdown.forced().await;
+    }
+
+    #[tokio::test]
+    async fn waiting_subscribers() {
+        let shutdown = Shutdown::default();
+
+        let waiting = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.forced().await }
+        });
+
+        shutdown.trigger();
+        shutdown.force();
+
+        timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("forced shutdown was not observed")
+            .unwrap();
+    }
+}
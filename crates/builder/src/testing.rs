@@ -0,0 +1,213 @@
+use std::{collections::HashMap, path::Path, sync::Mutex};
+
+use async_trait::async_trait;
+use bollard::{
+    auth::DockerCredentials,
+    container::{Config, LogOutput},
+    errors::Error,
+    service::ContainerWaitResponse,
+};
+use bytes::Bytes;
+use futures_util::{
+    future,
+    stream::{self, BoxStream},
+    StreamExt,
+};
+
+use crate::process::runtime::ContainerRuntime;
+
+pub(crate) use migration::testing::create_database;
+
+/// Build image digest reported by the [`FakeRuntime`].
+pub(crate) const IMAGE_DIGEST: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Scripted behavior of containers created from a single image.
+#[derive(Clone, Default)]
+pub(crate) struct FakeContainer {
+    /// Exit code returned after the container finishes running.
+    pub(crate) exit_code: i64,
+
+    /// Whether the container never finishes running.
+    pub(crate) hangs: bool,
+
+    /// Log lines written by the container.
+    pub(crate) logs: Vec<String>,
+
+    /// Files available for download, keyed by their paths.
+    pub(crate) files: HashMap<String, Vec<u8>>,
+}
+
+/// In-memory [`ContainerRuntime`], which runs scripted containers.
+#[derive(Default)]
+pub(crate) struct FakeRuntime {
+    /// Scripted containers, keyed by their image references.
+    scripts: HashMap<String, FakeContainer>,
+
+    /// References of locally available images.
+    images: Mutex<Vec<String>>,
+
+    /// Pulled image references alongside with the used registry credentials.
+    pulls: Mutex<Vec<(String, Option<DockerCredentials>)>>,
+
+    /// Whether image pulls fail.
+    failing_pulls: bool,
+
+    /// Image references of containers that were not removed yet, keyed by container identifiers.
+    containers: Mutex<HashMap<String, String>>,
+}
+
+impl FakeRuntime {
+    /// Script containers created from the provided image reference.
+    pub(crate) fn with_container(mut self, image: &str, container: FakeContainer) -> Self {
+        self.scripts.insert(String::from(image), container);
+        self
+    }
+
+    /// Make all image pulls fail.
+    pub(crate) fn with_failing_pulls(mut self) -> Self {
+        self.failing_pulls = true;
+        self
+    }
+
+    /// Get pulled image references alongside with the used registry credentials.
+    pub(crate) fn pulls(&self) -> Vec<(String, Option<DockerCredentials>)> {
+        self.pulls.lock().unwrap().clone()
+    }
+
+    /// Get the count of containers that were created, but not removed yet.
+    pub(crate) fn container_count(&self) -> usize {
+        self.containers.lock().unwrap().len()
+    }
+
+    /// Get the scripted behavior of the provided container.
+    fn script(&self, id: &str) -> Result<FakeContainer, Error> {
+        let containers = self.containers.lock().unwrap();
+        let image = containers.get(id).ok_or_else(|| not_found(id))?;
+
+        Ok(self.scripts.get(image).cloned().unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for FakeRuntime {
+    async fn list_images(&self, reference: &str) -> Result<Vec<String>, Error> {
+        let images = self.images.lock().unwrap();
+
+        Ok(images
+            .iter()
+            .filter(|image| *image == reference)
+            .cloned()
+            .collect())
+    }
+
+    async fn create_image(
+        &self,
+        image: &str,
+        credentials: Option<DockerCredentials>,
+    ) -> Result<(), Error> {
+        self.pulls
+            .lock()
+            .unwrap()
+            .push((String::from(image), credentials));
+
+        if self.failing_pulls {
+            return Err(not_found(image));
+        }
+
+        self.images.lock().unwrap().push(String::from(image));
+        Ok(())
+    }
+
+    async fn repository_digests(&self, image: &str) -> Result<Vec<String>, Error> {
+        let repository = image.split(':').next().unwrap_or(image);
+
+        Ok(vec![format!("{repository}@{IMAGE_DIGEST}")])
+    }
+
+    async fn create_volume(&self, _: &str, _: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn remove_volume(&self, _: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn create_container(&self, name: &str, config: Config<&str>) -> Result<String, Error> {
+        let image = config.image.unwrap_or_default();
+
+        self.containers
+            .lock()
+            .unwrap()
+            .insert(String::from(name), String::from(image));
+
+        Ok(String::from(name))
+    }
+
+    async fn start_container(&self, id: &str) -> Result<(), Error> {
+        self.script(id).map(|_| ())
+    }
+
+    async fn attach_container(
+        &self,
+        id: &str,
+    ) -> Result<BoxStream<'static, Result<LogOutput, Error>>, Error> {
+        let logs = self.script(id)?.logs.into_iter().map(|line| {
+            Ok(LogOutput::StdOut {
+                message: Bytes::from(line),
+            })
+        });
+
+        Ok(stream::iter(logs).boxed())
+    }
+
+    fn wait_container(&self, id: &str) -> BoxStream<'_, Result<ContainerWaitResponse, Error>> {
+        let response = match self.script(id) {
+            Ok(script) if script.hangs => return stream::pending().boxed(),
+            Ok(script) if script.exit_code == 0 => Ok(ContainerWaitResponse::default()),
+            Ok(script) => Err(Error::DockerContainerWaitError {
+                error: String::new(),
+                code: script.exit_code,
+            }),
+            Err(err) => Err(err),
+        };
+
+        stream::once(future::ready(response)).boxed()
+    }
+
+    async fn remove_container(&self, id: &str) -> Result<(), Error> {
+        self.containers
+            .lock()
+            .unwrap()
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| not_found(id))
+    }
+
+    fn download_from_container(&self, id: &str, path: &str) -> BoxStream<'_, Result<Bytes, Error>> {
+        let archive = self.script(id).and_then(|script| {
+            let contents = script.files.get(path).ok_or_else(|| not_found(path))?;
+
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+
+            let name = Path::new(path).file_name().unwrap_or_default();
+
+            let mut builder = tar::Builder::new(Vec::new());
+            builder.append_data(&mut header, name, contents.as_slice())?;
+
+            Ok(Bytes::from(builder.into_inner()?))
+        });
+
+        stream::once(future::ready(archive)).boxed()
+    }
+}
+
+/// Create an error returned by the [`FakeRuntime`] for unknown containers, images and files.
+fn not_found(name: &str) -> Error {
+    Error::DockerResponseServerError {
+        status_code: 404,
+        message: format!("no such container, image or file: {name}"),
+    }
+}
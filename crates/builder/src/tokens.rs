@@ -0,0 +1,162 @@
+use std::{sync::Arc, time::Duration};
+
+use db::{
+    build_session, build_session_token, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    OffsetDateTime, PrimitiveDateTime, QueryFilter, QuerySelect, QueryTrait,
+};
+use tracing::{error, info};
+
+/// Period between sweeps of expired build session tokens.
+const SWEEP_PERIOD: Duration = Duration::from_secs(3600);
+
+/// Start expired build session tokens sweeping process.
+///
+/// Tokens of finished build sessions are removed by workers, so this process
+/// only handles tokens that were left behind after their build sessions left the queue.
+///
+/// [`Future`] returned from this function should be
+/// spawned as a background process.
+///
+/// [`Future`]: std::future::Future
+pub(crate) async fn sweep(db: Arc<DatabaseConnection>, max_age: Duration) {
+    let mut interval = tokio::time::interval(SWEEP_PERIOD);
+
+    loop {
+        interval.tick().await;
+
+        match remove_expired(&db, max_age).await {
+            Ok(0) => {}
+            Ok(removed) => info!(%removed, "removed expired build session tokens"),
+            Err(e) => error!(%e, "unable to remove expired build session tokens"),
+        }
+    }
+}
+
+/// Remove build session tokens older than the provided age.
+///
+/// Tokens of queued build sessions are kept regardless of their age,
+/// since workers still need them to process such build sessions.
+///
+/// Returns the count of removed tokens.
+pub(crate) async fn remove_expired(
+    db: &DatabaseConnection,
+    max_age: Duration,
+) -> Result<u64, DbErr> {
+    let now = OffsetDateTime::now_utc();
+    let threshold = PrimitiveDateTime::new(now.date(), now.time()) - max_age;
+
+    let dequeued = build_session::Entity::find()
+        .select_only()
+        .column(build_session::Column::Id)
+        .filter(build_session::Column::Status.ne(build_session::Status::New))
+        .into_query();
+
+    let result = build_session_token::Entity::delete_many()
+        .filter(build_session_token::Column::CreatedAt.lt(threshold))
+        .filter(build_session_token::Column::BuildSessionId.in_subquery(dequeued))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use db::{
+        build_session, build_session_token, source_code, user, ActiveValue, DatabaseConnection,
+        EntityTrait, OffsetDateTime, PrimitiveDateTime, QueryOrder,
+    };
+
+    use crate::testing::create_database;
+
+    use super::remove_expired;
+
+    /// Create a build session token with the provided age and build session status.
+    async fn create_token(
+        db: &DatabaseConnection,
+        token: &str,
+        age: Duration,
+        status: build_session::Status,
+    ) {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        let build_session_id = build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(status),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert build session")
+        .id;
+
+        let now = OffsetDateTime::now_utc();
+
+        build_session_token::Entity::insert(build_session_token::ActiveModel {
+            token: ActiveValue::Set(String::from(token)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            build_session_id: ActiveValue::Set(build_session_id),
+            created_at: ActiveValue::Set(PrimitiveDateTime::new(now.date(), now.time()) - age),
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert build session token");
+    }
+
+    #[tokio::test]
+    async fn expired_tokens() {
+        let db = create_database().await;
+
+        create_token(
+            &db,
+            "expired",
+            Duration::from_secs(7200),
+            build_session::Status::Failed,
+        )
+        .await;
+        create_token(&db, "fresh", Duration::ZERO, build_session::Status::Failed).await;
+
+        // Build session is still waiting in the queue, thus its token must survive.
+        create_token(
+            &db,
+            "queued",
+            Duration::from_secs(7200),
+            build_session::Status::New,
+        )
+        .await;
+
+        let removed = remove_expired(&db, Duration::from_secs(3600))
+            .await
+            .expect("unable to remove expired tokens");
+
+        assert_eq!(removed, 1);
+
+        let tokens = build_session_token::Entity::find()
+            .order_by_asc(build_session_token::Column::Token)
+            .all(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|model| model.token)
+            .collect::<Vec<_>>();
+
+        assert_eq!(tokens, ["fresh", "queued"]);
+    }
+}
@@ -0,0 +1,403 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use common::{
+    signature::{sign_timestamped, unix_timestamp, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+    webhook_url::{self, WebhookUrlError},
+};
+use db::{
+    build_session, webhook, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QuerySelect,
+};
+use derive_more::{Display, Error, From};
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect::Policy,
+    Client,
+};
+use serde_json::json;
+use tokio::{sync::mpsc::UnboundedReceiver, time::Instant};
+use tracing::{error, info};
+
+/// Maximum count of delivery attempts for a single webhook request.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first delivery retry.
+///
+/// Each next retry doubles the delay value.
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Timeout for a single webhook request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum count of webhook requests sent concurrently.
+const MAX_CONCURRENT_DELIVERIES: usize = 16;
+
+/// A single item scheduled for delivery.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Pending<T> {
+    /// Scheduled item.
+    pub(crate) item: T,
+
+    /// Count of previously failed delivery attempts.
+    pub(crate) attempt: u32,
+}
+
+/// Delivery queue, that schedules failed items for a retry with an exponential backoff.
+pub(crate) struct RetryQueue<T> {
+    /// Scheduled items, ordered by their due time and insertion order.
+    entries: BTreeMap<(Instant, u64), Pending<T>>,
+
+    /// Counter used to order items with equal due time.
+    counter: u64,
+
+    /// Maximum count of delivery attempts for a single item.
+    max_attempts: u32,
+
+    /// Delay before the first retry.
+    base_delay: Duration,
+}
+
+impl<T> RetryQueue<T> {
+    /// Create new empty [`RetryQueue`].
+    pub(crate) fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            counter: 0,
+            max_attempts,
+            base_delay,
+        }
+    }
+
+    /// Schedule a new item for an immediate delivery.
+    pub(crate) fn push(&mut self, item: T, now: Instant) {
+        self.schedule(Pending { item, attempt: 0 }, now);
+    }
+
+    /// Schedule a failed item for a retry.
+    ///
+    /// Returns the item back if the maximum count of attempts is reached.
+    pub(crate) fn retry(&mut self, mut pending: Pending<T>, now: Instant) -> Result<(), T> {
+        pending.attempt += 1;
+
+        if pending.attempt >= self.max_attempts {
+            return Err(pending.item);
+        }
+
+        let delay = self.base_delay * 2u32.saturating_pow(pending.attempt - 1);
+        self.schedule(pending, now + delay);
+
+        Ok(())
+    }
+
+    /// Take the next item which is due for a delivery at the provided time.
+    pub(crate) fn pop_due(&mut self, now: Instant) -> Option<Pending<T>> {
+        let entry = self.entries.first_entry()?;
+
+        if entry.key().0 <= now {
+            Some(entry.remove())
+        } else {
+            None
+        }
+    }
+
+    /// Get the due time of the next scheduled item.
+    pub(crate) fn next_due(&self) -> Option<Instant> {
+        self.entries.keys().next().map(|(due, _)| *due)
+    }
+
+    /// Check if there are no items scheduled for a delivery.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Insert an item into the queue with the provided due time.
+    fn schedule(&mut self, pending: Pending<T>, due: Instant) {
+        self.entries.insert((due, self.counter), pending);
+        self.counter += 1;
+    }
+}
+
+/// A single webhook request.
+pub(crate) struct Delivery {
+    /// Webhook URL.
+    url: String,
+
+    /// Webhook secret used to sign the request body alongside with the request timestamp.
+    secret: String,
+
+    /// JSON request body.
+    body: Vec<u8>,
+}
+
+/// Errors that may occur during a single webhook delivery attempt.
+#[derive(Debug, Display, Error, From)]
+enum DeliveryError {
+    /// Webhook URL is no longer allowed.
+    Url(WebhookUrlError),
+
+    /// HTTP request error.
+    Request(reqwest::Error),
+}
+
+/// DNS resolver, that rejects host names resolving to non-public addresses.
+///
+/// Webhook URLs are validated before each delivery attempt, however
+/// host names are resolved once again when the connection is established.
+/// Checking addresses during the connection prevents webhook hosts from switching
+/// to a non-public address in between.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            // Connection port is set by the HTTP client after the resolution.
+            let addrs = webhook_url::resolve_public(name.as_str(), 0).await?;
+            Ok::<Addrs, Box<dyn std::error::Error + Send + Sync>>(Box::new(addrs.into_iter()))
+        })
+    }
+}
+
+/// Start webhook delivery process.
+///
+/// Each value received from the provided channel is a build session identifier,
+/// which must be sent only after the final build session status was committed.
+///
+/// Up to [`MAX_CONCURRENT_DELIVERIES`] webhook requests are sent concurrently.
+///
+/// [`Future`] returned from this function should be
+/// spawned as a background process.
+///
+/// [`Future`]: std::future::Future
+pub(crate) async fn deliver(db: Arc<DatabaseConnection>, mut receiver: UnboundedReceiver<i64>) {
+    let client = Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build();
+
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => {
+            error!(%e, "unable to create webhook HTTP client");
+            return;
+        }
+    };
+
+    let mut queue = RetryQueue::new(MAX_ATTEMPTS, BASE_RETRY_DELAY);
+    let mut in_flight = FuturesUnordered::new();
+    let mut receiver_open = true;
+
+    while receiver_open || !queue.is_empty() || !in_flight.is_empty() {
+        while in_flight.len() < MAX_CONCURRENT_DELIVERIES {
+            let Some(pending) = queue.pop_due(Instant::now()) else {
+                break;
+            };
+
+            in_flight.push(send(&client, pending));
+        }
+
+        // Due items are not taken until one of the in-flight requests completes.
+        let next_due = queue
+            .next_due()
+            .filter(|_| in_flight.len() < MAX_CONCURRENT_DELIVERIES);
+        let sleep = tokio::time::sleep_until(next_due.unwrap_or_else(Instant::now));
+
+        tokio::select! {
+            build_session_id = receiver.recv(), if receiver_open => match build_session_id {
+                Some(build_session_id) => match deliveries(&db, build_session_id).await {
+                    Ok(deliveries) => {
+                        let now = Instant::now();
+
+                        for delivery in deliveries {
+                            queue.push(delivery, now);
+                        }
+                    }
+                    Err(e) => error!(%e, build_session_id, "unable to load webhooks"),
+                },
+                None => receiver_open = false,
+            },
+            Some((pending, result)) = in_flight.next(), if !in_flight.is_empty() => {
+                match result {
+                    Ok(()) => info!(url = %pending.item.url, "webhook delivered"),
+                    Err(e) => {
+                        info!(
+                            %e,
+                            url = %pending.item.url,
+                            attempt = pending.attempt,
+                            "delivery failed"
+                        );
+
+                        if let Err(delivery) = queue.retry(pending, Instant::now()) {
+                            error!(url = %delivery.url, "webhook delivery attempts exhausted");
+                        }
+                    }
+                }
+            }
+            _ = sleep, if next_due.is_some() => {}
+        }
+    }
+}
+
+/// Send a single webhook request, returning the delivery back alongside with the result.
+async fn send(
+    client: &Client,
+    pending: Pending<Delivery>,
+) -> (Pending<Delivery>, Result<(), DeliveryError>) {
+    let result: Result<(), DeliveryError> = async {
+        let delivery = &pending.item;
+
+        webhook_url::validate(&delivery.url).await?;
+
+        // Each attempt is signed with the current timestamp, so that retried deliveries
+        // are not rejected by receivers as stale.
+        let timestamp = unix_timestamp();
+
+        client
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(
+                SIGNATURE_HEADER,
+                sign_timestamped(delivery.secret.as_bytes(), timestamp, &delivery.body),
+            )
+            .body(delivery.body.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+    .await;
+
+    (pending, result)
+}
+
+/// Create webhook requests for the provided build session.
+async fn deliveries(
+    db: &DatabaseConnection,
+    build_session_id: i64,
+) -> Result<Vec<Delivery>, DbErr> {
+    let Some((user_id, status, code_hash)) = build_session::Entity::find_by_id(build_session_id)
+        .select_only()
+        .columns([
+            build_session::Column::UserId,
+            build_session::Column::Status,
+            build_session::Column::CodeHash,
+        ])
+        .into_tuple::<(Option<i64>, build_session::Status, Option<Vec<u8>>)>()
+        .one(db)
+        .await?
+    else {
+        return Ok(Vec::new());
+    };
+
+    let Some(user_id) = user_id else {
+        return Ok(Vec::new());
+    };
+
+    let body = json!({
+        "build_session_id": build_session_id,
+        "status": status,
+        "code_hash": code_hash.map(hex::encode),
+    })
+    .to_string()
+    .into_bytes();
+
+    let webhooks = webhook::Entity::find()
+        .select_only()
+        .columns([webhook::Column::Url, webhook::Column::Secret])
+        .filter(webhook::Column::UserId.eq(user_id))
+        .into_tuple::<(String, String)>()
+        .all(db)
+        .await?;
+
+    Ok(webhooks
+        .into_iter()
+        .map(|(url, secret)| Delivery {
+            url,
+            secret,
+            body: body.clone(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use super::{Pending, RetryQueue};
+
+    #[test]
+    fn immediate_delivery() {
+        let now = Instant::now();
+        let mut queue = RetryQueue::new(3, Duration::from_secs(1));
+
+        queue.push("first", now);
+        queue.push("second", now);
+
+        assert_eq!(queue.next_due(), Some(now));
+        assert_eq!(
+            queue.pop_due(now),
+            Some(Pending {
+                item: "first",
+                attempt: 0
+            })
+        );
+        assert_eq!(
+            queue.pop_due(now),
+            Some(Pending {
+                item: "second",
+                attempt: 0
+            })
+        );
+        assert_eq!(queue.pop_due(now), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn exponential_backoff() {
+        let now = Instant::now();
+        let mut queue = RetryQueue::new(4, Duration::from_secs(1));
+
+        queue.push("item", now);
+
+        let pending = queue.pop_due(now).unwrap();
+        queue.retry(pending, now).unwrap();
+        assert_eq!(queue.next_due(), Some(now + Duration::from_secs(1)));
+        assert_eq!(queue.pop_due(now), None);
+
+        let pending = queue.pop_due(now + Duration::from_secs(1)).unwrap();
+        assert_eq!(pending.attempt, 1);
+        queue.retry(pending, now).unwrap();
+        assert_eq!(queue.next_due(), Some(now + Duration::from_secs(2)));
+
+        let pending = queue.pop_due(now + Duration::from_secs(2)).unwrap();
+        assert_eq!(pending.attempt, 2);
+        queue.retry(pending, now).unwrap();
+        assert_eq!(queue.next_due(), Some(now + Duration::from_secs(4)));
+
+        let pending = queue.pop_due(now + Duration::from_secs(4)).unwrap();
+        assert_eq!(pending.attempt, 3);
+        assert_eq!(queue.retry(pending, now), Err("item"));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn due_order() {
+        let now = Instant::now();
+        let mut queue = RetryQueue::new(3, Duration::from_secs(10));
+
+        queue.push("failed", now);
+        let pending = queue.pop_due(now).unwrap();
+        queue.retry(pending, now).unwrap();
+
+        queue.push("new", now + Duration::from_secs(1));
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(queue.pop_due(later).unwrap().item, "new");
+        assert_eq!(queue.pop_due(later).unwrap().item, "failed");
+    }
+}
@@ -0,0 +1,110 @@
+use sp_core::{
+    crypto::SecretStringError,
+    sr25519::{Pair, Public, Signature},
+    Pair as _,
+};
+
+use crate::hash::{self, HexBytes};
+
+/// Length of the signing key identifier, in bytes.
+const KEY_ID_LENGTH: usize = 8;
+
+/// Raw sr25519 signature of build artifacts.
+pub type ArtifactSignature = HexBytes<64>;
+
+/// Raw sr25519 public key used to verify build artifact signatures.
+pub type SigningPublicKey = HexBytes<32>;
+
+/// Builder key used to sign artifacts of completed build sessions.
+pub struct SigningKey(Pair);
+
+impl SigningKey {
+    /// Create a signing key from the provided secret seed.
+    ///
+    /// Seed is accepted in any format supported by Substrate secret URIs,
+    /// such as a hex-encoded seed or a mnemonic phrase.
+    pub fn from_seed(seed: &str) -> Result<Self, SecretStringError> {
+        Pair::from_string(seed, None).map(Self)
+    }
+
+    /// Get the public key of the current signing key.
+    pub fn public_key(&self) -> SigningPublicKey {
+        HexBytes(self.0.public().0)
+    }
+
+    /// Get the identifier of the current signing key.
+    ///
+    /// See [`key_id`] for more details.
+    pub fn key_id(&self) -> String {
+        key_id(&self.public_key())
+    }
+
+    /// Sign artifacts with the provided code hash and JSON metadata hash.
+    pub fn sign(&self, code_hash: &[u8], metadata_hash: &[u8]) -> ArtifactSignature {
+        HexBytes(self.0.sign(&message(code_hash, metadata_hash)).0)
+    }
+}
+
+/// Get the identifier of the provided public key.
+///
+/// Key identifier is a hex-encoded prefix of the Blake2b 256-bit hash of the public key,
+/// which allows to distinguish signatures made before and after key rotation.
+pub fn key_id(public_key: &SigningPublicKey) -> String {
+    hex::encode(&hash::blake2(&public_key.0)[..KEY_ID_LENGTH])
+}
+
+/// Verify the signature of artifacts with the provided code hash and JSON metadata hash.
+pub fn verify(
+    public_key: &SigningPublicKey,
+    signature: &ArtifactSignature,
+    code_hash: &[u8],
+    metadata_hash: &[u8],
+) -> bool {
+    Pair::verify(
+        &Signature::from_raw(signature.0),
+        message(code_hash, metadata_hash),
+        &Public::from_raw(public_key.0),
+    )
+}
+
+/// Create the signed message, which is a concatenation of the code hash
+/// and the JSON metadata hash.
+fn message(code_hash: &[u8], metadata_hash: &[u8]) -> Vec<u8> {
+    [code_hash, metadata_hash].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{key_id, verify, SigningKey};
+
+    /// Signing key seed used in tests.
+    const SEED: &str = "0x0101010101010101010101010101010101010101010101010101010101010101";
+
+    #[test]
+    fn round_trip() {
+        let key = SigningKey::from_seed(SEED).unwrap();
+        let signature = key.sign(&[1; 32], &[2; 32]);
+
+        assert!(verify(&key.public_key(), &signature, &[1; 32], &[2; 32]));
+        assert_eq!(key.key_id(), key_id(&key.public_key()));
+        assert_eq!(key.key_id().len(), 16);
+    }
+
+    #[test]
+    fn tampered_hash() {
+        let key = SigningKey::from_seed(SEED).unwrap();
+        let signature = key.sign(&[1; 32], &[2; 32]);
+
+        assert!(!verify(&key.public_key(), &signature, &[3; 32], &[2; 32]));
+        assert!(!verify(&key.public_key(), &signature, &[1; 32], &[3; 32]));
+
+        let other = SigningKey::from_seed(&SEED.replace("01", "02")).unwrap();
+
+        assert!(!verify(&other.public_key(), &signature, &[1; 32], &[2; 32]));
+    }
+
+    #[test]
+    fn invalid_seed() {
+        assert!(SigningKey::from_seed("0x1234").is_err());
+    }
+}
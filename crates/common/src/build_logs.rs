@@ -0,0 +1,4 @@
+/// Log entry stored after a build session exceeds its log size limit.
+///
+/// Any further log entries of such build session are dropped by the builder.
+pub const TRUNCATION_MARKER: &str = "\n[log size limit exceeded, further output is dropped]\n";
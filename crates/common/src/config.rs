@@ -1,4 +1,4 @@
-use std::{net::SocketAddr, path::PathBuf};
+use std::{collections::HashMap, net::SocketAddr, num::NonZeroUsize, path::PathBuf};
 
 use byte_unit::{n_gib_bytes, n_mib_bytes};
 use figment::{
@@ -22,6 +22,79 @@ pub struct Database {
 pub struct Server {
     /// Address, that HTTP server will listen on.
     pub address: SocketAddr,
+
+    /// Maximum count of items per page, that can be requested by paginated list endpoints.
+    #[serde(default = "default_max_page_size")]
+    pub max_page_size: u64,
+
+    /// Maximum count of the most recent build sessions, that can be listed by the public feed.
+    #[serde(default = "default_recent_build_sessions_window")]
+    pub recent_build_sessions_window: u64,
+
+    /// Maximum total size of source code files, that can be compared by the diff endpoint.
+    #[serde(default = "default_max_diff_size")]
+    pub max_diff_size: usize,
+
+    /// Secret shared with builders, used to validate build session completion callbacks.
+    ///
+    /// If not set, all completion callbacks are rejected.
+    #[serde(default)]
+    pub callback_secret: Option<String>,
+
+    /// Domain string included into signed account ownership proofs.
+    ///
+    /// Binds ownership proofs to this service, so that signatures
+    /// collected by other applications cannot be replayed.
+    #[serde(default = "default_signing_domain")]
+    pub signing_domain: String,
+
+    /// Accept deprecated account ownership proofs, that contain only the account address.
+    #[serde(default)]
+    pub legacy_signatures: bool,
+
+    /// Maximum count of contract call estimation requests per minute for each user.
+    #[serde(default = "default_estimate_rate_limit")]
+    pub estimate_rate_limit: u32,
+
+    /// Time since the last processed block, after which a node is reported as unhealthy,
+    /// in seconds.
+    #[serde(default = "default_node_staleness_threshold")]
+    pub node_staleness_threshold: u64,
+}
+
+/// Default domain string included into signed account ownership proofs.
+pub fn default_signing_domain() -> String {
+    String::from("patron.works")
+}
+
+/// Default maximum count of contract call estimation requests per minute for each user.
+pub fn default_estimate_rate_limit() -> u32 {
+    30
+}
+
+/// Default time since the last processed block, after which a node is reported as unhealthy.
+pub fn default_node_staleness_threshold() -> u64 {
+    300
+}
+
+/// Default maximum count of runtime metadata values kept in memory by the event client.
+pub fn default_metadata_cache_capacity() -> NonZeroUsize {
+    NonZeroUsize::new(5).unwrap()
+}
+
+/// Default maximum count of items per page.
+pub fn default_max_page_size() -> u64 {
+    100
+}
+
+/// Default maximum count of the most recent build sessions available via the public feed.
+pub fn default_recent_build_sessions_window() -> u64 {
+    500
+}
+
+/// Default maximum total size of source code files compared by the diff endpoint.
+pub fn default_max_diff_size() -> usize {
+    n_mib_bytes!(1) as usize
 }
 
 /// Implementation of [`serde`]'s deserializer for [`FromStr`] types.
@@ -71,14 +144,35 @@ pub struct Builder {
     #[serde(default = "default_build_duration")]
     pub max_build_duration: u64,
 
+    /// Duration in seconds, for which in-flight build sessions are awaited after
+    /// the shutdown signal, before their containers are forcefully removed.
+    #[serde(default = "default_shutdown_grace_seconds")]
+    pub shutdown_grace_seconds: u64,
+
+    /// Source code analysis timeout for a single file, in seconds.
+    #[serde(default = "default_analysis_timeout")]
+    pub analysis_timeout: u64,
+
+    /// Age in seconds, after which unused build session tokens are removed.
+    #[serde(default = "default_token_max_age")]
+    pub token_max_age: u64,
+
     /// Max WASM blob size, in bytes.
     #[serde(default = "default_wasm_size_limit")]
     pub wasm_size_limit: usize,
 
+    /// Max PolkaVM blob size, in bytes.
+    #[serde(default = "default_polkavm_size_limit")]
+    pub polkavm_size_limit: usize,
+
     /// Max JSON metadata size, in bytes.
     #[serde(default = "default_metadata_size_limit")]
     pub metadata_size_limit: usize,
 
+    /// Max total size of log entries stored for a single build session, in bytes.
+    #[serde(default = "default_max_log_bytes")]
+    pub max_log_bytes: usize,
+
     /// Memory limit per build.
     #[serde(default = "default_memory_limit")]
     pub memory_limit: i64,
@@ -88,10 +182,152 @@ pub struct Builder {
     #[serde(default = "default_memory_swap_limit")]
     pub memory_swap_limit: i64,
 
+    /// CPU limit per build, in fractional CPUs.
+    ///
+    /// If not set, no CPU limit is applied.
+    #[serde(default)]
+    pub cpu_quota: Option<f64>,
+
+    /// Maximum count of processes per build.
+    #[serde(default = "default_pids_limit")]
+    pub pids_limit: i64,
+
+    /// Block IO weight per build, between 10 and 1000.
+    ///
+    /// If not set, the default container engine weight is used.
+    #[serde(default)]
+    pub blkio_weight: Option<u16>,
+
     /// Volume size available to each build.
     /// Accepts the same format as passed to fallocate command.
     #[serde(default = "default_volume_size")]
     pub volume_size: String,
+
+    /// Storage backend used to create build session volumes.
+    #[serde(default)]
+    pub volume_backend: VolumeBackend,
+
+    /// Docker daemon endpoint.
+    ///
+    /// Accepts either a Unix socket path (optionally prefixed with `unix://`)
+    /// or a `tcp://` URL. If not set, the default local Docker socket is used.
+    #[serde(default)]
+    pub docker_endpoint: Option<String>,
+
+    /// TLS configuration used to connect to `tcp://` Docker endpoints.
+    #[serde(default)]
+    pub docker_tls: Option<DockerTls>,
+
+    /// Container engine-specific volume mounting behavior.
+    #[serde(default)]
+    pub volume_driver: VolumeDriver,
+
+    /// Build image digests, keyed by `cargo-contract` version.
+    ///
+    /// Digests use the `sha256:<hex>` format. Versions without a configured digest
+    /// are built using the mutable image tag instead.
+    #[serde(default)]
+    pub image_digests: HashMap<String, String>,
+
+    /// API server URL, which is notified after each build session completion.
+    ///
+    /// If not set, no completion callbacks are sent.
+    #[serde(default)]
+    pub completion_callback_url: Option<String>,
+
+    /// Secret shared with the API server, used to sign completion callbacks.
+    #[serde(default)]
+    pub callback_secret: Option<String>,
+
+    /// Builder instance hostname recorded alongside each handled build session.
+    ///
+    /// If not set, the system hostname is used instead.
+    #[serde(default)]
+    pub hostname: Option<String>,
+
+    /// Secret seed of the sr25519 key used to sign artifacts of completed build sessions.
+    ///
+    /// If not set, build artifacts are not signed.
+    #[serde(default)]
+    pub signing_key_seed: Option<String>,
+
+    /// Docker registry used to pull build images.
+    ///
+    /// If not set, build images are pulled from Docker Hub anonymously.
+    #[serde(default)]
+    pub registry: Option<Registry>,
+}
+
+/// Docker registry configuration used to pull build images.
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct Registry {
+    /// Registry mirror prefix, prepended to build image repository names.
+    ///
+    /// For example, `registry.example.com/dockerhub` results in build images being pulled
+    /// from `registry.example.com/dockerhub/paritytech/contracts-verifiable`.
+    #[serde(default)]
+    pub mirror: Option<String>,
+
+    /// Registry username.
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Registry password.
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// Registry identity token, used instead of username and password.
+    #[serde(default)]
+    pub identity_token: Option<String>,
+}
+
+/// TLS configuration of a remote Docker daemon.
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct DockerTls {
+    /// Path to the client private key.
+    pub key: PathBuf,
+
+    /// Path to the client certificate.
+    pub cert: PathBuf,
+
+    /// Path to the certificate authority certificate.
+    pub ca: PathBuf,
+}
+
+/// Storage backend of build session volumes.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum VolumeBackend {
+    /// ext4-formatted image file attached as a loop device.
+    ///
+    /// Requires loop devices to be available via `udisksctl`.
+    #[default]
+    Ext4Image,
+
+    /// Plain directory inside of the images path.
+    ///
+    /// Volume size is not enforced with this backend.
+    Bind,
+
+    /// Size-limited tmpfs mounted inside of the images path.
+    ///
+    /// Requires permissions to call `mount` and `umount`.
+    Tmpfs,
+}
+
+/// Volume mounting behavior of the used container engine.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeDriver {
+    /// Mount loop devices as anonymous volumes of the `local` driver.
+    #[default]
+    Docker,
+
+    /// Create a named volume of the `local` driver for each container.
+    ///
+    /// Podman ignores driver options of anonymous volumes,
+    /// thus volumes have to be created before the container itself.
+    Podman,
 }
 
 // Default values used for builder configuration.
@@ -105,14 +341,34 @@ fn default_build_duration() -> u64 {
     3600
 }
 
+fn default_shutdown_grace_seconds() -> u64 {
+    60
+}
+
+fn default_analysis_timeout() -> u64 {
+    30
+}
+
+fn default_token_max_age() -> u64 {
+    86400
+}
+
 fn default_wasm_size_limit() -> usize {
     n_mib_bytes!(5) as usize
 }
 
+fn default_polkavm_size_limit() -> usize {
+    n_mib_bytes!(5) as usize
+}
+
 fn default_metadata_size_limit() -> usize {
     n_mib_bytes!(1) as usize
 }
 
+fn default_max_log_bytes() -> usize {
+    n_mib_bytes!(10) as usize
+}
+
 fn default_memory_limit() -> i64 {
     n_gib_bytes!(4) as i64
 }
@@ -121,10 +377,34 @@ fn default_memory_swap_limit() -> i64 {
     n_gib_bytes!(4) as i64
 }
 
+fn default_pids_limit() -> i64 {
+    768
+}
+
 fn default_volume_size() -> String {
     String::from("8G")
 }
 
+/// Event client configuration.
+#[derive(Deserialize)]
+pub struct EventClient {
+    /// Address, that Prometheus metrics server will listen on.
+    ///
+    /// If not set, metrics are not served.
+    #[serde(default)]
+    pub metrics_address: Option<SocketAddr>,
+
+    /// Maximum count of runtime metadata values kept in memory.
+    #[serde(default = "default_metadata_cache_capacity")]
+    pub metadata_cache_capacity: NonZeroUsize,
+
+    /// Directory, in which runtime metadata fetched from nodes is persisted.
+    ///
+    /// If not set, metadata is only cached in memory.
+    #[serde(default)]
+    pub metadata_cache_dir: Option<PathBuf>,
+}
+
 /// AWS S3-compatible storage configuration.
 #[derive(Deserialize)]
 pub struct Storage {
@@ -142,6 +422,21 @@ pub struct Storage {
 
     /// S3 bucket name for source code archive storage.
     pub source_code_bucket: String,
+
+    /// Send integrity checksums alongside with uploaded objects and verify storage responses.
+    ///
+    /// Can be disabled for S3-compatible stores that reject checksum headers.
+    ///
+    /// ETags are not compared with MD5 digests of objects encrypted with SSE-KMS or SSE-C,
+    /// since such ETags are not MD5 digests. These uploads are verified by the storage
+    /// with the `Content-MD5` header instead.
+    #[serde(default = "default_storage_checksums")]
+    pub checksums: bool,
+}
+
+/// Default integrity checksums usage for uploaded objects.
+pub fn default_storage_checksums() -> bool {
+    true
 }
 
 /// General configuration.
@@ -163,6 +458,10 @@ pub struct Config {
     #[serde(default)]
     pub builder: Option<Builder>,
 
+    /// Event client configuration.
+    #[serde(default)]
+    pub event_client: Option<EventClient>,
+
     /// Storage configuration.
     pub storage: Storage,
 
@@ -207,15 +506,25 @@ impl Config {
             },
             server: Some(Server {
                 address: "127.0.0.1:3000".parse().unwrap(),
+                max_page_size: default_max_page_size(),
+                recent_build_sessions_window: default_recent_build_sessions_window(),
+                max_diff_size: default_max_diff_size(),
+                callback_secret: None,
+                signing_domain: default_signing_domain(),
+                legacy_signatures: false,
+                estimate_rate_limit: default_estimate_rate_limit(),
+                node_staleness_threshold: default_node_staleness_threshold(),
             }),
             logging: Logging::default(),
             builder: None,
+            event_client: None,
             storage: Storage {
                 access_key_id: String::new(),
                 secret_access_key: String::new(),
                 region: String::new(),
                 endpoint_url: String::new(),
                 source_code_bucket: String::new(),
+                checksums: default_storage_checksums(),
             },
             supported_cargo_contract_versions: default_supported_cargo_contract_versions(),
             payments: false,
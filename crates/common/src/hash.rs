@@ -1,4 +1,8 @@
+use std::{array::TryFromSliceError, fmt, str::FromStr};
+
 use blake2::{digest::typenum::U32, Blake2b, Digest};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sha3::Keccak256;
 
 /// Creates a Blake2b 256-bit hash from the provided input.
 ///
@@ -9,3 +13,186 @@ pub fn blake2(data: &[u8]) -> [u8; 32] {
     hasher.update(data);
     hasher.finalize().into()
 }
+
+/// Creates a Keccak 256-bit hash from the provided input.
+///
+/// This function is useful to determine the PolkaVM blob code hash,
+/// since its algorithm is identical to the one used by `pallet-revive`.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Hexadecimal representation of an `N`-byte array.
+///
+/// Values are displayed and serialized without the `0x` prefix,
+/// while parsing accepts values both with and without it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HexBytes<const N: usize>(pub [u8; N]);
+
+/// Hexadecimal representation of a 32-byte hash, such as a code hash or an archive hash.
+pub type Hash32 = HexBytes<32>;
+
+impl Hash32 {
+    /// Create a Blake2b 256-bit hash from the provided input.
+    ///
+    /// See [`blake2`] for more details.
+    pub fn blake2(data: &[u8]) -> Self {
+        Self(blake2(data))
+    }
+
+    /// Create a Keccak 256-bit hash from the provided input.
+    ///
+    /// See [`keccak256`] for more details.
+    pub fn keccak256(data: &[u8]) -> Self {
+        Self(keccak256(data))
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for HexBytes<N> {
+    fn from(value: [u8; N]) -> Self {
+        Self(value)
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for HexBytes<N> {
+    type Error = TryFromSliceError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        value.try_into().map(Self)
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for HexBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> FromStr for HexBytes<N> {
+    type Err = hex::FromHexError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0; N];
+        hex::decode_to_slice(value.strip_prefix("0x").unwrap_or(value), &mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl<const N: usize> fmt::Display for HexBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl<const N: usize> Serialize for HexBytes<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for HexBytes<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// Visitor that parses hex-encoded strings.
+        struct HexVisitor<const N: usize>;
+
+        impl<'de, const N: usize> de::Visitor<'de> for HexVisitor<N> {
+            type Value = HexBytes<N>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a hex-encoded {N}-byte array")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(HexVisitor)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl<const N: usize> schemars::JsonSchema for HexBytes<N> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        format!("HexBytes{N}")
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, SchemaObject, StringValidation};
+
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(format!("^(0x)?[0-9a-fA-F]{{{}}}$", N * 2)),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hash32, HexBytes};
+
+    #[test]
+    fn keccak256() {
+        assert_eq!(
+            Hash32::keccak256(b"").to_string(),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn parsing() {
+        let value = "ab".repeat(32);
+
+        assert_eq!(value.parse::<Hash32>().unwrap(), HexBytes([0xab; 32]));
+        assert_eq!(
+            format!("0x{}", value.to_uppercase())
+                .parse::<Hash32>()
+                .unwrap(),
+            HexBytes([0xab; 32])
+        );
+
+        assert!("0x1234".parse::<Hash32>().is_err());
+        assert!("xyz".parse::<Hash32>().is_err());
+        assert_eq!("0102".parse::<HexBytes<2>>().unwrap(), HexBytes([1, 2]));
+    }
+
+    #[test]
+    fn display_and_serde() {
+        let hash = Hash32::from([200; 32]);
+
+        assert_eq!(hash.to_string(), "c8".repeat(32));
+        assert_eq!(
+            serde_json::to_string(&hash).unwrap(),
+            format!("\"{}\"", "c8".repeat(32))
+        );
+        assert_eq!(
+            serde_json::from_str::<Hash32>(&format!("\"0x{}\"", "c8".repeat(32))).unwrap(),
+            hash
+        );
+        assert!(serde_json::from_str::<Hash32>("\"c8\"").is_err());
+    }
+
+    #[test]
+    fn slice_conversion() {
+        assert!(Hash32::try_from(&[0; 32][..]).is_ok());
+        assert!(Hash32::try_from(&[0; 31][..]).is_err());
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn schema_generation() {
+        let schema = serde_json::to_value(schemars::schema_for!(HexBytes<64>)).unwrap();
+
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["pattern"], "^(0x)?[0-9a-fA-F]{128}$");
+    }
+}
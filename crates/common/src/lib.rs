@@ -5,6 +5,13 @@
 //!
 //! [`Config`]: config::Config
 
+/// Build artifact signatures.
+#[cfg(feature = "artifact-signing")]
+pub mod artifact_signature;
+
+/// Build session log utilities.
+pub mod build_logs;
+
 /// Shared workspace configuration.
 pub mod config;
 
@@ -15,9 +22,19 @@ pub mod hash;
 #[cfg(feature = "logging")]
 pub mod logging;
 
+/// Project path utilities.
+pub mod paths;
+
 /// AWS S3-compatible storage wrapper.
 #[cfg(feature = "s3")]
 pub mod s3;
 
+/// HMAC-based request body signatures.
+pub mod signature;
+
+/// Webhook URL validation.
+#[cfg(feature = "webhooks")]
+pub mod webhook_url;
+
 #[cfg(feature = "rpc")]
 pub mod rpc;
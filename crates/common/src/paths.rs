@@ -0,0 +1,81 @@
+use std::path::{Component, Path};
+
+/// Normalize the user-supplied project directory path relative to the project root.
+///
+/// `.` components, duplicate and trailing slashes are removed, while `..` components
+/// are resolved against the preceding ones. An empty string is returned for the project
+/// root itself.
+///
+/// Returns [`None`] if the provided path is absolute, or if it resolves
+/// to a directory outside of the project root.
+pub fn normalize_project_dir(project_directory: &str) -> Option<String> {
+    let mut components = Vec::new();
+
+    for component in Path::new(project_directory).components() {
+        match component {
+            Component::Normal(name) => components.push(name.to_str()?),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                components.pop()?;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    Some(components.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_project_dir;
+
+    #[test]
+    fn escape_attempts() {
+        for project_directory in [
+            "..",
+            "../",
+            "../../",
+            "contracts/../..",
+            "./contracts/../../etc",
+            "/contract",
+            "/",
+        ] {
+            assert_eq!(
+                normalize_project_dir(project_directory),
+                None,
+                "{project_directory} was accepted"
+            );
+        }
+    }
+
+    #[test]
+    fn relative_paths() {
+        assert_eq!(normalize_project_dir("").as_deref(), Some(""));
+        assert_eq!(normalize_project_dir(".").as_deref(), Some(""));
+        assert_eq!(normalize_project_dir("contracts/..").as_deref(), Some(""));
+        assert_eq!(
+            normalize_project_dir("./contracts/test/../another_contract").as_deref(),
+            Some("contracts/another_contract")
+        );
+    }
+
+    #[test]
+    fn trailing_slashes() {
+        assert_eq!(
+            normalize_project_dir("contracts/flipper/").as_deref(),
+            Some("contracts/flipper")
+        );
+        assert_eq!(
+            normalize_project_dir("contracts//flipper//").as_deref(),
+            Some("contracts/flipper")
+        );
+    }
+
+    #[test]
+    fn unicode_directories() {
+        assert_eq!(
+            normalize_project_dir("контракты/🦀/../flipper").as_deref(),
+            Some("контракты/flipper")
+        );
+    }
+}
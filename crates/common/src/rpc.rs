@@ -12,7 +12,14 @@
 //! When metadata version change is detected, we fetch new metadata information from a node
 //! while caching it in the process.
 
-use std::{convert::identity, num::NonZeroUsize};
+use std::{
+    convert::identity,
+    fs,
+    future::Future,
+    io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
 
 use frame_metadata::{RuntimeMetadataPrefixed, StorageEntryType};
 use futures_util::{
@@ -23,7 +30,7 @@ use lru::LruCache;
 use pallet_contracts_primitives::ContractExecResult;
 use parity_scale_codec::{Decode, Encode};
 use scale_decode::DecodeAsType;
-use sp_core::crypto::AccountId32;
+use sp_core::crypto::{AccountId32, Ss58AddressFormat, Ss58Codec};
 use sp_version::RuntimeVersion;
 use substrate_api_client::{
     ac_compose_macros::rpc_params,
@@ -35,6 +42,7 @@ use substrate_api_client::{
     storage_key, Api, Error, GetChainInfo, GetStorage,
 };
 
+pub use pallet_contracts_primitives;
 pub use parity_scale_codec;
 pub use sp_core;
 pub use substrate_api_client;
@@ -42,6 +50,31 @@ pub use substrate_api_client;
 /// Default page size for fetching data by storage key prefix.
 pub const PAGE_SIZE: u32 = 10;
 
+/// Maximum page size accepted by Substrate nodes for paged storage key requests.
+pub const MAX_PAGE_SIZE: u32 = 1000;
+
+/// Options of paged storage iteration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Paging {
+    /// Count of storage keys requested within a single page.
+    ///
+    /// Values are clamped to the `1..=MAX_PAGE_SIZE` range. If a node rejects a page request,
+    /// the request is retried with a halved page size, which is used for all following pages.
+    pub page_size: u32,
+
+    /// Count of storage values requested concurrently within a single page.
+    pub concurrency: usize,
+}
+
+impl Default for Paging {
+    fn default() -> Self {
+        Self {
+            page_size: PAGE_SIZE,
+            concurrency: 1,
+        }
+    }
+}
+
 /// WASM blob information received from an RPC node.
 #[derive(DecodeAsType)]
 struct PrefabWasmModule {
@@ -56,6 +89,16 @@ pub struct ContractInfo {
     pub code_hash: H256,
 }
 
+/// Encode the provided account as an SS58 address using the network-specific `prefix`.
+///
+/// If `prefix` is [`None`], the generic Substrate prefix is used instead.
+pub fn ss58_address(account: &AccountId32, prefix: Option<u16>) -> String {
+    match prefix {
+        Some(prefix) => account.to_ss58check_with_version(Ss58AddressFormat::custom(prefix)),
+        None => account.to_ss58check(),
+    }
+}
+
 /// Get a [`Block`] information for the provided block hash.
 ///
 /// If the provided hash is [`None`], the latest block is retrieved.
@@ -72,9 +115,13 @@ pub async fn block<C: Request>(
 ///
 /// This method returns an asynchronous [`Stream`] of [`StorageKey`] (which can be decoded to receive the code hash value)
 /// and WASM blob bytes.
+///
+/// If `start_key` is provided, only storage keys that follow it are returned.
 pub async fn pristine_code_root<'a, C: Request>(
     api: &'a Api<PolkadotConfig, C>,
     at: H256,
+    start_key: Option<StorageKey>,
+    paging: Paging,
     metadata: &'a Metadata,
 ) -> Result<impl Stream<Item = Result<Vec<(StorageKey, Vec<u8>)>, Error>> + 'a, Error> {
     paged_key_values::<_, PrefabWasmModule, _, _>(
@@ -82,6 +129,8 @@ pub async fn pristine_code_root<'a, C: Request>(
         "Contracts",
         "PristineCode",
         at,
+        start_key,
+        paging,
         |module| module.code,
         metadata,
     )
@@ -113,12 +162,26 @@ pub async fn pristine_code<C: Request>(
 ///
 /// This method returns an asynchronous [`Stream`] of [`StorageKey`] (which can be decoded to receive the contract address value)
 /// and associated contract information.
+///
+/// If `start_key` is provided, only storage keys that follow it are returned.
 pub async fn contract_info_of_root<'a, C: Request + Send + Sync>(
     api: &'a Api<PolkadotConfig, C>,
     at: H256,
+    start_key: Option<StorageKey>,
+    paging: Paging,
     metadata: &'a Metadata,
 ) -> Result<impl Stream<Item = Result<Vec<(StorageKey, ContractInfo)>, Error>> + 'a, Error> {
-    paged_key_values(api, "Contracts", "ContractInfoOf", at, identity, metadata).await
+    paged_key_values(
+        api,
+        "Contracts",
+        "ContractInfoOf",
+        at,
+        start_key,
+        paging,
+        identity,
+        metadata,
+    )
+    .await
 }
 
 /// Get information about the specific contract at the provided block hash.
@@ -191,10 +254,38 @@ pub async fn call_contract<C: Request + Subscribe>(
     Ok(result)
 }
 
+/// Default count of metadata values kept in memory by [`MetadataCache`].
+pub const DEFAULT_METADATA_CACHE_CAPACITY: usize = 5;
+
+/// Runtime version identifier, that consists of authoring, spec and implementation versions.
+type RuntimeVersionKey = (u32, u32, u32);
+
+/// [`MetadataCache`] configuration.
+#[derive(Clone, Debug)]
+pub struct MetadataCacheOptions {
+    /// Maximum count of metadata values kept in memory.
+    pub capacity: NonZeroUsize,
+
+    /// Directory, in which fetched metadata is persisted.
+    ///
+    /// If not set, metadata is only cached in memory.
+    pub directory: Option<PathBuf>,
+}
+
+impl Default for MetadataCacheOptions {
+    fn default() -> Self {
+        Self {
+            capacity: NonZeroUsize::new(DEFAULT_METADATA_CACHE_CAPACITY).unwrap(),
+            directory: None,
+        }
+    }
+}
+
 /// Node metadata cache.
 #[derive(Debug)]
 pub struct MetadataCache {
-    cache: LruCache<(u32, u32, u32), Metadata>,
+    cache: LruCache<RuntimeVersionKey, Metadata>,
+    directory: Option<PathBuf>,
 }
 
 impl MetadataCache {
@@ -203,10 +294,27 @@ impl MetadataCache {
         Default::default()
     }
 
+    /// Create new [`MetadataCache`] with the provided options.
+    pub fn with_options(
+        MetadataCacheOptions {
+            capacity,
+            directory,
+        }: MetadataCacheOptions,
+    ) -> Self {
+        Self {
+            cache: LruCache::new(capacity),
+            directory,
+        }
+    }
+
     /// Get metadata associated with the provided block hash.
     ///
     /// This method requests node runtime version corresponding to the provided block,
     /// and either fetches it from node or retrieves from cache.
+    ///
+    /// If the cache directory is configured, metadata is looked up on disk before
+    /// fetching it from the node. Files are stored separately for each chain,
+    /// since runtime versions of different chains may overlap.
     pub async fn metadata<'a, C: Request>(
         &'a mut self,
         api: &Api<PolkadotConfig, C>,
@@ -222,40 +330,112 @@ impl MetadataCache {
             .request("state_getRuntimeVersion", rpc_params![at])
             .await?;
 
-        if !self
-            .cache
-            .contains(&(authoring_version, spec_version, impl_version))
-        {
-            let metadata_bytes: Bytes = api
-                .client()
-                .request("state_getMetadata", rpc_params![Some(at)])
-                .await?;
+        let directory = self
+            .directory
+            .as_ref()
+            .map(|directory| directory.join(hex::encode(api.genesis_hash())));
+
+        cached(
+            &mut self.cache,
+            directory.as_deref(),
+            (authoring_version, spec_version, impl_version),
+            || async {
+                let metadata_bytes: Bytes = api
+                    .client()
+                    .request("state_getMetadata", rpc_params![Some(at)])
+                    .await?;
+
+                Ok(metadata_bytes.0)
+            },
+            decode_metadata,
+        )
+        .await
+    }
+}
 
-            let runtime_metadata =
-                RuntimeMetadataPrefixed::decode(&mut metadata_bytes.0.as_slice())?;
-            let metadata: Metadata = runtime_metadata.try_into()?;
+impl Default for MetadataCache {
+    fn default() -> Self {
+        Self::with_options(MetadataCacheOptions::default())
+    }
+}
 
-            self.cache.push(
-                (authoring_version, spec_version, impl_version),
-                metadata.clone(),
-            );
-        }
+/// Decode SCALE-encoded runtime metadata.
+fn decode_metadata(mut bytes: &[u8]) -> Result<Metadata, Error> {
+    let runtime_metadata = RuntimeMetadataPrefixed::decode(&mut bytes)?;
 
-        let metadata = self
-            .cache
-            .get(&(authoring_version, spec_version, impl_version))
-            .unwrap();
+    Ok(runtime_metadata.try_into()?)
+}
 
-        Ok(metadata)
+/// Get a value associated with the provided runtime version from the cache.
+///
+/// If the value is missing in memory, it is loaded from the provided directory.
+/// If the directory doesn't contain a valid value either, the value is fetched
+/// using the provided `fetch` function and persisted to the directory.
+///
+/// Cache files that cannot be read or decoded are ignored and overwritten.
+async fn cached<'a, T, F, Fut, D>(
+    cache: &'a mut LruCache<RuntimeVersionKey, T>,
+    directory: Option<&Path>,
+    version: RuntimeVersionKey,
+    fetch: F,
+    decode: D,
+) -> Result<&'a T, Error>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, Error>>,
+    D: Fn(&[u8]) -> Result<T, Error>,
+{
+    if !cache.contains(&version) {
+        let path = directory.map(|directory| cache_file_path(directory, version));
+
+        let stored = path
+            .as_deref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| decode(&bytes).ok());
+
+        let value = match stored {
+            Some(value) => value,
+            None => {
+                let bytes = fetch().await?;
+                let value = decode(&bytes)?;
+
+                if let Some(path) = &path {
+                    persist(path, &bytes).map_err(|err| Error::Other(Box::new(err)))?;
+                }
+
+                value
+            }
+        };
+
+        cache.push(version, value);
     }
+
+    Ok(cache.get(&version).unwrap())
 }
 
-impl Default for MetadataCache {
-    fn default() -> Self {
-        Self {
-            cache: LruCache::new(NonZeroUsize::new(5).unwrap()),
-        }
+/// Get a path of the cache file corresponding to the provided runtime version.
+fn cache_file_path(
+    directory: &Path,
+    (authoring_version, spec_version, impl_version): RuntimeVersionKey,
+) -> PathBuf {
+    directory.join(format!(
+        "{authoring_version}-{spec_version}-{impl_version}.scale"
+    ))
+}
+
+/// Write cache file contents.
+///
+/// Contents are written to a temporary file first, so that interrupted writes
+/// do not leave partially written cache files behind.
+fn persist(path: &Path, bytes: &[u8]) -> Result<(), io::Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
     }
+
+    let temp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+
+    fs::write(&temp_path, bytes)?;
+    fs::rename(temp_path, path)
 }
 
 /// Fetch events associated with the provided block hash.
@@ -351,22 +531,77 @@ async fn get_ty_storage_by_key<C: Request, K: Encode, V: DecodeAsType>(
 }
 
 // Get storage keys and values with the provided prefix, mapping values in process.
+#[allow(clippy::too_many_arguments)]
 async fn paged_key_values<'a, C: Request, V: DecodeAsType, T, F: FnMut(V) -> T + 'static>(
     api: &'a Api<PolkadotConfig, C>,
     pallet: &'static str,
     storage_item: &'static str,
     at: H256,
-    map: F,
+    start_key: Option<StorageKey>,
+    paging: Paging,
+    mut map: F,
     metadata: &'a Metadata,
 ) -> Result<impl Stream<Item = Result<Vec<(StorageKey, T)>, Error>> + 'a, Error> {
     let prefix = api.get_storage_map_key_prefix(pallet, storage_item).await?;
 
-    Ok(try_unfold(
-        (None, prefix, map, metadata),
-        move |(start_key, prefix, mut map, metadata)| async move {
-            let storage_keys = api
-                .get_storage_keys_paged(Some(prefix.clone()), PAGE_SIZE, start_key, Some(at))
-                .await?;
+    let pages = paged_storage(
+        move |count, start_key| {
+            let prefix = prefix.clone();
+
+            async move {
+                api.get_storage_keys_paged(Some(prefix), count, start_key, Some(at))
+                    .await
+            }
+        },
+        move |storage_key| async move { api.get_opaque_storage_by_key(storage_key, Some(at)).await },
+        start_key,
+        paging,
+    );
+
+    Ok(pages.map(move |page| {
+        page?
+            .into_iter()
+            .map(|(storage_key, input)| {
+                let value = resolve_ty(metadata, pallet, storage_item, &mut &*input)?;
+
+                Ok((storage_key, map(value)))
+            })
+            .collect::<Result<_, Error>>()
+    }))
+}
+
+// Iterate over pages of storage keys and their opaque values, starting after the provided key.
+//
+// Storage keys of each page are requested with `keys`, which receives the requested page size
+// and the last storage key of the previous page. Values of a single page are requested with
+// `value` concurrently, but are returned in the storage key order, which allows to use
+// the last storage key of a page to resume the iteration.
+fn paged_storage<'a, K, KF, V, VF>(
+    keys: K,
+    value: V,
+    start_key: Option<StorageKey>,
+    paging: Paging,
+) -> impl Stream<Item = Result<Vec<(StorageKey, Vec<u8>)>, Error>> + 'a
+where
+    K: Fn(u32, Option<StorageKey>) -> KF + 'a,
+    KF: Future<Output = Result<Vec<StorageKey>, Error>> + 'a,
+    V: Fn(StorageKey) -> VF + 'a,
+    VF: Future<Output = Result<Option<Vec<u8>>, Error>> + 'a,
+{
+    let page_size = paging.page_size.clamp(1, MAX_PAGE_SIZE);
+    let concurrency = paging.concurrency.max(1);
+
+    try_unfold(
+        (start_key, page_size, keys, value),
+        move |(start_key, mut page_size, keys, value)| async move {
+            let storage_keys = loop {
+                match keys(page_size, start_key.clone()).await {
+                    Ok(storage_keys) => break storage_keys,
+                    // Nodes may impose lower page size limits, retry with a smaller page.
+                    Err(_) if page_size > 1 => page_size /= 2,
+                    Err(err) => return Err(err),
+                }
+            };
 
             if storage_keys.is_empty() {
                 return Ok(None);
@@ -374,24 +609,27 @@ async fn paged_key_values<'a, C: Request, V: DecodeAsType, T, F: FnMut(V) -> T +
 
             let start_key = storage_keys.last().cloned();
 
-            let values = stream::iter(storage_keys)
-                .then(move |storage_key| async move {
-                    let value = api
-                        .get_opaque_storage_by_key(storage_key.clone(), Some(at))
-                        .await?
-                        .map(|input| resolve_ty(metadata, pallet, storage_item, &mut &*input))
-                        .transpose()?
-                        .expect("unable to find value corresponding to the provided storage key");
+            let mut values: Vec<_> = stream::iter(storage_keys)
+                .map(|storage_key| {
+                    let value = value(storage_key.clone());
+
+                    async move {
+                        let value = value.await?.expect(
+                            "unable to find value corresponding to the provided storage key",
+                        );
 
-                    Result::<_, Error>::Ok((storage_key, value))
+                        Result::<_, Error>::Ok((storage_key, value))
+                    }
                 })
-                .map_ok(|(key, val)| (key, map(val)))
+                .buffer_unordered(concurrency)
                 .try_collect()
                 .await?;
 
-            Result::<_, Error>::Ok(Some((values, (start_key, prefix, map, metadata))))
+            values.sort_unstable_by(|(left, _), (right, _)| left.0.cmp(&right.0));
+
+            Ok(Some((values, (start_key, page_size, keys, value))))
         },
-    ))
+    )
 }
 
 fn resolve_ty<T: DecodeAsType>(
@@ -410,3 +648,341 @@ fn resolve_ty<T: DecodeAsType>(
 
     Ok(ty)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        collections::BTreeMap,
+        fs, io,
+        num::NonZeroUsize,
+        ops::Bound,
+        path::{Path, PathBuf},
+        sync::Mutex,
+    };
+
+    use futures_util::TryStreamExt;
+    use lru::LruCache;
+    use parity_scale_codec::{Decode, Encode};
+    use sp_core::crypto::{AccountId32, Ss58Codec};
+    use substrate_api_client::{ac_primitives::StorageKey, Error};
+
+    use super::{
+        cache_file_path, cached, paged_storage, ss58_address, Paging, RuntimeVersionKey,
+        MAX_PAGE_SIZE, PAGE_SIZE,
+    };
+
+    /// In-memory storage backend, that rejects pages larger than the provided limit.
+    struct FakeStorage {
+        /// Stored values by their storage keys.
+        values: BTreeMap<Vec<u8>, Vec<u8>>,
+
+        /// Maximum page size accepted by the backend.
+        max_page_size: u32,
+
+        /// Page sizes of all page requests, including rejected ones.
+        requests: Mutex<Vec<u32>>,
+    }
+
+    impl FakeStorage {
+        /// Create a backend with the provided count of single-byte storage keys.
+        fn new(count: u8, max_page_size: u32) -> Self {
+            Self {
+                values: (0..count).map(|key| (vec![key], vec![key, key])).collect(),
+                max_page_size,
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+
+        /// Get a page of storage keys, that follow the provided start key.
+        fn keys(
+            &self,
+            count: u32,
+            start_key: Option<StorageKey>,
+        ) -> Result<Vec<StorageKey>, Error> {
+            self.requests.lock().unwrap().push(count);
+
+            if count > self.max_page_size {
+                return Err(Error::Other(Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "page size limit exceeded",
+                ))));
+            }
+
+            let start = match start_key {
+                Some(start_key) => Bound::Excluded(start_key.0),
+                None => Bound::Unbounded,
+            };
+
+            Ok(self
+                .values
+                .range((start, Bound::Unbounded))
+                .map(|(key, _)| key)
+                .take(count as usize)
+                .map(|key| StorageKey(key.clone()))
+                .collect())
+        }
+
+        /// Collect all pages of storage keys and values.
+        async fn pages(
+            &self,
+            start_key: Option<StorageKey>,
+            paging: Paging,
+        ) -> Vec<Vec<(StorageKey, Vec<u8>)>> {
+            paged_storage(
+                |count, start_key| {
+                    let keys = self.keys(count, start_key);
+                    async move { keys }
+                },
+                |storage_key: StorageKey| {
+                    let value = self.values.get(&storage_key.0).cloned();
+                    async move { Ok::<_, Error>(value) }
+                },
+                start_key,
+                paging,
+            )
+            .try_collect()
+            .await
+            .unwrap()
+        }
+    }
+
+    /// Get storage keys of the provided pages.
+    fn page_keys(pages: &[Vec<(StorageKey, Vec<u8>)>]) -> Vec<Vec<u8>> {
+        pages
+            .iter()
+            .map(|page| page.iter().map(|(key, _)| key.0[0]).collect())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn pagination() {
+        let storage = FakeStorage::new(25, 100);
+
+        let pages = storage.pages(None, Paging::default()).await;
+
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[2].len(), 5);
+        assert!(pages
+            .iter()
+            .flatten()
+            .all(|(key, value)| *value == [key.0[0], key.0[0]]));
+        assert_eq!(page_keys(&pages).concat(), (0..25u8).collect::<Vec<_>>());
+
+        // Final empty page terminates the iteration.
+        assert_eq!(*storage.requests.lock().unwrap(), [PAGE_SIZE; 4]);
+    }
+
+    #[tokio::test]
+    async fn resume_from_start_key() {
+        let storage = FakeStorage::new(25, 100);
+
+        let pages = storage
+            .pages(Some(StorageKey(vec![19])), Paging::default())
+            .await;
+
+        assert_eq!(page_keys(&pages), [vec![20u8, 21, 22, 23, 24]]);
+    }
+
+    #[tokio::test]
+    async fn concurrent_values_are_ordered() {
+        let storage = FakeStorage::new(25, 100);
+
+        let pages = storage
+            .pages(
+                None,
+                Paging {
+                    page_size: 20,
+                    concurrency: 8,
+                },
+            )
+            .await;
+
+        assert_eq!(
+            page_keys(&pages),
+            [(0..20u8).collect::<Vec<_>>(), (20..25).collect()]
+        );
+    }
+
+    #[tokio::test]
+    async fn page_size_limits() {
+        let storage = FakeStorage::new(25, 4);
+
+        let pages = storage
+            .pages(
+                None,
+                Paging {
+                    page_size: 20,
+                    concurrency: 1,
+                },
+            )
+            .await;
+
+        assert_eq!(page_keys(&pages).concat(), (0..25u8).collect::<Vec<_>>());
+        assert!(pages.iter().all(|page| page.len() <= 4));
+
+        // Reduced page size is kept for the following pages.
+        let requests = storage.requests.lock().unwrap();
+        assert_eq!(requests[..4], [20, 10, 5, 2]);
+        assert!(requests[4..].iter().all(|count| *count == 2));
+
+        drop(requests);
+
+        // Page sizes above the node limit are clamped before any requests.
+        let storage = FakeStorage::new(1, u32::MAX);
+
+        storage
+            .pages(
+                None,
+                Paging {
+                    page_size: u32::MAX,
+                    concurrency: 1,
+                },
+            )
+            .await;
+
+        assert_eq!(storage.requests.lock().unwrap()[0], MAX_PAGE_SIZE);
+    }
+
+    #[tokio::test]
+    async fn persistent_errors() {
+        let storage = FakeStorage::new(5, 0);
+
+        let result = paged_storage(
+            |count, start_key| {
+                let keys = storage.keys(count, start_key);
+                async move { keys }
+            },
+            |_| async { Ok::<_, Error>(None) },
+            None,
+            Paging::default(),
+        )
+        .try_collect::<Vec<_>>()
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(*storage.requests.lock().unwrap(), [10, 5, 2, 1]);
+    }
+
+    /// Runtime version used in metadata cache tests.
+    const VERSION: RuntimeVersionKey = (1, 100, 2);
+
+    /// Create an empty temporary directory for metadata cache files.
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "patron-metadata-cache-{name}-{}",
+            std::process::id()
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+
+        dir
+    }
+
+    /// Get a cached value, while counting fetch attempts.
+    async fn cached_value(
+        cache: &mut LruCache<RuntimeVersionKey, u32>,
+        directory: Option<&Path>,
+        value: u32,
+        fetches: &Cell<usize>,
+    ) -> u32 {
+        let value = cached(
+            cache,
+            directory,
+            VERSION,
+            || {
+                fetches.set(fetches.get() + 1);
+                async move { Ok::<_, Error>(value.encode()) }
+            },
+            |mut bytes| Ok(u32::decode(&mut bytes)?),
+        )
+        .await
+        .unwrap();
+
+        *value
+    }
+
+    #[tokio::test]
+    async fn metadata_cache_hits() {
+        let mut cache = LruCache::new(NonZeroUsize::new(1).unwrap());
+        let fetches = Cell::new(0);
+
+        assert_eq!(cached_value(&mut cache, None, 1, &fetches).await, 1);
+        assert_eq!(cached_value(&mut cache, None, 2, &fetches).await, 1);
+        assert_eq!(fetches.get(), 1);
+
+        // Capacity of the cache is exceeded, evicting the first runtime version.
+        let other = cached(
+            &mut cache,
+            None,
+            (1, 101, 0),
+            || async { Ok::<_, Error>(3u32.encode()) },
+            |mut bytes| Ok(u32::decode(&mut bytes)?),
+        )
+        .await
+        .unwrap();
+        assert_eq!(*other, 3);
+
+        assert_eq!(cached_value(&mut cache, None, 2, &fetches).await, 2);
+        assert_eq!(fetches.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn metadata_cache_persistence() {
+        let dir = cache_dir("persistence");
+        let fetches = Cell::new(0);
+
+        let mut cache = LruCache::new(NonZeroUsize::new(1).unwrap());
+        assert_eq!(cached_value(&mut cache, Some(&dir), 1, &fetches).await, 1);
+
+        assert_eq!(
+            fs::read(cache_file_path(&dir, VERSION)).unwrap(),
+            1u32.encode()
+        );
+
+        // Fresh in-memory cache loads the value from disk without fetching it.
+        let mut cache = LruCache::new(NonZeroUsize::new(1).unwrap());
+        assert_eq!(cached_value(&mut cache, Some(&dir), 2, &fetches).await, 1);
+        assert_eq!(fetches.get(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn corrupt_metadata_cache() {
+        let dir = cache_dir("corruption");
+        let fetches = Cell::new(0);
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(cache_file_path(&dir, VERSION), [0xff]).unwrap();
+
+        let mut cache = LruCache::new(NonZeroUsize::new(1).unwrap());
+        assert_eq!(cached_value(&mut cache, Some(&dir), 1, &fetches).await, 1);
+        assert_eq!(fetches.get(), 1);
+
+        // Corrupt file is replaced with the fetched value.
+        assert_eq!(
+            fs::read(cache_file_path(&dir, VERSION)).unwrap(),
+            1u32.encode()
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// SS58 prefix of the Astar network.
+    const ASTAR_PREFIX: u16 = 5;
+
+    #[test]
+    fn ss58_prefixes() {
+        let account = AccountId32::new([1; 32]);
+
+        assert_eq!(ss58_address(&account, None), account.to_ss58check());
+
+        let astar = ss58_address(&account, Some(ASTAR_PREFIX));
+        assert_ne!(astar, account.to_ss58check());
+
+        let (decoded, format) = AccountId32::from_ss58check_with_version(&astar).unwrap();
+        assert_eq!(decoded, account);
+        assert_eq!(u16::from(format), ASTAR_PREFIX);
+    }
+}
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use async_trait::async_trait;
 pub use aws_sdk_s3::Error;
 use aws_sdk_s3::{
     config::{Credentials, Region},
@@ -7,6 +8,10 @@ use aws_sdk_s3::{
     primitives::ByteStream,
     Client,
 };
+use base64::{engine::general_purpose::STANDARD, Engine};
+use derive_more::{Display, From};
+use md5::Md5;
+use sha2::{Digest, Sha256};
 
 use crate::config;
 
@@ -16,10 +21,195 @@ use crate::config;
 /// pass files to isolated build environments.
 const EXPIRATION_TIME: Duration = Duration::from_secs(86400);
 
+/// Errors that may occur during the source code upload process.
+#[derive(Debug, Display, derive_more::Error, From)]
+pub enum UploadError {
+    /// AWS S3-related error.
+    S3(Error),
+
+    /// Uploaded object does not match the local contents.
+    Integrity(IntegrityError),
+}
+
+/// Uploaded object integrity verification errors.
+#[derive(Debug, Display, derive_more::Error)]
+pub enum IntegrityError {
+    /// SHA-256 checksum reported by the storage does not match the local contents.
+    #[display(
+        fmt = "SHA-256 checksum mismatch: expected {}, got {}",
+        expected,
+        actual
+    )]
+    Checksum {
+        /// Base64-encoded checksum of the local contents.
+        expected: String,
+
+        /// Base64-encoded checksum reported by the storage.
+        actual: String,
+    },
+
+    /// ETag reported by the storage does not match the MD5 digest of the local contents.
+    #[display(fmt = "ETag mismatch: expected {}, got {}", expected, actual)]
+    ETag {
+        /// Hex-encoded MD5 digest of the local contents.
+        expected: String,
+
+        /// ETag reported by the storage.
+        actual: String,
+    },
+}
+
+/// Integrity checksums sent alongside with the uploaded object contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksums {
+    /// Base64-encoded MD5 digest, sent as the `Content-MD5` header.
+    pub content_md5: String,
+
+    /// Base64-encoded SHA-256 digest, sent as the `x-amz-checksum-sha256` header.
+    pub sha256: String,
+}
+
+impl Checksums {
+    /// Compute checksums of the provided object contents.
+    pub fn new(contents: &[u8]) -> Self {
+        Self {
+            content_md5: STANDARD.encode(Md5::digest(contents)),
+            sha256: STANDARD.encode(Sha256::digest(contents)),
+        }
+    }
+
+    /// Verify the storage response to an upload of an object with the current checksums.
+    ///
+    /// SHA-256 checksum is compared if the storage reports it. Otherwise, the ETag
+    /// is compared with the MD5 digest, unless the ETag is not an MD5 digest,
+    /// as is the case with multipart uploads and objects encrypted with SSE-KMS or SSE-C.
+    /// Such uploads are still verified by the storage itself with the `Content-MD5` header.
+    fn verify(&self, output: &PutObjectOutput) -> Result<(), IntegrityError> {
+        if let Some(checksum) = &output.checksum_sha256 {
+            if *checksum != self.sha256 {
+                return Err(IntegrityError::Checksum {
+                    expected: self.sha256.clone(),
+                    actual: checksum.clone(),
+                });
+            }
+
+            return Ok(());
+        }
+
+        if output.e_tag_is_opaque() {
+            return Ok(());
+        }
+
+        let Some(e_tag) = &output.e_tag else {
+            return Ok(());
+        };
+
+        let digest = e_tag.trim_matches('"');
+
+        if digest.len() != 32 || !digest.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return Ok(());
+        }
+
+        let expected = STANDARD
+            .decode(&self.content_md5)
+            .map(hex::encode)
+            .unwrap_or_default();
+
+        if !digest.eq_ignore_ascii_case(&expected) {
+            return Err(IntegrityError::ETag {
+                expected,
+                actual: e_tag.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Object upload request.
+pub struct PutObject {
+    /// Bucket name.
+    pub bucket: String,
+
+    /// Object key.
+    pub key: String,
+
+    /// Object contents.
+    pub body: Vec<u8>,
+
+    /// Integrity checksums sent alongside with the object contents, if any.
+    pub checksums: Option<Checksums>,
+}
+
+/// Storage response to a successful object upload.
+#[derive(Debug, Default, Clone)]
+pub struct PutObjectOutput {
+    /// Entity tag of the uploaded object.
+    pub e_tag: Option<String>,
+
+    /// Base64-encoded SHA-256 checksum of the uploaded object, if reported by the storage.
+    pub checksum_sha256: Option<String>,
+
+    /// Server-side encryption algorithm used to store the object, if reported by the storage.
+    pub server_side_encryption: Option<String>,
+
+    /// Encryption algorithm of a customer-provided key (SSE-C), if reported by the storage.
+    pub sse_customer_algorithm: Option<String>,
+}
+
+impl PutObjectOutput {
+    /// Determine if the ETag of the uploaded object is not an MD5 digest
+    /// due to the object encryption with either SSE-KMS or SSE-C.
+    fn e_tag_is_opaque(&self) -> bool {
+        self.sse_customer_algorithm.is_some()
+            || self
+                .server_side_encryption
+                .as_deref()
+                .map_or(false, |algorithm| algorithm.starts_with("aws:kms"))
+    }
+}
+
+/// Object storage operations used to store source code archives.
+///
+/// This trait is implemented for the S3 [`Client`], and allows to replace
+/// the storage with an in-memory implementation in tests.
+#[async_trait]
+pub trait ObjectStorage: Send + Sync {
+    /// Upload an object, sending the provided integrity checksums, if any.
+    async fn put_object(&self, request: PutObject) -> Result<PutObjectOutput, Error>;
+}
+
+#[async_trait]
+impl ObjectStorage for Client {
+    async fn put_object(&self, request: PutObject) -> Result<PutObjectOutput, Error> {
+        let mut builder = Client::put_object(self)
+            .bucket(request.bucket)
+            .key(request.key)
+            .body(ByteStream::from(request.body));
+
+        if let Some(checksums) = request.checksums {
+            builder = builder
+                .content_md5(checksums.content_md5)
+                .checksum_sha256(checksums.sha256);
+        }
+
+        let output = builder.send().await?;
+
+        Ok(PutObjectOutput {
+            e_tag: output.e_tag().map(String::from),
+            checksum_sha256: output.checksum_sha256().map(String::from),
+            server_side_encryption: output
+                .server_side_encryption()
+                .map(|algorithm| String::from(algorithm.as_str())),
+            sse_customer_algorithm: output.sse_customer_algorithm().map(String::from),
+        })
+    }
+}
+
 /// Configured S3 client.
-pub struct ConfiguredClient<'a> {
+pub struct ConfiguredClient<'a, S = Client> {
     config: &'a config::Storage,
-    client: Client,
+    client: S,
 }
 
 impl<'a> ConfiguredClient<'a> {
@@ -65,20 +255,201 @@ impl<'a> ConfiguredClient<'a> {
 
         Ok(req)
     }
+}
+
+impl<'a, S: ObjectStorage> ConfiguredClient<'a, S> {
+    /// Create new [`ConfiguredClient`] from the provided [`Storage`] configuration,
+    /// which uses the provided object storage.
+    ///
+    /// [`Storage`]: config::Storage
+    pub fn with_storage(config: &'a config::Storage, client: S) -> Self {
+        ConfiguredClient { config, client }
+    }
 
     /// Upload source code with the provided code hash.
-    pub async fn upload_source_code<F>(&self, hash: &[u8], file: F) -> Result<(), Error>
-    where
-        ByteStream: From<F>,
-    {
-        self.client
-            .put_object()
-            .bucket(&self.config.source_code_bucket)
-            .key(hex::encode(hash))
-            .body(ByteStream::from(file))
-            .send()
+    ///
+    /// Unless disabled in the storage configuration, integrity checksums are sent
+    /// alongside with the source code, and the storage response is verified against them.
+    pub async fn upload_source_code(&self, hash: &[u8], file: &[u8]) -> Result<(), UploadError> {
+        let checksums = self.config.checksums.then(|| Checksums::new(file));
+
+        let output = self
+            .client
+            .put_object(PutObject {
+                bucket: self.config.source_code_bucket.clone(),
+                key: hex::encode(hash),
+                body: file.to_vec(),
+                checksums: checksums.clone(),
+            })
             .await?;
 
+        if let Some(checksums) = checksums {
+            checksums.verify(&output)?;
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::{
+        Checksums, ConfiguredClient, Error, IntegrityError, ObjectStorage, PutObject,
+        PutObjectOutput, UploadError,
+    };
+    use crate::config::Storage;
+
+    /// Base64-encoded MD5 digest of `hello`.
+    const HELLO_MD5: &str = "XUFAKrxLKna5cZ2REBfFkg==";
+
+    /// Base64-encoded SHA-256 digest of `hello`.
+    const HELLO_SHA256: &str = "LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=";
+
+    /// Create a storage configuration with the provided checksums usage.
+    fn storage_config(checksums: bool) -> Storage {
+        Storage {
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            region: String::new(),
+            endpoint_url: String::new(),
+            source_code_bucket: String::from("source-code"),
+            checksums,
+        }
+    }
+
+    /// In-memory [`ObjectStorage`], which records upload requests.
+    struct FakeStorage {
+        /// Response returned for each upload request.
+        output: PutObjectOutput,
+
+        /// Recorded upload requests.
+        requests: Mutex<Vec<PutObject>>,
+    }
+
+    impl FakeStorage {
+        /// Create a storage, that returns the provided response for each upload request.
+        fn new(output: PutObjectOutput) -> Self {
+            Self {
+                output,
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStorage for FakeStorage {
+        async fn put_object(&self, request: PutObject) -> Result<PutObjectOutput, Error> {
+            self.requests.lock().unwrap().push(request);
+            Ok(self.output.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn checksum_headers() {
+        let config = storage_config(true);
+        let storage = FakeStorage::new(PutObjectOutput {
+            checksum_sha256: Some(String::from(HELLO_SHA256)),
+            ..Default::default()
+        });
+        let client = ConfiguredClient::with_storage(&config, storage);
+
+        client.upload_source_code(&[1; 32], b"hello").await.unwrap();
+
+        let requests = client.client.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].key, hex::encode([1; 32]));
+        assert_eq!(requests[0].body, b"hello");
+        assert_eq!(
+            requests[0].checksums,
+            Some(Checksums {
+                content_md5: String::from(HELLO_MD5),
+                sha256: String::from(HELLO_SHA256),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn disabled_checksums() {
+        let config = storage_config(false);
+
+        let storage = FakeStorage::new(PutObjectOutput {
+            e_tag: Some(String::from("\"00000000000000000000000000000000\"")),
+            ..Default::default()
+        });
+        let client = ConfiguredClient::with_storage(&config, storage);
+
+        client.upload_source_code(&[1; 32], b"hello").await.unwrap();
+
+        assert_eq!(client.client.requests.lock().unwrap()[0].checksums, None);
+    }
+
+    #[tokio::test]
+    async fn checksum_mismatch() {
+        let config = storage_config(true);
+        let storage = FakeStorage::new(PutObjectOutput {
+            checksum_sha256: Some(String::from(HELLO_MD5)),
+            ..Default::default()
+        });
+        let client = ConfiguredClient::with_storage(&config, storage);
+
+        assert!(matches!(
+            client.upload_source_code(&[1; 32], b"hello").await,
+            Err(UploadError::Integrity(IntegrityError::Checksum { expected, .. }))
+                if expected == HELLO_SHA256
+        ));
+    }
+
+    #[test]
+    fn e_tag_verification() {
+        let checksums = Checksums::new(b"hello");
+        let e_tag = |e_tag: &str| PutObjectOutput {
+            e_tag: Some(String::from(e_tag)),
+            ..Default::default()
+        };
+
+        assert!(checksums
+            .verify(&e_tag("\"5d41402abc4b2a76b9719d911017c592\""))
+            .is_ok());
+        assert!(checksums
+            .verify(&e_tag("\"5D41402ABC4B2A76B9719D911017C592\""))
+            .is_ok());
+
+        // Multipart upload ETags are not MD5 digests of the object contents.
+        assert!(checksums
+            .verify(&e_tag("\"5d41402abc4b2a76b9719d911017c592-2\""))
+            .is_ok());
+        assert!(checksums.verify(&PutObjectOutput::default()).is_ok());
+
+        assert!(matches!(
+            checksums.verify(&e_tag("\"00000000000000000000000000000000\"")),
+            Err(IntegrityError::ETag { expected, .. })
+                if expected == "5d41402abc4b2a76b9719d911017c592"
+        ));
+
+        // ETags of objects encrypted with SSE-KMS or SSE-C are not MD5 digests either.
+        assert!(checksums
+            .verify(&PutObjectOutput {
+                server_side_encryption: Some(String::from("aws:kms")),
+                ..e_tag("\"00000000000000000000000000000000\"")
+            })
+            .is_ok());
+        assert!(checksums
+            .verify(&PutObjectOutput {
+                sse_customer_algorithm: Some(String::from("AES256")),
+                ..e_tag("\"00000000000000000000000000000000\"")
+            })
+            .is_ok());
+
+        // SSE-S3 ETags are still MD5 digests.
+        assert!(checksums
+            .verify(&PutObjectOutput {
+                server_side_encryption: Some(String::from("AES256")),
+                ..e_tag("\"00000000000000000000000000000000\"")
+            })
+            .is_err());
+    }
+}
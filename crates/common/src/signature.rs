@@ -0,0 +1,131 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Header name used to pass the request body signature.
+pub const SIGNATURE_HEADER: &str = "X-Patron-Signature";
+
+/// Header name used to pass the UNIX timestamp (in seconds) covered by the request signature.
+pub const TIMESTAMP_HEADER: &str = "X-Patron-Timestamp";
+
+/// Prefix of the signature header value.
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// Compute a signature of the provided request body using the shared secret.
+///
+/// Returned value is a hex-encoded HMAC-SHA256 value prefixed with the `sha256=` string.
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    format!(
+        "{SIGNATURE_PREFIX}{}",
+        hex::encode(mac(secret, body).finalize().into_bytes())
+    )
+}
+
+/// Verify the signature of the provided request body in constant time.
+///
+/// Accepts values in the same format as returned by the [`sign`] function.
+pub fn verify(secret: &[u8], body: &[u8], signature: &str) -> bool {
+    let Some(signature) = signature
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(|signature| hex::decode(signature).ok())
+    else {
+        return false;
+    };
+
+    mac(secret, body).verify_slice(&signature).is_ok()
+}
+
+/// Compute a signature of the provided request body and UNIX timestamp using the shared secret.
+///
+/// Signed message is the decimal timestamp followed by a `.` character and the request body,
+/// which allows receivers to reject requests replayed after the timestamp expires.
+pub fn sign_timestamped(secret: &[u8], timestamp: u64, body: &[u8]) -> String {
+    sign(secret, &timestamped_message(timestamp, body))
+}
+
+/// Verify the signature of the provided request body and UNIX timestamp in constant time.
+///
+/// Accepts values in the same format as returned by the [`sign_timestamped`] function.
+/// Timestamp freshness is not checked, see [`timestamp_is_fresh`].
+pub fn verify_timestamped(secret: &[u8], timestamp: u64, body: &[u8], signature: &str) -> bool {
+    verify(secret, &timestamped_message(timestamp, body), signature)
+}
+
+/// Check if the provided UNIX timestamp differs from the current time
+/// by no more than `tolerance` seconds in either direction.
+pub fn timestamp_is_fresh(timestamp: u64, tolerance: u64) -> bool {
+    unix_timestamp().abs_diff(timestamp) <= tolerance
+}
+
+/// Get the current UNIX timestamp in seconds.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Create a message that covers both the request body and its timestamp.
+fn timestamped_message(timestamp: u64, body: &[u8]) -> Vec<u8> {
+    let mut message = format!("{timestamp}.").into_bytes();
+    message.extend_from_slice(body);
+    message
+}
+
+/// Create a HMAC-SHA256 instance with the provided secret and body.
+fn mac(secret: &[u8], body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        sign, sign_timestamped, timestamp_is_fresh, unix_timestamp, verify, verify_timestamped,
+    };
+
+    #[test]
+    fn signature() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn verification() {
+        let signature = sign(b"secret", b"body");
+
+        assert!(verify(b"secret", b"body", &signature));
+        assert!(!verify(b"other", b"body", &signature));
+        assert!(!verify(b"secret", b"other", &signature));
+        assert!(!verify(b"secret", b"body", &signature["sha256=".len()..]));
+        assert!(!verify(b"secret", b"body", "sha256=xyz"));
+    }
+
+    #[test]
+    fn timestamped_verification() {
+        let signature = sign_timestamped(b"secret", 1000, b"body");
+
+        assert_eq!(signature, sign(b"secret", b"1000.body"));
+        assert!(verify_timestamped(b"secret", 1000, b"body", &signature));
+        assert!(!verify_timestamped(b"secret", 1001, b"body", &signature));
+        assert!(!verify_timestamped(b"secret", 1000, b"other", &signature));
+        assert!(!verify(b"secret", b"body", &signature));
+    }
+
+    #[test]
+    fn timestamp_freshness() {
+        let now = unix_timestamp();
+
+        assert!(timestamp_is_fresh(now, 60));
+        assert!(timestamp_is_fresh(now - 30, 60));
+        assert!(timestamp_is_fresh(now + 30, 60));
+        assert!(!timestamp_is_fresh(now - 600, 60));
+        assert!(!timestamp_is_fresh(now + 600, 60));
+        assert!(!timestamp_is_fresh(0, 60));
+    }
+}
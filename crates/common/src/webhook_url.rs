@@ -0,0 +1,229 @@
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+};
+
+use derive_more::{Display, From};
+use url::{Host, Url};
+
+/// Default port of HTTPS webhook URLs.
+const HTTPS_PORT: u16 = 443;
+
+/// Errors that may occur during the webhook URL validation.
+#[derive(Debug, Display, derive_more::Error, From)]
+pub enum WebhookUrlError {
+    /// Provided value is not a valid URL.
+    #[display(fmt = "invalid webhook URL: {}", _0)]
+    InvalidUrl(url::ParseError),
+
+    /// Webhook URL does not use the HTTPS scheme.
+    #[display(fmt = "webhook URL must use the https scheme")]
+    InsecureScheme,
+
+    /// Webhook URL does not contain a host.
+    #[display(fmt = "webhook URL must contain a host")]
+    MissingHost,
+
+    /// Webhook host name could not be resolved.
+    #[display(fmt = "unable to resolve webhook host: {}", _0)]
+    Resolve(io::Error),
+
+    /// Webhook host name does not resolve to any address.
+    #[display(fmt = "webhook host does not resolve to any address")]
+    Unresolved,
+
+    /// Webhook host is or resolves to a non-public address.
+    #[display(fmt = "webhook host resolves to a non-public address {}", _0)]
+    #[from(ignore)]
+    NonPublicAddress(#[error(not(source))] IpAddr),
+}
+
+/// Check whether the provided address is publicly routable.
+///
+/// Loopback, private, link-local, unspecified, multicast and shared
+/// address space addresses are not considered public.
+///
+/// IPv6 addresses, that embed an IPv4 address, are checked using the embedded address.
+pub fn is_public(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(addr) => is_public_v4(addr),
+        IpAddr::V6(addr) => match embedded_v4(addr) {
+            Some(addr) => is_public_v4(addr),
+            None => is_public_v6(addr),
+        },
+    }
+}
+
+/// Extract the IPv4 address embedded into the provided IPv6 address.
+///
+/// IPv4-mapped (`::ffff:0:0/96`), IPv4-compatible (`::/96`), NAT64 (`64:ff9b::/96`)
+/// and 6to4 (`2002::/16`) addresses are supported.
+fn embedded_v4(addr: Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = addr.segments();
+    let octets = addr.octets();
+
+    let last_v4 = || Ipv4Addr::new(octets[12], octets[13], octets[14], octets[15]);
+
+    match segments {
+        [0, 0, 0, 0, 0, 0xffff, ..] | [0, 0, 0, 0, 0, 0, ..] | [0x64, 0xff9b, 0, 0, 0, 0, ..] => {
+            Some(last_v4())
+        }
+        [0x2002, ..] => Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5])),
+        _ => None,
+    }
+}
+
+/// Check whether the provided IPv4 address is publicly routable.
+fn is_public_v4(addr: Ipv4Addr) -> bool {
+    let [first, second, ..] = addr.octets();
+
+    // 0.0.0.0/8 addresses refer to the current network.
+    let current_network = first == 0;
+
+    // 100.64.0.0/10 addresses are used for carrier-grade NAT.
+    let shared = first == 100 && (second & 0xc0) == 64;
+
+    !(addr.is_loopback()
+        || addr.is_private()
+        || addr.is_link_local()
+        || addr.is_unspecified()
+        || addr.is_broadcast()
+        || addr.is_multicast()
+        || current_network
+        || shared)
+}
+
+/// Check whether the provided IPv6 address is publicly routable.
+fn is_public_v6(addr: Ipv6Addr) -> bool {
+    let first_segment = addr.segments()[0];
+
+    // fc00::/7 unique local addresses.
+    let unique_local = (first_segment & 0xfe00) == 0xfc00;
+
+    // fe80::/10 link-local addresses.
+    let link_local = (first_segment & 0xffc0) == 0xfe80;
+
+    !(addr.is_loopback()
+        || addr.is_unspecified()
+        || addr.is_multicast()
+        || unique_local
+        || link_local)
+}
+
+/// Ensure that the provided address is publicly routable.
+fn ensure_public(addr: IpAddr) -> Result<(), WebhookUrlError> {
+    if is_public(addr) {
+        Ok(())
+    } else {
+        Err(WebhookUrlError::NonPublicAddress(addr))
+    }
+}
+
+/// Resolve the provided host name, ensuring that every resolved address is publicly routable.
+pub async fn resolve_public(host: &str, port: u16) -> Result<Vec<SocketAddr>, WebhookUrlError> {
+    let addrs = tokio::net::lookup_host((host, port))
+        .await?
+        .collect::<Vec<_>>();
+
+    if addrs.is_empty() {
+        return Err(WebhookUrlError::Unresolved);
+    }
+
+    for addr in &addrs {
+        ensure_public(addr.ip())?;
+    }
+
+    Ok(addrs)
+}
+
+/// Validate the provided webhook URL.
+///
+/// Webhook URLs must use the HTTPS scheme, and their hosts must be publicly routable,
+/// which prevents webhooks from being used to send requests to internal services.
+pub async fn validate(url: &str) -> Result<Url, WebhookUrlError> {
+    let url = Url::parse(url)?;
+
+    if url.scheme() != "https" {
+        return Err(WebhookUrlError::InsecureScheme);
+    }
+
+    match url.host().ok_or(WebhookUrlError::MissingHost)? {
+        Host::Ipv4(addr) => ensure_public(IpAddr::V4(addr))?,
+        Host::Ipv6(addr) => ensure_public(IpAddr::V6(addr))?,
+        Host::Domain(domain) => {
+            resolve_public(domain, url.port().unwrap_or(HTTPS_PORT)).await?;
+        }
+    }
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use super::{is_public, validate, WebhookUrlError};
+
+    #[test]
+    fn public_addresses() {
+        for addr in [
+            "203.0.113.10",
+            "8.8.8.8",
+            "2001:4860:4860::8888",
+            "64:ff9b::808:808",
+            "2002:808:808::1",
+        ] {
+            assert!(is_public(addr.parse().unwrap()), "{addr}");
+        }
+
+        for addr in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "0.1.2.3",
+            "100.64.0.1",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fc00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::10.0.0.1",
+            "::127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+            "64:ff9b::192.168.1.1",
+            "2002:a00:1::1",
+            "2002:7f00:1::",
+        ] {
+            assert!(!is_public(addr.parse::<IpAddr>().unwrap()), "{addr}");
+        }
+    }
+
+    #[tokio::test]
+    async fn validation() {
+        assert!(validate("https://203.0.113.10/webhook").await.is_ok());
+
+        assert!(matches!(
+            validate("http://203.0.113.10/webhook").await,
+            Err(WebhookUrlError::InsecureScheme)
+        ));
+
+        for url in [
+            "https://127.0.0.1/webhook",
+            "https://[::1]:8080/webhook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://localhost/webhook",
+        ] {
+            assert!(
+                matches!(
+                    validate(url).await,
+                    Err(WebhookUrlError::NonPublicAddress(_))
+                ),
+                "{url}"
+            );
+        }
+    }
+}
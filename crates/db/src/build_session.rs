@@ -5,11 +5,11 @@
 //!
 //! It contains all the necessary information on the related contract source code,
 //! Rust and `cargo-contract` tooling versions, and, as soon as the build is successful,
-//! WASM code hash and JSON metadata.
+//! contract code hash, its [`ArtifactKind`] and JSON metadata.
 
 use schemars::JsonSchema;
 use sea_orm::{entity::prelude::*, FromQueryResult};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Build session model.
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -36,18 +36,70 @@ pub struct Model {
     /// Relative project directory, that can be used to build multi-contract projects.
     pub project_directory: Option<String>,
 
-    /// WASM blob code hash, if the contract build was successful.
+    /// Contract blob code hash, if the contract build was successful.
     pub code_hash: Option<Vec<u8>>,
 
+    /// Kind of the contract blob produced by the build.
+    ///
+    /// Defaults to [`ArtifactKind::Wasm`] until the build is successful.
+    pub artifact_kind: ArtifactKind,
+
     /// JSON metadata value, if the contract build was successful.
     pub metadata: Option<Vec<u8>>,
 
+    /// Blake2b 256-bit hash of the stored JSON metadata value.
+    ///
+    /// [`None`] if the contract build was not successful, or if the build session
+    /// was completed before metadata hashes were stored.
+    pub metadata_hash: Option<Vec<u8>>,
+
+    /// Name of the built contract crate, if the contract build was successful.
+    pub contract_name: Option<String>,
+
+    /// Duration of the source code unarchiving stage, in milliseconds.
+    pub unarchive_duration: Option<i64>,
+
+    /// Duration of the contract build stage, in milliseconds.
+    pub build_duration: Option<i64>,
+
+    /// Duration of the build artifacts retrieval stage, in milliseconds.
+    pub move_duration: Option<i64>,
+
+    /// Digest of the build image used to build the contract, in the `sha256:<hex>` format.
+    ///
+    /// [`None`] if the build container was never started.
+    pub image_digest: Option<String>,
+
+    /// Hostname of the builder instance that handled this build session.
+    ///
+    /// [`None`] if the build session was not handled yet.
+    pub builder_hostname: Option<String>,
+
+    /// Reference of the build image used to build the contract.
+    ///
+    /// Image is referenced by its digest if it was resolved, and by its version tag otherwise.
+    /// [`None`] if the build container was never started.
+    pub build_image: Option<String>,
+
+    /// Builder signature of the code hash concatenated with the JSON metadata hash.
+    ///
+    /// [`None`] if the build session did not complete or its builder has no signing key.
+    pub signature: Option<Vec<u8>>,
+
+    /// Identifier of the [signing key](super::signing_key) used to create the signature.
+    pub signing_key_id: Option<String>,
+
+    /// Whether the source code analysis of this build session is not finished yet.
+    pub diagnostics_pending: bool,
+
     /// Build session creation time.
     pub created_at: TimeDateTime,
 }
 
 /// Build session status.
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, JsonSchema)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, JsonSchema,
+)]
 #[sea_orm(rs_type = "i16", db_type = "Integer")]
 #[serde(rename_all = "snake_case")]
 pub enum Status {
@@ -67,6 +119,47 @@ pub enum Status {
     Completed,
 }
 
+/// Kind of the contract blob produced by a build session.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[sea_orm(rs_type = "i16", db_type = "Integer")]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// WASM blob, which is used by `pallet-contracts`.
+    ///
+    /// Code hash of such blob is a Blake2b 256-bit hash.
+    #[default]
+    #[sea_orm(num_value = 0)]
+    Wasm,
+
+    /// PolkaVM blob, which is used by `pallet-revive`.
+    ///
+    /// Code hash of such blob is a Keccak 256-bit hash.
+    #[sea_orm(num_value = 1)]
+    Polkavm,
+}
+
+impl ArtifactKind {
+    /// File extension used by `cargo-contract` for blobs of this kind.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArtifactKind::Wasm => "wasm",
+            ArtifactKind::Polkavm => "polkavm",
+        }
+    }
+}
+
 /// Build session relations.
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
@@ -90,6 +183,13 @@ pub enum Relation {
         to = "super::user::Column::Id"
     )]
     User,
+
+    #[sea_orm(
+        belongs_to = "super::signing_key::Entity",
+        from = "Column::SigningKeyId",
+        to = "super::signing_key::Column::Id"
+    )]
+    SigningKey,
 }
 
 impl Related<super::code::Entity> for Entity {
@@ -110,6 +210,12 @@ impl Related<super::user::Entity> for Entity {
     }
 }
 
+impl Related<super::signing_key::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SigningKey.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
 /// Information about the build session necessary to
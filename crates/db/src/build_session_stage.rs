@@ -0,0 +1,75 @@
+//! Current processing stage of a build session.
+//!
+//! Stages are stored separately from the build session itself, since
+//! build session rows remain locked by the builder for the entire build duration,
+//! while stage updates have to be visible to readers immediately.
+//!
+//! A stage record is present only while the build session is being processed.
+
+use schemars::JsonSchema;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Build session stage model.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "build_session_stages")]
+pub struct Model {
+    /// Related build session identifier.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub build_session_id: i64,
+
+    /// Current build session stage.
+    pub stage: Stage,
+}
+
+/// Build session processing stage.
+///
+/// Stages are ordered in the same way they are processed by a builder.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(None)")]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Source code archive is being unpacked.
+    #[sea_orm(string_value = "unarchiving")]
+    Unarchiving,
+
+    /// Contract is being built.
+    #[sea_orm(string_value = "building")]
+    Building,
+
+    /// Build artifacts are being extracted from the build container.
+    #[sea_orm(string_value = "extracting")]
+    Extracting,
+}
+
+/// Build session stage relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::build_session::Entity",
+        from = "Column::BuildSessionId",
+        to = "super::build_session::Column::Id"
+    )]
+    BuildSession,
+}
+
+impl Related<super::build_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BuildSession.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -6,6 +6,9 @@
 //! As soon as all files are passed to an API server
 //! the build session token should be destroyed by calling
 //! a "seal" method on an API server.
+//!
+//! Tokens are also removed as soon as the related build session is finished,
+//! while tokens that outlived the configured age are removed by the builder.
 
 use rand::{
     distributions::{Alphanumeric, DistString},
@@ -33,6 +36,9 @@ pub struct Model {
 
     /// Related build session identifier
     pub build_session_id: i64,
+
+    /// Build session token creation time.
+    pub created_at: TimeDateTime,
 }
 
 /// Build session token relations.
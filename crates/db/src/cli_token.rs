@@ -7,8 +7,14 @@
 //! 3. As soon as authentication is successful,
 //! CLI can call a dedicated method to exchange
 //! the generated token for an authentication token.
+//!
+//! Each CLI token can be exchanged only once, after which it is removed.
+//!
+//! Only hashes of CLI tokens are stored, and exchanges look up CLI tokens by their hashes,
+//! so that lookup timing does not depend on the secret CLI token value.
 
 use sea_orm::entity::prelude::*;
+use sha2::{Digest, Sha256};
 
 pub const TOKEN_LENGTH: usize = 64;
 
@@ -16,9 +22,9 @@ pub const TOKEN_LENGTH: usize = 64;
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "cli_tokens")]
 pub struct Model {
-    /// Unique CLI token string.
-    #[sea_orm(primary_key)]
-    pub token: String,
+    /// Hex-encoded SHA-256 hash of a unique CLI token string.
+    #[sea_orm(primary_key, column_name = "token")]
+    pub token_hash: String,
 
     /// Related authentication token identifier.
     pub authentication_token_id: i64,
@@ -41,3 +47,46 @@ impl Related<super::token::Entity> for Entity {
 }
 
 impl ActiveModelBehavior for ActiveModel {}
+
+/// Hash the provided CLI token.
+///
+/// Returned value is a hex-encoded SHA-256 hash, suitable for [`Model::token_hash`].
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Compare two CLI token hashes in constant time.
+///
+/// Comparison time depends only on the hash lengths,
+/// which are equal for all hashes returned by [`hash_token`].
+pub fn hashes_equal(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_token, hashes_equal};
+
+    #[test]
+    fn token_hash() {
+        assert_eq!(
+            hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn hash_equality() {
+        assert!(hashes_equal("abcd", "abcd"));
+        assert!(hashes_equal("", ""));
+        assert!(!hashes_equal("abcd", "abce"));
+        assert!(!hashes_equal("abcd", "abc"));
+        assert!(!hashes_equal("abcd", "ABCD"));
+    }
+}
@@ -24,6 +24,11 @@ pub struct Model {
     /// Contract owner, if the contract was
     /// discovered via propagated node events.
     pub owner: Option<Vec<u8>>,
+
+    /// Number of a block during which the contract was instantiated.
+    ///
+    /// [`None`] if the contract was discovered during node initialization.
+    pub block_number: Option<i64>,
 }
 
 /// Smart contract model relations.
@@ -24,6 +24,21 @@ pub struct Model {
     /// Diagnostic end file position.
     pub end: i64,
 
+    /// Line number of the diagnostic start position, starting from 1.
+    ///
+    /// Line and column numbers are not available for diagnostics
+    /// stored before they were introduced.
+    pub start_line: Option<i64>,
+
+    /// Column number of the diagnostic start position in characters, starting from 1.
+    pub start_col: Option<i64>,
+
+    /// Line number of the diagnostic end position, starting from 1.
+    pub end_line: Option<i64>,
+
+    /// Column number of the diagnostic end position in characters, starting from 1.
+    pub end_col: Option<i64>,
+
     /// Diagnostic message.
     pub message: String,
 }
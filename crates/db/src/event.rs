@@ -2,8 +2,8 @@
 //!
 //! These events are discovered by a separate event client server (also known as a sync server).
 
-use sea_orm::entity::prelude::*;
-use serde::Serialize;
+use sea_orm::{entity::prelude::*, FromJsonQueryResult};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 /// Event model.
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -22,11 +22,17 @@ pub struct Model {
     /// Type of the current event model.
     pub event_type: EventType,
 
-    /// Raw event body value, instantiated from a JSON serialization of a [`EventBody`] enum.
-    pub body: String,
+    /// Event body value, stored as a JSON value.
+    #[sea_orm(column_type = "JsonBinary")]
+    pub body: EventBody,
 
     /// Timestamp of a block during which the event occured.
     pub block_timestamp: TimeDateTime,
+
+    /// Number of a block during which the event occured.
+    ///
+    /// [`None`] for events discovered before block numbers were recorded.
+    pub block_number: Option<i64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize)]
@@ -46,10 +52,18 @@ pub enum EventType {
     Termination,
 }
 
-#[derive(Serialize)]
+/// Event body, which contains event type-specific information.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(remote = "Self")]
 pub enum EventBody {
     /// A contract was instantiated.
-    Instantiation,
+    Instantiation {
+        /// SS58-encoded address of the contract deployer.
+        ///
+        /// Empty for events discovered before deployers were recorded.
+        #[serde(default)]
+        deployer: String,
+    },
 
     /// Contract's code hash was updated.
     CodeHashUpdate {
@@ -61,7 +75,79 @@ pub enum EventBody {
     Termination,
 }
 
+impl Serialize for EventBody {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EventBody::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EventBody {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+
+        // Instantiation events were stored as unit variants before deployers were recorded.
+        if value.as_str() == Some("Instantiation") {
+            return Ok(EventBody::Instantiation {
+                deployer: String::new(),
+            });
+        }
+
+        EventBody::deserialize(value).map_err(de::Error::custom)
+    }
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::EventBody;
+
+    /// SS58-encoded address used as a deployer.
+    const DEPLOYER: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    #[test]
+    fn legacy_instantiation_body() {
+        for value in [json!("Instantiation"), json!({ "Instantiation": {} })] {
+            assert_eq!(
+                serde_json::from_value::<EventBody>(value).unwrap(),
+                EventBody::Instantiation {
+                    deployer: String::new()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn body_round_trip() {
+        let instantiation = EventBody::Instantiation {
+            deployer: String::from(DEPLOYER),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&instantiation).unwrap(),
+            json!({ "Instantiation": { "deployer": DEPLOYER } })
+        );
+
+        for body in [
+            instantiation,
+            EventBody::CodeHashUpdate {
+                new_code_hash: "00".repeat(32),
+            },
+            EventBody::Termination,
+        ] {
+            let value = serde_json::to_value(&body).unwrap();
+            assert_eq!(serde_json::from_value::<EventBody>(value).unwrap(), body);
+        }
+
+        assert_eq!(
+            serde_json::from_value::<EventBody>(json!("Termination")).unwrap(),
+            EventBody::Termination
+        );
+        assert!(serde_json::from_value::<EventBody>(json!("Unknown")).is_err());
+    }
+}
@@ -7,6 +7,7 @@
 //! and to provide other crates with commonly used `SELECT` query utilities [`SelectExt`].
 
 pub mod build_session;
+pub mod build_session_stage;
 pub mod build_session_token;
 pub mod cli_token;
 pub mod code;
@@ -17,18 +18,22 @@ pub mod file;
 pub mod log;
 pub mod node;
 pub mod public_key;
+pub mod selector;
+pub mod signing_key;
+pub mod skipped_block;
 pub mod source_code;
 pub mod token;
 pub mod user;
+pub mod webhook;
 
 use std::error::Error;
 
 use async_trait::async_trait;
 pub use sea_orm::{
     self, sea_query, ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, Database,
-    DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, FromQueryResult, QueryFilter,
-    QueryOrder, QuerySelect, QueryTrait, StatementBuilder, TransactionError, TransactionTrait,
-    TryGetableMany,
+    DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, FromQueryResult, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, StatementBuilder, TransactionError,
+    TransactionTrait, TryGetableMany,
 };
 pub use time::{OffsetDateTime, PrimitiveDateTime};
 
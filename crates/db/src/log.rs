@@ -5,6 +5,9 @@
 //!
 //! To correctly display log output either manually split lines or output
 //! [`Model`]'s `text` field as-is.
+//!
+//! Identical consecutive log records are stored only once,
+//! with [`Model`]'s `repeat_count` field set to the count of collapsed records.
 
 use sea_orm::entity::prelude::*;
 
@@ -21,6 +24,9 @@ pub struct Model {
 
     /// Log record text value.
     pub text: String,
+
+    /// Count of identical consecutive log records collapsed into this one.
+    pub repeat_count: i64,
 }
 
 /// Log record model relations.
@@ -29,6 +29,64 @@ pub struct Model {
     /// `confirmed_block` value is used to catch-up to missed blocks if
     /// any such blocks are present.
     pub confirmed_block: i64,
+
+    /// Hash of the last confirmed block.
+    ///
+    /// Used to verify that newly discovered blocks are chained onto
+    /// the confirmed block.
+    pub confirmed_block_hash: Option<Vec<u8>>,
+
+    /// Last error that caused an event client to stop processing blocks.
+    ///
+    /// [`None`] if the last block was processed successfully.
+    pub last_error: Option<String>,
+
+    /// Time at which the last block was successfully processed by an event client.
+    ///
+    /// [`None`] if no blocks were processed yet.
+    pub last_block_seen_at: Option<TimeDateTime>,
+
+    /// Current initialization phase.
+    ///
+    /// [`None`] if node initialization was completed.
+    pub initialization_phase: Option<InitializationPhase>,
+
+    /// Last storage key processed during the current initialization phase.
+    ///
+    /// Used to resume an interrupted initialization process.
+    pub initialization_key: Option<Vec<u8>>,
+
+    /// Human-readable network name displayed by UIs.
+    pub display_name: Option<String>,
+
+    /// Block explorer URL template.
+    ///
+    /// `{address}` placeholder is replaced with an account address.
+    pub explorer_url_template: Option<String>,
+
+    /// Native token symbol.
+    pub token_symbol: Option<String>,
+
+    /// Count of decimal places of the native token.
+    pub token_decimals: Option<i16>,
+
+    /// SS58 address format prefix.
+    ///
+    /// [`None`] if the network uses the default prefix.
+    pub ss58_prefix: Option<i16>,
+}
+
+/// Node initialization phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "i16", db_type = "Integer")]
+pub enum InitializationPhase {
+    /// Uploaded WASM blobs are being collected.
+    #[sea_orm(num_value = 0)]
+    Codes,
+
+    /// Deployed contracts are being collected.
+    #[sea_orm(num_value = 1)]
+    Contracts,
 }
 
 /// Node model relations.
@@ -0,0 +1,167 @@
+//! Contract message selector.
+//!
+//! Selectors are derived from the JSON metadata of successfully built contracts,
+//! allowing to find contracts that define a message by its 4-byte selector.
+
+use sea_orm::{entity::prelude::*, sea_query::OnConflict, ActiveValue, ConnectionTrait};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Message selector model.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "selectors")]
+pub struct Model {
+    /// Unique selector identifier.
+    #[sea_orm(primary_key)]
+    pub id: i64,
+
+    /// Code hash of the contract that defines the message.
+    pub code_hash: Vec<u8>,
+
+    /// 4-byte message selector.
+    pub selector: Vec<u8>,
+
+    /// Message name.
+    pub message_name: String,
+
+    /// Whether the message mutates contract storage.
+    pub mutates: bool,
+}
+
+/// Selector model relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::code::Entity",
+        from = "Column::CodeHash",
+        to = "super::code::Column::Hash"
+    )]
+    Code,
+}
+
+impl Related<super::code::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Code.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// A single contract message inside of JSON metadata.
+#[derive(Deserialize)]
+struct MetadataMessage {
+    /// Message name.
+    label: String,
+
+    /// Hex-encoded message selector.
+    selector: String,
+
+    /// Whether the message mutates contract storage.
+    mutates: bool,
+}
+
+/// Extract message selectors from the provided JSON metadata.
+///
+/// Both current metadata format and the `V3` format of ink! 3 are supported.
+/// Returns [`None`] if the metadata could not be parsed.
+pub fn from_metadata(code_hash: &[u8], metadata: &[u8]) -> Option<Vec<ActiveModel>> {
+    let value: Value = serde_json::from_slice(metadata).ok()?;
+
+    let spec = match value.get("spec") {
+        Some(spec) => spec,
+        None => value.get("V3")?.get("spec")?,
+    };
+
+    let messages = Vec::<MetadataMessage>::deserialize(spec.get("messages")?).ok()?;
+
+    messages
+        .into_iter()
+        .map(|message| {
+            Some(ActiveModel {
+                code_hash: ActiveValue::Set(code_hash.to_vec()),
+                selector: ActiveValue::Set(parse_selector(&message.selector)?.to_vec()),
+                message_name: ActiveValue::Set(message.label),
+                mutates: ActiveValue::Set(message.mutates),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Store message selectors extracted from the provided JSON metadata.
+///
+/// Selectors already stored for the provided code hash are skipped, which allows
+/// to call this function multiple times for the same code hash.
+///
+/// Returns the count of stored selectors. Metadata that could not be parsed is ignored.
+pub async fn store<C: ConnectionTrait>(
+    db: &C,
+    code_hash: &[u8],
+    metadata: &[u8],
+) -> Result<u64, DbErr> {
+    let selectors = match from_metadata(code_hash, metadata) {
+        Some(selectors) if !selectors.is_empty() => selectors,
+        _ => return Ok(0),
+    };
+
+    Entity::insert_many(selectors)
+        .on_conflict(
+            OnConflict::columns([Column::Selector, Column::CodeHash])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await
+}
+
+/// Parse a hex-encoded 4-byte selector with an optional `0x` prefix.
+pub fn parse_selector(value: &str) -> Option<[u8; 4]> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+
+    if value.len() != 8 || !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    u32::from_str_radix(value, 16).ok().map(u32::to_be_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::ActiveValue;
+
+    use super::{from_metadata, parse_selector};
+
+    #[test]
+    fn selector_parsing() {
+        assert_eq!(parse_selector("0x633aa551"), Some([0x63, 0x3a, 0xa5, 0x51]));
+        assert_eq!(parse_selector("2f865bd9"), Some([0x2f, 0x86, 0x5b, 0xd9]));
+        assert_eq!(parse_selector("0x633aa5"), None);
+        assert_eq!(parse_selector("+633aa55"), None);
+    }
+
+    #[test]
+    fn metadata_formats() {
+        let spec = r#"{"messages":[{"label":"flip","selector":"0x633aa551","mutates":true}]}"#;
+
+        for metadata in [
+            format!(r#"{{"spec":{spec}}}"#),
+            format!(r#"{{"metadataVersion":"0.1.0","V3":{{"spec":{spec}}}}}"#),
+        ] {
+            let selectors = from_metadata(&[0; 32], metadata.as_bytes()).unwrap();
+
+            assert_eq!(selectors.len(), 1);
+            assert_eq!(
+                selectors[0].selector,
+                ActiveValue::Set(vec![0x63, 0x3a, 0xa5, 0x51])
+            );
+            assert_eq!(
+                selectors[0].message_name,
+                ActiveValue::Set(String::from("flip"))
+            );
+            assert_eq!(selectors[0].mutates, ActiveValue::Set(true));
+        }
+
+        assert!(from_metadata(&[0; 32], b"{}").is_none());
+        assert!(from_metadata(&[0; 32], b"not json").is_none());
+    }
+}
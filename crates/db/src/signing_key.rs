@@ -0,0 +1,27 @@
+//! Builder key used to sign build artifacts.
+//!
+//! Builders register public keys of their signing keys on startup, which allows
+//! clients to verify signatures of build sessions completed before key rotation.
+
+use sea_orm::entity::prelude::*;
+
+/// Signing key model.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "signing_keys")]
+pub struct Model {
+    /// Signing key identifier, derived from the public key.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+
+    /// Raw sr25519 public key.
+    pub public_key: Vec<u8>,
+
+    /// Time at which the key was last registered by a builder.
+    pub registered_at: TimeDateTime,
+}
+
+/// Signing key model relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,49 @@
+//! Block skipped by an event client.
+//!
+//! Blocks that could not be processed by an event client are recorded
+//! to be retried later, allowing the event client to proceed with other blocks.
+
+use sea_orm::entity::prelude::*;
+
+/// Skipped block model.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "skipped_blocks")]
+pub struct Model {
+    /// Unique skipped block identifier.
+    #[sea_orm(primary_key)]
+    pub id: i64,
+
+    /// Related node identifier.
+    pub node_id: i64,
+
+    /// Skipped block number.
+    pub block_number: i64,
+
+    /// Skipped block hash.
+    pub block_hash: Vec<u8>,
+
+    /// Error that occured during the last processing attempt.
+    pub error: String,
+
+    /// Block skip timestamp.
+    pub created_at: TimeDateTime,
+}
+
+/// Skipped block model relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::node::Entity",
+        from = "Column::NodeId",
+        to = "super::node::Column::Id"
+    )]
+    Node,
+}
+
+impl Related<super::node::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Node.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -5,8 +5,13 @@
 //!
 //! There are no guarantees related to the archive itself, thus the archive unpacking
 //! should only be performed in isolated environments.
+//!
+//! Each source code archive has a [`Visibility`], which determines whether
+//! other users can access it alongside with its build artifacts.
 
+use schemars::JsonSchema;
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// Source code archive model.
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -26,6 +31,37 @@ pub struct Model {
 
     /// Source code archive upload timestamp.
     pub created_at: TimeDateTime,
+
+    /// Source code archive visibility to other users.
+    pub visibility: Visibility,
+}
+
+/// Source code archive visibility.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[sea_orm(rs_type = "i16", db_type = "Integer")]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    /// Source code archive and its build artifacts are available to everyone.
+    #[default]
+    #[sea_orm(num_value = 0)]
+    Public,
+
+    /// Source code archive and its build artifacts are available only to its owner,
+    /// unless a contract with a matching code hash was discovered on-chain.
+    #[sea_orm(num_value = 1)]
+    Private,
 }
 
 /// Source code archive model relations.
@@ -5,12 +5,17 @@
 //!
 //! Authentication tokens have their lifespan limited to [`TOKEN_LIFESPAN`] [`Duration`]
 //! value, and are to have their length equal to the [`TOKEN_LENGTH`] value.
+//!
+//! Each authentication token has a [`Scope`], which limits the set of routes
+//! that can be accessed using it.
 
 use rand::{
     distributions::{Alphanumeric, DistString},
     thread_rng,
 };
+use schemars::JsonSchema;
 use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
 use time::{Duration, OffsetDateTime, PrimitiveDateTime};
 
 pub const TOKEN_LENGTH: usize = 64;
@@ -32,6 +37,45 @@ pub struct Model {
 
     /// Authentication token creation timestamp.
     pub created_at: TimeDateTime,
+
+    /// Set of routes that can be accessed using this token.
+    pub scope: Scope,
+}
+
+/// Authentication token scope.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[sea_orm(rs_type = "i16", db_type = "Integer")]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// Access to all routes, including key management and payments.
+    #[default]
+    #[sea_orm(num_value = 0)]
+    Full,
+
+    /// Access to build session and source code management routes only.
+    ///
+    /// Suitable for CI environments, which do not need to manage user accounts.
+    #[sea_orm(num_value = 1)]
+    Ci,
+}
+
+impl Scope {
+    /// Check if this scope allows access to routes that require the provided scope.
+    pub fn allows(self, required: Scope) -> bool {
+        matches!((self, required), (Scope::Full, _) | (Scope::Ci, Scope::Ci))
+    }
 }
 
 /// Authentication token model relations.
@@ -14,6 +14,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i64,
     pub paid: bool,
+    pub public_builds: bool,
     pub created_at: TimeDateTime,
 }
 
@@ -31,6 +32,9 @@ pub enum Relation {
 
     #[sea_orm(has_many = "super::build_session::Entity")]
     BuildSessions,
+
+    #[sea_orm(has_many = "super::webhook::Entity")]
+    Webhooks,
 }
 
 impl Related<super::public_key::Entity> for Entity {
@@ -57,4 +61,10 @@ impl Related<super::build_session::Entity> for Entity {
     }
 }
 
+impl Related<super::webhook::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhooks.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,37 @@
+//! User-defined webhook.
+//!
+//! Webhooks are notified whenever a build session of the related user
+//! finishes its execution, with each request being signed using the stored secret.
+
+use sea_orm::entity::prelude::*;
+
+/// Webhook model.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "webhooks")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub url: String,
+    pub secret: String,
+    pub created_at: TimeDateTime,
+}
+
+/// Webhook model relations.
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,6 +1,21 @@
+/// `backfill-owners` subcommand.
+mod backfill_owners;
+
 /// `initialize` subcommand.
 mod initialize;
 
+/// `list` subcommand.
+mod list;
+
+/// `remove` subcommand.
+mod remove;
+
+/// `rename` subcommand.
+mod rename;
+
+/// `reprocess-skipped` subcommand.
+mod reprocess_skipped;
+
 /// `traverse` subcommand.
 mod traverse;
 
@@ -10,14 +25,23 @@ mod update_contract;
 /// `watch` subcommand.
 mod watch;
 
+/// `watch-all` subcommand.
+mod watch_all;
+
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
 
-pub use initialize::initialize;
+pub use backfill_owners::{backfill_owners, OwnerSource};
+pub use initialize::{initialize, DisplayMetadata, InitializeOptions};
+pub use list::list;
+pub use remove::remove;
+pub use rename::rename;
+pub use reprocess_skipped::reprocess_skipped;
 pub use traverse::traverse;
 pub use update_contract::update_contract;
-pub use watch::watch;
+pub use watch::{watch, WatchOptions};
+pub use watch_all::watch_all;
 
 /// Primary CLI configuration, serves as an entrypoint to [`clap`].
 #[derive(Parser)]
@@ -35,6 +59,30 @@ pub(crate) struct Cli {
 /// Supported subcommands.
 #[derive(Subcommand)]
 pub(crate) enum Command {
+    /// Fill owners of contracts that were discovered without them.
+    BackfillOwners {
+        /// Node name.
+        name: String,
+
+        /// JSON file that maps contract addresses to their deployers.
+        ///
+        /// If not provided, owners are resolved from historical instantiation events instead.
+        #[clap(long, conflicts_with_all = ["from_block", "to_block"])]
+        mapping_file: Option<PathBuf>,
+
+        /// First block to scan for instantiation events.
+        #[clap(long, default_value_t = 0)]
+        from_block: u32,
+
+        /// Last block to scan for instantiation events, defaults to the last confirmed block.
+        #[clap(long)]
+        to_block: Option<u32>,
+
+        /// Number of contracts updated within a single transaction.
+        #[clap(long, default_value_t = 500)]
+        batch_size: usize,
+    },
+
     /// Initialize new node with the provided options.
     Initialize {
         /// Node name.
@@ -46,6 +94,42 @@ pub(crate) enum Command {
         /// Address of a contract that accepts membership payments.
         #[clap(long)]
         payment_address: Option<String>,
+
+        /// Node information displayed by UIs.
+        #[command(flatten)]
+        display: DisplayMetadata,
+
+        /// Initialization process options.
+        #[command(flatten)]
+        options: InitializeOptions,
+    },
+
+    /// List all available nodes.
+    List,
+
+    /// Remove the provided node.
+    Remove {
+        /// Node name.
+        name: String,
+
+        /// Remove related contracts and events as well.
+        #[clap(long)]
+        cascade: bool,
+    },
+
+    /// Rename the provided node.
+    Rename {
+        /// Current node name.
+        old_name: String,
+
+        /// New node name.
+        new_name: String,
+    },
+
+    /// Reprocess blocks that were skipped by the watcher.
+    ReprocessSkipped {
+        /// Node name.
+        name: String,
     },
 
     /// Traverse old blocks of the provided node for old events.
@@ -59,13 +143,28 @@ pub(crate) enum Command {
         /// Node name.
         name: String,
 
-        /// Address of a contract that accepts membership payments.
+        /// Address of a contract that accepts membership payments, in SS58 or hex format.
         payment_address: Option<String>,
+
+        /// Update the address even if no contract was found on-chain.
+        #[clap(long)]
+        force: bool,
     },
 
     /// Watch node for new blocks to discover contract events.
     Watch {
         /// Node name.
         name: String,
+
+        /// Watch process options.
+        #[command(flatten)]
+        options: WatchOptions,
+    },
+
+    /// Watch all available nodes for new blocks to discover contract events.
+    WatchAll {
+        /// Watch process options.
+        #[command(flatten)]
+        options: WatchOptions,
     },
 }
@@ -0,0 +1,403 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io, mem,
+    path::Path,
+};
+
+use common::rpc::{
+    self,
+    sp_core::ByteArray,
+    substrate_api_client::{self, rpc::JsonrpseeClient, Api},
+    Instantiated, MetadataCache, MetadataCacheOptions,
+};
+use db::{
+    contract, node, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect,
+    TransactionErrorExt, TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+use futures_util::{pin_mut, TryStreamExt};
+use tracing::{info, warn};
+
+use crate::utils::{self, block_mapping_stream};
+
+/// Errors that may occur during contract owners backfill process.
+#[derive(Debug, Display, Error, From)]
+pub enum BackfillOwnersError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Substrate RPC-related error.
+    #[display(fmt = "rpc error: {:?}", _0)]
+    RpcError(#[error(ignore)] substrate_api_client::Error),
+
+    /// The provided node name is incorrect.
+    #[display(fmt = "node not found")]
+    NodeNotFound,
+
+    /// Unable to read the provided mapping file.
+    #[display(fmt = "unable to read mapping file: {}", _0)]
+    MappingFileError(io::Error),
+
+    /// Mapping file is not a JSON object with string values.
+    #[display(fmt = "invalid mapping file: {}", _0)]
+    InvalidMapping(serde_json::Error),
+
+    /// Mapping file contains an account id that cannot be parsed.
+    #[display(fmt = "invalid account id in mapping file: {}", _0)]
+    #[from(ignore)]
+    InvalidAccountId(#[error(ignore)] String),
+}
+
+/// Source of contract owners information.
+pub enum OwnerSource<'a> {
+    /// JSON object that maps contract addresses to their deployers.
+    MappingFile(&'a Path),
+
+    /// `Instantiated` events within the provided inclusive block range.
+    ///
+    /// If the upper bound is not provided, the last confirmed block of the node is used instead.
+    Events {
+        /// First block to scan.
+        from_block: u32,
+
+        /// Last block to scan.
+        to_block: Option<u32>,
+    },
+}
+
+/// Fill owners of contracts that were discovered without them.
+///
+/// # Details
+///
+/// Contracts discovered during node initialization have no owner information available,
+/// as contract storage items do not contain deployer addresses.
+///
+/// Owners can be resolved either by scanning historical `Instantiated` events,
+/// or by using a mapping file provided by an external indexer. Mapping file is a JSON
+/// object, in which both contract addresses and deployer addresses are in either
+/// SS58 or hex format.
+///
+/// Owners are stored in batches of `batch_size` contracts, and only contracts
+/// without any owner are updated.
+pub async fn backfill_owners(
+    database: DatabaseConnection,
+    name: String,
+    source: OwnerSource<'_>,
+    batch_size: usize,
+    cache_options: MetadataCacheOptions,
+) -> Result<(), BackfillOwnersError> {
+    let node = node::Entity::find()
+        .filter(node::Column::Name.eq(name))
+        .one(&database)
+        .await?
+        .ok_or(BackfillOwnersError::NodeNotFound)?;
+
+    let mut pending = pending_contracts(&database, node.id).await?;
+
+    info!(pending = pending.len(), "found contracts without owners");
+
+    let resolved = match source {
+        OwnerSource::MappingFile(path) => {
+            let mapping = fs::read_to_string(path)?;
+            let owners = parse_mapping(&mapping)?;
+
+            backfill_from_mapping(&database, node.id, &mut pending, owners, batch_size).await?
+        }
+        OwnerSource::Events {
+            from_block,
+            to_block,
+        } => {
+            let to_block = to_block.unwrap_or(node.confirmed_block as u32);
+
+            backfill_from_events(
+                &database,
+                &node,
+                &mut pending,
+                from_block..=to_block,
+                batch_size,
+                cache_options,
+            )
+            .await?
+        }
+    };
+
+    info!(
+        resolved,
+        unresolved = pending.len(),
+        "contract owners backfill completed"
+    );
+
+    Ok(())
+}
+
+/// Get addresses of node contracts without any owner.
+async fn pending_contracts(
+    database: &DatabaseConnection,
+    node_id: i64,
+) -> Result<HashSet<Vec<u8>>, DbErr> {
+    let addresses = contract::Entity::find()
+        .select_only()
+        .column(contract::Column::Address)
+        .filter(contract::Column::NodeId.eq(node_id))
+        .filter(contract::Column::Owner.is_null())
+        .into_tuple::<Vec<u8>>()
+        .all(database)
+        .await?;
+
+    Ok(addresses.into_iter().collect())
+}
+
+/// Parse mapping file contents into pairs of contract and owner addresses.
+fn parse_mapping(mapping: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>, BackfillOwnersError> {
+    let mapping: HashMap<String, String> = serde_json::from_str(mapping)?;
+
+    mapping
+        .into_iter()
+        .map(|(contract, owner)| {
+            let parse = |value: String| {
+                utils::parse_account_id(&value)
+                    .map(|account_id| account_id.as_slice().to_vec())
+                    .ok_or(BackfillOwnersError::InvalidAccountId(value))
+            };
+
+            Ok((parse(contract)?, parse(owner)?))
+        })
+        .collect()
+}
+
+/// Store owners from the provided mapping for pending contracts.
+///
+/// Returns the count of resolved contracts.
+async fn backfill_from_mapping(
+    database: &DatabaseConnection,
+    node_id: i64,
+    pending: &mut HashSet<Vec<u8>>,
+    owners: Vec<(Vec<u8>, Vec<u8>)>,
+    batch_size: usize,
+) -> Result<usize, DbErr> {
+    let owners = owners
+        .into_iter()
+        .filter(|(contract, _)| pending.remove(contract))
+        .collect::<Vec<_>>();
+
+    let mut resolved = 0;
+
+    for batch in owners.chunks(batch_size) {
+        resolved += store_owners(database, node_id, batch.to_vec()).await?;
+
+        info!(resolved, "storing owners batch");
+    }
+
+    Ok(resolved)
+}
+
+/// Scan `Instantiated` events within the provided block range for owners of pending contracts.
+///
+/// Returns the count of resolved contracts.
+async fn backfill_from_events(
+    database: &DatabaseConnection,
+    node: &node::Model,
+    pending: &mut HashSet<Vec<u8>>,
+    range: impl IntoIterator<Item = u32>,
+    batch_size: usize,
+    cache_options: MetadataCacheOptions,
+) -> Result<usize, BackfillOwnersError> {
+    let client = JsonrpseeClient::new(&node.url).map_err(substrate_api_client::Error::RpcClient)?;
+    let api = Api::new(client).await?;
+
+    let stream = block_mapping_stream(range, 1, &api);
+
+    pin_mut!(stream);
+
+    let mut metadata_cache = MetadataCache::with_options(cache_options);
+    let mut buffer = Vec::with_capacity(batch_size);
+    let mut resolved = 0;
+
+    while let Some((block_number, block_hash)) = stream.try_next().await? {
+        if pending.is_empty() {
+            break;
+        }
+
+        let metadata = metadata_cache.metadata(&api, block_hash).await?;
+        let events = rpc::events(&api, block_hash, metadata.clone()).await?;
+
+        for event in events.find::<Instantiated>() {
+            let Instantiated { deployer, contract } = match event {
+                Ok(event) => event,
+                Err(err) => {
+                    warn!(%block_number, ?err, "unable to decode instantiation event");
+                    continue;
+                }
+            };
+
+            if pending.remove(contract.as_slice()) {
+                buffer.push((contract.as_slice().to_vec(), deployer.as_slice().to_vec()));
+            }
+        }
+
+        if buffer.len() >= batch_size {
+            resolved += store_owners(database, node.id, mem::take(&mut buffer)).await?;
+
+            info!(%block_number, resolved, "storing owners batch");
+        }
+    }
+
+    if !buffer.is_empty() {
+        resolved += store_owners(database, node.id, buffer).await?;
+    }
+
+    Ok(resolved)
+}
+
+/// Store owners of the provided contracts within a single transaction.
+///
+/// Contracts that already have an owner are left as-is.
+///
+/// Returns the count of updated contracts.
+async fn store_owners(
+    database: &DatabaseConnection,
+    node_id: i64,
+    owners: Vec<(Vec<u8>, Vec<u8>)>,
+) -> Result<usize, DbErr> {
+    database
+        .transaction::<_, _, DbErr>(|txn| {
+            Box::pin(async move {
+                let mut updated = 0;
+
+                for (contract, owner) in owners {
+                    updated += contract::Entity::update_many()
+                        .col_expr(contract::Column::Owner, owner.into())
+                        .filter(contract::Column::NodeId.eq(node_id))
+                        .filter(contract::Column::Address.eq(contract))
+                        .filter(contract::Column::Owner.is_null())
+                        .exec(txn)
+                        .await?
+                        .rows_affected as usize;
+                }
+
+                Ok(updated)
+            })
+        })
+        .await
+        .into_raw_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use db::{code, contract, node, ActiveValue, DatabaseConnection, EntityTrait, QueryOrder};
+
+    use super::{backfill_from_mapping, parse_mapping, pending_contracts, BackfillOwnersError};
+    use crate::testing::create_database;
+
+    const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    const ALICE_HEX: &str = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    async fn create_test_env(db: &DatabaseConnection) -> i64 {
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert node");
+
+        code::Entity::insert(code::ActiveModel {
+            hash: ActiveValue::Set(vec![0; 32]),
+            code: ActiveValue::Set(vec![1, 2, 3]),
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert code");
+
+        let contracts = [(1, None), (2, None), (3, Some(vec![4; 32]))]
+            .into_iter()
+            .map(|(address, owner)| contract::ActiveModel {
+                code_hash: ActiveValue::Set(vec![0; 32]),
+                node_id: ActiveValue::Set(node.id),
+                address: ActiveValue::Set(vec![address; 32]),
+                owner: ActiveValue::Set(owner),
+                ..Default::default()
+            });
+
+        contract::Entity::insert_many(contracts)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert contracts");
+
+        node.id
+    }
+
+    #[test]
+    fn mapping_formats() {
+        let mapping = format!(r#"{{"0x{}": "{ALICE}"}}"#, hex::encode([1; 32]));
+
+        let owners = parse_mapping(&mapping).unwrap();
+
+        assert_eq!(owners, [(vec![1; 32], hex::decode(ALICE_HEX).unwrap())]);
+    }
+
+    #[test]
+    fn invalid_mapping() {
+        assert!(matches!(
+            parse_mapping(r#"["not", "an", "object"]"#),
+            Err(BackfillOwnersError::InvalidMapping(_))
+        ));
+        assert!(matches!(
+            parse_mapping(&format!(r#"{{"{ALICE}": "0x1234"}}"#)),
+            Err(BackfillOwnersError::InvalidAccountId(value)) if value == "0x1234"
+        ));
+    }
+
+    #[tokio::test]
+    async fn mapping_backfill() {
+        let db = create_database().await;
+
+        let node_id = create_test_env(&db).await;
+
+        let mut pending = pending_contracts(&db, node_id).await.unwrap();
+
+        assert_eq!(pending, HashSet::from([vec![1; 32], vec![2; 32]]));
+
+        let mapping = format!(
+            r#"{{"{}": "{ALICE}", "{}": "{ALICE}", "{}": "{ALICE}"}}"#,
+            hex::encode([1; 32]),
+            hex::encode([3; 32]),
+            hex::encode([5; 32]),
+        );
+
+        let resolved = backfill_from_mapping(
+            &db,
+            node_id,
+            &mut pending,
+            parse_mapping(&mapping).unwrap(),
+            1,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(resolved, 1);
+        assert_eq!(pending, HashSet::from([vec![2; 32]]));
+
+        let owners = contract::Entity::find()
+            .order_by_asc(contract::Column::Address)
+            .all(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|contract| contract.owner)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            owners,
+            [
+                Some(hex::decode(ALICE_HEX).unwrap()),
+                None,
+                Some(vec![4; 32])
+            ]
+        );
+    }
+}
@@ -1,19 +1,29 @@
-use std::{pin::pin, str::FromStr};
+use std::str::FromStr;
 
+use clap::Args;
 use common::rpc::{
     self,
-    sp_core::crypto::AccountId32,
-    substrate_api_client::{self, ac_primitives::Block, rpc::JsonrpseeClient, Api},
-    MetadataCache,
+    sp_core::{crypto::AccountId32, H256},
+    substrate_api_client::{
+        self,
+        ac_primitives::{PolkadotConfig, StorageKey},
+        rpc::JsonrpseeClient,
+        Api, GetChainInfo,
+    },
+    MetadataCache, MetadataCacheOptions, Paging,
 };
 use db::{
-    code, contract, node, sea_query::OnConflict, ActiveValue, DatabaseConnection, DbErr,
-    EntityTrait, TransactionErrorExt, TransactionTrait,
+    code, contract,
+    node::{self, InitializationPhase},
+    sea_query::OnConflict,
+    ActiveValue, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait,
+    QueryFilter, TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
 use futures_util::TryStreamExt;
+use tracing::info;
 
-use crate::utils::{extract_code_hash, extract_twox_account_id};
+use crate::utils::{extract_code_hash, extract_twox_account_id, process_in_batches};
 
 /// Errors thay may occur during initialization process.
 #[derive(Debug, Display, Error, From)]
@@ -28,6 +38,64 @@ pub enum InitializeError {
     /// Invalid payment contract account id was provided.
     #[display(fmt = "invalid account id for payment contract")]
     InvalidPaymentAddress,
+
+    /// Node to resume initialization for was not found.
+    #[display(fmt = "node not found")]
+    NodeNotFound,
+
+    /// Block used for an interrupted initialization is no longer available.
+    #[display(fmt = "initialization block not found")]
+    BlockNotFound,
+}
+
+/// Node information displayed by UIs.
+#[derive(Args)]
+pub struct DisplayMetadata {
+    /// Human-readable network name.
+    #[clap(long)]
+    pub display_name: Option<String>,
+
+    /// Block explorer URL template, with `{address}` placeholder replaced by an account address.
+    #[clap(long)]
+    pub explorer_url_template: Option<String>,
+
+    /// Native token symbol.
+    #[clap(long)]
+    pub token_symbol: Option<String>,
+
+    /// Count of decimal places of the native token.
+    #[clap(long)]
+    pub token_decimals: Option<u8>,
+
+    /// SS58 address format prefix.
+    #[clap(long, value_parser = clap::value_parser!(u16).range(0..16384))]
+    pub ss58_prefix: Option<u16>,
+}
+
+/// Initialization process options.
+#[derive(Args)]
+pub struct InitializeOptions {
+    /// Number of codes or contracts inserted within a single transaction.
+    #[clap(long, default_value_t = 500)]
+    pub batch_size: usize,
+
+    /// Resume an interrupted initialization process from the last stored batch.
+    #[clap(long)]
+    pub resume: bool,
+
+    /// Number of storage keys requested from the node within a single page.
+    ///
+    /// Page size is reduced automatically if the node rejects page requests.
+    #[clap(
+        long,
+        default_value_t = rpc::PAGE_SIZE,
+        value_parser = clap::value_parser!(u32).range(1..=rpc::MAX_PAGE_SIZE as i64)
+    )]
+    pub page_size: u32,
+
+    /// Number of storage values requested from the node concurrently.
+    #[clap(long, default_value_t = 1)]
+    pub concurrency: usize,
 }
 
 /// Initialize an RPC node from the provided data.
@@ -41,26 +109,195 @@ pub enum InitializeError {
 /// You have to run this command every time you add a new node to the database,
 /// since [`initialize`] function initializes node information too.
 ///
+/// Collected data is inserted in batches of `batch_size` items, with each batch
+/// recording the last processed storage key. If `resume` is set to `true`,
+/// an interrupted initialization process continues from the last recorded key
+/// using the same block as before.
+///
+/// Storage keys are requested in pages of `page_size` keys, while values of each page
+/// are requested with up to `concurrency` concurrent requests.
+///
+/// Provided [`DisplayMetadata`] is stored alongside with the node information,
+/// unless the interrupted initialization process is resumed.
+///
 /// No traversal of previous blocks is being done by this command.
 pub async fn initialize(
     database: DatabaseConnection,
     name: String,
     url: String,
     payment_address: Option<String>,
+    display: DisplayMetadata,
+    InitializeOptions {
+        batch_size,
+        resume,
+        page_size,
+        concurrency,
+    }: InitializeOptions,
+    cache_options: MetadataCacheOptions,
 ) -> Result<(), InitializeError> {
+    let paging = Paging {
+        page_size,
+        concurrency,
+    };
+
     let client = JsonrpseeClient::new(&url).map_err(substrate_api_client::Error::RpcClient)?;
     let api = Api::new(client).await?;
 
-    let mut metadata_cache = MetadataCache::new();
+    let mut metadata_cache = MetadataCache::with_options(cache_options);
 
-    let latest_block = rpc::block(&api, None)
-        .await?
-        .expect("at least one block is expected");
+    let node = if resume {
+        node::Entity::find()
+            .filter(node::Column::Name.eq(name))
+            .one(&database)
+            .await?
+            .ok_or(InitializeError::NodeNotFound)?
+    } else {
+        create_node(&database, &api, name, url, payment_address, display).await?
+    };
 
-    let block_hash = latest_block.hash();
+    let Some(mut phase) = node.initialization_phase else {
+        info!("node is already initialized");
+        return Ok(());
+    };
+
+    let block_hash = api
+        .get_block_hash(Some(node.confirmed_block as u32))
+        .await?
+        .ok_or(InitializeError::BlockNotFound)?;
 
     let metadata = metadata_cache.metadata(&api, block_hash).await?;
 
+    let mut start_key = node.initialization_key.map(StorageKey);
+
+    if phase == InitializationPhase::Codes {
+        let wasm_blobs =
+            rpc::pristine_code_root(&api, block_hash, start_key.take(), paging, metadata)
+                .await?
+                .err_into();
+
+        let processed = process_in_batches(wasm_blobs, batch_size, |batch, last_key, processed| {
+            let database = &database;
+
+            async move {
+                info!(processed, key = %hex::encode(&last_key.0), "storing codes batch");
+
+                database
+                    .transaction::<_, _, InitializeError>(|txn| {
+                        Box::pin(async move {
+                            code::Entity::insert_many(batch.into_iter().map(|(key, wasm)| {
+                                code::ActiveModel {
+                                    hash: ActiveValue::Set(extract_code_hash(key)),
+                                    code: ActiveValue::Set(wasm),
+                                }
+                            }))
+                            .on_conflict(
+                                OnConflict::column(code::Column::Hash)
+                                    .do_nothing()
+                                    .to_owned(),
+                            )
+                            .exec_without_returning(txn)
+                            .await?;
+
+                            update_progress(txn, node.id, InitializationPhase::Codes, last_key)
+                                .await?;
+
+                            Ok(())
+                        })
+                    })
+                    .await
+                    .into_raw_result()
+            }
+        })
+        .await?;
+
+        info!(processed, "all codes were processed");
+
+        phase = InitializationPhase::Contracts;
+
+        node::Entity::update_many()
+            .col_expr(node::Column::InitializationPhase, Some(phase).into())
+            .col_expr(node::Column::InitializationKey, Option::<Vec<u8>>::None.into())
+            .filter(node::Column::Id.eq(node.id))
+            .exec(&database)
+            .await?;
+    }
+
+    if phase == InitializationPhase::Contracts {
+        let contracts = rpc::contract_info_of_root(&api, block_hash, start_key, paging, metadata)
+            .await?
+            .err_into();
+
+        let processed = process_in_batches(contracts, batch_size, |batch, last_key, processed| {
+            let database = &database;
+
+            async move {
+                info!(processed, key = %hex::encode(&last_key.0), "storing contracts batch");
+
+                database
+                    .transaction::<_, _, InitializeError>(|txn| {
+                        Box::pin(async move {
+                            contract::Entity::insert_many(batch.into_iter().map(
+                                |(key, contract)| contract::ActiveModel {
+                                    code_hash: ActiveValue::Set(contract.code_hash.0.to_vec()),
+                                    node_id: ActiveValue::Set(node.id),
+                                    address: ActiveValue::Set(extract_twox_account_id(key)),
+                                    ..Default::default()
+                                },
+                            ))
+                            .on_conflict(
+                                OnConflict::columns([
+                                    contract::Column::NodeId,
+                                    contract::Column::Address,
+                                ])
+                                .do_nothing()
+                                .to_owned(),
+                            )
+                            .exec_without_returning(txn)
+                            .await?;
+
+                            update_progress(txn, node.id, InitializationPhase::Contracts, last_key)
+                                .await?;
+
+                            Ok(())
+                        })
+                    })
+                    .await
+                    .into_raw_result()
+            }
+        })
+        .await?;
+
+        info!(processed, "all contracts were processed");
+    }
+
+    node::Entity::update_many()
+        .col_expr(
+            node::Column::InitializationPhase,
+            Option::<InitializationPhase>::None.into(),
+        )
+        .col_expr(node::Column::InitializationKey, Option::<Vec<u8>>::None.into())
+        .filter(node::Column::Id.eq(node.id))
+        .exec(&database)
+        .await?;
+
+    info!("node initialization completed");
+
+    Ok(())
+}
+
+/// Create or reset a node using the latest block available.
+async fn create_node(
+    database: &DatabaseConnection,
+    api: &Api<PolkadotConfig, JsonrpseeClient>,
+    name: String,
+    url: String,
+    payment_address: Option<String>,
+    display: DisplayMetadata,
+) -> Result<node::Model, InitializeError> {
+    let latest_block = rpc::block(api, None)
+        .await?
+        .expect("at least one block is expected");
+
     let payment_address = payment_address
         .as_deref()
         .map(AccountId32::from_str)
@@ -68,14 +305,46 @@ pub async fn initialize(
         .map_err(|_| InitializeError::InvalidPaymentAddress)?
         .map(|addr| <[u8; 32]>::from(addr).to_vec());
 
-    let node = database
+    upsert_node(
+        database,
+        name,
+        url,
+        payment_address,
+        display,
+        latest_block.header.number,
+        latest_block.hash(),
+    )
+    .await
+}
+
+/// Insert a new node or reset an existing one with the same name,
+/// using the provided block as the confirmed one.
+async fn upsert_node(
+    database: &DatabaseConnection,
+    name: String,
+    url: String,
+    payment_address: Option<Vec<u8>>,
+    display: DisplayMetadata,
+    block_number: u32,
+    block_hash: H256,
+) -> Result<node::Model, InitializeError> {
+    database
         .transaction::<_, _, InitializeError>(|txn| {
             Box::pin(async move {
                 let node = node::Entity::insert(node::ActiveModel {
                     name: ActiveValue::Set(name),
                     url: ActiveValue::Set(url),
                     payment_contract: ActiveValue::Set(payment_address),
-                    confirmed_block: ActiveValue::Set(latest_block.header.number as i64),
+                    confirmed_block: ActiveValue::Set(block_number as i64),
+                    confirmed_block_hash: ActiveValue::Set(Some(block_hash.0.to_vec())),
+                    initialization_phase: ActiveValue::Set(Some(InitializationPhase::Codes)),
+                    initialization_key: ActiveValue::Set(None),
+                    display_name: ActiveValue::Set(display.display_name),
+                    explorer_url_template: ActiveValue::Set(display.explorer_url_template),
+                    token_symbol: ActiveValue::Set(display.token_symbol),
+                    token_decimals: ActiveValue::Set(display.token_decimals.map(i16::from)),
+                    // SS58 prefixes are limited to 14 bits.
+                    ss58_prefix: ActiveValue::Set(display.ss58_prefix.map(|prefix| prefix as i16)),
                     ..Default::default()
                 })
                 .on_conflict(
@@ -84,6 +353,14 @@ pub async fn initialize(
                             node::Column::Url,
                             node::Column::PaymentContract,
                             node::Column::ConfirmedBlock,
+                            node::Column::ConfirmedBlockHash,
+                            node::Column::InitializationPhase,
+                            node::Column::InitializationKey,
+                            node::Column::DisplayName,
+                            node::Column::ExplorerUrlTemplate,
+                            node::Column::TokenSymbol,
+                            node::Column::TokenDecimals,
+                            node::Column::Ss58Prefix,
                         ])
                         .to_owned(),
                 )
@@ -94,63 +371,84 @@ pub async fn initialize(
             })
         })
         .await
-        .into_raw_result()?;
-
-    let mut wasm_blobs = pin!(rpc::pristine_code_root(&api, block_hash, metadata).await?);
-
-    while let Some(chunk) = wasm_blobs.try_next().await? {
-        database
-            .transaction::<_, _, InitializeError>(|txn| {
-                Box::pin(async move {
-                    code::Entity::insert_many(chunk.into_iter().map(|(key, wasm)| {
-                        code::ActiveModel {
-                            hash: ActiveValue::Set(extract_code_hash(key)),
-                            code: ActiveValue::Set(wasm),
-                        }
-                    }))
-                    .on_conflict(
-                        OnConflict::column(code::Column::Hash)
-                            .do_nothing()
-                            .to_owned(),
-                    )
-                    .exec_without_returning(txn)
-                    .await?;
-
-                    Ok(())
-                })
-            })
-            .await
-            .into_raw_result()?;
-    }
+        .into_raw_result()
+}
 
-    let mut contracts = pin!(rpc::contract_info_of_root(&api, block_hash, metadata).await?);
-
-    while let Some(chunk) = contracts.try_next().await? {
-        database
-            .transaction::<_, _, InitializeError>(|txn| {
-                Box::pin(async move {
-                    contract::Entity::insert_many(chunk.into_iter().map(|(key, contract)| {
-                        contract::ActiveModel {
-                            code_hash: ActiveValue::Set(contract.code_hash.0.to_vec()),
-                            node_id: ActiveValue::Set(node.id),
-                            address: ActiveValue::Set(extract_twox_account_id(key)),
-                            ..Default::default()
-                        }
-                    }))
-                    .on_conflict(
-                        OnConflict::columns([contract::Column::NodeId, contract::Column::Address])
-                            .do_nothing()
-                            .to_owned(),
-                    )
-                    .exec_without_returning(txn)
-                    .await?;
-
-                    Ok(())
-                })
-            })
-            .await
-            .into_raw_result()?;
-    }
+/// Record the last processed storage key of the current initialization phase.
+async fn update_progress(
+    txn: &DatabaseTransaction,
+    node_id: i64,
+    phase: InitializationPhase,
+    last_key: StorageKey,
+) -> Result<(), DbErr> {
+    node::Entity::update_many()
+        .col_expr(node::Column::InitializationPhase, Some(phase).into())
+        .col_expr(node::Column::InitializationKey, Some(last_key.0).into())
+        .filter(node::Column::Id.eq(node_id))
+        .exec(txn)
+        .await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::future::ready;
+
+    use common::rpc::sp_core::H256;
+    use db::{node, DatabaseConnection};
+
+    use super::{upsert_node, DisplayMetadata};
+    use crate::{testing::create_database, utils::fork_rollback_target};
+
+    async fn initialize(
+        db: &DatabaseConnection,
+        block_number: u32,
+        block_hash: H256,
+    ) -> node::Model {
+        let display = DisplayMetadata {
+            display_name: None,
+            explorer_url_template: None,
+            token_symbol: None,
+            token_decimals: None,
+            ss58_prefix: None,
+        };
+
+        upsert_node(
+            db,
+            String::from("test"),
+            String::from("ws://localhost:9944"),
+            None,
+            display,
+            block_number,
+            block_hash,
+        )
+        .await
+        .expect("unable to initialize node")
+    }
+
+    #[tokio::test]
+    async fn reinitialized_node_follows_new_block() {
+        let db = create_database().await;
+
+        let first = initialize(&db, 10, H256([1; 32])).await;
+        let node = initialize(&db, 20, H256([2; 32])).await;
+
+        assert_eq!(node.id, first.id);
+        assert_eq!(node.confirmed_block, 20);
+        assert_eq!(node.confirmed_block_hash, Some(vec![2; 32]));
+
+        // The block following the new confirmed block is chained onto it without a rollback.
+        let target = fork_rollback_target(
+            node.confirmed_block,
+            node.confirmed_block_hash.as_deref(),
+            21,
+            &[2; 32],
+            16,
+            |_| ready(Err::<Vec<u8>, ()>(())),
+        )
+        .await;
+
+        assert_eq!(target, Ok(None));
+    }
+}
@@ -0,0 +1,32 @@
+use common::rpc::{sp_core::crypto::AccountId32, ss58_address};
+use db::{node, DatabaseConnection, DbErr, EntityTrait, QueryOrder};
+
+/// List all available nodes.
+///
+/// # Details
+///
+/// Each node is printed on a separate line, containing its identifier, name, URL,
+/// last confirmed block and an optional payment contract address,
+/// encoded using the node's SS58 prefix.
+pub async fn list(database: DatabaseConnection) -> Result<(), DbErr> {
+    let nodes = node::Entity::find()
+        .order_by_asc(node::Column::Id)
+        .all(&database)
+        .await?;
+
+    for node in nodes {
+        let payment_contract = node
+            .payment_contract
+            .as_deref()
+            .and_then(|address| AccountId32::try_from(address).ok())
+            .map(|address| ss58_address(&address, node.ss58_prefix.map(|prefix| prefix as u16)))
+            .unwrap_or_else(|| String::from("-"));
+
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            node.id, node.name, node.url, node.confirmed_block, payment_contract
+        );
+    }
+
+    Ok(())
+}
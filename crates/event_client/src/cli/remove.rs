@@ -0,0 +1,204 @@
+use db::{
+    contract, event, node, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QuerySelect, SelectExt, TransactionErrorExt, TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+
+/// Errors that may occur during node removal process.
+#[derive(Debug, Display, Error, From)]
+pub enum RemoveError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// The provided node name is incorrect.
+    #[display(fmt = "node not found")]
+    NodeNotFound,
+
+    /// Node has related contracts or events, but cascade removal was not requested.
+    #[display(fmt = "node has related contracts or events, use --cascade to remove them")]
+    DependentsExist,
+}
+
+/// Remove an existing node.
+///
+/// # Details
+///
+/// If `cascade` is set to `true`, all contracts and events related to the node
+/// are removed within the same transaction.
+///
+/// Otherwise, [`remove`] will refuse to remove a node that has any related
+/// contracts or events.
+pub async fn remove(
+    database: DatabaseConnection,
+    name: String,
+    cascade: bool,
+) -> Result<(), RemoveError> {
+    database
+        .transaction(|txn| {
+            Box::pin(async move {
+                let node = node::Entity::find()
+                    .filter(node::Column::Name.eq(name))
+                    .one(txn)
+                    .await?
+                    .ok_or(RemoveError::NodeNotFound)?;
+
+                if cascade {
+                    event::Entity::delete_many()
+                        .filter(event::Column::NodeId.eq(node.id))
+                        .exec(txn)
+                        .await?;
+
+                    contract::Entity::delete_many()
+                        .filter(contract::Column::NodeId.eq(node.id))
+                        .exec(txn)
+                        .await?;
+                } else {
+                    let contracts_exist = contract::Entity::find()
+                        .select_only()
+                        .filter(contract::Column::NodeId.eq(node.id))
+                        .exists(txn)
+                        .await?;
+
+                    let events_exist = event::Entity::find()
+                        .select_only()
+                        .filter(event::Column::NodeId.eq(node.id))
+                        .exists(txn)
+                        .await?;
+
+                    if contracts_exist || events_exist {
+                        return Err(RemoveError::DependentsExist);
+                    }
+                }
+
+                node::Entity::delete_by_id(node.id).exec(txn).await?;
+
+                Ok(())
+            })
+        })
+        .await
+        .into_raw_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{
+        code, contract, event, node, ActiveValue, DatabaseConnection, EntityTrait,
+        OffsetDateTime, PrimitiveDateTime, QuerySelect, SelectExt,
+    };
+
+    use super::{remove, RemoveError};
+    use crate::testing::create_database;
+
+    async fn create_test_env(db: &DatabaseConnection) {
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert node");
+
+        code::Entity::insert(code::ActiveModel {
+            hash: ActiveValue::Set(vec![0; 32]),
+            code: ActiveValue::Set(vec![1, 2, 3]),
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert code");
+
+        contract::Entity::insert(contract::ActiveModel {
+            code_hash: ActiveValue::Set(vec![0; 32]),
+            node_id: ActiveValue::Set(node.id),
+            address: ActiveValue::Set(vec![1; 32]),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert contract");
+
+        let now = OffsetDateTime::now_utc();
+
+        event::Entity::insert(event::ActiveModel {
+            node_id: ActiveValue::Set(node.id),
+            account: ActiveValue::Set(vec![1; 32]),
+            event_type: ActiveValue::Set(event::EventType::Instantiation),
+            body: ActiveValue::Set(event::EventBody::Instantiation {
+                deployer: String::from("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
+            }),
+            block_timestamp: ActiveValue::Set(PrimitiveDateTime::new(now.date(), now.time())),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert event");
+    }
+
+    #[tokio::test]
+    async fn refuses_without_cascade() {
+        let db = create_database().await;
+
+        create_test_env(&db).await;
+
+        let result = remove(db.clone(), String::from("test"), false).await;
+
+        assert!(matches!(result, Err(RemoveError::DependentsExist)));
+
+        assert!(node::Entity::find()
+            .select_only()
+            .exists(&db)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn removes_with_cascade() {
+        let db = create_database().await;
+
+        create_test_env(&db).await;
+
+        remove(db.clone(), String::from("test"), true)
+            .await
+            .expect("unable to remove node");
+
+        assert!(!node::Entity::find()
+            .select_only()
+            .exists(&db)
+            .await
+            .unwrap());
+        assert!(!contract::Entity::find()
+            .select_only()
+            .exists(&db)
+            .await
+            .unwrap());
+        assert!(!event::Entity::find()
+            .select_only()
+            .exists(&db)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn removes_without_dependents() {
+        let db = create_database().await;
+
+        node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("empty")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert node");
+
+        remove(db.clone(), String::from("empty"), false)
+            .await
+            .expect("unable to remove node");
+
+        let result = remove(db, String::from("empty"), false).await;
+
+        assert!(matches!(result, Err(RemoveError::NodeNotFound)));
+    }
+}
@@ -0,0 +1,56 @@
+use db::{
+    node, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, TransactionErrorExt,
+    TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+
+/// Errors that may occur during node rename process.
+#[derive(Debug, Display, Error, From)]
+pub enum RenameError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// The provided node name is incorrect.
+    #[display(fmt = "node not found")]
+    NodeNotFound,
+
+    /// Node with the new name already exists.
+    #[display(fmt = "node with the provided name already exists")]
+    NameTaken,
+}
+
+/// Rename an existing node.
+pub async fn rename(
+    database: DatabaseConnection,
+    old_name: String,
+    new_name: String,
+) -> Result<(), RenameError> {
+    database
+        .transaction(|txn| {
+            Box::pin(async move {
+                let taken = node::Entity::find()
+                    .filter(node::Column::Name.eq(&new_name))
+                    .one(txn)
+                    .await?
+                    .is_some();
+
+                if taken {
+                    return Err(RenameError::NameTaken);
+                }
+
+                let result = node::Entity::update_many()
+                    .filter(node::Column::Name.eq(old_name))
+                    .col_expr(node::Column::Name, new_name.into())
+                    .exec(txn)
+                    .await?;
+
+                if result.rows_affected == 0 {
+                    return Err(RenameError::NodeNotFound);
+                }
+
+                Ok(())
+            })
+        })
+        .await
+        .into_raw_result()
+}
@@ -0,0 +1,193 @@
+use common::rpc::{
+    self,
+    sp_core::H256,
+    substrate_api_client::{self, ac_primitives::PolkadotConfig, rpc::JsonrpseeClient, Api},
+    MetadataCache, MetadataCacheOptions,
+};
+use db::{
+    node, skipped_block, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use tracing::{info, warn};
+
+use super::watch::{process_block, WatchError};
+
+/// Reprocess blocks that were previously skipped by the watcher.
+///
+/// # Details
+///
+/// Each skipped block of the provided node is processed again without
+/// affecting the node's confirmed block counter. Events of such blocks are stored as usual,
+/// while contract state changes are not applied to contracts with events
+/// discovered in newer blocks.
+///
+/// Successfully processed blocks are removed from the skipped blocks list,
+/// while failed ones are kept with an updated error message.
+pub async fn reprocess_skipped(
+    database: DatabaseConnection,
+    name: String,
+    cache_options: MetadataCacheOptions,
+) -> Result<(), WatchError> {
+    let node = node::Entity::find()
+        .filter(node::Column::Name.eq(&name))
+        .one(&database)
+        .await?
+        .ok_or(WatchError::NodeNotFound)?;
+
+    let client = JsonrpseeClient::new(&node.url).map_err(substrate_api_client::Error::RpcClient)?;
+    let api = Api::<PolkadotConfig, _>::new(client).await?;
+
+    let mut metadata_cache = MetadataCache::with_options(cache_options);
+
+    let skipped_blocks = skipped_block::Entity::find()
+        .filter(skipped_block::Column::NodeId.eq(node.id))
+        .order_by_asc(skipped_block::Column::BlockNumber)
+        .all(&database)
+        .await?;
+
+    let mut resolved = 0;
+    let mut unresolved = 0;
+
+    for skipped in skipped_blocks {
+        let block_hash = H256::from_slice(&skipped.block_hash);
+
+        let Some(block) = rpc::block(&api, Some(block_hash)).await? else {
+            warn!(block_number = %skipped.block_number, "skipped block is no longer available");
+            unresolved += 1;
+            continue;
+        };
+
+        let metadata = metadata_cache.metadata(&api, block_hash).await?;
+
+        let result = process_block(
+            node.clone(),
+            &database,
+            &api,
+            block.header(),
+            metadata,
+            false,
+        )
+        .await
+        .map(|_| ());
+
+        if record_reprocess_result(&database, skipped, result).await? {
+            resolved += 1;
+        } else {
+            unresolved += 1;
+        }
+    }
+
+    info!(%resolved, %unresolved, "skipped blocks reprocessed");
+
+    Ok(())
+}
+
+/// Record the result of a skipped block reprocessing attempt.
+///
+/// Returns `true` if the block was processed successfully.
+async fn record_reprocess_result(
+    database: &DatabaseConnection,
+    skipped: skipped_block::Model,
+    result: Result<(), WatchError>,
+) -> Result<bool, DbErr> {
+    match result {
+        Ok(()) => {
+            skipped_block::Entity::delete_by_id(skipped.id)
+                .exec(database)
+                .await?;
+
+            Ok(true)
+        }
+        Err(err) => {
+            warn!(block_number = %skipped.block_number, %err, "unable to reprocess skipped block");
+
+            skipped_block::Entity::update_many()
+                .col_expr(skipped_block::Column::Error, err.to_string().into())
+                .filter(skipped_block::Column::Id.eq(skipped.id))
+                .exec(database)
+                .await?;
+
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rpc::sp_core::H256;
+    use db::{node, skipped_block, ActiveValue, DatabaseConnection, EntityTrait};
+
+    use super::record_reprocess_result;
+    use crate::{
+        cli::watch::{skip_block, WatchError},
+        testing::create_database,
+    };
+
+    async fn create_test_env(db: &DatabaseConnection) -> skipped_block::Model {
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(9),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert node");
+
+        let node = skip_block(node, db, 10, H256([1; 32]), String::from("decode error"))
+            .await
+            .expect("unable to skip block");
+
+        assert_eq!(node.confirmed_block, 10);
+        assert_eq!(node.confirmed_block_hash, Some(vec![1; 32]));
+        assert_eq!(node.last_error.as_deref(), Some("decode error"));
+
+        skipped_block::Entity::find()
+            .one(db)
+            .await
+            .unwrap()
+            .expect("skipped block was not recorded")
+    }
+
+    #[tokio::test]
+    async fn successful_reprocess_removes_block() {
+        let db = create_database().await;
+
+        let skipped = create_test_env(&db).await;
+
+        assert_eq!(skipped.block_number, 10);
+        assert_eq!(skipped.error, "decode error");
+
+        let resolved = record_reprocess_result(&db, skipped, Ok(()))
+            .await
+            .unwrap();
+
+        assert!(resolved);
+        assert!(skipped_block::Entity::find()
+            .one(&db)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_reprocess_keeps_block() {
+        let db = create_database().await;
+
+        let skipped = create_test_env(&db).await;
+
+        let resolved = record_reprocess_result(&db, skipped, Err(WatchError::NodeNotFound))
+            .await
+            .unwrap();
+
+        assert!(!resolved);
+
+        let skipped = skipped_block::Entity::find()
+            .one(&db)
+            .await
+            .unwrap()
+            .expect("skipped block was removed");
+
+        assert_eq!(skipped.error, "node not found");
+    }
+}
@@ -7,7 +7,7 @@ use common::rpc::{
         rpc::{JsonrpseeClient, Request},
         Api, Error,
     },
-    Instantiated, MetadataCache,
+    Instantiated, MetadataCache, MetadataCacheOptions,
 };
 use db::{
     contract, node, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
@@ -46,7 +46,11 @@ pub enum TraverseError {
 ///
 /// If necessary, you may set up a separate service for batch block analysis
 /// and fill the database with models found in [`db`] crate.
-pub async fn traverse(database: DatabaseConnection, name: String) -> Result<(), TraverseError> {
+pub async fn traverse(
+    database: DatabaseConnection,
+    name: String,
+    cache_options: MetadataCacheOptions,
+) -> Result<(), TraverseError> {
     let node = node::Entity::find()
         .filter(node::Column::Name.eq(name))
         .one(&database)
@@ -56,11 +60,11 @@ pub async fn traverse(database: DatabaseConnection, name: String) -> Result<(),
     let client = JsonrpseeClient::new(&node.url).map_err(substrate_api_client::Error::RpcClient)?;
     let api = Api::new(client).await?;
 
-    let stream = block_mapping_stream(0..=node.confirmed_block as u32, &api);
+    let stream = block_mapping_stream(0..=node.confirmed_block as u32, 1, &api);
 
     pin_mut!(stream);
 
-    let mut metadata_cache = MetadataCache::new();
+    let mut metadata_cache = MetadataCache::with_options(cache_options);
 
     while let Some((_, block_hash)) = stream.try_next().await? {
         if let Ok(block_data) = parse_block(&api, block_hash, &mut metadata_cache).await {
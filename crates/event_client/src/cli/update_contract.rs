@@ -1,21 +1,38 @@
-use std::str::FromStr;
-
-use common::rpc::sp_core::crypto::AccountId32;
+use common::rpc::{
+    self,
+    sp_core::crypto::AccountId32,
+    substrate_api_client::{self, rpc::JsonrpseeClient, Api, GetChainInfo},
+    ContractInfo, MetadataCache, MetadataCacheOptions,
+};
 use db::{
     node, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, TransactionErrorExt,
     TransactionTrait,
 };
 use derive_more::{Display, Error, From};
 
+use crate::utils;
+
 /// Errors that may occur during payment contract address update process.
 #[derive(Debug, Display, Error, From)]
 pub enum UpdateContractError {
     /// Database-related error.
     DatabaseError(DbErr),
 
+    /// Substrate RPC-related error.
+    #[display(fmt = "rpc error: {:?}", _0)]
+    RpcError(#[error(ignore)] substrate_api_client::Error),
+
     /// Provided account id cannot be parsed.
     #[display(fmt = "invalid account id for payment contract")]
     InvalidPaymentAddress,
+
+    /// The provided node name is incorrect.
+    #[display(fmt = "node not found")]
+    NodeNotFound,
+
+    /// No contract is deployed at the provided address.
+    #[display(fmt = "no contract found at the provided address, use --force to update anyway")]
+    ContractNotFound,
 }
 
 /// Update payment contract address.
@@ -25,24 +42,63 @@ pub enum UpdateContractError {
 /// Using [`update_contract`] you can update an account id of a payment contract
 /// associated with the provided node.
 ///
+/// Payment contract address can be provided either in SS58 or in hex format.
+///
+/// Before updating the address, the node is queried to verify that the contract
+/// exists at the provided address. This check can be skipped with `force` set to `true`,
+/// in which case the node is not contacted at all.
+///
 /// Consult self-hosted documentation for more information on supported smart contract ABI.
 pub async fn update_contract(
     database: DatabaseConnection,
     name: String,
     payment_address: Option<String>,
+    force: bool,
+    cache_options: MetadataCacheOptions,
 ) -> Result<(), UpdateContractError> {
     let payment_address = payment_address
         .as_deref()
-        .map(AccountId32::from_str)
-        .transpose()
-        .map_err(|_| UpdateContractError::InvalidPaymentAddress)?
-        .map(|addr| <[u8; 32]>::from(addr).to_vec());
+        .map(parse_account_id)
+        .transpose()?;
+
+    let node = node::Entity::find()
+        .filter(node::Column::Name.eq(&name))
+        .one(&database)
+        .await?
+        .ok_or(UpdateContractError::NodeNotFound)?;
+
+    match &payment_address {
+        Some(_) if force => println!("Contract verification skipped"),
+        Some(address) => {
+            let client =
+                JsonrpseeClient::new(&node.url).map_err(substrate_api_client::Error::RpcClient)?;
+            let api = Api::new(client).await?;
+
+            let block_hash = api
+                .get_block_hash(None)
+                .await?
+                .expect("at least one block is expected");
+
+            let mut metadata_cache = MetadataCache::with_options(cache_options);
+            let metadata = metadata_cache.metadata(&api, block_hash).await?;
+
+            let info = rpc::contract_info_of(&api, block_hash, address, metadata).await?;
+
+            println!(
+                "Payment contract code hash: 0x{}",
+                verify_contract_info(info)?
+            );
+        }
+        None => {}
+    }
+
+    let payment_address = payment_address.map(|addr| <[u8; 32]>::from(addr).to_vec());
 
     database
         .transaction(|txn| {
             Box::pin(async move {
                 node::Entity::update_many()
-                    .filter(node::Column::Name.eq(name))
+                    .filter(node::Column::Id.eq(node.id))
                     .col_expr(node::Column::PaymentContract, payment_address.into())
                     .exec(txn)
                     .await?;
@@ -53,3 +109,136 @@ pub async fn update_contract(
         .await
         .into_raw_result()
 }
+
+/// Parse account id in either SS58 or hex format.
+fn parse_account_id(value: &str) -> Result<AccountId32, UpdateContractError> {
+    utils::parse_account_id(value).ok_or(UpdateContractError::InvalidPaymentAddress)
+}
+
+/// Verify that contract information was found on-chain.
+///
+/// Returns hex-encoded code hash of the found contract.
+fn verify_contract_info(info: Option<ContractInfo>) -> Result<String, UpdateContractError> {
+    info.map(|info| hex::encode(info.code_hash))
+        .ok_or(UpdateContractError::ContractNotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rpc::{
+        sp_core::{crypto::Ss58Codec, H256},
+        ContractInfo, MetadataCacheOptions,
+    };
+    use db::{node, ActiveValue, DatabaseConnection, EntityTrait};
+
+    use super::{parse_account_id, update_contract, verify_contract_info, UpdateContractError};
+    use crate::testing::create_database;
+
+    const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    const ALICE_HEX: &str = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    #[test]
+    fn parse_ss58_and_hex() {
+        let ss58 = parse_account_id(ALICE).unwrap();
+        let hex = parse_account_id(ALICE_HEX).unwrap();
+        let prefixed_hex = parse_account_id(&format!("0x{ALICE_HEX}")).unwrap();
+
+        assert_eq!(ss58.to_ss58check(), ALICE);
+        assert_eq!(ss58, hex);
+        assert_eq!(ss58, prefixed_hex);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(matches!(
+            parse_account_id("0x1234"),
+            Err(UpdateContractError::InvalidPaymentAddress)
+        ));
+        assert!(matches!(
+            parse_account_id("not an address"),
+            Err(UpdateContractError::InvalidPaymentAddress)
+        ));
+    }
+
+    #[test]
+    fn contract_present() {
+        let info = ContractInfo {
+            code_hash: H256([1; 32]),
+        };
+
+        assert_eq!(
+            verify_contract_info(Some(info)).unwrap(),
+            hex::encode([1; 32])
+        );
+    }
+
+    #[test]
+    fn contract_absent() {
+        assert!(matches!(
+            verify_contract_info(None),
+            Err(UpdateContractError::ContractNotFound)
+        ));
+    }
+
+    /// Insert a node, that points to an address without a running RPC node.
+    async fn create_unreachable_node(db: &DatabaseConnection) -> node::Model {
+        node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://127.0.0.1:1")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert node")
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn forced_update_without_node() {
+        let db = create_database().await;
+        let node = create_unreachable_node(&db).await;
+
+        update_contract(
+            db.clone(),
+            String::from("test"),
+            Some(String::from(ALICE)),
+            true,
+            MetadataCacheOptions::default(),
+        )
+        .await
+        .expect("forced update should not contact the node");
+
+        let node = node::Entity::find_by_id(node.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .expect("node was removed");
+
+        assert_eq!(node.payment_contract, Some(hex::decode(ALICE_HEX).unwrap()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn update_without_node() {
+        let db = create_database().await;
+        let node = create_unreachable_node(&db).await;
+
+        let result = update_contract(
+            db.clone(),
+            String::from("test"),
+            Some(String::from(ALICE)),
+            false,
+            MetadataCacheOptions::default(),
+        )
+        .await;
+
+        assert!(matches!(result, Err(UpdateContractError::RpcError(_))));
+
+        let node = node::Entity::find_by_id(node.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .expect("node was removed");
+
+        assert_eq!(node.payment_contract, None);
+    }
+}
@@ -1,8 +1,9 @@
-use std::{future::ready, iter};
+use std::{collections::HashSet, future::ready, iter, thread, time::Duration};
 
+use clap::Args;
 use common::rpc::{
     self,
-    sp_core::ByteArray,
+    sp_core::{crypto::AccountId32, ByteArray, H256},
     substrate_api_client::{
         self,
         ac_node_api::Metadata,
@@ -10,19 +11,38 @@ use common::rpc::{
         rpc::{HandleSubscription, JsonrpseeClient, Request},
         Api, GetChainInfo, SubscribeChain,
     },
-    CodeStored, ContractCodeUpdated, Instantiated, MetadataCache, Terminated,
+    CodeStored, ContractCodeUpdated, Instantiated, MetadataCache, MetadataCacheOptions,
+    Terminated,
 };
 use db::{
-    code, contract, event, node, sea_query::OnConflict, ActiveModelTrait, ActiveValue, ColumnTrait,
-    DatabaseConnection, DbErr, EntityTrait, OffsetDateTime, PrimitiveDateTime, QueryFilter,
+    code, contract, event, node, skipped_block,
+    sea_orm::{sqlx, RuntimeErr},
+    sea_query::{Expr, OnConflict},
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr,
+    EntityTrait, OffsetDateTime, PrimitiveDateTime, QueryFilter, QuerySelect,
     TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
 use futures_util::{pin_mut, stream, TryStreamExt};
 use itertools::Itertools;
-use tracing::{debug, info};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
 
-use crate::utils::block_mapping_stream;
+use crate::{
+    metrics::metrics,
+    shutdown::Shutdown,
+    utils::{
+        block_mapping_stream, catch_up_range, fork_rollback_target, retry_with_backoff, Backoff,
+        CatchUpProgress, ForkError,
+    },
+};
+
+/// Backoff configuration used to retry block processing.
+const PROCESS_BLOCK_BACKOFF: Backoff = Backoff {
+    max_attempts: 8,
+    initial_delay: Duration::from_millis(500),
+    max_delay: Duration::from_secs(60),
+};
 
 /// Errors that may occur during the watch process.
 #[derive(Debug, Display, Error, From)]
@@ -40,6 +60,97 @@ pub enum WatchError {
     /// The provided node name is incorrect.
     #[display(fmt = "node not found")]
     NodeNotFound,
+
+    /// Count of missed blocks exceeds the configured catch-up limit.
+    #[display(
+        fmt = "{} blocks are missing, which exceeds the catch-up limit of {} blocks. \
+            Either increase the limit with --max-catchup-blocks, or initialize the node again \
+            and use the traverse command to discover older events",
+        missed_blocks,
+        limit
+    )]
+    CatchUpLimitExceeded {
+        /// Count of blocks between the confirmed block and the latest block.
+        missed_blocks: u32,
+
+        /// Configured catch-up limit.
+        limit: u32,
+    },
+
+    /// Chain fork is deeper than the configured rollback depth.
+    #[display(
+        fmt = "block {} forks off more than {} blocks behind the confirmed block. \
+            Either increase the limit with --rollback-depth, or initialize the node again",
+        block_number,
+        depth
+    )]
+    ForkTooDeep {
+        /// Number of the block, that diverges from the confirmed chain.
+        block_number: u32,
+
+        /// Configured rollback depth.
+        depth: u32,
+    },
+
+    /// Block header required to find the fork's common ancestor is not available.
+    #[display(fmt = "block header not found")]
+    HeaderNotFound,
+}
+
+impl WatchError {
+    /// Determine if the error is transient and the failed operation can be retried.
+    ///
+    /// Only connection issues are considered retryable. Errors reported by the database itself,
+    /// such as constraint violations, stop the watcher right away.
+    fn is_retryable(&self) -> bool {
+        match self {
+            WatchError::RpcError(substrate_api_client::Error::RpcClient(_))
+            | WatchError::DatabaseError(DbErr::Conn(_) | DbErr::ConnectionAcquire) => true,
+            WatchError::DatabaseError(
+                DbErr::Exec(RuntimeErr::SqlxError(err)) | DbErr::Query(RuntimeErr::SqlxError(err)),
+            ) => matches!(
+                err,
+                sqlx::Error::Io(_)
+                    | sqlx::Error::PoolTimedOut
+                    | sqlx::Error::PoolClosed
+                    | sqlx::Error::WorkerCrashed
+            ),
+            _ => false,
+        }
+    }
+
+    /// Determine if the block that caused the error can be skipped and recorded
+    /// for later reprocessing.
+    ///
+    /// Only block decoding failures are skippable. Connection and database issues are not,
+    /// since they would cause every following block to be skipped and its events to be lost.
+    pub(crate) fn is_skippable(&self) -> bool {
+        matches!(
+            self,
+            WatchError::JsonError(_)
+                | WatchError::RpcError(
+                    substrate_api_client::Error::NodeApi(_) | substrate_api_client::Error::Codec(_)
+                )
+        )
+    }
+}
+
+/// Watch process options.
+#[derive(Args, Clone, Copy)]
+pub struct WatchOptions {
+    /// Maximum number of blocks to roll back when a chain fork is detected.
+    #[clap(long, default_value_t = 16)]
+    pub rollback_depth: u32,
+
+    /// Maximum number of missed blocks to catch up to on startup.
+    ///
+    /// If the confirmed block of a node is older than that, the watcher exits with an error.
+    #[clap(long, default_value_t = 1_000_000)]
+    pub max_catchup_blocks: u32,
+
+    /// Number of block hashes requested from the node concurrently during catch-up.
+    #[clap(long, default_value_t = 16)]
+    pub hash_batch_size: usize,
 }
 
 /// Watch an RPC node for new smart contract-related events.
@@ -51,20 +162,52 @@ pub enum WatchError {
 ///
 /// If catch-up process is required, [`watch`] function will stream
 /// blocks starting from the confirmed block and up to the latest block.
+/// Catch-up is refused if the count of missed blocks exceeds the `max_catchup_blocks` option.
 ///
 /// As soon as all missed blocks are processed, [`watch`] will start listening
 /// and processing only new blocks from now on.
-pub async fn watch(database: DatabaseConnection, name: String) -> Result<(), WatchError> {
-    let mut node = node::Entity::find()
+///
+/// Each processed block is verified to be chained onto the previously confirmed block.
+/// If a fork is detected, events and contracts discovered after the common ancestor
+/// of both chains are removed and re-processed from the canonical chain.
+/// Forks with a common ancestor more than `rollback_depth` blocks behind
+/// the confirmed block stop the watcher with an error.
+///
+/// Receiving a SIGTERM or Ctrl-C will stop the watcher after the current block is processed,
+/// or immediately if the watcher is waiting for new blocks.
+pub async fn watch(
+    database: DatabaseConnection,
+    name: String,
+    options: WatchOptions,
+    cache_options: MetadataCacheOptions,
+) -> Result<(), WatchError> {
+    let node = node::Entity::find()
         .filter(node::Column::Name.eq(&name))
         .one(&database)
         .await?
         .ok_or(WatchError::NodeNotFound)?;
 
+    let shutdown = Shutdown::default();
+    shutdown.listen();
+
+    watch_node(&database, node, options, cache_options, &shutdown).await
+}
+
+/// Watch the provided node until the shutdown signal is triggered.
+///
+/// Each call creates its own RPC client and [`MetadataCache`],
+/// which allows multiple nodes to be watched concurrently.
+pub(crate) async fn watch_node(
+    database: &DatabaseConnection,
+    mut node: node::Model,
+    options: WatchOptions,
+    cache_options: MetadataCacheOptions,
+    shutdown: &Shutdown,
+) -> Result<(), WatchError> {
     let client = JsonrpseeClient::new(&node.url).map_err(substrate_api_client::Error::RpcClient)?;
     let api = Api::<PolkadotConfig, _>::new(client).await?;
 
-    let mut metadata_cache = MetadataCache::new();
+    let mut metadata_cache = MetadataCache::with_options(cache_options);
 
     let mut subscription = api.subscribe_finalized_heads()?;
 
@@ -74,51 +217,380 @@ pub async fn watch(database: DatabaseConnection, name: String) -> Result<(), Wat
         .get_block(None)
         .await?
         .expect("at least one block is expected");
-    let stream = block_mapping_stream(
-        (node.confirmed_block + 1) as u32..=latest.header.number,
-        &api,
+    metrics().record_chain_head(&node.name, latest.header.number);
+
+    let range = catch_up_range(
+        node.confirmed_block,
+        latest.header.number,
+        options.max_catchup_blocks,
     )
-    .try_filter_map(|(_, hash)| rpc::block(&api, Some(hash)));
+    .map_err(|missed_blocks| {
+        error!(
+            %missed_blocks,
+            limit = %options.max_catchup_blocks,
+            "too many missed blocks to catch-up to"
+        );
+
+        WatchError::CatchUpLimitExceeded {
+            missed_blocks,
+            limit: options.max_catchup_blocks,
+        }
+    })?;
+
+    let total = range.clone().count() as u32;
+    let mut progress = CatchUpProgress::new(total);
+
+    let stream = block_mapping_stream(range, options.hash_batch_size, &api)
+        .try_filter_map(|(_, hash)| rpc::block(&api, Some(hash)));
 
     pin_mut!(stream);
 
     while let Some(block) = stream.try_next().await? {
+        if shutdown.is_triggered() {
+            info!("stopping watcher");
+            return Ok(());
+        }
+
         debug!(block_number = %block.header().number(), "found a block to catch-up to");
-        let metadata = metadata_cache.metadata(&api, block.hash()).await?;
-        node = process_block(node, &database, &api, block.header(), metadata).await?;
+        node = handle_block(
+            node,
+            database,
+            &api,
+            &mut metadata_cache,
+            block.header(),
+            options,
+        )
+        .await?;
+
+        if let Some((processed, eta)) = progress.record() {
+            info!(%processed, %total, eta_secs = %eta.as_secs(), "catch-up progress");
+        }
     }
 
     // Proceed with the subscription, since an attempt to traverse missed blocks was already made.
     info!("processing new blocks from now on");
 
     let confirmed_block = node.confirmed_block as u32;
-    let mut subscription_iter =
-        iter::from_fn(|| subscription.next()).filter_ok(|header| header.number() > confirmed_block);
-
-    while let Some(header) = subscription_iter
-        .next()
-        .transpose()
-        .map_err(substrate_api_client::Error::RpcClient)?
-    {
+    let (sender, mut receiver) = mpsc::channel(1);
+
+    // Subscription iterator blocks the current thread, thus it is polled on a dedicated thread,
+    // which allows the wait for new blocks to be raced against the shutdown signal.
+    // The thread exits as soon as a new header can't be delivered anymore.
+    thread::spawn(move || {
+        let headers = iter::from_fn(|| subscription.next())
+            .filter_ok(|header| header.number() > confirmed_block);
+
+        for header in headers {
+            if sender.blocking_send(header).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let header = tokio::select! {
+            biased;
+
+            _ = shutdown.triggered() => {
+                info!("stopping watcher");
+                break;
+            }
+            header = receiver.recv() => header,
+        };
+
+        let Some(header) = header
+            .transpose()
+            .map_err(substrate_api_client::Error::RpcClient)?
+        else {
+            break;
+        };
+
         debug!(block_number = %header.number(), "found new block");
-        let metadata = metadata_cache.metadata(&api, header.hash()).await?;
-        node = process_block(node, &database, &api, &header, metadata).await?;
+        metrics().record_chain_head(&node.name, header.number());
+
+        node = handle_block(node, database, &api, &mut metadata_cache, &header, options).await?;
+    }
+
+    Ok(())
+}
+
+/// Handle a newly discovered block, rolling back the node state if the block
+/// diverges from the previously confirmed block.
+async fn handle_block<C: Request>(
+    mut node: node::Model,
+    database: &DatabaseConnection,
+    api: &Api<PolkadotConfig, C>,
+    metadata_cache: &mut MetadataCache,
+    block_header: &<PolkadotConfig as Config>::Header,
+    options: WatchOptions,
+) -> Result<node::Model, WatchError> {
+    let block_number = block_header.number();
+
+    let target = fork_rollback_target(
+        node.confirmed_block,
+        node.confirmed_block_hash.as_deref(),
+        block_number,
+        block_header.parent_hash.as_ref(),
+        options.rollback_depth,
+        |hash| async move {
+            api.get_header(Some(H256::from_slice(&hash)))
+                .await?
+                .map(|header| header.parent_hash.0.to_vec())
+                .ok_or(WatchError::HeaderNotFound)
+        },
+    )
+    .await
+    .map_err(|err| match err {
+        ForkError::TooDeep => {
+            error!(
+                %block_number,
+                depth = %options.rollback_depth,
+                "chain fork is deeper than the rollback depth"
+            );
+
+            WatchError::ForkTooDeep {
+                block_number,
+                depth: options.rollback_depth,
+            }
+        }
+        ForkError::Lookup(err) => err,
+    })?;
+
+    if let Some(target) = target {
+        warn!(%block_number, rollback_to = %target, "chain fork detected, rolling back");
+
+        node = rollback(node, database, api, target).await?;
+
+        let stream = block_mapping_stream((target + 1)..block_number, options.hash_batch_size, api)
+            .try_filter_map(|(_, hash)| rpc::block(api, Some(hash)));
+
+        pin_mut!(stream);
+
+        while let Some(block) = stream.try_next().await? {
+            debug!(block_number = %block.header().number(), "re-processing canonical block");
+            let metadata = metadata_cache.metadata(api, block.hash()).await?;
+            node = process_block_with_retry(node, database, api, block.header(), metadata).await?;
+        }
+    }
+
+    let metadata = metadata_cache.metadata(api, block_header.hash()).await?;
+    process_block_with_retry(node, database, api, block_header, metadata).await
+}
+
+/// Roll back events, contracts and skipped blocks discovered after the provided block number.
+///
+/// Code hash updates and terminations cannot be reverted, since previous contract
+/// state is not stored, thus only instantiated contracts and recorded events are removed.
+async fn rollback<C: Request>(
+    node: node::Model,
+    database: &DatabaseConnection,
+    api: &Api<PolkadotConfig, C>,
+    target: u32,
+) -> Result<node::Model, WatchError> {
+    let target_hash = api.get_block_hash(Some(target)).await?;
+
+    let mut active_node: node::ActiveModel = node.clone().into();
+
+    database
+        .transaction(|txn| {
+            Box::pin(async move {
+                let events = event::Entity::delete_many()
+                    .filter(event::Column::NodeId.eq(node.id))
+                    .filter(event::Column::BlockNumber.gt(target as i64))
+                    .exec(txn)
+                    .await?;
+
+                let contracts = contract::Entity::delete_many()
+                    .filter(contract::Column::NodeId.eq(node.id))
+                    .filter(contract::Column::BlockNumber.gt(target as i64))
+                    .exec(txn)
+                    .await?;
+
+                // Skipped blocks of the abandoned fork are re-processed from the canonical chain.
+                let skipped_blocks = skipped_block::Entity::delete_many()
+                    .filter(skipped_block::Column::NodeId.eq(node.id))
+                    .filter(skipped_block::Column::BlockNumber.gt(target as i64))
+                    .exec(txn)
+                    .await?;
+
+                warn!(
+                    events = %events.rows_affected,
+                    contracts = %contracts.rows_affected,
+                    skipped_blocks = %skipped_blocks.rows_affected,
+                    "rolled back node state"
+                );
+
+                active_node.confirmed_block = ActiveValue::Set(target as i64);
+                active_node.confirmed_block_hash =
+                    ActiveValue::Set(target_hash.map(|hash| hash.0.to_vec()));
+
+                Ok(active_node.update(txn).await?)
+            })
+        })
+        .await
+        .into_raw_result()
+}
+
+/// Process one block, retrying transient failures with an exponential backoff.
+///
+/// If the block could not be processed, the error is recorded
+/// in the node's `last_error` column.
+///
+/// Blocks that still fail with a skippable error after all retry attempts are recorded
+/// in the `skipped_blocks` table and are considered confirmed, so that the watcher can proceed
+/// with the following blocks. Other errors are returned to the caller.
+async fn process_block_with_retry<C: Request>(
+    node: node::Model,
+    database: &DatabaseConnection,
+    api: &Api<PolkadotConfig, C>,
+    block_header: &<PolkadotConfig as Config>::Header,
+    metadata: &Metadata,
+) -> Result<node::Model, WatchError> {
+    let block_number = block_header.number();
+    let node_id = node.id;
+
+    let result = retry_with_backoff(
+        &PROCESS_BLOCK_BACKOFF,
+        || process_block(node.clone(), database, api, block_header, metadata, true),
+        |err| err.is_retryable() || err.is_skippable(),
+        |attempt, err, delay| {
+            warn!(%block_number, %attempt, ?delay, %err, "unable to process block, retrying");
+        },
+    )
+    .await;
+
+    match result {
+        Err(err) if err.is_skippable() => {
+            error!(%block_number, %err, "unable to process block, skipping");
+
+            skip_block(
+                node,
+                database,
+                block_number,
+                block_header.hash(),
+                err.to_string(),
+            )
+            .await
+        }
+        Err(err) => {
+            error!(%block_number, %err, "unable to process block");
+
+            if let Err(record_err) = record_node_error(database, node_id, err.to_string()).await {
+                warn!(%record_err, "unable to record node error");
+            }
+
+            Err(err)
+        }
+        ok => ok,
     }
+}
+
+/// Record the error that caused an event client to stop processing blocks of the provided node.
+async fn record_node_error(
+    database: &DatabaseConnection,
+    node_id: i64,
+    error: String,
+) -> Result<(), DbErr> {
+    node::Entity::update_many()
+        .col_expr(node::Column::LastError, Expr::value(error))
+        .filter(node::Column::Id.eq(node_id))
+        .exec(database)
+        .await?;
 
     Ok(())
 }
 
+/// Mark the provided block as confirmed after a successful processing.
+///
+/// Clears the last recorded error and records the current time
+/// as the last successful contact with the node.
+fn confirm_block(active_node: &mut node::ActiveModel, block_number: u32, block_hash: H256) {
+    let now = OffsetDateTime::now_utc();
+
+    active_node.confirmed_block = ActiveValue::Set(block_number as i64);
+    active_node.confirmed_block_hash = ActiveValue::Set(Some(block_hash.0.to_vec()));
+    active_node.last_error = ActiveValue::Set(None);
+    active_node.last_block_seen_at =
+        ActiveValue::Set(Some(PrimitiveDateTime::new(now.date(), now.time())));
+}
+
+/// Record the provided block as skipped and mark it as confirmed.
+pub(crate) async fn skip_block(
+    node: node::Model,
+    database: &DatabaseConnection,
+    block_number: u32,
+    block_hash: H256,
+    error: String,
+) -> Result<node::Model, WatchError> {
+    let node_id = node.id;
+    let mut active_node: node::ActiveModel = node.into();
+
+    database
+        .transaction(|txn| {
+            Box::pin(async move {
+                skipped_block::Entity::insert(skipped_block::ActiveModel {
+                    node_id: ActiveValue::Set(node_id),
+                    block_number: ActiveValue::Set(block_number as i64),
+                    block_hash: ActiveValue::Set(block_hash.0.to_vec()),
+                    error: ActiveValue::Set(error.clone()),
+                    ..Default::default()
+                })
+                .on_conflict(
+                    OnConflict::columns([
+                        skipped_block::Column::NodeId,
+                        skipped_block::Column::BlockNumber,
+                    ])
+                    .update_columns([
+                        skipped_block::Column::BlockHash,
+                        skipped_block::Column::Error,
+                    ])
+                    .to_owned(),
+                )
+                .exec_without_returning(txn)
+                .await?;
+
+                active_node.confirmed_block = ActiveValue::Set(block_number as i64);
+                active_node.confirmed_block_hash = ActiveValue::Set(Some(block_hash.0.to_vec()));
+                active_node.last_error = ActiveValue::Set(Some(error));
+
+                Ok(active_node.update(txn).await?)
+            })
+        })
+        .await
+        .into_raw_result()
+}
+
+/// Conflict resolution for events, that were already stored while processing the same block.
+///
+/// Blocks may be processed again after a crash, in which case events are not duplicated.
+fn event_conflict() -> OnConflict {
+    OnConflict::columns([
+        event::Column::NodeId,
+        event::Column::Account,
+        event::Column::EventType,
+        event::Column::BlockNumber,
+    ])
+    .do_nothing()
+    .to_owned()
+}
+
 /// Attempt to process one block from either traversal attempt, or
 /// block subscription.
 ///
 /// Returns new [`node::Model`], which represents an updated node
 /// with up-to-date confirmed block counter.
-async fn process_block<C: Request>(
+///
+/// If `advance` is set to `false`, the confirmed block counter is left as-is,
+/// which allows previously skipped blocks to be processed again.
+/// Contract state changes superseded by newer blocks are not applied in that case,
+/// see [`store_block_changes`] for more details.
+pub(crate) async fn process_block<C: Request>(
     node: node::Model,
     database: &DatabaseConnection,
     api: &Api<PolkadotConfig, C>,
     block_header: &<PolkadotConfig as Config>::Header,
     metadata: &Metadata,
+    advance: bool,
 ) -> Result<node::Model, WatchError> {
     let mut active_node: node::ActiveModel = node.clone().into();
 
@@ -158,12 +630,20 @@ async fn process_block<C: Request>(
         .try_filter_map(|(contract, deployer, info)| {
             ready(Ok(info.map(|val| (contract, deployer, val))))
         })
-        .map_ok(|(contract, deployer, info)| contract::ActiveModel {
-            code_hash: ActiveValue::Set(info.code_hash.0.to_vec()),
-            node_id: ActiveValue::Set(node.id),
-            address: ActiveValue::Set(contract.as_slice().to_vec()),
-            owner: ActiveValue::Set(Some(deployer.as_slice().to_vec())),
-            ..Default::default()
+        .map_ok(|(contract, deployer, info)| {
+            let model = contract::ActiveModel {
+                code_hash: ActiveValue::Set(info.code_hash.0.to_vec()),
+                node_id: ActiveValue::Set(node.id),
+                address: ActiveValue::Set(contract.as_slice().to_vec()),
+                owner: ActiveValue::Set(Some(deployer.as_slice().to_vec())),
+                block_number: ActiveValue::Set(Some(block_number as i64)),
+                ..Default::default()
+            };
+
+            let deployer =
+                rpc::ss58_address(&deployer, node.ss58_prefix.map(|prefix| prefix as u16));
+
+            (model, deployer)
         })
         .try_collect::<Vec<_>>()
         .await?;
@@ -186,105 +666,498 @@ async fn process_block<C: Request>(
         .try_collect()
         .map_err(substrate_api_client::Error::NodeApi)?;
 
-    database
-        .transaction(|txn| {
+    let event_counts = [
+        ("code_stored", code_uploads.len()),
+        ("instantiation", instantiations.len()),
+        ("code_hash_update", code_hash_updates.len()),
+        ("termination", terminations.len()),
+    ];
+
+    let changes = BlockChanges {
+        code_uploads,
+        instantiations,
+        code_hash_updates,
+        terminations,
+    };
+
+    let node = database
+        .transaction::<_, _, WatchError>(|txn| {
             Box::pin(async move {
-                if !code_uploads.is_empty() {
-                    code::Entity::insert_many(code_uploads)
-                        .on_conflict(
-                            OnConflict::column(code::Column::Hash)
-                                .do_nothing()
-                                .to_owned(),
-                        )
-                        .exec_without_returning(txn)
-                        .await?;
+                store_block_changes(
+                    txn,
+                    node.id,
+                    block_number,
+                    block_timestamp,
+                    changes,
+                    !advance,
+                )
+                .await?;
+
+                if !advance {
+                    return Ok(node);
                 }
 
-                if !instantiations.is_empty() {
-                    let instantiation_body =
-                        serde_json::to_string(&event::EventBody::Instantiation)?;
-
-                    event::Entity::insert_many(instantiations.iter().map(|model| {
-                        event::ActiveModel {
-                            node_id: ActiveValue::Set(node.id),
-                            account: model.address.clone(),
-                            event_type: ActiveValue::Set(event::EventType::Instantiation),
-                            body: ActiveValue::Set(instantiation_body.clone()),
-                            block_timestamp: ActiveValue::Set(block_timestamp),
-                            ..Default::default()
-                        }
-                    }))
-                    .exec_without_returning(txn)
-                    .await?;
+                confirm_block(&mut active_node, block_number, block_hash);
 
-                    contract::Entity::insert_many(instantiations)
-                        .on_conflict(
-                            OnConflict::columns([
-                                contract::Column::NodeId,
-                                contract::Column::Address,
-                            ])
-                            .update_column(contract::Column::CodeHash)
-                            .to_owned(),
-                        )
-                        .exec_without_returning(txn)
-                        .await?;
-                }
+                Ok(active_node.update(txn).await?)
+            })
+        })
+        .await
+        .into_raw_result()?;
 
-                for (contract, new_code_hash) in code_hash_updates {
-                    event::ActiveModel {
-                        node_id: ActiveValue::Set(node.id),
-                        account: ActiveValue::Set(contract.as_slice().to_vec()),
-                        event_type: ActiveValue::Set(event::EventType::CodeHashUpdate),
-                        body: ActiveValue::Set(serde_json::to_string(
-                            &event::EventBody::CodeHashUpdate {
-                                new_code_hash: hex::encode(new_code_hash),
-                            },
-                        )?),
-                        block_timestamp: ActiveValue::Set(block_timestamp),
-                        ..Default::default()
-                    }
-                    .insert(txn)
-                    .await?;
+    if advance {
+        metrics().record_block(&node.name, block_number, &event_counts);
+    }
 
-                    contract::Entity::update_many()
-                        .col_expr(contract::Column::CodeHash, (&new_code_hash[..]).into())
-                        .filter(contract::Column::NodeId.eq(node.id))
-                        .filter(contract::Column::Address.eq(contract.as_slice()))
-                        .exec(txn)
-                        .await?;
-                }
+    Ok(node)
+}
 
-                if !terminations.is_empty() {
-                    let termination_body = serde_json::to_string(&event::EventBody::Termination)?;
-
-                    event::Entity::insert_many(terminations.iter().map(|model| {
-                        event::ActiveModel {
-                            node_id: ActiveValue::Set(node.id),
-                            account: ActiveValue::Set(model.as_slice().to_vec()),
-                            event_type: ActiveValue::Set(event::EventType::Termination),
-                            body: ActiveValue::Set(termination_body.clone()),
-                            block_timestamp: ActiveValue::Set(block_timestamp),
-                            ..Default::default()
-                        }
-                    }))
-                    .exec_without_returning(txn)
-                    .await?;
+/// Contract-related changes discovered in a single block.
+struct BlockChanges {
+    /// Uploaded contract codes.
+    code_uploads: Vec<code::ActiveModel>,
 
-                    contract::Entity::delete_many()
-                        .filter(contract::Column::NodeId.eq(node.id))
-                        .filter(
-                            contract::Column::Address
-                                .is_in(terminations.iter().map(|val| val.as_slice())),
-                        )
-                        .exec(txn)
-                        .await?;
-                }
+    /// Instantiated contracts along with their deployer addresses.
+    instantiations: Vec<(contract::ActiveModel, String)>,
 
-                active_node.confirmed_block = ActiveValue::Set(block_number as i64);
+    /// Contract code hash updates.
+    code_hash_updates: Vec<(AccountId32, H256)>,
 
-                Ok(active_node.update(txn).await?)
+    /// Terminated contracts.
+    terminations: Vec<AccountId32>,
+}
+
+/// Find accounts among the provided ones, that have events discovered in blocks
+/// newer than the provided block number.
+async fn superseded_accounts(
+    txn: &DatabaseTransaction,
+    node_id: i64,
+    block_number: u32,
+    accounts: Vec<Vec<u8>>,
+) -> Result<HashSet<Vec<u8>>, DbErr> {
+    if accounts.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let accounts = event::Entity::find()
+        .select_only()
+        .column(event::Column::Account)
+        .filter(event::Column::NodeId.eq(node_id))
+        .filter(event::Column::BlockNumber.gt(block_number as i64))
+        .filter(event::Column::Account.is_in(accounts))
+        .into_tuple::<Vec<u8>>()
+        .all(txn)
+        .await?;
+
+    Ok(accounts.into_iter().collect())
+}
+
+/// Store events and apply contract state changes of a single block.
+///
+/// If `reprocess` is set to `true`, the block is older than the already processed ones,
+/// thus its events are stored as usual, while contract state changes are only applied
+/// to contracts without any events discovered in newer blocks. Otherwise, an old
+/// instantiation or code hash update would override the newer contract state,
+/// or restore an already terminated contract.
+async fn store_block_changes(
+    txn: &DatabaseTransaction,
+    node_id: i64,
+    block_number: u32,
+    block_timestamp: PrimitiveDateTime,
+    BlockChanges {
+        code_uploads,
+        instantiations,
+        code_hash_updates,
+        terminations,
+    }: BlockChanges,
+    reprocess: bool,
+) -> Result<(), DbErr> {
+    let superseded = if reprocess {
+        let accounts = instantiations
+            .iter()
+            .filter_map(|(model, _)| instantiated_address(model).cloned())
+            .chain(
+                code_hash_updates
+                    .iter()
+                    .map(|(contract, _)| contract.as_slice().to_vec()),
+            )
+            .chain(
+                terminations
+                    .iter()
+                    .map(|contract| contract.as_slice().to_vec()),
+            )
+            .collect();
+
+        superseded_accounts(txn, node_id, block_number, accounts).await?
+    } else {
+        HashSet::new()
+    };
+
+    if !code_uploads.is_empty() {
+        code::Entity::insert_many(code_uploads)
+            .on_conflict(
+                OnConflict::column(code::Column::Hash)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(txn)
+            .await?;
+    }
+
+    if !instantiations.is_empty() {
+        event::Entity::insert_many(instantiations.iter().map(|(model, deployer)| {
+            event::ActiveModel {
+                node_id: ActiveValue::Set(node_id),
+                account: model.address.clone(),
+                event_type: ActiveValue::Set(event::EventType::Instantiation),
+                body: ActiveValue::Set(event::EventBody::Instantiation {
+                    deployer: deployer.clone(),
+                }),
+                block_timestamp: ActiveValue::Set(block_timestamp),
+                block_number: ActiveValue::Set(Some(block_number as i64)),
+                ..Default::default()
+            }
+        }))
+        .on_conflict(event_conflict())
+        .exec_without_returning(txn)
+        .await?;
+
+        let contracts = instantiations
+            .into_iter()
+            .map(|(model, _)| model)
+            .filter(|model| {
+                instantiated_address(model).map_or(true, |address| !superseded.contains(address))
             })
+            .collect::<Vec<_>>();
+
+        if !contracts.is_empty() {
+            contract::Entity::insert_many(contracts)
+                .on_conflict(
+                    OnConflict::columns([contract::Column::NodeId, contract::Column::Address])
+                        .update_columns([contract::Column::CodeHash, contract::Column::BlockNumber])
+                        .to_owned(),
+                )
+                .exec_without_returning(txn)
+                .await?;
+        }
+    }
+
+    for (contract, new_code_hash) in code_hash_updates {
+        event::Entity::insert(event::ActiveModel {
+            node_id: ActiveValue::Set(node_id),
+            account: ActiveValue::Set(contract.as_slice().to_vec()),
+            event_type: ActiveValue::Set(event::EventType::CodeHashUpdate),
+            body: ActiveValue::Set(event::EventBody::CodeHashUpdate {
+                new_code_hash: hex::encode(new_code_hash),
+            }),
+            block_timestamp: ActiveValue::Set(block_timestamp),
+            block_number: ActiveValue::Set(Some(block_number as i64)),
+            ..Default::default()
+        })
+        .on_conflict(event_conflict())
+        .exec_without_returning(txn)
+        .await?;
+
+        if superseded.contains(contract.as_slice()) {
+            continue;
+        }
+
+        contract::Entity::update_many()
+            .col_expr(contract::Column::CodeHash, (&new_code_hash[..]).into())
+            .filter(contract::Column::NodeId.eq(node_id))
+            .filter(contract::Column::Address.eq(contract.as_slice()))
+            .exec(txn)
+            .await?;
+    }
+
+    if !terminations.is_empty() {
+        event::Entity::insert_many(terminations.iter().map(|model| event::ActiveModel {
+            node_id: ActiveValue::Set(node_id),
+            account: ActiveValue::Set(model.as_slice().to_vec()),
+            event_type: ActiveValue::Set(event::EventType::Termination),
+            body: ActiveValue::Set(event::EventBody::Termination),
+            block_timestamp: ActiveValue::Set(block_timestamp),
+            block_number: ActiveValue::Set(Some(block_number as i64)),
+            ..Default::default()
+        }))
+        .on_conflict(event_conflict())
+        .exec_without_returning(txn)
+        .await?;
+
+        contract::Entity::delete_many()
+            .filter(contract::Column::NodeId.eq(node_id))
+            .filter(
+                contract::Column::Address.is_in(
+                    terminations
+                        .iter()
+                        .map(|val| val.as_slice())
+                        .filter(|address| !superseded.contains(*address)),
+                ),
+            )
+            .exec(txn)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Get the address of an instantiated contract.
+fn instantiated_address(model: &contract::ActiveModel) -> Option<&Vec<u8>> {
+    match &model.address {
+        ActiveValue::Set(address) | ActiveValue::Unchanged(address) => Some(address),
+        ActiveValue::NotSet => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, time::Duration};
+
+    use common::rpc::{
+        sp_core::{crypto::AccountId32, ByteArray, H256},
+        substrate_api_client,
+    };
+    use db::{
+        contract, event, node, ActiveModelTrait, ActiveValue, DbErr, EntityTrait, OffsetDateTime,
+        PaginatorTrait, PrimitiveDateTime, QueryOrder, TransactionTrait,
+    };
+
+    use super::{
+        confirm_block, event_conflict, record_node_error, store_block_changes, BlockChanges,
+        WatchError,
+    };
+    use crate::testing::create_database;
+
+    #[tokio::test]
+    async fn reprocessed_events() {
+        let db = create_database().await;
+
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
         })
+        .exec_with_returning(&db)
         .await
-        .into_raw_result()
+        .expect("unable to insert node");
+
+        let now = OffsetDateTime::now_utc();
+
+        let model = |block_number| event::ActiveModel {
+            node_id: ActiveValue::Set(node.id),
+            account: ActiveValue::Set(vec![1; 32]),
+            event_type: ActiveValue::Set(event::EventType::Termination),
+            body: ActiveValue::Set(event::EventBody::Termination),
+            block_timestamp: ActiveValue::Set(PrimitiveDateTime::new(now.date(), now.time())),
+            block_number: ActiveValue::Set(Some(block_number)),
+            ..Default::default()
+        };
+
+        // The same block is processed twice, e.g. after a crash.
+        for _ in 0..2 {
+            event::Entity::insert_many([model(1), model(2)])
+                .on_conflict(event_conflict())
+                .exec_without_returning(&db)
+                .await
+                .expect("unable to insert events");
+        }
+
+        event::Entity::insert(model(2))
+            .on_conflict(event_conflict())
+            .exec_without_returning(&db)
+            .await
+            .expect("unable to insert an event");
+
+        assert_eq!(event::Entity::find().count(&db).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn reprocessed_block_keeps_newer_state() {
+        let db = create_database().await;
+
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_with_returning(&db)
+        .await
+        .expect("unable to insert node");
+
+        let now = OffsetDateTime::now_utc();
+        let timestamp = PrimitiveDateTime::new(now.date(), now.time());
+
+        let terminated = AccountId32::new([1; 32]);
+        let updated = AccountId32::new([2; 32]);
+        let instantiated = AccountId32::new([3; 32]);
+
+        let instantiation = |contract: &AccountId32| {
+            let model = contract::ActiveModel {
+                code_hash: ActiveValue::Set(vec![0; 32]),
+                node_id: ActiveValue::Set(node.id),
+                address: ActiveValue::Set(contract.as_slice().to_vec()),
+                owner: ActiveValue::Set(None),
+                block_number: ActiveValue::Set(Some(10)),
+                ..Default::default()
+            };
+
+            (model, contract.to_string())
+        };
+
+        let txn = db.begin().await.unwrap();
+
+        // Block 20 was processed after block 10 was skipped.
+        store_block_changes(
+            &txn,
+            node.id,
+            20,
+            timestamp,
+            BlockChanges {
+                code_uploads: Vec::new(),
+                instantiations: vec![instantiation(&updated)],
+                code_hash_updates: vec![(updated.clone(), H256([2; 32]))],
+                terminations: vec![terminated.clone()],
+            },
+            false,
+        )
+        .await
+        .expect("unable to store block changes");
+
+        // Skipped block is reprocessed afterwards.
+        store_block_changes(
+            &txn,
+            node.id,
+            10,
+            timestamp,
+            BlockChanges {
+                code_uploads: Vec::new(),
+                instantiations: vec![instantiation(&terminated), instantiation(&instantiated)],
+                code_hash_updates: vec![(updated.clone(), H256([1; 32]))],
+                terminations: Vec::new(),
+            },
+            true,
+        )
+        .await
+        .expect("unable to store block changes");
+
+        txn.commit().await.unwrap();
+
+        let contracts = contract::Entity::find()
+            .order_by_asc(contract::Column::Address)
+            .all(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|model| (model.address, model.code_hash))
+            .collect::<Vec<_>>();
+
+        // Terminated contract stays deleted and newer code hash is kept.
+        assert_eq!(
+            contracts,
+            [(vec![2; 32], vec![2; 32]), (vec![3; 32], vec![0; 32])]
+        );
+
+        // Events of the reprocessed block are stored regardless.
+        assert_eq!(event::Entity::find().count(&db).await.unwrap(), 6);
+    }
+
+    #[tokio::test]
+    async fn last_block_seen_at() {
+        let db = create_database().await;
+
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            last_error: ActiveValue::Set(Some(String::from("rpc error"))),
+            ..Default::default()
+        })
+        .exec_with_returning(&db)
+        .await
+        .expect("unable to insert node");
+
+        assert_eq!(node.last_block_seen_at, None);
+
+        // Stored timestamps may be truncated.
+        let before = OffsetDateTime::now_utc() - Duration::from_secs(1);
+
+        let mut active_node: node::ActiveModel = node.into();
+        confirm_block(&mut active_node, 1, H256([1; 32]));
+        let node = active_node
+            .update(&db)
+            .await
+            .expect("unable to update node");
+
+        let seen_at = node
+            .last_block_seen_at
+            .expect("last block time was not recorded")
+            .assume_utc();
+
+        assert!(seen_at >= before && seen_at <= OffsetDateTime::now_utc());
+        assert_eq!(node.confirmed_block, 1);
+        assert_eq!(node.confirmed_block_hash, Some(vec![1; 32]));
+        assert_eq!(node.last_error, None);
+
+        record_node_error(&db, node.id, String::from("rpc error"))
+            .await
+            .expect("unable to record node error");
+
+        let failed = node::Entity::find_by_id(node.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .expect("node was removed");
+
+        assert_eq!(failed.last_error.as_deref(), Some("rpc error"));
+        assert_eq!(failed.last_block_seen_at, node.last_block_seen_at);
+    }
+
+    #[tokio::test]
+    async fn constraint_violation_is_not_retried() {
+        let db = create_database().await;
+
+        let model = || node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        };
+
+        node::Entity::insert(model())
+            .exec_without_returning(&db)
+            .await
+            .expect("unable to insert node");
+
+        let err = node::Entity::insert(model())
+            .exec_without_returning(&db)
+            .await
+            .expect_err("duplicate node name was inserted");
+
+        assert!(!WatchError::DatabaseError(err).is_retryable());
+    }
+
+    #[test]
+    fn skip_decision() {
+        let json_error = serde_json::from_str::<u32>("invalid").unwrap_err();
+
+        assert!(WatchError::JsonError(json_error).is_skippable());
+        assert!(
+            WatchError::RpcError(substrate_api_client::Error::Codec("invalid block".into()))
+                .is_skippable()
+        );
+
+        let query_error = || {
+            WatchError::DatabaseError(DbErr::Query(db::sea_orm::RuntimeErr::SqlxError(
+                db::sea_orm::sqlx::Error::Io(io::ErrorKind::ConnectionReset.into()),
+            )))
+        };
+
+        assert!(!query_error().is_skippable());
+        assert!(query_error().is_retryable());
+        assert!(!WatchError::DatabaseError(DbErr::ConnectionAcquire).is_skippable());
+        assert!(!WatchError::RpcError(substrate_api_client::Error::BlockNotFound).is_skippable());
+        assert!(!WatchError::NodeNotFound.is_skippable());
+    }
 }
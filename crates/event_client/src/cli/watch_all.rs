@@ -0,0 +1,192 @@
+use std::future::Future;
+
+use common::rpc::MetadataCacheOptions;
+use db::{node, DatabaseConnection, DbErr, EntityTrait};
+use derive_more::{Display, Error, From};
+use tokio::task::JoinSet;
+use tracing::{error, info, info_span, Instrument};
+
+use super::watch::{watch_node, WatchError, WatchOptions};
+use crate::shutdown::Shutdown;
+
+/// Errors that may occur during the watch process of all nodes.
+#[derive(Debug, Display, Error, From)]
+pub enum WatchAllError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// No nodes were found in the database.
+    #[display(fmt = "no nodes available to watch")]
+    NoNodes,
+
+    /// Some node watchers exited with an error.
+    #[display(fmt = "{} node watcher(s) failed", _0)]
+    WatcherFailure(#[error(ignore)] usize),
+}
+
+/// Watch all available RPC nodes for new smart contract-related events.
+///
+/// # Details
+///
+/// Each node is watched by a separate task inside the same runtime, with
+/// its own RPC client and metadata cache. Failure of a single node watcher
+/// does not affect the rest of them.
+///
+/// Refer to the [`watch`] documentation for details on fork handling and catch-up options.
+///
+/// Receiving a SIGTERM or Ctrl-C will stop all watchers after their current blocks are processed.
+///
+/// [`watch`]: super::watch
+pub async fn watch_all(
+    database: DatabaseConnection,
+    options: WatchOptions,
+    cache_options: MetadataCacheOptions,
+) -> Result<(), WatchAllError> {
+    let nodes = node::Entity::find().all(&database).await?;
+
+    if nodes.is_empty() {
+        return Err(WatchAllError::NoNodes);
+    }
+
+    let shutdown = Shutdown::default();
+    shutdown.listen();
+
+    let failures = supervise(nodes, |node| {
+        let database = database.clone();
+        let shutdown = shutdown.clone();
+        let cache_options = cache_options.clone();
+
+        async move { watch_node(&database, node, options, cache_options, &shutdown).await }
+    })
+    .await;
+
+    if failures > 0 {
+        return Err(WatchAllError::WatcherFailure(failures));
+    }
+
+    Ok(())
+}
+
+/// Spawn a separate task for each of the provided nodes and wait for all of them to finish.
+///
+/// Returns the number of tasks that exited with an error or panicked.
+async fn supervise<F, Fut>(nodes: Vec<node::Model>, task: F) -> usize
+where
+    F: Fn(node::Model) -> Fut,
+    Fut: Future<Output = Result<(), WatchError>> + Send + 'static,
+{
+    let mut tasks = JoinSet::new();
+
+    for node in nodes {
+        let span = info_span!("watch", node = %node.name);
+        let name = node.name.clone();
+
+        tasks.spawn(
+            async move {
+                info!("starting node watcher");
+                (name, task(node).await)
+            }
+            .instrument(span),
+        );
+    }
+
+    let mut failures = 0;
+
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok((name, Ok(()))) => info!(node = %name, "node watcher stopped"),
+            Ok((name, Err(err))) => {
+                error!(node = %name, %err, "node watcher failed");
+                failures += 1;
+            }
+            Err(err) => {
+                error!(%err, "node watcher panicked");
+                failures += 1;
+            }
+        }
+    }
+
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use db::node;
+
+    use super::{supervise, WatchError};
+
+    fn fake_node(id: i64, name: &str) -> node::Model {
+        node::Model {
+            id,
+            name: name.to_owned(),
+            url: format!("ws://{name}:9944"),
+            payment_contract: None,
+            confirmed_block: 0,
+            confirmed_block_hash: None,
+            last_error: None,
+            last_block_seen_at: None,
+            initialization_phase: None,
+            initialization_key: None,
+            display_name: None,
+            explorer_url_template: None,
+            token_symbol: None,
+            token_decimals: None,
+            ss58_prefix: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_node_does_not_stop_others() {
+        let finished = Arc::new(Mutex::new(Vec::new()));
+
+        let failures = supervise(
+            vec![
+                fake_node(1, "alpha"),
+                fake_node(2, "beta"),
+                fake_node(3, "gamma"),
+            ],
+            |node| {
+                let finished = finished.clone();
+
+                async move {
+                    if node.name == "alpha" {
+                        return Err(WatchError::NodeNotFound);
+                    }
+
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    finished.lock().unwrap().push(node.name);
+                    Ok(())
+                }
+            },
+        )
+        .await;
+
+        let mut finished = finished.lock().unwrap().clone();
+        finished.sort();
+
+        assert_eq!(failures, 1);
+        assert_eq!(finished, vec!["beta", "gamma"]);
+    }
+
+    #[tokio::test]
+    async fn panicking_node_is_counted_as_failure() {
+        let failures = supervise(
+            vec![fake_node(1, "alpha"), fake_node(2, "beta")],
+            |node| async move {
+                if node.id == 1 {
+                    panic!("watcher panic");
+                }
+
+                Ok(())
+            },
+        )
+        .await;
+
+        assert_eq!(failures, 1);
+    }
+}
@@ -20,6 +20,9 @@
 //!
 //! Refer to the [`watch`] documentation for more details.
 //!
+//! To watch all nodes stored in the database within a single process,
+//! use the `watch-all` subcommand instead. Refer to the [`watch_all`] documentation for more details.
+//!
 //! ## Node traversal
 //!
 //! `traverse` subcommand attempts to traverse previous blocks to collect info about
@@ -29,6 +32,20 @@
 //!
 //! Refer to the [`traverse`] documentation for more details.
 //!
+//! ## Node management
+//!
+//! `list`, `remove` and `rename` subcommands can be used to manage existing nodes.
+//! Node removal refuses to remove nodes with related contracts or events,
+//! unless the `--cascade` flag is provided.
+//!
+//! ## Owner backfill
+//!
+//! Contracts discovered during node initialization have no owner information.
+//! `backfill-owners` subcommand resolves their owners either from historical
+//! instantiation events, or from a mapping file provided by an external indexer.
+//!
+//! Refer to the [`backfill_owners`] documentation for more details.
+//!
 //! ## Payment contract update
 //!
 //! Using `update-contract` subcommand you can update the address of the payment
@@ -36,8 +53,21 @@
 //!
 //! Refer to the [`update_contract`] documentation for more details.
 //!
+//! ## Metrics
+//!
+//! If the `event_client.metrics_address` configuration value is present, `watch` and `watch-all`
+//! subcommands serve Prometheus metrics on the configured address.
+//!
+//! ## Metadata cache
+//!
+//! Runtime metadata fetched from nodes is cached in memory, with the capacity configured
+//! by the `event_client.metadata_cache_capacity` value. If the `event_client.metadata_cache_dir`
+//! value is present, metadata is also persisted to the provided directory.
+//!
+//! [`backfill_owners`]: cli::backfill_owners
 //! [`initialize`]: cli::initialize
 //! [`watch`]: cli::watch
+//! [`watch_all`]: cli::watch_all
 //! [`traverse`]: cli::traverse
 //! [`update_contract`]: cli::update_contract
 
@@ -47,14 +77,24 @@
 /// CLI general configuration and subcommands.
 mod cli;
 
+/// Prometheus metrics collection and serving.
+pub(crate) mod metrics;
+
+/// Graceful shutdown signal handling.
+pub(crate) mod shutdown;
+
 /// Various extraction and mapping utilities.
 pub(crate) mod utils;
 
+/// Testing utilities.
+#[cfg(test)]
+mod testing;
+
 use clap::Parser;
 use cli::{Cli, Command};
-use common::{config::Config, logging};
+use common::{config::Config, logging, rpc::MetadataCacheOptions};
 use db::Database;
-use tracing::info;
+use tracing::{error, info};
 
 /// Event client entrypoint.
 #[tokio::main]
@@ -69,18 +109,82 @@ async fn main() -> Result<(), anyhow::Error> {
     let database = Database::connect(&config.database.url).await?;
     info!("database connection established");
 
+    if let (Some(address), Command::Watch { .. } | Command::WatchAll { .. }) = (
+        config
+            .event_client
+            .as_ref()
+            .and_then(|event_client| event_client.metrics_address),
+        &cli.command,
+    ) {
+        info!(%address, "starting metrics server");
+        tokio::spawn(async move {
+            if let Err(err) = metrics::serve(metrics::metrics(), address).await {
+                error!(%err, "metrics server failed");
+            }
+        });
+    }
+
+    let cache_options = config
+        .event_client
+        .as_ref()
+        .map(|event_client| MetadataCacheOptions {
+            capacity: event_client.metadata_cache_capacity,
+            directory: event_client.metadata_cache_dir.clone(),
+        })
+        .unwrap_or_default();
+
     match cli.command {
+        Command::BackfillOwners {
+            name,
+            mapping_file,
+            from_block,
+            to_block,
+            batch_size,
+        } => {
+            let source = match &mapping_file {
+                Some(path) => cli::OwnerSource::MappingFile(path),
+                None => cli::OwnerSource::Events {
+                    from_block,
+                    to_block,
+                },
+            };
+
+            cli::backfill_owners(database, name, source, batch_size, cache_options).await?
+        }
         Command::Initialize {
             name,
             url,
             payment_address,
-        } => cli::initialize(database, name, url, payment_address).await?,
-        Command::Traverse { name } => cli::traverse(database, name).await?,
+            display,
+            options,
+        } => {
+            cli::initialize(
+                database,
+                name,
+                url,
+                payment_address,
+                display,
+                options,
+                cache_options,
+            )
+            .await?
+        }
+        Command::List => cli::list(database).await?,
+        Command::Remove { name, cascade } => cli::remove(database, name, cascade).await?,
+        Command::Rename { old_name, new_name } => cli::rename(database, old_name, new_name).await?,
+        Command::ReprocessSkipped { name } => {
+            cli::reprocess_skipped(database, name, cache_options).await?
+        }
+        Command::Traverse { name } => cli::traverse(database, name, cache_options).await?,
         Command::UpdateContract {
             name,
             payment_address,
-        } => cli::update_contract(database, name, payment_address).await?,
-        Command::Watch { name } => cli::watch(database, name).await?,
+            force,
+        } => cli::update_contract(database, name, payment_address, force, cache_options).await?,
+        Command::Watch { name, options } => {
+            cli::watch(database, name, options, cache_options).await?
+        }
+        Command::WatchAll { options } => cli::watch_all(database, options, cache_options).await?,
     }
 
     Ok(())
@@ -0,0 +1,245 @@
+use std::{
+    convert::Infallible,
+    io,
+    net::{SocketAddr, TcpListener},
+    sync::OnceLock,
+};
+
+use derive_more::{Display, Error, From};
+
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Response, Server,
+};
+use prometheus::{Encoder, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder};
+
+/// Process-wide metrics instance.
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+/// Get process-wide metrics instance.
+pub(crate) fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::new)
+}
+
+/// Prometheus metrics collected by the event client.
+pub(crate) struct Metrics {
+    /// Metrics registry.
+    registry: Registry,
+
+    /// Last confirmed block per node.
+    confirmed_block: IntGaugeVec,
+
+    /// Latest chain head seen per node.
+    chain_head: IntGaugeVec,
+
+    /// Difference between the latest chain head and the confirmed block per node.
+    lag: IntGaugeVec,
+
+    /// Processed events per node and event type.
+    events: IntCounterVec,
+}
+
+impl Metrics {
+    /// Create new metrics registry with all event client metrics registered.
+    pub fn new() -> Self {
+        let confirmed_block = IntGaugeVec::new(
+            Opts::new("event_client_confirmed_block", "Last confirmed block"),
+            &["node"],
+        )
+        .expect("valid metric definition");
+
+        let chain_head = IntGaugeVec::new(
+            Opts::new("event_client_chain_head", "Latest chain head seen"),
+            &["node"],
+        )
+        .expect("valid metric definition");
+
+        let lag = IntGaugeVec::new(
+            Opts::new(
+                "event_client_lag_blocks",
+                "Difference between the chain head and the confirmed block",
+            ),
+            &["node"],
+        )
+        .expect("valid metric definition");
+
+        let events = IntCounterVec::new(
+            Opts::new("event_client_events_total", "Processed events"),
+            &["node", "type"],
+        )
+        .expect("valid metric definition");
+
+        let registry = Registry::new();
+
+        for collector in [
+            Box::new(confirmed_block.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(chain_head.clone()),
+            Box::new(lag.clone()),
+            Box::new(events.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("metric is registered only once");
+        }
+
+        Self {
+            registry,
+            confirmed_block,
+            chain_head,
+            lag,
+            events,
+        }
+    }
+
+    /// Record the latest chain head seen by the provided node.
+    pub fn record_chain_head(&self, node: &str, block_number: u32) {
+        self.chain_head
+            .with_label_values(&[node])
+            .set(i64::from(block_number));
+        self.update_lag(node);
+    }
+
+    /// Record a processed block with the provided event counts.
+    pub fn record_block(&self, node: &str, block_number: u32, events: &[(&str, usize)]) {
+        let chain_head = self.chain_head.with_label_values(&[node]);
+
+        if chain_head.get() < i64::from(block_number) {
+            chain_head.set(i64::from(block_number));
+        }
+
+        self.confirmed_block
+            .with_label_values(&[node])
+            .set(i64::from(block_number));
+
+        for (event_type, count) in events {
+            self.events
+                .with_label_values(&[node, event_type])
+                .inc_by(*count as u64);
+        }
+
+        self.update_lag(node);
+    }
+
+    /// Update lag value using the current chain head and confirmed block values.
+    fn update_lag(&self, node: &str) {
+        let chain_head = self.chain_head.with_label_values(&[node]).get();
+        let confirmed_block = self.confirmed_block.with_label_values(&[node]).get();
+
+        self.lag
+            .with_label_values(&[node])
+            .set(chain_head.saturating_sub(confirmed_block).max(0));
+    }
+
+    /// Encode all metrics in Prometheus text format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("unable to encode metrics");
+
+        buffer
+    }
+}
+
+/// Errors that may occur during metrics serving.
+#[derive(Debug, Display, Error, From)]
+pub(crate) enum MetricsError {
+    /// Unable to bind to the provided address.
+    IoError(io::Error),
+
+    /// HTTP server error.
+    HyperError(hyper::Error),
+}
+
+/// Serve metrics in Prometheus text format on the provided address.
+pub(crate) async fn serve(
+    metrics: &'static Metrics,
+    address: SocketAddr,
+) -> Result<(), MetricsError> {
+    let listener = TcpListener::bind(address)?;
+    listener.set_nonblocking(true)?;
+
+    serve_listener(metrics, listener).await
+}
+
+/// Serve metrics in Prometheus text format using the provided listener.
+async fn serve_listener(
+    metrics: &'static Metrics,
+    listener: TcpListener,
+) -> Result<(), MetricsError> {
+    let make_service = make_service_fn(move |_| async move {
+        Ok::<_, Infallible>(service_fn(move |_| async move {
+            Ok::<_, Infallible>(
+                Response::builder()
+                    .header(CONTENT_TYPE, TextEncoder::new().format_type())
+                    .body(Body::from(metrics.encode()))
+                    .expect("valid response"),
+            )
+        }))
+    });
+
+    Server::from_tcp(listener)?.serve(make_service).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    };
+
+    use super::{serve_listener, Metrics};
+
+    async fn scrape(address: std::net::SocketAddr) -> String {
+        let mut stream = TcpStream::connect(address)
+            .await
+            .expect("unable to connect to metrics server");
+
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .expect("unable to send request");
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .expect("unable to read response");
+
+        response
+    }
+
+    #[tokio::test]
+    async fn record_block_updates_gauges() {
+        let metrics: &'static Metrics = Box::leak(Box::new(Metrics::new()));
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("unable to bind");
+        listener.set_nonblocking(true).unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(serve_listener(metrics, listener));
+
+        metrics.record_chain_head("test", 10);
+
+        let response = scrape(address).await;
+        assert!(response.contains("event_client_chain_head{node=\"test\"} 10"));
+        assert!(response.contains("event_client_lag_blocks{node=\"test\"} 10"));
+
+        metrics.record_block("test", 8, &[("instantiation", 2), ("termination", 1)]);
+
+        let response = scrape(address).await;
+        assert!(response.contains("event_client_confirmed_block{node=\"test\"} 8"));
+        assert!(response.contains("event_client_lag_blocks{node=\"test\"} 2"));
+        assert!(response
+            .contains("event_client_events_total{node=\"test\",type=\"instantiation\"} 2"));
+        assert!(
+            response.contains("event_client_events_total{node=\"test\",type=\"termination\"} 1")
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use tokio::sync::Notify;
+use tracing::info;
+
+/// Graceful shutdown signal, shared between running watch tasks.
+///
+/// Tasks are expected to check [`Shutdown::is_triggered`] between
+/// processed blocks and stop as soon as the signal is triggered.
+/// Tasks waiting for new blocks can race the wait against [`Shutdown::triggered`].
+#[derive(Clone, Default)]
+pub(crate) struct Shutdown(Arc<ShutdownState>);
+
+/// Shared state of the [`Shutdown`] signal.
+#[derive(Default)]
+struct ShutdownState {
+    /// Whether the signal was triggered.
+    triggered: AtomicBool,
+
+    /// Notification for tasks waiting for the signal.
+    notify: Notify,
+}
+
+impl Shutdown {
+    /// Trigger the shutdown signal.
+    pub fn trigger(&self) {
+        self.0.triggered.store(true, Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    /// Check if the shutdown signal was triggered.
+    pub fn is_triggered(&self) -> bool {
+        self.0.triggered.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the shutdown signal is triggered.
+    pub async fn triggered(&self) {
+        // Notification future is created before the check,
+        // so that a concurrent trigger is not missed.
+        let notified = self.0.notify.notified();
+
+        if self.is_triggered() {
+            return;
+        }
+
+        notified.await;
+    }
+
+    /// Spawn a background task that triggers the shutdown signal
+    /// on SIGTERM or Ctrl-C.
+    pub fn listen(&self) {
+        let shutdown = self.clone();
+
+        tokio::spawn(async move {
+            wait_for_signal().await;
+            info!("shutdown signal received, finishing current blocks");
+            shutdown.trigger();
+        });
+    }
+}
+
+/// Wait for either SIGTERM or Ctrl-C.
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate()).expect("unable to install SIGTERM handler");
+
+    tokio::select! {
+        _ = terminate.recv() => {},
+        _ = tokio::signal::ctrl_c() => {},
+    }
+}
+
+/// Wait for Ctrl-C.
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Shutdown;
+
+    #[tokio::test]
+    async fn triggered() {
+        let shutdown = Shutdown::default();
+
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.triggered().await }
+        });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        shutdown.trigger();
+
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter was not notified")
+            .unwrap();
+
+        // Signal triggered earlier resolves immediately.
+        tokio::time::timeout(Duration::from_secs(1), shutdown.triggered())
+            .await
+            .expect("triggered signal was not observed");
+    }
+}
@@ -0,0 +1 @@
+pub(crate) use migration::testing::create_database;
@@ -1,6 +1,19 @@
+use std::{
+    future::{ready, Future},
+    mem,
+    ops::RangeInclusive,
+    pin::pin,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
 use common::rpc::{
-    sp_core::H256,
-    substrate_api_client::{ac_primitives::PolkadotConfig, rpc::Request, Api, Error, GetChainInfo},
+    sp_core::{crypto::AccountId32, H256},
+    substrate_api_client::{
+        ac_primitives::{PolkadotConfig, StorageKey},
+        rpc::Request,
+        Api, Error, GetChainInfo,
+    },
 };
 use futures_util::{stream, Stream, StreamExt, TryStreamExt};
 
@@ -10,6 +23,9 @@ const TWOX_HASH_LEN: usize = 8;
 /// Module + method information length.
 const STORAGE_PREFIX_LEN: usize = 32;
 
+/// Number of processed blocks between catch-up progress reports.
+const PROGRESS_INTERVAL: u32 = 1000;
+
 /// Extract account id from the provided storage key.
 ///
 /// For more information on the extraction algorithm consult [polkadot{.js}]'s
@@ -32,30 +48,706 @@ pub(crate) fn extract_code_hash<T: AsRef<[u8]>>(key: T) -> Vec<u8> {
     key.as_ref()[STORAGE_PREFIX_LEN..].to_owned()
 }
 
+/// Parse account id in either SS58 or hex format.
+pub(crate) fn parse_account_id(value: &str) -> Option<AccountId32> {
+    if let Ok(account_id) = AccountId32::from_str(value) {
+        return Some(account_id);
+    }
+
+    let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value)).ok()?;
+
+    <[u8; 32]>::try_from(bytes).ok().map(AccountId32::from)
+}
+
 /// Get a mapping stream from block number to block hash.
 ///
+/// Up to `batch_size` block hashes are requested from an RPC node concurrently,
+/// while the stream still yields them in the order of the provided block numbers.
+///
 /// The stream may skip blocks, to which an RPC node did not provide a hash.
 pub(crate) fn block_mapping_stream<'a, I: IntoIterator<Item = u32> + 'a, C: Request>(
     range: I,
+    batch_size: usize,
     api: &'a Api<PolkadotConfig, C>,
 ) -> impl Stream<Item = Result<(u32, H256), Error>> + 'a {
+    block_hash_stream(range, batch_size, move |block_number| async move {
+        api.get_block_hash(Some(block_number)).await
+    })
+}
+
+/// Map block numbers to block hashes using the provided lookup function,
+/// running up to `batch_size` lookups concurrently.
+fn block_hash_stream<'a, I, E, F, Fut>(
+    range: I,
+    batch_size: usize,
+    get_block_hash: F,
+) -> impl Stream<Item = Result<(u32, H256), E>> + 'a
+where
+    I: IntoIterator<Item = u32> + 'a,
+    E: 'a,
+    F: Fn(u32) -> Fut + 'a,
+    Fut: Future<Output = Result<Option<H256>, E>> + 'a,
+{
     stream::iter(range)
-        .map(Ok)
-        .try_filter_map(move |block_number| async move {
-            Ok(api
-                .get_block_hash(Some(block_number))
-                .await?
-                .map(|hash| (block_number, hash)))
+        .map(move |block_number| {
+            let lookup = get_block_hash(block_number);
+
+            async move { Ok(lookup.await?.map(|hash| (block_number, hash))) }
         })
+        .buffered(batch_size.max(1))
+        .try_filter_map(|block| ready(Ok(block)))
+}
+
+/// Determine the range of blocks that follow the confirmed block, up to the latest block.
+///
+/// Returns the total count of missed blocks as an error if it exceeds `max_blocks`.
+pub(crate) fn catch_up_range(
+    confirmed_block: i64,
+    latest_block: u32,
+    max_blocks: u32,
+) -> Result<RangeInclusive<u32>, u32> {
+    let first_block = (confirmed_block + 1) as u32;
+    let missed_blocks = (latest_block + 1).saturating_sub(first_block);
+
+    if missed_blocks > max_blocks {
+        return Err(missed_blocks);
+    }
+
+    Ok(first_block..=latest_block)
+}
+
+/// Progress tracker of a catch-up process.
+pub(crate) struct CatchUpProgress {
+    /// Time at which the catch-up process was started.
+    started_at: Instant,
+
+    /// Total count of blocks to process.
+    total: u32,
+
+    /// Count of blocks processed so far.
+    processed: u32,
+}
+
+impl CatchUpProgress {
+    /// Start tracking a catch-up process with the provided total count of blocks.
+    pub fn new(total: u32) -> Self {
+        Self::started_at(total, Instant::now())
+    }
+
+    /// Start tracking a catch-up process at the provided time.
+    fn started_at(total: u32, started_at: Instant) -> Self {
+        Self {
+            started_at,
+            total,
+            processed: 0,
+        }
+    }
+
+    /// Record a processed block.
+    ///
+    /// Returns the count of processed blocks along with the estimated remaining time
+    /// every [`PROGRESS_INTERVAL`] blocks.
+    pub fn record(&mut self) -> Option<(u32, Duration)> {
+        self.record_at(Instant::now())
+    }
+
+    /// Record a block processed at the provided time.
+    fn record_at(&mut self, now: Instant) -> Option<(u32, Duration)> {
+        self.processed += 1;
+
+        if self.processed % PROGRESS_INTERVAL != 0 {
+            return None;
+        }
+
+        let remaining = self.total.saturating_sub(self.processed);
+        let eta = now
+            .duration_since(self.started_at)
+            .mul_f64(f64::from(remaining) / f64::from(self.processed));
+
+        Some((self.processed, eta))
+    }
+}
+
+/// Process a paged storage stream in batches of the provided size.
+///
+/// `flush` is called for each collected batch with the last storage key of that batch
+/// and the total number of processed items, including the current batch.
+/// Storage key can be recorded to resume the process later.
+///
+/// Returns the total number of processed items.
+pub(crate) async fn process_in_batches<T, S, E, F, Fut>(
+    stream: S,
+    batch_size: usize,
+    mut flush: F,
+) -> Result<usize, E>
+where
+    S: Stream<Item = Result<Vec<(StorageKey, T)>, E>>,
+    F: FnMut(Vec<(StorageKey, T)>, StorageKey, usize) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    let mut stream = pin!(stream);
+    let mut buffer = Vec::with_capacity(batch_size);
+    let mut processed = 0;
+
+    loop {
+        let page = stream.try_next().await?;
+        let finished = page.is_none();
+
+        buffer.extend(page.into_iter().flatten());
+
+        if !buffer.is_empty() && (finished || buffer.len() >= batch_size) {
+            processed += buffer.len();
+
+            let last_key = buffer
+                .last()
+                .map(|(key, _)| key.clone())
+                .expect("buffer is not empty");
+
+            flush(mem::take(&mut buffer), last_key, processed).await?;
+        }
+
+        if finished {
+            return Ok(processed);
+        }
+    }
+}
+
+/// Errors that may occur during the chain fork detection.
+#[derive(Debug, PartialEq)]
+pub(crate) enum ForkError<E> {
+    /// Common ancestor of the confirmed block and the provided block
+    /// is deeper than the configured rollback depth.
+    TooDeep,
+
+    /// Parent hash lookup error.
+    Lookup(E),
+}
+
+/// Determine if the provided block diverges from the last confirmed block.
+///
+/// A block diverges if it either replaces an already confirmed block, or
+/// directly follows the confirmed block while referencing a different parent hash.
+///
+/// Diverged chains are walked back through their parent hashes, starting from
+/// the confirmed block hash and the provided parent hash, until the common ancestor is found.
+/// Parent hashes are requested with `parent_of`.
+///
+/// Returns the number of the common ancestor to roll back to, or [`None`]
+/// if the block can be processed as usual. If the common ancestor is more than `depth`
+/// blocks behind the confirmed block, [`ForkError::TooDeep`] is returned instead.
+pub(crate) async fn fork_rollback_target<F, Fut, E>(
+    confirmed_block: i64,
+    confirmed_block_hash: Option<&[u8]>,
+    block_number: u32,
+    parent_hash: &[u8],
+    depth: u32,
+    mut parent_of: F,
+) -> Result<Option<u32>, ForkError<E>>
+where
+    F: FnMut(Vec<u8>) -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, E>>,
+{
+    let Some(confirmed_block_hash) = confirmed_block_hash else {
+        return Ok(None);
+    };
+
+    let Ok(confirmed_block) = u32::try_from(confirmed_block) else {
+        return Ok(None);
+    };
+
+    if block_number == 0 || block_number > confirmed_block.saturating_add(1) {
+        return Ok(None);
+    }
+
+    let mut confirmed = (confirmed_block, confirmed_block_hash.to_vec());
+    let mut candidate = (block_number - 1, parent_hash.to_vec());
+
+    if confirmed == candidate {
+        return Ok(None);
+    }
+
+    let lowest = confirmed_block.saturating_sub(depth);
+
+    if candidate.0 < lowest {
+        return Err(ForkError::TooDeep);
+    }
+
+    loop {
+        if confirmed.0 > candidate.0 {
+            confirmed.1 = parent_of(confirmed.1).await.map_err(ForkError::Lookup)?;
+            confirmed.0 -= 1;
+        } else if confirmed.1 == candidate.1 {
+            return Ok(Some(candidate.0));
+        } else if candidate.0 <= lowest {
+            return Err(ForkError::TooDeep);
+        } else {
+            confirmed.1 = parent_of(confirmed.1).await.map_err(ForkError::Lookup)?;
+            candidate.1 = parent_of(candidate.1).await.map_err(ForkError::Lookup)?;
+            confirmed.0 -= 1;
+            candidate.0 -= 1;
+        }
+    }
+}
+
+/// Exponential backoff configuration.
+pub(crate) struct Backoff {
+    /// Maximum number of attempts, including the first one.
+    pub max_attempts: u32,
+
+    /// Delay before the first retry attempt.
+    pub initial_delay: Duration,
+
+    /// Upper bound for a delay between two attempts.
+    pub max_delay: Duration,
+}
+
+impl Backoff {
+    /// Get a delay that should precede the provided retry attempt.
+    ///
+    /// Attempt numbers start from 1, with each subsequent attempt doubling the delay.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay)
+    }
+}
+
+/// Retry the provided operation with an exponential backoff.
+///
+/// Errors, for which `retryable` returns `false`, are returned immediately.
+/// Otherwise, the operation is retried until the maximum attempt count is reached,
+/// calling `on_retry` before each subsequent attempt.
+pub(crate) async fn retry_with_backoff<T, E, F, Fut, R, L>(
+    backoff: &Backoff,
+    mut operation: F,
+    retryable: R,
+    mut on_retry: L,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    R: Fn(&E) -> bool,
+    L: FnMut(u32, &E, Duration),
+{
+    let mut attempt = 1;
+
+    loop {
+        match operation().await {
+            Ok(val) => return Ok(val),
+            Err(err) if retryable(&err) && attempt < backoff.max_attempts => {
+                let delay = backoff.delay(attempt);
+                on_retry(attempt, &err, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use common::rpc::sp_core::{
-        crypto::{AccountId32, Ss58Codec},
-        ByteArray,
+    use std::{
+        cell::Cell,
+        future::{ready, Ready},
+        rc::Rc,
+        time::{Duration, Instant},
     };
 
+    use common::rpc::{
+        sp_core::H256,
+        sp_core::{
+            crypto::{AccountId32, Ss58Codec},
+            ByteArray,
+        },
+        substrate_api_client::ac_primitives::StorageKey,
+    };
+    use futures_util::{stream, TryStreamExt};
+
+    use super::{
+        block_hash_stream, catch_up_range, fork_rollback_target, process_in_batches,
+        retry_with_backoff, Backoff, CatchUpProgress, ForkError, PROGRESS_INTERVAL,
+    };
+
+    const TEST_BACKOFF: Backoff = Backoff {
+        max_attempts: 3,
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(2),
+    };
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum StubError {
+        Transient,
+        Fatal,
+    }
+
+    /// RPC stub that fails with the provided errors before returning a value.
+    fn failing_stub(errors: Vec<StubError>) -> impl FnMut() -> Ready<Result<u32, StubError>> {
+        let mut calls = 0;
+        let mut errors = errors.into_iter();
+
+        move || {
+            calls += 1;
+            ready(errors.next().map(Err).unwrap_or(Ok(calls)))
+        }
+    }
+
+    /// Mocked storage stream with pages of the provided sizes.
+    fn storage_pages(sizes: &[u8]) -> Vec<Result<Vec<(StorageKey, u8)>, StubError>> {
+        let mut key = 0;
+
+        sizes
+            .iter()
+            .map(|size| {
+                Ok((0..*size)
+                    .map(|_| {
+                        key += 1;
+                        (StorageKey(vec![key]), key)
+                    })
+                    .collect())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn batching() {
+        let mut batches = Vec::new();
+
+        let processed = process_in_batches(
+            stream::iter(storage_pages(&[10, 10, 10, 5])),
+            20,
+            |batch, last_key, processed| {
+                batches.push((batch.len(), last_key.0, processed));
+                ready(Ok::<_, StubError>(()))
+            },
+        )
+        .await;
+
+        assert_eq!(processed, Ok(35));
+        assert_eq!(batches, vec![(20, vec![20], 20), (15, vec![35], 35)]);
+    }
+
+    #[tokio::test]
+    async fn empty_storage() {
+        let mut flushed = false;
+
+        let processed = process_in_batches(stream::iter(storage_pages(&[])), 20, |_, _, _| {
+            flushed = true;
+            ready(Ok::<_, StubError>(()))
+        })
+        .await;
+
+        assert_eq!(processed, Ok(0));
+        assert!(!flushed);
+    }
+
+    #[tokio::test]
+    async fn resume_key_points_to_last_stored_batch() {
+        let mut recorded_key = None;
+
+        let mut pages = storage_pages(&[10, 10, 10]);
+        pages.insert(2, Err(StubError::Transient));
+
+        let result = process_in_batches(stream::iter(pages), 10, |_, last_key, _| {
+            recorded_key = Some(last_key.0);
+            ready(Ok(()))
+        })
+        .await;
+
+        assert_eq!(result, Err(StubError::Transient));
+        assert_eq!(recorded_key, Some(vec![20]));
+    }
+
+    /// Synthetic chain of `(hash, parent hash)` pairs.
+    ///
+    /// Canonical chain A contains blocks from 1 to 12, while fork B diverges
+    /// from chain A after the provided block number.
+    fn synthetic_chain(fork_after: u8) -> Vec<([u8; 1], [u8; 1])> {
+        let canonical = (1..=12).map(|number| ([0xa0 + number], [0xa0 + number - 1]));
+        let fork = (fork_after + 1..=12).map(|number| {
+            let parent = if number == fork_after + 1 {
+                0xa0 + fork_after
+            } else {
+                0xb0 + number - 1
+            };
+
+            ([0xb0 + number], [parent])
+        });
+
+        canonical.chain(fork).collect()
+    }
+
+    /// Parent hash lookup of the provided synthetic chain.
+    fn parent_of(
+        chain: &[([u8; 1], [u8; 1])],
+    ) -> impl FnMut(Vec<u8>) -> Ready<Result<Vec<u8>, StubError>> + '_ {
+        move |hash| {
+            ready(
+                chain
+                    .iter()
+                    .find(|(block_hash, _)| block_hash[..] == hash[..])
+                    .map(|(_, parent)| parent.to_vec())
+                    .ok_or(StubError::Fatal),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn synthetic_fork_sequence() {
+        /// Synthetic block: number, hash and parent hash.
+        type SyntheticBlock = (u32, [u8; 1], [u8; 1]);
+
+        let chain = synthetic_chain(2);
+
+        // Canonical chain A: 1 <- 2 <- 3, fork B replaces block 3 and continues with 4.
+        let sequence: [(SyntheticBlock, Option<u32>); 5] = [
+            ((1, [0xa1], [0xa0]), None),
+            ((2, [0xa2], [0xa1]), None),
+            ((3, [0xa3], [0xa2]), None),
+            ((3, [0xb3], [0xa2]), Some(2)),
+            ((4, [0xb4], [0xb3]), None),
+        ];
+
+        let mut confirmed_block = 0i64;
+        let mut confirmed_block_hash = vec![0xa0];
+
+        for ((number, hash, parent), expected) in sequence {
+            let target = fork_rollback_target(
+                confirmed_block,
+                Some(&confirmed_block_hash),
+                number,
+                &parent,
+                1,
+                parent_of(&chain),
+            )
+            .await;
+
+            assert_eq!(
+                target,
+                Ok(expected),
+                "unexpected rollback target for block {number}"
+            );
+
+            // Rolled back blocks are re-processed from the canonical chain.
+            confirmed_block = i64::from(number);
+            confirmed_block_hash = hash.to_vec();
+        }
+    }
+
+    #[tokio::test]
+    async fn fork_with_mismatched_parent() {
+        let chain = synthetic_chain(8);
+
+        assert_eq!(
+            fork_rollback_target(10, Some(&[0xaa]), 11, &[0xaa], 4, parent_of(&chain)).await,
+            Ok(None)
+        );
+
+        // Rollback stops at the common ancestor instead of the maximum depth.
+        assert_eq!(
+            fork_rollback_target(10, Some(&[0xaa]), 11, &[0xba], 4, parent_of(&chain)).await,
+            Ok(Some(8))
+        );
+
+        // Blocks that replace older confirmed blocks are handled as well.
+        assert_eq!(
+            fork_rollback_target(10, Some(&[0xaa]), 10, &[0xb9], 4, parent_of(&chain)).await,
+            Ok(Some(8))
+        );
+    }
+
+    #[tokio::test]
+    async fn fork_deeper_than_rollback_depth() {
+        let chain = synthetic_chain(5);
+
+        assert_eq!(
+            fork_rollback_target(10, Some(&[0xaa]), 11, &[0xba], 4, parent_of(&chain)).await,
+            Err(ForkError::TooDeep)
+        );
+        assert_eq!(
+            fork_rollback_target(10, Some(&[0xaa]), 3, &[0xa2], 4, parent_of(&chain)).await,
+            Err(ForkError::TooDeep)
+        );
+
+        // The same fork is accepted with a sufficient rollback depth.
+        assert_eq!(
+            fork_rollback_target(10, Some(&[0xaa]), 11, &[0xba], 5, parent_of(&chain)).await,
+            Ok(Some(5))
+        );
+    }
+
+    #[tokio::test]
+    async fn fork_with_unknown_parent() {
+        let chain = synthetic_chain(8);
+
+        assert_eq!(
+            fork_rollback_target(10, Some(&[0xaa]), 11, &[0xff], 4, parent_of(&chain)).await,
+            Err(ForkError::Lookup(StubError::Fatal))
+        );
+    }
+
+    #[tokio::test]
+    async fn fork_without_confirmed_hash() {
+        let chain = synthetic_chain(8);
+
+        assert_eq!(
+            fork_rollback_target(10, None, 11, &[0xba], 4, parent_of(&chain)).await,
+            Ok(None)
+        );
+        assert_eq!(
+            fork_rollback_target(10, None, 5, &[0xb4], 4, parent_of(&chain)).await,
+            Ok(None)
+        );
+    }
+
+    #[tokio::test]
+    async fn gap_is_not_a_fork() {
+        let chain = synthetic_chain(8);
+
+        assert_eq!(
+            fork_rollback_target(10, Some(&[0xaa]), 15, &[0xff], 4, parent_of(&chain)).await,
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn backoff_delay() {
+        let backoff = Backoff {
+            max_attempts: 10,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+        };
+
+        assert_eq!(backoff.delay(1), Duration::from_secs(1));
+        assert_eq!(backoff.delay(2), Duration::from_secs(2));
+        assert_eq!(backoff.delay(3), Duration::from_secs(4));
+        assert_eq!(backoff.delay(4), Duration::from_secs(5));
+        assert_eq!(backoff.delay(32), Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn retry_transient_errors() {
+        let mut retries = Vec::new();
+
+        let result = retry_with_backoff(
+            &TEST_BACKOFF,
+            failing_stub(vec![StubError::Transient, StubError::Transient]),
+            |err| *err == StubError::Transient,
+            |attempt, _, _| retries.push(attempt),
+        )
+        .await;
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(retries, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn retry_exhausted() {
+        let result = retry_with_backoff(
+            &TEST_BACKOFF,
+            failing_stub(vec![StubError::Transient; 3]),
+            |err| *err == StubError::Transient,
+            |_, _, _| {},
+        )
+        .await;
+
+        assert_eq!(result, Err(StubError::Transient));
+    }
+
+    #[tokio::test]
+    async fn fatal_errors_are_not_retried() {
+        let mut retries = 0;
+
+        let result = retry_with_backoff(
+            &TEST_BACKOFF,
+            failing_stub(vec![StubError::Fatal]),
+            |err| *err == StubError::Transient,
+            |_, _, _| retries += 1,
+        )
+        .await;
+
+        assert_eq!(result, Err(StubError::Fatal));
+        assert_eq!(retries, 0);
+    }
+
+    #[tokio::test]
+    async fn block_hash_batching() {
+        let in_flight = Rc::new(Cell::new(0));
+        let max_in_flight = Rc::new(Cell::new(0));
+
+        let blocks: Vec<_> = block_hash_stream(0..10, 4, |block_number| {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+
+            async move {
+                in_flight.set(in_flight.get() + 1);
+                max_in_flight.set(max_in_flight.get().max(in_flight.get()));
+
+                // Later blocks resolve first, which verifies that the block order is preserved.
+                for _ in block_number..10 {
+                    tokio::task::yield_now().await;
+                }
+
+                in_flight.set(in_flight.get() - 1);
+
+                // RPC node does not provide a hash of one of the blocks.
+                Ok::<_, StubError>(
+                    (block_number != 5).then(|| H256::repeat_byte(block_number as u8)),
+                )
+            }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(max_in_flight.get(), 4);
+        assert_eq!(
+            blocks,
+            (0..10)
+                .filter(|block_number| *block_number != 5)
+                .map(|block_number| (block_number, H256::repeat_byte(block_number as u8)))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn block_hash_errors() {
+        let result = block_hash_stream(0..10, 4, |block_number| {
+            ready(if block_number == 3 {
+                Err(StubError::Fatal)
+            } else {
+                Ok(Some(H256::zero()))
+            })
+        })
+        .try_collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(result, Err(StubError::Fatal));
+    }
+
+    #[test]
+    fn catch_up_range_guard() {
+        assert_eq!(catch_up_range(100, 150, 1000), Ok(101..=150));
+        assert_eq!(catch_up_range(100, 1100, 1000), Ok(101..=1100));
+        assert_eq!(catch_up_range(100, 1101, 1000), Err(1001));
+        assert!(catch_up_range(100, 100, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn catch_up_progress() {
+        let started_at = Instant::now();
+        let mut progress = CatchUpProgress::started_at(3000, started_at);
+
+        for _ in 1..PROGRESS_INTERVAL {
+            assert_eq!(progress.record_at(started_at), None);
+        }
+
+        assert_eq!(
+            progress.record_at(started_at + Duration::from_secs(10)),
+            Some((1000, Duration::from_secs(20)))
+        );
+    }
+
     #[test]
     fn extract_twox_account_id() {
         let account_id =
@@ -0,0 +1,63 @@
+use db::{
+    build_session, sea_query::Query, selector, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect,
+};
+use tracing::{info, warn};
+
+/// Populate message selectors using the metadata of successfully finished build sessions.
+///
+/// Only build sessions with code hashes that have no stored selectors are processed.
+pub(crate) async fn selectors(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let build_session_ids = build_session::Entity::find()
+        .select_only()
+        .column(build_session::Column::Id)
+        .filter(build_session::Column::Status.eq(build_session::Status::Completed))
+        .filter(build_session::Column::Metadata.is_not_null())
+        .filter(
+            build_session::Column::CodeHash.not_in_subquery(
+                Query::select()
+                    .column(selector::Column::CodeHash)
+                    .from(selector::Entity)
+                    .to_owned(),
+            ),
+        )
+        .order_by_asc(build_session::Column::Id)
+        .into_tuple::<i64>()
+        .all(db)
+        .await?;
+
+    info!(
+        count = build_session_ids.len(),
+        "backfilling message selectors"
+    );
+
+    let mut stored = 0;
+
+    // Metadata is loaded separately for each build session to keep memory usage low.
+    for build_session_id in build_session_ids {
+        let Some((code_hash, metadata)) = build_session::Entity::find_by_id(build_session_id)
+            .select_only()
+            .columns([
+                build_session::Column::CodeHash,
+                build_session::Column::Metadata,
+            ])
+            .into_tuple::<(Vec<u8>, Vec<u8>)>()
+            .one(db)
+            .await?
+        else {
+            continue;
+        };
+
+        let count = selector::store(db, &code_hash, &metadata).await?;
+
+        if count == 0 {
+            warn!(%build_session_id, "no message selectors found in metadata");
+        }
+
+        stored += count;
+    }
+
+    info!(%stored, "message selectors backfilled");
+
+    Ok(())
+}
@@ -1,14 +1,23 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use sea_orm_cli::MigrateSubcommands;
 
 #[derive(Parser)]
 pub(crate) struct Cli {
     #[clap(subcommand)]
-    pub command: Option<MigrateSubcommands>,
+    pub command: Option<Command>,
 
     /// Path to configuration file.
     #[clap(short, long, value_parser)]
     pub config: Option<PathBuf>,
 }
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    #[clap(flatten)]
+    Migrate(MigrateSubcommands),
+
+    /// Populate message selectors of contracts built before selectors were tracked.
+    BackfillSelectors,
+}
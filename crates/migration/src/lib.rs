@@ -17,10 +17,43 @@ mod m20220101_000014_remove_node_schema;
 mod m20220101_000015_remove_rust_version;
 mod m20220101_000016_add_project_directory;
 mod m20220101_000017_create_diagnostics_table;
+mod m20220101_000018_add_node_last_error;
+mod m20220101_000019_add_node_initialization_progress;
+mod m20220101_000020_add_block_tracking;
+mod m20220101_000021_create_skipped_blocks_table;
+mod m20220101_000022_add_user_public_builds;
+mod m20220101_000023_create_webhooks_table;
+mod m20220101_000024_add_build_session_stage_durations;
+mod m20220101_000025_add_build_session_image_digest;
+mod m20220101_000026_add_build_session_diagnostics_pending;
+mod m20220101_000027_add_build_session_contract_name;
+mod m20220101_000028_create_build_session_stages_table;
+mod m20220101_000029_create_selectors_table;
+mod m20220101_000030_add_node_display_metadata;
+mod m20220101_000031_add_build_session_metadata_hash;
+mod m20220101_000032_convert_event_body_to_json;
+mod m20220101_000033_add_build_session_token_created_at;
+mod m20220101_000034_add_build_session_logs_truncated;
+mod m20220101_000035_add_diagnostic_positions;
+mod m20220101_000036_add_log_repeat_count;
+mod m20220101_000037_add_build_session_artifact_kind;
+mod m20220101_000038_add_authentication_token_scope;
+mod m20220101_000039_add_source_code_visibility;
+mod m20220101_000040_add_events_unique_index;
+mod m20220101_000041_add_build_session_builder_info;
+mod m20220101_000042_create_signing_keys_table;
+mod m20220101_000043_add_node_last_block_seen_at;
+mod m20220101_000044_hash_cli_tokens;
+mod m20220101_000045_drop_build_session_logs_truncated;
+
+/// Test database harness shared between workspace crates.
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 pub(crate) use m20220101_000001_create_users_table::Users;
 pub(crate) use m20220101_000003_create_authentication_tokens_table::AuthenticationTokens;
 pub(crate) use m20220101_000004_create_nodes_table::Nodes;
+pub(crate) use m20220101_000005_create_codes_table::Codes;
 pub(crate) use m20220101_000007_create_source_codes_table::SourceCodes;
 pub(crate) use m20220101_000008_create_files_table::Files;
 pub(crate) use m20220101_000009_create_build_sessions_table::BuildSessions;
@@ -48,6 +81,34 @@ impl MigratorTrait for Migrator {
             Box::new(m20220101_000015_remove_rust_version::Migration),
             Box::new(m20220101_000016_add_project_directory::Migration),
             Box::new(m20220101_000017_create_diagnostics_table::Migration),
+            Box::new(m20220101_000018_add_node_last_error::Migration),
+            Box::new(m20220101_000019_add_node_initialization_progress::Migration),
+            Box::new(m20220101_000020_add_block_tracking::Migration),
+            Box::new(m20220101_000021_create_skipped_blocks_table::Migration),
+            Box::new(m20220101_000022_add_user_public_builds::Migration),
+            Box::new(m20220101_000023_create_webhooks_table::Migration),
+            Box::new(m20220101_000024_add_build_session_stage_durations::Migration),
+            Box::new(m20220101_000025_add_build_session_image_digest::Migration),
+            Box::new(m20220101_000026_add_build_session_diagnostics_pending::Migration),
+            Box::new(m20220101_000027_add_build_session_contract_name::Migration),
+            Box::new(m20220101_000028_create_build_session_stages_table::Migration),
+            Box::new(m20220101_000029_create_selectors_table::Migration),
+            Box::new(m20220101_000030_add_node_display_metadata::Migration),
+            Box::new(m20220101_000031_add_build_session_metadata_hash::Migration),
+            Box::new(m20220101_000032_convert_event_body_to_json::Migration),
+            Box::new(m20220101_000033_add_build_session_token_created_at::Migration),
+            Box::new(m20220101_000034_add_build_session_logs_truncated::Migration),
+            Box::new(m20220101_000035_add_diagnostic_positions::Migration),
+            Box::new(m20220101_000036_add_log_repeat_count::Migration),
+            Box::new(m20220101_000037_add_build_session_artifact_kind::Migration),
+            Box::new(m20220101_000038_add_authentication_token_scope::Migration),
+            Box::new(m20220101_000039_add_source_code_visibility::Migration),
+            Box::new(m20220101_000040_add_events_unique_index::Migration),
+            Box::new(m20220101_000041_add_build_session_builder_info::Migration),
+            Box::new(m20220101_000042_create_signing_keys_table::Migration),
+            Box::new(m20220101_000043_add_node_last_block_seen_at::Migration),
+            Box::new(m20220101_000044_hash_cli_tokens::Migration),
+            Box::new(m20220101_000045_drop_build_session_logs_truncated::Migration),
         ]
     }
 }
@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SkippedBlocks::Table)
+                    .col(
+                        ColumnDef::new(SkippedBlocks::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SkippedBlocks::NodeId).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(SkippedBlocks::BlockNumber)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SkippedBlocks::BlockHash).binary().not_null())
+                    .col(ColumnDef::new(SkippedBlocks::Error).text().not_null())
+                    .col(
+                        ColumnDef::new(SkippedBlocks::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_string()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SkippedBlocks::Table, SkippedBlocks::NodeId)
+                            .to(crate::Nodes::Table, crate::Nodes::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("node_id_block_number_skipped_blocks_idx")
+                            .col(SkippedBlocks::NodeId)
+                            .col(SkippedBlocks::BlockNumber)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SkippedBlocks::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum SkippedBlocks {
+    Table,
+    Id,
+    NodeId,
+    BlockNumber,
+    BlockHash,
+    Error,
+    CreatedAt,
+}
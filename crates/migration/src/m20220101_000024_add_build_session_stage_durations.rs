@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite does not support multiple alter options in a single statement.
+        for column in BuildSessions::STAGE_DURATIONS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(BuildSessions::Table)
+                        .add_column(ColumnDef::new(column).big_integer())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in BuildSessions::STAGE_DURATIONS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(BuildSessions::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden, Clone, Copy)]
+pub(crate) enum BuildSessions {
+    Table,
+    UnarchiveDuration,
+    BuildDuration,
+    MoveDuration,
+}
+
+impl BuildSessions {
+    /// Columns that store build session stage durations.
+    const STAGE_DURATIONS: [Self; 3] = [
+        Self::UnarchiveDuration,
+        Self::BuildDuration,
+        Self::MoveDuration,
+    ];
+}
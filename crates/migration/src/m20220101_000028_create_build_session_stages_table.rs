@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BuildSessionStages::Table)
+                    .col(
+                        ColumnDef::new(BuildSessionStages::BuildSessionId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(BuildSessionStages::Stage)
+                            .string()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(
+                                BuildSessionStages::Table,
+                                BuildSessionStages::BuildSessionId,
+                            )
+                            .to(crate::BuildSessions::Table, crate::BuildSessions::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BuildSessionStages::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum BuildSessionStages {
+    Table,
+    BuildSessionId,
+    Stage,
+}
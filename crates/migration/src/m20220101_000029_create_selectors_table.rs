@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Selectors::Table)
+                    .col(
+                        ColumnDef::new(Selectors::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Selectors::CodeHash).binary().not_null())
+                    .col(ColumnDef::new(Selectors::Selector).binary().not_null())
+                    .col(ColumnDef::new(Selectors::MessageName).string().not_null())
+                    .col(ColumnDef::new(Selectors::Mutates).boolean().not_null())
+                    .index(
+                        Index::create()
+                            .name("selector_code_hash_selectors_idx")
+                            .col(Selectors::Selector)
+                            .col(Selectors::CodeHash)
+                            .unique(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Selectors::Table, Selectors::CodeHash)
+                            .to(crate::Codes::Table, crate::Codes::Hash)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Selectors::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Selectors {
+    Table,
+    Id,
+    CodeHash,
+    Selector,
+    MessageName,
+    Mutates,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite does not support multiple alter options in a single statement.
+        for mut column in [
+            ColumnDef::new(Nodes::DisplayName).string().to_owned(),
+            ColumnDef::new(Nodes::ExplorerUrlTemplate)
+                .string()
+                .to_owned(),
+            ColumnDef::new(Nodes::TokenSymbol).string().to_owned(),
+            ColumnDef::new(Nodes::TokenDecimals)
+                .small_integer()
+                .to_owned(),
+            ColumnDef::new(Nodes::Ss58Prefix).small_integer().to_owned(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Nodes::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in Nodes::DISPLAY_METADATA {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Nodes::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden, Clone, Copy)]
+pub(crate) enum Nodes {
+    Table,
+    DisplayName,
+    ExplorerUrlTemplate,
+    TokenSymbol,
+    TokenDecimals,
+    Ss58Prefix,
+}
+
+impl Nodes {
+    /// Columns that store node display metadata.
+    const DISPLAY_METADATA: [Self; 5] = [
+        Self::DisplayName,
+        Self::ExplorerUrlTemplate,
+        Self::TokenSymbol,
+        Self::TokenDecimals,
+        Self::Ss58Prefix,
+    ];
+}
@@ -0,0 +1,194 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, DatabaseBackend},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite stores JSON values as text, thus existing rows are already
+        // in the correct format. PostgreSQL rows are rewritten in place.
+        if manager.get_database_backend() == DatabaseBackend::Postgres {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "ALTER TABLE events ALTER COLUMN body TYPE jsonb USING body::jsonb",
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() == DatabaseBackend::Postgres {
+            manager
+                .get_connection()
+                .execute_unprepared(
+                    "ALTER TABLE events ALTER COLUMN body TYPE varchar USING body::text",
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{event, node, ActiveValue, EntityTrait, OffsetDateTime, PrimitiveDateTime};
+    use sea_orm_migration::{
+        prelude::*,
+        sea_orm::{ConnectionTrait, DatabaseConnection},
+    };
+
+    use crate::{
+        testing::{create_database, create_empty_database},
+        Migrator,
+    };
+
+    /// Count of migrations applied before the event body conversion.
+    const PREVIOUS_MIGRATIONS: u32 = 31;
+
+    /// Learn more at https://docs.rs/sea-query#iden
+    #[derive(Iden)]
+    enum Events {
+        Table,
+        NodeId,
+        Account,
+        EventType,
+        Body,
+        BlockTimestamp,
+    }
+
+    /// Insert an event with a pre-serialized body, as it was stored before the conversion.
+    async fn insert_string_body(db: &DatabaseConnection, node_id: i64, body: &str) {
+        let statement = Query::insert()
+            .into_table(Events::Table)
+            .columns([
+                Events::NodeId,
+                Events::Account,
+                Events::EventType,
+                Events::Body,
+                Events::BlockTimestamp,
+            ])
+            .values_panic([
+                node_id.into(),
+                vec![1u8; 32].into(),
+                0i16.into(),
+                body.into(),
+                Expr::current_timestamp().into(),
+            ])
+            .to_owned();
+
+        db.execute(db.get_database_backend().build(&statement))
+            .await
+            .expect("unable to insert event");
+    }
+
+    #[tokio::test]
+    async fn string_bodies() {
+        let db = create_empty_database().await;
+
+        Migrator::up(&db, Some(PREVIOUS_MIGRATIONS))
+            .await
+            .expect("unable to run previous migrations");
+
+        node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert node");
+
+        let node_id = node::Entity::find()
+            .one(&db)
+            .await
+            .expect("unable to get node")
+            .expect("node not found")
+            .id;
+
+        insert_string_body(&db, node_id, r#""Instantiation""#).await;
+        insert_string_body(
+            &db,
+            node_id,
+            &format!(
+                r#"{{"CodeHashUpdate":{{"new_code_hash":"{}"}}}}"#,
+                "00".repeat(32)
+            ),
+        )
+        .await;
+
+        Migrator::up(&db, None)
+            .await
+            .expect("unable to run migrations");
+
+        let bodies = event::Entity::find()
+            .all(&db)
+            .await
+            .expect("unable to get events")
+            .into_iter()
+            .map(|model| model.body)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            bodies,
+            [
+                event::EventBody::Instantiation {
+                    deployer: String::new()
+                },
+                event::EventBody::CodeHashUpdate {
+                    new_code_hash: "00".repeat(32)
+                }
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn typed_round_trip() {
+        let db = create_database().await;
+
+        node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert node");
+
+        let node_id = node::Entity::find()
+            .one(&db)
+            .await
+            .expect("unable to get node")
+            .expect("node not found")
+            .id;
+
+        let body = event::EventBody::CodeHashUpdate {
+            new_code_hash: "01".repeat(32),
+        };
+
+        let now = OffsetDateTime::now_utc();
+
+        let model = event::Entity::insert(event::ActiveModel {
+            node_id: ActiveValue::Set(node_id),
+            account: ActiveValue::Set(vec![1; 32]),
+            event_type: ActiveValue::Set(event::EventType::CodeHashUpdate),
+            body: ActiveValue::Set(body.clone()),
+            block_timestamp: ActiveValue::Set(PrimitiveDateTime::new(now.date(), now.time())),
+            ..Default::default()
+        })
+        .exec_with_returning(&db)
+        .await
+        .expect("unable to insert event");
+
+        assert_eq!(model.body, body);
+    }
+}
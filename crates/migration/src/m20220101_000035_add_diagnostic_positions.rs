@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite does not support multiple alter options in a single statement.
+        for column in Diagnostics::POSITIONS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Diagnostics::Table)
+                        .add_column(ColumnDef::new(column).big_integer())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in Diagnostics::POSITIONS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Diagnostics::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden, Clone, Copy)]
+pub(crate) enum Diagnostics {
+    Table,
+    StartLine,
+    StartCol,
+    EndLine,
+    EndCol,
+}
+
+impl Diagnostics {
+    /// Columns that store line and column numbers of diagnostics.
+    const POSITIONS: [Self; 4] = [Self::StartLine, Self::StartCol, Self::EndLine, Self::EndCol];
+}
@@ -0,0 +1,42 @@
+use db::token::Scope;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthenticationTokens::Table)
+                    .add_column(
+                        ColumnDef::new(AuthenticationTokens::Scope)
+                            .small_integer()
+                            .not_null()
+                            .default(Scope::Full),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthenticationTokens::Table)
+                    .drop_column(AuthenticationTokens::Scope)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(crate) enum AuthenticationTokens {
+    Table,
+    Scope,
+}
@@ -0,0 +1,42 @@
+use db::source_code::Visibility;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SourceCodes::Table)
+                    .add_column(
+                        ColumnDef::new(SourceCodes::Visibility)
+                            .small_integer()
+                            .not_null()
+                            .default(Visibility::Public),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SourceCodes::Table)
+                    .drop_column(SourceCodes::Visibility)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(crate) enum SourceCodes {
+    Table,
+    Visibility,
+}
@@ -0,0 +1,120 @@
+use sea_orm_migration::{prelude::*, sea_orm::ConnectionTrait};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Name of the unique index, which prevents duplicate events of reprocessed blocks.
+const INDEX_NAME: &str = "node_id_account_event_type_block_number_events_idx";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Blocks reprocessed after a crash may have already produced duplicate events,
+        // only the earliest of them is preserved. Events without block numbers are left as-is.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM events WHERE block_number IS NOT NULL AND id NOT IN (
+                    SELECT MIN(id) FROM events WHERE block_number IS NOT NULL
+                    GROUP BY node_id, account, event_type, block_number
+                )",
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(INDEX_NAME)
+                    .table(Events::Table)
+                    .col(Events::NodeId)
+                    .col(Events::Account)
+                    .col(Events::EventType)
+                    .col(Events::BlockNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(INDEX_NAME)
+                    .table(Events::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Events {
+    Table,
+    NodeId,
+    Account,
+    EventType,
+    BlockNumber,
+}
+
+#[cfg(test)]
+mod tests {
+    use db::{event, node, ActiveValue, EntityTrait, OffsetDateTime, PrimitiveDateTime};
+    use sea_orm_migration::prelude::*;
+
+    use crate::{testing::create_empty_database, Migrator};
+
+    /// Count of migrations applied before the unique index creation.
+    const PREVIOUS_MIGRATIONS: u32 = 39;
+
+    #[tokio::test]
+    async fn duplicate_events() {
+        let db = create_empty_database().await;
+
+        Migrator::up(&db, Some(PREVIOUS_MIGRATIONS))
+            .await
+            .expect("unable to run previous migrations");
+
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_with_returning(&db)
+        .await
+        .expect("unable to insert node");
+
+        let now = OffsetDateTime::now_utc();
+
+        event::Entity::insert_many([Some(1), Some(1), Some(2), None, None].map(|block_number| {
+            event::ActiveModel {
+                node_id: ActiveValue::Set(node.id),
+                account: ActiveValue::Set(vec![1; 32]),
+                event_type: ActiveValue::Set(event::EventType::Termination),
+                body: ActiveValue::Set(event::EventBody::Termination),
+                block_timestamp: ActiveValue::Set(PrimitiveDateTime::new(now.date(), now.time())),
+                block_number: ActiveValue::Set(block_number),
+                ..Default::default()
+            }
+        }))
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert events");
+
+        Migrator::up(&db, None)
+            .await
+            .expect("unable to run migrations");
+
+        let events = event::Entity::find()
+            .all(&db)
+            .await
+            .expect("unable to get events")
+            .into_iter()
+            .map(|model| (model.id, model.block_number))
+            .collect::<Vec<_>>();
+
+        assert_eq!(events, [(1, Some(1)), (3, Some(2)), (4, None), (5, None)]);
+    }
+}
@@ -1,9 +1,10 @@
+mod backfill;
 mod cli;
 
 use std::error::Error;
 
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Command};
 use common::config::Config;
 use migration::{cli::run_migrate, sea_orm::Database};
 use tracing::info;
@@ -18,7 +19,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let db = Database::connect(&config.database.url).await?;
     info!("database connection established");
 
-    run_migrate(migration::Migrator, &db, cli.command, false).await?;
+    match cli.command {
+        Some(Command::BackfillSelectors) => backfill::selectors(&db).await?,
+        Some(Command::Migrate(command)) => {
+            run_migrate(migration::Migrator, &db, Some(command), false).await?
+        }
+        None => run_migrate(migration::Migrator, &db, None, false).await?,
+    }
 
     Ok(())
 }
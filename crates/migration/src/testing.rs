@@ -0,0 +1,123 @@
+use std::{
+    env, future,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
+use sea_orm_migration::{
+    sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbErr},
+    MigratorTrait,
+};
+
+use crate::Migrator;
+
+/// Environment variable that contains a PostgreSQL database URL used by tests.
+///
+/// If it is not set, tests use an in-memory SQLite database instead.
+pub const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
+
+/// Counter used to generate unique schema names inside of a single test process.
+static SCHEMA_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Create a test database with all migrations applied.
+///
+/// If the [`TEST_DATABASE_URL`] environment variable is set, each call creates a new
+/// PostgreSQL schema, which is dropped after the current Tokio runtime shuts down.
+pub async fn create_database() -> DatabaseConnection {
+    let db = create_empty_database().await;
+
+    Migrator::up(&db, None)
+        .await
+        .expect("unable to run migrations");
+
+    db
+}
+
+/// Create a test database without applying any migrations.
+///
+/// Used to test data migrations, which require the database to be
+/// populated before the migration is applied.
+pub async fn create_empty_database() -> DatabaseConnection {
+    match env::var(TEST_DATABASE_URL) {
+        Ok(url) => create_schema(url).await,
+        Err(_) => Database::connect("sqlite::memory:")
+            .await
+            .expect("unable to create test database"),
+    }
+}
+
+/// Create a uniquely named PostgreSQL schema and connect to it.
+async fn create_schema(url: String) -> DatabaseConnection {
+    let schema = format!(
+        "test_{}_{}",
+        std::process::id(),
+        SCHEMA_COUNTER.fetch_add(1, Ordering::Relaxed)
+    );
+
+    // Schemas left by a crashed test process with the same PID are removed as well.
+    Database::connect(url.as_str())
+        .await
+        .expect("unable to connect to test database")
+        .execute_unprepared(&format!(
+            "DROP SCHEMA IF EXISTS {schema} CASCADE; CREATE SCHEMA {schema}"
+        ))
+        .await
+        .expect("unable to create test schema");
+
+    let mut options = ConnectOptions::new(url.clone());
+    options.set_schema_search_path(schema.clone());
+
+    let db = Database::connect(options)
+        .await
+        .expect("unable to connect to test schema");
+
+    let guard = SchemaGuard { url, schema };
+
+    // Spawned tasks are dropped during runtime shutdown, even if the test panics.
+    tokio::spawn(async move {
+        let _guard = guard;
+        future::pending::<()>().await
+    });
+
+    db
+}
+
+/// Test schema, which is dropped together with this guard.
+struct SchemaGuard {
+    /// PostgreSQL database URL.
+    url: String,
+
+    /// Test schema name.
+    schema: String,
+}
+
+impl Drop for SchemaGuard {
+    fn drop(&mut self) {
+        let url = self.url.clone();
+        let statement = format!(
+            "SET lock_timeout = '5s'; DROP SCHEMA IF EXISTS {} CASCADE",
+            self.schema
+        );
+
+        // The test runtime is already shutting down, so a separate one is used instead.
+        let result = thread::spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("unable to create runtime")
+                .block_on(async {
+                    Database::connect(url.as_str())
+                        .await?
+                        .execute_unprepared(&statement)
+                        .await?;
+
+                    Ok::<_, DbErr>(())
+                })
+        })
+        .join();
+
+        if !matches!(result, Ok(Ok(()))) {
+            eprintln!("unable to drop test schema {}", self.schema);
+        }
+    }
+}
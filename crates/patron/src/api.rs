@@ -0,0 +1,351 @@
+use std::fmt;
+
+use api_types::build_sessions::{BuildSessionCreateResponse, BuildSessionLogsResponse};
+use reqwest::{multipart::Form, Client, Response};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    config::AuthenticationConfig,
+    process::{BuildSessionCreateRequest, BuildSessionStatus, ExistingCodeHashResponse},
+};
+
+/// Patron API server client.
+///
+/// A single [`Client`] is reused for all requests, which allows
+/// to reuse connections and TLS sessions between them.
+pub(crate) struct ApiClient {
+    /// Underlying HTTP client.
+    client: Client,
+
+    /// API server base URL.
+    server_path: String,
+
+    /// Authentication token passed with each request.
+    token: String,
+}
+
+impl ApiClient {
+    /// Create new [`ApiClient`] using the provided authentication configuration.
+    pub fn new(auth_config: &AuthenticationConfig) -> Self {
+        Self {
+            client: Client::new(),
+            server_path: String::from(auth_config.server_path()),
+            token: String::from(auth_config.token()),
+        }
+    }
+
+    /// Retrieve the latest build session started using the source code archive
+    /// with the provided hash.
+    ///
+    /// Returns [`None`] if there are no such build sessions.
+    pub async fn latest(
+        &self,
+        archive_hash: &str,
+    ) -> Result<Option<ExistingCodeHashResponse>, reqwest::Error> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/buildSessions/latest/{archive_hash}",
+                self.server_path
+            ))
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+
+        response.json().await.map(Some)
+    }
+
+    /// Upload source code archive using the provided multipart form.
+    ///
+    /// Response is returned as is, since upload rejections are handled by the caller.
+    pub async fn upload_source(&self, form: Form) -> Result<Response, reqwest::Error> {
+        self.client
+            .post(format!("{}/sourceCode", self.server_path))
+            .bearer_auth(&self.token)
+            .multipart(form)
+            .send()
+            .await
+    }
+
+    /// Create new build session.
+    pub async fn create_build_session(
+        &self,
+        request: &BuildSessionCreateRequest<'_>,
+    ) -> Result<BuildSessionCreateResponse, reqwest::Error> {
+        self.client
+            .post(format!("{}/buildSessions", self.server_path))
+            .bearer_auth(&self.token)
+            .json(request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Get a page of build sessions of the current user.
+    ///
+    /// Server's default page size is used if `limit` is not provided.
+    pub async fn build_sessions<T: DeserializeOwned>(
+        &self,
+        page: u64,
+        limit: Option<u64>,
+    ) -> Result<Vec<T>, reqwest::Error> {
+        let mut request = self
+            .client
+            .get(format!("{}/buildSessions", self.server_path))
+            .query(&[("page", page)]);
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+
+        request
+            .bearer_auth(&self.token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Get logs of a build session identified by either numeric identifier or code hash.
+    ///
+    /// Only log entries with identifiers greater than `position` are returned.
+    pub async fn logs(
+        &self,
+        id: impl fmt::Display,
+        position: i64,
+    ) -> Result<BuildSessionLogsResponse, reqwest::Error> {
+        self.client
+            .get(format!("{}/buildSessions/logs/{id}", self.server_path))
+            .query(&[("position", position)])
+            .bearer_auth(&self.token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Get status of a build session identified by either numeric identifier or code hash.
+    pub async fn status(
+        &self,
+        id: impl fmt::Display,
+    ) -> Result<BuildSessionStatus, reqwest::Error> {
+        self.client
+            .get(format!("{}/buildSessions/status/{id}", self.server_path))
+            .bearer_auth(&self.token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Request WASM blob with the provided code hash.
+    ///
+    /// Contract blobs are returned regardless of their kind, use [`ApiClient::artifact`]
+    /// with servers that advertise artifact kinds.
+    pub async fn wasm(&self, code_hash: &str) -> Result<Response, reqwest::Error> {
+        self.download(&format!("wasm/{code_hash}")).await
+    }
+
+    /// Request WASM or PolkaVM blob with the provided code hash.
+    pub async fn artifact(&self, code_hash: &str) -> Result<Response, reqwest::Error> {
+        self.download(&format!("artifact/{code_hash}")).await
+    }
+
+    /// Request JSON metadata of a contract with the provided code hash.
+    pub async fn metadata(&self, code_hash: &str) -> Result<Response, reqwest::Error> {
+        self.download(&format!("metadata/{code_hash}")).await
+    }
+
+    /// Request the provided build session route.
+    ///
+    /// Successful responses are returned without reading their bodies,
+    /// which allows to stream them to the caller-provided destination.
+    async fn download(&self, route: &str) -> Result<Response, reqwest::Error> {
+        self.client
+            .get(format!("{}/buildSessions/{route}", self.server_path))
+            .bearer_auth(&self.token)
+            .send()
+            .await?
+            .error_for_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use reqwest::{
+        multipart::{Form, Part},
+        StatusCode,
+    };
+    use serde_json::Value;
+
+    use super::ApiClient;
+    use crate::{
+        config::AuthenticationConfig, process::BuildSessionCreateRequest, testing::stub_server,
+    };
+
+    /// Start a stub server, that records request paths and responds
+    /// to each request with the provided response.
+    async fn api_server(status: u16, body: &'static str) -> (ApiClient, Arc<Mutex<Vec<String>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        let server = stub_server(move |path| {
+            recorded.lock().unwrap().push(String::from(path));
+            (status, String::from(body))
+        })
+        .await;
+
+        (
+            ApiClient::new(&AuthenticationConfig::for_tests(server)),
+            requests,
+        )
+    }
+
+    #[tokio::test]
+    async fn latest() {
+        let (api, requests) = api_server(
+            200,
+            r#"{"code_hash":"ab","cargo_contract_version":"3.2.0"}"#,
+        )
+        .await;
+
+        let response = api.latest("cd").await.unwrap().unwrap();
+
+        assert_eq!(response.code_hash, "ab");
+        assert!(response.matches("3.2.0", None));
+        assert_eq!(*requests.lock().unwrap(), ["/buildSessions/latest/cd"]);
+
+        let (api, _) = api_server(404, "").await;
+
+        assert!(api.latest("cd").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn upload_source() {
+        let (api, requests) = api_server(200, r#"{"id":1}"#).await;
+
+        let form = Form::new().part("archive", Part::bytes(b"archive".to_vec()));
+        let response = api.upload_source(form).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(*requests.lock().unwrap(), ["/sourceCode"]);
+    }
+
+    #[tokio::test]
+    async fn create_build_session() {
+        let (api, requests) = api_server(200, r#"{"id":2}"#).await;
+
+        let response = api
+            .create_build_session(&BuildSessionCreateRequest {
+                source_code_id: 1,
+                cargo_contract_version: "3.2.0",
+                project_directory: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.id, 2);
+        assert_eq!(*requests.lock().unwrap(), ["/buildSessions"]);
+
+        let (api, _) = api_server(422, "").await;
+
+        let error = api
+            .create_build_session(&BuildSessionCreateRequest {
+                source_code_id: 1,
+                cargo_contract_version: "4.0.0",
+                project_directory: None,
+            })
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(error.status(), Some(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+
+    #[tokio::test]
+    async fn build_sessions() {
+        let (api, requests) = api_server(200, r#"[{"id":1},{"id":2}]"#).await;
+
+        let build_sessions = api.build_sessions::<Value>(3, None).await.unwrap();
+
+        assert_eq!(build_sessions.len(), 2);
+
+        api.build_sessions::<Value>(1, Some(50)).await.unwrap();
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            ["/buildSessions?page=3", "/buildSessions?page=1&limit=50"]
+        );
+    }
+
+    #[tokio::test]
+    async fn logs() {
+        let (api, requests) = api_server(200, r#"{"logs":[{"id":5,"text":"log\n"}]}"#).await;
+
+        let logs = api.logs(1, 4).await.unwrap();
+
+        assert_eq!(logs.logs.len(), 1);
+        assert_eq!(logs.logs[0].id, 5);
+        assert_eq!(logs.logs[0].count, 1);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            ["/buildSessions/logs/1?position=4"]
+        );
+    }
+
+    #[tokio::test]
+    async fn status() {
+        let (api, requests) = api_server(
+            200,
+            r#"{"status":"processing","code_hash":null,"stage":"building"}"#,
+        )
+        .await;
+
+        let status = api.status("ab").await.unwrap();
+
+        assert_eq!(status.status, "processing");
+        assert!(!status.is_finished());
+        assert_eq!(status.stage.as_deref(), Some("building"));
+        assert_eq!(*requests.lock().unwrap(), ["/buildSessions/status/ab"]);
+
+        let (api, _) = api_server(404, "").await;
+
+        let error = api.status(1).await.err().unwrap();
+
+        assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn artifacts() {
+        let (api, requests) = api_server(200, "blob").await;
+
+        assert_eq!(api.wasm("ab").await.unwrap().bytes().await.unwrap(), "blob");
+        assert_eq!(
+            api.artifact("ab").await.unwrap().bytes().await.unwrap(),
+            "blob"
+        );
+        assert_eq!(
+            api.metadata("ab").await.unwrap().bytes().await.unwrap(),
+            "blob"
+        );
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [
+                "/buildSessions/wasm/ab",
+                "/buildSessions/artifact/ab",
+                "/buildSessions/metadata/ab",
+            ]
+        );
+    }
+}
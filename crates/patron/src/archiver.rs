@@ -1,49 +1,157 @@
 use std::{
-    env::current_dir,
-    ffi::OsStr,
-    fs::File,
+    collections::HashMap,
+    error::Error as StdError,
+    ffi::{OsStr, OsString},
+    fmt,
+    fs::{self, File},
     io::{self, Seek, Write},
-    path::{Path, StripPrefixError},
+    path::{Component, Path, StripPrefixError},
 };
 
 use derive_more::{Display, Error, From};
-use indicatif::ProgressBar;
-use walkdir::{DirEntry, WalkDir};
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    DirEntry, Walk, WalkBuilder,
+};
+use indicatif::{HumanBytes, ProgressBar};
 use zip::{write::FileOptions, ZipWriter};
 
+/// Name of a project-specific ignore file, which uses the `.gitignore` syntax.
+const PATRONIGNORE_FILE_NAME: &str = ".patronignore";
+
+/// Amount of the largest top-level entries reported when the archive size limit is exceeded.
+const REPORTED_ENTRIES: usize = 5;
+
 /// Errors that may occur during the archive creation process.
 #[derive(Debug, Display, From, Error)]
 pub(crate) enum ArchiverError {
     /// [`zip`]-crate specific error.
     Zip(zip::result::ZipError),
 
-    /// [`walkdir`]-crate specific error.
-    WalkDir(walkdir::Error),
+    /// [`ignore`]-crate specific error.
+    Ignore(ignore::Error),
 
     /// IO error.
     Io(io::Error),
 
     /// Unable to strip current directory prefix from path.
     StripPrefix(StripPrefixError),
+
+    /// Archive size exceeds the configured limit.
+    TooLarge(ArchiveTooLarge),
+}
+
+/// Summary of the project directory archiving process.
+pub(crate) struct ArchiveSummary {
+    /// Names of top-level entries that were excluded from the archive.
+    pub skipped_entries: Vec<String>,
+
+    /// Total size of archived files in bytes.
+    pub total_size: u64,
+
+    /// Sizes of included top-level entries in bytes, sorted in descending order.
+    pub entry_sizes: Vec<(String, u64)>,
+}
+
+/// Archive size limit violation report.
+#[derive(Debug)]
+pub(crate) struct ArchiveTooLarge {
+    /// Total size of archived files in bytes.
+    pub total_size: u64,
+
+    /// Configured archive size limit in bytes.
+    pub limit: u64,
+
+    /// Largest top-level entries with their sizes in bytes.
+    pub largest_entries: Vec<(String, u64)>,
+}
+
+impl fmt::Display for ArchiveTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "archive size ({}) exceeds the limit of {}, largest entries:",
+            HumanBytes(self.total_size),
+            HumanBytes(self.limit)
+        )?;
+
+        for (name, size) in &self.largest_entries {
+            writeln!(f, "  {name}: {}", HumanBytes(*size))?;
+        }
+
+        write!(
+            f,
+            "add ignore rules to the {PATRONIGNORE_FILE_NAME} file or use the --force-large-upload flag"
+        )
+    }
 }
 
-/// Archive the current directory into the provided `file`.
+impl StdError for ArchiveTooLarge {}
+
+impl ArchiveSummary {
+    /// Ensure that the total archive size does not exceed the provided limit.
+    fn check_size(&self, limit: u64) -> Result<(), ArchiveTooLarge> {
+        if self.total_size <= limit {
+            return Ok(());
+        }
+
+        Err(ArchiveTooLarge {
+            total_size: self.total_size,
+            limit,
+            largest_entries: self
+                .entry_sizes
+                .iter()
+                .take(REPORTED_ENTRIES)
+                .cloned()
+                .collect(),
+        })
+    }
+}
+
+/// Archive the provided project directory into the provided `file`.
 ///
 /// [`build_zip_archive`] makes use of a [`walk_project_directory`] function,
 /// including its file filtering capabilities. See the corresponding documentation
 /// for more information on which files and directories are ignored during the packaging
 /// process.
+///
+/// If `size_limit` is provided, archiving fails when the total size of archived files exceeds it.
 pub(crate) fn build_zip_archive<W: Write + Seek>(
+    dir: &Path,
     file: W,
     progress: &ProgressBar,
+    size_limit: Option<u64>,
 ) -> Result<W, ArchiverError> {
+    let (file, summary) = archive_directory(dir, file, progress)?;
+
+    if !summary.skipped_entries.is_empty() {
+        progress.println(format!(
+            "Skipped entries: {}",
+            summary.skipped_entries.join(", ")
+        ));
+    }
+
+    if let Some(limit) = size_limit {
+        summary.check_size(limit)?;
+    }
+
+    Ok(file)
+}
+
+/// Archive the provided directory into the provided `file`.
+fn archive_directory<W: Write + Seek>(
+    dir: &Path,
+    file: W,
+    progress: &ProgressBar,
+) -> Result<(W, ArchiveSummary), ArchiverError> {
     let mut writer = ZipWriter::new(file);
+    let mut included_entries = HashMap::new();
 
-    let current_dir = current_dir()?;
-    let mut entries = walk_project_directory(&current_dir);
+    for entry in walk_project_directory(dir) {
+        let entry = entry?;
+        let relative_path = entry.path().strip_prefix(dir)?;
 
-    while let Some(entry) = entries.next().transpose()? {
-        let Some(path) = entry.path().strip_prefix(&current_dir)?.to_str() else {
+        let Some(path) = relative_path.to_str() else {
             progress.println(format!(
                 "File {} contains non-unicode symbols in path",
                 entry.path().display()
@@ -51,30 +159,217 @@ pub(crate) fn build_zip_archive<W: Write + Seek>(
             continue;
         };
 
-        if !path.is_empty() {
-            if entry.file_type().is_dir() {
+        if path.is_empty() {
+            continue;
+        }
+
+        let entry_size = match entry.file_type() {
+            Some(file_type) if file_type.is_dir() => {
                 writer.add_directory(path, FileOptions::default())?;
-            } else if entry.file_type().is_file() {
+                0
+            }
+            Some(file_type) if file_type.is_file() => {
                 writer.start_file(path, FileOptions::default())?;
-                io::copy(&mut File::open(path)?, &mut writer)?;
+                io::copy(&mut File::open(entry.path())?, &mut writer)?
             }
+            _ => 0,
+        };
+
+        if let Some(Component::Normal(name)) = relative_path.components().next() {
+            *included_entries.entry(name.to_owned()).or_insert(0) += entry_size;
+        }
+    }
+
+    let skipped_entries = skipped_entries(dir, &included_entries)?;
+
+    let mut entry_sizes: Vec<_> = included_entries
+        .into_iter()
+        .map(|(name, size)| (name.to_string_lossy().into_owned(), size))
+        .collect();
+    entry_sizes.sort_by(|(a_name, a_size), (b_name, b_size)| {
+        b_size.cmp(a_size).then_with(|| a_name.cmp(b_name))
+    });
+
+    Ok((
+        writer.finish()?,
+        ArchiveSummary {
+            skipped_entries,
+            total_size: entry_sizes.iter().map(|(_, size)| size).sum(),
+            entry_sizes,
+        },
+    ))
+}
+
+/// Get a sorted list of top-level entry names that were not included in the archive.
+fn skipped_entries(
+    dir: &Path,
+    included: &HashMap<OsString, u64>,
+) -> Result<Vec<String>, io::Error> {
+    let mut skipped = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name();
+
+        if !included.contains_key(&name) {
+            skipped.push(name.to_string_lossy().into_owned());
         }
     }
 
-    Ok(writer.finish()?)
+    skipped.sort();
+
+    Ok(skipped)
 }
 
 /// Recursively iterate over the project files and directories while filtering them.
 ///
-/// Returned [`Iterator`] will not yield any files or directories that are named `target`
-/// or any hidden files, names of which begin with a dot (`.git`, `.vscode`, etc.).
-fn walk_project_directory(dir: &Path) -> impl Iterator<Item = Result<DirEntry, walkdir::Error>> {
-    WalkDir::new(dir).into_iter().filter_entry(|entry| {
-        entry
+/// Returned [`Iterator`] will not yield any files or directories that are ignored by
+/// `.gitignore` or `.patronignore` files, as well as any entries named `target`
+/// or hidden entries, names of which begin with a dot (`.git`, `.vscode`, etc.).
+///
+/// Hidden entries can be included explicitly with negation patterns
+/// inside of the `.patronignore` file (for example, `!.cargo/`).
+fn walk_project_directory(dir: &Path) -> Walk {
+    let mut builder = GitignoreBuilder::new(dir);
+
+    // Missing .patronignore file is not an error.
+    let _ = builder.add(dir.join(PATRONIGNORE_FILE_NAME));
+
+    let patronignore = builder.build().unwrap_or_else(|_| Gitignore::empty());
+
+    WalkBuilder::new(dir)
+        .hidden(false)
+        .parents(false)
+        .git_global(false)
+        .require_git(false)
+        .add_custom_ignore_filename(PATRONIGNORE_FILE_NAME)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |entry| {
+            !is_excluded_by_default(entry)
+                || patronignore
+                    .matched(
+                        entry.path(),
+                        entry.file_type().map_or(false, |ty| ty.is_dir()),
+                    )
+                    .is_whitelist()
+        })
+        .build()
+}
+
+/// Check if the provided entry is excluded from the archive by default.
+fn is_excluded_by_default(entry: &DirEntry) -> bool {
+    entry.depth() > 0
+        && entry
             .path()
             .file_name()
             .and_then(OsStr::to_str)
-            .filter(|name| *name != "target" && !name.starts_with('.'))
-            .is_some()
-    })
+            .map_or(true, |name| name == "target" || name.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{Cursor, Seek, SeekFrom},
+        path::Path,
+    };
+
+    use indicatif::{HumanBytes, ProgressBar};
+    use zip::ZipArchive;
+
+    use super::{archive_directory, ArchiveTooLarge};
+
+    /// Create a file with the provided contents, creating any missing parent directories.
+    fn create_file(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn ignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        create_file(root, "Cargo.toml", "[package]");
+        create_file(root, "lib.rs", "");
+        create_file(root, "node_modules/package/index.js", "");
+        create_file(root, "fixtures/data.bin", "");
+        create_file(root, "target/debug/contract.wasm", "");
+        create_file(root, ".git/HEAD", "");
+        create_file(root, ".cargo/config.toml", "");
+        create_file(root, ".vscode/settings.json", "");
+        create_file(root, ".gitignore", "node_modules/\n");
+        create_file(root, ".patronignore", "fixtures/\n!.cargo/\n");
+
+        let (mut archive, summary) =
+            archive_directory(root, Cursor::new(Vec::new()), &ProgressBar::hidden()).unwrap();
+
+        archive.seek(SeekFrom::Start(0)).unwrap();
+
+        let archive = ZipArchive::new(archive).unwrap();
+        let mut entries: Vec<_> = archive.file_names().collect();
+        entries.sort();
+
+        assert_eq!(
+            entries,
+            [".cargo/", ".cargo/config.toml", "Cargo.toml", "lib.rs"]
+        );
+
+        assert_eq!(
+            summary.skipped_entries,
+            [
+                ".git",
+                ".gitignore",
+                ".patronignore",
+                ".vscode",
+                "fixtures",
+                "node_modules",
+                "target"
+            ]
+        );
+    }
+
+    #[test]
+    fn size_limit_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        for (path, size) in [
+            ("a/file", 5),
+            ("b/file", 50),
+            ("c/nested/file", 20),
+            ("c/file", 5),
+            ("d/file", 1),
+            ("e/file", 30),
+            ("f/file", 10),
+            ("lib.rs", 2),
+        ] {
+            create_file(root, path, &"0".repeat(size));
+        }
+
+        let (_, summary) =
+            archive_directory(root, Cursor::new(Vec::new()), &ProgressBar::hidden()).unwrap();
+
+        assert_eq!(summary.total_size, 123);
+        assert!(summary.check_size(123).is_ok());
+
+        let ArchiveTooLarge {
+            total_size,
+            limit,
+            largest_entries,
+        } = summary.check_size(100).unwrap_err();
+
+        assert_eq!(total_size, 123);
+        assert_eq!(limit, 100);
+        assert_eq!(
+            largest_entries,
+            [
+                (String::from("b"), 50),
+                (String::from("e"), 30),
+                (String::from("c"), 25),
+                (String::from("f"), 10),
+                (String::from("a"), 5),
+            ]
+        );
+    }
 }
@@ -4,9 +4,33 @@ mod auth;
 /// `build` subcommand.
 mod build;
 
+/// `call` subcommand.
+mod call;
+
+/// `completions` subcommand.
+mod completions;
+
 /// `deploy` subcommand.
 mod deploy;
 
+/// `doctor` subcommand.
+mod doctor;
+
+/// `download` subcommand.
+mod download;
+
+/// `init` subcommand.
+mod init;
+
+/// `list` subcommand.
+mod list;
+
+/// `logs` subcommand.
+mod logs;
+
+/// `status` subcommand.
+mod status;
+
 /// `verify` subcommand.
 mod verify;
 
@@ -15,13 +39,25 @@ mod watch;
 
 pub(crate) use auth::auth;
 pub(crate) use build::build;
+pub(crate) use call::call;
+pub(crate) use completions::completions;
 pub(crate) use deploy::deploy;
+pub(crate) use doctor::doctor;
+pub(crate) use download::download;
+pub(crate) use init::init;
+pub(crate) use list::list;
+pub(crate) use logs::logs;
+pub(crate) use status::status;
 pub(crate) use verify::verify;
 pub(crate) use watch::watch;
 
 use std::path::PathBuf;
 
 use clap::{Args, Parser, Subcommand};
+use clap_complete::Shell;
+use common::artifact_signature::SigningPublicKey;
+
+use crate::{output::OutputFormat, process::Salt};
 
 /// CLI configuration.
 #[derive(Parser)]
@@ -31,6 +67,12 @@ pub(crate) struct Cli {
     #[arg(short, long, default_value = "Deploy.toml")]
     pub config_file: Option<PathBuf>,
 
+    /// Output format.
+    ///
+    /// JSON output emits newline-delimited events instead of the progress indication.
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub output: OutputFormat,
+
     /// Selected subcommand.
     #[command(subcommand)]
     pub command: Commands,
@@ -42,6 +84,9 @@ pub(crate) enum Commands {
     /// Authenticate using the browser flow.
     Auth(Auth),
 
+    /// Create project configuration file in the current directory.
+    Init(Init),
+
     /// Start the build and deployment process.
     Deploy(Deploy),
 
@@ -53,6 +98,28 @@ pub(crate) enum Commands {
 
     /// Watch for changes and rebuild the contract.
     Watch(Watch),
+
+    /// Get status of a build session.
+    Status(Status),
+
+    /// Get logs of a build session.
+    Logs(Logs),
+
+    /// List recent build sessions.
+    List(List),
+
+    /// Download verified contract artifacts.
+    Download(Download),
+
+    /// Call a message of a deployed contract.
+    Call(Call),
+
+    /// Diagnose common environment and configuration issues.
+    Doctor,
+
+    /// Generate shell completions or Markdown documentation.
+    #[command(hide = true)]
+    Completions(Completions),
 }
 
 /// `auth` subcommand configuration.
@@ -65,14 +132,63 @@ pub struct Auth {
     /// Custom web path.
     #[arg(short, long)]
     web_path: Option<String>,
+
+    /// Authentication token used instead of the browser flow.
+    #[arg(short, long, conflicts_with = "status")]
+    token: Option<String>,
+
+    /// Display the current authentication status.
+    #[arg(long, conflicts_with_all = ["server_path", "web_path"])]
+    status: bool,
+
+    /// Store the authentication token in a configuration file instead of the OS keychain.
+    #[arg(long, conflicts_with = "status")]
+    no_keychain: bool,
+}
+
+/// `init` subcommand configuration.
+#[derive(Args)]
+pub struct Init {
+    /// `cargo-contract` version used to build the contract.
+    #[arg(long)]
+    cargo_contract_version: Option<String>,
+
+    /// Default WebSocket URL of an RPC node.
+    #[arg(short, long)]
+    url: Option<String>,
+
+    /// Overwrite an existing configuration file.
+    #[arg(short, long)]
+    force: bool,
+
+    /// Don't add build artifacts and CLI state directories to the `.gitignore` file.
+    #[arg(long)]
+    no_gitignore: bool,
 }
 
 /// `deploy` subcommand configuration.
 #[derive(Args)]
-#[clap(trailing_var_arg = true)]
 pub struct Deploy {
     /// Contract constructor name.
-    constructor: String,
+    #[arg(required_unless_present_any = ["upload_only", "profile"])]
+    constructor: Option<String>,
+
+    /// Deployment profile from the project configuration.
+    ///
+    /// Values provided with CLI flags take precedence over the profile ones.
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Only upload the contract code without instantiating it.
+    #[arg(
+        long,
+        conflicts_with_all = ["constructor", "args", "args_file", "gas", "proof_size", "salt"]
+    )]
+    upload_only: bool,
+
+    /// Print a summary of the deployment process without uploading, building or instantiating the contract.
+    #[arg(long)]
+    dry_run: bool,
 
     /// Always start new build sessions, even if the source code was verified previously.
     #[arg(short, long)]
@@ -82,7 +198,21 @@ pub struct Deploy {
     #[arg(short, long)]
     root: Option<PathBuf>,
 
+    /// Maximum amount of retries for transient source code upload failures.
+    #[arg(long, default_value_t = 3)]
+    upload_retries: u32,
+
+    /// Upload the source code archive even if it exceeds the configured size limit.
+    #[arg(long)]
+    force_large_upload: bool,
+
+    /// Maximum amount of seconds to wait for the remote build session to finish.
+    #[arg(long, value_name = "SECONDS")]
+    build_timeout: Option<u64>,
+
     /// WebSocket URL of an RPC node.
+    ///
+    /// Defaults to the URL from the project configuration.
     #[arg(short, long)]
     url: Option<String>,
 
@@ -94,6 +224,12 @@ pub struct Deploy {
     #[arg(short, long)]
     args: Option<String>,
 
+    /// Path to a file with constructor arguments.
+    ///
+    /// File may contain either space-separated values or a JSON array of values.
+    #[arg(long, conflicts_with = "args")]
+    args_file: Option<PathBuf>,
+
     /// Gas value used to instantiate the contract.
     #[arg(short, long)]
     gas: Option<u64>,
@@ -102,27 +238,53 @@ pub struct Deploy {
     #[arg(short, long)]
     proof_size: Option<u64>,
 
-    /// Salt value used to create multiple instances of the same contract.
+    /// Hex-encoded salt value used to derive the contract address.
+    ///
+    /// A random salt is used if none is provided.
     #[arg(long)]
-    salt: Option<u64>,
+    salt: Option<Salt>,
 
     /// Additional options passed to cargo-contract.
-    #[clap(allow_hyphen_values = true)]
+    ///
+    /// Options must be separated from the rest of the arguments with `--`.
+    #[arg(last = true, allow_hyphen_values = true)]
     cargo_contract_flags: Vec<String>,
 }
 
 /// `build` subcommand configuration.
+///
+/// Contracts are built locally without any network access, unless the `--remote` flag is passed.
 #[derive(Args)]
 pub struct Build {
+    /// Build the contract remotely using the Patron API server.
+    #[arg(long)]
+    remote: bool,
+
+    /// Build the contract locally using the Docker-based verifiable build.
+    #[arg(long, conflicts_with = "remote")]
+    verifiable: bool,
+
     /// Always start new build sessions, even if the source code was verified previously.
-    #[arg(short, long)]
+    #[arg(short, long, requires = "remote")]
     force_new_build_sessions: bool,
 
     /// Relative project root used to build multi-contract projects.
-    #[arg(short, long)]
+    #[arg(short, long, requires = "remote")]
     root: Option<PathBuf>,
 
-    /// Path where to output a newly built contract WASM blob.
+    /// Maximum amount of retries for transient source code upload failures.
+    #[arg(long, default_value_t = 3)]
+    upload_retries: u32,
+
+    /// Upload the source code archive even if it exceeds the configured size limit.
+    #[arg(long, requires = "remote")]
+    force_large_upload: bool,
+
+    /// Maximum amount of seconds to wait for the remote build session to finish.
+    #[arg(long, value_name = "SECONDS", requires = "remote")]
+    build_timeout: Option<u64>,
+
+    /// Path where to output a newly built contract WASM or PolkaVM blob.
     #[arg(short, long)]
     wasm_path: Option<PathBuf>,
 
@@ -130,7 +292,7 @@ pub struct Build {
     #[arg(short, long)]
     metadata_path: Option<PathBuf>,
 
-    /// Path where to output a bundled JSON, which contains both WASM and metadata.
+    /// Path where to output a bundled JSON, which contains both contract blob and metadata.
     #[arg(short, long)]
     bundle_path: Option<PathBuf>,
 }
@@ -145,6 +307,171 @@ pub struct Verify {
     /// Relative project root used to build multi-contract projects.
     #[arg(short, long)]
     root: Option<PathBuf>,
+
+    /// Maximum amount of retries for transient source code upload failures.
+    #[arg(long, default_value_t = 3)]
+    upload_retries: u32,
+
+    /// Upload the source code archive even if it exceeds the configured size limit.
+    #[arg(long)]
+    force_large_upload: bool,
+
+    /// Maximum amount of seconds to wait for the remote build session to finish.
+    #[arg(long, value_name = "SECONDS")]
+    build_timeout: Option<u64>,
+
+    /// Address of a deployed contract to verify against the remote build.
+    ///
+    /// When provided, the on-chain code hash is compared with the remotely built one
+    /// instead of building the contract locally.
+    #[arg(short, long)]
+    address: Option<String>,
+
+    /// API server URL used to look up the deployed contract.
+    ///
+    /// Defaults to the server path of the current authentication configuration.
+    #[arg(long, requires = "address", conflicts_with = "url")]
+    server: Option<String>,
+
+    /// WebSocket URL of an RPC node used to look up the deployed contract.
+    ///
+    /// When provided, the deployed code hash is retrieved from the node directly
+    /// instead of the API server.
+    #[arg(short, long, requires = "address")]
+    url: Option<String>,
+
+    /// Hex-encoded public keys of trusted builder signing keys.
+    ///
+    /// If provided, remotely built artifacts must be signed with one of these keys,
+    /// otherwise the command exits with an error.
+    #[arg(long, env = "PATRON_SIGNING_KEY", value_delimiter = ',')]
+    signing_key: Vec<SigningPublicKey>,
+}
+
+/// `status` subcommand configuration.
+#[derive(Args)]
+pub struct Status {
+    /// Build session identifier or code hash.
+    id: String,
+}
+
+/// `logs` subcommand configuration.
+#[derive(Args)]
+pub struct Logs {
+    /// Build session identifier or code hash.
+    id: String,
+
+    /// Keep polling for new logs until the build session is finished.
+    #[arg(short, long)]
+    follow: bool,
+}
+
+/// `list` subcommand configuration.
+#[derive(Args)]
+pub struct List {
+    /// Amount of build sessions per page.
+    ///
+    /// Defaults to the page size of the API server.
+    #[arg(short, long)]
+    limit: Option<u64>,
+
+    /// Page of build sessions to display.
+    #[arg(short, long, default_value_t = 1)]
+    page: u64,
+
+    /// Output build sessions in JSON format.
+    #[arg(long)]
+    json: bool,
+}
+
+/// `download` subcommand configuration.
+#[derive(Args)]
+pub struct Download {
+    /// Hex-encoded code hash of a verified contract.
+    code_hash: String,
+
+    /// Directory where to save downloaded files.
+    #[arg(short, long, default_value = ".")]
+    out: PathBuf,
+
+    /// API server URL used to download the artifacts.
+    ///
+    /// Defaults to the server path of the current authentication configuration.
+    #[arg(short, long)]
+    url: Option<String>,
+
+    /// Download the WASM or PolkaVM blob.
+    #[arg(long)]
+    wasm: bool,
+
+    /// Download the JSON metadata.
+    #[arg(long)]
+    metadata: bool,
+
+    /// Download the bundled `.contract` file, which contains both contract blob and metadata.
+    #[arg(long)]
+    contract: bool,
+
+    /// Hex-encoded public keys of trusted builder signing keys.
+    ///
+    /// If provided, artifacts must be signed with one of these keys, otherwise no files are written.
+    #[arg(long, env = "PATRON_SIGNING_KEY", value_delimiter = ',')]
+    signing_key: Vec<SigningPublicKey>,
+}
+
+/// `call` subcommand configuration.
+#[derive(Args)]
+#[clap(trailing_var_arg = true)]
+pub struct Call {
+    /// Address of a contract to call.
+    ///
+    /// Defaults to the address of the last contract deployed from the current directory.
+    #[arg(short, long)]
+    contract: Option<String>,
+
+    /// Name of a message to call.
+    #[arg(short, long)]
+    message: String,
+
+    /// Space-separated values passed to the message.
+    #[arg(short, long)]
+    args: Option<String>,
+
+    /// Secret URI for signing requests.
+    #[arg(short, long)]
+    suri: Option<String>,
+
+    /// WebSocket URL of an RPC node.
+    ///
+    /// Defaults to the URL from the project configuration.
+    #[arg(short, long)]
+    url: Option<String>,
+
+    /// Only dry-run the call and output its return value.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Path to contract JSON metadata.
+    ///
+    /// Defaults to the metadata of the last deployed contract.
+    #[arg(long)]
+    metadata_path: Option<PathBuf>,
+
+    /// Additional options passed to cargo-contract.
+    #[clap(allow_hyphen_values = true)]
+    cargo_contract_flags: Vec<String>,
+}
+
+/// `completions` subcommand configuration.
+#[derive(Args)]
+pub struct Completions {
+    /// Shell to generate completions for.
+    #[arg(value_enum, required_unless_present = "markdown_help")]
+    shell: Option<Shell>,
+
+    /// Print documentation of all commands in Markdown format instead.
+    #[arg(long, conflicts_with = "shell")]
+    markdown_help: bool,
 }
 
 /// `watch` subcommand configuration.
@@ -154,18 +481,45 @@ pub struct Watch {
     #[arg(short, long)]
     web_path: Option<String>,
 
+    /// Address used to bind the WebSocket server, which sends deployed contract info to the web UI.
+    ///
+    /// Defaults to the value from the project configuration or `127.0.0.1`.
+    #[arg(long)]
+    ws_host: Option<String>,
+
+    /// Port used to bind the WebSocket server, which sends deployed contract info to the web UI.
+    ///
+    /// Defaults to the value from the project configuration or `20600`.
+    #[arg(long)]
+    ws_port: Option<u16>,
+
     /// Contract constructor name.
-    constructor: String,
+    #[arg(required_unless_present = "profile")]
+    constructor: Option<String>,
+
+    /// Deployment profile from the project configuration.
+    ///
+    /// Values provided with CLI flags take precedence over the profile ones.
+    #[arg(long)]
+    profile: Option<String>,
 
     /// Space-separated values passed to constructor.
     #[arg(short, long)]
     args: Option<String>,
 
+    /// Path to a file with constructor arguments.
+    ///
+    /// File may contain either space-separated values or a JSON array of values.
+    #[arg(long, conflicts_with = "args")]
+    args_file: Option<PathBuf>,
+
     /// Secret URI for signing requests.
     #[arg(short, long)]
     suri: Option<String>,
 
     /// WebSocket URL of an RPC node.
+    ///
+    /// Defaults to the URL from the project configuration.
     #[arg(short, long)]
     url: Option<String>,
 
@@ -177,7 +531,290 @@ pub struct Watch {
     #[arg(short, long)]
     proof_size: Option<u64>,
 
+    /// Hex-encoded salt value used to derive the contract address.
+    ///
+    /// A random salt is used for each deployment if none is provided.
+    #[arg(long)]
+    salt: Option<Salt>,
+
+    /// Amount of seconds to wait for additional file changes before rebuilding the contract.
+    #[arg(long, default_value_t = 2)]
+    debounce: u64,
+
+    /// Shell command executed after each successful build, such as `cargo test`.
+    ///
+    /// Contract is not deployed if the command exits with a non-zero status code.
+    #[arg(long)]
+    pre_deploy_cmd: Option<String>,
+
     /// Additional options passed to cargo-contract.
-    #[clap(allow_hyphen_values = true)]
+    ///
+    /// Options must be separated from the rest of the arguments with `--`.
+    #[arg(last = true, allow_hyphen_values = true)]
     cargo_contract_flags: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::{error::ErrorKind, Parser};
+
+    use super::{Cli, Commands};
+    use crate::{output::OutputFormat, process::Salt};
+
+    #[test]
+    fn upload_only_without_constructor() {
+        let cli = Cli::try_parse_from(["patron", "deploy", "--upload-only"]).unwrap();
+
+        let Commands::Deploy(deploy) = cli.command else {
+            panic!("expected deploy subcommand");
+        };
+
+        assert!(deploy.upload_only);
+        assert!(deploy.constructor.is_none());
+    }
+
+    #[test]
+    fn constructor_required_without_upload_only() {
+        assert!(Cli::try_parse_from(["patron", "deploy"]).is_err());
+    }
+
+    #[test]
+    fn upload_only_conflicts() {
+        for args in [
+            &["patron", "deploy", "--upload-only", "new"][..],
+            &["patron", "deploy", "--upload-only", "--args", "1"],
+            &[
+                "patron",
+                "deploy",
+                "--upload-only",
+                "--args-file",
+                "args.txt",
+            ],
+            &["patron", "deploy", "--upload-only", "--gas", "1"],
+            &["patron", "deploy", "--upload-only", "--proof-size", "1"],
+            &["patron", "deploy", "--upload-only", "--salt", "01"],
+        ] {
+            assert!(Cli::try_parse_from(args).is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn profile_argument() {
+        let cli = Cli::try_parse_from(["patron", "deploy", "--profile", "testnet"]).unwrap();
+
+        let Commands::Deploy(deploy) = cli.command else {
+            panic!("expected deploy subcommand");
+        };
+
+        assert_eq!(deploy.profile.as_deref(), Some("testnet"));
+        assert!(deploy.constructor.is_none());
+
+        let cli = Cli::try_parse_from(["patron", "watch", "--profile", "local"]).unwrap();
+
+        let Commands::Watch(watch) = cli.command else {
+            panic!("expected watch subcommand");
+        };
+
+        assert_eq!(watch.profile.as_deref(), Some("local"));
+
+        assert!(Cli::try_parse_from(["patron", "watch"]).is_err());
+    }
+
+    #[test]
+    fn cargo_contract_flags_after_separator() {
+        let cli =
+            Cli::try_parse_from(["patron", "deploy", "--upload-only", "--", "--features", "x"])
+                .unwrap();
+
+        let Commands::Deploy(deploy) = cli.command else {
+            panic!("expected deploy subcommand");
+        };
+
+        assert!(deploy.upload_only);
+        assert!(deploy.constructor.is_none());
+        assert_eq!(deploy.cargo_contract_flags, ["--features", "x"]);
+
+        let cli =
+            Cli::try_parse_from(["patron", "deploy", "--profile", "p", "--", "--release"]).unwrap();
+
+        let Commands::Deploy(deploy) = cli.command else {
+            panic!("expected deploy subcommand");
+        };
+
+        assert_eq!(deploy.profile.as_deref(), Some("p"));
+        assert!(deploy.constructor.is_none());
+        assert_eq!(deploy.cargo_contract_flags, ["--release"]);
+
+        let cli =
+            Cli::try_parse_from(["patron", "deploy", "new", "--", "--password", "123"]).unwrap();
+
+        let Commands::Deploy(deploy) = cli.command else {
+            panic!("expected deploy subcommand");
+        };
+
+        assert_eq!(deploy.constructor.as_deref(), Some("new"));
+        assert_eq!(deploy.cargo_contract_flags, ["--password", "123"]);
+
+        let cli =
+            Cli::try_parse_from(["patron", "watch", "--profile", "p", "--", "--release"]).unwrap();
+
+        let Commands::Watch(watch) = cli.command else {
+            panic!("expected watch subcommand");
+        };
+
+        assert!(watch.constructor.is_none());
+        assert_eq!(watch.cargo_contract_flags, ["--release"]);
+    }
+
+    #[test]
+    fn args_file_conflicts_with_args() {
+        for subcommand in ["deploy", "watch"] {
+            let cli =
+                Cli::try_parse_from(["patron", subcommand, "new", "--args-file", "args.json"]);
+            assert!(cli.is_ok(), "{subcommand}");
+
+            let error = Cli::try_parse_from([
+                "patron",
+                subcommand,
+                "new",
+                "--args",
+                "1",
+                "--args-file",
+                "args.json",
+            ])
+            .err()
+            .unwrap();
+            assert_eq!(error.kind(), ErrorKind::ArgumentConflict, "{subcommand}");
+        }
+    }
+
+    #[test]
+    fn salt_argument() {
+        let cli = Cli::try_parse_from(["patron", "deploy", "new", "--salt", "0x0102"]).unwrap();
+
+        let Commands::Deploy(deploy) = cli.command else {
+            panic!("expected deploy subcommand");
+        };
+
+        assert_eq!(deploy.salt, Some("0102".parse::<Salt>().unwrap()));
+
+        let cli = Cli::try_parse_from(["patron", "watch", "new", "--salt", "ff"]).unwrap();
+
+        let Commands::Watch(watch) = cli.command else {
+            panic!("expected watch subcommand");
+        };
+
+        assert_eq!(watch.salt, Some("ff".parse::<Salt>().unwrap()));
+
+        assert!(Cli::try_parse_from(["patron", "deploy", "new", "--salt", "xyz"]).is_err());
+    }
+
+    #[test]
+    fn build_session_arguments() {
+        let cli = Cli::try_parse_from(["patron", "status", "123"]).unwrap();
+
+        let Commands::Status(status) = cli.command else {
+            panic!("expected status subcommand");
+        };
+
+        assert_eq!(status.id, "123");
+
+        let cli = Cli::try_parse_from(["patron", "logs", "--follow", "abcd"]).unwrap();
+
+        let Commands::Logs(logs) = cli.command else {
+            panic!("expected logs subcommand");
+        };
+
+        assert_eq!(logs.id, "abcd");
+        assert!(logs.follow);
+
+        assert!(Cli::try_parse_from(["patron", "logs"]).is_err());
+    }
+
+    #[test]
+    fn websocket_arguments() {
+        let cli = Cli::try_parse_from([
+            "patron",
+            "watch",
+            "new",
+            "--ws-host",
+            "0.0.0.0",
+            "--ws-port",
+            "20700",
+        ])
+        .unwrap();
+
+        let Commands::Watch(watch) = cli.command else {
+            panic!("expected watch subcommand");
+        };
+
+        assert_eq!(watch.ws_host.as_deref(), Some("0.0.0.0"));
+        assert_eq!(watch.ws_port, Some(20700));
+
+        assert!(Cli::try_parse_from(["patron", "watch", "new", "--ws-port", "70000"]).is_err());
+    }
+
+    #[test]
+    fn output_format_argument() {
+        let cli = Cli::try_parse_from(["patron", "build"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Text);
+
+        let cli = Cli::try_parse_from(["patron", "build", "--output", "json"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Json);
+
+        let cli = Cli::try_parse_from(["patron", "--output", "json", "verify"]).unwrap();
+        assert_eq!(cli.output, OutputFormat::Json);
+    }
+
+    #[test]
+    fn verify_lookup_arguments() {
+        const ADDRESS: &str = "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM";
+
+        let cli = Cli::try_parse_from([
+            "patron",
+            "verify",
+            "--address",
+            ADDRESS,
+            "--url",
+            "wss://node.example.com:443",
+        ])
+        .unwrap();
+
+        let Commands::Verify(verify) = cli.command else {
+            panic!("expected verify subcommand");
+        };
+
+        assert_eq!(verify.url.as_deref(), Some("wss://node.example.com:443"));
+        assert!(verify.server.is_none());
+
+        let cli = Cli::try_parse_from([
+            "patron",
+            "verify",
+            "--address",
+            ADDRESS,
+            "--server",
+            "https://api.example.com",
+        ])
+        .unwrap();
+
+        let Commands::Verify(verify) = cli.command else {
+            panic!("expected verify subcommand");
+        };
+
+        assert_eq!(verify.server.as_deref(), Some("https://api.example.com"));
+        assert!(verify.url.is_none());
+
+        assert!(Cli::try_parse_from(["patron", "verify", "--url", "ws://localhost:9944"]).is_err());
+        assert!(Cli::try_parse_from([
+            "patron",
+            "verify",
+            "--address",
+            ADDRESS,
+            "--url",
+            "ws://localhost:9944",
+            "--server",
+            "https://api.example.com",
+        ])
+        .is_err());
+    }
+}
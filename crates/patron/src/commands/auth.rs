@@ -33,6 +33,13 @@ struct ExchangeResponse {
     token: String,
 }
 
+/// JSON response body with account ownership proof message templates.
+#[derive(Deserialize)]
+struct SigningInfoResponse {
+    /// Message template signed during the authentication.
+    login: String,
+}
+
 /// `auth` subcommand errors.
 #[derive(Debug, Display, From, Error)]
 pub(crate) enum AuthError {
@@ -41,6 +48,10 @@ pub(crate) enum AuthError {
 
     /// HTTP client error.
     Http(reqwest::Error),
+
+    /// Provided authentication token was rejected by the server.
+    #[display(fmt = "provided authentication token is invalid")]
+    InvalidToken,
 }
 
 /// Authentication flow entrypoint.
@@ -48,11 +59,30 @@ pub(crate) async fn auth(
     Auth {
         server_path,
         web_path,
+        token,
+        status,
+        no_keychain,
     }: Auth,
 ) -> Result<(), AuthError> {
+    if status {
+        return auth_status().await;
+    }
+
     let server_domain = server_path.unwrap_or(default_server_path());
     let web_domain = web_path.unwrap_or(default_web_path());
 
+    if let Some(token) = token {
+        if !validate_token(&server_domain, &token).await? {
+            return Err(AuthError::InvalidToken);
+        }
+
+        AuthenticationConfig::write_token(token, server_domain, web_domain, !no_keychain)?;
+
+        println!("Authentication completed.");
+
+        return Ok(());
+    }
+
     let cli_token = Alphanumeric.sample_string(&mut thread_rng(), EXCHANGE_TOKEN_LENGTH);
 
     let exchange_url = format!("{web_domain}/login?cli_token={cli_token}");
@@ -62,6 +92,10 @@ pub(crate) async fn auth(
     pg.enable_steady_tick(Duration::from_millis(150));
     pg.println(format!("Opening {exchange_url}"));
 
+    if let Some(template) = login_message_template(&server_domain).await {
+        pg.println(format!("Your wallet will be asked to sign {template}"));
+    }
+
     let _ = open::that_in_background(&exchange_url);
 
     loop {
@@ -82,6 +116,7 @@ pub(crate) async fn auth(
                     response.json::<ExchangeResponse>().await?.token,
                     server_domain,
                     web_domain,
+                    !no_keychain,
                 )?;
                 break;
             }
@@ -96,3 +131,130 @@ pub(crate) async fn auth(
 
     Ok(())
 }
+
+/// Print the current authentication status.
+async fn auth_status() -> Result<(), AuthError> {
+    let auth_config = AuthenticationConfig::new()?;
+
+    println!("Server: {}", auth_config.server_path());
+
+    if validate_token(auth_config.server_path(), auth_config.token()).await? {
+        println!("Authentication token is valid.");
+    } else {
+        println!("Authentication token is invalid, use `patron auth` to authenticate again.");
+    }
+
+    Ok(())
+}
+
+/// Retrieve the message template, that has to be signed in the web UI during the authentication.
+///
+/// Returns [`None`] if the server does not provide message templates.
+async fn login_message_template(server_path: &str) -> Option<String> {
+    let response = Client::new()
+        .get(format!("{server_path}/auth/signingInfo"))
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+
+    let info: SigningInfoResponse = response.json().await.ok()?;
+
+    Some(info.login)
+}
+
+/// Check if the provided authentication token is accepted by the server.
+///
+/// Tokens with the `ci` scope cannot access key management routes,
+/// so build session routes are checked if the key management ones are rejected.
+pub(crate) async fn validate_token(server_path: &str, token: &str) -> Result<bool, reqwest::Error> {
+    for route in ["keys", "buildSessions"] {
+        let response = Client::new()
+            .get(format!("{server_path}/{route}"))
+            .bearer_auth(token)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {}
+            _ => return response.error_for_status().map(|_| true),
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{login_message_template, validate_token};
+    use crate::testing::stub_server;
+
+    #[tokio::test]
+    async fn valid_token() {
+        let server = stub_server(|path| {
+            if path == "/keys" {
+                (200, String::from("[]"))
+            } else {
+                (404, String::new())
+            }
+        })
+        .await;
+
+        assert!(validate_token(&server, "token").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn ci_scoped_token() {
+        let server = stub_server(|path| {
+            if path == "/buildSessions" {
+                (200, String::from("[]"))
+            } else {
+                (403, String::new())
+            }
+        })
+        .await;
+
+        assert!(validate_token(&server, "token").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn invalid_token() {
+        let server = stub_server(|_| (401, String::new())).await;
+
+        assert!(!validate_token(&server, "token").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn server_error() {
+        let server = stub_server(|_| (500, String::new())).await;
+
+        assert!(validate_token(&server, "token").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn signing_info() {
+        let server = stub_server(|path| {
+            if path == "/auth/signingInfo" {
+                (
+                    200,
+                    String::from(
+                        r#"{"login":"<Bytes>patron:example.com:login:{address}</Bytes>"}"#,
+                    ),
+                )
+            } else {
+                (404, String::new())
+            }
+        })
+        .await;
+
+        assert_eq!(
+            login_message_template(&server).await.as_deref(),
+            Some("<Bytes>patron:example.com:login:{address}</Bytes>")
+        );
+
+        let legacy_server = stub_server(|_| (404, String::new())).await;
+
+        assert_eq!(login_message_template(&legacy_server).await, None);
+    }
+}
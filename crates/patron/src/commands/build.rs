@@ -1,25 +1,29 @@
 use std::{
     fs::{self, File},
-    io::{self, Read, Seek, SeekFrom},
-    path::PathBuf,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 
+use common::hash::Hash32;
 use derive_more::{Display, Error, From};
-use indicatif::ProgressBar;
-use serde_json::Value;
-use tempfile::PersistError;
 
 use crate::{
     commands::Build,
     config::{AuthenticationConfig, AuthenticationConfigError, ProjectConfig},
-    process::{remote_build, FinishedBuildSession, RemoteBuildError},
+    output::{Event, OutputFormat},
+    process::{
+        self, build_locally, contract_bundle, ensure_docker_exists,
+        installed_cargo_contract_version, remote_build, ArtifactKind, BuildResult,
+        CargoContractInstallError, FinishedBuildSession, RemoteBuildError, RemoteBuildOptions,
+    },
 };
 
 /// Directory, where build artifacts will be stored.
 const TARGET_DIR: &str = "./target/ink";
 
-/// Default path used to save WASM blob.
-const DEFAULT_WASM_PATH: &str = "./target/ink/contract.wasm";
+/// Default path used to save contract blob, without the file extension of the blob kind.
+const DEFAULT_CODE_PATH: &str = "./target/ink/contract";
 
 /// Default path used to save JSON metadata.
 const DEFAULT_METADATA_PATH: &str = "./target/ink/contract.json";
@@ -42,88 +46,318 @@ pub(crate) enum BuildError {
     /// Metadata JSON parsing error.
     Json(serde_json::Error),
 
+    /// Local build process error.
+    LocalBuildProcessError(process::BuildError),
+
     /// Remote build process error.
     BuildProcessError(RemoteBuildError),
 
-    /// Unable to move temporary files onto a new location.
-    PersistError(PersistError),
+    /// [`which`] crate was unable to determine location of the `cargo` binary file.
+    #[display(fmt = "unable to locate cargo: {}", _0)]
+    Which(which::Error),
+
+    /// Unable to determine the installed `cargo-contract` version.
+    CargoContractInstallError(CargoContractInstallError),
+
+    /// `cargo-contract` installation was not found.
+    #[display(fmt = "unable to find cargo-contract installation")]
+    CargoContractMissing,
+
+    /// Docker installation was not found.
+    #[display(fmt = "unable to find docker installation")]
+    DockerInstallationMissing,
 
     /// Invalid metadata object.
     #[display(fmt = "unable to retrieve the 'source' key from the metadata JSON")]
     InvalidMetadataObject,
 }
 
+/// Contract artifacts produced by either local or remote build.
+struct BuiltContract {
+    /// Kind of the contract blob.
+    artifact_kind: ArtifactKind,
+
+    /// Contract blob contents.
+    code: Vec<u8>,
+
+    /// JSON metadata contents.
+    metadata: Vec<u8>,
+
+    /// Hex-encoded code hash of the contract blob.
+    code_hash: String,
+}
+
 /// Build flow entrypoint.
-pub(crate) async fn build(
+pub(crate) async fn build(build: Build, output: OutputFormat) -> Result<(), BuildError> {
+    build_with(
+        build,
+        output,
+        || which::which("cargo"),
+        AuthenticationConfig::new,
+    )
+    .await
+}
+
+/// Build flow, which uses the provided `cargo` binary locator and API client configuration factory.
+///
+/// API client configuration is only created for remote builds, which allows local builds
+/// to work on machines without network access or authentication configuration.
+async fn build_with<C, A>(
     Build {
+        remote,
+        verifiable,
         force_new_build_sessions,
         root,
+        upload_retries,
+        force_large_upload,
+        build_timeout,
         wasm_path,
         metadata_path,
         bundle_path,
     }: Build,
-) -> Result<(), BuildError> {
-    let auth_config = AuthenticationConfig::new()?;
-    let project_config = ProjectConfig::new()?;
+    output: OutputFormat,
+    cargo: C,
+    auth_config: A,
+) -> Result<(), BuildError>
+where
+    C: FnOnce() -> Result<PathBuf, which::Error>,
+    A: FnOnce() -> Result<AuthenticationConfig, AuthenticationConfigError>,
+{
+    let BuiltContract {
+        artifact_kind,
+        code,
+        metadata,
+        code_hash,
+    } = if remote {
+        let auth_config = auth_config()?;
+        let project_config = ProjectConfig::new()?;
 
-    let progress = ProgressBar::new_spinner();
+        let progress = output.progress_bar();
 
-    let FinishedBuildSession {
-        mut wasm_file,
-        mut metadata_file,
-        code_hash,
-        ..
-    } = remote_build(
-        &auth_config,
-        &project_config,
-        &progress,
-        force_new_build_sessions,
-        root.as_deref(),
-    )
-    .await?;
+        let FinishedBuildSession {
+            code_file,
+            artifact_kind,
+            metadata_file,
+            code_hash,
+        } = remote_build(
+            &auth_config,
+            &project_config,
+            &progress,
+            output,
+            &RemoteBuildOptions {
+                force_new_build_sessions,
+                project_directory: root.as_deref(),
+                upload_retries,
+                force_large_upload,
+                build_timeout: build_timeout.map(Duration::from_secs),
+            },
+        )
+        .await?;
+
+        progress.finish_with_message(format!(
+            "Contract uploaded: {}/codeHash/{}",
+            auth_config.web_path(),
+            code_hash
+        ));
+
+        BuiltContract {
+            artifact_kind,
+            code: fs::read(code_file.path())?,
+            metadata: fs::read(metadata_file.path())?,
+            code_hash,
+        }
+    } else {
+        build_offline(&cargo()?, verifiable).await?
+    };
 
     if wasm_path.is_none() || metadata_path.is_none() || bundle_path.is_none() {
         fs::create_dir_all(TARGET_DIR)?;
     }
 
-    wasm_file.seek(SeekFrom::Start(0))?;
-    let mut wasm_buf = Vec::new();
-    wasm_file.read_to_end(&mut wasm_buf)?;
-
-    metadata_file.seek(SeekFrom::Start(0))?;
-    let mut metadata: Value = serde_json::from_reader(&metadata_file)?;
-    let wasm_hex = format!("0x{}", hex::encode(&wasm_buf));
-    metadata["source"]
-        .as_object_mut()
-        .ok_or(BuildError::InvalidMetadataObject)?
-        .insert("wasm".into(), Value::String(wasm_hex));
-
-    // Ensure that cross-boundary filesystem copies are supported
-    // by manually calling fs::copy.
-    wasm_file.seek(SeekFrom::Start(0))?;
-
-    fs::copy(
-        &mut wasm_file,
-        wasm_path.unwrap_or(PathBuf::from(DEFAULT_WASM_PATH)),
-    )?;
-
-    metadata_file.seek(SeekFrom::Start(0))?;
-
-    fs::copy(
-        &mut metadata_file,
-        metadata_path.unwrap_or(PathBuf::from(DEFAULT_METADATA_PATH)),
-    )?;
-
-    serde_json::to_writer(
-        File::create(bundle_path.unwrap_or(PathBuf::from(DEFAULT_BUNDLE_PATH)))?,
-        &metadata,
-    )?;
-
-    progress.finish_with_message(format!(
-        "Contract uploaded: {}/codeHash/{}",
-        auth_config.web_path(),
-        code_hash
-    ));
+    let code_path = wasm_path.unwrap_or_else(|| {
+        PathBuf::from(format!("{DEFAULT_CODE_PATH}.{}", artifact_kind.extension()))
+    });
+    let metadata_path = metadata_path.unwrap_or(PathBuf::from(DEFAULT_METADATA_PATH));
+    let bundle_path = bundle_path.unwrap_or(PathBuf::from(DEFAULT_BUNDLE_PATH));
+
+    let bundle = contract_bundle(serde_json::from_slice(&metadata)?, artifact_kind, &code)
+        .ok_or(BuildError::InvalidMetadataObject)?;
+
+    // Artifacts are written from memory, since local build outputs
+    // may be located at the same paths as the destination files.
+    fs::write(&code_path, &code)?;
+    fs::write(&metadata_path, &metadata)?;
+    serde_json::to_writer(File::create(&bundle_path)?, &bundle)?;
+
+    if !output.is_json() {
+        println!("Contract blob: {}", code_path.display());
+        println!("JSON metadata: {}", metadata_path.display());
+        println!("Contract bundle: {}", bundle_path.display());
+        println!("Code hash: 0x{code_hash}");
+    }
+
+    output.emit(&Event::ArtifactsSaved {
+        code_path: &code_path,
+        metadata_path: &metadata_path,
+        bundle_path: &bundle_path,
+    });
+
+    output.emit(&Event::Completed {
+        code_hash: &code_hash,
+        address: None,
+        verified: None,
+    });
 
     Ok(())
 }
+
+/// Build contract using the locally installed `cargo-contract`.
+///
+/// Code hash is computed locally, without any requests to the API server.
+async fn build_offline(cargo: &Path, verifiable: bool) -> Result<BuiltContract, BuildError> {
+    if installed_cargo_contract_version(cargo).await?.is_none() {
+        return Err(BuildError::CargoContractMissing);
+    }
+
+    if verifiable && ensure_docker_exists().await {
+        return Err(BuildError::DockerInstallationMissing);
+    }
+
+    let BuildResult {
+        dest_wasm,
+        metadata_result,
+    } = build_locally(cargo, verifiable).await?;
+
+    let code = fs::read(dest_wasm)?;
+    let code_hash = Hash32::blake2(&code).to_string();
+
+    Ok(BuiltContract {
+        artifact_kind: ArtifactKind::Wasm,
+        code,
+        metadata: fs::read(metadata_result.dest_metadata)?,
+        code_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use common::hash::Hash32;
+    use serde_json::Value;
+
+    use super::{build_offline, build_with, BuildError};
+    use crate::{commands::Build, output::OutputFormat, process::ArtifactKind};
+
+    /// Contract blob produced by the stub `cargo-contract`.
+    const WASM: &str = "wasm";
+
+    /// JSON metadata produced by the stub `cargo-contract`.
+    const METADATA: &str = r#"{"source":{"hash":"0x00"}}"#;
+
+    /// Create an executable `cargo` stub, that emulates `cargo-contract` build output.
+    #[cfg(unix)]
+    fn stub_cargo(dir: &Path) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let wasm = dir.join("flipper.wasm");
+        let metadata = dir.join("flipper.json");
+
+        let script = format!(
+            r#"#!/bin/sh
+if [ "$2" = "--version" ]; then
+    echo 'cargo-contract-contract 3.2.0-unknown-x86_64'
+    exit 0
+fi
+printf '{WASM}' > '{wasm}'
+printf '%s' '{METADATA}' > '{metadata}'
+echo '{{"dest_wasm":"{wasm}","metadata_result":{{"dest_metadata":"{metadata}"}}}}'
+"#,
+            wasm = wasm.display(),
+            metadata = metadata.display(),
+        );
+
+        let path = dir.join("cargo");
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        path
+    }
+
+    /// Create `build` subcommand configuration, that saves artifacts to the provided directory.
+    fn build_args(dir: &Path) -> Build {
+        Build {
+            remote: false,
+            verifiable: false,
+            force_new_build_sessions: false,
+            root: None,
+            upload_retries: 3,
+            force_large_upload: false,
+            build_timeout: None,
+            wasm_path: Some(dir.join("contract.wasm")),
+            metadata_path: Some(dir.join("contract.json")),
+            bundle_path: Some(dir.join("bundle.contract")),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn offline_build() {
+        let dir = tempfile::tempdir().unwrap();
+        let cargo = stub_cargo(dir.path());
+
+        build_with(
+            build_args(dir.path()),
+            OutputFormat::Json,
+            || Ok(cargo),
+            || panic!("API client configuration must not be created for offline builds"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read(dir.path().join("contract.wasm")).unwrap(),
+            WASM.as_bytes()
+        );
+        assert_eq!(
+            std::fs::read(dir.path().join("contract.json")).unwrap(),
+            METADATA.as_bytes()
+        );
+
+        let bundle: Value =
+            serde_json::from_slice(&std::fs::read(dir.path().join("bundle.contract")).unwrap())
+                .unwrap();
+
+        assert_eq!(bundle["source"]["wasm"], format!("0x{}", hex::encode(WASM)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn local_code_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let cargo = stub_cargo(dir.path());
+
+        let built = build_offline(&cargo, false).await.unwrap();
+
+        assert_eq!(built.artifact_kind, ArtifactKind::Wasm);
+        assert_eq!(built.code, WASM.as_bytes());
+        assert_eq!(built.code_hash, Hash32::blake2(WASM.as_bytes()).to_string());
+    }
+
+    #[tokio::test]
+    async fn missing_cargo_contract() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let error = build_with(
+            build_args(dir.path()),
+            OutputFormat::Json,
+            || Ok(dir.path().join("missing")),
+            || panic!("API client configuration must not be created for offline builds"),
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert!(matches!(error, BuildError::CargoContractInstallError(_)));
+    }
+}
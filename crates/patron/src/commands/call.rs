@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use derive_more::{Display, Error, From};
+
+use crate::{
+    commands::Call,
+    config::{DeploymentCache, DeploymentCacheError, ProjectConfig},
+    process::{self, call_contract, ContractCallError},
+};
+
+/// `call` subcommand errors.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum CallError {
+    /// Unable to load the deployment cache.
+    DeploymentCache(DeploymentCacheError),
+
+    /// [`which`] crate was unable to determine location of the `cargo` binary file.
+    #[display(fmt = "unable to locate cargo: {}", _0)]
+    Which(which::Error),
+
+    /// JSON output serialization error.
+    Json(serde_json::Error),
+
+    /// Contract message could not be called.
+    #[display(fmt = "unable to call a contract: {}", _0)]
+    ContractCallError(ContractCallError),
+
+    /// Contract address was not provided and no contracts were deployed previously.
+    #[display(fmt = "contract address not provided and no previous deployments were found")]
+    ContractNotSpecified,
+}
+
+/// Contract to call, resolved from the provided arguments and the deployment cache.
+#[derive(Debug, PartialEq, Eq)]
+struct Target {
+    /// Contract address.
+    address: String,
+
+    /// Path to the contract metadata, if known.
+    metadata_path: Option<PathBuf>,
+}
+
+/// Call flow entrypoint.
+pub(crate) async fn call(
+    Call {
+        contract,
+        message,
+        args,
+        suri,
+        url,
+        dry_run,
+        metadata_path,
+        cargo_contract_flags,
+    }: Call,
+) -> Result<(), CallError> {
+    let target = resolve_target(contract, metadata_path, DeploymentCache::load()?)?;
+
+    // Project configuration is optional, since contracts can be called from any directory.
+    let url = url.or_else(|| ProjectConfig::new().ok().and_then(|config| config.url));
+
+    let cargo = which::which("cargo")?;
+
+    let result = call_contract(
+        &cargo,
+        &process::Call {
+            contract: &target.address,
+            message: &message,
+            args: args.as_deref(),
+            suri: suri.as_deref(),
+            url: url.as_deref(),
+            dry_run,
+        },
+        &cargo_contract_flags,
+        target.metadata_path.as_deref(),
+    )
+    .await?;
+
+    let output = if dry_run { &result["data"] } else { &result };
+
+    println!("{}", serde_json::to_string_pretty(output)?);
+
+    Ok(())
+}
+
+/// Resolve contract address and metadata path.
+///
+/// Explicitly provided values take precedence over the ones stored in the deployment cache.
+/// Cached metadata is only used for the cached contract address.
+fn resolve_target(
+    contract: Option<String>,
+    metadata_path: Option<PathBuf>,
+    cache: Option<DeploymentCache>,
+) -> Result<Target, CallError> {
+    match (contract, cache) {
+        (Some(address), _) => Ok(Target {
+            address,
+            metadata_path,
+        }),
+        (None, Some(cache)) => Ok(Target {
+            address: cache.address,
+            metadata_path: metadata_path.or(Some(cache.metadata_path)),
+        }),
+        (None, None) => Err(CallError::ContractNotSpecified),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{resolve_target, CallError, Target};
+    use crate::config::DeploymentCache;
+
+    /// Create deployment cache with the provided address.
+    fn cache(address: &str) -> DeploymentCache {
+        DeploymentCache {
+            address: String::from(address),
+            metadata_path: PathBuf::from(".patron/cached.json"),
+        }
+    }
+
+    #[test]
+    fn cached_target() {
+        assert_eq!(
+            resolve_target(None, None, Some(cache("cached"))).unwrap(),
+            Target {
+                address: String::from("cached"),
+                metadata_path: Some(PathBuf::from(".patron/cached.json")),
+            }
+        );
+    }
+
+    #[test]
+    fn explicit_values_take_precedence() {
+        assert_eq!(
+            resolve_target(
+                Some(String::from("explicit")),
+                Some(PathBuf::from("explicit.json")),
+                Some(cache("cached"))
+            )
+            .unwrap(),
+            Target {
+                address: String::from("explicit"),
+                metadata_path: Some(PathBuf::from("explicit.json")),
+            }
+        );
+
+        assert_eq!(
+            resolve_target(Some(String::from("explicit")), None, Some(cache("cached"))).unwrap(),
+            Target {
+                address: String::from("explicit"),
+                metadata_path: None,
+            }
+        );
+    }
+
+    #[test]
+    fn missing_target() {
+        assert!(matches!(
+            resolve_target(None, None, None),
+            Err(CallError::ContractNotSpecified)
+        ));
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::{self, Write};
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::commands::{Cli, Completions};
+
+/// Completions flow entrypoint.
+pub(crate) async fn completions(
+    Completions {
+        shell,
+        markdown_help,
+    }: Completions,
+) -> Result<(), io::Error> {
+    let mut stdout = io::stdout().lock();
+
+    if markdown_help {
+        write!(stdout, "{}", clap_markdown::help_markdown::<Cli>())?;
+    } else if let Some(shell) = shell {
+        write_completions(shell, &mut stdout);
+    }
+
+    Ok(())
+}
+
+/// Generate shell completion script and write it into the provided buffer.
+fn write_completions<W: Write>(shell: Shell, buf: &mut W) {
+    clap_complete::generate(shell, &mut Cli::command(), "patron", buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use clap_complete::Shell;
+
+    use super::write_completions;
+    use crate::commands::Cli;
+
+    #[test]
+    fn bash_completions() {
+        let mut buf = Vec::new();
+
+        write_completions(Shell::Bash, &mut buf);
+
+        let script = String::from_utf8(buf).unwrap();
+
+        for subcommand in ["auth", "deploy", "build", "verify", "watch", "call"] {
+            assert!(script.contains(subcommand), "{subcommand}");
+        }
+    }
+
+    #[test]
+    fn markdown_help() {
+        let markdown = clap_markdown::help_markdown::<Cli>();
+
+        assert!(markdown.contains("patron deploy"));
+        assert!(markdown.contains("--upload-only"));
+    }
+}
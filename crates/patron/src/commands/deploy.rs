@@ -1,17 +1,21 @@
-use std::{io, process::Stdio};
+use std::{env, fs, io, path::Path, time::Duration};
 
 use derive_more::{Display, Error, From};
-use indicatif::ProgressBar;
-use rand::{thread_rng, Rng};
-use tokio::process::Command;
+use indicatif::HumanBytes;
 
 use crate::{
     commands::Deploy,
-    config::{AuthenticationConfig, AuthenticationConfigError, ProjectConfig},
+    config::{
+        AuthenticationConfig, AuthenticationConfigError, DeploymentCache, DeploymentCacheError,
+        DeploymentOptions, ProfileError, ProjectConfig, STATE_DIR,
+    },
+    output::{Event, OutputFormat},
     process::{
-        ensure_cargo_contract_exists, instantiate_contract, remote_build,
-        CargoContractInstallError, FinishedBuildSession, Instantiation, InstantiationError,
-        RemoteBuildError,
+        ensure_cargo_contract_exists, instantiate_command_line, instantiate_contract,
+        plan_remote_build, remote_build, supported_cargo_contract_versions,
+        unsupported_version_warning, upload_code, upload_command_line, CargoContractInstallError,
+        FinishedBuildSession, Instantiation, InstantiationError, RemoteBuildError,
+        RemoteBuildOptions, Salt, UploadError,
     },
 };
 
@@ -40,75 +44,223 @@ pub(crate) enum DeployError {
     /// Contract could not be instantiated from the downloaded WASM blob.
     #[display(fmt = "unable to instantiate a contract")]
     InstantiationError(InstantiationError),
+
+    /// Contract code could not be uploaded.
+    #[display(fmt = "unable to upload contract code")]
+    UploadError(UploadError),
+
+    /// Unable to store the deployment cache.
+    DeploymentCache(DeploymentCacheError),
+
+    /// Selected deployment profile is invalid.
+    Profile(ProfileError),
+
+    /// Constructor name was provided neither with CLI arguments, nor with the selected profile.
+    #[display(fmt = "contract constructor name is not specified")]
+    ConstructorNotSpecified,
 }
 
 /// Deployment flow entrypoint.
 pub(crate) async fn deploy(
     Deploy {
         constructor,
+        profile,
+        upload_only,
+        dry_run,
         force_new_build_sessions,
         root,
+        upload_retries,
+        force_large_upload,
+        build_timeout,
         url,
         suri,
         args,
+        args_file,
         gas,
         proof_size,
         salt,
         cargo_contract_flags,
     }: Deploy,
+    output: OutputFormat,
 ) -> Result<(), DeployError> {
     let auth_config = AuthenticationConfig::new()?;
     let project_config = ProjectConfig::new()?;
 
-    let progress = ProgressBar::new_spinner();
+    let DeploymentOptions {
+        url,
+        suri,
+        constructor,
+        args,
+        gas,
+        proof_size,
+        cargo_contract_flags,
+        ..
+    } = project_config.deployment_options(
+        profile.as_deref(),
+        DeploymentOptions {
+            url,
+            suri,
+            constructor,
+            args,
+            args_file,
+            gas,
+            proof_size,
+            cargo_contract_flags,
+        },
+    )?;
+
+    if !upload_only && constructor.is_none() {
+        return Err(DeployError::ConstructorNotSpecified);
+    }
+
+    let progress = output.progress_bar();
+
+    // Warn about unsupported versions before uploading the source code,
+    // the server still rejects them during the build session creation.
+    if let Some(warning) = supported_cargo_contract_versions(auth_config.server_path())
+        .await
+        .and_then(|supported| {
+            unsupported_version_warning(&project_config.cargo_contract_version, &supported)
+        })
+    {
+        progress.println(warning);
+    }
 
     let cargo = which::which("cargo")?;
 
+    let build_options = RemoteBuildOptions {
+        force_new_build_sessions,
+        project_directory: root.as_deref(),
+        upload_retries,
+        force_large_upload,
+        build_timeout: build_timeout.map(Duration::from_secs),
+    };
+
+    if dry_run {
+        let plan = plan_remote_build(
+            &auth_config,
+            &project_config,
+            &env::current_dir()?,
+            &progress,
+            &build_options,
+        )
+        .await?;
+
+        progress.finish_and_clear();
+
+        // Temporary files are created only during the actual deployment.
+        let command = if upload_only {
+            upload_command_line(
+                &cargo,
+                Path::new("<contract.wasm>"),
+                url.as_deref(),
+                suri.as_deref(),
+                &cargo_contract_flags,
+            )
+        } else {
+            let salt = salt.unwrap_or_else(Salt::random);
+
+            instantiate_command_line(
+                &cargo,
+                &Instantiation {
+                    constructor: constructor.as_deref().unwrap_or_default(),
+                    args: args.as_deref(),
+                    suri: suri.as_deref(),
+                    url: url.as_deref(),
+                    gas,
+                    proof_size,
+                    salt: &salt,
+                },
+                &cargo_contract_flags,
+                Some(Path::new("<metadata.json>")),
+            )
+        };
+
+        if output.is_json() {
+            output.emit(&Event::DryRun {
+                archive_hash: &plan.archive_hash,
+                archive_size: plan.archive_size,
+                code_hash: plan.existing_code_hash.as_deref(),
+                command: &command,
+            });
+        } else {
+            println!("Archive hash: 0x{}", plan.archive_hash);
+            println!("Archive size: {}", HumanBytes(plan.archive_size));
+
+            match &plan.existing_code_hash {
+                Some(code_hash) => {
+                    println!("Existing build session found, code hash: 0x{code_hash}")
+                }
+                None => println!("No existing build session found, a new one would be started"),
+            }
+
+            println!("Command: {command}");
+        }
+
+        return Ok(());
+    }
+
     ensure_cargo_contract_exists(&cargo, &project_config.cargo_contract_version, &progress).await?;
 
     let FinishedBuildSession {
-        wasm_file,
+        code_file,
         metadata_file,
         code_hash,
+        ..
     } = remote_build(
         &auth_config,
         &project_config,
         &progress,
-        force_new_build_sessions,
-        root.as_deref(),
+        output,
+        &build_options,
     )
     .await?;
 
-    progress.set_message("Deploying...");
+    if upload_only {
+        progress.set_message("Uploading...");
 
-    let mut upload_command = Command::new(&cargo);
-
-    upload_command
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .args([
-            "contract",
-            "upload",
-            "--execute",
-            "--skip-confirm",
-            "--skip-dry-run",
-        ])
-        .arg(wasm_file.path())
-        .args(&cargo_contract_flags);
-
-    if let Some(url) = url.as_deref() {
-        upload_command.args(["--url", url]);
-    }
+        upload_code(
+            &cargo,
+            code_file.path(),
+            url.as_deref(),
+            suri.as_deref(),
+            &cargo_contract_flags,
+            output.child_stdout(),
+        )
+        .await?;
+
+        progress.finish_with_message(format!(
+            "Code uploaded: 0x{code_hash} ({}/codeHash/{code_hash})",
+            auth_config.web_path(),
+        ));
 
-    if let Some(suri) = suri.as_deref() {
-        upload_command.args(["--suri", suri]);
+        output.emit(&Event::Completed {
+            code_hash: &code_hash,
+            address: None,
+            verified: None,
+        });
+
+        return Ok(());
     }
 
-    upload_command.spawn()?.wait().await?;
+    progress.set_message("Deploying...");
+
+    let _ = upload_code(
+        &cargo,
+        code_file.path(),
+        url.as_deref(),
+        suri.as_deref(),
+        &cargo_contract_flags,
+        output.child_stdout(),
+    )
+    .await;
 
     // Don't check for upload errors, since we might already have
     // the same code hash uploaded. Proceed with instantiation instead.
 
+    let constructor = constructor.expect("constructor presence is checked before the build");
+    let salt = salt.unwrap_or_else(Salt::random);
+
     let instantiation_config = Instantiation {
         constructor: &constructor,
         args: args.as_deref(),
@@ -116,22 +268,39 @@ pub(crate) async fn deploy(
         url: url.as_deref(),
         gas,
         proof_size,
+        salt: &salt,
     };
 
-    instantiate_contract(
+    let address = instantiate_contract(
         &cargo,
         &instantiation_config,
         &cargo_contract_flags,
         Some(metadata_file.path()),
-        salt.unwrap_or_else(|| thread_rng().gen()),
     )
     .await?;
 
+    // Keep the metadata around to simplify further interactions with the contract.
+    let metadata_path = Path::new(STATE_DIR).join(format!("{code_hash}.json"));
+    fs::create_dir_all(STATE_DIR)?;
+    fs::copy(metadata_file.path(), &metadata_path)?;
+
+    DeploymentCache {
+        address: address.clone(),
+        metadata_path,
+    }
+    .store()?;
+
     progress.finish_with_message(format!(
-        "Contract uploaded: {}/codeHash/{}",
+        "Contract uploaded: {}/codeHash/{}\nAddress: {address}\nSalt: {salt}",
         auth_config.web_path(),
         code_hash
     ));
 
+    output.emit(&Event::Completed {
+        code_hash: &code_hash,
+        address: Some(&address),
+        verified: None,
+    });
+
     Ok(())
 }
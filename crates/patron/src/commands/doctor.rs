@@ -0,0 +1,415 @@
+use std::{ffi::OsStr, path::Path, process::Stdio};
+
+use derive_more::{Display, Error};
+use reqwest::Client;
+use tokio::process::Command;
+
+use crate::{
+    commands::auth::validate_token,
+    config::{default_server_path, AuthenticationConfig, ProjectConfig},
+    process::{docker_exists, docker_installation_guide, installed_cargo_contract_version},
+};
+
+/// Remediation hint used when the Rust toolchain is unavailable.
+const RUSTUP_HINT: &str = "install the Rust toolchain using https://rustup.rs";
+
+/// Remediation hint used when the authentication token is unavailable or invalid.
+const AUTH_HINT: &str = "use `patron auth` to authenticate";
+
+/// `doctor` subcommand errors.
+#[derive(Debug, Display, Error)]
+pub(crate) enum DoctorError {
+    /// One or more of the required checks failed.
+    #[display(fmt = "{} required check(s) failed", failed)]
+    RequiredChecksFailed {
+        /// Amount of failed required checks.
+        failed: usize,
+    },
+}
+
+/// Result of a single environment check.
+#[derive(Debug)]
+struct Check {
+    /// Human-readable check name.
+    name: &'static str,
+
+    /// Whether the CLI can't be used if this check fails.
+    required: bool,
+
+    /// Check outcome.
+    outcome: Outcome,
+}
+
+/// Environment check outcome.
+#[derive(Debug)]
+enum Outcome {
+    /// Check passed, with the provided details.
+    Passed(String),
+
+    /// Check failed.
+    Failed {
+        /// Failure details.
+        details: String,
+
+        /// Remediation hint.
+        hint: String,
+    },
+}
+
+impl Check {
+    /// Create a passed check.
+    fn passed(name: &'static str, required: bool, details: impl Into<String>) -> Self {
+        Self {
+            name,
+            required,
+            outcome: Outcome::Passed(details.into()),
+        }
+    }
+
+    /// Create a failed check.
+    fn failed(
+        name: &'static str,
+        required: bool,
+        details: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            name,
+            required,
+            outcome: Outcome::Failed {
+                details: details.into(),
+                hint: hint.into(),
+            },
+        }
+    }
+
+    /// Check if this check is required and failed.
+    fn is_hard_failure(&self) -> bool {
+        self.required && matches!(self.outcome, Outcome::Failed { .. })
+    }
+}
+
+/// Doctor flow entrypoint.
+pub(crate) async fn doctor() -> Result<(), DoctorError> {
+    let (server_path, token) = match AuthenticationConfig::new() {
+        Ok(config) => (
+            String::from(config.server_path()),
+            Some(String::from(config.token())),
+        ),
+        Err(_) => (
+            AuthenticationConfig::public_server_path().unwrap_or_else(|_| default_server_path()),
+            None,
+        ),
+    };
+
+    // Doctor can be launched outside of the project directory,
+    // in which case the installed cargo-contract version is not compared.
+    let project_config = ProjectConfig::new().ok();
+    let cargo = which::which("cargo").ok();
+
+    let checks = [
+        check_rustup("rustup").await,
+        check_cargo_contract(
+            cargo.as_deref(),
+            project_config
+                .as_ref()
+                .map(|config| &*config.cargo_contract_version),
+        )
+        .await,
+        check_docker("docker").await,
+        check_server(&server_path).await,
+        check_token(&server_path, token.as_deref()).await,
+    ];
+
+    print!("{}", format_table(&checks));
+
+    let failed = checks
+        .iter()
+        .filter(|check| check.is_hard_failure())
+        .count();
+
+    if failed > 0 {
+        return Err(DoctorError::RequiredChecksFailed { failed });
+    }
+
+    Ok(())
+}
+
+/// Check if the provided `rustup` binary has an active toolchain.
+async fn check_rustup(rustup: impl AsRef<OsStr>) -> Check {
+    /// Check name.
+    const NAME: &str = "Rust toolchain";
+
+    let output = Command::new(rustup)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .args(["show", "active-toolchain"])
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let toolchain = stdout.split_ascii_whitespace().next().unwrap_or_default();
+
+            Check::passed(NAME, true, toolchain)
+        }
+        Ok(_) => Check::failed(
+            NAME,
+            true,
+            "no active toolchain",
+            "use `rustup default stable` to install the stable toolchain",
+        ),
+        Err(_) => Check::failed(NAME, true, "rustup not found", RUSTUP_HINT),
+    }
+}
+
+/// Check if `cargo-contract` is installed with the version required by the project.
+///
+/// This check is not required, since the required version is installed automatically
+/// during the deployment process.
+async fn check_cargo_contract(cargo: Option<&Path>, required_version: Option<&str>) -> Check {
+    /// Check name.
+    const NAME: &str = "cargo-contract";
+
+    let Some(cargo) = cargo else {
+        return Check::failed(NAME, false, "cargo not found", RUSTUP_HINT);
+    };
+
+    let hint = "`patron deploy` installs the required version automatically";
+
+    match installed_cargo_contract_version(cargo).await {
+        Ok(Some(version)) => match required_version {
+            Some(required) if !version.starts_with(required) => Check::failed(
+                NAME,
+                false,
+                format!("{version} installed, project requires {required}"),
+                hint,
+            ),
+            _ => Check::passed(NAME, false, version),
+        },
+        Ok(None) => Check::failed(NAME, false, "not installed", hint),
+        Err(error) => Check::failed(NAME, false, error.to_string(), hint),
+    }
+}
+
+/// Check if the provided Docker binary can be invoked.
+///
+/// This check is not required, since Docker is only used by commands that build contracts locally.
+async fn check_docker(docker: impl AsRef<OsStr>) -> Check {
+    /// Check name.
+    const NAME: &str = "Docker";
+
+    if docker_exists(docker).await {
+        Check::passed(NAME, false, "available")
+    } else {
+        Check::failed(
+            NAME,
+            false,
+            "Docker not found",
+            format!(
+                "`patron verify` and `patron watch` require Docker, consult {} to install it",
+                docker_installation_guide()
+            ),
+        )
+    }
+}
+
+/// Check if the API server is reachable.
+async fn check_server(server_path: &str) -> Check {
+    /// Check name.
+    const NAME: &str = "API server";
+
+    let response = Client::new()
+        .get(format!("{server_path}/docs/api.json"))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+
+    match response {
+        Ok(_) => Check::passed(NAME, true, server_path),
+        Err(error) => Check::failed(
+            NAME,
+            true,
+            error.to_string(),
+            "check your network connection and the server path passed to `patron auth`",
+        ),
+    }
+}
+
+/// Check if the stored authentication token is accepted by the server.
+async fn check_token(server_path: &str, token: Option<&str>) -> Check {
+    /// Check name.
+    const NAME: &str = "Authentication";
+
+    let Some(token) = token else {
+        return Check::failed(NAME, true, "token not found", AUTH_HINT);
+    };
+
+    match validate_token(server_path, token).await {
+        Ok(true) => Check::passed(NAME, true, "token is valid"),
+        Ok(false) => Check::failed(NAME, true, "token was rejected by the server", AUTH_HINT),
+        Err(error) => Check::failed(
+            NAME,
+            true,
+            format!("unable to validate token: {error}"),
+            AUTH_HINT,
+        ),
+    }
+}
+
+/// Format checks as a human-readable table, followed by remediation hints for failed checks.
+fn format_table(checks: &[Check]) -> String {
+    let mut table = format!("{:<16} {:<6} {}\n", "CHECK", "STATUS", "DETAILS");
+    let mut hints = String::new();
+
+    for check in checks {
+        let (status, details) = match &check.outcome {
+            Outcome::Passed(details) => ("ok", details),
+            Outcome::Failed { details, hint } => {
+                hints.push_str(&format!("{}: {hint}\n", check.name));
+
+                (if check.required { "fail" } else { "warn" }, details)
+            }
+        };
+
+        table.push_str(&format!("{:<16} {:<6} {}\n", check.name, status, details));
+    }
+
+    if !hints.is_empty() {
+        table.push_str("\nHints:\n");
+        table.push_str(&hints);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_cargo_contract, check_docker, check_rustup, check_server, check_token, format_table,
+        Check, Outcome,
+    };
+    use crate::testing::stub_server;
+
+    /// Create an executable shell script, that prints the provided output.
+    #[cfg(unix)]
+    fn stub_binary(dir: &std::path::Path, output: &str) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("stub");
+        std::fs::write(&path, format!("#!/bin/sh\necho '{output}'\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        path
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn rustup_toolchain() {
+        let dir = tempfile::tempdir().unwrap();
+        let rustup = stub_binary(dir.path(), "stable-x86_64-unknown-linux-gnu (default)");
+
+        assert!(matches!(
+            check_rustup(&rustup).await.outcome,
+            Outcome::Passed(toolchain) if toolchain == "stable-x86_64-unknown-linux-gnu"
+        ));
+
+        assert!(check_rustup(dir.path().join("missing"))
+            .await
+            .is_hard_failure());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cargo_contract_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let cargo = stub_binary(dir.path(), "cargo-contract-contract 3.2.0-unknown-x86_64");
+
+        assert!(matches!(
+            check_cargo_contract(Some(&cargo), Some("3.2.0")).await.outcome,
+            Outcome::Passed(version) if version == "3.2.0-unknown-x86_64"
+        ));
+
+        let mismatch = check_cargo_contract(Some(&cargo), Some("4.0.0")).await;
+        assert!(matches!(mismatch.outcome, Outcome::Failed { .. }));
+        assert!(!mismatch.is_hard_failure());
+
+        assert!(matches!(
+            check_cargo_contract(None, None).await.outcome,
+            Outcome::Failed { .. }
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn docker_availability() {
+        let dir = tempfile::tempdir().unwrap();
+        let docker = stub_binary(dir.path(), "Docker version 24.0.5");
+
+        assert!(matches!(
+            check_docker(&docker).await.outcome,
+            Outcome::Passed(_)
+        ));
+        let missing = check_docker(dir.path().join("missing")).await;
+        assert!(matches!(missing.outcome, Outcome::Failed { .. }));
+        assert!(!missing.is_hard_failure());
+    }
+
+    #[tokio::test]
+    async fn server_connectivity() {
+        let server = stub_server(|path| {
+            if path == "/docs/api.json" {
+                (200, String::from("{}"))
+            } else {
+                (404, String::new())
+            }
+        })
+        .await;
+
+        assert!(matches!(
+            check_server(&server).await.outcome,
+            Outcome::Passed(_)
+        ));
+        assert!(check_server(&format!("{server}/missing"))
+            .await
+            .is_hard_failure());
+    }
+
+    #[tokio::test]
+    async fn token_validity() {
+        let server = stub_server(|path| {
+            if path == "/keys" {
+                (401, String::new())
+            } else {
+                (404, String::new())
+            }
+        })
+        .await;
+
+        assert!(check_token(&server, Some("token")).await.is_hard_failure());
+        assert!(check_token(&server, None).await.is_hard_failure());
+    }
+
+    #[test]
+    fn table_with_hints() {
+        let table = format_table(&[
+            Check::passed("Docker", true, "available"),
+            Check::failed("cargo-contract", false, "not installed", "install it"),
+            Check::failed("Authentication", true, "token not found", "authenticate"),
+        ]);
+
+        assert_eq!(
+            table,
+            "CHECK            STATUS DETAILS\n\
+             Docker           ok     available\n\
+             cargo-contract   warn   not installed\n\
+             Authentication   fail   token not found\n\
+             \n\
+             Hints:\n\
+             cargo-contract: install it\n\
+             Authentication: authenticate\n"
+        );
+    }
+}
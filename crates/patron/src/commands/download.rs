@@ -0,0 +1,593 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bytes::Bytes;
+use common::{artifact_signature::SigningPublicKey, hash::Hash32};
+use derive_more::{Display, Error, From};
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+
+use crate::{
+    commands::Download,
+    config::{AuthenticationConfig, AuthenticationConfigError},
+    process::{contract_bundle, ArtifactKind, BuildSessionStatus},
+    signature::{fetch_signature, verify_signature, SignatureError, SignatureStatus},
+};
+
+/// `download` subcommand errors.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum DownloadError {
+    /// Authentication configuration error.
+    Authentication(AuthenticationConfigError),
+
+    /// IO-related error.
+    Io(io::Error),
+
+    /// Metadata JSON parsing error.
+    Json(serde_json::Error),
+
+    /// HTTP client error.
+    Http(reqwest::Error),
+
+    /// Artifact signature verification error.
+    Signature(SignatureError),
+
+    /// The provided value is not a valid hex-encoded code hash.
+    #[display(fmt = "invalid code hash")]
+    InvalidCodeHash,
+
+    /// No verified artifacts were found for the provided code hash.
+    #[display(fmt = "no verified artifacts found for the provided code hash")]
+    ArtifactsNotFound,
+
+    /// Hash of the downloaded contract blob does not match the requested code hash.
+    #[display(fmt = "downloaded contract blob does not match the requested code hash")]
+    CodeHashMismatch,
+
+    /// Invalid metadata object.
+    #[display(fmt = "unable to retrieve the 'source' key from the metadata JSON")]
+    InvalidMetadataObject,
+}
+
+/// Artifacts selected for download.
+#[derive(Clone, Copy)]
+struct Selection {
+    /// Download contract blob.
+    wasm: bool,
+
+    /// Download JSON metadata.
+    metadata: bool,
+
+    /// Download bundled `.contract` file.
+    contract: bool,
+}
+
+/// Download flow entrypoint.
+pub(crate) async fn download(
+    Download {
+        code_hash,
+        out,
+        url,
+        wasm,
+        metadata,
+        contract,
+        signing_key,
+    }: Download,
+) -> Result<(), DownloadError> {
+    let server_path = match url {
+        Some(url) => url,
+        None => AuthenticationConfig::public_server_path()?,
+    };
+
+    // Download all artifacts if none were selected explicitly.
+    let selection = if wasm || metadata || contract {
+        Selection {
+            wasm,
+            metadata,
+            contract,
+        }
+    } else {
+        Selection {
+            wasm: true,
+            metadata: true,
+            contract: true,
+        }
+    };
+
+    let (paths, signature) =
+        download_artifacts(&server_path, &code_hash, &out, selection, &signing_key).await?;
+
+    for path in paths {
+        println!("Saved: {}", path.display());
+    }
+
+    signature.print();
+
+    Ok(())
+}
+
+/// Download selected artifacts into the `out` directory.
+///
+/// Contract blob is always downloaded to verify that its hash matches the requested code hash,
+/// and no files are written if the verification fails. The kind of the contract blob
+/// is taken from the build session status, and WASM blobs are assumed if it is not available.
+///
+/// If trusted signing keys are provided, artifacts must be signed with one of them.
+/// The JSON metadata is downloaded to verify the signature before writing any files,
+/// and no files are written if the signature is missing or invalid. Without trusted keys,
+/// artifacts are reported as unverified.
+///
+/// Returns paths of the written files alongside with the signature check outcome.
+async fn download_artifacts(
+    server_path: &str,
+    code_hash: &str,
+    out: &Path,
+    selection: Selection,
+    trusted_keys: &[SigningPublicKey],
+) -> Result<(Vec<PathBuf>, SignatureStatus), DownloadError> {
+    let code_hash = parse_code_hash(code_hash)?;
+
+    let artifact_kind = artifact_kind(server_path, &code_hash).await?;
+
+    let code = match artifact_kind {
+        Some(_) => fetch(server_path, "artifact", &code_hash).await?,
+        None => fetch(server_path, "wasm", &code_hash).await?,
+    };
+
+    let artifact_kind = artifact_kind.unwrap_or_default();
+
+    if artifact_kind.code_hash(&code).to_string() != code_hash {
+        return Err(DownloadError::CodeHashMismatch);
+    }
+
+    let signature = fetch_signature(server_path, None, &code_hash).await?;
+
+    // Signature verification requires the JSON metadata contents,
+    // even if the metadata itself was not selected for download.
+    let raw_metadata = if selection.metadata
+        || selection.contract
+        || signature.is_some()
+        || !trusted_keys.is_empty()
+    {
+        fetch(server_path, "metadata", &code_hash).await?
+    } else {
+        Bytes::new()
+    };
+
+    let signature = verify_signature(signature.as_ref(), &code_hash, &raw_metadata, trusted_keys)?;
+
+    let metadata: Option<Value> = if selection.metadata || selection.contract {
+        Some(serde_json::from_slice(&raw_metadata)?)
+    } else {
+        None
+    };
+
+    fs::create_dir_all(out)?;
+
+    let mut paths = Vec::new();
+
+    if selection.wasm {
+        let path = out.join(format!("{code_hash}.{}", artifact_kind.extension()));
+        fs::write(&path, &code)?;
+        paths.push(path);
+    }
+
+    if let Some(metadata) = metadata {
+        if selection.metadata {
+            let path = out.join(format!("{code_hash}.json"));
+            fs::write(&path, serde_json::to_vec_pretty(&metadata)?)?;
+            paths.push(path);
+        }
+
+        if selection.contract {
+            let bundle = contract_bundle(metadata, artifact_kind, &code)
+                .ok_or(DownloadError::InvalidMetadataObject)?;
+
+            let path = out.join(format!("{code_hash}.contract"));
+            fs::write(&path, serde_json::to_vec(&bundle)?)?;
+            paths.push(path);
+        }
+    }
+
+    Ok((paths, signature))
+}
+
+/// Get the contract blob kind advertised by the latest build session with the provided code hash.
+///
+/// Returns [`None`] if there are no such build sessions, or if the server does not report it.
+async fn artifact_kind(
+    server_path: &str,
+    code_hash: &str,
+) -> Result<Option<ArtifactKind>, DownloadError> {
+    let response = Client::new()
+        .get(format!("{server_path}/buildSessions/status/{code_hash}"))
+        .send()
+        .await?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+
+    let status: BuildSessionStatus = response.error_for_status()?.json().await?;
+
+    Ok(status.artifact_kind)
+}
+
+/// Fetch a single build session artifact from the public API route.
+async fn fetch(server_path: &str, route: &str, code_hash: &str) -> Result<Bytes, DownloadError> {
+    let response = Client::new()
+        .get(format!("{server_path}/buildSessions/{route}/{code_hash}"))
+        .send()
+        .await?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+        return Err(DownloadError::ArtifactsNotFound);
+    }
+
+    Ok(response.error_for_status()?.bytes().await?)
+}
+
+/// Parse hex-encoded code hash with an optional `0x` prefix, returning a lowercase value without it.
+fn parse_code_hash(value: &str) -> Result<String, DownloadError> {
+    value
+        .parse::<Hash32>()
+        .map(|code_hash| code_hash.to_string())
+        .map_err(|_| DownloadError::InvalidCodeHash)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        artifact_signature::SigningKey,
+        hash::{self, Hash32},
+    };
+    use serde_json::{json, Value};
+
+    use super::{download_artifacts, parse_code_hash, DownloadError, Selection};
+    use crate::{
+        signature::{SignatureError, SignatureStatus},
+        testing::stub_server,
+    };
+
+    /// WASM blob served by the stub server.
+    const WASM: &str = "\0asm\u{1}\0\0\0";
+
+    /// JSON metadata served by the stub server.
+    const METADATA: &str = r#"{"source":{"hash":"0x00"}}"#;
+
+    /// Start a stub server, that serves artifacts for the provided code hash.
+    async fn artifacts_server(code_hash: String) -> String {
+        signed_artifacts_server(code_hash, None).await
+    }
+
+    /// Start a stub server, that serves artifacts for the provided code hash
+    /// signed with the provided key over the provided signed code hash.
+    async fn signed_artifacts_server(
+        code_hash: String,
+        signature: Option<(&SigningKey, [u8; 32])>,
+    ) -> String {
+        let details = json!({
+            "signature": signature.map(|(key, signed_code_hash)| json!({
+                "key_id": key.key_id(),
+                "public_key": key.public_key(),
+                "signature": key.sign(&signed_code_hash, &hash::blake2(METADATA.as_bytes())),
+            })),
+        })
+        .to_string();
+
+        stub_server(move |path| {
+            if path == format!("/buildSessions/wasm/{code_hash}") {
+                (200, String::from(WASM))
+            } else if path == format!("/buildSessions/metadata/{code_hash}") {
+                (200, String::from(METADATA))
+            } else if path == format!("/buildSessions/details/{code_hash}") {
+                (200, details.clone())
+            } else {
+                (404, String::from(r#"{"error":"build session not found"}"#))
+            }
+        })
+        .await
+    }
+
+    #[test]
+    fn code_hash_parsing() {
+        let code_hash = "ab".repeat(32);
+
+        assert_eq!(parse_code_hash(&code_hash).unwrap(), code_hash);
+        assert_eq!(
+            parse_code_hash(&format!("0x{}", code_hash.to_uppercase())).unwrap(),
+            code_hash
+        );
+
+        assert!(parse_code_hash("0x1234").is_err());
+        assert!(parse_code_hash("xyz").is_err());
+    }
+
+    /// Create a signing key from the provided seed byte.
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_seed(&format!("0x{}", hex::encode([seed; 32]))).unwrap()
+    }
+
+    #[tokio::test]
+    async fn download_all_artifacts() {
+        let key = signing_key(1);
+        let code_hash = Hash32::blake2(WASM.as_bytes());
+        let server =
+            signed_artifacts_server(code_hash.to_string(), Some((&key, code_hash.0))).await;
+        let code_hash = code_hash.to_string();
+        let out = tempfile::tempdir().unwrap();
+
+        let (paths, signature) = download_artifacts(
+            &server,
+            &format!("0x{code_hash}"),
+            out.path(),
+            Selection {
+                wasm: true,
+                metadata: true,
+                contract: true,
+            },
+            &[key.public_key()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            paths,
+            ["wasm", "json", "contract"].map(|ext| out.path().join(format!("{code_hash}.{ext}")))
+        );
+
+        assert_eq!(std::fs::read(&paths[0]).unwrap(), WASM.as_bytes());
+        assert_eq!(signature, SignatureStatus::Verified(key.key_id()));
+
+        let bundle: Value = serde_json::from_slice(&std::fs::read(&paths[2]).unwrap()).unwrap();
+        assert_eq!(bundle["source"]["wasm"], format!("0x{}", hex::encode(WASM)));
+    }
+
+    #[tokio::test]
+    async fn download_polkavm_artifacts() {
+        const POLKAVM: &str = "PVM\0\u{1}\0\0\0";
+
+        let key = signing_key(1);
+        let code_hash = Hash32::keccak256(POLKAVM.as_bytes());
+        let details = json!({
+            "signature": {
+                "key_id": key.key_id(),
+                "public_key": key.public_key(),
+                "signature": key.sign(&code_hash.0, &hash::blake2(METADATA.as_bytes())),
+            },
+        })
+        .to_string();
+        let code_hash = code_hash.to_string();
+        let hash = code_hash.clone();
+
+        let server = stub_server(move |path| {
+            if path == format!("/buildSessions/status/{hash}") {
+                (
+                    200,
+                    format!(
+                        r#"{{"status":"completed","code_hash":"{hash}","artifact_kind":"polkavm"}}"#
+                    ),
+                )
+            } else if path == format!("/buildSessions/artifact/{hash}") {
+                (200, String::from(POLKAVM))
+            } else if path == format!("/buildSessions/metadata/{hash}") {
+                (200, String::from(METADATA))
+            } else if path == format!("/buildSessions/details/{hash}") {
+                (200, details.clone())
+            } else {
+                (404, String::from(r#"{"error":"build session not found"}"#))
+            }
+        })
+        .await;
+
+        let out = tempfile::tempdir().unwrap();
+
+        let (paths, signature) = download_artifacts(
+            &server,
+            &code_hash,
+            out.path(),
+            Selection {
+                wasm: true,
+                metadata: false,
+                contract: true,
+            },
+            &[key.public_key()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            paths,
+            ["polkavm", "contract"].map(|ext| out.path().join(format!("{code_hash}.{ext}")))
+        );
+
+        assert_eq!(std::fs::read(&paths[0]).unwrap(), POLKAVM.as_bytes());
+        assert_eq!(signature, SignatureStatus::Verified(key.key_id()));
+
+        let bundle: Value = serde_json::from_slice(&std::fs::read(&paths[1]).unwrap()).unwrap();
+        assert_eq!(
+            bundle["source"]["contract_binary"],
+            format!("0x{}", hex::encode(POLKAVM))
+        );
+    }
+
+    #[tokio::test]
+    async fn code_hash_mismatch() {
+        let code_hash = "00".repeat(32);
+        let server = artifacts_server(code_hash.clone()).await;
+        let out = tempfile::tempdir().unwrap();
+
+        let result = download_artifacts(
+            &server,
+            &code_hash,
+            out.path(),
+            Selection {
+                wasm: true,
+                metadata: false,
+                contract: false,
+            },
+            &[],
+        )
+        .await;
+
+        assert!(matches!(result, Err(DownloadError::CodeHashMismatch)));
+        assert_eq!(std::fs::read_dir(out.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn artifacts_not_found() {
+        let server = artifacts_server("00".repeat(32)).await;
+        let out = tempfile::tempdir().unwrap();
+
+        let result = download_artifacts(
+            &server,
+            &"11".repeat(32),
+            out.path(),
+            Selection {
+                wasm: true,
+                metadata: true,
+                contract: true,
+            },
+            &[],
+        )
+        .await;
+
+        assert!(matches!(result, Err(DownloadError::ArtifactsNotFound)));
+    }
+
+    #[tokio::test]
+    async fn unverified_artifacts() {
+        let key = signing_key(1);
+        let code_hash = Hash32::blake2(WASM.as_bytes());
+        let selection = Selection {
+            wasm: true,
+            metadata: true,
+            contract: true,
+        };
+
+        for signature in [None, Some((&key, code_hash.0))] {
+            let server = signed_artifacts_server(code_hash.to_string(), signature).await;
+            let out = tempfile::tempdir().unwrap();
+
+            let (paths, signature) =
+                download_artifacts(&server, &code_hash.to_string(), out.path(), selection, &[])
+                    .await
+                    .unwrap();
+
+            assert_eq!(signature, SignatureStatus::Unverified);
+            assert_eq!(paths.len(), 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn tampered_signature_without_trusted_key() {
+        let key = signing_key(1);
+        let code_hash = Hash32::blake2(WASM.as_bytes()).to_string();
+        let server = signed_artifacts_server(code_hash.clone(), Some((&key, [0; 32]))).await;
+        let out = tempfile::tempdir().unwrap();
+
+        let result = download_artifacts(
+            &server,
+            &code_hash,
+            out.path(),
+            Selection {
+                wasm: true,
+                metadata: false,
+                contract: false,
+            },
+            &[],
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(DownloadError::Signature(SignatureError::InvalidSignature))
+        ));
+        assert_eq!(std::fs::read_dir(out.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn substituted_signing_key() {
+        let key = signing_key(1);
+        let substituted = signing_key(2);
+        let code_hash = Hash32::blake2(WASM.as_bytes());
+        let server =
+            signed_artifacts_server(code_hash.to_string(), Some((&substituted, code_hash.0))).await;
+        let out = tempfile::tempdir().unwrap();
+
+        let result = download_artifacts(
+            &server,
+            &code_hash.to_string(),
+            out.path(),
+            Selection {
+                wasm: true,
+                metadata: false,
+                contract: false,
+            },
+            &[key.public_key()],
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(DownloadError::Signature(SignatureError::UntrustedKey { key_id }))
+                if key_id == substituted.key_id()
+        ));
+        assert_eq!(std::fs::read_dir(out.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn signed_artifacts() {
+        let key = signing_key(1);
+        let code_hash = Hash32::blake2(WASM.as_bytes());
+        let server =
+            signed_artifacts_server(code_hash.to_string(), Some((&key, code_hash.0))).await;
+        let out = tempfile::tempdir().unwrap();
+
+        let (paths, signature) = download_artifacts(
+            &server,
+            &code_hash.to_string(),
+            out.path(),
+            Selection {
+                wasm: true,
+                metadata: false,
+                contract: false,
+            },
+            &[key.public_key()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(paths.len(), 1);
+        assert_eq!(signature, SignatureStatus::Verified(key.key_id()));
+    }
+
+    #[tokio::test]
+    async fn tampered_signature() {
+        let key = signing_key(1);
+        let code_hash = Hash32::blake2(WASM.as_bytes()).to_string();
+        let server = signed_artifacts_server(code_hash.clone(), Some((&key, [0; 32]))).await;
+        let out = tempfile::tempdir().unwrap();
+
+        let result = download_artifacts(
+            &server,
+            &code_hash,
+            out.path(),
+            Selection {
+                wasm: true,
+                metadata: true,
+                contract: true,
+            },
+            &[key.public_key()],
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(DownloadError::Signature(SignatureError::InvalidSignature))
+        ));
+        assert_eq!(std::fs::read_dir(out.path()).unwrap().count(), 0);
+    }
+}
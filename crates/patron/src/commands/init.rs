@@ -0,0 +1,456 @@
+use std::{
+    env::current_dir,
+    fs,
+    io::{self, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use derive_more::{Display, Error, From};
+use serde::Serialize;
+use toml::Table;
+
+use crate::{
+    commands::Init,
+    config::{AuthenticationConfig, STATE_DIR},
+    process::{supported_cargo_contract_versions, unsupported_version_warning},
+};
+
+/// Project configuration file created by the `init` subcommand.
+const CONFIG_FILE: &str = "Patron.toml";
+
+/// `cargo-contract` version suggested by default,
+/// if the server does not report supported versions.
+const DEFAULT_CARGO_CONTRACT_VERSION: &str = "3.2.0";
+
+/// RPC node URL suggested by default.
+const DEFAULT_NODE_URL: &str = "ws://127.0.0.1:9944";
+
+/// Dependency names that mark a package as an ink! smart contract.
+const INK_DEPENDENCIES: [&str; 2] = ["ink", "ink_lang"];
+
+/// `init` subcommand errors.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum InitError {
+    /// IO-related error.
+    Io(io::Error),
+
+    /// Unable to parse `Cargo.toml` file.
+    #[display(fmt = "unable to parse Cargo.toml: {}", _0)]
+    ManifestParseError(toml::de::Error),
+
+    /// Unable to serialize the configuration using [`toml`] crate.
+    Toml(toml::ser::Error),
+
+    /// Current directory doesn't contain a `Cargo.toml` file.
+    #[display(fmt = "Cargo.toml not found in the current directory")]
+    ManifestNotFound,
+
+    /// Current directory is neither an ink! contract, nor a workspace with ones.
+    #[display(fmt = "no ink! contracts were found in the current directory")]
+    ContractNotFound,
+
+    /// Configuration file exists already.
+    #[display(fmt = "Patron.toml exists already, use --force to overwrite it")]
+    ConfigExists,
+}
+
+/// Detected project layout.
+#[derive(Debug, PartialEq, Eq)]
+enum ProjectLayout {
+    /// Single ink! contract.
+    Contract,
+
+    /// Workspace with the provided ink! contract members.
+    Workspace(Vec<String>),
+}
+
+/// Configuration written to the [`CONFIG_FILE`].
+#[derive(Serialize)]
+struct InitConfig<'a> {
+    /// `cargo-contract` package version.
+    cargo_contract_version: &'a str,
+
+    /// Default WebSocket URL of an RPC node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<&'a str>,
+}
+
+/// Init flow entrypoint.
+pub(crate) async fn init(
+    Init {
+        cargo_contract_version,
+        url,
+        force,
+        no_gitignore,
+    }: Init,
+) -> Result<(), InitError> {
+    let dir = current_dir()?;
+
+    match detect_project(&dir)? {
+        ProjectLayout::Contract => println!("Detected ink! contract."),
+        ProjectLayout::Workspace(members) => {
+            println!(
+                "Detected workspace with ink! contracts: {}",
+                members.join(", ")
+            );
+            println!("Use the --root flag to select a contract during the build process.");
+        }
+    }
+
+    if !force && dir.join(CONFIG_FILE).exists() {
+        return Err(InitError::ConfigExists);
+    }
+
+    let supported = match AuthenticationConfig::public_server_path() {
+        Ok(server_path) => supported_cargo_contract_versions(&server_path).await,
+        Err(_) => None,
+    };
+
+    let cargo_contract_version = match cargo_contract_version {
+        Some(version) => version,
+        None => prompt(
+            "cargo-contract version",
+            supported
+                .as_ref()
+                .and_then(|supported| supported.first())
+                .map_or(DEFAULT_CARGO_CONTRACT_VERSION, String::as_str),
+        )?,
+    };
+
+    if let Some(warning) = supported
+        .as_ref()
+        .and_then(|supported| unsupported_version_warning(&cargo_contract_version, supported))
+    {
+        println!("{warning}");
+    }
+
+    let url = match url {
+        Some(url) => url,
+        None => prompt("Default node URL", DEFAULT_NODE_URL)?,
+    };
+
+    let path = write_config(
+        &dir,
+        &InitConfig {
+            cargo_contract_version: &cargo_contract_version,
+            url: Some(&url).filter(|url| !url.is_empty()).map(String::as_str),
+        },
+        force,
+    )?;
+
+    println!("Created {}", path.display());
+
+    if !no_gitignore
+        && !prompt("Add target/ and .patron/ to .gitignore? [Y/n]", "y")?.eq_ignore_ascii_case("n")
+    {
+        update_gitignore(&dir)?;
+    }
+
+    Ok(())
+}
+
+/// Ask user for a value, using the provided default if the input is empty.
+///
+/// Default value is used without asking if the standard input is not a terminal.
+fn prompt(question: &str, default: &str) -> Result<String, io::Error> {
+    let stdin = io::stdin();
+
+    if !stdin.is_terminal() {
+        return Ok(String::from(default));
+    }
+
+    print!("{question} ({default}): ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    stdin.lock().read_line(&mut input)?;
+
+    let input = input.trim();
+
+    Ok(String::from(if input.is_empty() { default } else { input }))
+}
+
+/// Detect ink! contracts inside of the provided directory.
+fn detect_project(dir: &Path) -> Result<ProjectLayout, InitError> {
+    let manifest = read_manifest(dir)?.ok_or(InitError::ManifestNotFound)?;
+
+    if is_contract(dir, &manifest) {
+        return Ok(ProjectLayout::Contract);
+    }
+
+    let patterns = manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(|members| members.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|member| member.as_str());
+
+    let mut members = Vec::new();
+
+    for pattern in patterns {
+        for member in expand_member(dir, pattern)? {
+            let member_dir = dir.join(&member);
+
+            if let Some(manifest) = read_manifest(&member_dir)? {
+                if is_contract(&member_dir, &manifest) {
+                    members.push(member);
+                }
+            }
+        }
+    }
+
+    if members.is_empty() {
+        Err(InitError::ContractNotFound)
+    } else {
+        Ok(ProjectLayout::Workspace(members))
+    }
+}
+
+/// Read and parse `Cargo.toml` file from the provided directory, if there is one.
+fn read_manifest(dir: &Path) -> Result<Option<Table>, InitError> {
+    match fs::read_to_string(dir.join("Cargo.toml")) {
+        Ok(contents) => Ok(Some(contents.parse()?)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Check if the provided manifest describes an ink! contract library.
+fn is_contract(dir: &Path, manifest: &Table) -> bool {
+    let has_ink_dependency = manifest
+        .get("dependencies")
+        .and_then(|dependencies| dependencies.as_table())
+        .map(|dependencies| {
+            INK_DEPENDENCIES
+                .iter()
+                .any(|dep| dependencies.contains_key(*dep))
+        })
+        .unwrap_or(false);
+
+    let lib_path = manifest
+        .get("lib")
+        .and_then(|lib| lib.get("path"))
+        .and_then(|path| path.as_str());
+
+    let has_lib = match lib_path {
+        Some(path) => dir.join(path).is_file(),
+        None => dir.join("src/lib.rs").is_file() || dir.join("lib.rs").is_file(),
+    };
+
+    manifest.contains_key("package") && has_ink_dependency && has_lib
+}
+
+/// Expand workspace member pattern into relative member paths.
+///
+/// Only trailing `*` wildcards are supported, which cover most of the workspace layouts.
+fn expand_member(dir: &Path, pattern: &str) -> Result<Vec<String>, io::Error> {
+    let Some(parent) = pattern.strip_suffix("/*") else {
+        return Ok(vec![String::from(pattern)]);
+    };
+
+    let entries = match fs::read_dir(dir.join(parent)) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    let mut members = Vec::new();
+
+    for entry in entries {
+        let entry = entry?;
+
+        if entry.file_type()?.is_dir() {
+            members.push(format!("{parent}/{}", entry.file_name().to_string_lossy()));
+        }
+    }
+
+    members.sort();
+
+    Ok(members)
+}
+
+/// Write configuration file into the provided directory.
+///
+/// Existing configuration file is only overwritten if `force` is set.
+fn write_config(dir: &Path, config: &InitConfig, force: bool) -> Result<PathBuf, InitError> {
+    let path = dir.join(CONFIG_FILE);
+
+    if !force && path.exists() {
+        return Err(InitError::ConfigExists);
+    }
+
+    fs::write(&path, toml::to_string(config)?)?;
+
+    Ok(path)
+}
+
+/// Append build artifacts and CLI state directories to the `.gitignore` file,
+/// skipping entries that are present already.
+fn update_gitignore(dir: &Path) -> Result<(), io::Error> {
+    let path = dir.join(".gitignore");
+
+    let mut contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(error),
+    };
+
+    let state_dir = format!("{STATE_DIR}/");
+
+    for entry in ["target/", &state_dir] {
+        let name = entry.trim_end_matches('/');
+
+        let exists = contents.lines().any(|line| {
+            let line = line.trim().trim_start_matches('/').trim_end_matches('/');
+            line == name
+        });
+
+        if !exists {
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+
+            contents.push_str(entry);
+            contents.push('\n');
+        }
+    }
+
+    fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::{
+        detect_project, update_gitignore, write_config, InitConfig, InitError, ProjectLayout,
+    };
+
+    /// Manifest of an ink! contract package.
+    const CONTRACT_MANIFEST: &str = r#"
+        [package]
+        name = "flipper"
+
+        [dependencies]
+        ink = { version = "4.2.0", default-features = false }
+
+        [lib]
+        path = "lib.rs"
+    "#;
+
+    /// Create a file with the provided contents, creating any missing parent directories.
+    fn create_file(root: &Path, path: &str, contents: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn single_contract() {
+        let dir = tempfile::tempdir().unwrap();
+
+        create_file(dir.path(), "Cargo.toml", CONTRACT_MANIFEST);
+        create_file(dir.path(), "lib.rs", "");
+
+        assert_eq!(detect_project(dir.path()).unwrap(), ProjectLayout::Contract);
+    }
+
+    #[test]
+    fn contract_without_lib() {
+        let dir = tempfile::tempdir().unwrap();
+
+        create_file(dir.path(), "Cargo.toml", CONTRACT_MANIFEST);
+
+        assert!(matches!(
+            detect_project(dir.path()),
+            Err(InitError::ContractNotFound)
+        ));
+    }
+
+    #[test]
+    fn workspace_members() {
+        let dir = tempfile::tempdir().unwrap();
+
+        create_file(
+            dir.path(),
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["contracts/*", "tools/cli"]
+            "#,
+        );
+        create_file(
+            dir.path(),
+            "contracts/flipper/Cargo.toml",
+            CONTRACT_MANIFEST,
+        );
+        create_file(dir.path(), "contracts/flipper/lib.rs", "");
+        create_file(dir.path(), "contracts/psp22/Cargo.toml", CONTRACT_MANIFEST);
+        create_file(dir.path(), "contracts/psp22/lib.rs", "");
+        create_file(dir.path(), "contracts/README.md", "");
+        create_file(
+            dir.path(),
+            "tools/cli/Cargo.toml",
+            "[package]\nname = \"cli\"\n\n[dependencies]\nclap = \"4\"\n",
+        );
+        create_file(dir.path(), "tools/cli/src/main.rs", "");
+
+        assert_eq!(
+            detect_project(dir.path()).unwrap(),
+            ProjectLayout::Workspace(vec![
+                String::from("contracts/flipper"),
+                String::from("contracts/psp22")
+            ])
+        );
+    }
+
+    #[test]
+    fn missing_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(matches!(
+            detect_project(dir.path()),
+            Err(InitError::ManifestNotFound)
+        ));
+    }
+
+    #[test]
+    fn config_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = InitConfig {
+            cargo_contract_version: "3.2.0",
+            url: Some("ws://127.0.0.1:9944"),
+        };
+
+        let path = write_config(dir.path(), &config, false).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "cargo_contract_version = \"3.2.0\"\nurl = \"ws://127.0.0.1:9944\"\n"
+        );
+
+        assert!(matches!(
+            write_config(dir.path(), &config, false),
+            Err(InitError::ConfigExists)
+        ));
+
+        assert!(write_config(dir.path(), &config, true).is_ok());
+    }
+
+    #[test]
+    fn gitignore_entries() {
+        let dir = tempfile::tempdir().unwrap();
+
+        create_file(dir.path(), ".gitignore", "/target");
+
+        update_gitignore(dir.path()).unwrap();
+        update_gitignore(dir.path()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".gitignore")).unwrap(),
+            "/target\n.patron/\n"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use derive_more::{Display, Error, From};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use time::{macros::format_description, OffsetDateTime};
+
+use crate::{
+    api::ApiClient,
+    commands::List,
+    config::{AuthenticationConfig, AuthenticationConfigError},
+};
+
+/// Length of an abbreviated code hash displayed in a table.
+const ABBREVIATED_CODE_HASH_LENGTH: usize = 12;
+
+/// `list` subcommand errors.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum ListError {
+    /// Authentication configuration error.
+    Authentication(AuthenticationConfigError),
+
+    /// HTTP client error.
+    Http(reqwest::Error),
+
+    /// JSON serialization error.
+    Json(serde_json::Error),
+
+    /// Stored authentication token was rejected by the server.
+    #[display(fmt = "authentication token was rejected, use `patron auth` to authenticate again")]
+    Unauthorized,
+}
+
+/// Information about a single build session.
+#[derive(Serialize, Deserialize)]
+struct BuildSessionEntry {
+    /// Build session identifier.
+    id: i64,
+
+    /// Build session status.
+    status: String,
+
+    /// Code hash, if the build session was completed successfully.
+    code_hash: Option<String>,
+
+    /// Version of `cargo-contract` used to build the contract.
+    cargo_contract_version: String,
+
+    /// Build session creation time as a UNIX timestamp.
+    timestamp: i64,
+}
+
+/// List flow entrypoint.
+pub(crate) async fn list(List { limit, page, json }: List) -> Result<(), ListError> {
+    let auth_config = AuthenticationConfig::new()?;
+
+    let build_sessions = build_sessions(&ApiClient::new(&auth_config), page, limit).await?;
+
+    if json {
+        println!("{}", serde_json::to_string(&build_sessions)?);
+    } else {
+        print!("{}", format_table(&build_sessions));
+    }
+
+    Ok(())
+}
+
+/// Get a page of build sessions of the current user.
+async fn build_sessions(
+    api: &ApiClient,
+    page: u64,
+    limit: Option<u64>,
+) -> Result<Vec<BuildSessionEntry>, ListError> {
+    api.build_sessions(page, limit).await.map_err(|error| {
+        if error.status() == Some(StatusCode::UNAUTHORIZED) {
+            ListError::Unauthorized
+        } else {
+            ListError::Http(error)
+        }
+    })
+}
+
+/// Format build sessions as a human-readable table.
+fn format_table(build_sessions: &[BuildSessionEntry]) -> String {
+    let mut table = format!(
+        "{:<8} {:<12} {:<14} {:<16} {}\n",
+        "ID", "STATUS", "CODE HASH", "CARGO CONTRACT", "CREATED AT"
+    );
+
+    for build_session in build_sessions {
+        let code_hash = build_session
+            .code_hash
+            .as_deref()
+            .map(|code_hash| {
+                format!(
+                    "0x{}",
+                    &code_hash[..code_hash.len().min(ABBREVIATED_CODE_HASH_LENGTH)]
+                )
+            })
+            .unwrap_or_else(|| String::from("-"));
+
+        table.push_str(&format!(
+            "{:<8} {:<12} {:<14} {:<16} {}\n",
+            build_session.id,
+            build_session.status,
+            code_hash,
+            build_session.cargo_contract_version,
+            format_timestamp(build_session.timestamp),
+        ));
+    }
+
+    table
+}
+
+/// Format UNIX timestamp as a UTC date and time.
+fn format_timestamp(timestamp: i64) -> String {
+    OffsetDateTime::from_unix_timestamp(timestamp)
+        .ok()
+        .and_then(|datetime| {
+            datetime
+                .format(format_description!(
+                    "[year]-[month]-[day] [hour]:[minute]:[second] UTC"
+                ))
+                .ok()
+        })
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_sessions, format_table, BuildSessionEntry, ListError};
+    use crate::{api::ApiClient, config::AuthenticationConfig, testing::stub_server};
+
+    fn entries() -> Vec<BuildSessionEntry> {
+        vec![
+            BuildSessionEntry {
+                id: 2,
+                status: String::from("new"),
+                code_hash: None,
+                cargo_contract_version: String::from("3.2.0"),
+                timestamp: 0,
+            },
+            BuildSessionEntry {
+                id: 1,
+                status: String::from("completed"),
+                code_hash: Some("ab".repeat(32)),
+                cargo_contract_version: String::from("3.2.0"),
+                timestamp: 1_700_000_000,
+            },
+        ]
+    }
+
+    #[test]
+    fn table_formatting() {
+        assert_eq!(
+            format_table(&entries()),
+            "ID       STATUS       CODE HASH      CARGO CONTRACT   CREATED AT\n\
+             2        new          -              3.2.0            1970-01-01 00:00:00 UTC\n\
+             1        completed    0xabababababab 3.2.0            2023-11-14 22:13:20 UTC\n"
+        );
+    }
+
+    #[test]
+    fn json_output() {
+        assert_eq!(
+            serde_json::to_value(entries()).unwrap(),
+            serde_json::json!([
+                {
+                    "id": 2,
+                    "status": "new",
+                    "code_hash": null,
+                    "cargo_contract_version": "3.2.0",
+                    "timestamp": 0
+                },
+                {
+                    "id": 1,
+                    "status": "completed",
+                    "code_hash": "ab".repeat(32),
+                    "cargo_contract_version": "3.2.0",
+                    "timestamp": 1_700_000_000
+                }
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn unauthorized() {
+        let server = stub_server(|_| (401, String::new())).await;
+
+        assert!(matches!(
+            build_sessions(
+                &ApiClient::new(&AuthenticationConfig::for_tests(server)),
+                1,
+                None
+            )
+            .await,
+            Err(ListError::Unauthorized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn page_request() {
+        let server = stub_server(|path| {
+            if path == "/buildSessions?page=2&limit=50" {
+                (
+                    200,
+                    String::from(
+                        r#"[{"id":1,"source_code_id":1,"status":"completed","code_hash":null,"cargo_contract_version":"3.2.0","timestamp":0}]"#,
+                    ),
+                )
+            } else {
+                (404, String::new())
+            }
+        })
+        .await;
+
+        let build_sessions = build_sessions(
+            &ApiClient::new(&AuthenticationConfig::for_tests(server)),
+            2,
+            Some(50),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(build_sessions.len(), 1);
+        assert_eq!(build_sessions[0].status, "completed");
+    }
+}
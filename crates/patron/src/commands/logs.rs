@@ -0,0 +1,206 @@
+use std::time::Duration;
+
+use derive_more::{Display, Error, From};
+
+use crate::{
+    api::ApiClient,
+    commands::Logs,
+    config::{AuthenticationConfig, AuthenticationConfigError},
+    process::display_log_text,
+};
+
+/// Interval between build session log requests in follow mode.
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(3);
+
+/// `logs` subcommand errors.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum LogsError {
+    /// Authentication configuration error.
+    Authentication(AuthenticationConfigError),
+
+    /// HTTP client error.
+    Http(reqwest::Error),
+}
+
+/// Logs flow entrypoint.
+pub(crate) async fn logs(Logs { id, follow }: Logs) -> Result<(), LogsError> {
+    let auth_config = AuthenticationConfig::new()?;
+
+    print_logs(
+        &ApiClient::new(&auth_config),
+        &id,
+        follow,
+        FOLLOW_INTERVAL,
+        |text| print!("{text}"),
+    )
+    .await
+}
+
+/// Output build session logs using the provided callback.
+///
+/// If `follow` is set, logs are polled until the build session is finished.
+/// The log truncation marker is replaced with a human-readable notice.
+async fn print_logs<F>(
+    api: &ApiClient,
+    id: &str,
+    follow: bool,
+    interval: Duration,
+    mut output: F,
+) -> Result<(), LogsError>
+where
+    F: FnMut(&str),
+{
+    let mut position = 0;
+
+    loop {
+        // Status is requested before logs to ensure that
+        // no log entries are lost after the build session is finished.
+        let finished = !follow || api.status(id).await?.is_finished();
+
+        let logs = api.logs(id, position).await?;
+
+        for log in &logs.logs {
+            output(&display_log_text(log));
+        }
+
+        if let Some(log) = logs.logs.last() {
+            position = log.id;
+        }
+
+        if finished {
+            return Ok(());
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use common::build_logs::TRUNCATION_MARKER;
+
+    use super::print_logs;
+    use crate::{api::ApiClient, config::AuthenticationConfig, testing::stub_server};
+
+    /// Start a stub server, that finishes build session after the provided amount of status requests.
+    async fn build_session_server(status_requests_until_finished: usize) -> String {
+        let status_requests = Arc::new(AtomicUsize::new(0));
+
+        stub_server(move |path| {
+            if path.starts_with("/buildSessions/status/1") {
+                let requests = status_requests.fetch_add(1, Ordering::SeqCst) + 1;
+
+                let status = if requests >= status_requests_until_finished {
+                    "completed"
+                } else {
+                    "processing"
+                };
+
+                (200, format!(r#"{{"status":"{status}","code_hash":null}}"#))
+            } else if path == "/buildSessions/logs/1?position=0" {
+                (200, String::from(r#"{"logs":[{"id":1,"text":"first\n"}]}"#))
+            } else if path == "/buildSessions/logs/1?position=1" {
+                (
+                    200,
+                    String::from(r#"{"logs":[{"id":2,"text":"second\n"}]}"#),
+                )
+            } else {
+                (200, String::from(r#"{"logs":[]}"#))
+            }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn single_request_without_follow() {
+        let api = ApiClient::new(&AuthenticationConfig::for_tests(
+            build_session_server(1).await,
+        ));
+
+        let mut output = String::new();
+
+        print_logs(&api, "1", false, Duration::ZERO, |text| {
+            output.push_str(text)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(output, "first\n");
+    }
+
+    #[tokio::test]
+    async fn follow_until_finished() {
+        let api = ApiClient::new(&AuthenticationConfig::for_tests(
+            build_session_server(3).await,
+        ));
+
+        let mut output = String::new();
+
+        print_logs(&api, "1", true, Duration::ZERO, |text| {
+            output.push_str(text)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(output, "first\nsecond\n");
+    }
+
+    #[tokio::test]
+    async fn truncation_notice() {
+        let logs = serde_json::json!({
+            "logs": [
+                { "id": 1, "text": "first\n" },
+                { "id": 2, "text": TRUNCATION_MARKER },
+            ]
+        });
+
+        let api = ApiClient::new(&AuthenticationConfig::for_tests(
+            stub_server(move |_| (200, logs.to_string())).await,
+        ));
+
+        let mut output = String::new();
+
+        print_logs(&api, "1", false, Duration::ZERO, |text| {
+            output.push_str(text)
+        })
+        .await
+        .unwrap();
+
+        assert!(output.starts_with("first\n"));
+        assert!(output.contains("Notice: build log size limit was exceeded"));
+        assert!(!output.contains(TRUNCATION_MARKER));
+    }
+
+    #[tokio::test]
+    async fn repeated_entries() {
+        let logs = serde_json::json!({
+            "logs": [
+                { "id": 1, "text": "Downloading\n", "count": 3 },
+                { "id": 2, "text": "Compiling\n", "count": 1 },
+                { "id": 3, "text": "Waiting", "count": 2 },
+            ]
+        });
+
+        let api = ApiClient::new(&AuthenticationConfig::for_tests(
+            stub_server(move |_| (200, logs.to_string())).await,
+        ));
+
+        let mut output = String::new();
+
+        print_logs(&api, "1", false, Duration::ZERO, |text| {
+            output.push_str(text)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(output, "Downloading (x3)\nCompiling\nWaiting (x2)");
+    }
+}
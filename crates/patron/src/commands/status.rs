@@ -0,0 +1,40 @@
+use derive_more::{Display, Error, From};
+
+use crate::{
+    api::ApiClient,
+    commands::Status,
+    config::{AuthenticationConfig, AuthenticationConfigError},
+};
+
+/// `status` subcommand errors.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum StatusError {
+    /// Authentication configuration error.
+    Authentication(AuthenticationConfigError),
+
+    /// HTTP client error.
+    Http(reqwest::Error),
+}
+
+/// Status flow entrypoint.
+pub(crate) async fn status(Status { id }: Status) -> Result<(), StatusError> {
+    let auth_config = AuthenticationConfig::new()?;
+
+    let status = ApiClient::new(&auth_config).status(&id).await?;
+
+    println!("Status: {}", status.status);
+
+    if let Some(position) = status.queue_position {
+        println!("Queue position: {}", position + 1);
+    }
+
+    if let Some(stage) = &status.stage {
+        println!("Stage: {stage}");
+    }
+
+    if let Some(code_hash) = status.code_hash {
+        println!("Code hash: 0x{code_hash}");
+    }
+
+    Ok(())
+}
@@ -1,19 +1,34 @@
 use std::{
-    fs::File,
+    fs::{self, File},
     io::{self, Read},
+    str::FromStr,
+    time::Duration,
 };
 
-use common::hash::blake2;
+use api_types::{client::Client, contracts::ContractData};
+use common::{
+    artifact_signature::SigningPublicKey,
+    hash::Hash32,
+    rpc::{
+        self,
+        sp_core::crypto::AccountId32,
+        substrate_api_client::{self, rpc::JsonrpseeClient, Api, GetChainInfo},
+        MetadataCache,
+    },
+};
 use derive_more::{Display, Error, From};
-use indicatif::ProgressBar;
+use tokio::runtime::Handle;
 
 use crate::{
     commands::Verify,
     config::{AuthenticationConfig, AuthenticationConfigError, ProjectConfig},
+    output::{Event, OutputFormat},
     process::{
         build_locally, ensure_cargo_contract_exists, ensure_docker_exists, remote_build,
         BuildError, CargoContractInstallError, FinishedBuildSession, RemoteBuildError,
+        RemoteBuildOptions,
     },
+    signature::{fetch_signature, verify_signature, SignatureError, SignatureStatus},
 };
 
 /// `verify` subcommand errors.
@@ -44,6 +59,28 @@ pub(crate) enum VerifyError {
 
     /// Unable to install `cargo-contract`.
     CargoContractInstallError(CargoContractInstallError),
+
+    /// HTTP client error.
+    Http(reqwest::Error),
+
+    /// Substrate RPC-related error.
+    #[display(fmt = "rpc error: {:?}", _0)]
+    Rpc(#[error(ignore)] substrate_api_client::Error),
+
+    /// The provided contract address is not a valid account.
+    #[display(fmt = "invalid contract address")]
+    InvalidAddress,
+
+    /// Artifact signature verification error.
+    Signature(SignatureError),
+
+    /// The provided contract address was not found by the API server or the RPC node.
+    #[display(fmt = "contract not found")]
+    ContractNotFound,
+
+    /// Locally built or on-chain code hash does not match the remotely built one.
+    #[display(fmt = "code hashes do not match")]
+    CodeHashMismatch,
 }
 
 /// Verify flow entrypoint.
@@ -51,12 +88,96 @@ pub(crate) async fn verify(
     Verify {
         force_new_build_sessions,
         root,
+        upload_retries,
+        force_large_upload,
+        build_timeout,
+        address,
+        server,
+        url,
+        signing_key,
     }: Verify,
+    output: OutputFormat,
 ) -> Result<(), VerifyError> {
     let auth_config = AuthenticationConfig::new()?;
     let project_config = ProjectConfig::new()?;
 
-    let progress = ProgressBar::new_spinner();
+    let progress = output.progress_bar();
+
+    if let Some(address) = address {
+        progress.set_message("Retrieving deployed contract details...");
+
+        let (source, deployed_code_hash) = match url {
+            Some(url) => {
+                let code_hash = node_code_hash(url.clone(), &address).await?;
+
+                (url, code_hash)
+            }
+            None => {
+                let server_path = server.as_deref().unwrap_or(auth_config.server_path());
+                let details = contract_details(server_path, &address).await?;
+
+                (details.node, details.code_hash)
+            }
+        };
+
+        let FinishedBuildSession {
+            code_hash,
+            metadata_file,
+            ..
+        } = remote_build(
+            &auth_config,
+            &project_config,
+            &progress,
+            output,
+            &RemoteBuildOptions {
+                force_new_build_sessions,
+                project_directory: root.as_deref(),
+                upload_retries,
+                force_large_upload,
+                build_timeout: build_timeout.map(Duration::from_secs),
+            },
+        )
+        .await?;
+
+        progress.finish_and_clear();
+
+        let signature = check_remote_signature(
+            &auth_config,
+            &code_hash,
+            &fs::read(metadata_file.path())?,
+            &signing_key,
+        )
+        .await?;
+
+        let deployed_code_hash = deployed_code_hash.to_string();
+        let verified = code_hashes_match(&deployed_code_hash, &code_hash);
+
+        if output.is_json() {
+            if verified {
+                output.emit(&Event::Completed {
+                    code_hash: &code_hash,
+                    address: Some(&address),
+                    verified: Some(true),
+                });
+            }
+        } else {
+            println!("Deployed code hash ({source}): 0x{deployed_code_hash}");
+            println!("Remote code hash: 0x{}", normalize_code_hash(&code_hash));
+            signature.print();
+
+            if verified {
+                println!("Verified: deployed contract matches the source code.");
+            } else {
+                println!("Mismatch: deployed contract does not match the source code.");
+            }
+        }
+
+        if !verified {
+            return Err(VerifyError::CodeHashMismatch);
+        }
+
+        return Ok(());
+    }
 
     let cargo = which::which("cargo")?;
 
@@ -66,16 +187,37 @@ pub(crate) async fn verify(
         return Err(VerifyError::DockerInstallationMissing);
     }
 
-    let FinishedBuildSession { code_hash, .. } = remote_build(
+    let FinishedBuildSession {
+        code_hash,
+        metadata_file,
+        ..
+    } = remote_build(
         &auth_config,
         &project_config,
         &progress,
-        force_new_build_sessions,
-        root.as_deref(),
+        output,
+        &RemoteBuildOptions {
+            force_new_build_sessions,
+            project_directory: root.as_deref(),
+            upload_retries,
+            force_large_upload,
+            build_timeout: build_timeout.map(Duration::from_secs),
+        },
     )
     .await?;
 
-    println!("Remote code hash: 0x{code_hash}");
+    let signature = check_remote_signature(
+        &auth_config,
+        &code_hash,
+        &fs::read(metadata_file.path())?,
+        &signing_key,
+    )
+    .await?;
+
+    if !output.is_json() {
+        println!("Remote code hash: 0x{code_hash}");
+        signature.print();
+    }
 
     progress.finish_with_message("Remote build finished. Proceeding with the local build...");
 
@@ -85,15 +227,162 @@ pub(crate) async fn verify(
 
     File::open(build_result.dest_wasm)?.read_to_end(&mut wasm_buf)?;
 
-    let local_code_hash = hex::encode(blake2(&wasm_buf));
+    let local_code_hash = Hash32::blake2(&wasm_buf).to_string();
 
-    println!("Local code hash: 0x{local_code_hash}");
+    let verified = local_code_hash == code_hash;
 
-    if local_code_hash == code_hash {
-        println!("Code hashes are matching.");
+    if output.is_json() {
+        output.emit(&Event::Completed {
+            code_hash: &code_hash,
+            address: None,
+            verified: Some(verified),
+        });
     } else {
-        println!("Code hashes do not match.");
+        println!("Local code hash: 0x{local_code_hash}");
+
+        if verified {
+            println!("Code hashes are matching.");
+        } else {
+            println!("Code hashes do not match.");
+        }
+    }
+
+    if !verified {
+        return Err(VerifyError::CodeHashMismatch);
     }
 
     Ok(())
 }
+
+/// Fetch details of a deployed contract from the API server.
+async fn contract_details(server_path: &str, address: &str) -> Result<ContractData, VerifyError> {
+    Client::new(server_path, None)
+        .contract_details(address)
+        .await?
+        .ok_or(VerifyError::ContractNotFound)
+}
+
+/// Fetch the code hash of a deployed contract from the RPC node with the provided URL.
+async fn node_code_hash(url: String, address: &str) -> Result<Hash32, VerifyError> {
+    let contract = AccountId32::from_str(address).map_err(|_| VerifyError::InvalidAddress)?;
+
+    // RPC client blocks the current thread while connecting,
+    // thus it is moved out of the way of the CLI runtime.
+    let info = tokio::task::spawn_blocking(|| {
+        Handle::current().block_on(async move {
+            let client =
+                JsonrpseeClient::new(&url).map_err(substrate_api_client::Error::RpcClient)?;
+            let api = Api::new(client).await?;
+
+            let block_hash = api
+                .get_block_hash(None)
+                .await?
+                .expect("at least one block is expected");
+
+            let mut metadata_cache = MetadataCache::new();
+            let metadata = metadata_cache.metadata(&api, block_hash).await?;
+
+            rpc::contract_info_of(&api, block_hash, &contract, metadata).await
+        })
+    })
+    .await
+    .map_err(|err| substrate_api_client::Error::Other(Box::new(err)))??;
+
+    info.map(|info| Hash32::from(info.code_hash.0))
+        .ok_or(VerifyError::ContractNotFound)
+}
+
+/// Verify the builder signature of remotely built artifacts locally.
+///
+/// Artifacts are reported as unverified if no trusted signing keys are provided,
+/// while missing or invalid signatures are rejected only if they are.
+async fn check_remote_signature(
+    auth_config: &AuthenticationConfig,
+    code_hash: &str,
+    metadata: &[u8],
+    trusted_keys: &[SigningPublicKey],
+) -> Result<SignatureStatus, VerifyError> {
+    let signature = fetch_signature(
+        auth_config.server_path(),
+        Some(auth_config.token().to_owned()),
+        code_hash,
+    )
+    .await?;
+
+    Ok(verify_signature(
+        signature.as_ref(),
+        code_hash,
+        metadata,
+        trusted_keys,
+    )?)
+}
+
+/// Normalize hex-encoded code hash by removing `0x` prefix and converting it to lowercase.
+fn normalize_code_hash(code_hash: &str) -> String {
+    code_hash
+        .strip_prefix("0x")
+        .unwrap_or(code_hash)
+        .to_ascii_lowercase()
+}
+
+/// Compare two hex-encoded code hashes.
+fn code_hashes_match(deployed: &str, built: &str) -> bool {
+    normalize_code_hash(deployed) == normalize_code_hash(built)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{code_hashes_match, contract_details, VerifyError};
+    use crate::testing::stub_server;
+
+    #[test]
+    fn code_hash_comparison() {
+        assert!(code_hashes_match("0xABCD", "abcd"));
+        assert!(code_hashes_match("abcd", "0xabcd"));
+        assert!(!code_hashes_match("abcd", "abce"));
+    }
+
+    #[tokio::test]
+    async fn deployed_contract_details() {
+        let server = stub_server(|path| {
+            if path == "/contracts/5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM" {
+                (
+                    200,
+                    json!({
+                        "node": "alephzero",
+                        "node_id": 1,
+                        "node_display_name": null,
+                        "code_hash": "ab".repeat(32),
+                        "owner": null,
+                        "first_seen_at": null,
+                        "first_seen_block": null,
+                    })
+                    .to_string(),
+                )
+            } else {
+                (404, String::from(r#"{"error":"contract not found"}"#))
+            }
+        })
+        .await;
+
+        let details = contract_details(&server, "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM")
+            .await
+            .unwrap();
+
+        let code_hash = details.code_hash.to_string();
+
+        assert_eq!(details.node, "alephzero");
+        assert!(code_hashes_match(
+            &code_hash,
+            &format!("0x{}", "AB".repeat(32))
+        ));
+        assert!(!code_hashes_match(&code_hash, &"ba".repeat(32)));
+
+        assert!(matches!(
+            contract_details(&server, "unknown").await,
+            Err(VerifyError::ContractNotFound)
+        ));
+    }
+}
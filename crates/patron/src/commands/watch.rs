@@ -3,7 +3,8 @@ use std::{
     fmt::Debug,
     fs::File,
     io::{self, BufReader},
-    path::{Path, StripPrefixError},
+    path::{Path, PathBuf, StripPrefixError},
+    process::Stdio,
 };
 
 use derive_more::{Display, Error, From};
@@ -11,11 +12,11 @@ use futures_util::SinkExt;
 use indicatif::ProgressBar;
 use itertools::Itertools;
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
-use rand::{thread_rng, Rng};
 use serde::Serialize;
 use std::time::Duration;
 use tokio::{
     net::{TcpListener, TcpStream},
+    process::Command,
     sync::{
         mpsc::{self, error::TryRecvError},
         watch::{
@@ -28,10 +29,10 @@ use tokio_tungstenite::tungstenite::Message;
 
 use crate::{
     commands::Watch,
-    config::{default_web_path, ProjectConfig},
+    config::{default_web_path, DeploymentCache, DeploymentOptions, ProfileError, ProjectConfig},
     process::{
         build_locally, ensure_cargo_contract_exists, instantiate_contract, BuildError,
-        CargoContractInstallError, Instantiation, InstantiationError,
+        CargoContractInstallError, Instantiation, InstantiationError, Salt,
     },
 };
 
@@ -81,14 +82,40 @@ pub(crate) enum WatchError {
     /// WebSocket error.
     #[display(fmt = "websocket error: {}", _0)]
     WebsocketError(tokio_tungstenite::tungstenite::Error),
+
+    /// Selected deployment profile is invalid.
+    Profile(ProfileError),
+
+    /// Constructor name was provided neither with CLI arguments, nor with the selected profile.
+    #[display(fmt = "contract constructor name is not specified")]
+    ConstructorNotSpecified,
+
+    /// WebSocket server address is already in use.
+    #[display(
+        fmt = "websocket address {} is already in use, use --ws-port to select a different port",
+        address
+    )]
+    AddressInUse {
+        /// Address used to bind the WebSocket server.
+        address: String,
+    },
 }
 
+/// Default address used to bind the WebSocket server.
+const DEFAULT_WS_HOST: &str = "127.0.0.1";
+
+/// Default port used to bind the WebSocket server.
+const DEFAULT_WS_PORT: u16 = 20600;
+
 /// Information about contract that gets transferred to WebSocket clients.
 #[derive(Serialize)]
 pub(crate) struct ContractInfo {
     /// Node RPC URL.
     node: String,
 
+    /// URL of the WebSocket server that sends contract information.
+    websocket: String,
+
     /// Contract address.
     address: String,
 
@@ -98,30 +125,77 @@ pub(crate) struct ContractInfo {
 
 /// Watch for changes and deploy the contract.
 pub(crate) async fn watch(config: Watch) -> Result<(), WatchError> {
-    let web_domain = config.web_path.clone().unwrap_or_else(default_web_path);
+    let project_config = ProjectConfig::new()?;
 
-    let _ = open::that_in_background(format!("{web_domain}/local-contract-caller"));
+    let address = websocket_address(config.ws_host.as_deref(), config.ws_port, &project_config);
+    let websocket = websocket_url(&address);
 
-    let project_config = ProjectConfig::new()?;
+    let listener = bind_websocket(&address).await?;
+
+    let web_domain = config.web_path.clone().unwrap_or_else(default_web_path);
+
+    let _ = open::that_in_background(format!("{web_domain}/local-contract-caller?ws={websocket}"));
 
     let (sender, receiver) = watch::channel(None);
 
     tokio::try_join!(
-        websocket_server(receiver),
-        watch_for_changes(&project_config, &config, sender)
+        websocket_server(listener, receiver),
+        watch_for_changes(&project_config, &config, &websocket, sender)
     )?;
 
     Ok(())
 }
 
+/// Determine WebSocket server address.
+///
+/// Values provided with CLI flags take precedence over the project configuration.
+fn websocket_address(
+    ws_host: Option<&str>,
+    ws_port: Option<u16>,
+    project_config: &ProjectConfig,
+) -> (String, u16) {
+    let host = ws_host
+        .or(project_config.ws_host.as_deref())
+        .unwrap_or(DEFAULT_WS_HOST);
+
+    let port = ws_port
+        .or(project_config.ws_port)
+        .unwrap_or(DEFAULT_WS_PORT);
+
+    (String::from(host), port)
+}
+
+/// Get WebSocket server URL used by the web UI to connect to the provided address.
+///
+/// Unspecified addresses are replaced with the loopback one, since they cannot be connected to.
+fn websocket_url((host, port): &(String, u16)) -> String {
+    match host.as_str() {
+        "0.0.0.0" => format!("ws://127.0.0.1:{port}"),
+        "::" | "[::]" => format!("ws://[::1]:{port}"),
+        host if host.contains(':') && !host.starts_with('[') => format!("ws://[{host}]:{port}"),
+        host => format!("ws://{host}:{port}"),
+    }
+}
+
+/// Bind WebSocket server to the provided address.
+async fn bind_websocket((host, port): &(String, u16)) -> Result<TcpListener, WatchError> {
+    TcpListener::bind((host.trim_start_matches('[').trim_end_matches(']'), *port))
+        .await
+        .map_err(|error| match error.kind() {
+            io::ErrorKind::AddrInUse => WatchError::AddressInUse {
+                address: format!("{host}:{port}"),
+            },
+            _ => error.into(),
+        })
+}
+
 /// Start WebSocket server.
 ///
 /// This function spawns new task inside the Tokio runtime for each accepted connection.
 async fn websocket_server(
+    socket: TcpListener,
     receiver: watch::Receiver<Option<ContractInfo>>,
 ) -> Result<(), WatchError> {
-    let socket = TcpListener::bind("127.0.0.1:20600").await?;
-
     while let Ok((stream, _)) = socket.accept().await {
         tokio::spawn(handle_connection(stream, receiver.clone()));
     }
@@ -159,14 +233,20 @@ async fn watch_for_changes(
     project_config: &ProjectConfig,
     Watch {
         constructor,
+        profile,
         args,
+        args_file,
         suri,
         url,
         gas,
         proof_size,
+        salt,
+        debounce,
+        pre_deploy_cmd,
         cargo_contract_flags,
         ..
     }: &Watch,
+    websocket: &str,
     info_sender: watch::Sender<Option<ContractInfo>>,
 ) -> Result<(), WatchError> {
     let progress = ProgressBar::new_spinner();
@@ -175,6 +255,31 @@ async fn watch_for_changes(
 
     let pwd = current_dir()?;
 
+    let DeploymentOptions {
+        url,
+        suri,
+        constructor,
+        args,
+        gas,
+        proof_size,
+        cargo_contract_flags,
+        ..
+    } = project_config.deployment_options(
+        profile.as_deref(),
+        DeploymentOptions {
+            url: url.clone(),
+            suri: suri.clone(),
+            constructor: constructor.clone(),
+            args: args.clone(),
+            args_file: args_file.clone(),
+            gas: *gas,
+            proof_size: *proof_size,
+            cargo_contract_flags: cargo_contract_flags.clone(),
+        },
+    )?;
+
+    let constructor = constructor.ok_or(WatchError::ConstructorNotSpecified)?;
+
     ensure_cargo_contract_exists(&cargo, &project_config.cargo_contract_version, &progress).await?;
 
     reset_progress(&progress);
@@ -191,65 +296,75 @@ async fn watch_for_changes(
     )?;
     watcher.watch(Path::new("."), RecursiveMode::Recursive)?;
 
-    let instantiation_args = Instantiation {
-        constructor,
-        args: args.as_deref(),
-        suri: suri.as_deref(),
-        url: url.as_deref(),
-        gas: *gas,
-        proof_size: *proof_size,
-    };
-
-    let mut thread_rng = thread_rng();
-
     while receiver.recv().await.is_some() {
-        loop {
-            // Wait for any additional changes before starting the project build process.
-            tokio::time::sleep(Duration::from_secs(2)).await;
-
-            match receiver.try_recv() {
-                Ok(_) => {
-                    continue;
-                }
-                Err(TryRecvError::Empty) => {
-                    let (address, metadata) = match build_and_deploy(
-                        &cargo,
-                        &instantiation_args,
-                        cargo_contract_flags,
-                        &progress,
-                        thread_rng.gen(),
-                    )
-                    .await
-                    {
-                        Ok(val) => val,
-                        Err(WatchError::BuildError(BuildError::BuildError)) => {
-                            break;
-                        }
-                        Err(e) => return Err(e),
-                    };
-
-                    info_sender.send(Some(ContractInfo {
-                        node: url
-                            .clone()
-                            .unwrap_or_else(|| String::from("ws://127.0.0.1:9944")),
-                        address,
-                        metadata,
-                    }))?;
-
-                    reset_progress(&progress);
-
-                    break;
-                }
-                Err(e) => {
-                    return Err(e.into());
-                }
+        // Wait for any additional changes before starting the project build process.
+        debounce_events(&mut receiver, Duration::from_secs(*debounce)).await?;
+
+        let salt = salt.clone().unwrap_or_else(Salt::random);
+
+        let instantiation_args = Instantiation {
+            constructor: &constructor,
+            args: args.as_deref(),
+            suri: suri.as_deref(),
+            url: url.as_deref(),
+            gas,
+            proof_size,
+            salt: &salt,
+        };
+
+        let (address, metadata) = match build_and_deploy(
+            &cargo,
+            &instantiation_args,
+            &cargo_contract_flags,
+            pre_deploy_cmd.as_deref(),
+            &progress,
+        )
+        .await
+        {
+            Ok(Some(val)) => val,
+            Ok(None) => {
+                reset_progress(&progress);
+                continue;
             }
-        }
+            Err(WatchError::BuildError(BuildError::BuildError)) => {
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        progress.println(format!("Contract {address} instantiated with salt {salt}"));
+
+        info_sender.send(Some(ContractInfo {
+            node: url
+                .clone()
+                .unwrap_or_else(|| String::from("ws://127.0.0.1:9944")),
+            websocket: String::from(websocket),
+            address,
+            metadata,
+        }))?;
+
+        reset_progress(&progress);
     }
 
     Ok(())
 }
 
+/// Wait until no additional events are received during the debounce interval.
+async fn debounce_events<T>(
+    receiver: &mut mpsc::Receiver<T>,
+    interval: Duration,
+) -> Result<(), WatchError> {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        match receiver.try_recv() {
+            Ok(_) => continue,
+            Err(TryRecvError::Empty) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
 /// Check if the provided [`Event`] is eligible to be used as a trigger
 /// for project rebuild.
 ///
@@ -282,27 +397,73 @@ fn is_eligible_event(event: &Event, pwd: &Path) -> bool {
 }
 
 /// Build and deploy a contract locally.
+///
+/// Returns [`None`] if the deployment was skipped due to a failed pre-deploy command.
 async fn build_and_deploy(
     cargo: &Path,
     instantiation_args: &Instantiation<'_>,
     cargo_contract_flags: &[String],
+    pre_deploy_cmd: Option<&str>,
     progress: &ProgressBar,
-    salt: u64,
-) -> Result<(String, serde_json::Value), WatchError> {
+) -> Result<Option<(String, serde_json::Value)>, WatchError> {
     progress.set_message("Building...");
     progress.disable_steady_tick();
 
     let build_result = build_locally(cargo, false).await?;
 
-    let metadata_file = BufReader::new(File::open(build_result.metadata_result.dest_metadata)?);
+    let metadata_path = PathBuf::from(build_result.metadata_result.dest_metadata);
+    let metadata_file = BufReader::new(File::open(&metadata_path)?);
     let metadata: serde_json::Value = serde_json::from_reader(metadata_file)?;
 
+    if let Some(pre_deploy_cmd) = pre_deploy_cmd {
+        progress.set_message("Running pre-deploy command...");
+
+        if !run_pre_deploy_cmd(pre_deploy_cmd, progress).await {
+            progress.println("Pre-deploy command failed, skipping deployment.");
+            return Ok(None);
+        }
+    }
+
     progress.set_message("Deploying...");
 
     let address =
-        instantiate_contract(cargo, instantiation_args, cargo_contract_flags, None, salt).await?;
+        instantiate_contract(cargo, instantiation_args, cargo_contract_flags, None).await?;
+
+    let cache = DeploymentCache {
+        address: address.clone(),
+        metadata_path,
+    };
 
-    Ok((address, metadata))
+    if let Err(error) = cache.store() {
+        progress.println(format!("Unable to store deployment cache: {error}"));
+    }
+
+    Ok(Some((address, metadata)))
+}
+
+/// Run pre-deploy command using the system shell.
+///
+/// Returns `true` if the command exited successfully. Any errors
+/// are reported using the provided [`ProgressBar`] instead of being returned,
+/// since they must not interrupt the watch process.
+async fn run_pre_deploy_cmd(cmd: &str, progress: &ProgressBar) -> bool {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", cmd]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", cmd]);
+        command
+    };
+
+    match command.stdin(Stdio::null()).status().await {
+        Ok(status) => status.success(),
+        Err(error) => {
+            progress.println(format!("Unable to run pre-deploy command: {error}"));
+            false
+        }
+    }
 }
 
 /// Reset progress bar to default message and restore periodic ticks.
@@ -310,3 +471,123 @@ fn reset_progress(progress: &ProgressBar) {
     progress.enable_steady_tick(Duration::from_millis(150));
     progress.set_message("Watching for changes...");
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use indicatif::ProgressBar;
+    use tokio::{sync::mpsc, time::Instant};
+
+    use super::{
+        bind_websocket, debounce_events, run_pre_deploy_cmd, websocket_address, websocket_url,
+        WatchError,
+    };
+    use crate::config::ProjectConfig;
+
+    /// Create project configuration with the provided WebSocket server address.
+    fn project_config(ws_host: Option<&str>, ws_port: Option<u16>) -> ProjectConfig {
+        ProjectConfig {
+            cargo_contract_version: String::from("3.2.0"),
+            max_archive_size: 0,
+            max_artifact_size: 0,
+            url: None,
+            ws_host: ws_host.map(String::from),
+            ws_port,
+            profiles: Default::default(),
+        }
+    }
+
+    #[test]
+    fn address_precedence() {
+        assert_eq!(
+            websocket_address(None, None, &project_config(None, None)),
+            (String::from("127.0.0.1"), 20600)
+        );
+
+        assert_eq!(
+            websocket_address(None, None, &project_config(Some("0.0.0.0"), Some(20700))),
+            (String::from("0.0.0.0"), 20700)
+        );
+
+        assert_eq!(
+            websocket_address(
+                Some("localhost"),
+                Some(20800),
+                &project_config(Some("0.0.0.0"), Some(20700))
+            ),
+            (String::from("localhost"), 20800)
+        );
+    }
+
+    #[test]
+    fn url_construction() {
+        assert_eq!(
+            websocket_url(&(String::from("127.0.0.1"), 20600)),
+            "ws://127.0.0.1:20600"
+        );
+        assert_eq!(
+            websocket_url(&(String::from("0.0.0.0"), 20600)),
+            "ws://127.0.0.1:20600"
+        );
+        assert_eq!(
+            websocket_url(&(String::from("::1"), 20600)),
+            "ws://[::1]:20600"
+        );
+    }
+
+    #[tokio::test]
+    async fn address_in_use() {
+        let listener = bind_websocket(&(String::from("127.0.0.1"), 0))
+            .await
+            .unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(matches!(
+            bind_websocket(&(String::from("127.0.0.1"), port)).await,
+            Err(WatchError::AddressInUse { .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn debounce_without_changes() {
+        let (_sender, mut receiver) = mpsc::channel::<()>(1);
+
+        let start = Instant::now();
+
+        debounce_events(&mut receiver, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn debounce_restarts_on_changes() {
+        let (sender, mut receiver) = mpsc::channel(1);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            sender.send(()).await.unwrap();
+
+            // Keep the channel open until the debounce is finished.
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+
+        let start = Instant::now();
+
+        debounce_events(&mut receiver, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn pre_deploy_cmd_status() {
+        let progress = ProgressBar::hidden();
+
+        assert!(run_pre_deploy_cmd("exit 0", &progress).await);
+        assert!(!run_pre_deploy_cmd("exit 1", &progress).await);
+    }
+}
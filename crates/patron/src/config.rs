@@ -1,4 +1,8 @@
-use std::{fs, io, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
 
 use derive_more::{Display, Error, From};
 use figment::{
@@ -7,6 +11,8 @@ use figment::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::keychain::{self, TokenStore};
+
 /// Authentication configuration errors.
 #[derive(Debug, Display, From, Error)]
 pub enum AuthenticationConfigError {
@@ -22,21 +28,34 @@ pub enum AuthenticationConfigError {
     /// User's home directory cannot be determined.
     #[display(fmt = "unable to find home directory")]
     HomeDirNotFound,
+
+    /// Authentication token was not found in any of the configuration sources.
+    #[display(fmt = "authentication token not found, use `patron auth` to authenticate")]
+    TokenNotFound,
 }
 
 /// Primary authentication config.
 #[derive(Serialize, Deserialize)]
 pub struct AuthenticationConfig {
     /// Authentication token.
+    ///
+    /// This value is empty inside of the configuration file if the token
+    /// is stored in the OS keychain instead.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     token: String,
 
     /// Custom server path specification.
+    #[serde(default = "default_server_path")]
     server_path: String,
 
     /// Custom web path specification.
+    #[serde(default = "default_web_path")]
     web_path: String,
 }
 
+/// Environment variable that overrides the stored authentication token.
+const TOKEN_ENV_VARIABLE: &str = "PATRON_TOKEN";
+
 /// Default server path for the hosted environment.
 pub fn default_server_path() -> String {
     String::from("https://api.patron.works")
@@ -52,33 +71,150 @@ impl AuthenticationConfig {
     ///
     /// See [`Env`] for more details on how to use environment variables configuration.
     ///
+    /// Authentication token provided with the `PATRON_TOKEN` environment variable
+    /// takes precedence over any other configuration source, followed by the OS keychain
+    /// (if enabled) and the configuration file itself.
+    ///
     /// [`Env`]: figment::providers::Env
     pub fn new() -> Result<Self, AuthenticationConfigError> {
-        Ok(Figment::new()
-            .merge(Toml::file(Self::config_path()?))
+        Self::load(&Self::config_path()?, keychain::default_store())
+    }
+
+    /// Load authentication config from the provided path, using an optional token store.
+    ///
+    /// Tokens found in the legacy configuration file are transparently migrated
+    /// to the token store, if it is available.
+    fn load(
+        config_path: &Path,
+        store: Option<&dyn TokenStore>,
+    ) -> Result<Self, AuthenticationConfigError> {
+        let mut config: Self = Self::figment(config_path).extract()?;
+
+        if env::var_os(TOKEN_ENV_VARIABLE).is_some() {
+            return Ok(config);
+        }
+
+        if let Some(store) = store {
+            match store.get(&config.server_path) {
+                Ok(Some(token)) => {
+                    config.token = token;
+                    return Ok(config);
+                }
+                Ok(None) if !config.token.is_empty() => {
+                    if store.set(&config.server_path, &config.token).is_ok() {
+                        config.write_file(config_path, true)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if config.token.is_empty() {
+            return Err(AuthenticationConfigError::TokenNotFound);
+        }
+
+        Ok(config)
+    }
+
+    /// Get configured API server path without requiring an authentication token.
+    ///
+    /// Useful for commands that only use public API routes.
+    pub fn public_server_path() -> Result<String, AuthenticationConfigError> {
+        let config: Self = Self::figment(&Self::config_path()?).extract()?;
+        Ok(config.server_path)
+    }
+
+    /// Create [`Figment`] that merges all supported configuration sources.
+    fn figment(config_path: &Path) -> Figment {
+        Figment::new()
+            .merge(Toml::file(config_path))
             .merge(Env::prefixed("AUTH_"))
-            .extract()?)
+            .merge(
+                Env::raw()
+                    .only(&[TOKEN_ENV_VARIABLE])
+                    .map(|_| "token".into()),
+            )
     }
 
     /// Write the configuration file to the default file location.
+    ///
+    /// If `use_keychain` is set and the OS keychain is available, the token is stored
+    /// inside of the keychain instead of the configuration file.
     pub fn write_token(
         token: String,
         server_path: String,
         web_path: String,
+        use_keychain: bool,
     ) -> Result<(), AuthenticationConfigError> {
-        let path = Self::config_path()?;
-        fs::create_dir_all(path.ancestors().nth(1).expect("incorrect config path"))?;
-        fs::write(
-            path,
-            toml::to_string(&AuthenticationConfig {
-                token,
-                server_path,
-                web_path,
-            })?,
+        let store = keychain::default_store().filter(|_| use_keychain);
+
+        AuthenticationConfig {
+            token,
+            server_path,
+            web_path,
+        }
+        .store(&Self::config_path()?, store)
+    }
+
+    /// Store the current configuration, preferring the provided token store for the token itself.
+    fn store(
+        &self,
+        config_path: &Path,
+        store: Option<&dyn TokenStore>,
+    ) -> Result<(), AuthenticationConfigError> {
+        let stored_in_keychain = match store {
+            Some(store) => store.set(&self.server_path, &self.token).is_ok(),
+            None => {
+                // Remove stale keychain entries, since they take precedence over the file.
+                if let Some(store) = keychain::default_store() {
+                    let _ = store.delete(&self.server_path);
+                }
+
+                false
+            }
+        };
+
+        self.write_file(config_path, stored_in_keychain)
+    }
+
+    /// Write the configuration file, optionally omitting the authentication token.
+    fn write_file(
+        &self,
+        config_path: &Path,
+        omit_token: bool,
+    ) -> Result<(), AuthenticationConfigError> {
+        fs::create_dir_all(
+            config_path
+                .ancestors()
+                .nth(1)
+                .expect("incorrect config path"),
         )?;
+
+        let contents = if omit_token {
+            toml::to_string(&AuthenticationConfig {
+                token: String::new(),
+                server_path: self.server_path.clone(),
+                web_path: self.web_path.clone(),
+            })?
+        } else {
+            toml::to_string(self)?
+        };
+
+        fs::write(config_path, contents)?;
+
         Ok(())
     }
 
+    /// Create authentication config that targets the provided server path.
+    #[cfg(test)]
+    pub fn for_tests(server_path: String) -> Self {
+        Self {
+            token: String::from("test"),
+            server_path,
+            web_path: default_web_path(),
+        }
+    }
+
     /// Get authentication token from the current configuration.
     pub fn token(&self) -> &str {
         &self.token
@@ -109,14 +245,800 @@ impl AuthenticationConfig {
 pub struct ProjectConfig {
     /// `cargo-contract` package version.
     pub cargo_contract_version: String,
+
+    /// Maximum size of the uploaded source code archive in bytes.
+    #[serde(default = "default_max_archive_size")]
+    pub max_archive_size: u64,
+
+    /// Maximum size of a single downloaded build artifact in bytes.
+    #[serde(default = "default_max_artifact_size")]
+    pub max_artifact_size: u64,
+
+    /// Default WebSocket URL of an RPC node.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Address used to bind the WebSocket server of the `watch` subcommand.
+    #[serde(default)]
+    pub ws_host: Option<String>,
+
+    /// Port used to bind the WebSocket server of the `watch` subcommand.
+    #[serde(default)]
+    pub ws_port: Option<u16>,
+
+    /// Named deployment profiles.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Deployment profile, which overrides the default deployment options.
+#[derive(Clone, Default, Deserialize)]
+pub struct Profile {
+    /// WebSocket URL of an RPC node.
+    pub url: Option<String>,
+
+    /// Name of an environment variable that contains the secret URI for signing requests.
+    pub suri_env: Option<String>,
+
+    /// Contract constructor name.
+    pub constructor: Option<String>,
+
+    /// Space-separated values passed to constructor.
+    pub args: Option<String>,
+
+    /// Path to a file with constructor arguments.
+    ///
+    /// Ignored if `args` is set.
+    pub args_file: Option<PathBuf>,
+
+    /// Gas value used to instantiate the contract.
+    pub gas: Option<u64>,
+
+    /// Maximum proof size for contract instantiation.
+    pub proof_size: Option<u64>,
+
+    /// Additional options passed to cargo-contract.
+    #[serde(default)]
+    pub cargo_contract_flags: Vec<String>,
+}
+
+/// Deployment options, that can be provided using CLI flags, profiles or project configuration.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DeploymentOptions {
+    /// WebSocket URL of an RPC node.
+    pub url: Option<String>,
+
+    /// Secret URI for signing requests.
+    pub suri: Option<String>,
+
+    /// Contract constructor name.
+    pub constructor: Option<String>,
+
+    /// Space-separated values passed to constructor.
+    pub args: Option<String>,
+
+    /// Path to a file with constructor arguments.
+    ///
+    /// Always resolved into `args` by [`ProjectConfig::deployment_options`].
+    pub args_file: Option<PathBuf>,
+
+    /// Gas value used to instantiate the contract.
+    pub gas: Option<u64>,
+
+    /// Maximum proof size for contract instantiation.
+    pub proof_size: Option<u64>,
+
+    /// Additional options passed to cargo-contract.
+    pub cargo_contract_flags: Vec<String>,
+}
+
+/// Deployment profile errors.
+#[derive(Debug, Display, Error)]
+pub enum ProfileError {
+    /// Selected profile is not present in the project configuration.
+    #[display(fmt = "unknown profile {}, available profiles: {}", name, available)]
+    UnknownProfile {
+        /// Selected profile name.
+        name: String,
+
+        /// Comma-separated list of available profiles.
+        available: String,
+    },
+
+    /// Environment variable with the secret URI is not set.
+    #[display(
+        fmt = "environment variable {} with the secret URI is not set",
+        variable
+    )]
+    SuriNotSet {
+        /// Environment variable name.
+        variable: String,
+    },
+
+    /// Constructor arguments file could not be used.
+    ArgsFile(ArgsFileError),
+}
+
+/// Constructor arguments file errors.
+#[derive(Debug, Display, Error)]
+pub enum ArgsFileError {
+    /// Unable to read the arguments file.
+    #[display(
+        fmt = "unable to read constructor arguments from {}: {}",
+        "path.display()",
+        source
+    )]
+    Io {
+        /// Arguments file path.
+        path: PathBuf,
+
+        /// Underlying IO error.
+        source: io::Error,
+    },
+
+    /// Arguments file contains an invalid JSON array.
+    #[display(
+        fmt = "invalid JSON array in {}: {} (near `{}`)",
+        "path.display()",
+        source,
+        snippet
+    )]
+    InvalidJson {
+        /// Arguments file path.
+        path: PathBuf,
+
+        /// Offending line of the arguments file.
+        snippet: String,
+
+        /// Underlying JSON parsing error.
+        source: serde_json::Error,
+    },
+}
+
+/// Maximum length of a file content snippet included in error messages.
+const SNIPPET_LENGTH: usize = 64;
+
+/// Default maximum size of the uploaded source code archive (50 MB).
+fn default_max_archive_size() -> u64 {
+    50 * 1024 * 1024
+}
+
+/// Default maximum size of a single downloaded build artifact (32 MB).
+fn default_max_artifact_size() -> u64 {
+    32 * 1024 * 1024
 }
 
 impl ProjectConfig {
-    /// Create new config using default configuration file.
+    /// Create new config using default configuration files.
+    ///
+    /// Values from the `Patron.toml` file take precedence over the `Deploy.toml` ones.
     pub fn new() -> Result<Self, figment::Error> {
+        Self::figment().extract()
+    }
+
+    /// Merge deployment options provided with CLI flags with the selected profile.
+    ///
+    /// CLI flags take precedence over the profile values, which take
+    /// precedence over the project configuration defaults.
+    pub fn deployment_options(
+        &self,
+        profile: Option<&str>,
+        flags: DeploymentOptions,
+    ) -> Result<DeploymentOptions, ProfileError> {
+        let profile =
+            match profile {
+                Some(name) => self.profiles.get(name).cloned().ok_or_else(|| {
+                    ProfileError::UnknownProfile {
+                        name: String::from(name),
+                        available: if self.profiles.is_empty() {
+                            String::from("none")
+                        } else {
+                            self.profiles
+                                .keys()
+                                .map(String::as_str)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        },
+                    }
+                })?,
+                None => Profile::default(),
+            };
+
+        let suri = match (flags.suri, profile.suri_env) {
+            (Some(suri), _) => Some(suri),
+            (None, Some(variable)) => {
+                Some(env::var(&variable).map_err(|_| ProfileError::SuriNotSet { variable })?)
+            }
+            (None, None) => None,
+        };
+
+        let args = match (flags.args, flags.args_file, profile.args, profile.args_file) {
+            (Some(args), ..) => Some(args),
+            (None, Some(path), ..) => Some(read_args_file(&path).map_err(ProfileError::ArgsFile)?),
+            (None, None, Some(args), _) => Some(args),
+            (None, None, None, Some(path)) => {
+                Some(read_args_file(&path).map_err(ProfileError::ArgsFile)?)
+            }
+            (None, None, None, None) => None,
+        };
+
+        Ok(DeploymentOptions {
+            url: flags.url.or(profile.url).or_else(|| self.url.clone()),
+            suri,
+            constructor: flags.constructor.or(profile.constructor),
+            args,
+            args_file: None,
+            gas: flags.gas.or(profile.gas),
+            proof_size: flags.proof_size.or(profile.proof_size),
+            cargo_contract_flags: if flags.cargo_contract_flags.is_empty() {
+                profile.cargo_contract_flags
+            } else {
+                flags.cargo_contract_flags
+            },
+        })
+    }
+
+    /// Create [`Figment`] that merges all supported project configuration sources.
+    fn figment() -> Figment {
         Figment::new()
             .merge(Toml::file("Deploy.toml"))
+            .merge(Toml::file("Patron.toml"))
             .merge(Env::prefixed("DEPLOY_"))
-            .extract()
+    }
+}
+
+/// Read constructor arguments from the provided file.
+///
+/// Files that contain a JSON array are joined into a space-separated list of values,
+/// with string values used as-is. Otherwise, the file content is used directly,
+/// excluding the trailing whitespace.
+fn read_args_file(path: &Path) -> Result<String, ArgsFileError> {
+    let content = fs::read_to_string(path).map_err(|source| ArgsFileError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let content = content.trim_end();
+
+    if !content.trim_start().starts_with('[') {
+        return Ok(String::from(content));
+    }
+
+    let values: Vec<serde_json::Value> =
+        serde_json::from_str(content).map_err(|source| ArgsFileError::InvalidJson {
+            path: path.to_path_buf(),
+            snippet: snippet(content, source.line()),
+            source,
+        })?;
+
+    Ok(values
+        .into_iter()
+        .map(|value| match value {
+            serde_json::Value::String(value) => value,
+            value => value.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+/// Get a shortened line of the provided content, using 1-based line numbers.
+fn snippet(content: &str, line: usize) -> String {
+    let line = content
+        .lines()
+        .nth(line.saturating_sub(1))
+        .unwrap_or_default()
+        .trim();
+
+    if line.chars().count() > SNIPPET_LENGTH {
+        format!(
+            "{}...",
+            line.chars().take(SNIPPET_LENGTH).collect::<String>()
+        )
+    } else {
+        String::from(line)
+    }
+}
+
+/// Directory used to store project-specific CLI state.
+pub const STATE_DIR: &str = ".patron";
+
+/// Deployment cache file name inside of the [`STATE_DIR`].
+const DEPLOYMENT_CACHE_FILE: &str = "deployment.toml";
+
+/// Deployment cache errors.
+#[derive(Debug, Display, From, Error)]
+pub enum DeploymentCacheError {
+    /// Unable to load the deployment cache using [`figment`].
+    Figment(figment::Error),
+
+    /// IO-related error.
+    Io(io::Error),
+
+    /// Unable to serialize the deployment cache using [`toml`] crate.
+    Toml(toml::ser::Error),
+}
+
+/// Information about the last deployed contract.
+///
+/// Used to simplify interactions with the deployed contract without
+/// providing its address and metadata manually.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentCache {
+    /// Address of the deployed contract.
+    pub address: String,
+
+    /// Path to the JSON metadata of the deployed contract.
+    pub metadata_path: PathBuf,
+}
+
+impl DeploymentCache {
+    /// Load deployment cache from the default state directory.
+    ///
+    /// Returns [`None`] if no contracts were deployed previously.
+    pub fn load() -> Result<Option<Self>, DeploymentCacheError> {
+        Self::load_from(Path::new(STATE_DIR))
+    }
+
+    /// Store deployment cache inside of the default state directory.
+    pub fn store(&self) -> Result<(), DeploymentCacheError> {
+        self.store_in(Path::new(STATE_DIR))
+    }
+
+    /// Load deployment cache from the provided state directory.
+    fn load_from(state_dir: &Path) -> Result<Option<Self>, DeploymentCacheError> {
+        let path = state_dir.join(DEPLOYMENT_CACHE_FILE);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(Figment::new().merge(Toml::file(path)).extract()?))
+    }
+
+    /// Store deployment cache inside of the provided state directory.
+    fn store_in(&self, state_dir: &Path) -> Result<(), DeploymentCacheError> {
+        fs::create_dir_all(state_dir)?;
+        fs::write(
+            state_dir.join(DEPLOYMENT_CACHE_FILE),
+            toml::to_string(self)?,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        collections::HashMap,
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use figment::Jail;
+
+    use super::{
+        default_max_archive_size, default_max_artifact_size, default_server_path,
+        AuthenticationConfig, AuthenticationConfigError, DeploymentCache, DeploymentOptions,
+        ProfileError, ProjectConfig,
+    };
+    use crate::keychain::{KeychainError, TokenStore};
+
+    /// In-memory token store used to test the keychain integration.
+    #[derive(Default)]
+    struct MockStore {
+        /// Stored tokens, keyed by the server path.
+        tokens: RefCell<HashMap<String, String>>,
+
+        /// Simulate an unavailable platform keychain.
+        unavailable: bool,
+    }
+
+    impl TokenStore for MockStore {
+        fn get(&self, server_path: &str) -> Result<Option<String>, KeychainError> {
+            if self.unavailable {
+                return Err(KeychainError(String::from("unavailable")));
+            }
+
+            Ok(self.tokens.borrow().get(server_path).cloned())
+        }
+
+        fn set(&self, server_path: &str, token: &str) -> Result<(), KeychainError> {
+            if self.unavailable {
+                return Err(KeychainError(String::from("unavailable")));
+            }
+
+            self.tokens
+                .borrow_mut()
+                .insert(server_path.to_owned(), token.to_owned());
+
+            Ok(())
+        }
+
+        fn delete(&self, server_path: &str) -> Result<(), KeychainError> {
+            self.tokens.borrow_mut().remove(server_path);
+            Ok(())
+        }
+    }
+
+    /// Legacy configuration file contents with a plaintext token.
+    const LEGACY_CONFIG: &str = r#"
+        token = "file"
+        server_path = "https://api.example.com"
+        web_path = "https://example.com"
+    "#;
+
+    #[test]
+    fn keychain_takes_precedence() {
+        Jail::expect_with(|jail| {
+            jail.create_file("auth.toml", LEGACY_CONFIG)?;
+
+            let store = MockStore::default();
+            store.set("https://api.example.com", "keychain").unwrap();
+
+            let config = AuthenticationConfig::load(Path::new("auth.toml"), Some(&store)).unwrap();
+            assert_eq!(config.token(), "keychain");
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn legacy_file_migration() {
+        Jail::expect_with(|jail| {
+            jail.create_file("auth.toml", LEGACY_CONFIG)?;
+
+            let store = MockStore::default();
+
+            let config = AuthenticationConfig::load(Path::new("auth.toml"), Some(&store)).unwrap();
+            assert_eq!(config.token(), "file");
+
+            assert_eq!(
+                store.get("https://api.example.com").unwrap().as_deref(),
+                Some("file")
+            );
+            assert!(!fs::read_to_string("auth.toml").unwrap().contains("token"));
+
+            let config = AuthenticationConfig::load(Path::new("auth.toml"), Some(&store)).unwrap();
+            assert_eq!(config.token(), "file");
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn unavailable_keychain_fallback() {
+        Jail::expect_with(|jail| {
+            jail.create_file("auth.toml", LEGACY_CONFIG)?;
+
+            let store = MockStore {
+                unavailable: true,
+                ..Default::default()
+            };
+
+            let config = AuthenticationConfig::load(Path::new("auth.toml"), Some(&store)).unwrap();
+            assert_eq!(config.token(), "file");
+            assert!(fs::read_to_string("auth.toml").unwrap().contains("token"));
+
+            AuthenticationConfig::for_tests(String::from("https://api.example.com"))
+                .store(Path::new("stored.toml"), Some(&store))
+                .unwrap();
+            assert!(fs::read_to_string("stored.toml").unwrap().contains("token"));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn missing_token() {
+        Jail::expect_with(|jail| {
+            jail.create_file("auth.toml", r#"server_path = "https://api.example.com""#)?;
+
+            assert!(matches!(
+                AuthenticationConfig::load(Path::new("auth.toml"), Some(&MockStore::default())),
+                Err(AuthenticationConfigError::TokenNotFound)
+            ));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn token_env_variable_precedence() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                "auth.toml",
+                r#"
+                    token = "file"
+                    server_path = "https://api.example.com"
+                    web_path = "https://example.com"
+                "#,
+            )?;
+
+            let config: AuthenticationConfig =
+                AuthenticationConfig::figment(Path::new("auth.toml")).extract()?;
+            assert_eq!(config.token(), "file");
+
+            jail.set_env("AUTH_TOKEN", "auth");
+            let config: AuthenticationConfig =
+                AuthenticationConfig::figment(Path::new("auth.toml")).extract()?;
+            assert_eq!(config.token(), "auth");
+
+            jail.set_env("PATRON_TOKEN", "patron");
+            let config: AuthenticationConfig =
+                AuthenticationConfig::figment(Path::new("auth.toml")).extract()?;
+            assert_eq!(config.token(), "patron");
+            assert_eq!(config.server_path(), "https://api.example.com");
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn token_env_variable_without_file() {
+        Jail::expect_with(|jail| {
+            jail.set_env("PATRON_TOKEN", "patron");
+
+            let config: AuthenticationConfig =
+                AuthenticationConfig::figment(Path::new("missing.toml")).extract()?;
+            assert_eq!(config.token(), "patron");
+            assert_eq!(config.server_path(), default_server_path());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn project_config_sources() {
+        Jail::expect_with(|jail| {
+            jail.create_file("Deploy.toml", r#"cargo_contract_version = "3.2.0""#)?;
+
+            let config: ProjectConfig = ProjectConfig::figment().extract()?;
+            assert_eq!(config.cargo_contract_version, "3.2.0");
+            assert_eq!(config.max_archive_size, default_max_archive_size());
+            assert_eq!(config.max_artifact_size, default_max_artifact_size());
+
+            jail.create_file("Patron.toml", "max_archive_size = 1024")?;
+
+            let config: ProjectConfig = ProjectConfig::figment().extract()?;
+            assert_eq!(config.cargo_contract_version, "3.2.0");
+            assert_eq!(config.max_archive_size, 1024);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn deployment_cache_roundtrip() {
+        let state_dir = tempfile::tempdir().unwrap();
+
+        assert_eq!(DeploymentCache::load_from(state_dir.path()).unwrap(), None);
+
+        let cache = DeploymentCache {
+            address: String::from("5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM"),
+            metadata_path: state_dir.path().join("contract.json"),
+        };
+
+        cache.store_in(state_dir.path()).unwrap();
+
+        assert_eq!(
+            DeploymentCache::load_from(state_dir.path()).unwrap(),
+            Some(cache)
+        );
+    }
+
+    /// Project configuration with `testnet` and `mainnet` profiles.
+    const PROFILES_CONFIG: &str = r#"
+        cargo_contract_version = "3.2.0"
+        url = "ws://127.0.0.1:9944"
+
+        [profiles.testnet]
+        url = "wss://rpc.shibuya.astar.network"
+        constructor = "new"
+        args = "1000"
+
+        [profiles.mainnet]
+        url = "wss://rpc.astar.network"
+        suri_env = "MAINNET_SURI"
+        cargo_contract_flags = ["--storage-deposit-limit", "100"]
+    "#;
+
+    #[test]
+    fn deployment_options_precedence() {
+        Jail::expect_with(|jail| {
+            jail.create_file("Patron.toml", PROFILES_CONFIG)?;
+
+            let config: ProjectConfig = ProjectConfig::figment().extract()?;
+
+            let options = config
+                .deployment_options(None, DeploymentOptions::default())
+                .unwrap();
+            assert_eq!(options.url.as_deref(), Some("ws://127.0.0.1:9944"));
+            assert_eq!(options.constructor, None);
+
+            let options = config
+                .deployment_options(Some("testnet"), DeploymentOptions::default())
+                .unwrap();
+            assert_eq!(
+                options.url.as_deref(),
+                Some("wss://rpc.shibuya.astar.network")
+            );
+            assert_eq!(options.constructor.as_deref(), Some("new"));
+            assert_eq!(options.args.as_deref(), Some("1000"));
+
+            let options = config
+                .deployment_options(
+                    Some("testnet"),
+                    DeploymentOptions {
+                        url: Some(String::from("ws://localhost:9944")),
+                        args: Some(String::from("1")),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            assert_eq!(options.url.as_deref(), Some("ws://localhost:9944"));
+            assert_eq!(options.constructor.as_deref(), Some("new"));
+            assert_eq!(options.args.as_deref(), Some("1"));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn profile_suri_and_flags() {
+        Jail::expect_with(|jail| {
+            jail.create_file("Patron.toml", PROFILES_CONFIG)?;
+
+            let config: ProjectConfig = ProjectConfig::figment().extract()?;
+
+            assert!(matches!(
+                config.deployment_options(Some("mainnet"), DeploymentOptions::default()),
+                Err(ProfileError::SuriNotSet { .. })
+            ));
+
+            jail.set_env("MAINNET_SURI", "//Alice");
+
+            let options = config
+                .deployment_options(Some("mainnet"), DeploymentOptions::default())
+                .unwrap();
+            assert_eq!(options.suri.as_deref(), Some("//Alice"));
+            assert_eq!(
+                options.cargo_contract_flags,
+                ["--storage-deposit-limit", "100"]
+            );
+
+            let options = config
+                .deployment_options(
+                    Some("mainnet"),
+                    DeploymentOptions {
+                        suri: Some(String::from("//Bob")),
+                        cargo_contract_flags: vec![String::from("--verbose")],
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            assert_eq!(options.suri.as_deref(), Some("//Bob"));
+            assert_eq!(options.cargo_contract_flags, ["--verbose"]);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn args_file_precedence() {
+        Jail::expect_with(|jail| {
+            jail.create_file(
+                "Patron.toml",
+                r#"
+                    cargo_contract_version = "3.2.0"
+
+                    [profiles.file]
+                    args_file = "profile-args.txt"
+
+                    [profiles.inline]
+                    args = "inline"
+                    args_file = "profile-args.txt"
+                "#,
+            )?;
+            jail.create_file("profile-args.txt", "1 2 3\n\n")?;
+            jail.create_file("args.json", r#"["5GrwvaEF", 1000, {"a": true}]"#)?;
+
+            let config: ProjectConfig = ProjectConfig::figment().extract()?;
+
+            let options = config
+                .deployment_options(Some("file"), DeploymentOptions::default())
+                .unwrap();
+            assert_eq!(options.args.as_deref(), Some("1 2 3"));
+
+            let options = config
+                .deployment_options(Some("inline"), DeploymentOptions::default())
+                .unwrap();
+            assert_eq!(options.args.as_deref(), Some("inline"));
+
+            let options = config
+                .deployment_options(
+                    Some("inline"),
+                    DeploymentOptions {
+                        args_file: Some(PathBuf::from("args.json")),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            assert_eq!(options.args.as_deref(), Some(r#"5GrwvaEF 1000 {"a":true}"#));
+
+            let options = config
+                .deployment_options(
+                    Some("file"),
+                    DeploymentOptions {
+                        args: Some(String::from("flag")),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            assert_eq!(options.args.as_deref(), Some("flag"));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn invalid_args_file() {
+        Jail::expect_with(|jail| {
+            jail.create_file("Patron.toml", r#"cargo_contract_version = "3.2.0""#)?;
+            jail.create_file("args.json", "[\n  1,\n  oops\n]")?;
+
+            let config: ProjectConfig = ProjectConfig::figment().extract()?;
+
+            let error = config
+                .deployment_options(
+                    None,
+                    DeploymentOptions {
+                        args_file: Some(PathBuf::from("args.json")),
+                        ..Default::default()
+                    },
+                )
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(error.contains("args.json"), "{error}");
+            assert!(error.contains("`oops`"), "{error}");
+
+            let error = config
+                .deployment_options(
+                    None,
+                    DeploymentOptions {
+                        args_file: Some(PathBuf::from("missing.txt")),
+                        ..Default::default()
+                    },
+                )
+                .err()
+                .unwrap()
+                .to_string();
+            assert!(error.contains("missing.txt"), "{error}");
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn unknown_profile() {
+        Jail::expect_with(|jail| {
+            jail.create_file("Patron.toml", PROFILES_CONFIG)?;
+
+            let config: ProjectConfig = ProjectConfig::figment().extract()?;
+
+            let error = config
+                .deployment_options(Some("devnet"), DeploymentOptions::default())
+                .err()
+                .unwrap();
+
+            assert_eq!(
+                error.to_string(),
+                "unknown profile devnet, available profiles: mainnet, testnet"
+            );
+
+            Ok(())
+        });
     }
 }
@@ -0,0 +1,72 @@
+use derive_more::{Display, Error};
+
+/// Service name used to store authentication tokens in the OS keychain.
+#[cfg(feature = "keychain")]
+const KEYCHAIN_SERVICE: &str = "patron";
+
+/// Keychain access error, which contains a message reported by the keychain backend.
+#[derive(Debug, Display, Error)]
+#[display(fmt = "keychain is unavailable: {}", _0)]
+pub struct KeychainError(#[error(not(source))] pub String);
+
+/// Secure storage of authentication tokens, keyed by the API server path.
+pub trait TokenStore {
+    /// Get authentication token related to the provided server path.
+    fn get(&self, server_path: &str) -> Result<Option<String>, KeychainError>;
+
+    /// Store authentication token for the provided server path.
+    fn set(&self, server_path: &str, token: &str) -> Result<(), KeychainError>;
+
+    /// Remove authentication token related to the provided server path.
+    fn delete(&self, server_path: &str) -> Result<(), KeychainError>;
+}
+
+/// Platform-specific keychain provided by the [`keyring`] crate.
+#[cfg(feature = "keychain")]
+pub struct OsKeychain;
+
+#[cfg(feature = "keychain")]
+impl OsKeychain {
+    /// Get keychain entry related to the provided server path.
+    fn entry(server_path: &str) -> Result<keyring::Entry, KeychainError> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, server_path)
+            .map_err(|err| KeychainError(err.to_string()))
+    }
+}
+
+#[cfg(feature = "keychain")]
+impl TokenStore for OsKeychain {
+    fn get(&self, server_path: &str) -> Result<Option<String>, KeychainError> {
+        match Self::entry(server_path)?.get_password() {
+            Ok(token) => Ok(Some(token)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(err) => Err(KeychainError(err.to_string())),
+        }
+    }
+
+    fn set(&self, server_path: &str, token: &str) -> Result<(), KeychainError> {
+        Self::entry(server_path)?
+            .set_password(token)
+            .map_err(|err| KeychainError(err.to_string()))
+    }
+
+    fn delete(&self, server_path: &str) -> Result<(), KeychainError> {
+        match Self::entry(server_path)?.delete_password() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(err) => Err(KeychainError(err.to_string())),
+        }
+    }
+}
+
+/// Get the default token store, if the keychain support is enabled.
+pub fn default_store() -> Option<&'static dyn TokenStore> {
+    #[cfg(feature = "keychain")]
+    {
+        Some(&OsKeychain)
+    }
+
+    #[cfg(not(feature = "keychain"))]
+    {
+        None
+    }
+}
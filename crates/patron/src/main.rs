@@ -5,13 +5,18 @@
 //! To archive the project itself, we recursively iterate over contents
 //! of the directory where user launched the deployment flow. While collecting available
 //! paths, we need to ignore directories which are most likely to be unused during builds,
-//! such as the `target` directory and hidden entries (for example, `.git`).
+//! such as the `target` directory and hidden entries (for example, `.git`), as well as
+//! any entries ignored by `.gitignore` and `.patronignore` files.
 
 #![deny(missing_docs)]
 #![deny(clippy::missing_docs_in_private_items)]
 
 use clap::Parser;
 use commands::{Cli, Commands};
+use output::{Event, OutputFormat};
+
+/// Patron API server client.
+mod api;
 
 /// Contract source code archiving utilities.
 mod archiver;
@@ -22,20 +27,59 @@ mod commands;
 /// CLI-specific configuration (authentication, project).
 mod config;
 
+/// OS keychain integration used to store authentication tokens.
+mod keychain;
+
+/// Human-readable and machine-readable command output.
+mod output;
+
 /// Remote build process implementation.
 mod process;
 
+/// Build artifact signature verification.
+mod signature;
+
+/// Testing utilities.
+#[cfg(test)]
+mod testing;
+
 /// CLI entrypoint.
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<(), anyhow::Error> {
     let cli = Cli::parse();
+    let output = cli.output;
+
+    if let Err(error) = run(cli.command, output).await {
+        if output.is_json() {
+            output.emit(&Event::Error {
+                message: error.to_string(),
+            });
+
+            std::process::exit(1);
+        }
+
+        return Err(error);
+    }
+
+    Ok(())
+}
 
-    match cli.command {
+/// Run the selected subcommand.
+async fn run(command: Commands, output: OutputFormat) -> Result<(), anyhow::Error> {
+    match command {
         Commands::Auth(args) => commands::auth(args).await?,
-        Commands::Deploy(args) => commands::deploy(args).await?,
-        Commands::Build(args) => commands::build(args).await?,
-        Commands::Verify(args) => commands::verify(args).await?,
+        Commands::Init(args) => commands::init(args).await?,
+        Commands::Deploy(args) => commands::deploy(args, output).await?,
+        Commands::Build(args) => commands::build(args, output).await?,
+        Commands::Verify(args) => commands::verify(args, output).await?,
         Commands::Watch(args) => commands::watch(args).await?,
+        Commands::Status(args) => commands::status(args).await?,
+        Commands::Logs(args) => commands::logs(args).await?,
+        Commands::List(args) => commands::list(args).await?,
+        Commands::Download(args) => commands::download(args).await?,
+        Commands::Call(args) => commands::call(args).await?,
+        Commands::Doctor => commands::doctor().await?,
+        Commands::Completions(args) => commands::completions(args).await?,
     }
 
     Ok(())
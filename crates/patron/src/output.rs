@@ -0,0 +1,196 @@
+use std::{path::Path, process::Stdio};
+
+use clap::ValueEnum;
+use indicatif::ProgressBar;
+use serde::Serialize;
+
+/// Supported CLI output formats.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Human-readable output with progress indication.
+    #[default]
+    Text,
+
+    /// Newline-delimited JSON events.
+    Json,
+}
+
+/// Machine-readable event emitted when JSON output is enabled.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum Event<'a> {
+    /// Remote build session was started.
+    BuildStarted,
+
+    /// Build session log entry.
+    Log {
+        /// Log entry text value.
+        text: &'a str,
+
+        /// Count of identical consecutive log entries collapsed into this one.
+        #[serde(skip_serializing_if = "is_single")]
+        count: i64,
+    },
+
+    /// Build artifacts were saved to the local filesystem.
+    ArtifactsSaved {
+        /// Path to the contract WASM or PolkaVM blob.
+        code_path: &'a Path,
+
+        /// Path to the contract JSON metadata.
+        metadata_path: &'a Path,
+
+        /// Path to the bundled contract file.
+        bundle_path: &'a Path,
+    },
+
+    /// Command finished successfully.
+    Completed {
+        /// Code hash of a built contract.
+        code_hash: &'a str,
+
+        /// Address of an instantiated contract.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        address: Option<&'a str>,
+
+        /// Verification result.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        verified: Option<bool>,
+    },
+
+    /// Deployment dry run finished.
+    DryRun {
+        /// Hex-encoded hash of the source code archive.
+        archive_hash: &'a str,
+
+        /// Size of the source code archive in bytes.
+        archive_size: u64,
+
+        /// Code hash of an existing build session, that would be reused.
+        code_hash: Option<&'a str>,
+
+        /// `cargo-contract` command that would be invoked, with secret values redacted.
+        command: &'a str,
+    },
+
+    /// Command finished with an error.
+    Error {
+        /// Error message.
+        message: String,
+    },
+}
+
+impl OutputFormat {
+    /// Check if the JSON output is enabled.
+    pub fn is_json(self) -> bool {
+        self == OutputFormat::Json
+    }
+
+    /// Create a progress bar, which is hidden if the JSON output is enabled.
+    pub fn progress_bar(self) -> ProgressBar {
+        match self {
+            OutputFormat::Text => ProgressBar::new_spinner(),
+            OutputFormat::Json => ProgressBar::hidden(),
+        }
+    }
+
+    /// Standard output configuration for child processes.
+    ///
+    /// Child process output is discarded if the JSON output is enabled
+    /// to keep the standard output parseable.
+    pub fn child_stdout(self) -> Stdio {
+        match self {
+            OutputFormat::Text => Stdio::inherit(),
+            OutputFormat::Json => Stdio::null(),
+        }
+    }
+
+    /// Emit the provided event to the standard output if the JSON output is enabled.
+    pub fn emit(self, event: &Event) {
+        if self.is_json() {
+            println!("{}", event.to_json());
+        }
+    }
+}
+
+impl Event<'_> {
+    /// Serialize event as a single-line JSON value.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("event serialization is infallible")
+    }
+}
+
+/// Check if a log entry was not repeated, in which case its count is omitted from events.
+fn is_single(count: &i64) -> bool {
+    *count == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Event;
+
+    #[test]
+    fn event_shape() {
+        assert_eq!(
+            Event::BuildStarted.to_json(),
+            r#"{"event":"build_started"}"#
+        );
+
+        assert_eq!(
+            Event::Log {
+                text: "Compiling",
+                count: 1
+            }
+            .to_json(),
+            r#"{"event":"log","text":"Compiling"}"#
+        );
+
+        assert_eq!(
+            Event::Log {
+                text: "Compiling",
+                count: 3
+            }
+            .to_json(),
+            r#"{"event":"log","text":"Compiling","count":3}"#
+        );
+
+        assert_eq!(
+            Event::Completed {
+                code_hash: "abcd",
+                address: Some("5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM"),
+                verified: None,
+            }
+            .to_json(),
+            r#"{"event":"completed","code_hash":"abcd","address":"5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM"}"#
+        );
+
+        assert_eq!(
+            Event::Completed {
+                code_hash: "abcd",
+                address: None,
+                verified: Some(false),
+            }
+            .to_json(),
+            r#"{"event":"completed","code_hash":"abcd","verified":false}"#
+        );
+
+        assert_eq!(
+            Event::DryRun {
+                archive_hash: "abcd",
+                archive_size: 1024,
+                code_hash: None,
+                command: "cargo contract upload",
+            }
+            .to_json(),
+            r#"{"event":"dry_run","archive_hash":"abcd","archive_size":1024,"code_hash":null,"command":"cargo contract upload"}"#
+        );
+
+        assert_eq!(
+            Event::Error {
+                message: String::from("unable to locate cargo")
+            }
+            .to_json(),
+            r#"{"event":"error","message":"unable to locate cargo"}"#
+        );
+    }
+}
@@ -1,90 +1,233 @@
 use std::{
+    borrow::Cow,
+    env,
+    ffi::OsStr,
+    fmt,
+    future::Future,
     io::{self, Read, Seek},
     path::Path,
     process::Stdio,
+    str::FromStr,
     time::Duration,
 };
 
-use common::hash;
+use api_types::{build_sessions::LogEntry, source_code::SourceCodeUploadResponse};
+use bytes::Bytes;
+use common::{build_logs::TRUNCATION_MARKER, hash::Hash32};
 use derive_more::{Display, Error, From};
-use indicatif::ProgressBar;
+use futures_util::{stream, StreamExt};
+use indicatif::{HumanBytes, ProgressBar};
 use os_info::Type;
+use rand::{thread_rng, Rng};
 use reqwest::{
     multipart::{Form, Part},
-    Client,
+    Body, Client, Response, StatusCode,
 };
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tempfile::NamedTempFile;
 use tokio::{
-    io::{AsyncBufReadExt, AsyncSeekExt, BufReader},
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
     process::Command,
 };
 
 use crate::{
+    api::ApiClient,
     archiver::{build_zip_archive, ArchiverError},
     config::{AuthenticationConfig, ProjectConfig},
+    output::{Event, OutputFormat},
 };
 
 /// `cargo-contract` repository used to install the potentially missing `cargo-contract` binary.
 const CARGO_CONTRACT_REPO: &str = "https://github.com/paritytech/cargo-contract";
 
+/// Size of a single chunk of the uploaded source code archive.
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Initial delay between source code upload retries.
+const UPLOAD_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Maximum delay between source code upload retries.
+const MAX_UPLOAD_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Delay between build session status and log requests.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Maximum amount of consecutive failed build session status and log requests.
+const MAX_POLL_FAILURES: u32 = 5;
+
+/// Timeout of the supported `cargo-contract` versions request.
+const SUPPORTED_VERSIONS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Notice displayed instead of the build session log truncation marker.
+const TRUNCATION_NOTICE: &str =
+    "\nNotice: build log size limit was exceeded, the rest of the build output is unavailable.\n";
+
 /// Default value passed to weight configuration flags of the `cargo-contract`.
 const DEFAULT_WEIGHT_VAL: u64 = 10_000_000_000;
 
 /// JSON response body with the code hash of a cached build session that matches some source code.
 #[derive(Deserialize)]
-struct ExistingCodeHashResponse {
+pub(crate) struct ExistingCodeHashResponse {
     /// Code hash hex-encoded value.
-    code_hash: String,
+    pub code_hash: String,
+
+    /// Version of `cargo-contract` used by the cached build session.
+    ///
+    /// [`None`] if the server does not report it.
+    #[serde(default)]
+    pub cargo_contract_version: Option<String>,
+
+    /// Project directory used by the cached build session.
+    #[serde(default)]
+    pub project_directory: Option<String>,
 }
 
-/// JSON response body returned by build session creation and source code upload requests.
-#[derive(Deserialize)]
-struct CreateResponse {
-    /// Resource identifier.
-    id: i64,
+impl ExistingCodeHashResponse {
+    /// Check if the cached build session was started with the provided build configuration.
+    pub fn matches(&self, cargo_contract_version: &str, project_directory: Option<&str>) -> bool {
+        self.cargo_contract_version.as_deref() == Some(cargo_contract_version)
+            && self.project_directory.as_deref() == project_directory
+    }
 }
 
 /// JSON request body that is used to create a new build session.
 #[derive(Serialize)]
-struct BuildSessionCreateRequest<'a> {
+pub(crate) struct BuildSessionCreateRequest<'a> {
     /// Source code identifier to build from.
-    source_code_id: i64,
+    pub source_code_id: i64,
 
     /// Preferred `cargo-contract` version.
-    cargo_contract_version: &'a str,
+    pub cargo_contract_version: &'a str,
 
     /// Relative project directory used to build multi-contract projects.
-    project_directory: Option<&'a str>,
+    pub project_directory: Option<&'a str>,
+}
+
+/// Kind of the contract blob produced by a build session.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ArtifactKind {
+    /// WASM blob, which is used by `pallet-contracts`.
+    #[default]
+    Wasm,
+
+    /// PolkaVM blob, which is used by `pallet-revive`.
+    Polkavm,
+}
+
+impl ArtifactKind {
+    /// File extension of contract blobs of this kind.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArtifactKind::Wasm => "wasm",
+            ArtifactKind::Polkavm => "polkavm",
+        }
+    }
+
+    /// Human-readable contract blob name.
+    pub fn description(self) -> &'static str {
+        match self {
+            ArtifactKind::Wasm => "WASM blob",
+            ArtifactKind::Polkavm => "PolkaVM blob",
+        }
+    }
+
+    /// Compute the code hash of a contract blob of this kind.
+    pub fn code_hash(self, code: &[u8]) -> Hash32 {
+        match self {
+            ArtifactKind::Wasm => Hash32::blake2(code),
+            ArtifactKind::Polkavm => Hash32::keccak256(code),
+        }
+    }
+
+    /// Magic bytes, which contract blobs of this kind start with.
+    fn magic(self) -> &'static [u8] {
+        match self {
+            ArtifactKind::Wasm => b"\0asm",
+            ArtifactKind::Polkavm => b"PVM\0",
+        }
+    }
+
+    /// Key of the `source` metadata object, which contains the contract blob
+    /// inside of `.contract` bundles.
+    fn bundle_key(self) -> &'static str {
+        match self {
+            ArtifactKind::Wasm => "wasm",
+            ArtifactKind::Polkavm => "contract_binary",
+        }
+    }
 }
 
 /// JSON response body with the status of an initiated build session.
 #[derive(Deserialize)]
-struct BuildSessionStatus {
+pub(crate) struct BuildSessionStatus {
     /// Current build session status.
     ///
     /// For an enumeration of supported values see the `db` crate documentation.
-    status: String,
+    pub status: String,
 
     /// Build session code hash, if the build was completed successfully.
-    code_hash: Option<String>,
+    pub code_hash: Option<String>,
+
+    /// Kind of the contract blob, if the build was completed successfully.
+    ///
+    /// [`None`] if the server does not report it.
+    #[serde(default)]
+    pub artifact_kind: Option<ArtifactKind>,
+
+    /// JSON metadata hash, if the build was completed successfully.
+    ///
+    /// [`None`] if the server does not report it.
+    #[serde(default)]
+    pub metadata_hash: Option<String>,
+
+    /// Count of build sessions that will be processed before this one,
+    /// if the build session is still queued.
+    pub queue_position: Option<i64>,
+
+    /// Current build session stage, if the build session is being processed.
+    pub stage: Option<String>,
 }
 
-/// JSON response body with build session logs.
-#[derive(Deserialize)]
-struct BuildSessionLogs {
-    /// Contained build session logs.
-    logs: Vec<BuildSessionLog>,
+impl BuildSessionStatus {
+    /// Check if the build session reached its final status.
+    pub fn is_finished(&self) -> bool {
+        matches!(&*self.status, "completed" | "failed")
+    }
+
+    /// Get the progress message for a build session that is not finished yet.
+    pub fn progress_message(&self) -> String {
+        if let Some(position) = self.queue_position {
+            return format!("Queued (position {})", position + 1);
+        }
+
+        match self.stage.as_deref() {
+            Some("unarchiving") => String::from("Unarchiving source code..."),
+            Some("building") => String::from("Building contract..."),
+            Some("extracting") => String::from("Extracting build artifacts..."),
+            _ => String::from("Awaiting for build to finish..."),
+        }
+    }
 }
 
-/// A single build session log entry.
-#[derive(Deserialize)]
-struct BuildSessionLog {
-    /// Log entry identifier, that can be used to paginate over build session logs.
-    id: i64,
+/// Get text of a build session log entry to display in the terminal.
+///
+/// The log truncation marker stored by builders is replaced with a human-readable notice,
+/// while repeated log entries are displayed once with an `(xN)` suffix.
+pub(crate) fn display_log_text(log: &LogEntry) -> Cow<'_, str> {
+    if log.text == TRUNCATION_MARKER {
+        return Cow::Borrowed(TRUNCATION_NOTICE);
+    }
+
+    if log.count <= 1 {
+        return Cow::Borrowed(&log.text);
+    }
 
-    /// Log entry text value.
-    text: String,
+    let text = log.text.trim_end_matches('\n');
+    let newlines = &log.text[text.len()..];
+
+    Cow::Owned(format!("{text} (x{}){newlines}", log.count))
 }
 
 /// `deploy` subcommand errors.
@@ -103,140 +246,353 @@ pub(crate) enum RemoteBuildError {
     /// Build session failed.
     #[display(fmt = "unable to finish this build session")]
     BuildFailed,
+
+    /// Source code upload was rejected by the server.
+    #[display(fmt = "source code upload was rejected ({}): {}", status, body)]
+    UploadRejected {
+        /// Response status code.
+        status: StatusCode,
+
+        /// Response body returned by the server.
+        body: String,
+    },
+
+    /// Build session did not finish within the configured timeout.
+    #[display(
+        fmt = "build session {} did not finish in time, resume with `patron logs --follow {}`",
+        id,
+        id
+    )]
+    BuildTimedOut {
+        /// Build session identifier.
+        id: i64,
+    },
+
+    /// Downloaded build artifact exceeds the configured maximum size.
+    #[display(
+        fmt = "downloaded {} exceeds the maximum size of {} bytes, adjust `max_artifact_size` to download it",
+        artifact,
+        max_size
+    )]
+    ArtifactTooLarge {
+        /// Human-readable artifact name.
+        artifact: &'static str,
+
+        /// Maximum artifact size in bytes.
+        max_size: u64,
+    },
+
+    /// Downloaded build artifact has unexpected contents.
+    #[display(fmt = "downloaded {} is invalid: {}", artifact, reason)]
+    InvalidArtifact {
+        /// Human-readable artifact name.
+        artifact: &'static str,
+
+        /// Human-readable description of the problem.
+        reason: &'static str,
+    },
+
+    /// Downloaded build artifact does not match the hash it was requested with.
+    #[display(
+        fmt = "downloaded {} does not match the expected hash {} (actual hash {})",
+        artifact,
+        expected,
+        actual
+    )]
+    ArtifactHashMismatch {
+        /// Human-readable artifact name.
+        artifact: &'static str,
+
+        /// Expected hex-encoded hash.
+        expected: String,
+
+        /// Hex-encoded hash of the downloaded artifact.
+        actual: String,
+    },
+}
+
+/// Remote build configuration.
+pub(crate) struct RemoteBuildOptions<'a> {
+    /// Always start new build sessions, even if the source code was verified previously.
+    pub force_new_build_sessions: bool,
+
+    /// Relative project directory used to build multi-contract projects.
+    pub project_directory: Option<&'a Path>,
+
+    /// Maximum amount of retries for transient source code upload failures.
+    pub upload_retries: u32,
+
+    /// Skip the source code archive size check.
+    pub force_large_upload: bool,
+
+    /// Maximum duration of waiting for the build session to finish.
+    pub build_timeout: Option<Duration>,
+}
+
+/// Build session polling configuration.
+struct PollOptions {
+    /// Delay between consecutive polling requests.
+    interval: Duration,
+
+    /// Maximum amount of consecutive failed polling requests.
+    max_failures: u32,
+
+    /// Maximum duration of polling, if it is limited.
+    timeout: Option<Duration>,
 }
 
 /// Finished remote build session.
 pub(crate) struct FinishedBuildSession {
-    /// Downloaded WASM blob from a remote build session.
-    pub wasm_file: NamedTempFile,
+    /// Downloaded WASM or PolkaVM blob from a remote build session.
+    pub code_file: NamedTempFile,
+
+    /// Kind of the downloaded contract blob.
+    pub artifact_kind: ArtifactKind,
 
     /// Downloaded JSON metadata from a remote build session.
     pub metadata_file: NamedTempFile,
 
-    /// Code hash value of a resulted WASM blob.
+    /// Code hash value of a resulted contract blob.
     pub code_hash: String,
 }
 
 /// Start remote build process.
 ///
-/// This method returns [`FinishedBuildSession`], which contains WASM blob, JSON metadata and the resulting code hash.
+/// This method returns [`FinishedBuildSession`], which contains contract blob,
+/// JSON metadata and the resulting code hash.
 pub(crate) async fn remote_build(
     auth_config: &AuthenticationConfig,
     project_config: &ProjectConfig,
     progress: &ProgressBar,
-    force_new_build_sessions: bool,
-    project_directory: Option<&Path>,
+    output: OutputFormat,
+    options: &RemoteBuildOptions<'_>,
 ) -> Result<FinishedBuildSession, RemoteBuildError> {
-    let server_path = auth_config.server_path();
+    let api = ApiClient::new(auth_config);
 
     progress.enable_steady_tick(Duration::from_millis(150));
     progress.set_message("Archiving...");
 
-    let mut archive_file = NamedTempFile::new()?;
-
-    build_zip_archive(&mut archive_file, progress)?;
+    let size_limit = Some(project_config.max_archive_size).filter(|_| !options.force_large_upload);
 
-    let mut archive_buf = Vec::with_capacity(archive_file.stream_position()? as usize);
-    archive_file.seek(std::io::SeekFrom::Start(0))?;
-    archive_file.read_to_end(&mut archive_buf)?;
-    let archive_hash = hex::encode(hash::blake2(&archive_buf));
+    let (archive_buf, archive_hash) =
+        archive_source_code(&env::current_dir()?, progress, size_limit)?;
 
     progress.set_message("Retrieving existing build session...");
 
-    let response = Client::new()
-        .get(format!("{server_path}/buildSessions/latest/{archive_hash}"))
-        .bearer_auth(auth_config.token())
-        .send()
-        .await?;
-
-    let code_hash = if response.status().is_success() && !force_new_build_sessions {
-        let json: ExistingCodeHashResponse = response.json().await?;
-        json.code_hash
+    let existing_code_hash = existing_code_hash(
+        &api,
+        &archive_hash,
+        &project_config.cargo_contract_version,
+        options.project_directory,
+    )
+    .await?
+    .filter(|_| !options.force_new_build_sessions);
+
+    let code_hash = if let Some(code_hash) = existing_code_hash {
+        code_hash
     } else {
-        let (file, _path) = archive_file.into_parts();
-
-        let mut tokio_file = tokio::fs::File::from_std(file);
-        tokio_file.seek(std::io::SeekFrom::Start(0)).await?;
-        let length = tokio_file.metadata().await?.len();
-
-        let source_code_body = Form::new().part(
-            "archive",
-            Part::stream_with_length(tokio_file, length).mime_str("application/zip")?,
-        );
-
-        progress.set_message("Uploading source code...");
-
-        let source_code_upload: CreateResponse = Client::new()
-            .post(format!("{server_path}/sourceCode"))
-            .bearer_auth(auth_config.token())
-            .multipart(source_code_body)
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
+        let source_code_upload = upload_source_code(
+            &api,
+            Bytes::from(archive_buf),
+            progress,
+            size_limit,
+            options.upload_retries,
+            UPLOAD_RETRY_DELAY,
+        )
+        .await?;
 
         progress.set_message("Creating build session...");
 
-        let build_session_create: CreateResponse = Client::new()
-            .post(format!("{server_path}/buildSessions"))
-            .bearer_auth(auth_config.token())
-            .json(&BuildSessionCreateRequest {
+        let build_session_create = api
+            .create_build_session(&BuildSessionCreateRequest {
                 source_code_id: source_code_upload.id,
                 cargo_contract_version: &project_config.cargo_contract_version,
-                project_directory: project_directory
+                project_directory: options
+                    .project_directory
                     .map(|p| p.display().to_string())
                     .as_deref(),
             })
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
             .await?;
 
-        let mut log_position = 0;
+        output.emit(&Event::BuildStarted);
+
+        poll_build_session(
+            &api,
+            build_session_create.id,
+            progress,
+            output,
+            &PollOptions {
+                interval: POLL_INTERVAL,
+                max_failures: MAX_POLL_FAILURES,
+                timeout: options.build_timeout,
+            },
+        )
+        .await?
+    };
+
+    let (artifact_kind, code_file, metadata_file) =
+        download_build_artifacts(&api, &code_hash, project_config.max_artifact_size).await?;
+
+    Ok(FinishedBuildSession {
+        code_file,
+        artifact_kind,
+        metadata_file,
+        code_hash,
+    })
+}
+
+/// Download contract blob and JSON metadata of a finished build session
+/// with the provided code hash.
+///
+/// Contract blob is downloaded according to the artifact kind advertised by the server,
+/// and is verified against the code hash itself, while JSON metadata is verified
+/// against the metadata hash reported by the server, if there is one.
+///
+/// Both artifacts are streamed to temporary files, rejecting artifacts larger than `max_size`
+/// bytes, and their contents are validated before the hash verification, to provide
+/// a meaningful error if the server responded with something else entirely.
+///
+/// Servers that do not advertise artifact kinds only serve WASM blobs.
+async fn download_build_artifacts(
+    api: &ApiClient,
+    code_hash: &str,
+    max_size: u64,
+) -> Result<(ArtifactKind, NamedTempFile, NamedTempFile), RemoteBuildError> {
+    // Build sessions completed before metadata hashes were stored do not report them.
+    let BuildSessionStatus {
+        artifact_kind,
+        metadata_hash,
+        ..
+    } = api.status(code_hash).await?;
+
+    let response = if artifact_kind.is_some() {
+        api.artifact(code_hash).await?
+    } else {
+        api.wasm(code_hash).await?
+    };
+    let artifact_kind = artifact_kind.unwrap_or_default();
+    let artifact = artifact_kind.description();
+
+    let code_file = tempfile::Builder::new()
+        .suffix(&format!(".{}", artifact_kind.extension()))
+        .tempfile()?;
+    let (code_file, code) = stream_to_tempfile(code_file, response, artifact, max_size).await?;
+
+    if !code.starts_with(artifact_kind.magic()) {
+        return Err(RemoteBuildError::InvalidArtifact {
+            artifact,
+            reason: "unexpected file format, the server may have returned an error page",
+        });
+    }
+
+    compare_artifact_hash(artifact, artifact_kind.code_hash(&code), code_hash)?;
+
+    let metadata_file = tempfile::Builder::new().suffix(".json").tempfile()?;
+    let (metadata_file, metadata) = stream_to_tempfile(
+        metadata_file,
+        api.metadata(code_hash).await?,
+        "JSON metadata",
+        max_size,
+    )
+    .await?;
+
+    validate_metadata(&metadata)?;
+
+    if let Some(metadata_hash) = metadata_hash {
+        verify_artifact_hash("JSON metadata", &metadata, &metadata_hash)?;
+    }
+
+    Ok((artifact_kind, code_file, metadata_file))
+}
+
+/// Check that the downloaded JSON metadata is a JSON object with the `spec` section.
+fn validate_metadata(metadata: &[u8]) -> Result<(), RemoteBuildError> {
+    let reason = match serde_json::from_slice::<Value>(metadata) {
+        Ok(Value::Object(object)) if object.contains_key("spec") => return Ok(()),
+        Ok(_) => "`spec` section is missing",
+        Err(_) => "invalid JSON document, the server may have returned an error page",
+    };
+
+    Err(RemoteBuildError::InvalidArtifact {
+        artifact: "JSON metadata",
+        reason,
+    })
+}
+
+/// Check that the Blake2b 256-bit hash of the provided artifact matches
+/// the expected hex-encoded value.
+fn verify_artifact_hash(
+    artifact: &'static str,
+    contents: &[u8],
+    expected: &str,
+) -> Result<(), RemoteBuildError> {
+    compare_artifact_hash(artifact, Hash32::blake2(contents), expected)
+}
+
+/// Check that the provided artifact hash matches the expected hex-encoded value.
+fn compare_artifact_hash(
+    artifact: &'static str,
+    actual: Hash32,
+    expected: &str,
+) -> Result<(), RemoteBuildError> {
+    if expected.parse::<Hash32>().ok() == Some(actual) {
+        Ok(())
+    } else {
+        Err(RemoteBuildError::ArtifactHashMismatch {
+            artifact,
+            expected: String::from(expected),
+            actual: actual.to_string(),
+        })
+    }
+}
 
-        progress.set_message("Awaiting for build to finish...");
+/// Wait for the build session to finish, outputting its logs along the way.
+///
+/// Returns the code hash of a successfully finished build session.
+async fn poll_build_session(
+    api: &ApiClient,
+    id: i64,
+    progress: &ProgressBar,
+    output: OutputFormat,
+    options: &PollOptions,
+) -> Result<String, RemoteBuildError> {
+    progress.set_message("Awaiting for build to finish...");
+
+    let poll = async {
+        let mut log_position = 0;
+        let mut failures = 0;
 
         loop {
-            let logs: BuildSessionLogs = Client::new()
-                .get(format!(
-                    "{server_path}/buildSessions/logs/{}",
-                    build_session_create.id
-                ))
-                .query(&[("position", log_position)])
-                .bearer_auth(auth_config.token())
-                .send()
-                .await?
-                .error_for_status()?
-                .json()
-                .await?;
+            let logs = retry_poll(&mut failures, progress, options, || {
+                api.logs(id, log_position)
+            })
+            .await?;
 
             for log in &logs.logs {
-                progress.suspend(|| print!("{}", log.text));
+                if output.is_json() {
+                    output.emit(&Event::Log {
+                        text: &log.text,
+                        count: log.count,
+                    });
+                } else {
+                    progress.suspend(|| print!("{}", display_log_text(log)));
+                }
             }
 
             if let Some(log) = logs.logs.last() {
                 log_position = log.id;
             }
 
-            let build_session_status: BuildSessionStatus = Client::new()
-                .get(format!(
-                    "{server_path}/buildSessions/status/{}",
-                    build_session_create.id
-                ))
-                .bearer_auth(auth_config.token())
-                .send()
-                .await?
-                .error_for_status()?
-                .json()
-                .await?;
+            let build_session_status =
+                retry_poll(&mut failures, progress, options, || api.status(id)).await?;
+
+            progress.set_message(build_session_status.progress_message());
 
             match (
                 &*build_session_status.status,
                 build_session_status.code_hash,
             ) {
-                ("completed", Some(code_hash)) => break code_hash,
+                ("completed", Some(code_hash)) => return Ok(code_hash),
                 ("failed", _) => {
                     progress.finish_with_message("Build failed.");
                     return Err(RemoteBuildError::BuildFailed);
@@ -244,46 +600,296 @@ pub(crate) async fn remote_build(
                 _ => {}
             }
 
-            std::thread::sleep(Duration::from_secs(3));
+            tokio::time::sleep(options.interval).await;
         }
     };
 
-    let wasm_file = tempfile::Builder::new().suffix(".wasm").tempfile()?;
-    let metadata_file = tempfile::Builder::new().suffix(".json").tempfile()?;
+    match options.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, poll)
+            .await
+            .unwrap_or_else(|_| {
+                progress.finish_with_message("Build timed out.");
+                Err(RemoteBuildError::BuildTimedOut { id })
+            }),
+        None => poll.await,
+    }
+}
 
-    let wasm = Client::new()
-        .get(format!("{server_path}/buildSessions/wasm/{}", code_hash))
-        .bearer_auth(auth_config.token())
-        .send()
-        .await?
-        .error_for_status()?
-        .bytes()
-        .await?;
+/// Send a single build session polling request, retrying it on transient failures.
+///
+/// `failures` counts consecutive failed requests and is reset after a successful one.
+/// Once it exceeds the configured maximum, the last error is returned.
+async fn retry_poll<T, F, Fut>(
+    failures: &mut u32,
+    progress: &ProgressBar,
+    options: &PollOptions,
+    mut request: F,
+) -> Result<T, reqwest::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, reqwest::Error>>,
+{
+    loop {
+        match request().await {
+            Ok(value) => {
+                *failures = 0;
+                return Ok(value);
+            }
+            Err(error) if is_transient(&error) && *failures < options.max_failures => {
+                *failures += 1;
 
-    let wasm_file = write_to_tempfile(wasm_file, &wasm).await?;
+                progress.println(format!(
+                    "Build session polling failed ({error}), retrying ({}/{})...",
+                    failures, options.max_failures
+                ));
 
-    let metadata = Client::new()
-        .get(format!(
-            "{server_path}/buildSessions/metadata/{}",
-            code_hash
-        ))
-        .bearer_auth(auth_config.token())
-        .send()
-        .await?
-        .error_for_status()?
-        .bytes()
-        .await?;
+                tokio::time::sleep(options.interval).await;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
 
-    let metadata_file = write_to_tempfile(metadata_file, &metadata).await?;
+/// Check if the provided HTTP client error may disappear after a retry.
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_connect()
+        || error.is_timeout()
+        || error.is_request()
+        || error.status().map_or(false, |status| {
+            status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+        })
+}
 
-    Ok(FinishedBuildSession {
-        wasm_file,
-        metadata_file,
-        code_hash,
+/// Summary of a remote build process, that is collected without uploading the source code.
+pub(crate) struct RemoteBuildPlan {
+    /// Hex-encoded hash of the source code archive.
+    pub archive_hash: String,
+
+    /// Size of the source code archive in bytes.
+    pub archive_size: u64,
+
+    /// Code hash of an existing build session, that would be reused instead of starting a new one.
+    pub existing_code_hash: Option<String>,
+}
+
+/// Archive the provided project directory and look up an existing build session
+/// without uploading the source code or starting a new build session.
+pub(crate) async fn plan_remote_build(
+    auth_config: &AuthenticationConfig,
+    project_config: &ProjectConfig,
+    source_dir: &Path,
+    progress: &ProgressBar,
+    options: &RemoteBuildOptions<'_>,
+) -> Result<RemoteBuildPlan, RemoteBuildError> {
+    progress.enable_steady_tick(Duration::from_millis(150));
+    progress.set_message("Archiving...");
+
+    let size_limit = Some(project_config.max_archive_size).filter(|_| !options.force_large_upload);
+
+    let (archive_buf, archive_hash) = archive_source_code(source_dir, progress, size_limit)?;
+
+    progress.set_message("Retrieving existing build session...");
+
+    let existing_code_hash = existing_code_hash(
+        &ApiClient::new(auth_config),
+        &archive_hash,
+        &project_config.cargo_contract_version,
+        options.project_directory,
+    )
+    .await?
+    .filter(|_| !options.force_new_build_sessions);
+
+    Ok(RemoteBuildPlan {
+        archive_hash,
+        archive_size: archive_buf.len() as u64,
+        existing_code_hash,
     })
 }
 
-/// Write the provided buffer to [`NamedTempFile`] in asynchronous manner.
+/// Archive the provided project directory, returning the archive contents along with its hex-encoded hash.
+fn archive_source_code(
+    source_dir: &Path,
+    progress: &ProgressBar,
+    size_limit: Option<u64>,
+) -> Result<(Vec<u8>, String), RemoteBuildError> {
+    let mut archive_file = NamedTempFile::new()?;
+
+    build_zip_archive(source_dir, &mut archive_file, progress, size_limit)?;
+
+    let mut archive_buf = Vec::with_capacity(archive_file.stream_position()? as usize);
+    archive_file.seek(std::io::SeekFrom::Start(0))?;
+    archive_file.read_to_end(&mut archive_buf)?;
+    let archive_hash = Hash32::blake2(&archive_buf).to_string();
+
+    Ok((archive_buf, archive_hash))
+}
+
+/// Retrieve code hash of the latest build session, that was started using the source code archive
+/// with the provided hash.
+///
+/// Build sessions started with a different `cargo-contract` version or project directory
+/// are ignored, since their artifacts do not correspond to the current project configuration.
+async fn existing_code_hash(
+    api: &ApiClient,
+    archive_hash: &str,
+    cargo_contract_version: &str,
+    project_directory: Option<&Path>,
+) -> Result<Option<String>, reqwest::Error> {
+    let Some(json) = api.latest(archive_hash).await? else {
+        return Ok(None);
+    };
+
+    let project_directory = project_directory.map(|path| path.display().to_string());
+
+    if !json.matches(cargo_contract_version, project_directory.as_deref()) {
+        return Ok(None);
+    }
+
+    Ok(Some(json.code_hash))
+}
+
+/// Retrieve `cargo-contract` versions supported by the server, ordered by preference.
+///
+/// Returns [`None`] if the server is unavailable or does not report supported versions,
+/// in which case unsupported versions are only rejected during the build session creation.
+pub(crate) async fn supported_cargo_contract_versions(server_path: &str) -> Option<Vec<String>> {
+    let response = Client::new()
+        .get(format!("{server_path}/buildSessions/supportedVersions"))
+        .timeout(SUPPORTED_VERSIONS_TIMEOUT)
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+
+    response.json().await.ok()
+}
+
+/// Get a warning message if the provided `cargo-contract` version
+/// is not among the supported ones.
+pub(crate) fn unsupported_version_warning(
+    cargo_contract_version: &str,
+    supported: &[String],
+) -> Option<String> {
+    if supported
+        .iter()
+        .any(|version| version == cargo_contract_version)
+    {
+        return None;
+    }
+
+    Some(format!(
+        "cargo-contract {cargo_contract_version} is not supported, supported versions: {}",
+        supported.join(", ")
+    ))
+}
+
+/// Upload source code archive, retrying on transient failures with an exponential backoff.
+///
+/// Source code uploads are not idempotent, thus only failures that guarantee
+/// that the server did not process the archive are retried: connection errors
+/// and rate limiting responses. Server errors and timeouts are returned immediately,
+/// since the archive may have been stored already.
+///
+/// Upload progress is reported using the provided [`ProgressBar`], along
+/// with the archive size limit, if there is one.
+async fn upload_source_code(
+    api: &ApiClient,
+    archive: Bytes,
+    progress: &ProgressBar,
+    size_limit: Option<u64>,
+    retries: u32,
+    initial_delay: Duration,
+) -> Result<SourceCodeUploadResponse, RemoteBuildError> {
+    let mut attempt = 0;
+
+    loop {
+        let result = api
+            .upload_source(
+                Form::new().part(
+                    "archive",
+                    Part::stream_with_length(
+                        progress_body(archive.clone(), progress.clone(), size_limit),
+                        archive.len() as u64,
+                    )
+                    .mime_str("application/zip")?,
+                ),
+            )
+            .await;
+
+        let transient_error = match result {
+            Ok(response) if response.status().is_success() => {
+                return Ok(response.json().await?);
+            }
+            Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                RemoteBuildError::UploadRejected {
+                    status: response.status(),
+                    body: response.text().await.unwrap_or_default(),
+                }
+            }
+            Ok(response) => {
+                return Err(RemoteBuildError::UploadRejected {
+                    status: response.status(),
+                    body: response.text().await.unwrap_or_default(),
+                });
+            }
+            Err(error) if error.is_connect() => error.into(),
+            Err(error) => return Err(error.into()),
+        };
+
+        if attempt >= retries {
+            return Err(transient_error);
+        }
+
+        let delay = initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_UPLOAD_RETRY_DELAY);
+
+        attempt += 1;
+
+        progress.println(format!(
+            "Source code upload failed ({transient_error}), retrying in {}s ({attempt}/{retries})...",
+            delay.as_secs()
+        ));
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Create request body from the provided buffer, which reports its progress
+/// using the provided [`ProgressBar`].
+fn progress_body(buf: Bytes, progress: ProgressBar, size_limit: Option<u64>) -> Body {
+    let total = buf.len();
+
+    let limit = size_limit
+        .map(|limit| format!(", limit {}", HumanBytes(limit)))
+        .unwrap_or_default();
+
+    let chunks: Vec<Bytes> = (0..total)
+        .step_by(UPLOAD_CHUNK_SIZE)
+        .map(|start| buf.slice(start..total.min(start + UPLOAD_CHUNK_SIZE)))
+        .collect();
+
+    let mut sent = 0;
+
+    Body::wrap_stream(stream::iter(chunks).map(move |chunk| {
+        sent += chunk.len();
+
+        progress.set_message(format!(
+            "Uploading source code ({}/{}{limit})...",
+            HumanBytes(sent as u64),
+            HumanBytes(total as u64)
+        ));
+
+        Ok::<_, io::Error>(chunk)
+    }))
+}
+
+/// Stream the provided response body to [`NamedTempFile`] in asynchronous manner,
+/// returning the written contents alongside with the file.
+///
+/// Response bodies larger than `max_size` bytes are rejected
+/// as soon as the limit is exceeded.
 ///
 /// This function internally converts [`NamedTempFile`] to a regular [`std::fs::File`],
 /// which itself is then converted to [`tokio::fs::File`] for writing purposes.
@@ -291,19 +897,50 @@ pub(crate) async fn remote_build(
 /// To ensure that [`NamedTempFile`] gets deleted in a RAII manner, convertion operations
 /// are done in reverse as soon as the writing process gets finished, and the resulting
 /// [`NamedTempFile`] is returned from this function.
-async fn write_to_tempfile(
+async fn stream_to_tempfile(
     file: NamedTempFile,
-    mut val: &[u8],
-) -> Result<NamedTempFile, io::Error> {
+    mut response: Response,
+    artifact: &'static str,
+    max_size: u64,
+) -> Result<(NamedTempFile, Vec<u8>), RemoteBuildError> {
     let (file, path) = file.into_parts();
 
     let mut tokio_file = tokio::fs::File::from_std(file);
 
-    let result = tokio::io::copy(&mut val, &mut tokio_file).await;
+    let result = write_response(&mut tokio_file, &mut response, artifact, max_size).await;
 
     let temp_file = NamedTempFile::from_parts(tokio_file.into_std().await, path);
 
-    result.map(|_| temp_file)
+    result.map(|contents| (temp_file, contents))
+}
+
+/// Write the provided response body to a file chunk by chunk, returning the written contents.
+async fn write_response(
+    file: &mut tokio::fs::File,
+    response: &mut Response,
+    artifact: &'static str,
+    max_size: u64,
+) -> Result<Vec<u8>, RemoteBuildError> {
+    let too_large = || RemoteBuildError::ArtifactTooLarge { artifact, max_size };
+
+    if matches!(response.content_length(), Some(length) if length > max_size) {
+        return Err(too_large());
+    }
+
+    let mut contents = Vec::new();
+
+    while let Some(chunk) = response.chunk().await? {
+        if (contents.len() + chunk.len()) as u64 > max_size {
+            return Err(too_large());
+        }
+
+        file.write_all(&chunk).await?;
+        contents.extend_from_slice(&chunk);
+    }
+
+    file.flush().await?;
+
+    Ok(contents)
 }
 
 /// Errors related to the contract build process.
@@ -362,6 +999,23 @@ pub(crate) async fn build_locally(
     Ok(serde_json::from_slice(&spawned.stdout)?)
 }
 
+/// Embed hex-encoded contract blob into the contract JSON metadata,
+/// producing a `.contract` bundle.
+///
+/// Returns [`None`] if the metadata doesn't contain the `source` object.
+pub(crate) fn contract_bundle(
+    mut metadata: Value,
+    kind: ArtifactKind,
+    code: &[u8],
+) -> Option<Value> {
+    metadata["source"].as_object_mut()?.insert(
+        kind.bundle_key().into(),
+        Value::String(format!("0x{}", hex::encode(code))),
+    );
+
+    Some(metadata)
+}
+
 /// Instantiation configuration.
 pub(crate) struct Instantiation<'a> {
     /// Constructor to call.
@@ -381,6 +1035,57 @@ pub(crate) struct Instantiation<'a> {
 
     /// Maximum proof size for contract instantiation.
     pub proof_size: Option<u64>,
+
+    /// Salt value used to derive the contract address.
+    pub salt: &'a Salt,
+}
+
+/// Maximum salt length in bytes.
+const MAX_SALT_LENGTH: usize = 32;
+
+/// Salt value used to derive the address of an instantiated contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Salt(Vec<u8>);
+
+impl Salt {
+    /// Generate a new random salt value.
+    pub fn random() -> Self {
+        Self(thread_rng().gen::<u64>().to_le_bytes().to_vec())
+    }
+}
+
+/// Errors that may occur during salt value parsing.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum SaltError {
+    /// Salt value is not a valid hex string.
+    #[display(fmt = "invalid hex value: {}", _0)]
+    Hex(hex::FromHexError),
+
+    /// Salt value exceeds the maximum length.
+    #[display(fmt = "salt value must not exceed {} bytes", MAX_SALT_LENGTH)]
+    TooLong,
+}
+
+impl FromStr for Salt {
+    type Err = SaltError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.strip_prefix("0x").unwrap_or(value);
+
+        let bytes = hex::decode(value)?;
+
+        if bytes.len() > MAX_SALT_LENGTH {
+            return Err(SaltError::TooLong);
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for Salt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(&self.0))
+    }
 }
 
 /// Errors related to the contract instantiation process.
@@ -410,11 +1115,31 @@ pub(crate) async fn instantiate_contract(
     instantiation: &Instantiation<'_>,
     cargo_contract_flags: &[String],
     metadata_path: Option<&Path>,
-    salt: u64,
 ) -> Result<String, InstantiationError> {
-    let mut instantiate_command = Command::new(cargo);
-
-    instantiate_command
+    let spawned = instantiate_command(cargo, instantiation, cargo_contract_flags, metadata_path)
+        .spawn()?
+        .wait_with_output()
+        .await?;
+
+    if !spawned.status.success() {
+        return Err(InstantiationError::InstantiationError);
+    }
+
+    let parsed_output: InstantiationResult = serde_json::from_slice(&spawned.stdout)?;
+
+    Ok(parsed_output.contract)
+}
+
+/// Construct `cargo-contract` command used to instantiate a contract.
+fn instantiate_command(
+    cargo: &Path,
+    instantiation: &Instantiation<'_>,
+    cargo_contract_flags: &[String],
+    metadata_path: Option<&Path>,
+) -> Command {
+    let mut instantiate_command = Command::new(cargo);
+
+    instantiate_command
         .stdout(Stdio::piped())
         .stderr(Stdio::inherit())
         .args([
@@ -432,9 +1157,9 @@ pub(crate) async fn instantiate_contract(
                 .unwrap_or(DEFAULT_WEIGHT_VAL)
                 .to_string(),
             "--salt",
-            &hex::encode(salt.to_le_bytes()),
+            &hex::encode(&instantiation.salt.0),
         ])
-        .args(["--constructor", &instantiation.constructor])
+        .args(["--constructor", instantiation.constructor])
         .args(cargo_contract_flags);
 
     if let Some(metadata_path) = metadata_path {
@@ -450,18 +1175,239 @@ pub(crate) async fn instantiate_contract(
     }
 
     if let Some(args) = instantiation.args {
-        instantiate_command.args(["--args", &args]);
+        instantiate_command.args(["--args", args]);
     }
 
-    let spawned = instantiate_command.spawn()?.wait_with_output().await?;
+    instantiate_command
+}
+
+/// Contract call configuration.
+pub(crate) struct Call<'a> {
+    /// Address of a contract to call.
+    pub contract: &'a str,
+
+    /// Message to call.
+    pub message: &'a str,
+
+    /// Message arguments.
+    pub args: Option<&'a str>,
+
+    /// Substrate node URI.
+    pub suri: Option<&'a str>,
+
+    /// Substrate node URL.
+    pub url: Option<&'a str>,
+
+    /// Only dry-run the call without submitting an extrinsic.
+    pub dry_run: bool,
+}
+
+/// Errors related to the contract call process.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum ContractCallError {
+    /// IO-related error.
+    Io(io::Error),
+
+    /// JSON result parsing error.
+    Json(serde_json::Error),
+
+    /// Contract message could not be called.
+    #[display(fmt = "unable to call a contract")]
+    CallError,
+}
+
+/// Call a contract message, returning the JSON output of `cargo-contract`.
+pub(crate) async fn call_contract(
+    cargo: &Path,
+    call: &Call<'_>,
+    cargo_contract_flags: &[String],
+    metadata_path: Option<&Path>,
+) -> Result<Value, ContractCallError> {
+    let spawned = call_command(cargo, call, cargo_contract_flags, metadata_path)
+        .spawn()?
+        .wait_with_output()
+        .await?;
 
     if !spawned.status.success() {
-        return Err(InstantiationError::InstantiationError);
+        return Err(ContractCallError::CallError);
     }
 
-    let parsed_output: InstantiationResult = serde_json::from_slice(&spawned.stdout)?;
+    Ok(serde_json::from_slice(&spawned.stdout)?)
+}
 
-    Ok(parsed_output.contract)
+/// Construct `cargo-contract` command used to call a contract message.
+fn call_command(
+    cargo: &Path,
+    call: &Call<'_>,
+    cargo_contract_flags: &[String],
+    metadata_path: Option<&Path>,
+) -> Command {
+    let mut call_command = Command::new(cargo);
+
+    call_command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .args(["contract", "call", "--output-json"])
+        .args(["--contract", call.contract])
+        .args(["--message", call.message]);
+
+    if !call.dry_run {
+        call_command.args(["--execute", "--skip-confirm"]);
+    }
+
+    call_command.args(cargo_contract_flags);
+
+    if let Some(metadata_path) = metadata_path {
+        call_command.arg(metadata_path);
+    }
+
+    if let Some(url) = call.url {
+        call_command.args(["--url", url]);
+    }
+
+    if let Some(suri) = call.suri {
+        call_command.args(["--suri", suri]);
+    }
+
+    if let Some(args) = call.args {
+        call_command.args(["--args", args]);
+    }
+
+    call_command
+}
+
+/// Placeholder used instead of secret values in rendered command lines.
+const REDACTED: &str = "<redacted>";
+
+/// Flags, which values are replaced with the [`REDACTED`] placeholder in rendered command lines.
+const SECRET_FLAGS: [&str; 3] = ["--suri", "-s", "--password"];
+
+/// Render `cargo-contract` command used to instantiate a contract, replacing secret values.
+pub(crate) fn instantiate_command_line(
+    cargo: &Path,
+    instantiation: &Instantiation<'_>,
+    cargo_contract_flags: &[String],
+    metadata_path: Option<&Path>,
+) -> String {
+    render_command(&instantiate_command(
+        cargo,
+        instantiation,
+        cargo_contract_flags,
+        metadata_path,
+    ))
+}
+
+/// Render `cargo-contract` command used to upload contract code, replacing secret values.
+pub(crate) fn upload_command_line(
+    cargo: &Path,
+    wasm_path: &Path,
+    url: Option<&str>,
+    suri: Option<&str>,
+    cargo_contract_flags: &[String],
+) -> String {
+    render_command(&upload_command(
+        cargo,
+        wasm_path,
+        url,
+        suri,
+        cargo_contract_flags,
+    ))
+}
+
+/// Render the provided [`Command`] as a single line, replacing values of [`SECRET_FLAGS`].
+fn render_command(command: &Command) -> String {
+    let command = command.as_std();
+
+    let mut parts = vec![command.get_program().to_string_lossy().into_owned()];
+    let mut redact_next = false;
+
+    for arg in command.get_args() {
+        let arg = arg.to_string_lossy();
+
+        let rendered = if redact_next {
+            String::from(REDACTED)
+        } else if let Some((flag, _)) = arg
+            .split_once('=')
+            .filter(|(flag, _)| SECRET_FLAGS.contains(flag))
+        {
+            format!("{flag}={REDACTED}")
+        } else if arg.contains(char::is_whitespace) {
+            format!("{arg:?}")
+        } else {
+            arg.clone().into_owned()
+        };
+
+        redact_next = SECRET_FLAGS.contains(&&*arg);
+        parts.push(rendered);
+    }
+
+    parts.join(" ")
+}
+
+/// Errors related to the contract code upload process.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum UploadError {
+    /// IO-related error.
+    Io(io::Error),
+
+    /// Contract code could not be uploaded.
+    #[display(fmt = "unable to upload contract code")]
+    UploadError,
+}
+
+/// Upload contract code without instantiating it.
+pub(crate) async fn upload_code(
+    cargo: &Path,
+    wasm_path: &Path,
+    url: Option<&str>,
+    suri: Option<&str>,
+    cargo_contract_flags: &[String],
+    stdout: Stdio,
+) -> Result<(), UploadError> {
+    let status = upload_command(cargo, wasm_path, url, suri, cargo_contract_flags)
+        .stdout(stdout)
+        .spawn()?
+        .wait()
+        .await?;
+
+    if !status.success() {
+        return Err(UploadError::UploadError);
+    }
+
+    Ok(())
+}
+
+/// Construct `cargo-contract` command used to upload contract code.
+fn upload_command(
+    cargo: &Path,
+    wasm_path: &Path,
+    url: Option<&str>,
+    suri: Option<&str>,
+    cargo_contract_flags: &[String],
+) -> Command {
+    let mut upload_command = Command::new(cargo);
+
+    upload_command
+        .stderr(Stdio::inherit())
+        .args([
+            "contract",
+            "upload",
+            "--execute",
+            "--skip-confirm",
+            "--skip-dry-run",
+        ])
+        .arg(wasm_path)
+        .args(cargo_contract_flags);
+
+    if let Some(url) = url {
+        upload_command.args(["--url", url]);
+    }
+
+    if let Some(suri) = suri {
+        upload_command.args(["--suri", suri]);
+    }
+
+    upload_command
 }
 
 /// Errors that may occur during the `cargo-contract` installation phase.
@@ -486,25 +1432,9 @@ pub(crate) async fn ensure_cargo_contract_exists(
 ) -> Result<(), CargoContractInstallError> {
     progress.set_message("Installing cargo-contract...");
 
-    let cargo_contract_output = Command::new(cargo)
-        .stdin(Stdio::null())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .args(["contract", "--version"])
-        .spawn()?
-        .wait_with_output()
-        .await?;
-
-    let should_reinstall = !cargo_contract_output.status.success() || {
-        let output = String::from_utf8(cargo_contract_output.stdout)
-            .map_err(|_| CargoContractInstallError::InvalidCargoContractOutput)?;
-
-        !output
-            .split_ascii_whitespace()
-            .nth(1)
-            .ok_or(CargoContractInstallError::InvalidCargoContractOutput)?
-            .starts_with(cargo_contract_version)
-    };
+    let should_reinstall = installed_cargo_contract_version(cargo)
+        .await?
+        .map_or(true, |version| !version.starts_with(cargo_contract_version));
 
     if should_reinstall {
         let mut install_command = Command::new(cargo)
@@ -535,40 +1465,933 @@ pub(crate) async fn ensure_cargo_contract_exists(
     Ok(())
 }
 
+/// Get version of the installed `cargo-contract`, if it is available.
+pub(crate) async fn installed_cargo_contract_version(
+    cargo: &Path,
+) -> Result<Option<String>, CargoContractInstallError> {
+    let cargo_contract_output = Command::new(cargo)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .args(["contract", "--version"])
+        .spawn()?
+        .wait_with_output()
+        .await?;
+
+    if !cargo_contract_output.status.success() {
+        return Ok(None);
+    }
+
+    let output = String::from_utf8(cargo_contract_output.stdout)
+        .map_err(|_| CargoContractInstallError::InvalidCargoContractOutput)?;
+
+    output
+        .split_ascii_whitespace()
+        .nth(1)
+        .map(|version| Some(String::from(version)))
+        .ok_or(CargoContractInstallError::InvalidCargoContractOutput)
+}
+
 /// Ensure Docker exists, assisting user with its installation if it was not found.
 pub(crate) async fn ensure_docker_exists() -> bool {
-    let docker_exists = Command::new("docker")
+    if !docker_exists("docker").await {
+        println!("It seems that you don't have a Docker installation available.");
+        println!("Detected OS: {}", os_info::get().os_type());
+        println!(
+            "Consult {} for more information on how to install Docker on your local machine.",
+            docker_installation_guide()
+        );
+
+        true
+    } else {
+        false
+    }
+}
+
+/// Check if the provided Docker binary can be invoked.
+pub(crate) async fn docker_exists(docker: impl AsRef<OsStr>) -> bool {
+    let docker_exists = Command::new(docker)
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .arg("--version")
         .spawn()
         .map(|val| val.wait_with_output());
 
-    let should_provide_guide = if let Ok(val) = docker_exists {
-        val.await.is_err()
+    if let Ok(val) = docker_exists {
+        val.await.is_ok()
     } else {
-        true
+        false
+    }
+}
+
+/// Get Docker installation guide URL for the current OS.
+pub(crate) fn docker_installation_guide() -> &'static str {
+    match os_info::get().os_type() {
+        Type::Ubuntu => "https://docs.docker.com/desktop/install/ubuntu/",
+        Type::Debian => "https://docs.docker.com/desktop/install/debian/",
+        Type::Fedora => "https://docs.docker.com/desktop/install/fedora/",
+        Type::Arch => "https://docs.docker.com/desktop/install/archlinux/",
+        Type::Windows => "https://docs.docker.com/desktop/install/windows-install/",
+        Type::Macos => "https://docs.docker.com/desktop/install/mac-install/",
+        _ => "https://docs.docker.com/desktop/install/linux-install/",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::OsStr,
+        path::Path,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    use bytes::Bytes;
+    use common::hash::Hash32;
+    use indicatif::ProgressBar;
+    use reqwest::StatusCode;
+    use serde_json::{json, Value};
+
+    use super::{
+        call_command, contract_bundle, download_build_artifacts, existing_code_hash,
+        instantiate_command, poll_build_session, supported_cargo_contract_versions,
+        unsupported_version_warning, upload_command, upload_source_code, verify_artifact_hash,
+        ArtifactKind, BuildSessionStatus, Call, Instantiation, PollOptions, RemoteBuildError, Salt,
+        SaltError,
+    };
+    use crate::{
+        api::ApiClient, config::AuthenticationConfig, output::OutputFormat, testing::stub_server,
     };
 
-    if should_provide_guide {
-        let os_type = os_info::get().os_type();
-
-        let instructions = match os_type {
-            Type::Ubuntu => "https://docs.docker.com/desktop/install/ubuntu/",
-            Type::Debian => "https://docs.docker.com/desktop/install/debian/",
-            Type::Fedora => "https://docs.docker.com/desktop/install/fedora/",
-            Type::Arch => "https://docs.docker.com/desktop/install/archlinux/",
-            Type::Windows => "https://docs.docker.com/desktop/install/windows-install/",
-            Type::Macos => "https://docs.docker.com/desktop/install/mac-install/",
-            _ => "https://docs.docker.com/desktop/install/linux-install/",
+    /// Start a stub server, that responds to source code uploads with the provided responses
+    /// depending on the attempt number, and returns the attempt counter.
+    async fn upload_server(
+        responses: fn(usize) -> (u16, &'static str),
+    ) -> (ApiClient, Arc<AtomicUsize>) {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counter = attempts.clone();
+
+        let server = stub_server(move |_| {
+            let (status, body) = responses(counter.fetch_add(1, Ordering::SeqCst));
+            (status, String::from(body))
+        })
+        .await;
+
+        (
+            ApiClient::new(&AuthenticationConfig::for_tests(server)),
+            attempts,
+        )
+    }
+
+    #[tokio::test]
+    async fn upload_retry_after_transient_failure() {
+        let (api, attempts) = upload_server(|attempt| match attempt {
+            0 => (429, "too many requests"),
+            _ => (200, r#"{"id":42}"#),
+        })
+        .await;
+
+        let response = upload_source_code(
+            &api,
+            Bytes::from(vec![0; 200_000]),
+            &ProgressBar::hidden(),
+            None,
+            3,
+            Duration::ZERO,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.id, 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn upload_retries_exhausted() {
+        let (api, attempts) = upload_server(|_| (429, "too many requests")).await;
+
+        let result = upload_source_code(
+            &api,
+            Bytes::from_static(b"archive"),
+            &ProgressBar::hidden(),
+            None,
+            2,
+            Duration::ZERO,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(RemoteBuildError::UploadRejected { status, .. }) if status == StatusCode::TOO_MANY_REQUESTS
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn upload_server_error_is_not_retried() {
+        let (api, attempts) = upload_server(|attempt| match attempt {
+            0 => (503, "unavailable"),
+            _ => (200, r#"{"id":42}"#),
+        })
+        .await;
+
+        let result = upload_source_code(
+            &api,
+            Bytes::from_static(b"archive"),
+            &ProgressBar::hidden(),
+            None,
+            3,
+            Duration::ZERO,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(RemoteBuildError::UploadRejected { status, .. }) if status == StatusCode::SERVICE_UNAVAILABLE
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn upload_rejected() {
+        let (api, attempts) = upload_server(|_| (400, "archive is too large")).await;
+
+        let error = upload_source_code(
+            &api,
+            Bytes::from_static(b"archive"),
+            &ProgressBar::hidden(),
+            None,
+            3,
+            Duration::ZERO,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert!(error.to_string().contains("archive is too large"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    /// Start a stub server, that responds to build session status requests with the provided
+    /// responses depending on the request number, and returns the status request counter.
+    async fn polling_server(
+        responses: fn(usize) -> (u16, &'static str),
+    ) -> (ApiClient, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        let server = stub_server(move |path| {
+            if path.starts_with("/buildSessions/status/1") {
+                let (status, body) = responses(counter.fetch_add(1, Ordering::SeqCst));
+                (status, String::from(body))
+            } else {
+                (200, String::from(r#"{"logs":[]}"#))
+            }
+        })
+        .await;
+
+        (
+            ApiClient::new(&AuthenticationConfig::for_tests(server)),
+            requests,
+        )
+    }
+
+    /// Polling configuration used by tests.
+    fn poll_options(max_failures: u32, timeout: Option<Duration>) -> PollOptions {
+        PollOptions {
+            interval: Duration::from_millis(1),
+            max_failures,
+            timeout,
+        }
+    }
+
+    #[tokio::test]
+    async fn poll_intermittent_failures() {
+        let (api, requests) = polling_server(|request| match request {
+            0 | 2 => (500, "internal server error"),
+            1 | 3 => (200, r#"{"status":"processing","code_hash":null}"#),
+            _ => (200, r#"{"status":"completed","code_hash":"abcd"}"#),
+        })
+        .await;
+
+        let code_hash = poll_build_session(
+            &api,
+            1,
+            &ProgressBar::hidden(),
+            OutputFormat::Text,
+            &poll_options(1, None),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(code_hash, "abcd");
+        assert_eq!(requests.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn poll_failures_exhausted() {
+        let (api, requests) = polling_server(|request| match request {
+            0 => (200, r#"{"status":"processing","code_hash":null}"#),
+            _ => (503, "unavailable"),
+        })
+        .await;
+
+        let result = poll_build_session(
+            &api,
+            1,
+            &ProgressBar::hidden(),
+            OutputFormat::Text,
+            &poll_options(2, None),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(RemoteBuildError::Http(error)) if error.status() == Some(StatusCode::SERVICE_UNAVAILABLE)
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn poll_timed_out() {
+        let (api, _) = polling_server(|_| (200, r#"{"status":"new","code_hash":null}"#)).await;
+
+        let error = poll_build_session(
+            &api,
+            1,
+            &ProgressBar::hidden(),
+            OutputFormat::Text,
+            &poll_options(1, Some(Duration::from_millis(50))),
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert!(matches!(error, RemoteBuildError::BuildTimedOut { id: 1 }));
+        assert!(error.to_string().contains("patron logs --follow 1"));
+    }
+
+    /// WASM blob served by the artifacts stub server.
+    const WASM: &str = "\0asm\u{1}\0\0\0";
+
+    /// JSON metadata served by the artifacts stub server.
+    const METADATA: &str = r#"{"source":{"hash":"0x00"},"spec":{}}"#;
+
+    /// Maximum build artifact size used by tests.
+    const MAX_ARTIFACT_SIZE: u64 = 1024;
+
+    /// Start a stub server, that serves the provided build artifacts and metadata hash
+    /// for the code hash of [`WASM`], which is returned alongside with the configuration.
+    async fn artifacts_server(
+        wasm: &'static str,
+        metadata: &'static str,
+        metadata_hash: Option<String>,
+    ) -> (ApiClient, String) {
+        let code_hash = Hash32::blake2(WASM.as_bytes()).to_string();
+        let status = json!({
+            "status": "completed",
+            "code_hash": code_hash,
+            "metadata_hash": metadata_hash,
+        })
+        .to_string();
+
+        let hash = code_hash.clone();
+
+        let server = stub_server(move |path| {
+            if path == format!("/buildSessions/wasm/{hash}") {
+                (200, String::from(wasm))
+            } else if path == format!("/buildSessions/status/{hash}") {
+                (200, status.clone())
+            } else if path == format!("/buildSessions/metadata/{hash}") {
+                (200, String::from(metadata))
+            } else {
+                (404, String::new())
+            }
+        })
+        .await;
+
+        (
+            ApiClient::new(&AuthenticationConfig::for_tests(server)),
+            code_hash,
+        )
+    }
+
+    #[tokio::test]
+    async fn verified_artifacts() {
+        let metadata_hash = Hash32::blake2(METADATA.as_bytes()).to_string();
+        let (api, code_hash) = artifacts_server(WASM, METADATA, Some(metadata_hash)).await;
+
+        let (kind, wasm, metadata) = download_build_artifacts(&api, &code_hash, MAX_ARTIFACT_SIZE)
+            .await
+            .unwrap();
+
+        assert_eq!(kind, ArtifactKind::Wasm);
+        assert_eq!(wasm.path().extension().unwrap(), "wasm");
+        assert_eq!(std::fs::read(wasm.path()).unwrap(), WASM.as_bytes());
+        assert_eq!(std::fs::read(metadata.path()).unwrap(), METADATA.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn polkavm_artifacts() {
+        const POLKAVM: &str = "PVM\0\u{1}\0\0\0";
+
+        let code_hash = Hash32::keccak256(POLKAVM.as_bytes()).to_string();
+        let status = json!({
+            "status": "completed",
+            "code_hash": code_hash,
+            "artifact_kind": "polkavm",
+            "metadata_hash": null,
+        })
+        .to_string();
+
+        let hash = code_hash.clone();
+
+        let server = stub_server(move |path| {
+            if path == format!("/buildSessions/artifact/{hash}") {
+                (200, String::from(POLKAVM))
+            } else if path == format!("/buildSessions/status/{hash}") {
+                (200, status.clone())
+            } else if path == format!("/buildSessions/metadata/{hash}") {
+                (200, String::from(METADATA))
+            } else {
+                (404, String::new())
+            }
+        })
+        .await;
+
+        let (kind, code, _) = download_build_artifacts(
+            &ApiClient::new(&AuthenticationConfig::for_tests(server)),
+            &code_hash,
+            MAX_ARTIFACT_SIZE,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(kind, ArtifactKind::Polkavm);
+        assert_eq!(code.path().extension().unwrap(), "polkavm");
+        assert_eq!(std::fs::read(code.path()).unwrap(), POLKAVM.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn corrupted_wasm() {
+        let metadata_hash = Hash32::blake2(METADATA.as_bytes()).to_string();
+        let (api, code_hash) =
+            artifacts_server("\0asm\u{2}\0\0\0", METADATA, Some(metadata_hash)).await;
+
+        let result = download_build_artifacts(&api, &code_hash, MAX_ARTIFACT_SIZE).await;
+
+        assert!(matches!(
+            result,
+            Err(RemoteBuildError::ArtifactHashMismatch { artifact: "WASM blob", expected, .. })
+                if expected == code_hash
+        ));
+    }
+
+    #[tokio::test]
+    async fn corrupted_metadata() {
+        let metadata_hash = Hash32::blake2(METADATA.as_bytes()).to_string();
+        let (api, code_hash) = artifacts_server(
+            WASM,
+            r#"{"source":{"hash":"0x01"},"spec":{}}"#,
+            Some(metadata_hash),
+        )
+        .await;
+
+        let result = download_build_artifacts(&api, &code_hash, MAX_ARTIFACT_SIZE).await;
+
+        assert!(matches!(
+            result,
+            Err(RemoteBuildError::ArtifactHashMismatch {
+                artifact: "JSON metadata",
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn unknown_metadata_hash() {
+        let (api, code_hash) = artifacts_server(WASM, METADATA, None).await;
+
+        let (_, _, metadata) = download_build_artifacts(&api, &code_hash, MAX_ARTIFACT_SIZE)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(metadata.path()).unwrap(), METADATA.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn error_page_instead_of_wasm() {
+        let (api, code_hash) =
+            artifacts_server("<html>Sign in to continue</html>", METADATA, None).await;
+
+        let error = download_build_artifacts(&api, &code_hash, MAX_ARTIFACT_SIZE)
+            .await
+            .err()
+            .unwrap();
+
+        assert!(matches!(
+            error,
+            RemoteBuildError::InvalidArtifact {
+                artifact: "WASM blob",
+                ..
+            }
+        ));
+        assert!(error.to_string().contains("error page"));
+    }
+
+    #[tokio::test]
+    async fn invalid_metadata() {
+        for (metadata, reason) in [
+            ("<html>Sign in to continue</html>", "invalid JSON document"),
+            (r#"{"source":{"hash":"0x00"}}"#, "`spec` section is missing"),
+            ("[]", "`spec` section is missing"),
+        ] {
+            let (api, code_hash) = artifacts_server(WASM, metadata, None).await;
+
+            let error = download_build_artifacts(&api, &code_hash, MAX_ARTIFACT_SIZE)
+                .await
+                .err()
+                .unwrap();
+
+            assert!(matches!(
+                error,
+                RemoteBuildError::InvalidArtifact {
+                    artifact: "JSON metadata",
+                    ..
+                }
+            ));
+            assert!(error.to_string().contains(reason), "{error}");
+        }
+    }
+
+    #[tokio::test]
+    async fn oversized_artifacts() {
+        let (api, code_hash) = artifacts_server(WASM, METADATA, None).await;
+
+        let error = download_build_artifacts(&api, &code_hash, 4)
+            .await
+            .err()
+            .unwrap();
+
+        assert!(matches!(
+            error,
+            RemoteBuildError::ArtifactTooLarge {
+                artifact: "WASM blob",
+                max_size: 4,
+            }
+        ));
+
+        let size = WASM.len() as u64;
+        let error = download_build_artifacts(&api, &code_hash, size)
+            .await
+            .err()
+            .unwrap();
+
+        assert!(matches!(
+            error,
+            RemoteBuildError::ArtifactTooLarge {
+                artifact: "JSON metadata",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn contract_bundles() {
+        let metadata: Value = serde_json::from_str(METADATA).unwrap();
+
+        let bundle = contract_bundle(metadata.clone(), ArtifactKind::Wasm, &[1, 2]).unwrap();
+        assert_eq!(bundle["source"]["wasm"], "0x0102");
+
+        let bundle = contract_bundle(metadata, ArtifactKind::Polkavm, &[1, 2]).unwrap();
+        assert_eq!(bundle["source"]["contract_binary"], "0x0102");
+        assert!(bundle["source"].get("wasm").is_none());
+
+        assert!(contract_bundle(json!({}), ArtifactKind::Wasm, &[1, 2]).is_none());
+    }
+
+    #[test]
+    fn artifact_hash_verification() {
+        let hash = Hash32::blake2(METADATA.as_bytes()).to_string();
+
+        assert!(verify_artifact_hash("JSON metadata", METADATA.as_bytes(), &hash).is_ok());
+        assert!(
+            verify_artifact_hash("JSON metadata", METADATA.as_bytes(), &format!("0x{hash}"))
+                .is_ok()
+        );
+
+        for expected in ["", "invalid", hex::encode([0; 32]).as_str()] {
+            let error = verify_artifact_hash("JSON metadata", METADATA.as_bytes(), expected)
+                .err()
+                .unwrap();
+
+            assert!(error.to_string().contains(&hash));
+        }
+    }
+
+    #[test]
+    fn build_session_progress_message() {
+        let message = |value| {
+            serde_json::from_value::<BuildSessionStatus>(value)
+                .unwrap()
+                .progress_message()
         };
 
-        println!("It seems that you don't have a Docker installation available.");
-        println!("Detected OS: {os_type}");
-        println!("Consult {instructions} for more information on how to install Docker on your local machine.");
+        assert_eq!(
+            message(serde_json::json!({ "status": "new", "queue_position": 0 })),
+            "Queued (position 1)"
+        );
+        assert_eq!(
+            message(serde_json::json!({ "status": "new", "stage": "building" })),
+            "Building contract..."
+        );
+        assert_eq!(
+            message(serde_json::json!({ "status": "new", "code_hash": null })),
+            "Awaiting for build to finish..."
+        );
+    }
 
-        true
-    } else {
-        false
+    #[test]
+    fn salt_parsing() {
+        assert_eq!("0x0102".parse::<Salt>().unwrap(), Salt(vec![0x01, 0x02]));
+        assert_eq!("abcd".parse::<Salt>().unwrap(), Salt(vec![0xab, 0xcd]));
+        assert_eq!("".parse::<Salt>().unwrap(), Salt(vec![]));
+        assert_eq!("0xabcd".parse::<Salt>().unwrap().to_string(), "0xabcd");
+    }
+
+    #[test]
+    fn salt_validation() {
+        assert!(matches!("0x123".parse::<Salt>(), Err(SaltError::Hex(_))));
+        assert!(matches!("zz".parse::<Salt>(), Err(SaltError::Hex(_))));
+        assert!("ff".repeat(32).parse::<Salt>().is_ok());
+        assert!(matches!(
+            "ff".repeat(33).parse::<Salt>(),
+            Err(SaltError::TooLong)
+        ));
+    }
+
+    #[test]
+    fn instantiate_command_construction() {
+        let salt = "0xdeadbeef".parse().unwrap();
+
+        let instantiation = Instantiation {
+            constructor: "new",
+            args: Some("true"),
+            suri: Some("//Alice"),
+            url: None,
+            gas: Some(1),
+            proof_size: Some(2),
+            salt: &salt,
+        };
+
+        let command = instantiate_command(
+            Path::new("cargo"),
+            &instantiation,
+            &[],
+            Some(Path::new("metadata.json")),
+        );
+
+        let args: Vec<_> = command.as_std().get_args().collect();
+
+        assert_eq!(
+            args,
+            [
+                "contract",
+                "instantiate",
+                "--execute",
+                "--output-json",
+                "--skip-confirm",
+                "--skip-dry-run",
+                "--gas",
+                "1",
+                "--proof-size",
+                "2",
+                "--salt",
+                "deadbeef",
+                "--constructor",
+                "new",
+                "metadata.json",
+                "--suri",
+                "//Alice",
+                "--args",
+                "true",
+            ]
+            .map(OsStr::new)
+        );
+    }
+
+    #[test]
+    fn upload_command_construction() {
+        let command = upload_command(
+            Path::new("cargo"),
+            Path::new("contract.wasm"),
+            Some("ws://127.0.0.1:9944"),
+            Some("//Alice"),
+            &[String::from("--verbose")],
+        );
+
+        let args: Vec<_> = command.as_std().get_args().collect();
+
+        assert_eq!(
+            args,
+            [
+                "contract",
+                "upload",
+                "--execute",
+                "--skip-confirm",
+                "--skip-dry-run",
+                "contract.wasm",
+                "--verbose",
+                "--url",
+                "ws://127.0.0.1:9944",
+                "--suri",
+                "//Alice",
+            ]
+            .map(OsStr::new)
+        );
+    }
+
+    #[test]
+    fn upload_command_without_optional_args() {
+        let command = upload_command(
+            Path::new("cargo"),
+            Path::new("contract.wasm"),
+            None,
+            None,
+            &[],
+        );
+
+        let args: Vec<_> = command.as_std().get_args().collect();
+
+        assert!(!args.contains(&OsStr::new("--url")));
+        assert!(!args.contains(&OsStr::new("--suri")));
+    }
+
+    #[test]
+    fn call_command_construction() {
+        let call = Call {
+            contract: "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM",
+            message: "flip",
+            args: None,
+            suri: Some("//Alice"),
+            url: Some("ws://127.0.0.1:9944"),
+            dry_run: false,
+        };
+
+        let command = call_command(
+            Path::new("cargo"),
+            &call,
+            &[],
+            Some(Path::new("metadata.json")),
+        );
+
+        let args: Vec<_> = command.as_std().get_args().collect();
+
+        assert_eq!(
+            args,
+            [
+                "contract",
+                "call",
+                "--output-json",
+                "--contract",
+                "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM",
+                "--message",
+                "flip",
+                "--execute",
+                "--skip-confirm",
+                "metadata.json",
+                "--url",
+                "ws://127.0.0.1:9944",
+                "--suri",
+                "//Alice",
+            ]
+            .map(OsStr::new)
+        );
+    }
+
+    #[test]
+    fn call_command_dry_run() {
+        let call = Call {
+            contract: "5C4hrfjw9DjXZTzV3MwzrrAr9P1MJhSrvWGWqi1eSuyUpnhM",
+            message: "get",
+            args: Some("1"),
+            suri: None,
+            url: None,
+            dry_run: true,
+        };
+
+        let command = call_command(Path::new("cargo"), &call, &[], None);
+
+        let args: Vec<_> = command.as_std().get_args().collect();
+
+        assert!(!args.contains(&OsStr::new("--execute")));
+        assert!(args.ends_with(&["--args", "1"].map(OsStr::new)));
+    }
+
+    #[tokio::test]
+    async fn plan_without_upload() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        let server = stub_server(move |path| {
+            recorded.lock().unwrap().push(String::from(path));
+
+            if path.starts_with("/buildSessions/latest/") {
+                (
+                    200,
+                    json!({
+                        "code_hash": "abcd",
+                        "cargo_contract_version": "3.2.0",
+                        "project_directory": null,
+                    })
+                    .to_string(),
+                )
+            } else {
+                (500, String::new())
+            }
+        })
+        .await;
+
+        let source_dir = tempfile::tempdir().unwrap();
+        std::fs::write(source_dir.path().join("lib.rs"), "").unwrap();
+
+        let plan = plan_remote_build(
+            &AuthenticationConfig::for_tests(server),
+            &ProjectConfig {
+                cargo_contract_version: String::from("3.2.0"),
+                max_archive_size: 1024 * 1024,
+                max_artifact_size: 1024 * 1024,
+                url: None,
+                ws_host: None,
+                ws_port: None,
+                profiles: Default::default(),
+            },
+            source_dir.path(),
+            &ProgressBar::hidden(),
+            &RemoteBuildOptions {
+                force_new_build_sessions: false,
+                project_directory: None,
+                upload_retries: 0,
+                force_large_upload: false,
+                build_timeout: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(plan.existing_code_hash.as_deref(), Some("abcd"));
+        assert!(plan.archive_size > 0);
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [format!("/buildSessions/latest/{}", plan.archive_hash)]
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_cached_build_session() {
+        let cached = |body: Value| async move {
+            let server = stub_server(move |_| (200, body.to_string())).await;
+
+            existing_code_hash(
+                &ApiClient::new(&AuthenticationConfig::for_tests(server)),
+                "abcd",
+                "3.2.0",
+                Some(Path::new("contracts/flipper")),
+            )
+            .await
+            .unwrap()
+        };
+
+        let body = |cargo_contract_version: &str, project_directory: Option<&str>| {
+            json!({
+                "code_hash": "ab",
+                "cargo_contract_version": cargo_contract_version,
+                "project_directory": project_directory,
+            })
+        };
+
+        assert_eq!(
+            cached(body("3.2.0", Some("contracts/flipper")))
+                .await
+                .as_deref(),
+            Some("ab")
+        );
+
+        // Mismatched build configuration is ignored, falling through to a new build session.
+        for body in [
+            body("3.0.0", Some("contracts/flipper")),
+            body("3.2.0", Some("contracts/erc20")),
+            body("3.2.0", None),
+            json!({ "code_hash": "ab" }),
+        ] {
+            assert_eq!(cached(body.clone()).await, None, "{body}");
+        }
+    }
+
+    #[test]
+    fn command_line_redaction() {
+        let salt = "0x01".parse().unwrap();
+
+        let instantiation = Instantiation {
+            constructor: "new",
+            args: Some("1 2"),
+            suri: Some("//Alice"),
+            url: None,
+            gas: None,
+            proof_size: None,
+            salt: &salt,
+        };
+
+        let command_line = instantiate_command_line(
+            Path::new("cargo"),
+            &instantiation,
+            &[String::from("--password=123")],
+            None,
+        );
+
+        assert!(!command_line.contains("//Alice"));
+        assert!(!command_line.contains("123"));
+        assert!(command_line.contains("--suri <redacted>"));
+        assert!(command_line.contains("--password=<redacted>"));
+        assert!(command_line.contains(r#"--args "1 2""#));
+    }
+
+    #[tokio::test]
+    async fn supported_versions() {
+        let server = stub_server(|path| {
+            if path == "/buildSessions/supportedVersions" {
+                (200, json!(["4.0.0", "3.2.0"]).to_string())
+            } else {
+                (404, String::new())
+            }
+        })
+        .await;
+
+        assert_eq!(
+            supported_cargo_contract_versions(&server).await,
+            Some(vec![String::from("4.0.0"), String::from("3.2.0")])
+        );
+
+        let legacy_server = stub_server(|_| (404, String::new())).await;
+
+        assert_eq!(
+            supported_cargo_contract_versions(&legacy_server).await,
+            None
+        );
+    }
+
+    #[test]
+    fn unsupported_versions() {
+        let supported = [String::from("4.0.0"), String::from("3.2.0")];
+
+        assert_eq!(unsupported_version_warning("3.2.0", &supported), None);
+        assert_eq!(
+            unsupported_version_warning("3.0.0", &supported).as_deref(),
+            Some("cargo-contract 3.0.0 is not supported, supported versions: 4.0.0, 3.2.0")
+        );
     }
 }
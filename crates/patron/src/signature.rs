@@ -0,0 +1,264 @@
+use api_types::{build_sessions::ArtifactSignature, client::Client};
+use common::{
+    artifact_signature::{self, SigningPublicKey},
+    hash::{self, Hash32},
+};
+use derive_more::{Display, Error, From};
+
+/// Artifact signature verification errors.
+#[derive(Debug, Display, From, Error)]
+pub(crate) enum SignatureError {
+    /// HTTP client error.
+    Http(reqwest::Error),
+
+    /// The provided value is not a valid hex-encoded code hash.
+    #[display(fmt = "invalid code hash")]
+    InvalidCodeHash,
+
+    /// Artifacts are not signed, while a trusted signing key was provided.
+    #[display(fmt = "build artifacts are not signed")]
+    MissingSignature,
+
+    /// Artifacts are signed with a key other than the trusted ones.
+    #[display(fmt = "build artifacts are signed with an untrusted key {}", key_id)]
+    UntrustedKey {
+        /// Identifier of the key used to sign artifacts.
+        #[error(not(source))]
+        key_id: String,
+    },
+
+    /// Signature does not match the build artifacts.
+    #[display(fmt = "build artifact signature is invalid")]
+    InvalidSignature,
+}
+
+/// Outcome of a successful artifact signature check.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum SignatureStatus {
+    /// Artifacts are signed with the trusted key with the provided identifier.
+    Verified(String),
+
+    /// No trusted signing key was provided, thus artifacts were not verified.
+    Unverified,
+}
+
+impl SignatureStatus {
+    /// Print the signature check outcome in a human-readable format.
+    pub(crate) fn print(&self) {
+        match self {
+            Self::Verified(key_id) => println!("Signature: verified with builder key {key_id}"),
+            Self::Unverified => println!("Signature: unverified, no trusted builder key provided"),
+        }
+    }
+}
+
+/// Fetch the builder signature of artifacts with the provided hex-encoded code hash.
+///
+/// Authentication token is required to access signatures of private build sessions.
+/// Returns [`None`] if artifacts are not signed or unknown to the API server.
+pub(crate) async fn fetch_signature(
+    server_path: &str,
+    token: Option<String>,
+    code_hash: &str,
+) -> Result<Option<ArtifactSignature>, SignatureError> {
+    Ok(Client::new(server_path, token)
+        .artifact_signature(code_hash)
+        .await?)
+}
+
+/// Verify the builder signature of artifacts with the provided hex-encoded code hash
+/// and JSON metadata contents locally.
+///
+/// If trusted keys are provided, artifacts must be signed with one of them, which is selected by
+/// the signature key identifier.
+///
+/// Public key advertised by the API server is never trusted, thus if no trusted keys are provided,
+/// artifacts are reported as unverified. Signature, if present, is still checked against
+/// the advertised key to reject artifacts which don't match their own signature.
+pub(crate) fn verify_signature(
+    signature: Option<&ArtifactSignature>,
+    code_hash: &str,
+    metadata: &[u8],
+    trusted_keys: &[SigningPublicKey],
+) -> Result<SignatureStatus, SignatureError> {
+    let code_hash = code_hash
+        .parse::<Hash32>()
+        .map_err(|_| SignatureError::InvalidCodeHash)?;
+
+    if trusted_keys.is_empty() {
+        if let Some(signature) = signature {
+            if artifact_signature::key_id(&signature.public_key) != signature.key_id
+                || !artifact_signature::verify(
+                    &signature.public_key,
+                    &signature.signature,
+                    &code_hash.0,
+                    &hash::blake2(metadata),
+                )
+            {
+                return Err(SignatureError::InvalidSignature);
+            }
+        }
+
+        return Ok(SignatureStatus::Unverified);
+    }
+
+    let signature = signature.ok_or(SignatureError::MissingSignature)?;
+
+    let trusted_key = trusted_keys
+        .iter()
+        .find(|key| artifact_signature::key_id(key) == signature.key_id)
+        .ok_or_else(|| SignatureError::UntrustedKey {
+            key_id: signature.key_id.clone(),
+        })?;
+
+    if !artifact_signature::verify(
+        trusted_key,
+        &signature.signature,
+        &code_hash.0,
+        &hash::blake2(metadata),
+    ) {
+        return Err(SignatureError::InvalidSignature);
+    }
+
+    Ok(SignatureStatus::Verified(artifact_signature::key_id(
+        trusted_key,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use api_types::build_sessions::ArtifactSignature;
+    use common::{artifact_signature::SigningKey, hash};
+
+    use super::{verify_signature, SignatureError, SignatureStatus};
+
+    /// Code hash of signed artifacts.
+    const CODE_HASH: [u8; 32] = [1; 32];
+
+    /// JSON metadata of signed artifacts.
+    const METADATA: &[u8] = br#"{"source":{"hash":"0x00"}}"#;
+
+    /// Create a signing key from the provided seed byte.
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_seed(&format!("0x{}", hex::encode([seed; 32]))).unwrap()
+    }
+
+    /// Sign artifacts with [`CODE_HASH`] and [`METADATA`] using the provided key.
+    fn sign(key: &SigningKey) -> ArtifactSignature {
+        ArtifactSignature {
+            key_id: key.key_id(),
+            public_key: key.public_key(),
+            signature: key.sign(&CODE_HASH, &hash::blake2(METADATA)),
+        }
+    }
+
+    /// Hex-encoded [`CODE_HASH`].
+    fn code_hash() -> String {
+        hex::encode(CODE_HASH)
+    }
+
+    #[test]
+    fn round_trip() {
+        let key = signing_key(1);
+        let signature = sign(&key);
+
+        assert_eq!(
+            verify_signature(
+                Some(&signature),
+                &code_hash(),
+                METADATA,
+                &[signing_key(2).public_key(), key.public_key()]
+            )
+            .unwrap(),
+            SignatureStatus::Verified(key.key_id())
+        );
+    }
+
+    #[test]
+    fn tampered_hash() {
+        let key = signing_key(1);
+        let signature = sign(&key);
+
+        assert!(matches!(
+            verify_signature(
+                Some(&signature),
+                &hex::encode([3; 32]),
+                METADATA,
+                &[key.public_key()]
+            ),
+            Err(SignatureError::InvalidSignature)
+        ));
+        assert!(matches!(
+            verify_signature(Some(&signature), &code_hash(), b"{}", &[key.public_key()]),
+            Err(SignatureError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn trusted_key() {
+        let key = signing_key(1);
+        let signature = sign(&key);
+
+        assert!(matches!(
+            verify_signature(
+                Some(&signature),
+                &code_hash(),
+                METADATA,
+                &[signing_key(2).public_key()]
+            ),
+            Err(SignatureError::UntrustedKey { key_id }) if key_id == key.key_id()
+        ));
+        assert!(matches!(
+            verify_signature(None, &code_hash(), METADATA, &[key.public_key()]),
+            Err(SignatureError::MissingSignature)
+        ));
+    }
+
+    #[test]
+    fn without_trusted_key() {
+        let key = signing_key(1);
+
+        assert_eq!(
+            verify_signature(Some(&sign(&key)), &code_hash(), METADATA, &[]).unwrap(),
+            SignatureStatus::Unverified
+        );
+        assert_eq!(
+            verify_signature(None, &code_hash(), METADATA, &[]).unwrap(),
+            SignatureStatus::Unverified
+        );
+
+        // Signatures present without trusted keys must still match the artifacts.
+        assert!(matches!(
+            verify_signature(Some(&sign(&key)), &code_hash(), b"{}", &[]),
+            Err(SignatureError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn substituted_key() {
+        let trusted = signing_key(1);
+        let substituted = signing_key(2);
+
+        // Server advertises its own key and signature, but claims the trusted key identifier.
+        let signature = ArtifactSignature {
+            key_id: trusted.key_id(),
+            ..sign(&substituted)
+        };
+
+        assert!(matches!(
+            verify_signature(
+                Some(&signature),
+                &code_hash(),
+                METADATA,
+                &[trusted.public_key()]
+            ),
+            Err(SignatureError::InvalidSignature)
+        ));
+
+        // Signatures made with the server-provided key are never trusted on their own.
+        assert_eq!(
+            verify_signature(Some(&sign(&substituted)), &code_hash(), METADATA, &[]).unwrap(),
+            SignatureStatus::Unverified
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+/// Start a stub HTTP server that responds to each request using the provided handler.
+///
+/// Handler receives the request path (including the query string) and returns
+/// a status code with a response body.
+///
+/// Returns the base URL of the started server.
+pub(crate) async fn stub_server<F>(handler: F) -> String
+where
+    F: Fn(&str) -> (u16, String) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let handler = Arc::new(handler);
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let handler = handler.clone();
+
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut reader = BufReader::new(reader);
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).await.unwrap();
+
+                let mut content_length = 0;
+                let mut chunked = false;
+
+                loop {
+                    let mut header = String::new();
+
+                    if reader.read_line(&mut header).await.unwrap() == 0 || header == "\r\n" {
+                        break;
+                    }
+
+                    let header = header.to_ascii_lowercase();
+
+                    if let Some(value) = header.strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap();
+                    } else if header.starts_with("transfer-encoding:") && header.contains("chunked")
+                    {
+                        chunked = true;
+                    }
+                }
+
+                // Consume the request body to avoid resetting the connection
+                // before the client finishes sending it.
+                if chunked {
+                    read_chunked_body(&mut reader).await;
+                } else {
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).await.unwrap();
+                }
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+
+                let (status, body) = handler(path);
+
+                writer
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {status} Stub\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                            body.len()
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+            });
+        }
+    });
+
+    format!("http://{address}")
+}
+
+/// Read and discard a request body sent with the chunked transfer encoding.
+async fn read_chunked_body<R: AsyncBufRead + Unpin>(reader: &mut R) {
+    loop {
+        let mut size_line = String::new();
+        reader.read_line(&mut size_line).await.unwrap();
+
+        let size = usize::from_str_radix(size_line.trim(), 16).unwrap();
+
+        // Chunk data is followed by the CRLF sequence.
+        let mut chunk = vec![0; size + 2];
+        reader.read_exact(&mut chunk).await.unwrap();
+
+        if size == 0 {
+            break;
+        }
+    }
+}
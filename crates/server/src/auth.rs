@@ -11,8 +11,10 @@ use axum::{
 use axum_derive_error::ErrorResponse;
 use common::config::Config;
 use db::{
-    public_key, token, user, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
-    QuerySelect, SelectExt, TransactionErrorExt, TransactionTrait,
+    public_key,
+    token::{self, Scope},
+    user, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect, SelectExt,
+    TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
 
@@ -55,22 +57,31 @@ pub(super) enum AuthenticationError {
     #[status(StatusCode::FORBIDDEN)]
     #[display(fmt = "paid membership is required to access")]
     PaymentRequired,
+
+    /// User attempted to access a route that is not allowed by the authentication token scope.
+    #[status(StatusCode::FORBIDDEN)]
+    #[display(fmt = "authentication token scope does not allow access")]
+    InsufficientScope,
 }
 
 /// Authentication middleware for [`axum`].
 ///
 /// # Generics
 ///
-/// This function accepts two generics which configure the middleware
+/// This function accepts three generics which configure the middleware
 /// behaviour and internal checks.
 ///
 /// Set `REQUIRE_VERIFIED_KEY` to require users to have at least verified key
 /// to access a route.
 ///
 /// Set `REQUIRE_PAYMENT` to require users to have a membership to access a route.
+///
+/// Set `REQUIRE_FULL_SCOPE` to require authentication tokens with the [`Scope::Full`] scope,
+/// otherwise tokens with the [`Scope::Ci`] scope are accepted as well.
 pub(super) async fn require_authentication<
     const REQUIRE_VERIFIED_KEY: bool,
     const REQUIRE_PAYMENT: bool,
+    const REQUIRE_FULL_SCOPE: bool,
     B,
 >(
     State((db, config)): State<(Arc<DatabaseConnection>, Arc<Config>)>,
@@ -83,15 +94,25 @@ pub(super) async fn require_authentication<
             Box::pin(async move {
                 let bearer = authorization.token();
 
-                let user_id: i64 = token::Entity::find()
+                let (user_id, scope): (i64, Scope) = token::Entity::find()
                     .select_only()
-                    .column(token::Column::UserId)
+                    .columns([token::Column::UserId, token::Column::Scope])
                     .filter(token::Column::Token.eq(bearer))
                     .into_tuple()
                     .one(txn)
                     .await?
                     .ok_or(AuthenticationError::InvalidAuthenticationToken)?;
 
+                let required_scope = if REQUIRE_FULL_SCOPE {
+                    Scope::Full
+                } else {
+                    Scope::Ci
+                };
+
+                if !scope.allows(required_scope) {
+                    return Err(AuthenticationError::InsufficientScope);
+                }
+
                 if REQUIRE_VERIFIED_KEY {
                     let has_verified_keys = public_key::Entity::find()
                         .select_only()
@@ -126,3 +147,117 @@ pub(super) async fn require_authentication<
 
     Ok(next.run(req).await)
 }
+
+/// Optional authentication middleware for [`axum`].
+///
+/// Unlike [`require_authentication`], this middleware allows requests without
+/// an authentication token, in which case no [`AuthenticatedUserId`] extension is inserted.
+/// Requests with an invalid authentication token are still rejected.
+pub(super) async fn optional_authentication<B>(
+    State(db): State<Arc<DatabaseConnection>>,
+    authorization: Option<TypedHeader<Authorization<Bearer>>>,
+    mut req: Request<B>,
+    next: Next<B>,
+) -> Result<Response, AuthenticationError> {
+    if let Some(TypedHeader(authorization)) = authorization {
+        let user_id: i64 = token::Entity::find()
+            .select_only()
+            .column(token::Column::UserId)
+            .filter(token::Column::Token.eq(authorization.token()))
+            .into_tuple()
+            .one(&*db)
+            .await?
+            .ok_or(AuthenticationError::InvalidAuthenticationToken)?;
+
+        req.extensions_mut().insert(AuthenticatedUserId(user_id));
+    }
+
+    Ok(next.run(req).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{
+        public_key,
+        token::{self, Scope},
+        user, ActiveValue, DatabaseConnection, EntityTrait,
+    };
+    use tower::ServiceExt;
+
+    use crate::testing::create_database;
+
+    async fn create_token(db: &DatabaseConnection, scope: Scope) -> String {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        public_key::Entity::insert(public_key::ActiveModel {
+            user_id: ActiveValue::Set(user.id),
+            address: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to create public key");
+
+        let (mut model, token) = token::generate_token(user.id);
+        model.scope = ActiveValue::Set(scope);
+
+        token::Entity::insert(model)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert token");
+
+        token
+    }
+
+    async fn request_status(db: Arc<DatabaseConnection>, uri: &str, token: &str) -> StatusCode {
+        crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(uri)
+                    .header("Authorization", format!("Bearer {token}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn full_scope() {
+        let db = Arc::new(create_database().await);
+        let token = create_token(&db, Scope::Full).await;
+
+        assert_eq!(
+            request_status(db.clone(), "/buildSessions", &token).await,
+            StatusCode::OK
+        );
+        assert_eq!(request_status(db, "/keys", &token).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn ci_scope() {
+        let db = Arc::new(create_database().await);
+        let token = create_token(&db, Scope::Ci).await;
+
+        assert_eq!(
+            request_status(db.clone(), "/buildSessions", &token).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            request_status(db, "/keys", &token).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use axum::async_trait;
+use common::rpc::{
+    self,
+    pallet_contracts_primitives::ContractExecResult,
+    sp_core::crypto::AccountId32,
+    substrate_api_client::{self, rpc::JsonrpseeClient, Api},
+};
+use tokio::runtime::Handle;
+
+/// Result of a contract call dry-run.
+pub(crate) type ExecResult = ContractExecResult<u128, ()>;
+
+/// Substrate node RPC client used to dry-run contract calls.
+///
+/// Request handlers use this trait instead of connecting to nodes directly,
+/// which allows to replace RPC nodes with canned responses in tests.
+#[async_trait]
+pub(crate) trait ContractRpc: Send + Sync {
+    /// Dry-run a call of the provided contract with raw call data
+    /// using the node with the provided URL.
+    async fn call_contract(
+        &self,
+        url: String,
+        contract: AccountId32,
+        data: Vec<u8>,
+    ) -> Result<ExecResult, substrate_api_client::Error>;
+}
+
+/// [`ContractRpc`] implementation, that connects to Substrate nodes via WebSocket.
+pub(crate) struct NodeRpc;
+
+#[async_trait]
+impl ContractRpc for NodeRpc {
+    async fn call_contract(
+        &self,
+        url: String,
+        contract: AccountId32,
+        data: Vec<u8>,
+    ) -> Result<ExecResult, substrate_api_client::Error> {
+        tokio::task::spawn_blocking(|| {
+            Handle::current().block_on(async move {
+                let client =
+                    JsonrpseeClient::new(&url).map_err(substrate_api_client::Error::RpcClient)?;
+                let api = Api::new(client).await?;
+
+                rpc::call_contract(&api, contract, data).await
+            })
+        })
+        .await
+        .map_err(|err| substrate_api_client::Error::Other(Box::new(err)))?
+    }
+}
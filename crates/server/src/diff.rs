@@ -0,0 +1,221 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Maximum edit distance computed by the diff algorithm.
+///
+/// Files that differ by more lines are reported as completely replaced,
+/// which keeps the memory usage of the algorithm bounded.
+const MAX_EDIT_DISTANCE: usize = 2048;
+
+/// Kind of a changed line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LineChangeKind {
+    /// Line was added to the new file.
+    Added,
+
+    /// Line was removed from the old file.
+    Removed,
+}
+
+/// A single changed line.
+#[derive(Debug, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct LineChange {
+    /// Line change kind.
+    pub kind: LineChangeKind,
+
+    /// Line number, starting from 1.
+    ///
+    /// Removed lines are numbered according to the old file,
+    /// while added lines are numbered according to the new file.
+    pub line: usize,
+
+    /// Line contents.
+    pub text: String,
+}
+
+impl LineChange {
+    /// Create a new removed line change.
+    fn removed(index: usize, text: &str) -> Self {
+        Self {
+            kind: LineChangeKind::Removed,
+            line: index + 1,
+            text: String::from(text),
+        }
+    }
+
+    /// Create a new added line change.
+    fn added(index: usize, text: &str) -> Self {
+        Self {
+            kind: LineChangeKind::Added,
+            line: index + 1,
+            text: String::from(text),
+        }
+    }
+}
+
+/// Compute line changes required to transform the `old` text into the `new` one.
+///
+/// This function uses the Myers diff algorithm, and only returns changed lines.
+pub fn diff_lines(old: &str, new: &str) -> Vec<LineChange> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    match trace(&old, &new) {
+        Some(trace) => backtrack(&old, &new, &trace),
+        None => old
+            .iter()
+            .enumerate()
+            .map(|(index, text)| LineChange::removed(index, text))
+            .chain(
+                new.iter()
+                    .enumerate()
+                    .map(|(index, text)| LineChange::added(index, text)),
+            )
+            .collect(),
+    }
+}
+
+/// Compute the furthest reaching paths for each edit distance.
+///
+/// Element `d` of the returned trace contains `x` coordinates for diagonals `-d..=d`.
+///
+/// Returns [`None`] if the edit distance exceeds [`MAX_EDIT_DISTANCE`].
+fn trace(old: &[&str], new: &[&str]) -> Option<Vec<Vec<isize>>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (old.len() + new.len()).min(MAX_EDIT_DISTANCE) as isize;
+
+    let offset = max + 1;
+    let mut v = vec![0; 2 * offset as usize + 1];
+    let mut trace = Vec::new();
+
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            v[index] = x;
+
+            if x >= n && y >= m {
+                trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+                return Some(trace);
+            }
+        }
+
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+    }
+
+    None
+}
+
+/// Restore line changes from the provided trace.
+fn backtrack(old: &[&str], new: &[&str], trace: &[Vec<isize>]) -> Vec<LineChange> {
+    let mut changes = Vec::new();
+    let (mut x, mut y) = (old.len() as isize, new.len() as isize);
+
+    for d in (1..trace.len() as isize).rev() {
+        let previous = &trace[d as usize - 1];
+        let get = |k: isize| previous[(k + d - 1) as usize];
+
+        let k = x - y;
+        let previous_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+
+        let previous_x = get(previous_k);
+        let previous_y = previous_x - previous_k;
+
+        // Skip unchanged lines of a diagonal.
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+        }
+
+        if x == previous_x {
+            changes.push(LineChange::added(
+                previous_y as usize,
+                new[previous_y as usize],
+            ));
+        } else {
+            changes.push(LineChange::removed(
+                previous_x as usize,
+                old[previous_x as usize],
+            ));
+        }
+
+        (x, y) = (previous_x, previous_y);
+    }
+
+    changes.reverse();
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_lines, LineChange, LineChangeKind};
+
+    /// Create a line change for tests.
+    fn change(kind: LineChangeKind, line: usize, text: &str) -> LineChange {
+        LineChange {
+            kind,
+            line,
+            text: String::from(text),
+        }
+    }
+
+    #[test]
+    fn identical() {
+        assert!(diff_lines("a\nb\nc", "a\nb\nc").is_empty());
+    }
+
+    #[test]
+    fn modified_line() {
+        assert_eq!(
+            diff_lines("a\nb\nc", "a\nd\nc"),
+            [
+                change(LineChangeKind::Removed, 2, "b"),
+                change(LineChangeKind::Added, 2, "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn insertions_and_deletions() {
+        assert_eq!(
+            diff_lines("a\nb\nc\nd", "b\nc\ne\nd\nf"),
+            [
+                change(LineChangeKind::Removed, 1, "a"),
+                change(LineChangeKind::Added, 3, "e"),
+                change(LineChangeKind::Added, 5, "f"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_files() {
+        assert_eq!(
+            diff_lines("", "a\nb"),
+            [
+                change(LineChangeKind::Added, 1, "a"),
+                change(LineChangeKind::Added, 2, "b"),
+            ]
+        );
+        assert_eq!(
+            diff_lines("a", ""),
+            [change(LineChangeKind::Removed, 1, "a")]
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use db::{build_session, build_session_stage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::hex_hash::HexHash;
+
+/// Count of build session events buffered for each subscriber.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Build session status change event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct BuildSessionEvent {
+    /// Build session identifier.
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    pub build_session_id: i64,
+
+    /// Build session status.
+    #[schemars(example = "crate::schema::example_build_session_status")]
+    pub status: build_session::Status,
+
+    /// Code hash, if the build session was completed successfully.
+    #[schemars(example = "crate::schema::example_hex_hash")]
+    pub code_hash: Option<HexHash>,
+
+    /// Current build session stage, if the build session is being processed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(example = "crate::schema::example_build_session_stage")]
+    pub stage: Option<build_session_stage::Stage>,
+}
+
+/// Broadcast channel of build session events, shared between all request handlers.
+#[derive(Clone)]
+pub(crate) struct BuildSessionEvents(broadcast::Sender<BuildSessionEvent>);
+
+impl Default for BuildSessionEvents {
+    fn default() -> Self {
+        Self(broadcast::channel(CHANNEL_CAPACITY).0)
+    }
+}
+
+impl BuildSessionEvents {
+    /// Subscribe to all build session events published after this call.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<BuildSessionEvent> {
+        self.0.subscribe()
+    }
+
+    /// Publish a new build session event to all current subscribers.
+    ///
+    /// Events published without any subscribers are discarded.
+    pub(crate) fn publish(&self, event: BuildSessionEvent) {
+        let _ = self.0.send(event);
+    }
+}
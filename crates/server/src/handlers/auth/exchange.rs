@@ -4,7 +4,8 @@ use aide::{transform::TransformOperation, OperationIo};
 use axum::{extract::State, http::StatusCode, Json};
 use axum_derive_error::ErrorResponse;
 use db::{
-    cli_token, token, DatabaseConnection, DbErr, EntityTrait, TransactionErrorExt, TransactionTrait,
+    cli_token, token, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
 use schemars::JsonSchema;
@@ -12,7 +13,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use validator::Validate;
 
-use crate::{schema::example_error, validation::ValidatedJson};
+use crate::{
+    schema::{example_error, example_token},
+    validation::ValidatedJson,
+};
 
 /// Errors related to the token exchange.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -21,7 +25,7 @@ pub(super) enum ExchangeTokenError {
     /// Database-related error.
     DatabaseError(DbErr),
 
-    /// Invalid CLI token was submitted.
+    /// Invalid or already exchanged CLI token was submitted.
     #[status(StatusCode::NOT_FOUND)]
     #[display(fmt = "provided CLI token was not found")]
     TokenNotFound,
@@ -52,9 +56,14 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
 to exchange a locally-generated token for an authentication one, which
 can be used to authenticate with any other route later."#,
         )
-        .response::<200, Json<ExchangeTokenResponse>>()
+        .response_with::<200, Json<ExchangeTokenResponse>, _>(|op| {
+            op.description("Authentication token response.")
+                .example(ExchangeTokenResponse {
+                    token: example_token(),
+                })
+        })
         .response_with::<404, Json<Value>, _>(|op| {
-            op.description("Invalid CLI token.")
+            op.description("Invalid or already exchanged CLI token.")
                 .example(example_error(ExchangeTokenError::TokenNotFound))
         })
 }
@@ -64,23 +73,41 @@ can be used to authenticate with any other route later."#,
 /// This handler will exchange the token provided by the CLI
 /// for an authentication one if user previously finished an authentication
 /// flow with the same CLI token.
+///
+/// CLI tokens are single-use: the token row is deleted within the same transaction,
+/// and only the transaction that actually deleted it returns the authentication token.
+/// Unknown and already exchanged CLI tokens are indistinguishable to the caller.
+///
+/// CLI tokens are looked up by their hashes, and the stored hash is then compared
+/// with the hash of the provided CLI token in constant time.
 pub(super) async fn exchange(
     State(db): State<Arc<DatabaseConnection>>,
     ValidatedJson(request): ValidatedJson<ExchangeTokenRequest>,
 ) -> Result<Json<ExchangeTokenResponse>, ExchangeTokenError> {
     db.transaction(|txn| {
         Box::pin(async move {
-            let (cli_token_model, token_model) = cli_token::Entity::find_by_id(request.cli_token)
+            let token_hash = cli_token::hash_token(&request.cli_token);
+
+            let (cli_token_model, token_model) = cli_token::Entity::find_by_id(token_hash.clone())
                 .find_also_related(token::Entity)
                 .one(txn)
                 .await?
+                .filter(|(model, _)| cli_token::hashes_equal(&model.token_hash, &token_hash))
                 .ok_or(ExchangeTokenError::TokenNotFound)?;
 
             let token_model = token_model.ok_or(ExchangeTokenError::TokenNotFound)?;
 
-            cli_token::Entity::delete(cli_token::ActiveModel::from(cli_token_model))
+            // Concurrent exchanges of the same CLI token are serialized by the row lock
+            // acquired during deletion, so only one of them removes the row.
+            let deleted = cli_token::Entity::delete_many()
+                .filter(cli_token::Column::TokenHash.eq(cli_token_model.token_hash))
                 .exec(txn)
-                .await?;
+                .await?
+                .rows_affected;
+
+            if deleted == 0 {
+                return Err(ExchangeTokenError::TokenNotFound);
+            }
 
             Ok(Json(ExchangeTokenResponse {
                 token: token_model.token,
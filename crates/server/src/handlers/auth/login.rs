@@ -4,24 +4,29 @@ use aide::{transform::TransformOperation, OperationIo};
 use axum::{
     extract::{Query, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
-use common::rpc::sp_core::{
-    sr25519::{Pair, Public, Signature},
-    Pair as _,
+use common::{
+    config::Config,
+    rpc::sp_core::sr25519::{Public, Signature},
 };
 use db::{
-    cli_token, public_key, sea_query::OnConflict, token, ActiveValue, ColumnTrait,
-    DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect, TransactionErrorExt,
-    TransactionTrait,
+    cli_token, public_key,
+    sea_query::OnConflict,
+    token::{self, Scope},
+    ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect,
+    TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::schema::example_error;
+use crate::{
+    schema::{example_error, example_token},
+    signing::{self, Purpose},
+};
 
 /// Errors that may occur during the authentication process.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -42,13 +47,20 @@ pub(super) enum UserAuthenticationError {
     NoRelatedAccounts,
 }
 
-/// Query string deserialization struct for an optional CLI token.
+/// Query string deserialization struct for an optional CLI token and token scope.
 #[derive(Deserialize, JsonSchema)]
 pub(super) struct UserAuthenticationQuery {
     /// User-generated CLI token.
     #[serde(default)]
     #[schemars(example = "crate::schema::example_token")]
     cli_token: Option<String>,
+
+    /// Requested authentication token scope.
+    ///
+    /// Tokens with the `ci` scope can only access build session
+    /// and source code management routes.
+    #[serde(default)]
+    scope: Scope,
 }
 
 /// Authentication request.
@@ -60,11 +72,11 @@ pub(super) struct UserAuthenticationRequest {
 
     /// Message signed with the provided public key for verification.
     ///
-    /// Verification message consists of
-    /// a string equal to the account address
-    /// used for verification purposes.
+    /// Verification message consists of the server signing domain,
+    /// the `login` purpose and the account address. Message template
+    /// can be retrieved using the `/auth/signingInfo` route.
     ///
-    /// Example: `<Bytes>5FeLhJAs4CUHqpWmPDBLeL7NLAoHsB2ZuFZ5Mk62EgYemtFj</Bytes>`
+    /// Example: `<Bytes>patron:patron.works:login:5FeLhJAs4CUHqpWmPDBLeL7NLAoHsB2ZuFZ5Mk62EgYemtFj</Bytes>`
     #[schemars(example = "crate::schema::example_signature", with = "String")]
     signature: Signature,
 }
@@ -103,9 +115,17 @@ Regular authentication flow returns an authentication token from this route
 as soon as the signature validation is successful. CLI authentication flow
 does not return anything from this route, relying on client calling an `/auth/exchange` route.
 To proceed with the CLI authentication flow, pass `cli_token` value as specified
-in the query string documentation."#,
+in the query string documentation.
+
+Tokens with a limited scope can be requested using the `scope` query string value."#,
         )
-        .response::<200, Json<UserAuthenticationResponse>>()
+        .response_with::<200, Json<UserAuthenticationResponse>, _>(|op| {
+            op.description("User authentication response.").example(
+                UserAuthenticationResponse::Web {
+                    token: example_token(),
+                },
+            )
+        })
         .response_with::<422, Json<Value>, _>(|op| {
             op.description("The provided signature is invalid.")
                 .example(example_error(UserAuthenticationError::InvalidSignature))
@@ -118,6 +138,7 @@ in the query string documentation."#,
 /// and return an authentication token for the relevant user.
 pub(super) async fn login(
     State(db): State<Arc<DatabaseConnection>>,
+    Extension(config): Extension<Arc<Config>>,
     Query(query): Query<UserAuthenticationQuery>,
     Json(request): Json<UserAuthenticationRequest>,
 ) -> Result<Json<UserAuthenticationResponse>, UserAuthenticationError> {
@@ -132,12 +153,14 @@ pub(super) async fn login(
                 .await?
                 .ok_or(UserAuthenticationError::NoRelatedAccounts)?;
 
-            if Pair::verify(
+            if signing::verify(
+                &config,
+                Purpose::Login,
                 &request.signature,
-                format!("<Bytes>{}</Bytes>", &request.account),
                 &request.account,
             ) {
-                let (active_model, token) = token::generate_token(user_id);
+                let (mut active_model, token) = token::generate_token(user_id);
+                active_model.scope = ActiveValue::Set(query.scope);
 
                 let model = token::Entity::insert(active_model)
                     .exec_with_returning(txn)
@@ -145,11 +168,11 @@ pub(super) async fn login(
 
                 let response = if let Some(token) = query.cli_token {
                     cli_token::Entity::insert(cli_token::ActiveModel {
-                        token: ActiveValue::Set(token),
+                        token_hash: ActiveValue::Set(cli_token::hash_token(&token)),
                         authentication_token_id: ActiveValue::Set(model.id),
                     })
                     .on_conflict(
-                        OnConflict::column(cli_token::Column::Token)
+                        OnConflict::column(cli_token::Column::TokenHash)
                             .do_nothing()
                             .to_owned(),
                     )
@@ -184,28 +207,54 @@ mod tests {
     };
     use common::{
         config::Config,
-        rpc::sp_core::crypto::{AccountId32, Ss58Codec},
+        rpc::sp_core::{
+            crypto::{AccountId32, Ss58Codec},
+            sr25519::Pair,
+            Pair as _,
+        },
     };
     use db::{
-        cli_token, public_key, token::TOKEN_LENGTH, user, ActiveValue, DatabaseConnection,
-        EntityTrait,
+        cli_token, public_key,
+        token::{self, Scope, TOKEN_LENGTH},
+        user, ActiveValue, DatabaseConnection, EntityTrait,
     };
     use rand::{
         distributions::{Alphanumeric, DistString},
         thread_rng,
     };
-    use serde_json::json;
+    use serde_json::{json, Value};
     use tower::{Service, ServiceExt};
 
+    use crate::signing::{self, Purpose};
+
     const ACCOUNT_ID: &str = "5FeLhJAs4CUHqpWmPDBLeL7NLAoHsB2ZuFZ5Mk62EgYemtFj";
 
-    async fn create_test_account(db: &DatabaseConnection) {
+    /// Signature of the deprecated ownership proof, that contains only the [`ACCOUNT_ID`].
+    const LEGACY_SIGNATURE: &str = "0x6aa1134d5082aae91dc710cf70d79d2abf6c261cc58eeb13d25ef4dfc8eeed54de76e49f186cde3efd41f6008598ab8d895c78b4354f26e868ead1d8e6410d8a";
+
+    /// Create an authentication request body signed with the test account.
+    fn signed_request() -> (String, Value) {
+        let pair = Pair::from_seed(&[1; 32]);
+        let account = pair.public().to_ss58check();
+
+        let message = signing::message(&Config::for_tests(), Purpose::Login, &account);
+        let signature = pair.sign(message.as_bytes());
+
+        let body = json!({
+            "account": account,
+            "signature": format!("0x{}", hex::encode(signature)),
+        });
+
+        (account, body)
+    }
+
+    async fn create_test_account(db: &DatabaseConnection, account: &str) {
         let user = user::Entity::insert(user::ActiveModel::default())
             .exec_with_returning(db)
             .await
             .expect("unable to create user");
 
-        let account = AccountId32::from_ss58check(ACCOUNT_ID).unwrap();
+        let account = AccountId32::from_ss58check(account).unwrap();
         let account_buf: &[u8] = account.as_ref();
 
         public_key::Entity::insert(public_key::ActiveModel {
@@ -222,7 +271,8 @@ mod tests {
     async fn successful() {
         let db = create_database().await;
 
-        create_test_account(&db).await;
+        let (account, body) = signed_request();
+        create_test_account(&db, &account).await;
 
         let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
             .oneshot(
@@ -230,10 +280,7 @@ mod tests {
                     .method("POST")
                     .uri("/auth/login")
                     .header("Content-Type", "application/json")
-                    .body(Body::from_json(json!({
-                        "account": ACCOUNT_ID,
-                        "signature": "0x6aa1134d5082aae91dc710cf70d79d2abf6c261cc58eeb13d25ef4dfc8eeed54de76e49f186cde3efd41f6008598ab8d895c78b4354f26e868ead1d8e6410d8a"
-                    })))
+                    .body(Body::from_json(&body))
                     .unwrap(),
             )
             .await
@@ -252,7 +299,7 @@ mod tests {
     async fn invalid_account() {
         let db = create_database().await;
 
-        create_test_account(&db).await;
+        create_test_account(&db, ACCOUNT_ID).await;
 
         let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
             .oneshot(
@@ -276,7 +323,7 @@ mod tests {
     async fn invalid_signature() {
         let db = create_database().await;
 
-        create_test_account(&db).await;
+        create_test_account(&db, ACCOUNT_ID).await;
 
         let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
             .oneshot(
@@ -300,7 +347,7 @@ mod tests {
     async fn unmatching_signature() {
         let db = create_database().await;
 
-        create_test_account(&db).await;
+        create_test_account(&db, ACCOUNT_ID).await;
 
         let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
             .oneshot(
@@ -320,6 +367,41 @@ mod tests {
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
     }
 
+    #[tokio::test]
+    async fn legacy_signature() {
+        for legacy_signatures in [false, true] {
+            let db = create_database().await;
+
+            create_test_account(&db, ACCOUNT_ID).await;
+
+            let mut config = Config::for_tests();
+            config.server.as_mut().unwrap().legacy_signatures = legacy_signatures;
+
+            let response = crate::app_router(Arc::new(db), Arc::new(config))
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/auth/login")
+                        .header("Content-Type", "application/json")
+                        .body(Body::from_json(json!({
+                            "account": ACCOUNT_ID,
+                            "signature": LEGACY_SIGNATURE,
+                        })))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let expected_status = if legacy_signatures {
+                StatusCode::OK
+            } else {
+                StatusCode::UNPROCESSABLE_ENTITY
+            };
+
+            assert_eq!(response.status(), expected_status);
+        }
+    }
+
     #[tokio::test]
     async fn missing_account() {
         let db = create_database().await;
@@ -332,7 +414,7 @@ mod tests {
                     .header("Content-Type", "application/json")
                     .body(Body::from_json(json!({
                         "account": ACCOUNT_ID,
-                        "signature": "0x6aa1134d5082aae91dc710cf70d79d2abf6c261cc58eeb13d25ef4dfc8eeed54de76e49f186cde3efd41f6008598ab8d895c78b4354f26e868ead1d8e6410d8a"
+                        "signature": LEGACY_SIGNATURE,
                     })))
                     .unwrap(),
             )
@@ -342,15 +424,42 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn ci_scope() {
+        let db = Arc::new(create_database().await);
+
+        let (account, body) = signed_request();
+        create_test_account(&db, &account).await;
+
+        let response = crate::app_router(db.clone(), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/auth/login?scope=ci")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from_json(&body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let model = token::Entity::find().one(&*db).await.unwrap().unwrap();
+
+        assert_eq!(model.scope, Scope::Ci);
+    }
+
     #[tokio::test]
     async fn exchange() {
-        let db = create_database().await;
+        let db = Arc::new(create_database().await);
 
-        create_test_account(&db).await;
+        let (account, body) = signed_request();
+        create_test_account(&db, &account).await;
 
         let cli_token = Alphanumeric.sample_string(&mut thread_rng(), cli_token::TOKEN_LENGTH);
 
-        let mut service = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()));
+        let mut service = crate::app_router(db.clone(), Arc::new(Config::for_tests()));
 
         let login_response = service
             .call(
@@ -358,10 +467,7 @@ mod tests {
                     .method("POST")
                     .uri(format!("/auth/login?cli_token={cli_token}"))
                     .header("Content-Type", "application/json")
-                    .body(Body::from_json(json!({
-                        "account": ACCOUNT_ID,
-                        "signature": "0x6aa1134d5082aae91dc710cf70d79d2abf6c261cc58eeb13d25ef4dfc8eeed54de76e49f186cde3efd41f6008598ab8d895c78b4354f26e868ead1d8e6410d8a",
-                    })))
+                    .body(Body::from_json(&body))
                     .unwrap(),
             )
             .await
@@ -369,6 +475,9 @@ mod tests {
 
         assert_eq!(login_response.status(), StatusCode::OK);
 
+        let stored = cli_token::Entity::find().one(&*db).await.unwrap().unwrap();
+        assert_eq!(stored.token_hash, cli_token::hash_token(&cli_token));
+
         let exchange_response = service
             .call(
                 Request::builder()
@@ -388,13 +497,76 @@ mod tests {
                     .ok_or(String::from("invalid length"))
             })
         });
+
+        let cli_tokens = cli_token::Entity::find().all(&*db).await.unwrap();
+
+        assert!(cli_tokens.is_empty());
+    }
+
+    #[tokio::test]
+    async fn repeated_exchange() {
+        let db = create_database().await;
+
+        let (account, body) = signed_request();
+        create_test_account(&db, &account).await;
+
+        let cli_token = Alphanumeric.sample_string(&mut thread_rng(), cli_token::TOKEN_LENGTH);
+
+        let mut service = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()));
+
+        let login_response = service
+            .call(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/auth/login?cli_token={cli_token}"))
+                    .header("Content-Type", "application/json")
+                    .body(Body::from_json(&body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(login_response.status(), StatusCode::OK);
+
+        let exchange_response = service
+            .call(
+                Request::builder()
+                    .method("POST")
+                    .uri("/auth/exchange")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from_json(json!({ "cli_token": &cli_token })))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(exchange_response.status(), StatusCode::OK);
+
+        let exchange_response = service
+            .call(
+                Request::builder()
+                    .method("POST")
+                    .uri("/auth/exchange")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from_json(json!({ "cli_token": &cli_token })))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(exchange_response.status(), StatusCode::NOT_FOUND);
+        assert_json!(exchange_response.json().await, {
+            "code": 404,
+            "error": "provided CLI token was not found",
+        });
     }
 
     #[tokio::test]
     async fn cli_token_repetition() {
         let db = create_database().await;
 
-        create_test_account(&db).await;
+        let (account, body) = signed_request();
+        create_test_account(&db, &account).await;
 
         let cli_token = Alphanumeric.sample_string(&mut thread_rng(), cli_token::TOKEN_LENGTH);
 
@@ -406,10 +578,7 @@ mod tests {
                     .method("POST")
                     .uri(format!("/auth/login?cli_token={cli_token}"))
                     .header("Content-Type", "application/json")
-                    .body(Body::from_json(json!({
-                        "account": ACCOUNT_ID,
-                        "signature": "0x6aa1134d5082aae91dc710cf70d79d2abf6c261cc58eeb13d25ef4dfc8eeed54de76e49f186cde3efd41f6008598ab8d895c78b4354f26e868ead1d8e6410d8a",
-                    })))
+                    .body(Body::from_json(&body))
                     .unwrap(),
             )
             .await
@@ -423,10 +592,7 @@ mod tests {
                     .method("POST")
                     .uri(format!("/auth/login?cli_token={cli_token}"))
                     .header("Content-Type", "application/json")
-                    .body(Body::from_json(json!({
-                        "account": ACCOUNT_ID,
-                        "signature": "0x6aa1134d5082aae91dc710cf70d79d2abf6c261cc58eeb13d25ef4dfc8eeed54de76e49f186cde3efd41f6008598ab8d895c78b4354f26e868ead1d8e6410d8a",
-                    })))
+                    .body(Body::from_json(&body))
                     .unwrap(),
             )
             .await
@@ -7,9 +7,15 @@ mod login;
 /// User registration route.
 mod register;
 
+/// Account ownership proof message templates route.
+mod signing_info;
+
 use std::sync::Arc;
 
-use aide::axum::{routing::post_with, ApiRouter};
+use aide::axum::{
+    routing::{get_with, post_with},
+    ApiRouter,
+};
 use db::DatabaseConnection;
 
 /// Create an [`ApiRouter`] that provides an API server with authentication routes.
@@ -18,5 +24,9 @@ pub(crate) fn routes() -> ApiRouter<Arc<DatabaseConnection>> {
         .api_route("/login", post_with(login::login, login::docs))
         .api_route("/register", post_with(register::register, register::docs))
         .api_route("/exchange", post_with(exchange::exchange, exchange::docs))
+        .api_route(
+            "/signingInfo",
+            get_with(signing_info::signing_info, signing_info::docs),
+        )
         .with_path_items(|op| op.tag("Authentication"))
 }
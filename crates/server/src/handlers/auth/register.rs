@@ -10,6 +10,8 @@ use derive_more::{Display, Error, From};
 use schemars::JsonSchema;
 use serde::Serialize;
 
+use crate::schema::example_token;
+
 /// Errors that may occur during the user registration process.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
 #[aide(output)]
@@ -35,7 +37,12 @@ thus registering user immediately. Be aware, that a newly registered user does n
 have any public keys attached to their account, meaning that you have to attach one
 as soon as possible to ensure that a user account does not get lost."#,
         )
-        .response::<200, Json<UserRegistrationResponse>>()
+        .response_with::<200, Json<UserRegistrationResponse>, _>(|op| {
+            op.description("Registered user's authentication token response.")
+                .example(UserRegistrationResponse {
+                    token: example_token(),
+                })
+        })
 }
 
 /// User registration handler.
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use aide::transform::TransformOperation;
+use axum::{Extension, Json};
+use common::config::Config;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::{
+    schema::{
+        example_login_message_template, example_signing_domain, example_verify_message_template,
+    },
+    signing::{self, Purpose, ADDRESS_PLACEHOLDER},
+};
+
+/// Account ownership proof message templates.
+#[derive(Serialize, JsonSchema)]
+pub(super) struct SigningInfoResponse {
+    /// Domain string included into signed messages.
+    #[schemars(example = "crate::schema::example_signing_domain")]
+    domain: String,
+
+    /// Message template used by the `/auth/login` route.
+    ///
+    /// The `{address}` placeholder has to be replaced with the signing account address.
+    #[schemars(example = "crate::schema::example_login_message_template")]
+    login: String,
+
+    /// Message template used by the `/keys` verification route.
+    ///
+    /// The `{address}` placeholder has to be replaced with the signing account address.
+    #[schemars(example = "crate::schema::example_verify_message_template")]
+    verify: String,
+
+    /// Whether deprecated messages, that contain only the account address, are still accepted.
+    legacy_signatures: bool,
+}
+
+/// Generate OAPI documentation for the [`signing_info`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get account ownership proof message templates.")
+        .description(
+            r#"Signed messages are bound to this server and to the route they are used with,
+so that signatures collected by other applications cannot be replayed.
+
+Deprecated messages, that consist only of the account address wrapped into
+`<Bytes>` tags, are accepted only if enabled in the server configuration."#,
+        )
+        .response_with::<200, Json<SigningInfoResponse>, _>(|op| {
+            op.description("Message templates response.")
+                .example(SigningInfoResponse {
+                    domain: example_signing_domain(),
+                    login: example_login_message_template(),
+                    verify: example_verify_message_template(),
+                    legacy_signatures: false,
+                })
+        })
+}
+
+/// Account ownership proof message templates request handler.
+pub(super) async fn signing_info(
+    Extension(config): Extension<Arc<Config>>,
+) -> Json<SigningInfoResponse> {
+    Json(SigningInfoResponse {
+        domain: signing::signing_domain(&config).into_owned(),
+        login: signing::message(&config, Purpose::Login, ADDRESS_PLACEHOLDER),
+        verify: signing::message(&config, Purpose::Verify, ADDRESS_PLACEHOLDER),
+        legacy_signatures: signing::legacy_signatures(&config),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use assert_json::assert_json;
+    use axum::{body::Body, http::Request};
+    use common::config::Config;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn signing_info() {
+        let db = create_database().await;
+
+        let mut config = Config::for_tests();
+        let server = config.server.as_mut().unwrap();
+        server.signing_domain = String::from("example.com");
+        server.legacy_signatures = true;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(config))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/auth/signingInfo")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, {
+            "domain": "example.com",
+            "login": "<Bytes>patron:example.com:login:{address}</Bytes>",
+            "verify": "<Bytes>patron:example.com:verify:{address}</Bytes>",
+            "legacy_signatures": true,
+        });
+    }
+}
@@ -0,0 +1,258 @@
+use std::sync::Arc;
+
+use aide::{
+    gen::GenContext,
+    openapi::{Operation, Response as OapiResponse},
+    transform::TransformOperation,
+    OperationIo, OperationOutput,
+};
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use db::{
+    build_session, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    schema::{example_abi_spec, example_abi_types, example_error},
+    visibility,
+};
+
+/// `Cache-Control` header value used for ABI responses.
+///
+/// Metadata is immutable for a given code hash, thus ABI responses can be cached indefinitely.
+const CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Errors that may occur during the contract ABI request.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum BuildSessionAbiError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Unable to extract ABI from the metadata stored inside of a database.
+    #[status(StatusCode::BAD_GATEWAY)]
+    #[display(fmt = "unable to parse stored metadata")]
+    InvalidMetadata,
+
+    /// Unable to find the requested build session.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "build session not found")]
+    BuildSessionNotFound,
+}
+
+/// Contract ABI extracted from JSON metadata.
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct AbiData {
+    /// Contract specification, containing constructors, messages and events.
+    pub spec: Value,
+
+    /// Type registry referenced by the contract specification.
+    pub types: Value,
+}
+
+/// [`AbiData`] response with immutable cache headers.
+pub(super) struct CachedAbi(AbiData);
+
+impl IntoResponse for CachedAbi {
+    fn into_response(self) -> Response {
+        ([(header::CACHE_CONTROL, CACHE_CONTROL)], Json(self.0)).into_response()
+    }
+}
+
+impl OperationOutput for CachedAbi {
+    type Inner = AbiData;
+
+    fn operation_response(ctx: &mut GenContext, operation: &mut Operation) -> Option<OapiResponse> {
+        <Json<AbiData> as OperationOutput>::operation_response(ctx, operation)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, OapiResponse)> {
+        <Json<AbiData> as OperationOutput>::inferred_responses(ctx, operation)
+    }
+}
+
+/// Generate OAPI documentation for the [`abi`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get contract ABI of the latest build session.")
+        .description(
+            r#"Only `spec` and `types` sections of the JSON metadata are returned.
+
+Responses are immutable for the provided code hash, and are cached accordingly."#,
+        )
+        .response_with::<200, Json<AbiData>, _>(|op| {
+            op.description("Contract ABI response.").example(AbiData {
+                spec: example_abi_spec(),
+                types: example_abi_types(),
+            })
+        })
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description(
+                "No build sessions with the provided code hash were found, \
+                or their source code is private.",
+            )
+            .example(example_error(BuildSessionAbiError::BuildSessionNotFound))
+        })
+        .response_with::<502, Json<Value>, _>(|op| {
+            op.description("Stored metadata could not be parsed.")
+                .example(example_error(BuildSessionAbiError::InvalidMetadata))
+        })
+}
+
+/// Contract ABI request handler.
+pub(super) async fn abi(
+    Path(code_hash): Path<HexHash>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> Result<CachedAbi, BuildSessionAbiError> {
+    let current_user = current_user.map(|Extension(user)| user);
+
+    if !visibility::code_hash_visible(&*db, &code_hash.0, current_user).await? {
+        return Err(BuildSessionAbiError::BuildSessionNotFound);
+    }
+
+    let metadata = build_session::Entity::find()
+        .select_only()
+        .column(build_session::Column::Metadata)
+        .filter(build_session::Column::CodeHash.eq(&code_hash.0[..]))
+        .filter(build_session::Column::Metadata.is_not_null())
+        .order_by_desc(build_session::Column::CreatedAt)
+        .into_tuple::<Vec<u8>>()
+        .one(&*db)
+        .await?
+        .ok_or(BuildSessionAbiError::BuildSessionNotFound)?;
+
+    let abi =
+        serde_json::from_slice(&metadata).map_err(|_| BuildSessionAbiError::InvalidMetadata)?;
+
+    Ok(CachedAbi(abi))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use assert_json::assert_json;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{build_session, source_code, user, ActiveValue, DatabaseConnection, EntityTrait};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    /// Contract metadata fixture.
+    const METADATA: &[u8] = include_bytes!("../../../fixtures/metadata.json");
+
+    async fn create_test_env(db: &DatabaseConnection, metadata: &[u8]) {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::Completed),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            code_hash: ActiveValue::Set(Some(vec![0; 32])),
+            metadata: ActiveValue::Set(Some(metadata.to_vec())),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert build session");
+    }
+
+    async fn request(db: DatabaseConnection) -> axum::response::Response {
+        crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!(
+                        "/buildSessions/metadata/{}/abi",
+                        hex::encode([0; 32])
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn successful() {
+        let db = create_database().await;
+
+        create_test_env(&db, METADATA).await;
+
+        let response = request(db).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=31536000, immutable"
+        );
+
+        let metadata: Value = serde_json::from_slice(METADATA).unwrap();
+
+        assert_eq!(
+            response.json().await,
+            serde_json::json!({
+                "spec": metadata["spec"],
+                "types": metadata["types"],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_metadata() {
+        let db = create_database().await;
+
+        create_test_env(&db, br#"{"source": {}}"#).await;
+
+        let response = request(db).await;
+
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        assert_json!(response.json().await, {
+            "code": 502,
+            "error": "unable to parse stored metadata",
+        });
+    }
+
+    #[tokio::test]
+    async fn unknown() {
+        let db = create_database().await;
+
+        let response = request(db).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
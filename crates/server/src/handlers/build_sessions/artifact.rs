@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use aide::{
+    gen::GenContext,
+    openapi::{Operation, Response as OapiResponse},
+    transform::TransformOperation,
+    OperationIo, OperationOutput,
+};
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use db::{
+    build_session::{self, ArtifactKind},
+    code, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use derive_more::{Display, Error, From};
+use serde_json::Value;
+
+use crate::{auth::AuthenticatedUserId, hex_hash::HexHash, schema::example_error, visibility};
+
+/// Errors that may occur during the contract blob request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum BuildSessionArtifactError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// The provided code hash doesn't have any contract blobs saved in the database.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "build session not found")]
+    BuildSessionNotFound,
+}
+
+/// Contract blob response, which uses the content type of the blob kind.
+pub(super) struct ContractArtifact {
+    /// Kind of the contract blob.
+    kind: ArtifactKind,
+
+    /// Contract blob contents.
+    code: Vec<u8>,
+}
+
+impl IntoResponse for ContractArtifact {
+    fn into_response(self) -> Response {
+        let content_type = match self.kind {
+            ArtifactKind::Wasm => "application/wasm",
+            ArtifactKind::Polkavm => "application/octet-stream",
+        };
+
+        ([(header::CONTENT_TYPE, content_type)], self.code).into_response()
+    }
+}
+
+impl OperationOutput for ContractArtifact {
+    type Inner = Vec<u8>;
+
+    fn operation_response(ctx: &mut GenContext, operation: &mut Operation) -> Option<OapiResponse> {
+        <Vec<u8> as OperationOutput>::operation_response(ctx, operation)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, OapiResponse)> {
+        <Vec<u8> as OperationOutput>::inferred_responses(ctx, operation)
+    }
+}
+
+/// Generate OAPI documentation for the [`artifact`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get WASM or PolkaVM blob of the latest build session.")
+        .description(
+            r#"The kind of the returned blob is advertised by the `artifact_kind` field
+of the build session status route. WASM blobs are returned with the `application/wasm`
+content type, while PolkaVM blobs use the `application/octet-stream` content type.
+        "#,
+        )
+        .response::<200, Vec<u8>>()
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description(
+                "No build sessions with the provided code hash were found, \
+                or their source code is private.",
+            )
+            .example(example_error(
+                BuildSessionArtifactError::BuildSessionNotFound,
+            ))
+        })
+}
+
+/// Contract blob request handler.
+///
+/// Contract blobs without any related build sessions are considered to be WASM blobs,
+/// since only `pallet-contracts` blobs were stored before artifact kinds were introduced.
+pub(super) async fn artifact(
+    Path(code_hash): Path<HexHash>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> Result<ContractArtifact, BuildSessionArtifactError> {
+    let current_user = current_user.map(|Extension(user)| user);
+
+    if !visibility::code_hash_visible(&*db, &code_hash.0, current_user).await? {
+        return Err(BuildSessionArtifactError::BuildSessionNotFound);
+    }
+
+    let code = code::Entity::find()
+        .select_only()
+        .column(code::Column::Code)
+        .filter(code::Column::Hash.eq(&code_hash.0[..]))
+        .into_tuple::<Vec<u8>>()
+        .one(&*db)
+        .await?
+        .ok_or(BuildSessionArtifactError::BuildSessionNotFound)?;
+
+    let kind = build_session::Entity::find()
+        .select_only()
+        .column(build_session::Column::ArtifactKind)
+        .filter(build_session::Column::CodeHash.eq(&code_hash.0[..]))
+        .order_by_desc(build_session::Column::Id)
+        .into_tuple::<ArtifactKind>()
+        .one(&*db)
+        .await?
+        .unwrap_or_default();
+
+    Ok(ContractArtifact { kind, code })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{
+        build_session::{self, ArtifactKind},
+        code, source_code, user, ActiveValue, DatabaseConnection, EntityTrait,
+    };
+    use tower::ServiceExt;
+
+    /// Minimal WASM blob.
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+    /// Minimal PolkaVM blob.
+    const POLKAVM: &[u8] = b"PVM\0\x01\0\0\0";
+
+    async fn create_test_env(db: &DatabaseConnection, kind: ArtifactKind, code: &[u8]) {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        code::Entity::insert(code::ActiveModel {
+            hash: ActiveValue::Set(vec![0; 32]),
+            code: ActiveValue::Set(code.to_vec()),
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert code");
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::Completed),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            code_hash: ActiveValue::Set(Some(vec![0; 32])),
+            artifact_kind: ActiveValue::Set(kind),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert build session");
+    }
+
+    #[tokio::test]
+    async fn artifact_kinds() {
+        for (kind, code, content_type) in [
+            (ArtifactKind::Wasm, WASM, "application/wasm"),
+            (ArtifactKind::Polkavm, POLKAVM, "application/octet-stream"),
+        ] {
+            let db = create_database().await;
+
+            create_test_env(&db, kind, code).await;
+
+            let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri(format!("/buildSessions/artifact/{}", hex::encode([0; 32])))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.headers()[header::CONTENT_TYPE], content_type);
+            assert_eq!(response.bytes().await, code);
+        }
+    }
+
+    #[tokio::test]
+    async fn without_build_session() {
+        let db = create_database().await;
+
+        code::Entity::insert(code::ActiveModel {
+            hash: ActiveValue::Set(vec![0; 32]),
+            code: ActiveValue::Set(WASM.to_vec()),
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert code");
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/artifact/{}", hex::encode([0; 32])))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/wasm");
+        assert_eq!(response.bytes().await, WASM);
+    }
+
+    #[tokio::test]
+    async fn unknown() {
+        let db = create_database().await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/artifact/{}", hex::encode([0; 32])))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND)
+    }
+}
@@ -0,0 +1,399 @@
+use std::sync::Arc;
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use db::{
+    diagnostic, file, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, TransactionErrorExt, TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    schema::{
+        example_diagnostic_end, example_diagnostic_level, example_diagnostic_message,
+        example_diagnostic_start, example_error, example_file,
+    },
+    visibility,
+};
+
+/// Errors that may occur during the diagnostics comparison request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum BuildSessionCompareError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// One of the requested build sessions was not found.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "build session not found")]
+    BuildSessionNotFound,
+}
+
+/// A single diagnostic with its related file name.
+#[derive(Serialize, JsonSchema)]
+pub struct ComparedDiagnostic {
+    /// Name of the file that contains the diagnostic.
+    #[schemars(example = "crate::schema::example_file")]
+    pub file: String,
+
+    /// Diagnostic severity level.
+    #[schemars(example = "crate::schema::example_diagnostic_level")]
+    pub level: diagnostic::Level,
+
+    /// Start cursor position of the diagnostic.
+    #[schemars(example = "crate::schema::example_diagnostic_start")]
+    pub start: i64,
+
+    /// End cursor position of the diagnostic.
+    #[schemars(example = "crate::schema::example_diagnostic_end")]
+    pub end: i64,
+
+    /// Diagnostic message.
+    #[schemars(example = "crate::schema::example_diagnostic_message")]
+    pub message: String,
+}
+
+impl ComparedDiagnostic {
+    /// Check if both diagnostics describe the same issue.
+    ///
+    /// Cursor positions are not compared, since the code may move between builds.
+    fn same_issue(&self, other: &Self) -> bool {
+        self.file == other.file && self.level == other.level && self.message == other.message
+    }
+}
+
+/// Diagnostics comparison response.
+#[derive(Serialize, JsonSchema)]
+pub struct DiagnosticsComparison {
+    /// Diagnostics that are not present in the base build session.
+    pub new: Vec<ComparedDiagnostic>,
+
+    /// Base build session diagnostics that are no longer present.
+    pub resolved: Vec<ComparedDiagnostic>,
+
+    /// Count of diagnostics present in both build sessions.
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    pub unchanged_count: usize,
+}
+
+/// Generate OAPI documentation for the [`compare`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Compare diagnostics of the provided build session with the base one.")
+        .description(
+            r#"Diagnostics are matched using file name, level and message,
+ignoring cursor positions since the code may move between builds."#,
+        )
+        .response_with::<200, Json<DiagnosticsComparison>, _>(|op| {
+            op.description("Diagnostics comparison response.")
+                .example(DiagnosticsComparison {
+                    new: vec![ComparedDiagnostic {
+                        file: example_file(),
+                        level: example_diagnostic_level(),
+                        start: example_diagnostic_start(),
+                        end: example_diagnostic_end(),
+                        message: example_diagnostic_message(),
+                    }],
+                    resolved: Vec::new(),
+                    unchanged_count: 1,
+                })
+        })
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description(
+                "One of the build sessions was not found, or its source code is private.",
+            )
+            .example(example_error(
+                BuildSessionCompareError::BuildSessionNotFound,
+            ))
+        })
+}
+
+/// Diagnostics comparison request handler.
+pub(super) async fn compare(
+    Path((id, base_id)): Path<(i64, i64)>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> Result<Json<DiagnosticsComparison>, BuildSessionCompareError> {
+    let current_user = current_user.map(|Extension(user)| user);
+
+    let (current, mut resolved) = db
+        .transaction::<_, _, BuildSessionCompareError>(|txn| {
+            Box::pin(async move {
+                Ok((
+                    diagnostics(txn, id, current_user).await?,
+                    diagnostics(txn, base_id, current_user).await?,
+                ))
+            })
+        })
+        .await
+        .into_raw_result()?;
+
+    let mut new = Vec::new();
+    let mut unchanged_count = 0;
+
+    for diagnostic in current {
+        match resolved
+            .iter()
+            .position(|base| base.same_issue(&diagnostic))
+        {
+            Some(index) => {
+                resolved.remove(index);
+                unchanged_count += 1;
+            }
+            None => new.push(diagnostic),
+        }
+    }
+
+    Ok(Json(DiagnosticsComparison {
+        new,
+        resolved,
+        unchanged_count,
+    }))
+}
+
+/// Get diagnostics of the provided build session, if it is visible to the current user.
+async fn diagnostics(
+    txn: &DatabaseTransaction,
+    build_session_id: i64,
+    current_user: Option<AuthenticatedUserId>,
+) -> Result<Vec<ComparedDiagnostic>, BuildSessionCompareError> {
+    if !visibility::build_session_visible(txn, build_session_id, current_user).await? {
+        return Err(BuildSessionCompareError::BuildSessionNotFound);
+    }
+
+    let diagnostics = diagnostic::Entity::find()
+        .select_only()
+        .column(file::Column::Name)
+        .columns([
+            diagnostic::Column::Level,
+            diagnostic::Column::Start,
+            diagnostic::Column::End,
+            diagnostic::Column::Message,
+        ])
+        .inner_join(file::Entity)
+        .filter(diagnostic::Column::BuildSessionId.eq(build_session_id))
+        .order_by_asc(diagnostic::Column::Id)
+        .into_tuple::<(String, diagnostic::Level, i64, i64, String)>()
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|(file, level, start, end, message)| ComparedDiagnostic {
+            file,
+            level,
+            start,
+            end,
+            message,
+        })
+        .collect();
+
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
+
+    use assert_json::assert_json;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{
+        build_session, diagnostic, file, source_code, user, ActiveValue, DatabaseConnection,
+        EntityTrait,
+    };
+    use tower::ServiceExt;
+
+    /// Create a build session with the provided diagnostics in a single file.
+    async fn create_build_session(
+        db: &DatabaseConnection,
+        diagnostics: &[(diagnostic::Level, i64, &str)],
+    ) -> i64 {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        let build_session_id = build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::Failed),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert build session")
+        .id;
+
+        let file_id = file::Entity::insert(file::ActiveModel {
+            source_code_id: ActiveValue::Set(source_code_id),
+            name: ActiveValue::Set(String::from("lib.rs")),
+            text: ActiveValue::Set(String::new()),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert file")
+        .id;
+
+        for (level, start, message) in diagnostics {
+            diagnostic::Entity::insert(diagnostic::ActiveModel {
+                build_session_id: ActiveValue::Set(build_session_id),
+                file_id: ActiveValue::Set(file_id),
+                level: ActiveValue::Set(level.clone()),
+                start: ActiveValue::Set(*start),
+                end: ActiveValue::Set(*start + 1),
+                message: ActiveValue::Set(String::from(*message)),
+                ..Default::default()
+            })
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert diagnostic");
+        }
+
+        build_session_id
+    }
+
+    /// Compare diagnostics of the provided build sessions.
+    async fn request(
+        db: Arc<DatabaseConnection>,
+        id: i64,
+        base_id: i64,
+    ) -> axum::response::Response {
+        crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/{id}/diagnostics/compare/{base_id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn overlapping() {
+        let db = Arc::new(create_database().await);
+
+        let base = create_build_session(
+            &db,
+            &[
+                (diagnostic::Level::Error, 0, "unchanged"),
+                (diagnostic::Level::Warning, 10, "resolved"),
+            ],
+        )
+        .await;
+        let current = create_build_session(
+            &db,
+            &[
+                (diagnostic::Level::Error, 5, "unchanged"),
+                (diagnostic::Level::Error, 15, "new"),
+            ],
+        )
+        .await;
+
+        let response = request(db, current, base).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, {
+            "new": [
+                {
+                    "file": "lib.rs",
+                    "level": "error",
+                    "start": 15,
+                    "end": 16,
+                    "message": "new",
+                }
+            ],
+            "resolved": [
+                {
+                    "file": "lib.rs",
+                    "level": "warning",
+                    "start": 10,
+                    "end": 11,
+                    "message": "resolved",
+                }
+            ],
+            "unchanged_count": 1,
+        });
+    }
+
+    #[tokio::test]
+    async fn disjoint() {
+        let db = Arc::new(create_database().await);
+
+        let base = create_build_session(&db, &[(diagnostic::Level::Error, 0, "first")]).await;
+        let current = create_build_session(
+            &db,
+            &[
+                (diagnostic::Level::Warning, 0, "first"),
+                (diagnostic::Level::Error, 0, "second"),
+            ],
+        )
+        .await;
+
+        let response = request(db, current, base).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.json().await;
+        assert_eq!(body["new"].as_array().unwrap().len(), 2);
+        assert_eq!(body["resolved"].as_array().unwrap().len(), 1);
+        assert_eq!(body["unchanged_count"], 0);
+    }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = Arc::new(create_database().await);
+
+        let private = create_build_session(&db, &[]).await;
+        make_source_code_private(&db).await;
+        let public = create_build_session(&db, &[]).await;
+
+        for (id, base_id) in [(public, private), (private, public)] {
+            let response = request(db.clone(), id, base_id).await;
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown() {
+        let db = Arc::new(create_database().await);
+
+        let id = create_build_session(&db, &[]).await;
+
+        for (id, base_id) in [(id, id + 1), (id + 1, id)] {
+            let response = request(db.clone(), id, base_id).await;
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            assert_json!(response.json().await, {
+                "code": 404,
+                "error": "build session not found",
+            });
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use std::sync::Arc;
 
 use aide::{transform::TransformOperation, OperationIo};
+use api_types::build_sessions::BuildSessionCreateResponse;
 use axum::{extract::State, http::StatusCode, Extension, Json};
 use axum_derive_error::ErrorResponse;
+use common::{config::Config, paths};
 use db::{
     build_session, build_session_token, source_code, user, ActiveValue, DatabaseConnection, DbErr,
     EntityTrait, QuerySelect, SelectExt, TransactionErrorExt, TransactionTrait,
@@ -10,11 +12,15 @@ use db::{
 use derive_more::{Display, Error, From};
 use schemars::JsonSchema;
 use semver::Version;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use serde_json::Value;
 use validator::{Validate, ValidationError};
 
-use crate::{auth::AuthenticatedUserId, schema::example_error, validation::ValidatedJson};
+use crate::{
+    auth::AuthenticatedUserId,
+    schema::{example_cargo_contract_version, example_database_identifier, example_error},
+    validation::ValidatedJson,
+};
 
 /// Errors that may occur during the build session creation process.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -32,6 +38,17 @@ pub(super) enum BuildSessionCreateError {
     #[status(StatusCode::NOT_FOUND)]
     #[display(fmt = "source code not found")]
     SourceCodeNotFound,
+
+    /// Provided `cargo-contract` version is not supported by the builder.
+    #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+    #[display(
+        fmt = "unsupported cargo-contract version, supported versions: {}",
+        supported
+    )]
+    UnsupportedCargoContractVersion {
+        /// Comma-separated list of supported `cargo-contract` versions.
+        supported: String,
+    },
 }
 
 /// JSON request body.
@@ -48,7 +65,8 @@ pub(super) struct BuildSessionCreateRequest {
 
     /// Relative project directory, that can be used to build multi-contract projects.
     ///
-    /// If empty, the source code root will be used.
+    /// If empty, the source code root will be used. Paths that resolve
+    /// to a directory outside of the source code root are rejected.
     #[validate(length(max = 64), custom = "validate_project_directory")]
     #[schemars(example = "crate::schema::example_folder")]
     project_directory: Option<String>,
@@ -61,43 +79,70 @@ fn validate_cargo_contract_version(cargo_contract_version: &str) -> Result<(), V
         .map_err(|_| ValidationError::new("invalid cargo-contract version"))
 }
 
-/// Validate the provided project directory to be an alphanumeric-based path.
+/// Validate the provided project directory to be an alphanumeric-based path
+/// inside of the project root.
+///
+/// Paths are resolved in the same way as in the builder itself.
 fn validate_project_directory(project_directory: &str) -> Result<(), ValidationError> {
-    if project_directory.chars().all(|ch| {
+    if !project_directory.chars().all(|ch| {
         matches!(ch, '.' | '/' | '_' | '-')
             || ch.is_ascii_alphanumeric()
             || ch.is_ascii_whitespace()
     }) {
-        Ok(())
-    } else {
-        Err(ValidationError::new("expected alphanumeric-based path"))
+        return Err(ValidationError::new("expected alphanumeric-based path"));
     }
-}
 
-/// JSON response body.
-#[derive(Serialize, JsonSchema)]
-pub(super) struct BuildSessionCreateResponse {
-    /// Build session identifier.
-    #[schemars(example = "crate::schema::example_database_identifier")]
-    id: i64,
+    if paths::normalize_project_dir(project_directory).is_none() {
+        return Err(ValidationError::new(
+            "expected path inside of the project root",
+        ));
+    }
+
+    Ok(())
 }
 
 /// Generate OAPI documentation for the [`create`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Create new build session.")
-        .response::<200, Json<BuildSessionCreateResponse>>()
+        .response_with::<200, Json<BuildSessionCreateResponse>, _>(|op| {
+            op.description("Created build session response.")
+                .example(BuildSessionCreateResponse {
+                    id: example_database_identifier(),
+                })
+        })
         .response_with::<404, Json<Value>, _>(|op| {
             op.description("Provided source code identifier is incorrect.")
                 .example(example_error(BuildSessionCreateError::SourceCodeNotFound))
         })
+        .response_with::<422, Json<Value>, _>(|op| {
+            op.description("Provided cargo-contract version is not supported.")
+                .example(example_error(
+                    BuildSessionCreateError::UnsupportedCargoContractVersion {
+                        supported: example_cargo_contract_version(),
+                    },
+                ))
+        })
 }
 
 /// Build session creation handler.
+///
+/// Only `cargo-contract` versions from the server configuration are accepted,
+/// since the builder would reject any other version anyway.
 pub(super) async fn create(
     Extension(current_user): Extension<AuthenticatedUserId>,
+    Extension(config): Extension<Arc<Config>>,
     State(db): State<Arc<DatabaseConnection>>,
     ValidatedJson(request): ValidatedJson<BuildSessionCreateRequest>,
 ) -> Result<Json<BuildSessionCreateResponse>, BuildSessionCreateError> {
+    if !config
+        .supported_cargo_contract_versions
+        .contains(&request.cargo_contract_version)
+    {
+        return Err(BuildSessionCreateError::UnsupportedCargoContractVersion {
+            supported: config.supported_cargo_contract_versions.join(", "),
+        });
+    }
+
     db.transaction(|txn| {
         Box::pin(async move {
             let user_exists = user::Entity::find_by_id(current_user.id())
@@ -129,6 +174,7 @@ pub(super) async fn create(
                     token: ActiveValue::Set(build_session_token::generate_token()),
                     source_code_id: ActiveValue::Set(request.source_code_id),
                     build_session_id: ActiveValue::Set(model.id),
+                    ..Default::default()
                 })
                 .exec_without_returning(txn)
                 .await?;
@@ -209,7 +255,7 @@ mod tests {
                     .header("Content-Type", "application/json")
                     .body(Body::from_json(json!({
                         "source_code_id": source_code_id,
-                        "cargo_contract_version": "3.0.0",
+                        "cargo_contract_version": "3.1.0",
                         "project_directory": "./contracts/test/../another_contract"
                     })))
                     .unwrap(),
@@ -247,6 +293,35 @@ mod tests {
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
     }
 
+    #[tokio::test]
+    async fn unsupported_version() {
+        let db = create_database().await;
+
+        let (token, source_code_id) = create_test_env(&db).await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/buildSessions")
+                    .header("Authorization", format!("Bearer {token}"))
+                    .header("Content-Type", "application/json")
+                    .body(Body::from_json(json!({
+                        "source_code_id": source_code_id,
+                        "cargo_contract_version": "3.0.0",
+                    })))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_json!(response.json().await, {
+            "code": 422,
+            "error": "unsupported cargo-contract version, supported versions: 4.0.0-alpha, 3.1.0",
+        });
+    }
+
     #[tokio::test]
     async fn invalid_source_code_id() {
         let db = create_database().await;
@@ -262,7 +337,7 @@ mod tests {
                     .header("Content-Type", "application/json")
                     .body(Body::from_json(json!({
                         "source_code_id": 123,
-                        "cargo_contract_version": "3.0.0",
+                        "cargo_contract_version": "3.1.0",
                     })))
                     .unwrap(),
             )
@@ -289,7 +364,7 @@ mod tests {
                     .header("Content-Type", "application/json")
                     .body(Body::from_json(json!({
                         "source_code_id": 123,
-                        "cargo_contract_version": "3.0.0",
+                        "cargo_contract_version": "3.1.0",
                         "project_directory": "��",
                     })))
                     .unwrap(),
@@ -308,7 +383,7 @@ mod tests {
                     .header("Content-Type", "application/json")
                     .body(Body::from_json(json!({
                         "source_code_id": 123,
-                        "cargo_contract_version": "3.0.0",
+                        "cargo_contract_version": "3.1.0",
                         "project_directory": "\\",
                     })))
                     .unwrap(),
@@ -317,5 +392,26 @@ mod tests {
             .unwrap();
 
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        for project_directory in ["../../", "contracts/../..", "/contract"] {
+            let response = service
+                .call(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/buildSessions")
+                        .header("Authorization", format!("Bearer {token}"))
+                        .header("Content-Type", "application/json")
+                        .body(Body::from_json(json!({
+                            "source_code_id": 123,
+                            "cargo_contract_version": "3.1.0",
+                            "project_directory": project_directory,
+                        })))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
     }
 }
@@ -1,22 +1,32 @@
 use std::sync::Arc;
 
 use aide::{transform::TransformOperation, OperationIo};
+use api_types::build_sessions::ArtifactSignature;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
 use db::{
-    build_session, sea_orm, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, FromQueryResult,
-    QueryFilter, QueryOrder, QuerySelect,
+    build_session, sea_orm, sea_query::SimpleExpr, signing_key, ColumnTrait, DatabaseConnection,
+    DbErr, EntityTrait, FromQueryResult, QueryFilter, QueryOrder, QuerySelect,
 };
 use derive_more::{Display, Error, From};
 use schemars::JsonSchema;
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::{hex_hash::HexHash, schema::example_error};
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    schema::{
+        example_artifact_signature, example_build_image, example_builder_hostname,
+        example_cargo_contract_version, example_contract_name, example_database_identifier,
+        example_error, example_image_digest, example_stage_duration,
+    },
+    visibility,
+};
 
 /// Build session tooling and source code details response.
 #[derive(Serialize, FromQueryResult, JsonSchema)]
@@ -28,6 +38,76 @@ pub struct BuildSessionInfo {
     /// Version of `cargo-contract` used to build the contract.
     #[schemars(example = "crate::schema::example_cargo_contract_version")]
     pub cargo_contract_version: String,
+
+    /// Name of the built contract crate.
+    #[schemars(example = "crate::schema::example_contract_name")]
+    pub contract_name: Option<String>,
+
+    /// Duration of the source code unarchiving stage, in milliseconds.
+    #[schemars(example = "crate::schema::example_stage_duration")]
+    pub unarchive_duration: Option<i64>,
+
+    /// Duration of the contract build stage, in milliseconds.
+    #[schemars(example = "crate::schema::example_stage_duration")]
+    pub build_duration: Option<i64>,
+
+    /// Duration of the build artifacts retrieval stage, in milliseconds.
+    #[schemars(example = "crate::schema::example_stage_duration")]
+    pub move_duration: Option<i64>,
+
+    /// Digest of the build image used to build the contract.
+    #[schemars(example = "crate::schema::example_image_digest")]
+    pub image_digest: Option<String>,
+
+    /// Reference of the build image used to build the contract.
+    ///
+    /// Image is referenced by its digest, if it was available to the builder.
+    #[schemars(example = "crate::schema::example_build_image")]
+    pub build_image: Option<String>,
+
+    /// Hostname of the builder instance that handled the build session.
+    #[schemars(example = "crate::schema::example_builder_hostname")]
+    pub builder_hostname: Option<String>,
+}
+
+/// Build session details response.
+#[derive(Serialize, JsonSchema)]
+pub struct BuildSessionDetails {
+    /// Build session tooling and source code details.
+    #[serde(flatten)]
+    pub info: BuildSessionInfo,
+
+    /// Builder signature of the build artifacts.
+    ///
+    /// [`None`] if the build session did not complete or its builder had no signing key.
+    #[schemars(example = "crate::schema::example_artifact_signature")]
+    pub signature: Option<ArtifactSignature>,
+}
+
+/// Artifact signature columns of a build session.
+#[derive(FromQueryResult)]
+struct SignatureRow {
+    /// Raw sr25519 signature.
+    signature: Option<Vec<u8>>,
+
+    /// Signing key identifier.
+    signing_key_id: Option<String>,
+
+    /// Raw sr25519 public key of the signing key.
+    public_key: Option<Vec<u8>>,
+}
+
+impl SignatureRow {
+    /// Convert raw signature columns into an [`ArtifactSignature`].
+    ///
+    /// Returns [`None`] if the build session is not signed or if stored values are malformed.
+    fn into_signature(self) -> Option<ArtifactSignature> {
+        Some(ArtifactSignature {
+            key_id: self.signing_key_id?,
+            public_key: self.public_key?.as_slice().try_into().ok()?,
+            signature: self.signature?.as_slice().try_into().ok()?,
+        })
+    }
 }
 
 /// Errors that may occur during the detail preview process.
@@ -51,46 +131,100 @@ pub(super) enum BuildSessionDetailsError {
 /// Generate OAPI documentation for the [`details`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get build session tooling and source code information.")
-        .response::<200, Json<BuildSessionInfo>>()
+        .response_with::<200, Json<BuildSessionDetails>, _>(|op| {
+            op.description("Build session details response.")
+                .example(BuildSessionDetails {
+                    info: BuildSessionInfo {
+                        source_code_id: example_database_identifier(),
+                        cargo_contract_version: example_cargo_contract_version(),
+                        contract_name: example_contract_name(),
+                        unarchive_duration: example_stage_duration(),
+                        build_duration: example_stage_duration(),
+                        move_duration: example_stage_duration(),
+                        image_digest: example_image_digest(),
+                        build_image: example_build_image(),
+                        builder_hostname: example_builder_hostname(),
+                    },
+                    signature: example_artifact_signature(),
+                })
+        })
         .response_with::<404, Json<Value>, _>(|op| {
-            op.description("No build sessions with the provided code hash were found.")
-                .example(example_error(
-                    BuildSessionDetailsError::BuildSessionNotFound,
-                ))
+            op.description(
+                "No build sessions with the provided identifier were found, \
+                or their source code is private.",
+            )
+            .example(example_error(
+                BuildSessionDetailsError::BuildSessionNotFound,
+            ))
         })
 }
 
 /// Build session details handler.
 ///
 /// This route is suitable to acquire the information on tooling
-/// versions used during the smart contract build process.
+/// versions used during the smart contract build process,
+/// as well as the builder signature of build artifacts.
 pub(super) async fn details(
     Path(id): Path<String>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
     State(db): State<Arc<DatabaseConnection>>,
-) -> Result<Json<BuildSessionInfo>, BuildSessionDetailsError> {
+) -> Result<Json<BuildSessionDetails>, BuildSessionDetailsError> {
+    let filter: SimpleExpr = match serde_plain::from_str::<HexHash>(&id) {
+        Ok(val) => build_session::Column::CodeHash.eq(&val.0[..]),
+        Err(_) => {
+            let id = id
+                .parse::<i64>()
+                .map_err(|_| BuildSessionDetailsError::UnknownIdFormat)?;
+
+            build_session::Column::Id.eq(id)
+        }
+    };
+
     let model = build_session::Entity::find()
         .select_only()
         .columns([
             build_session::Column::SourceCodeId,
             build_session::Column::CargoContractVersion,
+            build_session::Column::ContractName,
+            build_session::Column::UnarchiveDuration,
+            build_session::Column::BuildDuration,
+            build_session::Column::MoveDuration,
+            build_session::Column::ImageDigest,
+            build_session::Column::BuildImage,
+            build_session::Column::BuilderHostname,
         ])
-        .filter(match serde_plain::from_str::<HexHash>(&id) {
-            Ok(val) => build_session::Column::CodeHash.eq(&val.0[..]),
-            Err(_) => {
-                let id = id
-                    .parse::<i64>()
-                    .map_err(|_| BuildSessionDetailsError::UnknownIdFormat)?;
-
-                build_session::Column::Id.eq(id)
-            }
-        })
+        .filter(filter.clone())
         .order_by_desc(build_session::Column::CreatedAt)
-        .into_model()
+        .into_model::<BuildSessionInfo>()
         .one(&*db)
         .await?
         .ok_or(BuildSessionDetailsError::BuildSessionNotFound)?;
 
-    Ok(Json(model))
+    let current_user = current_user.map(|Extension(user)| user);
+
+    if !visibility::source_code_visible(&*db, model.source_code_id, current_user).await? {
+        return Err(BuildSessionDetailsError::BuildSessionNotFound);
+    }
+
+    let signature = build_session::Entity::find()
+        .select_only()
+        .columns([
+            build_session::Column::Signature,
+            build_session::Column::SigningKeyId,
+        ])
+        .column(signing_key::Column::PublicKey)
+        .left_join(signing_key::Entity)
+        .filter(filter)
+        .order_by_desc(build_session::Column::CreatedAt)
+        .into_model::<SignatureRow>()
+        .one(&*db)
+        .await?
+        .and_then(SignatureRow::into_signature);
+
+    Ok(Json(BuildSessionDetails {
+        info: model,
+        signature,
+    }))
 }
 
 #[cfg(test)]
@@ -99,15 +233,25 @@ mod tests {
 
     use crate::testing::{create_database, ResponseBodyExt};
 
-    use assert_json::assert_json;
+    use assert_json::{assert_json, validators};
     use axum::{
         body::Body,
         http::{Request, StatusCode},
     };
     use common::config::Config;
-    use db::{build_session, source_code, user, ActiveValue, DatabaseConnection, EntityTrait};
+    use db::{
+        build_session, signing_key, source_code, user, ActiveValue, DatabaseConnection, EntityTrait,
+    };
     use tower::ServiceExt;
 
+    /// Build image digest used in tests.
+    const IMAGE_DIGEST: &str =
+        "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
+    /// Build image reference used in tests.
+    const BUILD_IMAGE: &str = "paritytech/contracts-verifiable\
+        @sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
     async fn create_test_env(db: &DatabaseConnection) -> i64 {
         let user = user::Entity::insert(user::ActiveModel::default())
             .exec_with_returning(db)
@@ -130,6 +274,12 @@ mod tests {
             status: ActiveValue::Set(build_session::Status::New),
             cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
             code_hash: ActiveValue::Set(Some(vec![0; 32])),
+            contract_name: ActiveValue::Set(Some(String::from("flipper"))),
+            unarchive_duration: ActiveValue::Set(Some(1500)),
+            build_duration: ActiveValue::Set(Some(60000)),
+            image_digest: ActiveValue::Set(Some(String::from(IMAGE_DIGEST))),
+            build_image: ActiveValue::Set(Some(String::from(BUILD_IMAGE))),
+            builder_hostname: ActiveValue::Set(Some(String::from("builder-1"))),
             ..Default::default()
         })
         .exec_with_returning(db)
@@ -159,7 +309,15 @@ mod tests {
 
         assert_json!(response.json().await, {
             "source_code_id": 1,
-            "cargo_contract_version": "3.0.0"
+            "cargo_contract_version": "3.0.0",
+            "contract_name": "flipper",
+            "unarchive_duration": 1500,
+            "build_duration": 60000,
+            "move_duration": validators::null(),
+            "image_digest": IMAGE_DIGEST,
+            "build_image": BUILD_IMAGE,
+            "builder_hostname": "builder-1",
+            "signature": validators::null(),
         });
     }
 
@@ -182,7 +340,69 @@ mod tests {
 
         assert_json!(response.json().await, {
             "source_code_id": 1,
-            "cargo_contract_version": "3.0.0"
+            "cargo_contract_version": "3.0.0",
+            "contract_name": "flipper",
+            "unarchive_duration": 1500,
+            "build_duration": 60000,
+            "move_duration": validators::null(),
+            "image_digest": IMAGE_DIGEST,
+            "build_image": BUILD_IMAGE,
+            "builder_hostname": "builder-1",
+            "signature": validators::null(),
+        });
+    }
+
+    #[tokio::test]
+    async fn signed() {
+        let db = create_database().await;
+
+        let build_session_id = create_test_env(&db).await;
+
+        signing_key::Entity::insert(signing_key::ActiveModel {
+            id: ActiveValue::Set(String::from("0102030405060708")),
+            public_key: ActiveValue::Set(vec![1; 32]),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert signing key");
+
+        build_session::Entity::update(build_session::ActiveModel {
+            id: ActiveValue::Unchanged(build_session_id),
+            signature: ActiveValue::Set(Some(vec![2; 64])),
+            signing_key_id: ActiveValue::Set(Some(String::from("0102030405060708"))),
+            ..Default::default()
+        })
+        .exec(&db)
+        .await
+        .expect("unable to sign build session");
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/details/{}", hex::encode([0; 32])))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, {
+            "source_code_id": 1,
+            "cargo_contract_version": "3.0.0",
+            "contract_name": "flipper",
+            "unarchive_duration": 1500,
+            "build_duration": 60000,
+            "move_duration": validators::null(),
+            "image_digest": IMAGE_DIGEST,
+            "build_image": BUILD_IMAGE,
+            "builder_hostname": "builder-1",
+            "signature": {
+                "key_id": "0102030405060708",
+                "public_key": hex::encode([1; 32]),
+                "signature": hex::encode([2; 64]),
+            },
         });
     }
 
@@ -4,20 +4,27 @@ use aide::{transform::TransformOperation, OperationIo};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
 use db::{
-    build_session, diagnostic, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
-    QuerySelect, SelectExt, TransactionErrorExt, TransactionTrait,
+    build_session, diagnostic, sea_orm, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    FromQueryResult, QueryFilter, QuerySelect, TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
-use futures_util::TryStreamExt;
 use schemars::JsonSchema;
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::schema::example_error;
+use crate::{
+    auth::AuthenticatedUserId,
+    schema::{
+        example_diagnostic_end, example_diagnostic_end_col, example_diagnostic_end_line,
+        example_diagnostic_level, example_diagnostic_message, example_diagnostic_start,
+        example_diagnostic_start_col, example_diagnostic_start_line, example_error,
+    },
+    visibility,
+};
 
 /// Errors that may occur during the diagnostics request handling.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -32,8 +39,29 @@ pub(super) enum BuildSessionDiagnosticError {
     BuildSessionNotFound,
 }
 
+/// Source code analysis status.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum DiagnosticsStatus {
+    /// Source code analysis is not finished yet.
+    Pending,
+
+    /// Source code analysis is finished and all diagnostics are available.
+    Completed,
+}
+
 /// JSON response body.
 #[derive(Serialize, JsonSchema)]
+pub(super) struct BuildSessionDiagnostics {
+    /// Source code analysis status.
+    status: DiagnosticsStatus,
+
+    /// Found diagnostics, which are always empty while the analysis is pending.
+    diagnostics: Vec<BuildSessionDiagnosticResponse>,
+}
+
+/// A single diagnostic.
+#[derive(Serialize, FromQueryResult, JsonSchema)]
 pub(super) struct BuildSessionDiagnosticResponse {
     /// Diagnostic severity level.
     #[schemars(example = "crate::schema::example_diagnostic_level")]
@@ -47,6 +75,22 @@ pub(super) struct BuildSessionDiagnosticResponse {
     #[schemars(example = "crate::schema::example_diagnostic_end")]
     end: i64,
 
+    /// Line number of the diagnostic start, starting from 1.
+    #[schemars(example = "crate::schema::example_diagnostic_start_line")]
+    start_line: Option<i64>,
+
+    /// Column number of the diagnostic start in characters, starting from 1.
+    #[schemars(example = "crate::schema::example_diagnostic_start_col")]
+    start_col: Option<i64>,
+
+    /// Line number of the diagnostic end, starting from 1.
+    #[schemars(example = "crate::schema::example_diagnostic_end_line")]
+    end_line: Option<i64>,
+
+    /// Column number of the diagnostic end in characters, starting from 1.
+    #[schemars(example = "crate::schema::example_diagnostic_end_col")]
+    end_col: Option<i64>,
+
     /// Diagnostic message.
     #[schemars(example = "crate::schema::example_diagnostic_message")]
     message: String,
@@ -55,15 +99,39 @@ pub(super) struct BuildSessionDiagnosticResponse {
 /// Generate OAPI documentation for the [`diagnostics`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get diagnostics related to the provided build session.")
-        .description(r#""#)
-        .response_with::<200, Json<Vec<BuildSessionDiagnosticResponse>>, _>(|op| {
+        .description(
+            r#"Source code is analyzed in the background after the build session is finished.
+
+Until then, `pending` status is returned without any diagnostics.
+
+Diagnostic positions are provided both as byte offsets and as line and column numbers.
+Columns are counted in Unicode characters, and both LF and CRLF line endings are supported.
+Line and column numbers are `null` for diagnostics found before they were introduced."#,
+        )
+        .response_with::<200, Json<BuildSessionDiagnostics>, _>(|op| {
             op.description("JSON diagnostics response.")
+                .example(BuildSessionDiagnostics {
+                    status: DiagnosticsStatus::Completed,
+                    diagnostics: vec![BuildSessionDiagnosticResponse {
+                        level: example_diagnostic_level(),
+                        start: example_diagnostic_start(),
+                        end: example_diagnostic_end(),
+                        start_line: example_diagnostic_start_line(),
+                        start_col: example_diagnostic_start_col(),
+                        end_line: example_diagnostic_end_line(),
+                        end_col: example_diagnostic_end_col(),
+                        message: example_diagnostic_message(),
+                    }],
+                })
         })
         .response_with::<404, Json<Value>, _>(|op| {
-            op.description("No build sessions with the provided identifier were found.")
-                .example(example_error(
-                    BuildSessionDiagnosticError::BuildSessionNotFound,
-                ))
+            op.description(
+                "No build sessions with the provided identifier were found, \
+                or their source code is private.",
+            )
+            .example(example_error(
+                BuildSessionDiagnosticError::BuildSessionNotFound,
+            ))
         })
 }
 
@@ -72,44 +140,59 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
 /// This route is used in the CLI to get all diagnostics for a file.
 pub(super) async fn diagnostics(
     Path(id): Path<i64>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
     State(db): State<Arc<DatabaseConnection>>,
-) -> Result<Json<Vec<BuildSessionDiagnosticResponse>>, BuildSessionDiagnosticError> {
+) -> Result<Json<BuildSessionDiagnostics>, BuildSessionDiagnosticError> {
+    let current_user = current_user.map(|Extension(user)| user);
+
     db.transaction(|txn| {
         Box::pin(async move {
-            let build_session_exists = build_session::Entity::find()
-                .select_only()
-                .filter(build_session::Column::Id.eq(id))
-                .exists(txn)
-                .await?;
-
-            if !build_session_exists {
+            let (source_code_id, status, diagnostics_pending) =
+                build_session::Entity::find_by_id(id)
+                    .select_only()
+                    .columns([
+                        build_session::Column::SourceCodeId,
+                        build_session::Column::Status,
+                        build_session::Column::DiagnosticsPending,
+                    ])
+                    .into_tuple::<(i64, build_session::Status, bool)>()
+                    .one(txn)
+                    .await?
+                    .ok_or(BuildSessionDiagnosticError::BuildSessionNotFound)?;
+
+            if !visibility::source_code_visible(txn, source_code_id, current_user).await? {
                 return Err(BuildSessionDiagnosticError::BuildSessionNotFound);
             }
 
-            diagnostic::Entity::find()
+            // Unfinished build sessions are not analyzed yet.
+            if status == build_session::Status::New || diagnostics_pending {
+                return Ok(Json(BuildSessionDiagnostics {
+                    status: DiagnosticsStatus::Pending,
+                    diagnostics: Vec::new(),
+                }));
+            }
+
+            let diagnostics = diagnostic::Entity::find()
                 .select_only()
                 .columns([
                     diagnostic::Column::Level,
                     diagnostic::Column::Start,
                     diagnostic::Column::End,
+                    diagnostic::Column::StartLine,
+                    diagnostic::Column::StartCol,
+                    diagnostic::Column::EndLine,
+                    diagnostic::Column::EndCol,
                     diagnostic::Column::Message,
                 ])
                 .filter(diagnostic::Column::BuildSessionId.eq(id))
-                .into_tuple::<(diagnostic::Level, i64, i64, String)>()
-                .stream(txn)
-                .await?
-                .err_into()
-                .and_then(|(level, start, end, message)| async move {
-                    Ok(BuildSessionDiagnosticResponse {
-                        level,
-                        start,
-                        end,
-                        message,
-                    })
-                })
-                .try_collect()
-                .await
-                .map(Json)
+                .into_model::<BuildSessionDiagnosticResponse>()
+                .all(txn)
+                .await?;
+
+            Ok(Json(BuildSessionDiagnostics {
+                status: DiagnosticsStatus::Completed,
+                diagnostics,
+            }))
         })
     })
     .await
@@ -120,9 +203,9 @@ pub(super) async fn diagnostics(
 mod tests {
     use std::sync::Arc;
 
-    use crate::testing::{create_database, ResponseBodyExt};
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
 
-    use assert_json::assert_json;
+    use assert_json::{assert_json, validators};
     use axum::{body::Body, http::Request};
     use common::config::Config;
     use db::{
@@ -191,6 +274,10 @@ mod tests {
             level: ActiveValue::Set(diagnostic::Level::Error),
             start: ActiveValue::Set(0),
             end: ActiveValue::Set(1),
+            start_line: ActiveValue::Set(Some(1)),
+            start_col: ActiveValue::Set(Some(1)),
+            end_line: ActiveValue::Set(Some(1)),
+            end_col: ActiveValue::Set(Some(2)),
             message: ActiveValue::Set(String::from("test")),
             ..Default::default()
         })
@@ -229,22 +316,89 @@ mod tests {
             .await
             .unwrap();
 
-        assert_json!(response.json().await,
-            [
+        assert_json!(response.json().await, {
+            "status": "completed",
+            "diagnostics": [
                 {
                     "level": "error",
                     "end": 1,
                     "start": 0,
+                    "start_line": 1,
+                    "start_col": 1,
+                    "end_line": 1,
+                    "end_col": 2,
                     "message": "test"
                 },
                 {
                     "level": "warning",
                     "end": 3,
                     "start": 2,
+                    "start_line": validators::null(),
+                    "start_col": validators::null(),
+                    "end_line": validators::null(),
+                    "end_col": validators::null(),
                     "message": "test2"
                 }
             ]
-        );
+        });
+    }
+
+    #[tokio::test]
+    async fn pending() {
+        let db = Arc::new(create_database().await);
+
+        create_test_env(&db).await;
+
+        for (status, diagnostics_pending) in [
+            (build_session::Status::New, false),
+            (build_session::Status::Completed, true),
+        ] {
+            build_session::Entity::update_many()
+                .col_expr(build_session::Column::Status, status.into())
+                .col_expr(
+                    build_session::Column::DiagnosticsPending,
+                    diagnostics_pending.into(),
+                )
+                .exec(&*db)
+                .await
+                .expect("unable to update build session");
+
+            let response = crate::app_router(db.clone(), Arc::new(Config::for_tests()))
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri("/buildSessions/diagnostics/1")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_json!(response.json().await, {
+                "status": "pending",
+                "diagnostics": [],
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = create_database().await;
+
+        create_test_env(&db).await;
+        make_source_code_private(&db).await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/buildSessions/diagnostics/1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+
+        assert_eq!(404, response.unwrap().status());
     }
 
     #[tokio::test]
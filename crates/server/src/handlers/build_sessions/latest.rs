@@ -4,7 +4,7 @@ use aide::{transform::TransformOperation, OperationIo};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
 use db::{
@@ -16,7 +16,12 @@ use schemars::JsonSchema;
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::{hex_hash::HexHash, schema::example_error};
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    schema::{example_cargo_contract_version, example_error, example_folder, example_hex_hash},
+    visibility,
+};
 
 /// Code hash details.
 #[derive(Serialize, JsonSchema)]
@@ -24,6 +29,14 @@ pub struct BuildSessionLatestData {
     /// Code hash corresponding to the provided source code archive hash.
     #[schemars(example = "crate::schema::example_hex_hash")]
     pub code_hash: HexHash,
+
+    /// Version of `cargo-contract` used to build the contract.
+    #[schemars(example = "crate::schema::example_cargo_contract_version")]
+    pub cargo_contract_version: String,
+
+    /// Project directory, relative to the source code archive root.
+    #[schemars(example = "crate::schema::example_folder")]
+    pub project_directory: Option<String>,
 }
 
 /// Errors that may occur during the request handling.
@@ -45,7 +58,18 @@ pub(super) enum BuildSessionLatestError {
 /// Generate OAPI documentation for the [`latest`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get the latest build session code hash.")
-        .response::<200, Json<BuildSessionLatestData>>()
+        .description(
+            r#"Private source code archives are searched only if the current user owns them,
+or if a contract built from them was discovered on-chain."#,
+        )
+        .response_with::<200, Json<BuildSessionLatestData>, _>(|op| {
+            op.description("Latest build session code hash response.")
+                .example(BuildSessionLatestData {
+                    code_hash: example_hex_hash(),
+                    cargo_contract_version: example_cargo_contract_version(),
+                    project_directory: example_folder(),
+                })
+        })
         .response_with::<404, Json<Value>, _>(|op| {
             op.description("No related build sessions were found.")
                 .example(example_error(
@@ -57,35 +81,49 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
 /// Handler for getting the latest code hash that corresponds to the provided archive hash.
 ///
 /// This handler searches only for successful build sessions, as code hashes are generated only for those.
+///
+/// Build configuration is returned alongside with the code hash, allowing clients to ignore
+/// build sessions that were started with a different `cargo-contract` version or project directory.
 pub(super) async fn latest(
     State(db): State<Arc<DatabaseConnection>>,
     Path(archive_hash): Path<HexHash>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
 ) -> Result<Json<BuildSessionLatestData>, BuildSessionLatestError> {
+    let current_user = current_user.map(|Extension(user)| user);
+
     db.transaction(|txn| {
         Box::pin(async move {
             let source_code_id = source_code::Entity::find()
                 .select_only()
                 .column(source_code::Column::Id)
                 .filter(source_code::Column::ArchiveHash.eq(&archive_hash.0[..]))
+                .filter(visibility::source_code_visible_condition(current_user))
                 .into_tuple::<i64>()
                 .one(txn)
                 .await?
                 .ok_or(BuildSessionLatestError::NoRelatedBuildSessions)?;
 
-            let code_hash = build_session::Entity::find()
-                .select_only()
-                .column(build_session::Column::CodeHash)
-                .filter(build_session::Column::CodeHash.is_not_null())
-                .filter(build_session::Column::Status.eq(build_session::Status::Completed))
-                .filter(build_session::Column::SourceCodeId.eq(source_code_id))
-                .order_by_desc(build_session::Column::CreatedAt)
-                .into_tuple::<Vec<u8>>()
-                .one(txn)
-                .await?
-                .ok_or(BuildSessionLatestError::NoRelatedBuildSessions)?;
+            let (code_hash, cargo_contract_version, project_directory) =
+                build_session::Entity::find()
+                    .select_only()
+                    .columns([
+                        build_session::Column::CodeHash,
+                        build_session::Column::CargoContractVersion,
+                        build_session::Column::ProjectDirectory,
+                    ])
+                    .filter(build_session::Column::CodeHash.is_not_null())
+                    .filter(build_session::Column::Status.eq(build_session::Status::Completed))
+                    .filter(build_session::Column::SourceCodeId.eq(source_code_id))
+                    .order_by_desc(build_session::Column::CreatedAt)
+                    .into_tuple::<(Vec<u8>, String, Option<String>)>()
+                    .one(txn)
+                    .await?
+                    .ok_or(BuildSessionLatestError::NoRelatedBuildSessions)?;
 
             Ok(Json(BuildSessionLatestData {
                 code_hash: code_hash.as_slice().try_into()?,
+                cargo_contract_version,
+                project_directory,
             }))
         })
     })
@@ -97,7 +135,7 @@ pub(super) async fn latest(
 mod tests {
     use std::sync::Arc;
 
-    use crate::testing::{create_database, ResponseBodyExt};
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
 
     use assert_json::assert_json;
     use axum::{
@@ -139,6 +177,7 @@ mod tests {
             status: ActiveValue::Set(build_session::Status::Completed),
             cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
             code_hash: ActiveValue::Set(Some(vec![0; 32])),
+            project_directory: ActiveValue::Set(Some(String::from("contracts/flipper"))),
             ..Default::default()
         })
         .exec_without_returning(db)
@@ -165,9 +204,32 @@ mod tests {
 
         assert_json!(response.json().await, {
             "code_hash": hex::encode([0; 32]),
+            "cargo_contract_version": "3.0.0",
+            "project_directory": "contracts/flipper",
         });
     }
 
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = create_database().await;
+
+        create_test_env(&db).await;
+        make_source_code_private(&db).await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/latest/{}", hex::encode([0; 32])))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND)
+    }
+
     #[tokio::test]
     async fn source_code_without_build_sessions() {
         let db = create_database().await;
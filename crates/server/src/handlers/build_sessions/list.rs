@@ -1,21 +1,26 @@
 use std::{array::TryFromSliceError, sync::Arc};
 
 use aide::{transform::TransformOperation, OperationIo};
-use axum::{
-    extract::{Query, State},
-    Extension, Json,
-};
+use axum::{extract::State, Extension, Json};
 use axum_derive_error::ErrorResponse;
 use db::{
-    build_session, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PrimitiveDateTime,
-    QueryFilter, QueryOrder, QuerySelect,
+    build_session, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, FromQueryResult,
+    PrimitiveDateTime, QueryFilter, QueryOrder, QuerySelect,
 };
 use derive_more::{Display, Error, From};
 use futures_util::TryStreamExt;
 use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::{auth::AuthenticatedUserId, hex_hash::HexHash, pagination::Pagination};
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    pagination::Pagination,
+    schema::{
+        example_build_session_status, example_cargo_contract_version, example_database_identifier,
+        example_folder, example_hex_hash, example_timestamp,
+    },
+};
 
 /// Information about a single build session.
 #[derive(Serialize, JsonSchema)]
@@ -36,11 +41,44 @@ pub struct BuildSessionData {
     #[schemars(example = "crate::schema::example_hex_hash")]
     pub code_hash: Option<HexHash>,
 
+    /// Version of `cargo-contract` used to build the contract.
+    #[schemars(example = "crate::schema::example_cargo_contract_version")]
+    pub cargo_contract_version: String,
+
+    /// Project directory, relative to the source code archive root.
+    #[schemars(example = "crate::schema::example_folder")]
+    pub project_directory: Option<String>,
+
     /// Build session creation time.
     #[schemars(example = "crate::schema::example_timestamp")]
     pub timestamp: i64,
 }
 
+/// Build session columns selected by the [`list`] handler.
+#[derive(FromQueryResult)]
+struct BuildSessionRow {
+    /// Build session identifier.
+    id: i64,
+
+    /// Related source code identifier.
+    source_code_id: i64,
+
+    /// Build session status.
+    status: build_session::Status,
+
+    /// Raw code hash.
+    code_hash: Option<Vec<u8>>,
+
+    /// Version of `cargo-contract` used to build the contract.
+    cargo_contract_version: String,
+
+    /// Project directory, relative to the source code archive root.
+    project_directory: Option<String>,
+
+    /// Build session creation time.
+    created_at: PrimitiveDateTime,
+}
+
 /// Errors that may occur during the list request.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
 #[aide(output)]
@@ -57,6 +95,15 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get list of build sessions of the current user.")
         .response_with::<200, Json<Vec<BuildSessionData>>, _>(|op| {
             op.description("Build session list response.")
+                .example(vec![BuildSessionData {
+                    id: example_database_identifier(),
+                    source_code_id: example_database_identifier(),
+                    status: example_build_session_status(),
+                    code_hash: Some(example_hex_hash()),
+                    cargo_contract_version: example_cargo_contract_version(),
+                    project_directory: example_folder(),
+                    timestamp: example_timestamp(),
+                }])
         })
 }
 
@@ -64,7 +111,7 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
 pub(super) async fn list(
     Extension(current_user): Extension<AuthenticatedUserId>,
     State(db): State<Arc<DatabaseConnection>>,
-    Query(pagination): Query<Pagination>,
+    pagination: Pagination,
 ) -> Result<Json<Vec<BuildSessionData>>, BuildSessionListError> {
     build_session::Entity::find()
         .select_only()
@@ -73,33 +120,33 @@ pub(super) async fn list(
             build_session::Column::SourceCodeId,
             build_session::Column::Status,
             build_session::Column::CodeHash,
+            build_session::Column::CargoContractVersion,
+            build_session::Column::ProjectDirectory,
             build_session::Column::CreatedAt,
         ])
         .filter(build_session::Column::UserId.eq(current_user.id()))
         .limit(pagination.limit())
         .offset(pagination.offset())
         .order_by_desc(build_session::Column::Id)
-        .into_tuple::<(
-            i64,
-            i64,
-            build_session::Status,
-            Option<Vec<u8>>,
-            PrimitiveDateTime,
-        )>()
+        .into_model::<BuildSessionRow>()
         .stream(&*db)
         .await?
         .err_into()
-        .and_then(
-            |(id, source_code_id, status, code_hash, timestamp)| async move {
-                Ok(BuildSessionData {
-                    id,
-                    source_code_id,
-                    status,
-                    code_hash: code_hash.as_deref().map(HexHash::try_from).transpose()?,
-                    timestamp: timestamp.assume_utc().unix_timestamp(),
-                })
-            },
-        )
+        .and_then(|row| async move {
+            Ok(BuildSessionData {
+                id: row.id,
+                source_code_id: row.source_code_id,
+                status: row.status,
+                code_hash: row
+                    .code_hash
+                    .as_deref()
+                    .map(HexHash::try_from)
+                    .transpose()?,
+                cargo_contract_version: row.cargo_contract_version,
+                project_directory: row.project_directory,
+                timestamp: row.created_at.assume_utc().unix_timestamp(),
+            })
+        })
         .try_collect()
         .await
         .map(Json)
@@ -160,6 +207,7 @@ mod tests {
             status: ActiveValue::Set(build_session::Status::Completed),
             cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
             code_hash: ActiveValue::Set(Some(vec![0; 32])),
+            project_directory: ActiveValue::Set(Some(String::from("contracts/flipper"))),
             ..Default::default()
         })
         .exec_with_returning(db)
@@ -209,6 +257,8 @@ mod tests {
                 "source_code_id": source_code_id,
                 "status": "new",
                 "code_hash": validators::null(),
+                "cargo_contract_version": "3.0.0",
+                "project_directory": validators::null(),
                 "timestamp": second_unix,
             },
             {
@@ -216,8 +266,54 @@ mod tests {
                 "source_code_id": source_code_id,
                 "status": "completed",
                 "code_hash": hex::encode([0; 32]),
+                "cargo_contract_version": "3.0.0",
+                "project_directory": "contracts/flipper",
                 "timestamp": first_unix
             }
         ]);
     }
+
+    #[tokio::test]
+    async fn page_size_bounds() {
+        let db = Arc::new(create_database().await);
+
+        let (token, ..) = create_test_env(&db).await;
+
+        for (limit, status, count) in [
+            ("0", 422, None),
+            ("1", 200, Some(1)),
+            ("100", 200, Some(2)),
+            ("101", 422, None),
+        ] {
+            let response = crate::app_router(db.clone(), Arc::new(Config::for_tests()))
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri(format!("/buildSessions?limit={limit}"))
+                        .header("Authorization", format!("Bearer {token}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), status, "{limit}");
+
+            let body = response.json().await;
+
+            match count {
+                Some(count) => assert_eq!(body.as_array().unwrap().len(), count),
+                None => assert_json!(body, {
+                    "code": 422,
+                    "error": validators::string(|error| {
+                        if error.contains("limit") {
+                            Ok(())
+                        } else {
+                            Err(String::from("missing field name"))
+                        }
+                    }),
+                }),
+            }
+        }
+    }
 }
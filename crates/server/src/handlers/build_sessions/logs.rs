@@ -1,10 +1,11 @@
 use std::sync::Arc;
 
 use aide::{transform::TransformOperation, OperationIo};
+use api_types::build_sessions::{BuildSessionLogsResponse, LogEntry};
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
 use db::{
@@ -14,10 +15,19 @@ use db::{
 use derive_more::{Display, Error, From};
 use futures_util::TryStreamExt;
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use serde_json::Value;
 
-use crate::{hex_hash::HexHash, schema::example_error};
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    pagination::CursorPagination,
+    schema::{
+        example_database_identifier, example_error, example_log_entry, example_log_position,
+        example_log_repeat_count,
+    },
+    visibility,
+};
 
 /// Errors that may occur during the log list request.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -50,25 +60,6 @@ pub(super) struct BuildSessionLogsQuery {
     position: Option<i64>,
 }
 
-/// A single log entry.
-#[derive(Serialize, JsonSchema)]
-pub(super) struct LogEntry {
-    /// Log entry identifier.
-    #[schemars(example = "crate::schema::example_database_identifier")]
-    id: i64,
-
-    /// Log entry text value.
-    #[schemars(example = "crate::schema::example_log_entry")]
-    text: String,
-}
-
-/// Log entries response.
-#[derive(Serialize, JsonSchema)]
-pub(super) struct BuildSessionLogsResponse {
-    /// Log entries.
-    logs: Vec<LogEntry>,
-}
-
 /// Generate OAPI documentation for the [`logs`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get build session logs.")
@@ -77,16 +68,37 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
 to a single line of log output, due to log collector processes batching log outputs
 from build session containers. However, you should be able to correctly reproduce
 the exact build output by printing log entries without any additional newlines.
+
+By default, all log entries following the provided `position` are returned.
+Provide the `after` query parameter (use `0` for the first page) to receive log entries
+in pages of limited size instead, using the returned `next_cursor` value to get the next page.
+
+Identical consecutive log entries are collapsed into a single log entry, with the `count` field
+containing the amount of repetitions. Be aware, that the `count` value of the last log entry
+may still increase after it was returned.
         "#,
         )
-        .response::<200, Json<BuildSessionLogsResponse>>()
+        .response_with::<200, Json<BuildSessionLogsResponse>, _>(|op| {
+            op.description("Build session logs response.")
+                .example(BuildSessionLogsResponse {
+                    logs: vec![LogEntry {
+                        id: example_database_identifier(),
+                        text: example_log_entry(),
+                        count: example_log_repeat_count(),
+                    }],
+                    next_cursor: example_log_position(),
+                })
+        })
         .response_with::<400, Json<Value>, _>(|op| {
             op.description("Incorrect identifier format was provided.")
                 .example(example_error(BuildSessionLogsError::UnknownIdFormat))
         })
         .response_with::<404, Json<Value>, _>(|op| {
-            op.description("No build sessions with the provided identifier were found.")
-                .example(example_error(BuildSessionLogsError::BuildSessionNotFound))
+            op.description(
+                "No build sessions with the provided identifier were found, \
+                or their source code is private.",
+            )
+            .example(example_error(BuildSessionLogsError::BuildSessionNotFound))
         })
 }
 
@@ -96,48 +108,69 @@ the exact build output by printing log entries without any additional newlines.
 /// and CLI usage.
 pub(super) async fn logs(
     Path(id): Path<String>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
     State(db): State<Arc<DatabaseConnection>>,
     Query(query): Query<BuildSessionLogsQuery>,
+    pagination: CursorPagination,
 ) -> Result<Json<BuildSessionLogsResponse>, BuildSessionLogsError> {
+    let current_user = current_user.map(|Extension(user)| user);
+
     db.transaction(|txn| {
         Box::pin(async move {
+            let id = match serde_plain::from_str::<HexHash>(&id) {
+                Ok(val) => build_session::Entity::find()
+                    .select_only()
+                    .column(build_session::Column::Id)
+                    .filter(build_session::Column::CodeHash.eq(&val.0[..]))
+                    .order_by_desc(build_session::Column::Id)
+                    .into_tuple::<i64>()
+                    .one(txn)
+                    .await?
+                    .ok_or(BuildSessionLogsError::BuildSessionNotFound)?,
+                Err(_) => id
+                    .parse::<i64>()
+                    .map_err(|_| BuildSessionLogsError::UnknownIdFormat)?,
+            };
+
+            let source_code_id = build_session::Entity::find_by_id(id)
+                .select_only()
+                .column(build_session::Column::SourceCodeId)
+                .into_tuple::<i64>()
+                .one(txn)
+                .await?;
+
+            // Unknown numeric identifiers have no logs, and are not treated as an error.
+            if let Some(source_code_id) = source_code_id {
+                if !visibility::source_code_visible(txn, source_code_id, current_user).await? {
+                    return Err(BuildSessionLogsError::BuildSessionNotFound);
+                }
+            }
+
             let logs = log::Entity::find()
                 .select_only()
-                .columns([log::Column::Id, log::Column::Text])
-                .filter(match serde_plain::from_str::<HexHash>(&id) {
-                    Ok(val) => {
-                        let id = build_session::Entity::find()
-                            .select_only()
-                            .column(build_session::Column::Id)
-                            .filter(build_session::Column::CodeHash.eq(&val.0[..]))
-                            .order_by_desc(build_session::Column::Id)
-                            .into_tuple::<i64>()
-                            .one(txn)
-                            .await?
-                            .ok_or(BuildSessionLogsError::BuildSessionNotFound)?;
-
-                        log::Column::BuildSessionId.eq(id)
-                    }
-                    Err(_) => {
-                        let id = id
-                            .parse::<i64>()
-                            .map_err(|_| BuildSessionLogsError::UnknownIdFormat)?;
-
-                        log::Column::BuildSessionId.eq(id)
-                    }
-                })
+                .columns([log::Column::Id, log::Column::Text, log::Column::RepeatCount])
+                .filter(log::Column::BuildSessionId.eq(id))
                 .apply_if(query.position, |query, position| {
                     query.filter(log::Column::Id.gt(position))
                 })
+                .apply_if(pagination.after(), |query, after| {
+                    query
+                        .filter(log::Column::Id.gt(after))
+                        .limit(pagination.limit())
+                })
                 .order_by_asc(log::Column::Id)
-                .into_tuple::<(i64, String)>()
+                .into_tuple::<(i64, String, i64)>()
                 .stream(txn)
                 .await?
-                .map_ok(|(id, text)| LogEntry { id, text })
-                .try_collect()
+                .map_ok(|(id, text, count)| LogEntry { id, text, count })
+                .try_collect::<Vec<_>>()
                 .await?;
 
-            Ok(Json(BuildSessionLogsResponse { logs }))
+            let next_cursor = pagination
+                .after()
+                .and_then(|_| pagination.next_cursor(&logs, |entry| entry.id));
+
+            Ok(Json(BuildSessionLogsResponse { logs, next_cursor }))
         })
     })
     .await
@@ -148,10 +181,13 @@ pub(super) async fn logs(
 mod tests {
     use std::sync::Arc;
 
-    use crate::testing::{create_database, ResponseBodyExt};
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
 
     use assert_json::assert_json;
-    use axum::{body::Body, http::Request};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
     use common::config::Config;
     use db::{build_session, log, source_code, user, ActiveValue, DatabaseConnection, EntityTrait};
     use tower::ServiceExt;
@@ -194,6 +230,7 @@ mod tests {
             log::ActiveModel {
                 build_session_id: ActiveValue::Set(build_session_id),
                 text: ActiveValue::Set(String::from("Second log\n")),
+                repeat_count: ActiveValue::Set(4),
                 ..Default::default()
             },
             log::ActiveModel {
@@ -230,15 +267,18 @@ mod tests {
             "logs": [
                 {
                     "id": 1,
-                    "text": "First log\n"
+                    "text": "First log\n",
+                    "count": 1
                 },
                 {
                     "id": 2,
-                    "text": "Second log\n"
+                    "text": "Second log\n",
+                    "count": 4
                 },
                 {
                     "id": 3,
-                    "text": "Third log"
+                    "text": "Third log",
+                    "count": 1
                 }
             ]
         });
@@ -265,15 +305,18 @@ mod tests {
             "logs": [
                 {
                     "id": 1,
-                    "text": "First log\n"
+                    "text": "First log\n",
+                    "count": 1
                 },
                 {
                     "id": 2,
-                    "text": "Second log\n"
+                    "text": "Second log\n",
+                    "count": 4
                 },
                 {
                     "id": 3,
-                    "text": "Third log"
+                    "text": "Third log",
+                    "count": 1
                 }
             ]
         });
@@ -303,12 +346,83 @@ mod tests {
             "logs": [
                 {
                     "id": 3,
-                    "text": "Third log"
+                    "text": "Third log",
+                    "count": 1
                 }
             ]
         });
     }
 
+    #[tokio::test]
+    async fn cursor_pagination() {
+        let db = Arc::new(create_database().await);
+
+        let build_session_id = create_test_env(&db).await;
+
+        let request = |query: String| {
+            crate::app_router(db.clone(), Arc::new(Config::for_tests())).oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/logs/{build_session_id}?{query}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        let offset_logs = request(String::from("position=0"))
+            .await
+            .unwrap()
+            .json()
+            .await;
+
+        let mut cursor_logs = Vec::new();
+        let mut cursor = 0;
+
+        loop {
+            let page = request(format!("after={cursor}&limit=2"))
+                .await
+                .unwrap()
+                .json()
+                .await;
+
+            let logs = page["logs"].as_array().unwrap();
+            assert!(logs.len() <= 2);
+            cursor_logs.extend(logs.iter().cloned());
+
+            match page.get("next_cursor") {
+                Some(next_cursor) => cursor = next_cursor.as_i64().unwrap(),
+                None => break,
+            }
+        }
+
+        assert_eq!(offset_logs.get("next_cursor"), None);
+        assert_eq!(offset_logs["logs"].as_array().unwrap(), &cursor_logs);
+        assert_eq!(cursor_logs.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = Arc::new(create_database().await);
+
+        let build_session_id = create_test_env(&db).await;
+        make_source_code_private(&db).await;
+
+        for id in [build_session_id.to_string(), hex::encode([0; 32])] {
+            let response = crate::app_router(db.clone(), Arc::new(Config::for_tests()))
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri(format!("/buildSessions/logs/{id}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+    }
+
     #[tokio::test]
     async fn unknown() {
         let db = create_database().await;
@@ -1,20 +1,26 @@
 use std::sync::Arc;
 
-use aide::{transform::TransformOperation, OperationIo};
+use aide::{
+    gen::GenContext,
+    openapi::{Operation, Response as OapiResponse},
+    transform::TransformOperation,
+    OperationIo, OperationOutput,
+};
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
-    Json,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
 use db::{
-    build_session, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
-    QuerySelect,
+    build_session, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect,
 };
 use derive_more::{Display, Error, From};
 use serde_json::Value;
 
-use crate::{hex_hash::HexHash, schema::example_error};
+use crate::{auth::AuthenticatedUserId, hex_hash::HexHash, schema::example_error, visibility};
 
 /// Errors that may occur during the contract metadata request.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -31,6 +37,38 @@ pub(super) enum BuildSessionMetadataError {
     #[status(StatusCode::NOT_FOUND)]
     #[display(fmt = "build session not found")]
     BuildSessionNotFound,
+
+    /// Build session was completed before metadata was stored alongside with build artifacts.
+    #[status(StatusCode::GONE)]
+    #[display(fmt = "metadata unavailable")]
+    MetadataUnavailable,
+}
+
+/// JSON metadata response, which contains the stored metadata bytes verbatim.
+///
+/// Metadata is not re-serialized, so that clients can verify it
+/// using the metadata hash returned by the build session status route.
+pub(super) struct RawMetadata(Vec<u8>);
+
+impl IntoResponse for RawMetadata {
+    fn into_response(self) -> Response {
+        ([(header::CONTENT_TYPE, "application/json")], self.0).into_response()
+    }
+}
+
+impl OperationOutput for RawMetadata {
+    type Inner = Value;
+
+    fn operation_response(ctx: &mut GenContext, operation: &mut Operation) -> Option<OapiResponse> {
+        <Json<Value> as OperationOutput>::operation_response(ctx, operation)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, OapiResponse)> {
+        <Json<Value> as OperationOutput>::inferred_responses(ctx, operation)
+    }
 }
 
 /// Generate OAPI documentation for the [`metadata`] handler.
@@ -41,18 +79,42 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
                 .example(Value::Object(Default::default()))
         })
         .response_with::<404, Json<Value>, _>(|op| {
-            op.description("No build sessions with the provided code hash were found.")
-                .example(example_error(
-                    BuildSessionMetadataError::BuildSessionNotFound,
-                ))
+            op.description(
+                "No build sessions with the provided code hash were found, \
+                or their source code is private.",
+            )
+            .example(example_error(
+                BuildSessionMetadataError::BuildSessionNotFound,
+            ))
+        })
+        .response_with::<410, Json<Value>, _>(|op| {
+            op.description(
+                "Build session with the provided code hash was completed without storing \
+                its metadata. Re-run the build to make metadata available.",
+            )
+            .example(example_error(
+                BuildSessionMetadataError::MetadataUnavailable,
+            ))
         })
 }
 
 /// Contract metadata request handler.
+///
+/// Build sessions completed before metadata was persisted have a code hash,
+/// but no metadata, which is reported separately from unknown code hashes.
+///
+/// Stored metadata is validated, but returned as is.
 pub(super) async fn metadata(
     Path(code_hash): Path<HexHash>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
     State(db): State<Arc<DatabaseConnection>>,
-) -> Result<Json<serde_json::Value>, BuildSessionMetadataError> {
+) -> Result<RawMetadata, BuildSessionMetadataError> {
+    let current_user = current_user.map(|Extension(user)| user);
+
+    if !visibility::code_hash_visible(&*db, &code_hash.0, current_user).await? {
+        return Err(BuildSessionMetadataError::BuildSessionNotFound);
+    }
+
     let model = build_session::Entity::find()
         .select_only()
         .column(build_session::Column::Metadata)
@@ -61,13 +123,26 @@ pub(super) async fn metadata(
         .order_by_desc(build_session::Column::CreatedAt)
         .into_tuple::<Vec<u8>>()
         .one(&*db)
-        .await?
-        .ok_or(BuildSessionMetadataError::BuildSessionNotFound)?;
+        .await?;
+
+    let Some(model) = model else {
+        let completed = build_session::Entity::find()
+            .filter(build_session::Column::CodeHash.eq(&code_hash.0[..]))
+            .filter(build_session::Column::Status.eq(build_session::Status::Completed))
+            .count(&*db)
+            .await?;
 
-    let json =
-        serde_json::from_slice(&model).map_err(|_| BuildSessionMetadataError::InvalidMetadata)?;
+        return Err(if completed > 0 {
+            BuildSessionMetadataError::MetadataUnavailable
+        } else {
+            BuildSessionMetadataError::BuildSessionNotFound
+        });
+    };
 
-    Ok(Json(json))
+    serde_json::from_slice::<Value>(&model)
+        .map_err(|_| BuildSessionMetadataError::InvalidMetadata)?;
+
+    Ok(RawMetadata(model))
 }
 
 #[cfg(test)]
@@ -79,14 +154,14 @@ mod tests {
     use assert_json::assert_json;
     use axum::{
         body::Body,
-        http::{Request, StatusCode},
+        http::{header, Request, StatusCode},
     };
     use common::config::Config;
     use db::{build_session, source_code, user, ActiveValue, DatabaseConnection, EntityTrait};
     use serde_json::json;
     use tower::ServiceExt;
 
-    async fn create_test_env(db: &DatabaseConnection) {
+    async fn create_test_env(db: &DatabaseConnection, metadata: Option<Vec<u8>>) {
         let user = user::Entity::insert(user::ActiveModel::default())
             .exec_with_returning(db)
             .await
@@ -108,12 +183,7 @@ mod tests {
             status: ActiveValue::Set(build_session::Status::Completed),
             cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
             code_hash: ActiveValue::Set(Some(vec![0; 32])),
-            metadata: ActiveValue::Set(Some(
-                serde_json::to_vec(&json! ({
-                    "val": 123
-                }))
-                .unwrap(),
-            )),
+            metadata: ActiveValue::Set(metadata),
             ..Default::default()
         })
         .exec_without_returning(db)
@@ -125,7 +195,11 @@ mod tests {
     async fn successful() {
         let db = create_database().await;
 
-        create_test_env(&db).await;
+        create_test_env(
+            &db,
+            Some(serde_json::to_vec(&json!({ "val": 123 })).unwrap()),
+        )
+        .await;
 
         let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
             .oneshot(
@@ -143,6 +217,29 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn verbatim() {
+        let db = create_database().await;
+
+        let metadata = b"{\n  \"val\": 123,\n  \"abc\": []\n}".to_vec();
+
+        create_test_env(&db, Some(metadata.clone())).await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/metadata/{}", hex::encode([0; 32])))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.bytes().await, metadata);
+    }
+
     #[tokio::test]
     async fn unknown() {
         let db = create_database().await;
@@ -160,4 +257,28 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn unavailable() {
+        let db = create_database().await;
+
+        create_test_env(&db, None).await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/metadata/{}", hex::encode([0; 32])))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::GONE);
+        assert_json!(response.json().await, {
+            "code": 410,
+            "error": "metadata unavailable",
+        });
+    }
 }
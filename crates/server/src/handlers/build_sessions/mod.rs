@@ -1,3 +1,12 @@
+/// Contract ABI route.
+mod abi;
+
+/// Contract WASM or PolkaVM blob route.
+mod artifact;
+
+/// Build session diagnostics comparison route.
+mod compare;
+
 /// Build session create route.
 mod create;
 
@@ -19,9 +28,21 @@ mod logs;
 /// Contract JSON metadata route.
 mod metadata;
 
+/// Recently verified build sessions route.
+mod recent;
+
 /// Build session status route.
 mod status;
 
+/// Build session status events route.
+mod status_events;
+
+/// Current builder signing key route.
+mod signing_key;
+
+/// Supported cargo-contract versions route.
+mod supported_versions;
+
 /// WASM blob route.
 mod wasm;
 
@@ -40,25 +61,57 @@ pub(crate) fn routes(
     database: Arc<DatabaseConnection>,
     config: Arc<Config>,
 ) -> ApiRouter<Arc<DatabaseConnection>> {
-    let public_routes = ApiRouter::new()
+    // Build session data is visible according to the related source code archive visibility.
+    let visible_routes = ApiRouter::new()
         .api_route(
             "/latest/:archiveHash",
             get_with(latest::latest, latest::docs),
         )
+        .api_route("/recent", get_with(recent::recent, recent::docs))
+        .api_route("/status/:id", get_with(status::status, status::docs))
+        .api_route(
+            "/status/:id/events",
+            get_with(status_events::status_events, status_events::docs),
+        )
+        .api_route("/logs/:id", get_with(logs::logs, logs::docs))
+        .api_route(
+            "/diagnostics/:id",
+            get_with(diagnostics::diagnostics, diagnostics::docs),
+        )
+        .api_route(
+            "/:id/diagnostics/compare/:base_id",
+            get_with(compare::compare, compare::docs),
+        )
         .api_route(
             "/metadata/:codeHash",
             get_with(metadata::metadata, metadata::docs),
         )
+        .api_route("/metadata/:codeHash/abi", get_with(abi::abi, abi::docs))
         .api_route("/wasm/:codeHash", get_with(wasm::wasm, wasm::docs))
+        .api_route(
+            "/artifact/:codeHash",
+            get_with(artifact::artifact, artifact::docs),
+        )
         .api_route(
             "/details/:codeHash",
             get_with(details::details, details::docs),
         )
-        .api_route("/status/:id", get_with(status::status, status::docs))
-        .api_route("/logs/:id", get_with(logs::logs, logs::docs))
+        .route_layer(from_fn_with_state(
+            database.clone(),
+            auth::optional_authentication,
+        ));
+
+    let public_routes = ApiRouter::new()
         .api_route(
-            "/diagnostics/:id",
-            get_with(diagnostics::diagnostics, diagnostics::docs),
+            "/signingKey",
+            get_with(signing_key::signing_key, signing_key::docs),
+        )
+        .api_route(
+            "/supportedVersions",
+            get_with(
+                supported_versions::supported_versions,
+                supported_versions::docs,
+            ),
         );
 
     let private_routes = ApiRouter::new()
@@ -68,12 +121,13 @@ pub(crate) fn routes(
         )
         .route_layer(from_fn_with_state(
             (database, config),
-            auth::require_authentication::<true, true, _>,
+            auth::require_authentication::<true, true, false, _>,
         ))
         .with_path_items(|op| op.security_requirement("Authentication token"));
 
     ApiRouter::new()
         .merge(private_routes)
         .merge(public_routes)
+        .merge(visible_routes)
         .with_path_items(|op| op.tag("Build session management"))
 }
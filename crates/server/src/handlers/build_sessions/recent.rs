@@ -0,0 +1,374 @@
+use std::{array::TryFromSliceError, collections::HashMap, sync::Arc};
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{extract::State, Extension, Json};
+use axum_derive_error::ErrorResponse;
+use common::{
+    config::{default_recent_build_sessions_window, Config},
+    rpc::{sp_core::crypto::AccountId32, ss58_address},
+};
+use db::{
+    build_session, contract, node, source_code, user, ColumnTrait, DatabaseConnection, DbErr,
+    EntityTrait, PrimitiveDateTime, QueryFilter, QueryOrder, QuerySelect, TransactionErrorExt,
+    TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    pagination::Pagination,
+    schema::{
+        example_account, example_cargo_contract_version, example_database_identifier,
+        example_hex_hash, example_node, example_timestamp,
+    },
+    visibility,
+};
+
+/// Information about a single recently verified build session.
+#[derive(Serialize, JsonSchema)]
+pub struct RecentBuildSessionData {
+    /// Build session identifier.
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    pub id: i64,
+
+    /// Code hash produced by the build session.
+    #[schemars(example = "crate::schema::example_hex_hash")]
+    pub code_hash: HexHash,
+
+    /// Version of `cargo-contract` used to build the contract.
+    #[schemars(example = "crate::schema::example_cargo_contract_version")]
+    pub cargo_contract_version: String,
+
+    /// Build session creation time.
+    #[schemars(example = "crate::schema::example_timestamp")]
+    pub timestamp: i64,
+
+    /// Address of a contract deployed with the produced code hash, if any.
+    #[schemars(example = "crate::schema::example_account")]
+    pub address: Option<String>,
+
+    /// Name of the node on which the contract was deployed, if any.
+    #[schemars(example = "crate::schema::example_node")]
+    pub node: Option<String>,
+}
+
+/// Errors that may occur during the recent build sessions request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum RecentBuildSessionsError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Incorrect hash size stored inside of a database
+    IncorrectCodeHash(TryFromSliceError),
+
+    /// Contract address stored inside of a database is invalid.
+    #[display(fmt = "incorrect address size of a contract account")]
+    IncorrectAddressSize,
+}
+
+/// Generate OAPI documentation for the [`recent`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get list of recently verified build sessions.")
+        .description(
+            r#"Only completed build sessions are listed, excluding those that
+belong to users who opted out of the public build session feed, as well as those
+with source code archives that are not visible to the current user.
+
+Pagination is limited to a configured amount of the most recent build sessions."#,
+        )
+        .response_with::<200, Json<Vec<RecentBuildSessionData>>, _>(|op| {
+            op.description("Recent build session list response.")
+                .example(vec![RecentBuildSessionData {
+                    id: example_database_identifier(),
+                    code_hash: example_hex_hash(),
+                    cargo_contract_version: example_cargo_contract_version(),
+                    timestamp: example_timestamp(),
+                    address: Some(example_account().to_string()),
+                    node: Some(example_node()),
+                }])
+        })
+}
+
+/// List recently completed build sessions of all users.
+pub(super) async fn recent(
+    State(db): State<Arc<DatabaseConnection>>,
+    Extension(config): Extension<Arc<Config>>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    pagination: Pagination,
+) -> Result<Json<Vec<RecentBuildSessionData>>, RecentBuildSessionsError> {
+    let window = config
+        .server
+        .as_ref()
+        .map_or_else(default_recent_build_sessions_window, |server| {
+            server.recent_build_sessions_window
+        });
+
+    let offset = pagination.offset();
+    let limit = pagination.limit().min(window.saturating_sub(offset));
+
+    if limit == 0 {
+        return Ok(Json(Vec::new()));
+    }
+
+    let current_user = current_user.map(|Extension(user)| user);
+
+    db.transaction(|txn| {
+        Box::pin(async move {
+            let build_sessions = build_session::Entity::find()
+                .select_only()
+                .columns([
+                    build_session::Column::Id,
+                    build_session::Column::CodeHash,
+                    build_session::Column::CargoContractVersion,
+                    build_session::Column::CreatedAt,
+                ])
+                .inner_join(user::Entity)
+                .inner_join(source_code::Entity)
+                .filter(build_session::Column::Status.eq(build_session::Status::Completed))
+                .filter(build_session::Column::CodeHash.is_not_null())
+                .filter(user::Column::PublicBuilds.eq(true))
+                .filter(visibility::source_code_visible_condition(current_user))
+                .order_by_desc(build_session::Column::Id)
+                .limit(limit)
+                .offset(offset)
+                .into_tuple::<(i64, Vec<u8>, String, PrimitiveDateTime)>()
+                .all(txn)
+                .await?;
+
+            // Multiple contracts may be deployed with the same code hash,
+            // in which case the earliest discovered contract is used.
+            let mut contracts = HashMap::new();
+
+            for (code_hash, address, node, ss58_prefix) in contract::Entity::find()
+                .select_only()
+                .columns([contract::Column::CodeHash, contract::Column::Address])
+                .columns([node::Column::Name, node::Column::Ss58Prefix])
+                .inner_join(node::Entity)
+                .filter(
+                    contract::Column::CodeHash.is_in(
+                        build_sessions
+                            .iter()
+                            .map(|(_, code_hash, ..)| code_hash.clone()),
+                    ),
+                )
+                .order_by_asc(contract::Column::Id)
+                .into_tuple::<(Vec<u8>, Vec<u8>, String, Option<i16>)>()
+                .all(txn)
+                .await?
+            {
+                contracts
+                    .entry(code_hash)
+                    .or_insert((address, node, ss58_prefix));
+            }
+
+            build_sessions
+                .into_iter()
+                .map(|(id, code_hash, cargo_contract_version, timestamp)| {
+                    let (address, node) = contracts
+                        .get(&code_hash)
+                        .map(|(address, node, ss58_prefix)| {
+                            let address: [u8; 32] = address
+                                .as_slice()
+                                .try_into()
+                                .map_err(|_| RecentBuildSessionsError::IncorrectAddressSize)?;
+
+                            Ok::<_, RecentBuildSessionsError>((
+                                ss58_address(
+                                    &AccountId32::new(address),
+                                    ss58_prefix.map(|prefix| prefix as u16),
+                                ),
+                                node.clone(),
+                            ))
+                        })
+                        .transpose()?
+                        .unzip();
+
+                    Ok(RecentBuildSessionData {
+                        id,
+                        code_hash: code_hash.as_slice().try_into()?,
+                        cargo_contract_version,
+                        timestamp: timestamp.assume_utc().unix_timestamp(),
+                        address,
+                        node,
+                    })
+                })
+                .collect::<Result<Vec<_>, RecentBuildSessionsError>>()
+                .map(Json)
+        })
+    })
+    .await
+    .into_raw_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
+
+    use assert_json::{assert_json, validators};
+    use axum::{body::Body, http::Request};
+    use common::{
+        config::Config,
+        rpc::sp_core::crypto::{AccountId32, Ss58Codec},
+    };
+    use db::{
+        build_session, code, contract, node, source_code, user, ActiveValue, DatabaseConnection,
+        EntityTrait,
+    };
+    use tower::ServiceExt;
+
+    /// Create a user with a single completed build session producing the provided code hash.
+    async fn create_build_session(
+        db: &DatabaseConnection,
+        public_builds: bool,
+        code_hash: [u8; 32],
+    ) {
+        let user = user::Entity::insert(user::ActiveModel {
+            public_builds: ActiveValue::Set(public_builds),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(code_hash.to_vec()),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        code::Entity::insert(code::ActiveModel {
+            hash: ActiveValue::Set(code_hash.to_vec()),
+            code: ActiveValue::Set(Vec::new()),
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert code");
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::Completed),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            code_hash: ActiveValue::Set(Some(code_hash.to_vec())),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert build session");
+    }
+
+    /// Request the recent build session feed using the provided configuration.
+    async fn request(
+        db: Arc<DatabaseConnection>,
+        config: Config,
+        query: &str,
+    ) -> serde_json::Value {
+        crate::app_router(db, Arc::new(config))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/recent{query}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .json()
+            .await
+    }
+
+    #[tokio::test]
+    async fn opted_out_users_and_contracts() {
+        let db = create_database().await;
+
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            ..Default::default()
+        })
+        .exec_with_returning(&db)
+        .await
+        .expect("unable to insert node");
+
+        create_build_session(&db, true, [1; 32]).await;
+        create_build_session(&db, false, [2; 32]).await;
+        create_build_session(&db, true, [3; 32]).await;
+
+        contract::Entity::insert(contract::ActiveModel {
+            code_hash: ActiveValue::Set(vec![1; 32]),
+            node_id: ActiveValue::Set(node.id),
+            address: ActiveValue::Set(vec![4; 32]),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert contract");
+
+        let response = request(Arc::new(db), Config::for_tests(), "").await;
+
+        assert_json!(response, [
+            {
+                "id": 3,
+                "code_hash": hex::encode([3; 32]),
+                "cargo_contract_version": "3.0.0",
+                "timestamp": validators::i64(|_| Ok(())),
+                "address": validators::null(),
+                "node": validators::null(),
+            },
+            {
+                "id": 1,
+                "code_hash": hex::encode([1; 32]),
+                "cargo_contract_version": "3.0.0",
+                "timestamp": validators::i64(|_| Ok(())),
+                "address": AccountId32::new([4; 32]).to_ss58check(),
+                "node": "test",
+            }
+        ]);
+    }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = Arc::new(create_database().await);
+
+        create_build_session(&db, true, [1; 32]).await;
+        make_source_code_private(&db).await;
+        create_build_session(&db, true, [2; 32]).await;
+
+        let response = request(db, Config::for_tests(), "").await;
+
+        assert_eq!(response.as_array().unwrap().len(), 1);
+        assert_eq!(response[0]["code_hash"], hex::encode([2; 32]));
+    }
+
+    #[tokio::test]
+    async fn window() {
+        let db = Arc::new(create_database().await);
+
+        for code_hash in 1..=3 {
+            create_build_session(&db, true, [code_hash; 32]).await;
+        }
+
+        let config = || {
+            let mut config = Config::for_tests();
+            config.server.as_mut().unwrap().recent_build_sessions_window = 2;
+            config
+        };
+
+        let response = request(db.clone(), config(), "?limit=1&page=2").await;
+        assert_eq!(response.as_array().unwrap().len(), 1);
+        assert_eq!(response[0]["id"], 2);
+
+        let response = request(db, config(), "?limit=2&page=2").await;
+        assert_eq!(response.as_array().unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,145 @@
+use std::{array::TryFromSliceError, sync::Arc};
+
+use aide::{transform::TransformOperation, OperationIo};
+use api_types::build_sessions::SigningKeyResponse;
+use axum::{extract::State, http::StatusCode, Json};
+use axum_derive_error::ErrorResponse;
+use db::{signing_key, DatabaseConnection, DbErr, EntityTrait, QueryOrder};
+use derive_more::{Display, Error, From};
+use serde_json::Value;
+
+use crate::schema::{example_error, example_signing_key_id, example_signing_public_key};
+
+/// Errors that may occur during the signing key retrieval.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum SigningKeyError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Incorrect public key size stored inside of a database.
+    IncorrectPublicKey(TryFromSliceError),
+
+    /// No builders registered their signing keys.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "no signing keys were registered")]
+    NoSigningKeys,
+}
+
+/// Generate OAPI documentation for the [`signing_key`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get the current builder signing key.")
+        .description(
+            r#"Builders sign the code hash concatenated with the JSON metadata hash
+of each completed build session. Signatures are available via the build session details route.
+
+Signatures of build sessions completed before the key rotation reference
+the previous key by its identifier.
+        "#,
+        )
+        .response_with::<200, Json<SigningKeyResponse>, _>(|op| {
+            op.description("Current signing key response.")
+                .example(SigningKeyResponse {
+                    key_id: example_signing_key_id(),
+                    public_key: example_signing_public_key(),
+                })
+        })
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description("No signing keys were registered by builders.")
+                .example(example_error(SigningKeyError::NoSigningKeys))
+        })
+}
+
+/// Current signing key request handler.
+///
+/// The most recently registered signing key is considered current.
+pub(super) async fn signing_key(
+    State(db): State<Arc<DatabaseConnection>>,
+) -> Result<Json<SigningKeyResponse>, SigningKeyError> {
+    let model = signing_key::Entity::find()
+        .order_by_desc(signing_key::Column::RegisteredAt)
+        .one(&*db)
+        .await?
+        .ok_or(SigningKeyError::NoSigningKeys)?;
+
+    Ok(Json(SigningKeyResponse {
+        key_id: model.id,
+        public_key: model.public_key.as_slice().try_into()?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use assert_json::assert_json;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{signing_key, ActiveValue, EntityTrait, OffsetDateTime, PrimitiveDateTime};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn current_key() {
+        let db = create_database().await;
+
+        let now = OffsetDateTime::now_utc();
+        let now = PrimitiveDateTime::new(now.date(), now.time());
+
+        for (id, public_key, registered_at) in [
+            (
+                "0101010101010101",
+                [1; 32],
+                now - Duration::from_secs(86400),
+            ),
+            ("0202020202020202", [2; 32], now),
+        ] {
+            signing_key::Entity::insert(signing_key::ActiveModel {
+                id: ActiveValue::Set(String::from(id)),
+                public_key: ActiveValue::Set(public_key.to_vec()),
+                registered_at: ActiveValue::Set(registered_at),
+            })
+            .exec_without_returning(&db)
+            .await
+            .expect("unable to insert signing key");
+        }
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/buildSessions/signingKey")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, {
+            "key_id": "0202020202020202",
+            "public_key": hex::encode([2; 32]),
+        });
+    }
+
+    #[tokio::test]
+    async fn no_keys() {
+        let db = create_database().await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/buildSessions/signingKey")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -4,16 +4,27 @@ use aide::{transform::TransformOperation, OperationIo};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
-use db::{build_session, DatabaseConnection, DbErr, EntityTrait, QuerySelect};
+use common::build_logs::TRUNCATION_MARKER;
+use db::{
+    build_session, build_session_stage, log, sea_query::Expr, ColumnTrait, DatabaseConnection,
+    DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+};
 use derive_more::{Display, Error, From};
 use schemars::JsonSchema;
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::{hex_hash::HexHash, schema::example_error};
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    schema::{
+        example_artifact_kind, example_build_session_status, example_error, example_hex_hash,
+    },
+    visibility,
+};
 
 /// Errors that may occur during the build session status request handling.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -29,6 +40,11 @@ pub(super) enum BuildSessionStatusError {
     #[status(StatusCode::NOT_FOUND)]
     #[display(fmt = "build session not found")]
     BuildSessionNotFound,
+
+    /// Provided identifier could not be parsed as a code hash or as a numeric identifier.
+    #[status(StatusCode::BAD_REQUEST)]
+    #[display(fmt = "unknown identifier format, use either code hash or numeric id")]
+    UnknownIdFormat,
 }
 
 /// JSON response body.
@@ -41,15 +57,60 @@ pub(super) struct BuildSessionStatusResponse {
     /// Code hash, if the build session was completed successfully.
     #[schemars(example = "crate::schema::example_hex_hash")]
     code_hash: Option<HexHash>,
+
+    /// Kind of the contract blob, if the build session was completed successfully.
+    ///
+    /// Clients can use this value to select the code hash algorithm
+    /// and the file extension of the blob returned by the artifact route.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(example = "crate::schema::example_artifact_kind")]
+    artifact_kind: Option<build_session::ArtifactKind>,
+
+    /// Blake2b 256-bit hash of the JSON metadata, if the build session was completed successfully.
+    ///
+    /// Clients can use this value to verify the metadata returned by the metadata route.
+    #[schemars(example = "crate::schema::example_hex_hash")]
+    metadata_hash: Option<HexHash>,
+
+    /// Whether build session logs were truncated after exceeding the log size limit.
+    logs_truncated: bool,
+
+    /// Current build session stage.
+    ///
+    /// Only present while the build session is being processed by a builder.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(example = "crate::schema::example_build_session_stage")]
+    stage: Option<build_session_stage::Stage>,
+
+    /// Count of build sessions that will be processed before this one.
+    ///
+    /// Only present while the build session is waiting in the queue.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(example = "crate::schema::example_queue_position")]
+    queue_position: Option<i64>,
 }
 
 /// Generate OAPI documentation for the [`status`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get build session status.")
-        .response::<200, Json<BuildSessionStatusResponse>>()
+        .response_with::<200, Json<BuildSessionStatusResponse>, _>(|op| {
+            op.description("Build session status response.")
+                .example(BuildSessionStatusResponse {
+                    status: example_build_session_status(),
+                    code_hash: Some(example_hex_hash()),
+                    artifact_kind: example_artifact_kind(),
+                    metadata_hash: Some(example_hex_hash()),
+                    logs_truncated: false,
+                    stage: None,
+                    queue_position: None,
+                })
+        })
         .response_with::<404, Json<Value>, _>(|op| {
-            op.description("No build sessions with the provided identifier were found.")
-                .example(example_error(BuildSessionStatusError::BuildSessionNotFound))
+            op.description(
+                "No build sessions with the provided identifier were found, \
+                or their source code is private.",
+            )
+            .example(example_error(BuildSessionStatusError::BuildSessionNotFound))
         })
 }
 
@@ -57,24 +118,101 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
 ///
 /// This route is used in the CLI to check if the build session completeness
 /// status.
+///
+/// Build session can be identified either by its numeric identifier or by a code hash,
+/// in which case the latest build session with the provided code hash is used.
+///
+/// While the build session is queued, its position in the queue is returned as well.
+/// As soon as a builder starts processing the build session, its current stage
+/// is returned instead.
 pub(super) async fn status(
-    Path(id): Path<i64>,
+    Path(id): Path<String>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
     State(db): State<Arc<DatabaseConnection>>,
 ) -> Result<Json<BuildSessionStatusResponse>, BuildSessionStatusError> {
-    let (status, code_hash) = build_session::Entity::find_by_id(id)
-        .select_only()
-        .columns([
-            build_session::Column::Status,
-            build_session::Column::CodeHash,
-        ])
-        .into_tuple::<(build_session::Status, Option<Vec<u8>>)>()
-        .one(&*db)
+    let (build_session_id, status, code_hash, artifact_kind, metadata_hash) =
+        build_session::Entity::find()
+            .select_only()
+            .columns([
+                build_session::Column::Id,
+                build_session::Column::Status,
+                build_session::Column::CodeHash,
+                build_session::Column::ArtifactKind,
+                build_session::Column::MetadataHash,
+            ])
+            .filter(match serde_plain::from_str::<HexHash>(&id) {
+                Ok(val) => build_session::Column::CodeHash.eq(&val.0[..]),
+                Err(_) => {
+                    let id = id
+                        .parse::<i64>()
+                        .map_err(|_| BuildSessionStatusError::UnknownIdFormat)?;
+
+                    build_session::Column::Id.eq(id)
+                }
+            })
+            .order_by_desc(build_session::Column::Id)
+            .into_tuple::<(
+                i64,
+                build_session::Status,
+                Option<Vec<u8>>,
+                build_session::ArtifactKind,
+                Option<Vec<u8>>,
+            )>()
+            .one(&*db)
+            .await?
+            .ok_or(BuildSessionStatusError::BuildSessionNotFound)?;
+
+    let current_user = current_user.map(|Extension(user)| user);
+
+    if !visibility::build_session_visible(&*db, build_session_id, current_user).await? {
+        return Err(BuildSessionStatusError::BuildSessionNotFound);
+    }
+
+    // Builders store the truncation marker instead of updating the build session row,
+    // which is locked for the whole build.
+    let logs_truncated = log::Entity::find()
+        .filter(log::Column::BuildSessionId.eq(build_session_id))
+        .filter(log::Column::Text.eq(TRUNCATION_MARKER))
+        .count(&*db)
         .await?
-        .ok_or(BuildSessionStatusError::BuildSessionNotFound)?;
+        > 0;
+
+    let stage = if status == build_session::Status::New {
+        build_session_stage::Entity::find_by_id(build_session_id)
+            .select_only()
+            .column(build_session_stage::Column::Stage)
+            .into_tuple::<build_session_stage::Stage>()
+            .one(&*db)
+            .await?
+    } else {
+        None
+    };
+
+    // Builders process queued build sessions in the order of their identifiers.
+    let queue_position = if status == build_session::Status::New && stage.is_none() {
+        build_session::Entity::find()
+            .select_only()
+            .column_as(Expr::col(build_session::Column::Id).count(), "count")
+            .filter(build_session::Column::Status.eq(build_session::Status::New))
+            .filter(build_session::Column::Id.lt(build_session_id))
+            .into_tuple::<i64>()
+            .one(&*db)
+            .await?
+    } else {
+        None
+    };
 
     Ok(Json(BuildSessionStatusResponse {
         status,
+        artifact_kind: code_hash.as_ref().map(|_| artifact_kind),
         code_hash: code_hash.as_deref().map(HexHash::try_from).transpose()?,
+        metadata_hash: metadata_hash
+            .as_deref()
+            .map(HexHash::try_from)
+            .transpose()?,
+        logs_truncated,
+        stage,
+        queue_position,
     }))
 }
 
@@ -82,15 +220,18 @@ pub(super) async fn status(
 mod tests {
     use std::sync::Arc;
 
-    use crate::testing::{create_database, ResponseBodyExt};
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
 
     use assert_json::assert_json;
     use axum::{
         body::Body,
         http::{Request, StatusCode},
     };
-    use common::config::Config;
-    use db::{build_session, source_code, user, ActiveValue, DatabaseConnection, EntityTrait};
+    use common::{build_logs::TRUNCATION_MARKER, config::Config};
+    use db::{
+        build_session, build_session_stage, log, source_code, user, ActiveValue,
+        DatabaseConnection, EntityTrait,
+    };
     use tower::ServiceExt;
 
     async fn create_test_env(db: &DatabaseConnection) -> i64 {
@@ -109,18 +250,30 @@ mod tests {
         .expect("unable to create source code")
         .id;
 
-        build_session::Entity::insert(build_session::ActiveModel {
+        let build_session_id = build_session::Entity::insert(build_session::ActiveModel {
             user_id: ActiveValue::Set(Some(user.id)),
             source_code_id: ActiveValue::Set(source_code_id),
             status: ActiveValue::Set(build_session::Status::Completed),
             cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
             code_hash: ActiveValue::Set(Some(vec![0; 32])),
+            metadata_hash: ActiveValue::Set(Some(vec![1; 32])),
             ..Default::default()
         })
         .exec_with_returning(db)
         .await
         .expect("unable to insert build session")
-        .id
+        .id;
+
+        log::Entity::insert(log::ActiveModel {
+            build_session_id: ActiveValue::Set(build_session_id),
+            text: ActiveValue::Set(String::from(TRUNCATION_MARKER)),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert log entry");
+
+        build_session_id
     }
 
     #[tokio::test]
@@ -142,8 +295,229 @@ mod tests {
 
         assert_json!(response.json().await, {
             "status": "completed",
-            "code_hash": hex::encode([0; 32])
+            "code_hash": hex::encode([0; 32]),
+            "artifact_kind": "wasm",
+            "metadata_hash": hex::encode([1; 32]),
+            "logs_truncated": true
+        });
+    }
+
+    #[tokio::test]
+    async fn successful_by_code_hash() {
+        let db = create_database().await;
+
+        create_test_env(&db).await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/status/{}", hex::encode([0; 32])))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, {
+            "status": "completed",
+            "code_hash": hex::encode([0; 32]),
+            "artifact_kind": "wasm",
+            "metadata_hash": hex::encode([1; 32]),
+            "logs_truncated": true
+        });
+    }
+
+    #[tokio::test]
+    async fn polkavm_artifact_kind() {
+        let db = create_database().await;
+
+        let build_session_id = create_test_env(&db).await;
+
+        build_session::Entity::update(build_session::ActiveModel {
+            id: ActiveValue::Unchanged(build_session_id),
+            artifact_kind: ActiveValue::Set(build_session::ArtifactKind::Polkavm),
+            ..Default::default()
+        })
+        .exec(&db)
+        .await
+        .expect("unable to update build session");
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/status/{build_session_id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.json().await["artifact_kind"], "polkavm");
+    }
+
+    #[tokio::test]
+    async fn queued() {
+        let db = Arc::new(create_database().await);
+
+        let completed = build_session::Entity::find_by_id(create_test_env(&db).await)
+            .one(&*db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut queued = Vec::new();
+
+        for status in [
+            build_session::Status::New,
+            build_session::Status::Failed,
+            build_session::Status::New,
+            build_session::Status::New,
+        ] {
+            let model = build_session::Entity::insert(build_session::ActiveModel {
+                user_id: ActiveValue::Set(completed.user_id),
+                source_code_id: ActiveValue::Set(completed.source_code_id),
+                status: ActiveValue::Set(status.clone()),
+                cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+                ..Default::default()
+            })
+            .exec_with_returning(&*db)
+            .await
+            .expect("unable to insert build session");
+
+            if status == build_session::Status::New {
+                queued.push(model.id);
+            }
+        }
+
+        for (position, id) in queued.into_iter().enumerate() {
+            let response = crate::app_router(db.clone(), Arc::new(Config::for_tests()))
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri(format!("/buildSessions/status/{}", id))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_json!(response.json().await, {
+                "status": "new",
+                "code_hash": null,
+                "metadata_hash": null,
+                "logs_truncated": false,
+                "queue_position": position as i64,
+            });
+        }
+
+        let response = crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/status/{}", completed.id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert!(response.json().await.get("queue_position").is_none());
+    }
+
+    #[tokio::test]
+    async fn processing() {
+        let db = Arc::new(create_database().await);
+
+        let completed = build_session::Entity::find_by_id(create_test_env(&db).await)
+            .one(&*db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let build_session_id = build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(completed.user_id),
+            source_code_id: ActiveValue::Set(completed.source_code_id),
+            status: ActiveValue::Set(build_session::Status::New),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            ..Default::default()
+        })
+        .exec_with_returning(&*db)
+        .await
+        .expect("unable to insert build session")
+        .id;
+
+        build_session_stage::Entity::insert(build_session_stage::ActiveModel {
+            build_session_id: ActiveValue::Set(build_session_id),
+            stage: ActiveValue::Set(build_session_stage::Stage::Building),
+        })
+        .exec_without_returning(&*db)
+        .await
+        .expect("unable to insert build session stage");
+
+        let response = crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/status/{}", build_session_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = response.json().await;
+
+        assert_json!(body.clone(), {
+            "status": "new",
+            "code_hash": null,
+            "metadata_hash": null,
+            "logs_truncated": false,
+            "stage": "building",
         });
+        assert!(body.get("queue_position").is_none());
+    }
+
+    #[tokio::test]
+    async fn unknown_id_format() {
+        let db = create_database().await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/buildSessions/status/unknown")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = Arc::new(create_database().await);
+
+        let build_session_id = create_test_env(&db).await;
+        make_source_code_private(&db).await;
+
+        for id in [build_session_id.to_string(), hex::encode([0; 32])] {
+            let response = crate::app_router(db.clone(), Arc::new(Config::for_tests()))
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri(format!("/buildSessions/status/{id}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
     }
 
     #[tokio::test]
@@ -0,0 +1,376 @@
+use std::{array::TryFromSliceError, sync::Arc};
+
+use aide::{
+    gen::GenContext,
+    openapi::{Operation, Response as OapiResponse},
+    transform::TransformOperation,
+    OperationIo, OperationOutput,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use db::{build_session, build_session_stage, DatabaseConnection, DbErr, EntityTrait, QuerySelect};
+use derive_more::{Display, Error, From};
+use futures_util::{future, stream, StreamExt};
+use serde_json::Value;
+use tokio::sync::broadcast::Receiver;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    events::{BuildSessionEvent, BuildSessionEvents},
+    hex_hash::HexHash,
+    schema::{
+        example_build_session_status, example_database_identifier, example_error, example_hex_hash,
+    },
+    visibility,
+};
+
+/// Errors that may occur during the build session status events request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum BuildSessionStatusEventsError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Incorrect hash size stored inside of a database
+    IncorrectCodeHash(TryFromSliceError),
+
+    /// The requested build session was not found.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "build session not found")]
+    BuildSessionNotFound,
+}
+
+/// Server-sent event stream of build session status changes.
+pub(super) struct StatusEvents {
+    /// Current build session status.
+    current: BuildSessionEvent,
+
+    /// Build session events receiver, if the build session is not finished yet.
+    receiver: Option<Receiver<BuildSessionEvent>>,
+}
+
+impl IntoResponse for StatusEvents {
+    fn into_response(self) -> Response {
+        let build_session_id = self.current.build_session_id;
+
+        let completion = stream::unfold(self.receiver, move |receiver| async move {
+            let mut receiver = receiver?;
+
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.build_session_id == build_session_id => {
+                        return Some((event, None))
+                    }
+                    Ok(_) => {}
+                    // Lagging subscribers may have missed the completion event,
+                    // thus the stream is closed to let the client request the status again.
+                    Err(_) => return None,
+                }
+            }
+        });
+
+        let stream = stream::once(future::ready(self.current))
+            .chain(completion)
+            .map(|event| Event::default().event("status").json_data(event));
+
+        Sse::new(stream)
+            .keep_alive(KeepAlive::default())
+            .into_response()
+    }
+}
+
+impl OperationOutput for StatusEvents {
+    type Inner = BuildSessionEvent;
+
+    fn operation_response(ctx: &mut GenContext, operation: &mut Operation) -> Option<OapiResponse> {
+        <Json<BuildSessionEvent> as OperationOutput>::operation_response(ctx, operation)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, OapiResponse)> {
+        <Json<BuildSessionEvent> as OperationOutput>::inferred_responses(ctx, operation)
+    }
+}
+
+/// Generate OAPI documentation for the [`status_events`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Subscribe to build session status changes.")
+        .description(
+            r#"Returns a stream of server-sent `status` events, each containing the JSON value
+described below.
+
+The first event contains the current build session status, alongside with
+the current stage if the build session is being processed. If the build session
+is not finished yet, the second event is sent after its completion.
+The stream is closed after the final build session status is sent."#,
+        )
+        .response_with::<200, Json<BuildSessionEvent>, _>(|op| {
+            op.description("Build session status event stream.")
+                .example(BuildSessionEvent {
+                    build_session_id: example_database_identifier(),
+                    status: example_build_session_status(),
+                    code_hash: Some(example_hex_hash()),
+                    stage: None,
+                })
+        })
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description(
+                "No build sessions with the provided identifier were found, \
+                or their source code is private.",
+            )
+            .example(example_error(
+                BuildSessionStatusEventsError::BuildSessionNotFound,
+            ))
+        })
+}
+
+/// Build session status events request handler.
+pub(super) async fn status_events(
+    Path(id): Path<i64>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Extension(events): Extension<BuildSessionEvents>,
+) -> Result<StatusEvents, BuildSessionStatusEventsError> {
+    // Subscribe before querying the current status to not miss events published in between.
+    let receiver = events.subscribe();
+
+    let (source_code_id, status, code_hash) = build_session::Entity::find_by_id(id)
+        .select_only()
+        .columns([
+            build_session::Column::SourceCodeId,
+            build_session::Column::Status,
+            build_session::Column::CodeHash,
+        ])
+        .into_tuple::<(i64, build_session::Status, Option<Vec<u8>>)>()
+        .one(&*db)
+        .await?
+        .ok_or(BuildSessionStatusEventsError::BuildSessionNotFound)?;
+
+    let current_user = current_user.map(|Extension(user)| user);
+
+    if !visibility::source_code_visible(&*db, source_code_id, current_user).await? {
+        return Err(BuildSessionStatusEventsError::BuildSessionNotFound);
+    }
+
+    let stage = if status == build_session::Status::New {
+        build_session_stage::Entity::find_by_id(id)
+            .select_only()
+            .column(build_session_stage::Column::Stage)
+            .into_tuple::<build_session_stage::Stage>()
+            .one(&*db)
+            .await?
+    } else {
+        None
+    };
+
+    let receiver = (status == build_session::Status::New).then_some(receiver);
+
+    Ok(StatusEvents {
+        current: BuildSessionEvent {
+            build_session_id: id,
+            status,
+            code_hash: code_hash.as_deref().map(HexHash::try_from).transpose()?,
+            stage,
+        },
+        receiver,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
+
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use common::{
+        config::Config,
+        signature::{sign_timestamped, unix_timestamp, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+    };
+    use db::{
+        build_session, source_code, user, ActiveValue, ColumnTrait, DatabaseConnection,
+        EntityTrait, QueryFilter,
+    };
+    use serde_json::{json, Value};
+    use tower::{Service, ServiceExt};
+
+    async fn create_test_env(db: &DatabaseConnection, status: build_session::Status) -> i64 {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            code_hash: ActiveValue::Set(
+                (status == build_session::Status::Completed).then(|| vec![0; 32]),
+            ),
+            status: ActiveValue::Set(status),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert build session")
+        .id
+    }
+
+    fn events_request(id: i64) -> Request<Body> {
+        Request::builder()
+            .method("GET")
+            .uri(format!("/buildSessions/status/{id}/events"))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    /// Parse JSON data of all events inside of the provided event stream.
+    fn parse_events(body: &str) -> Vec<Value> {
+        body.split("\n\n")
+            .filter_map(|event| event.lines().find_map(|line| line.strip_prefix("data:")))
+            .map(|data| serde_json::from_str(data.trim()).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn finished() {
+        let db = Arc::new(create_database().await);
+
+        let id = create_test_env(&db, build_session::Status::Completed).await;
+
+        let response = crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(events_request(id))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+        assert_eq!(
+            parse_events(&response.text().await),
+            [json!({
+                "build_session_id": id,
+                "status": "completed",
+                "code_hash": hex::encode([0; 32]),
+            })]
+        );
+    }
+
+    #[tokio::test]
+    async fn completion() {
+        let db = Arc::new(create_database().await);
+
+        let id = create_test_env(&db, build_session::Status::New).await;
+
+        let mut config = Config::for_tests();
+        config.server.as_mut().unwrap().callback_secret = Some(String::from("secret"));
+
+        let mut service = crate::app_router(db.clone(), Arc::new(config));
+
+        let response = service.call(events_request(id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        build_session::Entity::update_many()
+            .filter(build_session::Column::Id.eq(id))
+            .col_expr(
+                build_session::Column::Status,
+                build_session::Status::Failed.into(),
+            )
+            .exec(&*db)
+            .await
+            .unwrap();
+
+        let body = serde_json::to_vec(&json!({
+            "build_session_id": id,
+            "status": "failed",
+            "code_hash": null,
+        }))
+        .unwrap();
+        let timestamp = unix_timestamp();
+
+        let notification = service
+            .call(
+                Request::builder()
+                    .method("POST")
+                    .uri("/internal/buildSessions/notify")
+                    .header(TIMESTAMP_HEADER, timestamp)
+                    .header(
+                        SIGNATURE_HEADER,
+                        sign_timestamped(b"secret", timestamp, &body),
+                    )
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(notification.status(), StatusCode::OK);
+
+        assert_eq!(
+            parse_events(&response.text().await),
+            [
+                json!({
+                    "build_session_id": id,
+                    "status": "new",
+                    "code_hash": null,
+                }),
+                json!({
+                    "build_session_id": id,
+                    "status": "failed",
+                    "code_hash": null,
+                })
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = Arc::new(create_database().await);
+
+        let id = create_test_env(&db, build_session::Status::Completed).await;
+        make_source_code_private(&db).await;
+
+        let response = crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(events_request(id))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn unknown() {
+        let db = Arc::new(create_database().await);
+
+        let response = crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(events_request(1))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use aide::transform::TransformOperation;
+use axum::{Extension, Json};
+use common::config::Config;
+
+use crate::schema::example_cargo_contract_version;
+
+/// Generate OAPI documentation for the [`supported_versions`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get supported cargo-contract versions.")
+        .description(
+            r#"Versions are ordered by preference, with the first one being
+the suggested default for new projects. Build sessions with any other
+`cargo-contract` version are rejected during the creation process.
+        "#,
+        )
+        .response_with::<200, Json<Vec<String>>, _>(|op| {
+            op.description("Supported cargo-contract versions.")
+                .example(vec![example_cargo_contract_version()])
+        })
+}
+
+/// Supported `cargo-contract` versions request handler.
+pub(super) async fn supported_versions(
+    Extension(config): Extension<Arc<Config>>,
+) -> Json<Vec<String>> {
+    Json(config.supported_cargo_contract_versions.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use assert_json::assert_json;
+    use axum::{body::Body, http::Request};
+    use common::config::Config;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn supported_versions() {
+        let db = create_database().await;
+
+        let mut config = Config::for_tests();
+        config.supported_cargo_contract_versions =
+            vec![String::from("4.0.0"), String::from("3.2.0")];
+
+        let response = crate::app_router(Arc::new(db), Arc::new(config))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/buildSessions/supportedVersions")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, ["4.0.0", "3.2.0"]);
+    }
+}
@@ -4,14 +4,14 @@ use aide::{transform::TransformOperation, OperationIo};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
 use db::{code, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect};
 use derive_more::{Display, Error, From};
 use serde_json::Value;
 
-use crate::{hex_hash::HexHash, schema::example_error};
+use crate::{auth::AuthenticatedUserId, hex_hash::HexHash, schema::example_error, visibility};
 
 /// Errors that may occur during the WASM blob request handling.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -29,18 +29,33 @@ pub(super) enum BuildSessionWasmError {
 /// Generate OAPI documentation for the [`wasm`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get WASM blob of the latest build session.")
+        .description(
+            r#"Contract blobs are returned regardless of their kind.
+Use the artifact route to receive PolkaVM blobs with the correct content type.
+        "#,
+        )
         .response::<200, Vec<u8>>()
         .response_with::<404, Json<Value>, _>(|op| {
-            op.description("No build sessions with the provided code hash were found.")
-                .example(example_error(BuildSessionWasmError::BuildSessionNotFound))
+            op.description(
+                "No build sessions with the provided code hash were found, \
+                or their source code is private.",
+            )
+            .example(example_error(BuildSessionWasmError::BuildSessionNotFound))
         })
 }
 
 /// WASM blob request handler.
 pub(super) async fn wasm(
     Path(code_hash): Path<HexHash>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
     State(db): State<Arc<DatabaseConnection>>,
 ) -> Result<Vec<u8>, BuildSessionWasmError> {
+    let current_user = current_user.map(|Extension(user)| user);
+
+    if !visibility::code_hash_visible(&*db, &code_hash.0, current_user).await? {
+        return Err(BuildSessionWasmError::BuildSessionNotFound);
+    }
+
     let wasm = code::Entity::find()
         .select_only()
         .column(code::Column::Code)
@@ -0,0 +1,290 @@
+use std::sync::Arc;
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use db::{
+    build_session, code,
+    sea_query::{Alias, Expr, Func},
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect, SelectExt,
+    TransactionErrorExt, TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    schema::{example_code_size, example_error},
+    visibility,
+};
+
+/// Errors that may occur during the code details request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum CodeDetailsError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Provided code hash could not be parsed.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "invalid code hash")]
+    InvalidCodeHash,
+}
+
+/// Code details response.
+#[derive(Serialize, JsonSchema)]
+pub struct CodeData {
+    /// Whether the WASM blob with the provided code hash is stored.
+    pub exists: bool,
+
+    /// Size of the stored WASM blob in bytes.
+    #[schemars(example = "crate::schema::example_code_size")]
+    pub size_bytes: Option<i64>,
+
+    /// Whether any build session produced JSON metadata for the provided code hash.
+    pub has_metadata: bool,
+
+    /// Whether any completed build session produced the provided code hash.
+    pub verified: bool,
+}
+
+/// Generate OAPI documentation for the [`details`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get information about the provided code hash.")
+        .description(
+            r#"Unknown code hashes are not treated as an error,
+instead the response indicates that no information is available.
+
+Code hashes produced only from private source code archives are reported as unknown
+to everyone except the archive owners."#,
+        )
+        .response_with::<200, Json<CodeData>, _>(|op| {
+            op.description("Code details response.").example(CodeData {
+                exists: true,
+                size_bytes: Some(example_code_size()),
+                has_metadata: true,
+                verified: true,
+            })
+        })
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description("Provided code hash is invalid.")
+                .example(example_error(CodeDetailsError::InvalidCodeHash))
+        })
+}
+
+/// Code details request handler.
+///
+/// WASM blobs are never loaded by this handler, only their sizes are queried.
+pub(super) async fn details(
+    Path(hash): Path<String>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> Result<Json<CodeData>, CodeDetailsError> {
+    let hash: HexHash = hash
+        .parse()
+        .map_err(|_| CodeDetailsError::InvalidCodeHash)?;
+
+    let current_user = current_user.map(|Extension(user)| user);
+
+    db.transaction(|txn| {
+        Box::pin(async move {
+            if !visibility::code_hash_visible(txn, &hash.0, current_user).await? {
+                return Ok(Json(CodeData {
+                    exists: false,
+                    size_bytes: None,
+                    has_metadata: false,
+                    verified: false,
+                }));
+            }
+
+            let length = Func::cust(Alias::new("LENGTH")).arg(Expr::col(code::Column::Code));
+
+            let size_bytes = code::Entity::find_by_id(hash.0.to_vec())
+                .select_only()
+                .column_as(
+                    Expr::expr(length).cast_as(Alias::new("BIGINT")),
+                    "size_bytes",
+                )
+                .into_tuple::<i64>()
+                .one(txn)
+                .await?;
+
+            let has_metadata = build_session::Entity::find()
+                .select_only()
+                .filter(build_session::Column::CodeHash.eq(&hash.0[..]))
+                .filter(build_session::Column::Metadata.is_not_null())
+                .exists(txn)
+                .await?;
+
+            let verified = build_session::Entity::find()
+                .select_only()
+                .filter(build_session::Column::CodeHash.eq(&hash.0[..]))
+                .filter(build_session::Column::Status.eq(build_session::Status::Completed))
+                .exists(txn)
+                .await?;
+
+            Ok(Json(CodeData {
+                exists: size_bytes.is_some(),
+                size_bytes,
+                has_metadata,
+                verified,
+            }))
+        })
+    })
+    .await
+    .into_raw_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
+
+    use assert_json::{assert_json, validators};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{
+        build_session, code, source_code, user, ActiveValue, DatabaseConnection, EntityTrait,
+    };
+    use tower::ServiceExt;
+
+    async fn create_test_env(db: &DatabaseConnection) {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        code::Entity::insert_many([[1; 32], [2; 32]].map(|hash| code::ActiveModel {
+            hash: ActiveValue::Set(hash.to_vec()),
+            code: ActiveValue::Set(vec![0; 16]),
+        }))
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert codes");
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::Completed),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            code_hash: ActiveValue::Set(Some(vec![2; 32])),
+            metadata: ActiveValue::Set(Some(b"{}".to_vec())),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert build session");
+    }
+
+    async fn request(db: Arc<DatabaseConnection>, hash: &str) -> axum::response::Response {
+        crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/codes/{hash}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn unknown() {
+        let db = Arc::new(create_database().await);
+        create_test_env(&db).await;
+
+        let response = request(db, &hex::encode([3; 32])).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, {
+            "exists": false,
+            "size_bytes": validators::null(),
+            "has_metadata": false,
+            "verified": false,
+        });
+    }
+
+    #[tokio::test]
+    async fn stored() {
+        let db = Arc::new(create_database().await);
+        create_test_env(&db).await;
+
+        let response = request(db, &hex::encode([1; 32])).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, {
+            "exists": true,
+            "size_bytes": 16,
+            "has_metadata": false,
+            "verified": false,
+        });
+    }
+
+    #[tokio::test]
+    async fn verified() {
+        let db = Arc::new(create_database().await);
+        create_test_env(&db).await;
+
+        let response = request(db, &hex::encode([2; 32])).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, {
+            "exists": true,
+            "size_bytes": 16,
+            "has_metadata": true,
+            "verified": true,
+        });
+    }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = Arc::new(create_database().await);
+        create_test_env(&db).await;
+        make_source_code_private(&db).await;
+
+        let response = request(db, &hex::encode([2; 32])).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, {
+            "exists": false,
+            "size_bytes": validators::null(),
+            "has_metadata": false,
+            "verified": false,
+        });
+    }
+
+    #[tokio::test]
+    async fn invalid_hash() {
+        let db = create_database().await;
+
+        let response = request(Arc::new(db), "invalid").await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_json!(response.json().await, {
+            "code": 404,
+            "error": "invalid code hash",
+        });
+    }
+}
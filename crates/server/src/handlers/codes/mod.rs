@@ -0,0 +1,18 @@
+/// Code details route.
+mod details;
+
+use std::sync::Arc;
+
+use aide::axum::{routing::get_with, ApiRouter};
+use axum::middleware::from_fn_with_state;
+use db::DatabaseConnection;
+
+use crate::auth;
+
+/// Create an [`ApiRouter`] that provides an API server with uploaded code information routes.
+pub(crate) fn routes(database: Arc<DatabaseConnection>) -> ApiRouter<Arc<DatabaseConnection>> {
+    ApiRouter::new()
+        .api_route("/:hash", get_with(details::details, details::docs))
+        .route_layer(from_fn_with_state(database, auth::optional_authentication))
+        .with_path_items(|op| op.tag("Code management"))
+}
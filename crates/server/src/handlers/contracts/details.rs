@@ -1,26 +1,28 @@
 use std::{array::TryFromSliceError, sync::Arc};
 
 use aide::{transform::TransformOperation, OperationIo};
+use api_types::contracts::ContractData;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
     Json,
 };
 use axum_derive_error::ErrorResponse;
-use common::rpc::sp_core::{
-    crypto::{AccountId32, Ss58Codec},
-    ByteArray,
+use common::rpc::{
+    sp_core::{crypto::AccountId32, ByteArray},
+    ss58_address,
 };
 use db::{
-    contract, node, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect,
-    TransactionErrorExt, TransactionTrait,
+    contract, event, node, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PrimitiveDateTime,
+    QueryFilter, QueryOrder, QuerySelect, TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
-use schemars::JsonSchema;
-use serde::Serialize;
 use serde_json::Value;
 
-use crate::{hex_hash::HexHash, schema::example_error};
+use crate::schema::{
+    example_account, example_block_number, example_database_identifier, example_error,
+    example_hex_hash, example_node, example_node_display_name, example_timestamp,
+};
 
 use super::WrappedAccountId32;
 
@@ -38,39 +40,27 @@ pub(super) enum ContractDetailsError {
     #[display(fmt = "incorrect address size of an owner account")]
     IncorrectAddressSizeOfOwner,
 
-    /// Owner account attached to a contract is invalid.
-    #[display(fmt = "found a contract without related node")]
-    ContractWithoutRelatedNode,
-
     /// The requested contract was not found.
     #[status(StatusCode::NOT_FOUND)]
     #[display(fmt = "contract not found")]
     ContractNotFound,
 }
 
-/// Contract details response.
-#[derive(Serialize, JsonSchema)]
-pub struct ContractData {
-    /// Related node name.
-    #[schemars(example = "crate::schema::example_node")]
-    pub node: String,
-
-    /// Related code hash.
-    #[schemars(example = "crate::schema::example_hex_hash")]
-    pub code_hash: HexHash,
-
-    /// Contract owner.
-    ///
-    /// This field is only available is the contract
-    /// was discovered after the initial activation of an event server.
-    #[schemars(example = "crate::schema::example_account")]
-    pub owner: Option<String>,
-}
-
 /// Generate OAPI documentation for the [`details`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get details about the provided contract account.")
-        .response::<200, Json<ContractData>>()
+        .response_with::<200, Json<ContractData>, _>(|op| {
+            op.description("Contract details response.")
+                .example(ContractData {
+                    node: example_node(),
+                    node_id: example_database_identifier(),
+                    node_display_name: example_node_display_name(),
+                    code_hash: example_hex_hash(),
+                    owner: Some(example_account().to_string()),
+                    first_seen_at: Some(example_timestamp()),
+                    first_seen_block: example_block_number(),
+                })
+        })
         .response_with::<404, Json<Value>, _>(|op| {
             op.description("Provided contract account was not found.")
                 .example(example_error(ContractDetailsError::ContractNotFound))
@@ -84,44 +74,66 @@ pub(super) async fn details(
 ) -> Result<Json<ContractData>, ContractDetailsError> {
     db.transaction(|txn| {
         Box::pin(async move {
-            let (node_id, code_hash, owner) = contract::Entity::find()
-                .select_only()
-                .columns([
-                    contract::Column::NodeId,
-                    contract::Column::CodeHash,
-                    contract::Column::Owner,
-                ])
-                .filter(contract::Column::Address.eq(account.0.as_slice()))
-                .into_tuple::<(i64, Vec<u8>, Option<Vec<u8>>)>()
-                .one(txn)
-                .await?
-                .ok_or(ContractDetailsError::ContractNotFound)?;
+            let (node_id, name, display_name, ss58_prefix, code_hash, owner) =
+                contract::Entity::find()
+                    .select_only()
+                    .columns([
+                        node::Column::Id,
+                        node::Column::Name,
+                        node::Column::DisplayName,
+                        node::Column::Ss58Prefix,
+                    ])
+                    .columns([contract::Column::CodeHash, contract::Column::Owner])
+                    .inner_join(node::Entity)
+                    .filter(contract::Column::Address.eq(account.0.as_slice()))
+                    .into_tuple::<(
+                        i64,
+                        String,
+                        Option<String>,
+                        Option<i16>,
+                        Vec<u8>,
+                        Option<Vec<u8>>,
+                    )>()
+                    .one(txn)
+                    .await?
+                    .ok_or(ContractDetailsError::ContractNotFound)?;
 
-            let node = node::Entity::find_by_id(node_id)
+            let first_seen = event::Entity::find()
                 .select_only()
-                .column(node::Column::Name)
-                .into_tuple::<String>()
+                .columns([event::Column::BlockTimestamp, event::Column::BlockNumber])
+                .filter(event::Column::NodeId.eq(node_id))
+                .filter(event::Column::Account.eq(account.0.as_slice()))
+                .filter(event::Column::EventType.eq(event::EventType::Instantiation))
+                .order_by_asc(event::Column::BlockTimestamp)
+                .order_by_asc(event::Column::Id)
+                .into_tuple::<(PrimitiveDateTime, Option<i64>)>()
                 .one(txn)
-                .await?
-                .ok_or(ContractDetailsError::ContractWithoutRelatedNode)?;
+                .await?;
 
             let owner = owner
                 .map(|address| {
-                    Result::<_, ContractDetailsError>::Ok(
-                        AccountId32::new(
-                            address
-                                .try_into()
-                                .map_err(|_| ContractDetailsError::IncorrectAddressSizeOfOwner)?,
-                        )
-                        .to_ss58check(),
-                    )
+                    let account = AccountId32::new(
+                        address
+                            .try_into()
+                            .map_err(|_| ContractDetailsError::IncorrectAddressSizeOfOwner)?,
+                    );
+
+                    Result::<_, ContractDetailsError>::Ok(ss58_address(
+                        &account,
+                        ss58_prefix.map(|prefix| prefix as u16),
+                    ))
                 })
                 .transpose()?;
 
             Ok(Json(ContractData {
-                node,
+                node: name,
+                node_id,
+                node_display_name: display_name,
                 code_hash: code_hash.as_slice().try_into()?,
                 owner,
+                first_seen_at: first_seen
+                    .map(|(timestamp, _)| timestamp.assume_utc().unix_timestamp()),
+                first_seen_block: first_seen.and_then(|(_, block_number)| block_number),
             }))
         })
     })
@@ -135,20 +147,34 @@ mod tests {
 
     use crate::testing::{create_database, ResponseBodyExt};
 
-    use assert_json::assert_json;
+    use assert_json::{assert_json, validators};
     use axum::{
         body::Body,
         http::{Request, StatusCode},
     };
-    use common::{config::Config, rpc::sp_core::crypto::AccountId32};
-    use db::{code, contract, node, ActiveValue, DatabaseConnection, EntityTrait};
+    use common::{
+        config::Config,
+        rpc::{
+            sp_core::crypto::{AccountId32, Ss58Codec},
+            ss58_address,
+        },
+    };
+    use db::{
+        code, contract, event, node, ActiveValue, DatabaseConnection, EntityTrait, OffsetDateTime,
+        PrimitiveDateTime,
+    };
     use tower::ServiceExt;
 
-    async fn create_test_env(db: &DatabaseConnection) {
+    /// SS58 prefix of the Astar network.
+    const ASTAR_PREFIX: u16 = 5;
+
+    async fn create_test_env(db: &DatabaseConnection, ss58_prefix: Option<i16>) -> node::Model {
         let node = node::Entity::insert(node::ActiveModel {
             name: ActiveValue::Set(String::from("test")),
             url: ActiveValue::Set(String::from("ws://localhost:9944")),
             confirmed_block: ActiveValue::Set(0),
+            display_name: ActiveValue::Set(Some(String::from("Test Network"))),
+            ss58_prefix: ActiveValue::Set(ss58_prefix),
             ..Default::default()
         })
         .exec_with_returning(db)
@@ -173,13 +199,43 @@ mod tests {
         .exec_without_returning(db)
         .await
         .expect("unable to insert contract");
+
+        node
+    }
+
+    /// Insert an event of the test contract, that occured at the provided block.
+    async fn create_event(
+        db: &DatabaseConnection,
+        node_id: i64,
+        event_type: event::EventType,
+        body: event::EventBody,
+        timestamp: i64,
+        block_number: Option<i64>,
+    ) {
+        let datetime = OffsetDateTime::from_unix_timestamp(timestamp).expect("invalid date");
+
+        event::Entity::insert(event::ActiveModel {
+            node_id: ActiveValue::Set(node_id),
+            account: ActiveValue::Set(vec![1; 32]),
+            event_type: ActiveValue::Set(event_type),
+            body: ActiveValue::Set(body),
+            block_timestamp: ActiveValue::Set(PrimitiveDateTime::new(
+                datetime.date(),
+                datetime.time(),
+            )),
+            block_number: ActiveValue::Set(block_number),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert an event");
     }
 
     #[tokio::test]
     async fn successful() {
         let db = create_database().await;
 
-        create_test_env(&db).await;
+        let node = create_test_env(&db, None).await;
 
         let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
             .oneshot(
@@ -194,8 +250,63 @@ mod tests {
 
         assert_json!(response.json().await, {
             "node": "test",
+            "node_id": node.id,
+            "node_display_name": "Test Network",
             "code_hash": hex::encode([0; 32]),
             "owner": AccountId32::from([2; 32]).to_string(),
+            "first_seen_at": validators::null(),
+            "first_seen_block": validators::null(),
+        })
+    }
+
+    #[tokio::test]
+    async fn first_seen() {
+        let db = create_database().await;
+
+        let node = create_test_env(&db, None).await;
+
+        create_event(
+            &db,
+            node.id,
+            event::EventType::CodeHashUpdate,
+            event::EventBody::CodeHashUpdate {
+                new_code_hash: hex::encode([0; 32]),
+            },
+            50,
+            Some(5),
+        )
+        .await;
+
+        for (timestamp, block_number) in [(200, Some(20)), (100, Some(10))] {
+            create_event(
+                &db,
+                node.id,
+                event::EventType::Instantiation,
+                event::EventBody::Instantiation {
+                    deployer: AccountId32::new([2; 32]).to_string(),
+                },
+                timestamp,
+                block_number,
+            )
+            .await;
+        }
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/contracts/{}", AccountId32::new([1; 32])))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, {
+            "node": "test",
+            "node_id": node.id,
+            "first_seen_at": 100,
+            "first_seen_block": 10,
         })
     }
 
@@ -216,4 +327,36 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn astar_addresses() {
+        let db = create_database().await;
+
+        create_test_env(&db, Some(ASTAR_PREFIX as i16)).await;
+
+        let address = ss58_address(&AccountId32::new([1; 32]), Some(ASTAR_PREFIX));
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/contracts/{address}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let owner = ss58_address(&AccountId32::new([2; 32]), Some(ASTAR_PREFIX));
+
+        assert_json!(response.json().await, {
+            "node": "test",
+            "code_hash": hex::encode([0; 32]),
+            "owner": owner.as_str(),
+        });
+
+        let (account, format) = AccountId32::from_ss58check_with_version(&owner).unwrap();
+        assert_eq!(account, AccountId32::new([2; 32]));
+        assert_eq!(u16::from(format), ASTAR_PREFIX);
+    }
 }
@@ -0,0 +1,581 @@
+use std::sync::Arc;
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use common::{
+    config::{default_estimate_rate_limit, Config},
+    rpc::{
+        pallet_contracts_primitives::StorageDeposit, parity_scale_codec::Encode,
+        sp_core::ByteArray, substrate_api_client,
+    },
+};
+use db::{
+    contract, node, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::{
+    auth::AuthenticatedUserId, contract_rpc::ContractRpc, rate_limit::RateLimiter,
+    schema::example_error,
+};
+
+use super::WrappedAccountId32;
+
+/// Errors that may occur during the contract call estimation request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum ContractEstimateError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Substrate RPC-related error.
+    #[status(StatusCode::BAD_GATEWAY)]
+    #[display(fmt = "substrate rpc error: {:?}", _0)]
+    Rpc(#[error(ignore)] substrate_api_client::Error),
+
+    /// Contract call was rejected by the node.
+    ///
+    /// Contains hex-encoded SCALE representation of the dispatch error,
+    /// which includes module error bytes.
+    #[status(StatusCode::BAD_GATEWAY)]
+    #[display(fmt = "contract call failed: 0x{}", _0)]
+    #[from(ignore)]
+    CallFailed(#[error(ignore)] String),
+
+    /// The requested contract was not found.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "contract not found")]
+    ContractNotFound,
+
+    /// Both the node identifier and node name were provided.
+    #[status(StatusCode::BAD_REQUEST)]
+    #[display(fmt = "only one of node_id or node_name can be provided")]
+    InvalidNodeSelection,
+
+    /// Contract was discovered on multiple nodes, while no node was selected.
+    #[status(StatusCode::BAD_REQUEST)]
+    #[display(fmt = "contract exists on multiple nodes, select one with node_id or node_name")]
+    AmbiguousNode,
+
+    /// User exceeded the allowed count of estimation requests.
+    #[status(StatusCode::TOO_MANY_REQUESTS)]
+    #[display(fmt = "too many estimation requests")]
+    TooManyRequests,
+}
+
+/// JSON request body.
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct ContractEstimateRequest {
+    /// Hex-encoded call data, which consists of the message selector
+    /// and SCALE-encoded message arguments.
+    #[schemars(example = "crate::schema::example_call_data", with = "String")]
+    #[serde(deserialize_with = "deserialize_call_data")]
+    data: Vec<u8>,
+
+    /// Identifier of the node used to dry-run the call.
+    ///
+    /// Mutually exclusive with `node_name`.
+    #[serde(default)]
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    node_id: Option<i64>,
+
+    /// Name of the node used to dry-run the call, as returned by the `/nodes` route.
+    ///
+    /// Mutually exclusive with `node_id`.
+    #[serde(default)]
+    #[schemars(example = "crate::schema::example_node")]
+    node_name: Option<String>,
+}
+
+/// Deserialize hex-encoded call data with an optional `0x` prefix.
+fn deserialize_call_data<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let data = String::deserialize(deserializer)?;
+
+    hex::decode(data.strip_prefix("0x").unwrap_or(&data))
+        .map_err(|_| de::Error::custom("invalid hex-encoded call data"))
+}
+
+/// Two-dimensional weight of a contract call.
+#[derive(Serialize, JsonSchema)]
+pub struct GasData {
+    /// Computational time used during the call execution.
+    #[schemars(example = "example_ref_time")]
+    pub ref_time: u64,
+
+    /// Size of the storage proof used during the call execution.
+    #[schemars(example = "example_proof_size")]
+    pub proof_size: u64,
+}
+
+/// Storage deposit, that is either charged from or refunded to the caller.
+///
+/// Amounts are encoded as decimal strings, since they may exceed the safe integer range
+/// of JSON number parsers.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageDepositData {
+    /// Storage deposit charged from the caller.
+    Charge(String),
+
+    /// Storage deposit refunded to the caller.
+    Refund(String),
+}
+
+/// Contract call estimation response.
+#[derive(Serialize, JsonSchema)]
+pub struct EstimateData {
+    /// Weight consumed during the call execution.
+    pub gas_consumed: GasData,
+
+    /// Weight required to execute the call, which should be used as a gas limit.
+    pub gas_required: GasData,
+
+    /// Storage deposit required to execute the call.
+    pub storage_deposit: StorageDepositData,
+}
+
+/// Generate example reference time for OAPI documentation.
+fn example_ref_time() -> u64 {
+    1_000_000_000
+}
+
+/// Generate example proof size for OAPI documentation.
+fn example_proof_size() -> u64 {
+    65_536
+}
+
+/// Generate OAPI documentation for the [`estimate`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Estimate gas and storage deposit of a contract call.")
+        .description(
+            r#"Provided call data is used to dry-run the contract call with the node
+on which the contract was discovered. Use the returned `gas_required` value as a gas limit
+of the actual call.
+
+If the contract was discovered on multiple nodes, select one of them either by its identifier
+with the `node_id` field, or by its name with the `node_name` field."#,
+        )
+        .response_with::<200, Json<EstimateData>, _>(|op| {
+            op.description("Contract call estimation response.")
+                .example(EstimateData {
+                    gas_consumed: GasData {
+                        ref_time: example_ref_time(),
+                        proof_size: example_proof_size(),
+                    },
+                    gas_required: GasData {
+                        ref_time: example_ref_time(),
+                        proof_size: example_proof_size(),
+                    },
+                    storage_deposit: StorageDepositData::Charge(String::from("0")),
+                })
+        })
+        .response_with::<400, Json<Value>, _>(|op| {
+            op.description(
+                "Contract exists on multiple nodes and no node was selected, \
+                or both of the node fields were provided.",
+            )
+            .example(example_error(ContractEstimateError::AmbiguousNode))
+        })
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description("Provided contract account was not found on the selected node.")
+                .example(example_error(ContractEstimateError::ContractNotFound))
+        })
+        .response_with::<429, Json<Value>, _>(|op| {
+            op.description("Too many estimation requests were sent during the last minute.")
+                .example(example_error(ContractEstimateError::TooManyRequests))
+        })
+        .response_with::<502, Json<Value>, _>(|op| {
+            op.description(
+                "Node was unavailable or rejected the contract call. \
+                Rejected calls include hex-encoded dispatch error.",
+            )
+            .example(example_error(ContractEstimateError::CallFailed(
+                String::from("03040b000000"),
+            )))
+        })
+}
+
+/// Contract call estimation request handler.
+pub(super) async fn estimate(
+    Path(account): Path<WrappedAccountId32>,
+    Extension(current_user): Extension<AuthenticatedUserId>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(rate_limiter): Extension<RateLimiter>,
+    Extension(rpc): Extension<Arc<dyn ContractRpc>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Json(request): Json<ContractEstimateRequest>,
+) -> Result<Json<EstimateData>, ContractEstimateError> {
+    let limit = config
+        .server
+        .as_ref()
+        .map_or_else(default_estimate_rate_limit, |server| {
+            server.estimate_rate_limit
+        });
+
+    if !rate_limiter.try_acquire(current_user.id(), limit) {
+        return Err(ContractEstimateError::TooManyRequests);
+    }
+
+    let query = contract::Entity::find()
+        .select_only()
+        .column(node::Column::Url)
+        .inner_join(node::Entity)
+        .filter(contract::Column::Address.eq(account.0.as_slice()));
+
+    let query = match (request.node_id, request.node_name) {
+        (Some(node_id), None) => query.filter(node::Column::Id.eq(node_id)),
+        (None, Some(node_name)) => query.filter(node::Column::Name.eq(node_name)),
+        (None, None) => query,
+        (Some(_), Some(_)) => return Err(ContractEstimateError::InvalidNodeSelection),
+    };
+
+    // Two rows are enough to detect contracts discovered on multiple nodes.
+    let mut urls = query.limit(2).into_tuple::<String>().all(&*db).await?;
+
+    let url = match (urls.pop(), urls.is_empty()) {
+        (Some(url), true) => url,
+        (Some(_), false) => return Err(ContractEstimateError::AmbiguousNode),
+        (None, _) => return Err(ContractEstimateError::ContractNotFound),
+    };
+
+    let result = rpc.call_contract(url, account.0, request.data).await?;
+
+    if let Err(error) = result.result {
+        return Err(ContractEstimateError::CallFailed(hex::encode(
+            error.encode(),
+        )));
+    }
+
+    let storage_deposit = match result.storage_deposit {
+        StorageDeposit::Charge(amount) => StorageDepositData::Charge(amount.to_string()),
+        StorageDeposit::Refund(amount) => StorageDepositData::Refund(amount.to_string()),
+    };
+
+    Ok(Json(EstimateData {
+        gas_consumed: GasData {
+            ref_time: result.gas_consumed.ref_time(),
+            proof_size: result.gas_consumed.proof_size(),
+        },
+        gas_required: GasData {
+            ref_time: result.gas_required.ref_time(),
+            proof_size: result.gas_required.proof_size(),
+        },
+        storage_deposit,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+        contract_rpc::{ContractRpc, ExecResult},
+        testing::{create_database, RequestBodyExt, ResponseBodyExt},
+    };
+
+    use assert_json::assert_json;
+    use axum::{
+        async_trait,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::{
+        config::Config,
+        rpc::{
+            parity_scale_codec::{Compact, Decode, Encode},
+            sp_core::crypto::AccountId32,
+            substrate_api_client,
+        },
+    };
+    use db::{code, contract, node, token, user, ActiveValue, DatabaseConnection, EntityTrait};
+    use serde_json::{json, Value};
+    use tower::{Service, ServiceExt};
+
+    /// [`ContractRpc`] implementation, that returns a canned SCALE-encoded exec result
+    /// and records the received call data.
+    struct CannedRpc {
+        /// SCALE-encoded exec result.
+        result: Vec<u8>,
+
+        /// Call data received by the last call.
+        data: Mutex<Option<Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl ContractRpc for CannedRpc {
+        async fn call_contract(
+            &self,
+            url: String,
+            contract: AccountId32,
+            data: Vec<u8>,
+        ) -> Result<ExecResult, substrate_api_client::Error> {
+            assert_eq!(url, "ws://localhost:9944");
+            assert_eq!(contract, AccountId32::new([1; 32]));
+
+            *self.data.lock().unwrap() = Some(data);
+
+            Ok(ExecResult::decode(&mut &*self.result)?)
+        }
+    }
+
+    /// Encode an exec result with the provided call result.
+    fn exec_result<R: Encode>(result: R) -> Vec<u8> {
+        (
+            // Consumed weight.
+            Compact(100u64),
+            Compact(10u64),
+            // Required weight.
+            Compact(200u64),
+            Compact(20u64),
+            // Charged storage deposit.
+            1u8,
+            5u128,
+            // Debug message.
+            Vec::<u8>::new(),
+            result,
+            // Events.
+            None::<Vec<()>>,
+        )
+            .encode()
+    }
+
+    /// Create a user with an authentication token and a contract deployed on a test node.
+    async fn create_test_env(db: &DatabaseConnection) -> String {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let (model, token) = token::generate_token(user.id);
+
+        token::Entity::insert(model)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert token");
+
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert node");
+
+        code::Entity::insert(code::ActiveModel {
+            hash: ActiveValue::Set(vec![0; 32]),
+            code: ActiveValue::Set(vec![1, 2, 3]),
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert code");
+
+        contract::Entity::insert(contract::ActiveModel {
+            node_id: ActiveValue::Set(node.id),
+            code_hash: ActiveValue::Set(vec![0; 32]),
+            address: ActiveValue::Set(vec![1; 32]),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert contract");
+
+        token
+    }
+
+    /// Create an estimation request for the provided contract account.
+    fn request(token: &str, account: [u8; 32]) -> Request<Body> {
+        request_with_body(token, account, json!({ "data": "0x633aa551" }))
+    }
+
+    /// Create an estimation request for the provided contract account with a custom body.
+    fn request_with_body(token: &str, account: [u8; 32], body: Value) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/contracts/{}/estimate", AccountId32::new(account)))
+            .header("Authorization", format!("Bearer {token}"))
+            .header("Content-Type", "application/json")
+            .body(Body::from_json(body))
+            .unwrap()
+    }
+
+    /// Create [`CannedRpc`] with the provided SCALE-encoded exec result.
+    fn canned_rpc(result: Vec<u8>) -> Arc<CannedRpc> {
+        Arc::new(CannedRpc {
+            result,
+            data: Mutex::new(None),
+        })
+    }
+
+    #[tokio::test]
+    async fn estimate() {
+        let db = create_database().await;
+        let token = create_test_env(&db).await;
+
+        let rpc = canned_rpc(exec_result(Ok::<_, ()>((0u32, Vec::<u8>::new()))));
+
+        let response =
+            crate::app_router_with_rpc(Arc::new(db), Arc::new(Config::for_tests()), rpc.clone())
+                .oneshot(request(&token, [1; 32]))
+                .await
+                .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, {
+            "gas_consumed": {
+                "ref_time": 100,
+                "proof_size": 10,
+            },
+            "gas_required": {
+                "ref_time": 200,
+                "proof_size": 20,
+            },
+            "storage_deposit": {
+                "charge": "5",
+            },
+        });
+
+        assert_eq!(
+            *rpc.data.lock().unwrap(),
+            Some(vec![0x63, 0x3a, 0xa5, 0x51])
+        );
+    }
+
+    #[tokio::test]
+    async fn module_error() {
+        let db = create_database().await;
+        let token = create_test_env(&db).await;
+
+        // `DispatchError::Module` with module index and error bytes.
+        let rpc = canned_rpc(exec_result(Err::<(u32, Vec<u8>), _>((
+            3u8,
+            4u8,
+            [11u8, 0, 0, 0],
+        ))));
+
+        let response = crate::app_router_with_rpc(Arc::new(db), Arc::new(Config::for_tests()), rpc)
+            .oneshot(request(&token, [1; 32]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        assert_json!(response.json().await, {
+            "code": 502,
+            "error": "contract call failed: 0x03040b000000",
+        });
+    }
+
+    #[tokio::test]
+    async fn unknown_contract() {
+        let db = create_database().await;
+        let token = create_test_env(&db).await;
+
+        let rpc = canned_rpc(exec_result(Ok::<_, ()>((0u32, Vec::<u8>::new()))));
+
+        let response =
+            crate::app_router_with_rpc(Arc::new(db), Arc::new(Config::for_tests()), rpc.clone())
+                .oneshot(request(&token, [2; 32]))
+                .await
+                .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(rpc.data.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn node_selection() {
+        let db = create_database().await;
+        let token = create_test_env(&db).await;
+
+        // The same contract address is discovered on another network.
+        let other = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("other")),
+            url: ActiveValue::Set(String::from("ws://other:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_with_returning(&db)
+        .await
+        .expect("unable to insert node");
+
+        contract::Entity::insert(contract::ActiveModel {
+            node_id: ActiveValue::Set(other.id),
+            code_hash: ActiveValue::Set(vec![0; 32]),
+            address: ActiveValue::Set(vec![1; 32]),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert contract");
+
+        let rpc = canned_rpc(exec_result(Ok::<_, ()>((0u32, Vec::<u8>::new()))));
+        let mut service =
+            crate::app_router_with_rpc(Arc::new(db), Arc::new(Config::for_tests()), rpc.clone());
+
+        for (body, status) in [
+            (json!({ "data": "0x633aa551" }), StatusCode::BAD_REQUEST),
+            (
+                json!({ "data": "0x633aa551", "node_id": other.id, "node_name": "test" }),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                json!({ "data": "0x633aa551", "node_name": "unknown" }),
+                StatusCode::NOT_FOUND,
+            ),
+            (
+                json!({ "data": "0x633aa551", "node_id": other.id + 1 }),
+                StatusCode::NOT_FOUND,
+            ),
+        ] {
+            let response = service
+                .call(request_with_body(&token, [1; 32], body.clone()))
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), status, "{body}");
+        }
+
+        assert!(rpc.data.lock().unwrap().is_none());
+
+        // Canned RPC only accepts calls to the URL of the test node.
+        let response = service
+            .call(request_with_body(
+                &token,
+                [1; 32],
+                json!({ "data": "0x633aa551", "node_name": "test" }),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(rpc.data.lock().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn rate_limit() {
+        let db = create_database().await;
+        let token = create_test_env(&db).await;
+
+        let mut config = Config::for_tests();
+        config.server.as_mut().unwrap().estimate_rate_limit = 1;
+
+        let rpc = canned_rpc(exec_result(Ok::<_, ()>((0u32, Vec::<u8>::new()))));
+
+        let mut service = crate::app_router_with_rpc(Arc::new(db), Arc::new(config), rpc);
+
+        let response = service.call(request(&token, [1; 32])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = service.call(request(&token, [1; 32])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -9,7 +9,7 @@ use axum_derive_error::ErrorResponse;
 use common::rpc::sp_core::ByteArray;
 use db::{
     event, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PrimitiveDateTime, QueryFilter,
-    QueryOrder, QuerySelect,
+    QueryOrder, QuerySelect, QueryTrait,
 };
 use derive_more::{Display, Error, From};
 use futures_util::TryStreamExt;
@@ -17,6 +17,13 @@ use schemars::JsonSchema;
 use serde::Serialize;
 
 use super::WrappedAccountId32;
+use crate::{
+    pagination::CursorPagination,
+    schema::{
+        example_block_number, example_contract_event_body, example_database_identifier,
+        example_timestamp,
+    },
+};
 
 /// Errors that may occur during the contract event list request handling.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -29,6 +36,10 @@ pub(super) enum ContractEventsError {
 /// A single contract event.
 #[derive(Serialize, JsonSchema)]
 pub struct ContractEvent {
+    /// Event identifier, which can be used as a pagination cursor.
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    id: i64,
+
     /// Serialized JSON body of a contract event.
     #[schemars(example = "crate::schema::example_event_body")]
     body: String,
@@ -36,6 +47,25 @@ pub struct ContractEvent {
     /// Timestamp of a block in which the event was discovered.
     #[schemars(example = "crate::schema::example_timestamp")]
     timestamp: i64,
+
+    /// Number of a block in which the event was discovered.
+    ///
+    /// This field is only available for events discovered after
+    /// block numbers were recorded by an event client.
+    #[schemars(example = "crate::schema::example_block_number")]
+    block_number: Option<i64>,
+}
+
+/// Contract event list response.
+#[derive(Serialize, JsonSchema)]
+pub struct ContractEventsResponse {
+    /// Contract events.
+    events: Vec<ContractEvent>,
+
+    /// Cursor of the next page, if there may be more events available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    next_cursor: Option<i64>,
 }
 
 /// Generate OAPI documentation for the [`events`] handler.
@@ -43,10 +73,24 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Get events related to the contract account.")
         .description(
             r#"Smart contract events are discovered
-only after the initial activation of an event client."#,
+only after the initial activation of an event client.
+
+Events are returned from the oldest to the newest one, ordered by their identifiers.
+If the page is full, the returned `next_cursor` value should be passed using the `after`
+query parameter to get the next page. To poll for new events, pass the identifier
+of the last received event using the same parameter."#,
         )
-        .response_with::<200, Json<Vec<ContractEvent>>, _>(|op| {
+        .response_with::<200, Json<ContractEventsResponse>, _>(|op| {
             op.description("Event list response.")
+                .example(ContractEventsResponse {
+                    events: vec![ContractEvent {
+                        id: example_database_identifier(),
+                        body: example_contract_event_body(),
+                        timestamp: example_timestamp(),
+                        block_number: example_block_number(),
+                    }],
+                    next_cursor: Some(example_database_identifier()),
+                })
         })
 }
 
@@ -54,24 +98,40 @@ only after the initial activation of an event client."#,
 pub(super) async fn events(
     Path(account): Path<WrappedAccountId32>,
     State(db): State<Arc<DatabaseConnection>>,
-) -> Result<Json<Vec<ContractEvent>>, ContractEventsError> {
-    let model = event::Entity::find()
+    pagination: CursorPagination,
+) -> Result<Json<ContractEventsResponse>, ContractEventsError> {
+    let events: Vec<_> = event::Entity::find()
         .select_only()
-        .columns([event::Column::Body, event::Column::BlockTimestamp])
+        .columns([
+            event::Column::Id,
+            event::Column::Body,
+            event::Column::BlockTimestamp,
+            event::Column::BlockNumber,
+        ])
         .filter(event::Column::Account.eq(account.0.as_slice()))
-        .order_by_desc(event::Column::BlockTimestamp)
-        .limit(25)
-        .into_tuple::<(String, PrimitiveDateTime)>()
+        .apply_if(pagination.after(), |query, after| {
+            query.filter(event::Column::Id.gt(after))
+        })
+        .order_by_asc(event::Column::Id)
+        .limit(pagination.limit())
+        .into_tuple::<(i64, event::EventBody, PrimitiveDateTime, Option<i64>)>()
         .stream(&*db)
         .await?
-        .map_ok(|(body, date)| ContractEvent {
-            body,
+        .map_ok(|(id, body, date, block_number)| ContractEvent {
+            id,
+            body: serde_json::to_string(&body).expect("event body is always serializable"),
             timestamp: date.assume_utc().unix_timestamp(),
+            block_number,
         })
         .try_collect()
         .await?;
 
-    Ok(Json(model))
+    let next_cursor = pagination.next_cursor(&events, |event| event.id);
+
+    Ok(Json(ContractEventsResponse {
+        events,
+        next_cursor,
+    }))
 }
 
 #[cfg(test)]
@@ -80,7 +140,7 @@ mod tests {
 
     use crate::testing::{create_database, ResponseBodyExt};
 
-    use assert_json::assert_json;
+    use assert_json::{assert_json, validators};
     use axum::{body::Body, http::Request};
     use common::{config::Config, rpc::sp_core::crypto::AccountId32};
     use db::{
@@ -125,9 +185,9 @@ mod tests {
             node_id: ActiveValue::Set(node.id),
             account: ActiveValue::Set(vec![1; 32]),
             event_type: ActiveValue::Set(event::EventType::Instantiation),
-            body: ActiveValue::Set(
-                serde_json::to_string(&event::EventBody::Instantiation).unwrap(),
-            ),
+            body: ActiveValue::Set(event::EventBody::Instantiation {
+                deployer: AccountId32::new([2; 32]).to_string(),
+            }),
             block_timestamp: ActiveValue::Set(PrimitiveDateTime::new(
                 datetime.date(),
                 datetime.time(),
@@ -156,12 +216,219 @@ mod tests {
             .await
             .unwrap();
 
-        assert_json!(response.json().await, [
-            {
-                "body": r#""Instantiation""#,
-                "timestamp": 0
-            }
-        ])
+        let response = response.json().await;
+
+        assert_json!(response.clone(), {
+            "events": [
+                {
+                    "id": 1,
+                    "body": format!(
+                        r#"{{"Instantiation":{{"deployer":"{}"}}}}"#,
+                        AccountId32::new([2; 32])
+                    ),
+                    "timestamp": 0,
+                    "block_number": validators::null(),
+                }
+            ]
+        });
+        assert_eq!(response.get("next_cursor"), None);
+    }
+
+    #[tokio::test]
+    async fn code_hash_update() {
+        let db = create_database().await;
+
+        create_test_env(&db).await;
+
+        let node_id = node::Entity::find()
+            .one(&db)
+            .await
+            .expect("unable to get node")
+            .expect("node not found")
+            .id;
+
+        let datetime = OffsetDateTime::from_unix_timestamp(0).expect("invalid date");
+
+        event::Entity::insert(event::ActiveModel {
+            node_id: ActiveValue::Set(node_id),
+            account: ActiveValue::Set(vec![1; 32]),
+            event_type: ActiveValue::Set(event::EventType::CodeHashUpdate),
+            body: ActiveValue::Set(event::EventBody::CodeHashUpdate {
+                new_code_hash: hex::encode([2; 32]),
+            }),
+            block_timestamp: ActiveValue::Set(PrimitiveDateTime::new(
+                datetime.date(),
+                datetime.time(),
+            )),
+            block_number: ActiveValue::Set(Some(10)),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert an event");
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!(
+                        "/contracts/events/{}?after=1&limit=1",
+                        AccountId32::new([1; 32])
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let body = format!(
+            r#"{{"CodeHashUpdate":{{"new_code_hash":"{}"}}}}"#,
+            hex::encode([2; 32])
+        );
+
+        assert_json!(response.json().await, {
+            "events": [
+                {
+                    "id": 2,
+                    "body": body,
+                    "timestamp": 0,
+                    "block_number": 10,
+                }
+            ],
+            "next_cursor": 2,
+        })
+    }
+
+    #[tokio::test]
+    async fn cursor_pagination() {
+        let db = create_database().await;
+
+        create_test_env(&db).await;
+
+        let node_id = node::Entity::find()
+            .one(&db)
+            .await
+            .expect("unable to get node")
+            .expect("node not found")
+            .id;
+
+        let datetime = OffsetDateTime::from_unix_timestamp(0).expect("invalid date");
+
+        event::Entity::insert_many((0..2).map(|_| event::ActiveModel {
+            node_id: ActiveValue::Set(node_id),
+            account: ActiveValue::Set(vec![1; 32]),
+            event_type: ActiveValue::Set(event::EventType::Termination),
+            body: ActiveValue::Set(event::EventBody::Termination),
+            block_timestamp: ActiveValue::Set(PrimitiveDateTime::new(
+                datetime.date(),
+                datetime.time(),
+            )),
+            ..Default::default()
+        }))
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert events");
+
+        let db = Arc::new(db);
+
+        let mut pages = Vec::new();
+        let mut cursors = Vec::new();
+
+        for query in ["", "?limit=2", "?after=2&limit=2", "?after=3"] {
+            let response = crate::app_router(db.clone(), Arc::new(Config::for_tests()))
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri(format!(
+                            "/contracts/events/{}{query}",
+                            AccountId32::new([1; 32])
+                        ))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let page = response.json().await;
+
+            let ids: Vec<i64> = page["events"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|event| event["id"].as_i64().unwrap())
+                .collect();
+
+            pages.push(ids);
+            cursors.push(page.get("next_cursor").and_then(|cursor| cursor.as_i64()));
+        }
+
+        assert_eq!(pages[0], [1, 2, 3]);
+        assert_eq!(pages[1], [1, 2]);
+        assert_eq!(pages[2], [3]);
+        assert!(pages[3].is_empty());
+
+        assert_eq!(cursors, [None, Some(2), None, None]);
+    }
+
+    #[tokio::test]
+    async fn resume_from_cursor() {
+        let db = create_database().await;
+
+        create_test_env(&db).await;
+
+        let db = Arc::new(db);
+
+        let request = |after: i64| {
+            crate::app_router(db.clone(), Arc::new(Config::for_tests())).oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!(
+                        "/contracts/events/{}?after={after}",
+                        AccountId32::new([1; 32])
+                    ))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        assert_json!(request(1).await.unwrap().json().await, { "events": [] });
+
+        let node_id = node::Entity::find()
+            .one(&*db)
+            .await
+            .expect("unable to get node")
+            .expect("node not found")
+            .id;
+
+        let datetime = OffsetDateTime::from_unix_timestamp(10).expect("invalid date");
+
+        event::Entity::insert(event::ActiveModel {
+            node_id: ActiveValue::Set(node_id),
+            account: ActiveValue::Set(vec![1; 32]),
+            event_type: ActiveValue::Set(event::EventType::Termination),
+            body: ActiveValue::Set(event::EventBody::Termination),
+            block_timestamp: ActiveValue::Set(PrimitiveDateTime::new(
+                datetime.date(),
+                datetime.time(),
+            )),
+            block_number: ActiveValue::Set(Some(20)),
+            ..Default::default()
+        })
+        .exec_without_returning(&*db)
+        .await
+        .expect("unable to insert an event");
+
+        // Polling with the last received identifier returns only the new event.
+        assert_json!(request(1).await.unwrap().json().await, {
+            "events": [
+                {
+                    "id": 2,
+                    "body": r#""Termination""#,
+                    "timestamp": 10,
+                    "block_number": 20,
+                }
+            ]
+        });
     }
 
     #[tokio::test]
@@ -179,6 +446,6 @@ mod tests {
             .await
             .unwrap();
 
-        assert_json!(response.json().await, [])
+        assert_json!(response.json().await, { "events": [] })
     }
 }
@@ -1,28 +1,94 @@
 /// Smart contract details route.
 mod details;
 
+/// Smart contract call estimation route.
+mod estimate;
+
 /// Smart contract events list route.
 mod events;
 
 use std::sync::Arc;
 
-use aide::axum::{routing::get_with, ApiRouter};
-use common::rpc::sp_core::crypto::AccountId32;
+use aide::axum::{
+    routing::{get_with, post_with},
+    ApiRouter,
+};
+use axum::middleware::from_fn_with_state;
+use common::{
+    config::Config,
+    rpc::sp_core::crypto::{AccountId32, Ss58Codec},
+};
 use db::DatabaseConnection;
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{de, Deserialize, Deserializer};
+
+use crate::auth;
 
 /// [`AccountId32`] wrapper for OAPI documentation purposes.
+///
+/// Addresses are accepted with any valid SS58 prefix, since the stored
+/// account identifiers do not depend on the network address format.
 #[derive(Deserialize, JsonSchema)]
 #[serde(transparent)]
 struct WrappedAccountId32(
-    #[schemars(example = "crate::schema::example_account", with = "String")] pub AccountId32,
+    #[schemars(example = "crate::schema::example_account", with = "String")]
+    #[serde(deserialize_with = "deserialize_account")]
+    pub AccountId32,
 );
 
+/// Deserialize an [`AccountId32`] from an SS58 address with any prefix.
+fn deserialize_account<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<AccountId32, D::Error> {
+    let address = String::deserialize(deserializer)?;
+
+    AccountId32::from_ss58check_with_version(&address)
+        .map(|(account, _)| account)
+        .map_err(|_| de::Error::custom("invalid SS58 address"))
+}
+
 /// Create an [`ApiRouter`] that provides an API server with contract information routes.
-pub(crate) fn routes() -> ApiRouter<Arc<DatabaseConnection>> {
+pub(crate) fn routes(
+    database: Arc<DatabaseConnection>,
+    config: Arc<Config>,
+) -> ApiRouter<Arc<DatabaseConnection>> {
+    let private_routes = ApiRouter::new()
+        .api_route(
+            "/:account/estimate",
+            post_with(estimate::estimate, estimate::docs),
+        )
+        .route_layer(from_fn_with_state(
+            (database, config),
+            auth::require_authentication::<false, false, false, _>,
+        ))
+        .with_path_items(|op| op.security_requirement("Authentication token"));
+
     ApiRouter::new()
         .api_route("/events/:account", get_with(events::events, events::docs))
         .api_route("/:account", get_with(details::details, details::docs))
+        .merge(private_routes)
         .with_path_items(|op| op.tag("Contract management"))
 }
+
+#[cfg(test)]
+mod tests {
+    use common::rpc::{sp_core::crypto::AccountId32, ss58_address};
+    use serde_json::json;
+
+    use super::WrappedAccountId32;
+
+    #[test]
+    fn any_ss58_prefix() {
+        let account = AccountId32::new([1; 32]);
+
+        // Generic Substrate, Polkadot, Astar and an unregistered custom prefix.
+        for prefix in [None, Some(0), Some(5), Some(16383)] {
+            let address = ss58_address(&account, prefix);
+            let wrapped: WrappedAccountId32 = serde_json::from_value(json!(address)).unwrap();
+
+            assert_eq!(wrapped.0, account);
+        }
+
+        assert!(serde_json::from_value::<WrappedAccountId32>(json!("invalid")).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+use std::{collections::HashSet, mem, sync::Arc};
+
+use aide::openapi::{OpenApi, Operation, PathItem, ReferenceOr};
+use axum::{extract::Query, Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Prefix of references to schemas stored inside of the document components.
+const SCHEMA_REFERENCE_PREFIX: &str = "#/components/schemas/";
+
+/// Query string that can be used to filter the OpenAPI document.
+#[derive(Deserialize)]
+pub(super) struct ApiDocumentQuery {
+    /// Comma-separated list of tags.
+    ///
+    /// If provided, only operations with at least one of these tags are returned.
+    #[serde(default)]
+    tags: Option<String>,
+
+    /// Whether to exclude deprecated operations.
+    #[serde(default)]
+    exclude_deprecated: bool,
+}
+
+/// OpenAPI document request handler.
+///
+/// The full document is returned unless any filters are provided, in which case
+/// schemas that are no longer referenced by the remaining operations are removed as well.
+pub(super) async fn document(
+    Extension(oapi): Extension<Arc<OpenApi>>,
+    Query(query): Query<ApiDocumentQuery>,
+) -> Json<Arc<OpenApi>> {
+    let tags = query
+        .tags
+        .as_deref()
+        .map(|tags| {
+            tags.split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .collect::<HashSet<_>>()
+        })
+        .filter(|tags| !tags.is_empty());
+
+    if tags.is_none() && !query.exclude_deprecated {
+        return Json(oapi);
+    }
+
+    let mut api = OpenApi::clone(&oapi);
+
+    retain_operations(&mut api, |operation| {
+        let tagged = match &tags {
+            Some(tags) => operation.tags.iter().any(|tag| tags.contains(tag.as_str())),
+            None => true,
+        };
+
+        tagged && !(query.exclude_deprecated && operation.deprecated)
+    });
+
+    if let Some(tags) = &tags {
+        api.tags.retain(|tag| tags.contains(tag.name.as_str()));
+    }
+
+    remove_orphaned_schemas(&mut api);
+
+    Json(Arc::new(api))
+}
+
+/// Get all operations of the provided path item.
+fn operations_mut(item: &mut PathItem) -> [&mut Option<Operation>; 8] {
+    [
+        &mut item.get,
+        &mut item.put,
+        &mut item.post,
+        &mut item.delete,
+        &mut item.options,
+        &mut item.head,
+        &mut item.patch,
+        &mut item.trace,
+    ]
+}
+
+/// Remove operations that do not match the provided predicate,
+/// alongside with paths that have no operations left.
+fn retain_operations<F: Fn(&Operation) -> bool>(api: &mut OpenApi, predicate: F) {
+    let Some(paths) = &mut api.paths else {
+        return;
+    };
+
+    paths.paths.retain(|_, item| {
+        let ReferenceOr::Item(item) = item else {
+            return true;
+        };
+
+        let mut retained = false;
+
+        for operation in operations_mut(item) {
+            if matches!(operation, Some(operation) if !predicate(operation)) {
+                *operation = None;
+            }
+
+            retained |= operation.is_some();
+        }
+
+        retained
+    });
+}
+
+/// Remove component schemas, which are not referenced by the document, either directly
+/// or through other schemas.
+///
+/// Other component types are left as-is, since they are not generated from handler types.
+fn remove_orphaned_schemas(api: &mut OpenApi) {
+    let Some(components) = &mut api.components else {
+        return;
+    };
+
+    let mut schemas = mem::take(&mut components.schemas);
+
+    let mut referenced = HashSet::new();
+    collect_references(&to_value(&*api), &mut referenced);
+
+    let mut pending = referenced.iter().cloned().collect::<Vec<_>>();
+
+    while let Some(name) = pending.pop() {
+        let Some(schema) = schemas.get(&name) else {
+            continue;
+        };
+
+        let mut nested = HashSet::new();
+        collect_references(&to_value(schema), &mut nested);
+
+        for name in nested {
+            if referenced.insert(name.clone()) {
+                pending.push(name);
+            }
+        }
+    }
+
+    schemas.retain(|name, _| referenced.contains(name));
+
+    if let Some(components) = &mut api.components {
+        components.schemas = schemas;
+    }
+}
+
+/// Convert an OpenAPI document part into a JSON value.
+fn to_value<T: Serialize>(value: &T) -> Value {
+    // OpenAPI document parts contain only string map keys, so serialization cannot fail.
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+/// Collect names of component schemas referenced by the provided JSON value.
+fn collect_references(value: &Value, references: &mut HashSet<String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match value {
+                    Value::String(reference) if key == "$ref" => {
+                        if let Some(name) = reference.strip_prefix(SCHEMA_REFERENCE_PREFIX) {
+                            references.insert(String::from(name));
+                        }
+                    }
+                    value => collect_references(value, references),
+                }
+            }
+        }
+        Value::Array(array) => {
+            for value in array {
+                collect_references(value, references);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc};
+
+    use aide::openapi::{OpenApi, ReferenceOr};
+    use axum::{body::Body, http::Request, Extension};
+    use common::config::Config;
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use super::{collect_references, operations_mut, remove_orphaned_schemas, retain_operations};
+
+    /// Request the OpenAPI document with the provided query string.
+    async fn request_document(query: &str) -> (Value, Value) {
+        let db = Arc::new(create_database().await);
+
+        let mut api = OpenApi::default();
+        let router = crate::app_router(db, Arc::new(Config::for_tests()))
+            .finish_api_with(&mut api, crate::api_docs);
+
+        let full = serde_json::to_value(&api).unwrap();
+
+        let response = router
+            .layer(Extension(Arc::new(api)))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/docs/api.json{query}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        (response.json().await, full)
+    }
+
+    /// Get tags of all operations within the provided document.
+    fn operation_tags(document: &Value) -> Vec<Vec<String>> {
+        document["paths"]
+            .as_object()
+            .expect("no documented paths")
+            .values()
+            .flat_map(|item| item.as_object().unwrap().values())
+            .filter_map(|operation| operation.get("tags"))
+            .map(|tags| serde_json::from_value(tags.clone()).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn unfiltered() {
+        let (document, full) = request_document("").await;
+
+        assert_eq!(document, full);
+    }
+
+    #[tokio::test]
+    async fn filtered_by_tags() {
+        let (document, full) =
+            request_document("?tags=Build%20session%20management,File%20uploads").await;
+
+        let tags = operation_tags(&document);
+
+        assert!(!tags.is_empty());
+        assert!(tags.len() < operation_tags(&full).len());
+
+        for tags in tags {
+            assert!(tags
+                .iter()
+                .any(|tag| tag == "Build session management" || tag == "File uploads"));
+        }
+
+        let document_tags = document["tags"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tag| tag["name"].as_str().unwrap())
+            .collect::<HashSet<_>>();
+
+        assert_eq!(
+            document_tags,
+            HashSet::from(["Build session management", "File uploads"])
+        );
+
+        // Every remaining schema is still referenced, and all references can be resolved.
+        let schemas = document["components"]["schemas"]
+            .as_object()
+            .map(|schemas| schemas.keys().cloned().collect::<HashSet<_>>())
+            .unwrap_or_default();
+
+        let mut references = HashSet::new();
+        collect_references(&document, &mut references);
+
+        assert_eq!(schemas, references);
+    }
+
+    #[tokio::test]
+    async fn deprecated_operations() {
+        let db = Arc::new(create_database().await);
+
+        let mut api = OpenApi::default();
+        let _ = crate::app_router(db, Arc::new(Config::for_tests()))
+            .finish_api_with(&mut api, crate::api_docs);
+
+        let paths = &mut api.paths.as_mut().unwrap().paths;
+        let Some(ReferenceOr::Item(item)) = paths.get_mut("/buildSessions/logs/{id}") else {
+            panic!("build session logs route is not documented");
+        };
+
+        for operation in operations_mut(item).into_iter().flatten() {
+            operation.deprecated = true;
+        }
+
+        let path_count = paths.len();
+
+        retain_operations(&mut api, |operation| !operation.deprecated);
+        remove_orphaned_schemas(&mut api);
+
+        let paths = &api.paths.unwrap().paths;
+
+        assert_eq!(paths.len(), path_count - 1);
+        assert!(!paths.contains_key("/buildSessions/logs/{id}"));
+    }
+}
@@ -1,19 +1,79 @@
+/// OpenAPI document route.
+mod api;
+
 use std::sync::Arc;
 
 use aide::{
     axum::{routing::get, ApiRouter},
-    openapi::OpenApi,
     redoc::Redoc,
 };
-use axum::{Extension, Json};
 use db::DatabaseConnection;
 
 /// Create an [`ApiRouter`] that provides an API server with documentation routes.
 pub(crate) fn routes() -> ApiRouter<Arc<DatabaseConnection>> {
     ApiRouter::new()
         .route("/", Redoc::new("/docs/api.json").axum_route())
-        .route(
-            "/api.json",
-            get(|Extension(oapi): Extension<Arc<OpenApi>>| async move { Json(oapi) }),
-        )
+        .route("/api.json", get(api::document))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use aide::openapi::{OpenApi, ReferenceOr, StatusCode};
+    use common::config::Config;
+
+    use crate::testing::create_database;
+
+    #[tokio::test]
+    async fn response_examples() {
+        let db = Arc::new(create_database().await);
+
+        let mut api = OpenApi::default();
+        let _ = crate::app_router(db, Arc::new(Config::for_tests()))
+            .finish_api_with(&mut api, crate::api_docs);
+
+        let paths = api.paths.expect("no documented paths");
+        let mut operation_count = 0;
+
+        for (path, item) in paths.paths {
+            let ReferenceOr::Item(item) = item else {
+                panic!("unexpected path reference for {path}");
+            };
+
+            let operations = [
+                item.get,
+                item.put,
+                item.post,
+                item.delete,
+                item.options,
+                item.head,
+                item.patch,
+                item.trace,
+            ];
+
+            for operation in operations.into_iter().flatten() {
+                operation_count += 1;
+
+                let response = operation
+                    .responses
+                    .and_then(|responses| responses.responses.get(&StatusCode::Code(200)).cloned())
+                    .unwrap_or_else(|| panic!("{path} has no documented 200 response"));
+
+                let ReferenceOr::Item(response) = response else {
+                    panic!("unexpected response reference for {path}");
+                };
+
+                // Empty and binary responses have nothing to provide an example for.
+                if let Some(media) = response.content.get("application/json") {
+                    assert!(
+                        media.example.is_some() || !media.examples.is_empty(),
+                        "{path} has no 200 response example"
+                    );
+                }
+            }
+        }
+
+        assert!(operation_count > 0);
+    }
 }
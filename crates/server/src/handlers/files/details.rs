@@ -4,7 +4,7 @@ use aide::{transform::TransformOperation, OperationIo};
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
 use db::{file, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect};
@@ -13,7 +13,11 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::schema::example_error;
+use crate::{
+    auth::AuthenticatedUserId,
+    schema::{example_error, example_files},
+    visibility,
+};
 
 /// Max count of files that can be fetched from the database.
 const MAX_FILES: u64 = 1000;
@@ -36,6 +40,7 @@ pub(super) enum DetailsResponse {
     /// Single-file contents request.
     File {
         /// Contents of a single file.
+        #[schemars(example = "crate::schema::example_file_text")]
         text: String,
     },
 
@@ -58,6 +63,11 @@ pub(super) enum DetailsError {
     #[status(StatusCode::NOT_FOUND)]
     #[display(fmt = "file not found")]
     FileNotFound,
+
+    /// The requested source code archive was not found or is not visible to the current user.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "source code not found")]
+    SourceCodeNotFound,
 }
 
 /// Generate OAPI documentation for the [`details`] handler.
@@ -65,11 +75,19 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Retrieve source code archive file details.")
         .description(
             r#"This route conditionally returns either a single file contents
-or a list of files contained within a provided source code archive."#,
+or a list of files contained within a provided source code archive.
+
+Files of private source code archives are available only to their owners,
+unless a contract built from them was discovered on-chain."#,
         )
-        .response::<200, Json<DetailsResponse>>()
+        .response_with::<200, Json<DetailsResponse>, _>(|op| {
+            op.description("File contents or file list response.")
+                .example(DetailsResponse::List {
+                    files: example_files(),
+                })
+        })
         .response_with::<404, Json<Value>, _>(|op| {
-            op.description("File not found.")
+            op.description("File or source code archive not found.")
                 .example(example_error(DetailsError::FileNotFound))
         })
 }
@@ -82,8 +100,15 @@ or a list of files contained within a provided source code archive."#,
 pub(super) async fn details(
     State(db): State<Arc<DatabaseConnection>>,
     Path(source_code_id): Path<i64>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
     Query(details): Query<DetailsQuery>,
 ) -> Result<Json<DetailsResponse>, DetailsError> {
+    let current_user = current_user.map(|Extension(user)| user);
+
+    if !visibility::source_code_visible(&*db, source_code_id, current_user).await? {
+        return Err(DetailsError::SourceCodeNotFound);
+    }
+
     let response = if let Some(file) = details.file {
         file::Entity::find()
             .select_only()
@@ -114,7 +139,7 @@ pub(super) async fn details(
 mod tests {
     use std::sync::Arc;
 
-    use crate::testing::{create_database, ResponseBodyExt};
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
 
     use assert_json::assert_json;
     use axum::{
@@ -219,4 +244,27 @@ mod tests {
             ]
         })
     }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = Arc::new(create_database().await);
+
+        let source_code_id = create_test_env(&db).await;
+        make_source_code_private(&db).await;
+
+        for query in ["", "?file=lib.rs"] {
+            let response = crate::app_router(db.clone(), Arc::new(Config::for_tests()))
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri(format!("/files/{source_code_id}{query}"))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+    }
 }
@@ -13,13 +13,20 @@ use aide::axum::{
     routing::{get_with, post_with},
     ApiRouter,
 };
+use axum::middleware::from_fn_with_state;
 use db::DatabaseConnection;
 
+use crate::auth;
+
 /// Create an [`ApiRouter`] that provides an API server with source code file handling routes.
-pub(crate) fn routes() -> ApiRouter<Arc<DatabaseConnection>> {
+pub(crate) fn routes(database: Arc<DatabaseConnection>) -> ApiRouter<Arc<DatabaseConnection>> {
+    let details_routes = ApiRouter::new()
+        .api_route("/:sourceCode", get_with(details::details, details::docs))
+        .route_layer(from_fn_with_state(database, auth::optional_authentication));
+
     ApiRouter::new()
         .api_route("/seal/:token", post_with(seal::seal, seal::docs))
         .api_route("/upload/:token", post_with(upload::upload, upload::docs))
-        .api_route("/:sourceCode", get_with(details::details, details::docs))
+        .merge(details_routes)
         .with_path_items(|op| op.tag("File uploads"))
 }
@@ -1,13 +1,20 @@
 use std::sync::Arc;
 
 use aide::{transform::TransformOperation, OperationIo};
-use axum::extract::{Path, State};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
 use axum_derive_error::ErrorResponse;
 use db::{
-    build_session_token, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
-    TransactionErrorExt, TransactionTrait,
+    build_session, build_session_token, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QuerySelect, TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
+use serde_json::Value;
+
+use crate::schema::example_error;
 
 /// Errors that may occur during the file upload sealing process.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -15,6 +22,11 @@ use derive_more::{Display, Error, From};
 pub(super) enum SealError {
     /// Database-related error.
     DatabaseError(DbErr),
+
+    /// The related build session is already finished.
+    #[status(StatusCode::FORBIDDEN)]
+    #[display(fmt = "invalid token provided")]
+    InvalidToken,
 }
 
 /// Generate OAPI documentation for the [`seal`] handler.
@@ -28,6 +40,10 @@ Make sure to always seal build session tokens
 to protect the database from malicious file uploads within a build session container."#,
         )
         .response::<200, ()>()
+        .response_with::<403, Json<Value>, _>(|op| {
+            op.description("Build session related to the provided token is already finished.")
+                .example(example_error(SealError::InvalidToken))
+        })
 }
 
 /// Seal the provided build session token to prevent further file uploads.
@@ -35,12 +51,28 @@ to protect the database from malicious file uploads within a build session conta
 /// After executing this route no additional files can be uploaded with the provided
 /// build session token, preventing any modifications from custom scripts that user may execute
 /// during the build process.
+///
+/// Tokens of finished build sessions are rejected, while unknown tokens are considered
+/// to be already sealed.
 pub(super) async fn seal(
     State(db): State<Arc<DatabaseConnection>>,
     Path(token): Path<String>,
 ) -> Result<(), SealError> {
     db.transaction(|txn| {
         Box::pin(async move {
+            let status = build_session_token::Entity::find()
+                .select_only()
+                .column(build_session::Column::Status)
+                .inner_join(build_session::Entity)
+                .filter(build_session_token::Column::Token.eq(token.as_str()))
+                .into_tuple::<build_session::Status>()
+                .one(txn)
+                .await?;
+
+            if matches!(status, Some(status) if status != build_session::Status::New) {
+                return Err(SealError::InvalidToken);
+            }
+
             build_session_token::Entity::delete_many()
                 .filter(build_session_token::Column::Token.eq(token))
                 .exec(txn)
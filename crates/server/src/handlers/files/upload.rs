@@ -8,13 +8,17 @@ use axum::{
 };
 use axum_derive_error::ErrorResponse;
 use db::{
-    build_session_token, file, sea_query::OnConflict, ActiveValue, ColumnTrait, DatabaseConnection,
-    DbErr, EntityTrait, QueryFilter, QuerySelect, TransactionErrorExt, TransactionTrait,
+    build_session, build_session_token, file, sea_query::OnConflict, ActiveValue, ColumnTrait,
+    DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect, TransactionErrorExt,
+    TransactionTrait,
 };
 use derive_more::{Display, Error, From};
 use serde_json::Value;
 
-use crate::schema::example_error;
+use crate::schema::{example_error, example_multipart_error};
+
+/// Max length of uploaded file names, in bytes.
+const MAX_FILE_NAME_LENGTH: usize = 512;
 
 /// Errors that may occur during the file upload process.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -27,7 +31,8 @@ pub(super) enum UploadFileError {
     #[status(StatusCode::BAD_REQUEST)]
     MultipartError(MultipartError),
 
-    /// Invalid build session token was provided.
+    /// Invalid build session token was provided,
+    /// or the related build session is already finished.
     #[status(StatusCode::FORBIDDEN)]
     #[display(fmt = "invalid token provided")]
     InvalidToken,
@@ -36,6 +41,11 @@ pub(super) enum UploadFileError {
     #[status(StatusCode::UNPROCESSABLE_ENTITY)]
     #[display(fmt = "no file upload was found")]
     NoFileUpload,
+
+    /// Uploaded file name is not a relative path inside of the source code archive.
+    #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+    #[display(fmt = "invalid file name")]
+    InvalidFileName,
 }
 
 /// Generate OAPI documentation for the [`upload`] handler.
@@ -44,14 +54,17 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
         .response::<200, ()>()
         .response_with::<400, Json<Value>, _>(|op| {
             op.description("Incorrect multipart/form-data request.")
+                .example(example_multipart_error())
         })
         .response_with::<403, Json<Value>, _>(|op| {
-            op.description("Invalid build session token was provided.")
+            op.description("Invalid or finished build session token was provided.")
                 .example(example_error(UploadFileError::InvalidToken))
         })
         .response_with::<422, Json<Value>, _>(|op| {
-            op.description("No file upload was found in the request.")
-                .example(example_error(UploadFileError::NoFileUpload))
+            op.description(
+                "No file upload was found in the request, or the uploaded file name is invalid.",
+            )
+            .example(example_error(UploadFileError::NoFileUpload))
         })
 }
 
@@ -59,6 +72,9 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
 ///
 /// This handler is used by smart contract builders to
 /// pass source code archive contents for web UI preview.
+///
+/// Tokens of finished build sessions are rejected, as well as file names
+/// that are not relative paths inside of the source code archive.
 pub(super) async fn upload(
     State(db): State<Arc<DatabaseConnection>>,
     Path(token): Path<String>,
@@ -71,8 +87,8 @@ pub(super) async fn upload(
 
     let name = archive
         .name()
-        .ok_or(UploadFileError::NoFileUpload)?
-        .to_string();
+        .ok_or(UploadFileError::NoFileUpload)
+        .and_then(normalize_file_name)?;
 
     let text = archive.text().await?;
 
@@ -81,7 +97,9 @@ pub(super) async fn upload(
             let source_code_id = build_session_token::Entity::find()
                 .select_only()
                 .column(build_session_token::Column::SourceCodeId)
+                .inner_join(build_session::Entity)
                 .filter(build_session_token::Column::Token.eq(token))
+                .filter(build_session::Column::Status.eq(build_session::Status::New))
                 .into_tuple::<i64>()
                 .one(txn)
                 .await?
@@ -108,6 +126,33 @@ pub(super) async fn upload(
     .into_raw_result()
 }
 
+/// Validate the provided file name and remove duplicate slashes and `.` components from it.
+///
+/// Absolute paths, `..` components, backslashes and NUL bytes are rejected,
+/// since file names are later joined with other paths by API consumers.
+fn normalize_file_name(name: &str) -> Result<String, UploadFileError> {
+    if name.starts_with('/') || name.contains(['\\', '\0']) {
+        return Err(UploadFileError::InvalidFileName);
+    }
+
+    let components = name
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>();
+
+    if components.is_empty() || components.contains(&"..") {
+        return Err(UploadFileError::InvalidFileName);
+    }
+
+    let name = components.join("/");
+
+    if name.len() > MAX_FILE_NAME_LENGTH {
+        return Err(UploadFileError::InvalidFileName);
+    }
+
+    Ok(name)
+}
+
 #[cfg(test)]
 mod tests {
     use std::{io::Cursor, sync::Arc};
@@ -122,12 +167,14 @@ mod tests {
     use common::config::Config;
     use common_multipart_rfc7578::client::multipart;
     use db::{
-        build_session, build_session_token, source_code, user, ActiveValue, DatabaseConnection,
-        EntityTrait,
+        build_session, build_session_token, file, source_code, user, ActiveValue,
+        DatabaseConnection, EntityTrait,
     };
     use tower::{Service, ServiceExt};
 
-    async fn create_test_env(db: &DatabaseConnection) -> i64 {
+    use super::{normalize_file_name, UploadFileError, MAX_FILE_NAME_LENGTH};
+
+    async fn create_test_env(db: &DatabaseConnection, status: build_session::Status) -> i64 {
         let user = user::Entity::insert(user::ActiveModel::default())
             .exec_with_returning(db)
             .await
@@ -146,7 +193,7 @@ mod tests {
         let build_session_id = build_session::Entity::insert(build_session::ActiveModel {
             user_id: ActiveValue::Set(Some(user.id)),
             source_code_id: ActiveValue::Set(source_code_id),
-            status: ActiveValue::Set(build_session::Status::New),
+            status: ActiveValue::Set(status),
             cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
             ..Default::default()
         })
@@ -159,6 +206,7 @@ mod tests {
             build_session_id: ActiveValue::Set(build_session_id),
             source_code_id: ActiveValue::Set(source_code_id),
             token: ActiveValue::Set(String::from("testtoken")),
+            ..Default::default()
         })
         .exec_without_returning(db)
         .await
@@ -171,7 +219,7 @@ mod tests {
     async fn upload_and_seal() {
         let db = create_database().await;
 
-        let build_session_id = create_test_env(&db).await;
+        let build_session_id = create_test_env(&db, build_session::Status::New).await;
 
         let mut form = multipart::Form::default();
         form.add_reader("lib.rs", Cursor::new(b"Hello, world"));
@@ -240,6 +288,143 @@ mod tests {
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
     }
 
+    #[tokio::test]
+    async fn finished_build_session() {
+        let db = create_database().await;
+
+        create_test_env(&db, build_session::Status::Completed).await;
+
+        let mut service = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()));
+
+        let mut form = multipart::Form::default();
+        form.add_reader("lib.rs", Cursor::new(b"Hello, world"));
+
+        let response = service
+            .call(
+                Request::builder()
+                    .method("POST")
+                    .uri("/files/upload/testtoken")
+                    .header("Content-Type", form.content_type())
+                    .body(Body::wrap_stream(multipart::Body::from(form)))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = service
+            .call(
+                Request::builder()
+                    .method("POST")
+                    .uri("/files/seal/testtoken")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Upload a single file with the provided name and get its response status code.
+    async fn upload_file(db: Arc<DatabaseConnection>, name: &str) -> StatusCode {
+        let mut form = multipart::Form::default();
+        form.add_reader(name.to_string(), Cursor::new(b"Hello, world"));
+
+        crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/files/upload/testtoken")
+                    .header("Content-Type", form.content_type())
+                    .body(Body::wrap_stream(multipart::Body::from(form)))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn invalid_file_names() {
+        let db = Arc::new(create_database().await);
+
+        create_test_env(&db, build_session::Status::New).await;
+
+        for name in [
+            "/etc/passwd",
+            "../../etc/passwd",
+            "contracts/../../lib.rs",
+            "contracts\\lib.rs",
+            "//",
+            &"a".repeat(MAX_FILE_NAME_LENGTH + 1),
+        ] {
+            assert_eq!(
+                upload_file(db.clone(), name).await,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "{name} was accepted"
+            );
+        }
+
+        assert!(file::Entity::find().all(&*db).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn nested_file_name() {
+        let db = Arc::new(create_database().await);
+
+        create_test_env(&db, build_session::Status::New).await;
+
+        assert_eq!(
+            upload_file(db.clone(), "contracts//flipper/lib.rs").await,
+            StatusCode::OK
+        );
+
+        let names = file::Entity::find()
+            .all(&*db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|file| file.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["contracts/flipper/lib.rs"]);
+    }
+
+    #[test]
+    fn file_name_validation() {
+        assert_eq!(
+            normalize_file_name("contracts/flipper/lib.rs")
+                .ok()
+                .as_deref(),
+            Some("contracts/flipper/lib.rs")
+        );
+        assert_eq!(
+            normalize_file_name("contracts///flipper/").ok().as_deref(),
+            Some("contracts/flipper")
+        );
+        assert_eq!(
+            normalize_file_name("./contracts/./flipper/.hidden/lib.rs")
+                .ok()
+                .as_deref(),
+            Some("contracts/flipper/.hidden/lib.rs")
+        );
+        assert!(matches!(
+            normalize_file_name("./."),
+            Err(UploadFileError::InvalidFileName)
+        ));
+        assert!(matches!(
+            normalize_file_name("lib\0.rs"),
+            Err(UploadFileError::InvalidFileName)
+        ));
+        assert!(matches!(
+            normalize_file_name(""),
+            Err(UploadFileError::InvalidFileName)
+        ));
+        assert!(normalize_file_name(&"a".repeat(MAX_FILE_NAME_LENGTH)).is_ok());
+    }
+
     #[tokio::test]
     async fn empty_request() {
         let db = create_database().await;
@@ -0,0 +1,14 @@
+/// Build session completion callback route.
+mod notify;
+
+use std::sync::Arc;
+
+use aide::axum::{routing::post, ApiRouter};
+use db::DatabaseConnection;
+
+/// Create an [`ApiRouter`] that provides an API server with routes used by other services.
+///
+/// These routes are not documented, since they are not meant to be used by API clients.
+pub(crate) fn routes() -> ApiRouter<Arc<DatabaseConnection>> {
+    ApiRouter::new().route("/buildSessions/notify", post(notify::notify))
+}
@@ -0,0 +1,315 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    Extension,
+};
+use axum_derive_error::ErrorResponse;
+use common::{
+    config::Config,
+    signature::{timestamp_is_fresh, verify_timestamped, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+};
+use db::{
+    build_session, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect,
+    SelectExt,
+};
+use derive_more::{Display, Error, From};
+
+use crate::events::{BuildSessionEvent, BuildSessionEvents};
+
+/// Maximum difference in seconds between the signed request timestamp and the current time.
+///
+/// Requests outside of this window are rejected, which limits the time frame
+/// during which an intercepted request can be replayed.
+const TIMESTAMP_TOLERANCE: u64 = 300;
+
+/// Errors that may occur during the completion callback handling.
+#[derive(ErrorResponse, Display, From, Error)]
+pub(super) enum NotifyError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Callback secret is not configured.
+    #[status(StatusCode::FORBIDDEN)]
+    #[display(fmt = "completion callbacks are disabled")]
+    CallbacksDisabled,
+
+    /// Request signature is either missing or invalid.
+    #[status(StatusCode::UNAUTHORIZED)]
+    #[display(fmt = "invalid signature")]
+    InvalidSignature,
+
+    /// Signed request timestamp is outside of the accepted window.
+    #[status(StatusCode::UNAUTHORIZED)]
+    #[display(fmt = "request timestamp is outside of the accepted window")]
+    ExpiredTimestamp,
+
+    /// Request body could not be parsed.
+    #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+    #[display(fmt = "invalid request body")]
+    InvalidBody,
+
+    /// The provided build session was not found or is not finished yet.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "build session not found")]
+    BuildSessionNotFound,
+}
+
+/// Build session completion callback handler.
+///
+/// This route is called by the builder after the final build session status was committed.
+/// Request body and timestamp are signed with the shared callback secret, and requests
+/// with timestamps older or newer than 5 minutes are rejected.
+/// The validated event is published to all build session event subscribers.
+pub(super) async fn notify(
+    State(db): State<Arc<DatabaseConnection>>,
+    Extension(config): Extension<Arc<Config>>,
+    Extension(events): Extension<BuildSessionEvents>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(), NotifyError> {
+    let secret = config
+        .server
+        .as_ref()
+        .and_then(|server| server.callback_secret.as_deref())
+        .ok_or(NotifyError::CallbacksDisabled)?;
+
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(NotifyError::InvalidSignature)?;
+
+    let timestamp = headers
+        .get(TIMESTAMP_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .ok_or(NotifyError::InvalidSignature)?;
+
+    if !verify_timestamped(secret.as_bytes(), timestamp, &body, signature) {
+        return Err(NotifyError::InvalidSignature);
+    }
+
+    if !timestamp_is_fresh(timestamp, TIMESTAMP_TOLERANCE) {
+        return Err(NotifyError::ExpiredTimestamp);
+    }
+
+    let event: BuildSessionEvent =
+        serde_json::from_slice(&body).map_err(|_| NotifyError::InvalidBody)?;
+
+    let build_session_finished = build_session::Entity::find()
+        .select_only()
+        .filter(build_session::Column::Id.eq(event.build_session_id))
+        .filter(build_session::Column::Status.ne(build_session::Status::New))
+        .exists(&*db)
+        .await?;
+
+    if !build_session_finished {
+        return Err(NotifyError::BuildSessionNotFound);
+    }
+
+    events.publish(event);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use assert_json::assert_json;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::{
+        config::Config,
+        signature::{sign_timestamped, unix_timestamp, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+    };
+    use db::{build_session, source_code, user, ActiveValue, DatabaseConnection, EntityTrait};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    async fn create_test_env(db: &DatabaseConnection, status: build_session::Status) -> i64 {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(status),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to insert build session")
+        .id
+    }
+
+    /// Sign the provided body with the current timestamp.
+    fn sign(secret: &[u8], body: &[u8]) -> (u64, String) {
+        let timestamp = unix_timestamp();
+        (timestamp, sign_timestamped(secret, timestamp, body))
+    }
+
+    /// Send a completion callback with the provided body, timestamp and signature.
+    async fn request(
+        db: Arc<DatabaseConnection>,
+        callback_secret: Option<&str>,
+        body: Vec<u8>,
+        signature: Option<(u64, String)>,
+    ) -> axum::response::Response {
+        let mut config = Config::for_tests();
+        config.server.as_mut().unwrap().callback_secret = callback_secret.map(String::from);
+
+        let mut request = Request::builder()
+            .method("POST")
+            .uri("/internal/buildSessions/notify");
+
+        if let Some((timestamp, signature)) = signature {
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp)
+                .header(SIGNATURE_HEADER, signature);
+        }
+
+        crate::app_router(db, Arc::new(config))
+            .oneshot(request.body(Body::from(body)).unwrap())
+            .await
+            .unwrap()
+    }
+
+    fn body(build_session_id: i64) -> Vec<u8> {
+        serde_json::to_vec(&json!({
+            "build_session_id": build_session_id,
+            "status": "failed",
+            "code_hash": null,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn valid_signature() {
+        let db = Arc::new(create_database().await);
+
+        let id = create_test_env(&db, build_session::Status::Failed).await;
+
+        let body = body(id);
+        let signature = sign(b"secret", &body);
+
+        let response = request(db, Some("secret"), body, Some(signature)).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn invalid_signature() {
+        let db = Arc::new(create_database().await);
+
+        let id = create_test_env(&db, build_session::Status::Failed).await;
+
+        let now = unix_timestamp();
+
+        for signature in [
+            None,
+            Some(sign(b"other", &body(id))),
+            Some(sign(b"secret", &body(id + 1))),
+            Some((now, String::from("sha256=invalid"))),
+            // Signature does not cover the provided timestamp.
+            Some((now + 1, sign_timestamped(b"secret", now, &body(id)))),
+        ] {
+            let response = request(db.clone(), Some("secret"), body(id), signature).await;
+
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_json!(response.json().await, {
+                "code": 401,
+                "error": "invalid signature",
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn replayed_request() {
+        let db = Arc::new(create_database().await);
+
+        let id = create_test_env(&db, build_session::Status::Failed).await;
+
+        let body = body(id);
+        let now = unix_timestamp();
+
+        for timestamp in [now - 600, now + 600] {
+            let signature = sign_timestamped(b"secret", timestamp, &body);
+
+            let response = request(
+                db.clone(),
+                Some("secret"),
+                body.clone(),
+                Some((timestamp, signature)),
+            )
+            .await;
+
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_json!(response.json().await, {
+                "code": 401,
+                "error": "request timestamp is outside of the accepted window",
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn disabled() {
+        let db = Arc::new(create_database().await);
+
+        let id = create_test_env(&db, build_session::Status::Failed).await;
+
+        let body = body(id);
+        let signature = sign(b"secret", &body);
+
+        let response = request(db, None, body, Some(signature)).await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn invalid_body() {
+        let db = Arc::new(create_database().await);
+
+        let body = b"{}".to_vec();
+        let signature = sign(b"secret", &body);
+
+        let response = request(db, Some("secret"), body, Some(signature)).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn unfinished() {
+        let db = Arc::new(create_database().await);
+
+        let id = create_test_env(&db, build_session::Status::New).await;
+
+        for id in [id, id + 1] {
+            let body = body(id);
+            let signature = sign(b"secret", &body);
+
+            let response = request(db.clone(), Some("secret"), body, Some(signature)).await;
+
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
@@ -1,10 +1,7 @@
 use std::sync::Arc;
 
 use aide::{transform::TransformOperation, OperationIo};
-use axum::{
-    extract::{Query, State},
-    Extension, Json,
-};
+use axum::{extract::State, Extension, Json};
 use axum_derive_error::ErrorResponse;
 use common::rpc::sp_core::crypto::AccountId32;
 use db::{
@@ -15,7 +12,11 @@ use futures_util::TryStreamExt;
 use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::{auth::AuthenticatedUserId, pagination::Pagination};
+use crate::{
+    auth::AuthenticatedUserId,
+    pagination::Pagination,
+    schema::{example_account, example_database_identifier},
+};
 
 /// A single public key data.
 #[derive(Serialize, JsonSchema)]
@@ -44,14 +45,20 @@ pub(super) enum PublicKeyListError {
 /// Generate OAPI documentation for the [`list`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("List public keys attached to the current user.")
-        .response_with::<200, Json<Vec<PublicKeyData>>, _>(|op| op.description("Public key list."))
+        .response_with::<200, Json<Vec<PublicKeyData>>, _>(|op| {
+            op.description("Public key list.")
+                .example(vec![PublicKeyData {
+                    id: example_database_identifier(),
+                    address: example_account(),
+                }])
+        })
 }
 
 /// List public keys attached to the current authenticated user's account.
 pub(super) async fn list(
     Extension(current_user): Extension<AuthenticatedUserId>,
     State(db): State<Arc<DatabaseConnection>>,
-    Query(pagination): Query<Pagination>,
+    pagination: Pagination,
 ) -> Result<Json<Vec<PublicKeyData>>, PublicKeyListError> {
     public_key::Entity::find()
         .select_only()
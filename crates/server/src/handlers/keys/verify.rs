@@ -3,9 +3,9 @@ use std::sync::Arc;
 use aide::{transform::TransformOperation, OperationIo};
 use axum::{extract::State, http::StatusCode, Extension, Json};
 use axum_derive_error::ErrorResponse;
-use common::rpc::sp_core::{
-    sr25519::{Pair, Public, Signature},
-    Pair as _,
+use common::{
+    config::Config,
+    rpc::sp_core::sr25519::{Public, Signature},
 };
 use db::{
     public_key, user, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
@@ -16,7 +16,11 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_json::Value;
 
-use crate::{auth::AuthenticatedUserId, schema::example_error};
+use crate::{
+    auth::AuthenticatedUserId,
+    schema::example_error,
+    signing::{self, Purpose},
+};
 
 /// Errors that may occur during the public key verification process.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -45,11 +49,11 @@ pub(super) struct PublicKeyVerificationRequest {
 
     /// Signed verification message.
     ///
-    /// Verification message consists of
-    /// a string equal to the account address
-    /// used for verification purposes.
+    /// Verification message consists of the server signing domain,
+    /// the `verify` purpose and the account address. Message template
+    /// can be retrieved using the `/auth/signingInfo` route.
     ///
-    /// Example: `<Bytes>5FeLhJAs4CUHqpWmPDBLeL7NLAoHsB2ZuFZ5Mk62EgYemtFj</Bytes>`
+    /// Example: `<Bytes>patron:patron.works:verify:5FeLhJAs4CUHqpWmPDBLeL7NLAoHsB2ZuFZ5Mk62EgYemtFj</Bytes>`
     #[schemars(example = "crate::schema::example_signature", with = "String")]
     signature: Signature,
 }
@@ -74,12 +78,14 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
 /// signature see [`PublicKeyVerificationRequest`].
 pub(super) async fn verify(
     Extension(current_user): Extension<AuthenticatedUserId>,
+    Extension(config): Extension<Arc<Config>>,
     State(db): State<Arc<DatabaseConnection>>,
     Json(request): Json<PublicKeyVerificationRequest>,
 ) -> Result<(), PublicKeyVerificationError> {
-    if Pair::verify(
+    if signing::verify(
+        &config,
+        Purpose::Verify,
         &request.signature,
-        format!("<Bytes>{}</Bytes>", &request.account),
         &request.account,
     ) {
         db.transaction(|txn| {
@@ -128,13 +134,21 @@ mod tests {
         body::Body,
         http::{Request, StatusCode},
     };
-    use common::config::Config;
+    use common::{
+        config::Config,
+        rpc::sp_core::{crypto::Ss58Codec, sr25519::Pair, Pair as _},
+    };
     use db::{token, user, DatabaseConnection, EntityTrait};
     use serde_json::json;
-    use tower::Service;
+    use tower::{Service, ServiceExt};
+
+    use crate::signing::{self, Purpose};
 
     const ACCOUNT_ID: &str = "5FeLhJAs4CUHqpWmPDBLeL7NLAoHsB2ZuFZ5Mk62EgYemtFj";
 
+    /// Signature of the deprecated ownership proof, that contains only the [`ACCOUNT_ID`].
+    const LEGACY_SIGNATURE: &str = "0x6aa1134d5082aae91dc710cf70d79d2abf6c261cc58eeb13d25ef4dfc8eeed54de76e49f186cde3efd41f6008598ab8d895c78b4354f26e868ead1d8e6410d8a";
+
     async fn create_test_env(db: &DatabaseConnection) -> String {
         let user = user::Entity::insert(user::ActiveModel::default())
             .exec_with_returning(db)
@@ -157,6 +171,11 @@ mod tests {
 
         let token = create_test_env(&db).await;
 
+        let pair = Pair::from_seed(&[1; 32]);
+        let account = pair.public().to_ss58check();
+        let message = signing::message(&Config::for_tests(), Purpose::Verify, &account);
+        let signature = pair.sign(message.as_bytes());
+
         let mut service = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()));
 
         let response = service
@@ -181,8 +200,8 @@ mod tests {
                     .header("Authorization", format!("Bearer {token}"))
                     .header("Content-Type", "application/json")
                     .body(Body::from_json(json!({
-                        "account": ACCOUNT_ID,
-                        "signature": "0x6aa1134d5082aae91dc710cf70d79d2abf6c261cc58eeb13d25ef4dfc8eeed54de76e49f186cde3efd41f6008598ab8d895c78b4354f26e868ead1d8e6410d8a"
+                        "account": account,
+                        "signature": format!("0x{}", hex::encode(signature)),
                     })))
                     .unwrap(),
             )
@@ -206,8 +225,44 @@ mod tests {
         assert_json!(response.json().await, [
             {
                 "id": 1,
-                "address": ACCOUNT_ID
+                "address": account.as_str()
             }
         ]);
     }
+
+    #[tokio::test]
+    async fn legacy_signature() {
+        for legacy_signatures in [false, true] {
+            let db = create_database().await;
+
+            let token = create_test_env(&db).await;
+
+            let mut config = Config::for_tests();
+            config.server.as_mut().unwrap().legacy_signatures = legacy_signatures;
+
+            let response = crate::app_router(Arc::new(db), Arc::new(config))
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/keys")
+                        .header("Authorization", format!("Bearer {token}"))
+                        .header("Content-Type", "application/json")
+                        .body(Body::from_json(json!({
+                            "account": ACCOUNT_ID,
+                            "signature": LEGACY_SIGNATURE,
+                        })))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let expected_status = if legacy_signatures {
+                StatusCode::OK
+            } else {
+                StatusCode::UNPROCESSABLE_ENTITY
+            };
+
+            assert_eq!(response.status(), expected_status);
+        }
+    }
 }
@@ -0,0 +1,21 @@
+/// Message selector lookup route.
+mod selector;
+
+use std::sync::Arc;
+
+use aide::axum::{routing::get_with, ApiRouter};
+use axum::middleware::from_fn_with_state;
+use db::DatabaseConnection;
+
+use crate::auth;
+
+/// Create an [`ApiRouter`] that provides an API server with contract metadata search routes.
+pub(crate) fn routes(database: Arc<DatabaseConnection>) -> ApiRouter<Arc<DatabaseConnection>> {
+    ApiRouter::new()
+        .api_route(
+            "/selector/:selector",
+            get_with(selector::lookup, selector::docs),
+        )
+        .route_layer(from_fn_with_state(database, auth::optional_authentication))
+        .with_path_items(|op| op.tag("Metadata search"))
+}
@@ -0,0 +1,278 @@
+use std::{array::TryFromSliceError, sync::Arc};
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use db::{
+    selector, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use derive_more::{Display, Error, From};
+use futures_util::TryStreamExt;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    pagination::Pagination,
+    schema::{example_error, example_hex_hash, example_message_name},
+    visibility,
+};
+
+/// Errors that may occur during the selector lookup request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum SelectorLookupError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Incorrect hash size stored inside of a database
+    IncorrectCodeHash(TryFromSliceError),
+
+    /// Provided selector is not a hex-encoded 4-byte value.
+    #[status(StatusCode::BAD_REQUEST)]
+    #[display(fmt = "selector must be a hex-encoded 4-byte value")]
+    InvalidSelector,
+}
+
+/// Contract message that matches the requested selector.
+#[derive(Serialize, JsonSchema)]
+pub struct SelectorMatch {
+    /// Code hash of the contract that defines the message.
+    #[schemars(example = "crate::schema::example_hex_hash")]
+    pub code_hash: HexHash,
+
+    /// Message name.
+    #[schemars(example = "crate::schema::example_message_name")]
+    pub message_name: String,
+
+    /// Whether the message mutates contract storage.
+    pub mutates: bool,
+}
+
+/// Generate OAPI documentation for the [`lookup`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Find contract messages by their selector.")
+        .description(
+            r#"Selector must be provided as a hex-encoded 4-byte value, with an optional `0x` prefix.
+
+Only contracts built with Patron are searched, excluding contracts built only from
+private source code archives of other users."#,
+        )
+        .response_with::<200, Json<Vec<SelectorMatch>>, _>(|op| {
+            op.description("Matching contract messages.")
+                .example(vec![SelectorMatch {
+                    code_hash: example_hex_hash(),
+                    message_name: example_message_name(),
+                    mutates: true,
+                }])
+        })
+        .response_with::<400, Json<Value>, _>(|op| {
+            op.description("Invalid selector format.")
+                .example(example_error(SelectorLookupError::InvalidSelector))
+        })
+}
+
+/// Selector lookup request handler.
+pub(super) async fn lookup(
+    Path(selector): Path<String>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    pagination: Pagination,
+) -> Result<Json<Vec<SelectorMatch>>, SelectorLookupError> {
+    let selector =
+        selector::parse_selector(&selector).ok_or(SelectorLookupError::InvalidSelector)?;
+
+    let current_user = current_user.map(|Extension(user)| user);
+
+    selector::Entity::find()
+        .select_only()
+        .columns([
+            selector::Column::CodeHash,
+            selector::Column::MessageName,
+            selector::Column::Mutates,
+        ])
+        .filter(selector::Column::Selector.eq(&selector[..]))
+        .filter(visibility::code_hash_visible_condition(
+            selector::Column::CodeHash,
+            current_user,
+        ))
+        .order_by_asc(selector::Column::Id)
+        .limit(pagination.limit())
+        .offset(pagination.offset())
+        .into_tuple::<(Vec<u8>, String, bool)>()
+        .stream(&*db)
+        .await?
+        .err_into()
+        .and_then(|(code_hash, message_name, mutates)| async move {
+            Ok(SelectorMatch {
+                code_hash: HexHash::try_from(&code_hash[..])?,
+                message_name,
+                mutates,
+            })
+        })
+        .try_collect()
+        .await
+        .map(Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, make_source_code_private, ResponseBodyExt};
+
+    use assert_json::assert_json;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{
+        build_session, code, selector, source_code, user, ActiveValue, DatabaseConnection,
+        EntityTrait,
+    };
+    use tower::ServiceExt;
+
+    /// Store a code with the provided message selectors.
+    async fn create_code(db: &DatabaseConnection, hash: [u8; 32], messages: &[(&str, &str, bool)]) {
+        code::Entity::insert(code::ActiveModel {
+            hash: ActiveValue::Set(hash.to_vec()),
+            code: ActiveValue::Set(vec![1, 2, 3]),
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert code");
+
+        selector::Entity::insert_many(messages.iter().map(|(value, name, mutates)| {
+            selector::ActiveModel {
+                code_hash: ActiveValue::Set(hash.to_vec()),
+                selector: ActiveValue::Set(selector::parse_selector(value).unwrap().to_vec()),
+                message_name: ActiveValue::Set(String::from(*name)),
+                mutates: ActiveValue::Set(*mutates),
+                ..Default::default()
+            }
+        }))
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert selectors");
+    }
+
+    async fn request(db: Arc<DatabaseConnection>, selector: &str) -> axum::response::Response {
+        crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/metadata/selector/{selector}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn matching() {
+        let db = Arc::new(create_database().await);
+
+        create_code(
+            &db,
+            [1; 32],
+            &[("0x633aa551", "flip", true), ("0x2f865bd9", "get", false)],
+        )
+        .await;
+        create_code(&db, [2; 32], &[("0x633aa551", "toggle", true)]).await;
+
+        for selector in ["0x633aa551", "633aa551"] {
+            let response = request(db.clone(), selector).await;
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_json!(response.json().await, [
+                {
+                    "code_hash": hex::encode([1; 32]),
+                    "message_name": "flip",
+                    "mutates": true,
+                },
+                {
+                    "code_hash": hex::encode([2; 32]),
+                    "message_name": "toggle",
+                    "mutates": true,
+                }
+            ]);
+        }
+
+        let response = request(db, "0x00000000").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, []);
+    }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = Arc::new(create_database().await);
+
+        create_code(&db, [1; 32], &[("0x633aa551", "flip", true)]).await;
+        create_code(&db, [2; 32], &[("0x633aa551", "toggle", true)]).await;
+
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(&*db)
+            .await
+            .expect("unable to create user");
+
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(&*db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::Completed),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            code_hash: ActiveValue::Set(Some(vec![2; 32])),
+            ..Default::default()
+        })
+        .exec_without_returning(&*db)
+        .await
+        .expect("unable to insert build session");
+
+        make_source_code_private(&db).await;
+
+        let response = request(db, "0x633aa551").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, [
+            {
+                "code_hash": hex::encode([1; 32]),
+                "message_name": "flip",
+                "mutates": true,
+            }
+        ]);
+    }
+
+    #[tokio::test]
+    async fn invalid_selector() {
+        let db = Arc::new(create_database().await);
+
+        for selector in ["0x633aa5", "flip", "0x633aa551ff"] {
+            let response = request(db.clone(), selector).await;
+
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            assert_json!(response.json().await, {
+                "code": 400,
+                "error": "selector must be a hex-encoded 4-byte value",
+            });
+        }
+    }
+}
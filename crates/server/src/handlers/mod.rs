@@ -4,6 +4,9 @@ pub(crate) mod auth;
 /// Build session management routes.
 pub(crate) mod build_sessions;
 
+/// Uploaded code information routes.
+pub(crate) mod codes;
+
 /// Smart contract management routes.
 pub(crate) mod contracts;
 
@@ -13,11 +16,23 @@ pub(crate) mod docs;
 /// Source code file browsing and uploading routes.
 pub(crate) mod files;
 
+/// Routes used by other services.
+pub(crate) mod internal;
+
 /// Authentication key management routes.
 pub(crate) mod keys;
 
+/// Contract metadata search routes.
+pub(crate) mod metadata;
+
+/// Node information routes.
+pub(crate) mod nodes;
+
 /// Payment-related routes.
 pub(crate) mod payment;
 
 /// Source code routes.
 pub(crate) mod source_code;
+
+/// Build session webhook management routes.
+pub(crate) mod webhooks;
@@ -0,0 +1,322 @@
+use std::{sync::Arc, time::Duration};
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{extract::State, Extension, Json};
+use axum_derive_error::ErrorResponse;
+use common::config::{default_node_staleness_threshold, Config};
+use db::{
+    node, DatabaseConnection, DbErr, EntityTrait, FromQueryResult, OffsetDateTime,
+    PrimitiveDateTime, QueryOrder, QuerySelect,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::schema::{
+    example_explorer_url_template, example_last_block_seen_at, example_node,
+    example_node_display_name, example_node_healthy, example_node_last_error, example_ss58_prefix,
+    example_token_decimals, example_token_symbol,
+};
+
+/// A single node data.
+#[derive(Serialize, JsonSchema)]
+pub struct NodeData {
+    /// Node name.
+    #[schemars(example = "crate::schema::example_node")]
+    pub name: String,
+
+    /// Human-readable network name.
+    #[schemars(example = "crate::schema::example_node_display_name")]
+    pub display_name: Option<String>,
+
+    /// Block explorer URL template.
+    ///
+    /// `{address}` placeholder should be replaced with an account address.
+    #[schemars(example = "crate::schema::example_explorer_url_template")]
+    pub explorer_url_template: Option<String>,
+
+    /// Native token symbol.
+    #[schemars(example = "crate::schema::example_token_symbol")]
+    pub token_symbol: Option<String>,
+
+    /// Count of decimal places of the native token.
+    #[schemars(example = "crate::schema::example_token_decimals")]
+    pub token_decimals: Option<i16>,
+
+    /// SS58 address format prefix.
+    ///
+    /// If not set, the default prefix is used to render addresses.
+    #[schemars(example = "crate::schema::example_ss58_prefix")]
+    pub ss58_prefix: Option<i16>,
+
+    /// Time at which the last block was processed, as a UNIX timestamp.
+    ///
+    /// [`None`] if no blocks were processed yet.
+    #[schemars(example = "crate::schema::example_last_block_seen_at")]
+    pub last_block_seen_at: Option<i64>,
+
+    /// Last error that caused block processing to stop.
+    #[schemars(example = "crate::schema::example_node_last_error")]
+    pub last_error: Option<String>,
+
+    /// Whether a block was processed within the configured staleness threshold.
+    #[schemars(example = "crate::schema::example_node_healthy")]
+    pub healthy: bool,
+}
+
+/// Node columns selected by the [`list`] handler.
+#[derive(FromQueryResult)]
+struct NodeRow {
+    /// Node name.
+    name: String,
+
+    /// Human-readable network name.
+    display_name: Option<String>,
+
+    /// Block explorer URL template.
+    explorer_url_template: Option<String>,
+
+    /// Native token symbol.
+    token_symbol: Option<String>,
+
+    /// Count of decimal places of the native token.
+    token_decimals: Option<i16>,
+
+    /// SS58 address format prefix.
+    ss58_prefix: Option<i16>,
+
+    /// Time at which the last block was processed.
+    last_block_seen_at: Option<PrimitiveDateTime>,
+
+    /// Last error that caused block processing to stop.
+    last_error: Option<String>,
+}
+
+/// Errors that may occur during the node list request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum NodeListError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+}
+
+/// Generate OAPI documentation for the [`list`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List supported nodes.")
+        .response_with::<200, Json<Vec<NodeData>>, _>(|op| {
+            op.description("Node list.").example(vec![NodeData {
+                name: example_node(),
+                display_name: example_node_display_name(),
+                explorer_url_template: example_explorer_url_template(),
+                token_symbol: example_token_symbol(),
+                token_decimals: example_token_decimals(),
+                ss58_prefix: example_ss58_prefix(),
+                last_block_seen_at: example_last_block_seen_at(),
+                last_error: example_node_last_error(),
+                healthy: example_node_healthy(),
+            }])
+        })
+}
+
+/// List all supported nodes alongside with their display metadata and health status.
+pub(super) async fn list(
+    State(db): State<Arc<DatabaseConnection>>,
+    Extension(config): Extension<Arc<Config>>,
+) -> Result<Json<Vec<NodeData>>, NodeListError> {
+    let threshold = config
+        .server
+        .as_ref()
+        .map_or_else(default_node_staleness_threshold, |server| {
+            server.node_staleness_threshold
+        });
+
+    let now = OffsetDateTime::now_utc();
+
+    let nodes = node::Entity::find()
+        .select_only()
+        .columns([
+            node::Column::Name,
+            node::Column::DisplayName,
+            node::Column::ExplorerUrlTemplate,
+            node::Column::TokenSymbol,
+            node::Column::TokenDecimals,
+            node::Column::Ss58Prefix,
+            node::Column::LastBlockSeenAt,
+            node::Column::LastError,
+        ])
+        .order_by_asc(node::Column::Id)
+        .into_model::<NodeRow>()
+        .all(&*db)
+        .await?
+        .into_iter()
+        .map(|row| NodeData {
+            name: row.name,
+            display_name: row.display_name,
+            explorer_url_template: row.explorer_url_template,
+            token_symbol: row.token_symbol,
+            token_decimals: row.token_decimals,
+            ss58_prefix: row.ss58_prefix,
+            last_block_seen_at: row
+                .last_block_seen_at
+                .map(|seen_at| seen_at.assume_utc().unix_timestamp()),
+            last_error: row.last_error,
+            healthy: is_healthy(row.last_block_seen_at, now, threshold),
+        })
+        .collect();
+
+    Ok(Json(nodes))
+}
+
+/// Check if a block was processed within the provided staleness threshold, in seconds.
+fn is_healthy(
+    last_block_seen_at: Option<PrimitiveDateTime>,
+    now: OffsetDateTime,
+    threshold: u64,
+) -> bool {
+    match last_block_seen_at {
+        Some(seen_at) => now - seen_at.assume_utc() <= Duration::from_secs(threshold),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use assert_json::{assert_json, validators};
+    use axum::{body::Body, http::Request};
+    use common::config::Config;
+    use db::{node, ActiveValue, EntityTrait, OffsetDateTime, PrimitiveDateTime};
+    use tower::ServiceExt;
+
+    use super::is_healthy;
+
+    #[tokio::test]
+    async fn successful() {
+        let db = create_database().await;
+
+        let now = OffsetDateTime::now_utc();
+        let now = PrimitiveDateTime::new(now.date(), now.time());
+
+        node::Entity::insert_many([
+            node::ActiveModel {
+                name: ActiveValue::Set(String::from("alephzero")),
+                url: ActiveValue::Set(String::from("ws://localhost:9944")),
+                confirmed_block: ActiveValue::Set(0),
+                display_name: ActiveValue::Set(Some(String::from("Aleph Zero"))),
+                explorer_url_template: ActiveValue::Set(Some(String::from(
+                    "https://alephzero.subscan.io/account/{address}",
+                ))),
+                token_symbol: ActiveValue::Set(Some(String::from("AZERO"))),
+                token_decimals: ActiveValue::Set(Some(12)),
+                ss58_prefix: ActiveValue::Set(Some(42)),
+                last_block_seen_at: ActiveValue::Set(Some(now)),
+                ..Default::default()
+            },
+            node::ActiveModel {
+                name: ActiveValue::Set(String::from("local")),
+                url: ActiveValue::Set(String::from("ws://localhost:9945")),
+                confirmed_block: ActiveValue::Set(0),
+                last_error: ActiveValue::Set(Some(String::from("rpc error"))),
+                ..Default::default()
+            },
+        ])
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert nodes");
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/nodes")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, [
+            {
+                "name": "alephzero",
+                "display_name": "Aleph Zero",
+                "explorer_url_template": "https://alephzero.subscan.io/account/{address}",
+                "token_symbol": "AZERO",
+                "token_decimals": 12,
+                "ss58_prefix": 42,
+                "last_block_seen_at": now.assume_utc().unix_timestamp(),
+                "last_error": validators::null(),
+                "healthy": true,
+            },
+            {
+                "name": "local",
+                "display_name": validators::null(),
+                "explorer_url_template": validators::null(),
+                "token_symbol": validators::null(),
+                "token_decimals": validators::null(),
+                "ss58_prefix": validators::null(),
+                "last_block_seen_at": validators::null(),
+                "last_error": "rpc error",
+                "healthy": false,
+            }
+        ]);
+    }
+
+    #[test]
+    fn staleness_threshold() {
+        let now = OffsetDateTime::now_utc();
+        let seen_at = |secs| {
+            let time = now - Duration::from_secs(secs);
+            Some(PrimitiveDateTime::new(time.date(), time.time()))
+        };
+
+        assert!(is_healthy(seen_at(0), now, 300));
+        assert!(is_healthy(seen_at(300), now, 300));
+        assert!(!is_healthy(seen_at(301), now, 300));
+        assert!(!is_healthy(None, now, 300));
+    }
+
+    #[tokio::test]
+    async fn stale_node() {
+        let db = create_database().await;
+
+        let seen_at = OffsetDateTime::now_utc() - Duration::from_secs(120);
+
+        node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("alephzero")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            last_block_seen_at: ActiveValue::Set(Some(PrimitiveDateTime::new(
+                seen_at.date(),
+                seen_at.time(),
+            ))),
+            ..Default::default()
+        })
+        .exec_without_returning(&db)
+        .await
+        .expect("unable to insert node");
+
+        let db = Arc::new(db);
+
+        for (threshold, healthy) in [(60, false), (300, true)] {
+            let mut config = Config::for_tests();
+            config.server.as_mut().unwrap().node_staleness_threshold = threshold;
+
+            let response = crate::app_router(db.clone(), Arc::new(config))
+                .oneshot(
+                    Request::builder()
+                        .method("GET")
+                        .uri("/nodes")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let body = response.json().await;
+            assert_eq!(body[0]["healthy"], healthy, "{threshold}");
+        }
+    }
+}
@@ -0,0 +1,14 @@
+/// Node list route.
+mod list;
+
+use std::sync::Arc;
+
+use aide::axum::{routing::get_with, ApiRouter};
+use db::DatabaseConnection;
+
+/// Create an [`ApiRouter`] that provides an API server with node information routes.
+pub(crate) fn routes() -> ApiRouter<Arc<DatabaseConnection>> {
+    ApiRouter::new()
+        .api_route("/", get_with(list::list, list::docs))
+        .with_path_items(|op| op.tag("Node information"))
+}
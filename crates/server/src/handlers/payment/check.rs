@@ -27,17 +27,37 @@ use tokio::{runtime::Handle, task::JoinError};
 use crate::{auth::AuthenticatedUserId, schema::example_error};
 
 /// JSON request body.
+///
+/// Node used to check the membership payment can be selected either by its identifier
+/// or by its name, exactly one of which must be provided.
 #[derive(Deserialize, JsonSchema)]
 pub(super) struct PaymentCheckRequest {
     /// Node identifier used to check the membership payment.
+    ///
+    /// Mutually exclusive with `node_name`.
     #[schemars(example = "crate::schema::example_database_identifier")]
-    node_id: i64,
+    node_id: Option<i64>,
+
+    /// Node name used to check the membership payment, as returned by the `/nodes` route.
+    ///
+    /// Mutually exclusive with `node_id`.
+    #[schemars(example = "crate::schema::example_node")]
+    node_name: Option<String>,
 
     /// Account identifier against which the check will be executed.
     #[schemars(example = "crate::schema::example_account", with = "String")]
     account: AccountId32,
 }
 
+/// Node selected by the [`PaymentCheckRequest`].
+enum NodeSelection {
+    /// Node identifier.
+    Id(i64),
+
+    /// Node name, which is resolved to an identifier inside of the transaction.
+    Name(String),
+}
+
 /// Errors that may occur during the membership check process.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
 #[aide(output)]
@@ -77,6 +97,16 @@ pub(super) enum PaymentCheckError {
     #[display(fmt = "invalid node id")]
     InvalidNodeId,
 
+    /// Provided node name is incorrect.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "invalid node name")]
+    InvalidNodeName,
+
+    /// Either both or none of the node identifier and node name were provided.
+    #[status(StatusCode::BAD_REQUEST)]
+    #[display(fmt = "exactly one of node_id or node_name must be provided")]
+    InvalidNodeSelection,
+
     /// Provided node identifier is not marked as the one that supports payments.
     #[status(StatusCode::BAD_REQUEST)]
     #[display(fmt = "provided node doesn't support payments")]
@@ -96,15 +126,23 @@ pub(super) enum PaymentCheckError {
 /// Generate OAPI documentation for the [`check`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Check membership payment with the provided node.")
-        .description("See self-hosted documentation for more information about the contract ABI.")
+        .description(
+            r#"Node can be selected either by its identifier with the `node_id` field,
+or by its name with the `node_name` field. Exactly one of these fields must be provided.
+
+See self-hosted documentation for more information about the contract ABI."#,
+        )
         .response::<200, ()>()
         .response_with::<400, Json<Value>, _>(|op| {
-            op.description("Invalid account identifier was provided.")
-                .example(example_error(PaymentCheckError::InvalidKey))
+            op.description(
+                "Invalid account identifier was provided, \
+                or the node was not selected with exactly one of the node fields.",
+            )
+            .example(example_error(PaymentCheckError::InvalidNodeSelection))
         })
         .response_with::<404, Json<Value>, _>(|op| {
-            op.description("The provided node identifier is invalid.")
-                .example(example_error(PaymentCheckError::InvalidNodeId))
+            op.description("The provided node identifier or name is invalid.")
+                .example(example_error(PaymentCheckError::InvalidNodeName))
         })
 }
 
@@ -116,6 +154,12 @@ pub(super) async fn check(
     State(db): State<Arc<DatabaseConnection>>,
     Json(request): Json<PaymentCheckRequest>,
 ) -> Result<(), PaymentCheckError> {
+    let node = match (request.node_id, request.node_name) {
+        (Some(node_id), None) => NodeSelection::Id(node_id),
+        (None, Some(node_name)) => NodeSelection::Name(node_name),
+        _ => return Err(PaymentCheckError::InvalidNodeSelection),
+    };
+
     db.transaction(|txn| {
         Box::pin(async move {
             let user = user::Entity::find_by_id(current_user.id())
@@ -139,7 +183,19 @@ pub(super) async fn check(
                 return Err(PaymentCheckError::InvalidKey);
             }
 
-            let (url, contract) = node::Entity::find_by_id(request.node_id)
+            let node_id = match node {
+                NodeSelection::Id(node_id) => node_id,
+                NodeSelection::Name(node_name) => node::Entity::find()
+                    .select_only()
+                    .column(node::Column::Id)
+                    .filter(node::Column::Name.eq(node_name))
+                    .into_tuple::<i64>()
+                    .one(txn)
+                    .await?
+                    .ok_or(PaymentCheckError::InvalidNodeName)?,
+            };
+
+            let (url, contract) = node::Entity::find_by_id(node_id)
                 .select_only()
                 .columns([node::Column::Url, node::Column::PaymentContract])
                 .into_tuple::<(String, Option<Vec<u8>>)>()
@@ -191,3 +247,122 @@ pub(super) async fn check(
     .await
     .into_raw_result()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, RequestBodyExt, ResponseBodyExt};
+
+    use assert_json::assert_json;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{node, public_key, token, user, ActiveValue, DatabaseConnection, EntityTrait};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use crate::schema::example_account;
+
+    /// Create a user with a verified public key and a node without a payment contract.
+    async fn create_test_env(db: &DatabaseConnection) -> String {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let (model, token) = token::generate_token(user.id);
+
+        token::Entity::insert(model)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert token");
+
+        public_key::Entity::insert(public_key::ActiveModel {
+            user_id: ActiveValue::Set(user.id),
+            address: ActiveValue::Set(AsRef::<[u8]>::as_ref(&example_account()).to_vec()),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to create public key");
+
+        node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("astar")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert node");
+
+        token
+    }
+
+    /// Send a membership check request with the provided node fields.
+    async fn check(db: DatabaseConnection, token: &str, mut body: Value) -> (StatusCode, Value) {
+        body["account"] = json!(example_account().to_string());
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/payment")
+                    .header("Authorization", format!("Bearer {token}"))
+                    .header("Content-Type", "application/json")
+                    .body(Body::from_json(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        (response.status(), response.json().await)
+    }
+
+    #[tokio::test]
+    async fn node_name_resolution() {
+        let db = create_database().await;
+        let token = create_test_env(&db).await;
+
+        let (status, body) = check(db, &token, json!({ "node_name": "astar" })).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_json!(body, {
+            "code": 400,
+            "error": "provided node doesn't support payments",
+        });
+    }
+
+    #[tokio::test]
+    async fn unknown_node_name() {
+        let db = create_database().await;
+        let token = create_test_env(&db).await;
+
+        let (status, body) = check(db, &token, json!({ "node_name": "shibuya" })).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_json!(body, {
+            "code": 404,
+            "error": "invalid node name",
+        });
+    }
+
+    #[tokio::test]
+    async fn invalid_node_selection() {
+        for body in [json!({}), json!({ "node_id": 1, "node_name": "astar" })] {
+            let db = create_database().await;
+            let token = create_test_env(&db).await;
+
+            let (status, body) = check(db, &token, body).await;
+
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_json!(body, {
+                "code": 400,
+                "error": "exactly one of node_id or node_name must be provided",
+            });
+        }
+    }
+}
@@ -0,0 +1,438 @@
+use std::{array::TryFromSliceError, sync::Arc};
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use db::{
+    build_session, file,
+    sea_query::Expr,
+    source_code::{self, Visibility},
+    ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, PrimitiveDateTime,
+    QueryFilter, QuerySelect, SelectExt, TransactionErrorExt, TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    schema::{example_database_identifier, example_error, example_hex_hash, example_timestamp},
+    visibility,
+};
+
+/// Errors that may occur during the source code details request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum SourceCodeDetailsError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Incorrect hash size stored inside of a database
+    IncorrectArchiveHash(TryFromSliceError),
+
+    /// Requested source code was not found or is not visible to the current user.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "source code not found")]
+    SourceCodeNotFound,
+}
+
+/// Source code archive details.
+#[derive(Serialize, JsonSchema)]
+pub struct SourceCodeDetails {
+    /// Source code identifier.
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    pub id: i64,
+
+    /// Blake2b256 hash of an uploaded archive.
+    #[schemars(example = "crate::schema::example_hex_hash")]
+    pub archive_hash: HexHash,
+
+    /// Source code archive upload timestamp.
+    #[schemars(example = "crate::schema::example_timestamp")]
+    pub created_at: i64,
+
+    /// Whether the source code archive was uploaded by the current authenticated user.
+    pub mine: bool,
+
+    /// Source code archive visibility to other users.
+    pub visibility: Visibility,
+
+    /// Count of source code files stored after a build.
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    pub file_count: i64,
+}
+
+/// Source code archive visible to the current user.
+pub(super) struct VisibleSourceCode {
+    /// Blake2b256 hash of an uploaded archive.
+    pub archive_hash: Vec<u8>,
+
+    /// Source code archive upload timestamp.
+    pub created_at: PrimitiveDateTime,
+
+    /// Whether the source code archive was uploaded by the current user.
+    pub mine: bool,
+
+    /// Source code archive visibility to other users.
+    pub visibility: Visibility,
+}
+
+/// Find source code archive by its identifier, if it is visible to the current user.
+///
+/// Source code archives are visible to their owners, while other users can only access
+/// source code archives with at least one completed build session.
+///
+/// Private source code archives of other users are only accessible
+/// if they were deployed on-chain.
+pub(super) async fn find_visible(
+    txn: &DatabaseTransaction,
+    id: i64,
+    current_user: Option<AuthenticatedUserId>,
+) -> Result<Option<VisibleSourceCode>, DbErr> {
+    let Some((user_id, archive_hash, created_at, visibility)) = source_code::Entity::find_by_id(id)
+        .select_only()
+        .columns([
+            source_code::Column::UserId,
+            source_code::Column::ArchiveHash,
+            source_code::Column::CreatedAt,
+            source_code::Column::Visibility,
+        ])
+        .into_tuple::<(Option<i64>, Vec<u8>, PrimitiveDateTime, Visibility)>()
+        .one(txn)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let mine = visibility::owned(user_id, current_user);
+
+    if !mine {
+        let has_completed_builds = build_session::Entity::find()
+            .select_only()
+            .filter(build_session::Column::SourceCodeId.eq(id))
+            .filter(build_session::Column::Status.eq(build_session::Status::Completed))
+            .exists(txn)
+            .await?;
+
+        // Hide source code archives of other users instead of returning 403,
+        // to avoid disclosing their existence.
+        if !has_completed_builds {
+            return Ok(None);
+        }
+
+        if visibility == Visibility::Private && !visibility::source_code_deployed(txn, id).await? {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(VisibleSourceCode {
+        archive_hash,
+        created_at,
+        mine,
+        visibility,
+    }))
+}
+
+/// Generate OAPI documentation for the [`details`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Get source code archive details.")
+        .description(
+            r#"Source code archives uploaded by other users are only available
+if they have at least one completed build session. Private source code archives
+of other users are only available if a contract with a matching code hash
+was discovered on-chain.
+
+Authentication is optional for this route, and is only used to
+determine whether the source code archive belongs to the current user."#,
+        )
+        .response_with::<200, Json<SourceCodeDetails>, _>(|op| {
+            op.description("Source code archive details response.")
+                .example(SourceCodeDetails {
+                    id: example_database_identifier(),
+                    archive_hash: example_hex_hash(),
+                    created_at: example_timestamp(),
+                    mine: true,
+                    visibility: Visibility::Public,
+                    file_count: example_database_identifier(),
+                })
+        })
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description("Source code archive was not found.")
+                .example(example_error(SourceCodeDetailsError::SourceCodeNotFound))
+        })
+}
+
+/// Source code archive details request handler.
+pub(super) async fn details(
+    Path(id): Path<i64>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> Result<Json<SourceCodeDetails>, SourceCodeDetailsError> {
+    db.transaction(|txn| {
+        Box::pin(async move {
+            let source_code = find_visible(txn, id, current_user.map(|Extension(user)| user))
+                .await?
+                .ok_or(SourceCodeDetailsError::SourceCodeNotFound)?;
+
+            let file_count = file::Entity::find()
+                .select_only()
+                .column_as(Expr::col(file::Column::Id).count(), "count")
+                .filter(file::Column::SourceCodeId.eq(id))
+                .into_tuple::<i64>()
+                .one(txn)
+                .await?
+                .unwrap_or_default();
+
+            Ok(Json(SourceCodeDetails {
+                id,
+                archive_hash: source_code.archive_hash.as_slice().try_into()?,
+                created_at: source_code.created_at.assume_utc().unix_timestamp(),
+                mine: source_code.mine,
+                visibility: source_code.visibility,
+                file_count,
+            }))
+        })
+    })
+    .await
+    .into_raw_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use assert_json::{assert_json, validators};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{
+        build_session, code, contract, file, node,
+        source_code::{self, Visibility},
+        token, user, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    };
+    use tower::ServiceExt;
+
+    /// Create a user with an authentication token, returning its identifier and the token.
+    async fn create_user(db: &DatabaseConnection) -> (i64, String) {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let (model, token) = token::generate_token(user.id);
+
+        token::Entity::insert(model)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert token");
+
+        (user.id, token)
+    }
+
+    /// Create a source code archive with two files owned by the provided user.
+    async fn create_source_code(
+        db: &DatabaseConnection,
+        user_id: i64,
+        status: Option<build_session::Status>,
+    ) -> i64 {
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user_id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        if let Some(status) = status {
+            build_session::Entity::insert(build_session::ActiveModel {
+                user_id: ActiveValue::Set(Some(user_id)),
+                source_code_id: ActiveValue::Set(source_code_id),
+                status: ActiveValue::Set(status),
+                cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+                ..Default::default()
+            })
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert build session");
+        }
+
+        file::Entity::insert_many(["lib.rs", "Cargo.toml"].map(|name| file::ActiveModel {
+            source_code_id: ActiveValue::Set(source_code_id),
+            name: ActiveValue::Set(String::from(name)),
+            text: ActiveValue::Set(String::new()),
+            ..Default::default()
+        }))
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert files");
+
+        source_code_id
+    }
+
+    /// Request source code details, optionally using the provided authentication token.
+    async fn request(
+        db: Arc<DatabaseConnection>,
+        id: i64,
+        token: Option<&str>,
+    ) -> axum::response::Response {
+        let mut request = Request::builder()
+            .method("GET")
+            .uri(format!("/sourceCode/{id}"));
+
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+
+        crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn own_source_code() {
+        let db = create_database().await;
+
+        let (user_id, token) = create_user(&db).await;
+        let id = create_source_code(&db, user_id, None).await;
+
+        let response = request(Arc::new(db), id, Some(&token)).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, {
+            "id": id,
+            "archive_hash": hex::encode([0; 32]),
+            "created_at": validators::i64(|_| Ok(())),
+            "mine": true,
+            "visibility": "public",
+            "file_count": 2,
+        });
+    }
+
+    #[tokio::test]
+    async fn other_user_source_code() {
+        let db = Arc::new(create_database().await);
+
+        let (owner_id, _) = create_user(&db).await;
+        let (_, token) = create_user(&db).await;
+
+        let verified =
+            create_source_code(&db, owner_id, Some(build_session::Status::Completed)).await;
+        let failed = create_source_code(&db, owner_id, Some(build_session::Status::Failed)).await;
+
+        let response = request(db.clone(), verified, Some(&token)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json().await["mine"], false);
+
+        let response = request(db.clone(), verified, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json().await["mine"], false);
+
+        for token in [Some(token.as_str()), None] {
+            let response = request(db.clone(), failed, token).await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[tokio::test]
+    async fn private_source_code() {
+        let db = Arc::new(create_database().await);
+
+        let (owner_id, owner_token) = create_user(&db).await;
+        let (_, token) = create_user(&db).await;
+
+        let id = create_source_code(&db, owner_id, Some(build_session::Status::Completed)).await;
+
+        source_code::Entity::update_many()
+            .filter(source_code::Column::Id.eq(id))
+            .col_expr(source_code::Column::Visibility, Visibility::Private.into())
+            .exec(&*db)
+            .await
+            .expect("unable to update source code");
+
+        let response = request(db.clone(), id, Some(&owner_token)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json().await["visibility"], "private");
+
+        for token in [Some(token.as_str()), None] {
+            let response = request(db.clone(), id, token).await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+
+        // Contracts discovered on-chain make private source code archives public.
+        let node = node::Entity::insert(node::ActiveModel {
+            name: ActiveValue::Set(String::from("test")),
+            url: ActiveValue::Set(String::from("ws://localhost:9944")),
+            confirmed_block: ActiveValue::Set(0),
+            ..Default::default()
+        })
+        .exec_with_returning(&*db)
+        .await
+        .expect("unable to insert node");
+
+        code::Entity::insert(code::ActiveModel {
+            hash: ActiveValue::Set(vec![0; 32]),
+            code: ActiveValue::Set(vec![1, 2, 3]),
+        })
+        .exec_without_returning(&*db)
+        .await
+        .expect("unable to insert code");
+
+        build_session::Entity::update_many()
+            .filter(build_session::Column::SourceCodeId.eq(id))
+            .col_expr(build_session::Column::CodeHash, vec![0u8; 32].into())
+            .exec(&*db)
+            .await
+            .expect("unable to update build session");
+
+        contract::Entity::insert(contract::ActiveModel {
+            node_id: ActiveValue::Set(node.id),
+            code_hash: ActiveValue::Set(vec![0; 32]),
+            address: ActiveValue::Set(vec![1; 32]),
+            ..Default::default()
+        })
+        .exec_without_returning(&*db)
+        .await
+        .expect("unable to insert contract");
+
+        for token in [Some(token.as_str()), None] {
+            let response = request(db.clone(), id, token).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.json().await["mine"], false);
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown() {
+        let db = create_database().await;
+
+        let response = request(Arc::new(db), 1, None).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_json!(response.json().await, {
+            "code": 404,
+            "error": "source code not found",
+        });
+    }
+
+    #[tokio::test]
+    async fn invalid_token() {
+        let db = create_database().await;
+
+        let response = request(Arc::new(db), 1, Some("invalid")).await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
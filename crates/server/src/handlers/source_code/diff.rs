@@ -0,0 +1,391 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use common::config::{default_max_diff_size, Config};
+use db::{
+    file,
+    sea_query::{Alias, Expr, Func},
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    TransactionErrorExt, TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    diff::{diff_lines, LineChange},
+    schema::{example_error, example_file, example_line_change},
+};
+
+use super::details::find_visible;
+
+/// Errors that may occur during the source code diff request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum SourceCodeDiffError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// One of the requested source codes was not found or is not visible to the current user.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "source code not found")]
+    SourceCodeNotFound,
+
+    /// Total size of the compared files exceeds the configured limit.
+    #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+    #[display(fmt = "source code files are too large to compare")]
+    DiffTooLarge,
+}
+
+/// Changes of a single file present in both source code archives.
+#[derive(Serialize, JsonSchema)]
+pub struct FileDiff {
+    /// File name.
+    #[schemars(example = "crate::schema::example_file")]
+    pub name: String,
+
+    /// Changed lines.
+    pub changes: Vec<LineChange>,
+}
+
+/// Difference between two source code archives.
+#[derive(Serialize, JsonSchema)]
+pub struct SourceCodeDiff {
+    /// Names of files that are only present in the other source code archive.
+    #[schemars(example = "crate::schema::example_files")]
+    pub added: Vec<String>,
+
+    /// Names of files that are only present in the original source code archive.
+    #[schemars(example = "crate::schema::example_files")]
+    pub removed: Vec<String>,
+
+    /// Files that are present in both source code archives, but have different contents.
+    pub modified: Vec<FileDiff>,
+}
+
+/// Generate OAPI documentation for the [`diff`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Compare files of two source code archives.")
+        .description(
+            r#"Both source code archives must be visible to the current user,
+using the same rules as the source code archive details route.
+
+Files of the source code archive identified by `id` are treated as the original ones."#,
+        )
+        .response_with::<200, Json<SourceCodeDiff>, _>(|op| {
+            op.description("Source code archive diff response.")
+                .example(SourceCodeDiff {
+                    added: vec![String::from("Cargo.lock")],
+                    removed: Vec::new(),
+                    modified: vec![FileDiff {
+                        name: example_file(),
+                        changes: vec![example_line_change()],
+                    }],
+                })
+        })
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description("One of the source code archives was not found.")
+                .example(example_error(SourceCodeDiffError::SourceCodeNotFound))
+        })
+        .response_with::<422, Json<Value>, _>(|op| {
+            op.description("Source code files are too large to compare.")
+                .example(example_error(SourceCodeDiffError::DiffTooLarge))
+        })
+}
+
+/// Source code archive diff request handler.
+pub(super) async fn diff(
+    Path((id, other_id)): Path<(i64, i64)>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Extension(config): Extension<Arc<Config>>,
+) -> Result<Json<SourceCodeDiff>, SourceCodeDiffError> {
+    let max_diff_size = config
+        .server
+        .as_ref()
+        .map_or_else(default_max_diff_size, |server| server.max_diff_size);
+
+    let current_user = current_user.map(|Extension(user)| user);
+
+    let files = db
+        .transaction(|txn| {
+            Box::pin(async move {
+                for source_code_id in [id, other_id] {
+                    find_visible(txn, source_code_id, current_user)
+                        .await?
+                        .ok_or(SourceCodeDiffError::SourceCodeNotFound)?;
+                }
+
+                let length = Func::cust(Alias::new("LENGTH")).arg(Expr::col(file::Column::Text));
+
+                // Check the total size before loading any files.
+                let total_size = file::Entity::find()
+                    .select_only()
+                    .column_as(
+                        Expr::expr(Expr::expr(length).sum()).cast_as(Alias::new("BIGINT")),
+                        "total_size",
+                    )
+                    .filter(file::Column::SourceCodeId.is_in([id, other_id]))
+                    .into_tuple::<Option<i64>>()
+                    .one(txn)
+                    .await?
+                    .flatten()
+                    .unwrap_or_default();
+
+                if total_size as usize > max_diff_size {
+                    return Err(SourceCodeDiffError::DiffTooLarge);
+                }
+
+                Ok(file::Entity::find()
+                    .select_only()
+                    .columns([
+                        file::Column::SourceCodeId,
+                        file::Column::Name,
+                        file::Column::Text,
+                    ])
+                    .filter(file::Column::SourceCodeId.is_in([id, other_id]))
+                    .order_by_asc(file::Column::Name)
+                    .into_tuple::<(i64, String, String)>()
+                    .all(txn)
+                    .await?)
+            })
+        })
+        .await
+        .into_raw_result()?;
+
+    let mut old = BTreeMap::new();
+    let mut new = BTreeMap::new();
+
+    for (source_code_id, name, text) in files {
+        if source_code_id == other_id {
+            new.insert(name.clone(), text.clone());
+        }
+
+        if source_code_id == id {
+            old.insert(name, text);
+        }
+    }
+
+    let removed = old
+        .keys()
+        .filter(|name| !new.contains_key(*name))
+        .cloned()
+        .collect();
+
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+
+    for (name, text) in new {
+        match old.get(&name) {
+            Some(old_text) if *old_text != text => modified.push(FileDiff {
+                changes: diff_lines(old_text, &text),
+                name,
+            }),
+            Some(_) => {}
+            None => added.push(name),
+        }
+    }
+
+    Ok(Json(SourceCodeDiff {
+        added,
+        removed,
+        modified,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use assert_json::assert_json;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{
+        build_session, file, source_code, token, user, ActiveValue, DatabaseConnection, EntityTrait,
+    };
+    use tower::ServiceExt;
+
+    /// Create a user with an authentication token, returning its identifier and the token.
+    async fn create_user(db: &DatabaseConnection) -> (i64, String) {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let (model, token) = token::generate_token(user.id);
+
+        token::Entity::insert(model)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert token");
+
+        (user.id, token)
+    }
+
+    /// Create a source code archive with the provided files.
+    async fn create_source_code(
+        db: &DatabaseConnection,
+        user_id: i64,
+        files: &[(&str, &str)],
+    ) -> i64 {
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user_id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        file::Entity::insert_many(files.iter().map(|(name, text)| file::ActiveModel {
+            source_code_id: ActiveValue::Set(source_code_id),
+            name: ActiveValue::Set(String::from(*name)),
+            text: ActiveValue::Set(String::from(*text)),
+            ..Default::default()
+        }))
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert files");
+
+        source_code_id
+    }
+
+    /// Request a diff between two source code archives.
+    async fn request(
+        db: Arc<DatabaseConnection>,
+        config: Config,
+        (id, other_id): (i64, i64),
+        token: Option<&str>,
+    ) -> axum::response::Response {
+        let mut request = Request::builder()
+            .method("GET")
+            .uri(format!("/sourceCode/{id}/diff/{other_id}"));
+
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+
+        crate::app_router(db, Arc::new(config))
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn added_removed_and_modified() {
+        let db = Arc::new(create_database().await);
+
+        let (user_id, token) = create_user(&db).await;
+
+        let old = create_source_code(
+            &db,
+            user_id,
+            &[
+                ("Cargo.toml", "[package]"),
+                ("lib.rs", "fn a() {}\nfn b() {}\nfn c() {}"),
+                ("old.rs", ""),
+            ],
+        )
+        .await;
+        let new = create_source_code(
+            &db,
+            user_id,
+            &[
+                ("Cargo.toml", "[package]"),
+                ("lib.rs", "fn a() {}\nfn d() {}\nfn c() {}"),
+                ("new.rs", ""),
+            ],
+        )
+        .await;
+
+        let response = request(db, Config::for_tests(), (old, new), Some(&token)).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, {
+            "added": ["new.rs"],
+            "removed": ["old.rs"],
+            "modified": [
+                {
+                    "name": "lib.rs",
+                    "changes": [
+                        {
+                            "kind": "removed",
+                            "line": 2,
+                            "text": "fn b() {}",
+                        },
+                        {
+                            "kind": "added",
+                            "line": 2,
+                            "text": "fn d() {}",
+                        }
+                    ]
+                }
+            ]
+        });
+    }
+
+    #[tokio::test]
+    async fn visibility() {
+        let db = Arc::new(create_database().await);
+
+        let (owner_id, _) = create_user(&db).await;
+        let (user_id, token) = create_user(&db).await;
+
+        let hidden = create_source_code(&db, owner_id, &[("lib.rs", "")]).await;
+        let own = create_source_code(&db, user_id, &[("lib.rs", "")]).await;
+
+        for token in [Some(token.as_str()), None] {
+            let response = request(db.clone(), Config::for_tests(), (own, hidden), token).await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(owner_id)),
+            source_code_id: ActiveValue::Set(hidden),
+            status: ActiveValue::Set(build_session::Status::Completed),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            ..Default::default()
+        })
+        .exec_without_returning(&*db)
+        .await
+        .expect("unable to insert build session");
+
+        let response = request(db, Config::for_tests(), (own, hidden), Some(&token)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn size_limit() {
+        let db = Arc::new(create_database().await);
+
+        let (user_id, token) = create_user(&db).await;
+
+        let old = create_source_code(&db, user_id, &[("lib.rs", "fn a() {}")]).await;
+        let new = create_source_code(&db, user_id, &[("lib.rs", "fn b() {}")]).await;
+
+        let mut config = Config::for_tests();
+        config.server.as_mut().unwrap().max_diff_size = 16;
+
+        let response = request(db, config, (old, new), Some(&token)).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_json!(response.json().await, {
+            "code": 422,
+            "error": "source code files are too large to compare",
+        });
+    }
+}
@@ -1,20 +1,23 @@
-use std::{array::TryFromSliceError, sync::Arc};
+use std::{array::TryFromSliceError, collections::HashMap, sync::Arc};
 
 use aide::{transform::TransformOperation, OperationIo};
-use axum::{
-    extract::{Query, State},
-    Extension, Json,
-};
+use axum::{extract::State, Extension, Json};
 use axum_derive_error::ErrorResponse;
 use db::{
-    source_code, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect,
+    build_session, sea_query::Expr, source_code, ColumnTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QuerySelect, TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
 use futures_util::TryStreamExt;
 use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::{auth::AuthenticatedUserId, hex_hash::HexHash, pagination::Pagination};
+use crate::{
+    auth::AuthenticatedUserId,
+    hex_hash::HexHash,
+    pagination::Pagination,
+    schema::{example_build_session_status, example_database_identifier, example_hex_hash},
+};
 
 /// A single source code archive data.
 #[derive(Serialize, JsonSchema)]
@@ -26,6 +29,18 @@ pub struct SourceCodeData {
     /// Blake2b256 hash of an uploaded archive.
     #[schemars(example = "crate::schema::example_hex_hash")]
     pub archive_hash: HexHash,
+
+    /// Status of the latest build session started for this source code archive.
+    #[schemars(example = "crate::schema::example_build_session_status")]
+    pub latest_build_session_status: Option<build_session::Status>,
+
+    /// Code hash produced by the latest build session, if it was completed.
+    #[schemars(example = "crate::schema::example_hex_hash")]
+    pub latest_code_hash: Option<HexHash>,
+
+    /// Total count of build sessions started for this source code archive.
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    pub build_session_count: i64,
 }
 
 /// Errors that may occur during the list process.
@@ -42,8 +57,19 @@ pub(super) enum SourceCodeListError {
 /// Generate OAPI documentation for the [`list`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("List source code archives uploaded by the current user.")
+        .description(
+            r#"Each source code archive includes a summary of its build sessions,
+such as the status and code hash of the latest build session."#,
+        )
         .response_with::<200, Json<Vec<SourceCodeData>>, _>(|op| {
             op.description("Source code archive list response.")
+                .example(vec![SourceCodeData {
+                    id: example_database_identifier(),
+                    archive_hash: example_hex_hash(),
+                    latest_build_session_status: Some(example_build_session_status()),
+                    latest_code_hash: Some(example_hex_hash()),
+                    build_session_count: example_database_identifier(),
+                }])
         })
 }
 
@@ -51,25 +77,188 @@ pub(super) fn docs(op: TransformOperation) -> TransformOperation {
 pub(super) async fn list(
     Extension(current_user): Extension<AuthenticatedUserId>,
     State(db): State<Arc<DatabaseConnection>>,
-    Query(pagination): Query<Pagination>,
+    pagination: Pagination,
 ) -> Result<Json<Vec<SourceCodeData>>, SourceCodeListError> {
-    source_code::Entity::find()
-        .select_only()
-        .columns([source_code::Column::Id, source_code::Column::ArchiveHash])
-        .filter(source_code::Column::UserId.eq(current_user.id()))
-        .limit(pagination.limit())
-        .offset(pagination.offset())
-        .into_tuple::<(i64, Vec<u8>)>()
-        .stream(&*db)
-        .await?
-        .err_into()
-        .and_then(|(id, archive_hash)| async move {
-            Ok(SourceCodeData {
-                id,
-                archive_hash: archive_hash.as_slice().try_into()?,
-            })
+    db.transaction(|txn| {
+        Box::pin(async move {
+            let source_codes = source_code::Entity::find()
+                .select_only()
+                .columns([source_code::Column::Id, source_code::Column::ArchiveHash])
+                .filter(source_code::Column::UserId.eq(current_user.id()))
+                .limit(pagination.limit())
+                .offset(pagination.offset())
+                .into_tuple::<(i64, Vec<u8>)>()
+                .all(txn)
+                .await?;
+
+            // Build session count and the latest build session identifier
+            // for each source code archive on the current page.
+            let summaries: HashMap<i64, (i64, i64)> = build_session::Entity::find()
+                .select_only()
+                .column(build_session::Column::SourceCodeId)
+                .column_as(Expr::col(build_session::Column::Id).count(), "count")
+                .column_as(Expr::col(build_session::Column::Id).max(), "latest_id")
+                .filter(
+                    build_session::Column::SourceCodeId
+                        .is_in(source_codes.iter().map(|(id, _)| *id)),
+                )
+                .group_by(build_session::Column::SourceCodeId)
+                .into_tuple::<(i64, i64, i64)>()
+                .stream(txn)
+                .await?
+                .map_ok(|(source_code_id, count, latest_id)| (source_code_id, (count, latest_id)))
+                .try_collect()
+                .await?;
+
+            let latest: HashMap<i64, (build_session::Status, Option<Vec<u8>>)> =
+                build_session::Entity::find()
+                    .select_only()
+                    .columns([
+                        build_session::Column::Id,
+                        build_session::Column::Status,
+                        build_session::Column::CodeHash,
+                    ])
+                    .filter(
+                        build_session::Column::Id
+                            .is_in(summaries.values().map(|(_, latest_id)| *latest_id)),
+                    )
+                    .into_tuple::<(i64, build_session::Status, Option<Vec<u8>>)>()
+                    .stream(txn)
+                    .await?
+                    .map_ok(|(id, status, code_hash)| (id, (status, code_hash)))
+                    .try_collect()
+                    .await?;
+
+            source_codes
+                .into_iter()
+                .map(|(id, archive_hash)| {
+                    let (build_session_count, latest) = match summaries.get(&id) {
+                        Some((count, latest_id)) => (*count, latest.get(latest_id)),
+                        None => (0, None),
+                    };
+
+                    Ok(SourceCodeData {
+                        id,
+                        archive_hash: archive_hash.as_slice().try_into()?,
+                        latest_build_session_status: latest.map(|(status, _)| status.clone()),
+                        latest_code_hash: latest
+                            .and_then(|(_, code_hash)| code_hash.as_deref())
+                            .map(HexHash::try_from)
+                            .transpose()?,
+                        build_session_count,
+                    })
+                })
+                .collect::<Result<Vec<_>, SourceCodeListError>>()
+                .map(Json)
         })
-        .try_collect()
+    })
+    .await
+    .into_raw_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, ResponseBodyExt};
+
+    use assert_json::{assert_json, validators};
+    use axum::{body::Body, http::Request};
+    use common::config::Config;
+    use db::{
+        build_session, public_key, source_code, token, user, ActiveValue, DatabaseConnection,
+        EntityTrait,
+    };
+    use tower::ServiceExt;
+
+    async fn create_test_env(db: &DatabaseConnection) -> String {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let (model, token) = token::generate_token(user.id);
+
+        token::Entity::insert(model)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert token");
+
+        public_key::Entity::insert(public_key::ActiveModel {
+            user_id: ActiveValue::Set(user.id),
+            address: ActiveValue::Set(Vec::new()),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to create public key");
+
+        let source_codes = [[0; 32], [1; 32]].map(|archive_hash| source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user.id)),
+            archive_hash: ActiveValue::Set(archive_hash.to_vec()),
+            ..Default::default()
+        });
+
+        source_code::Entity::insert_many(source_codes)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to create source code");
+
+        build_session::Entity::insert_many(
+            [
+                (build_session::Status::New, None),
+                (build_session::Status::Failed, None),
+                (build_session::Status::Completed, Some(vec![2; 32])),
+            ]
+            .map(|(status, code_hash)| build_session::ActiveModel {
+                user_id: ActiveValue::Set(Some(user.id)),
+                source_code_id: ActiveValue::Set(1),
+                status: ActiveValue::Set(status),
+                cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+                code_hash: ActiveValue::Set(code_hash),
+                ..Default::default()
+            }),
+        )
+        .exec_without_returning(db)
         .await
-        .map(Json)
+        .expect("unable to insert build sessions");
+
+        token
+    }
+
+    #[tokio::test]
+    async fn build_summary() {
+        let db = create_database().await;
+
+        let token = create_test_env(&db).await;
+
+        let response = crate::app_router(Arc::new(db), Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri("/sourceCode")
+                    .header("Authorization", format!("Bearer {token}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, [
+            {
+                "id": 1,
+                "archive_hash": hex::encode([0; 32]),
+                "latest_build_session_status": "completed",
+                "latest_code_hash": hex::encode([2; 32]),
+                "build_session_count": 3,
+            },
+            {
+                "id": 2,
+                "archive_hash": hex::encode([1; 32]),
+                "latest_build_session_status": validators::null(),
+                "latest_code_hash": validators::null(),
+                "build_session_count": 0,
+            }
+        ]);
+    }
 }
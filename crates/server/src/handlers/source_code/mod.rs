@@ -1,6 +1,15 @@
+/// Source code archive details route.
+mod details;
+
+/// Source code archive diff route.
+mod diff;
+
 /// Source code archive list route.
 mod list;
 
+/// Source code archive update route.
+mod update;
+
 /// Source code archive upload route.
 mod upload;
 
@@ -18,17 +27,30 @@ pub(crate) fn routes(
     database: Arc<DatabaseConnection>,
     config: Arc<Config>,
 ) -> ApiRouter<Arc<DatabaseConnection>> {
-    ApiRouter::new()
+    let public_routes = ApiRouter::new()
+        .api_route(
+            "/:id",
+            get_with(details::details, details::docs).patch_with(update::update, update::docs),
+        )
+        .api_route("/:id/diff/:other_id", get_with(diff::diff, diff::docs))
+        .route_layer(from_fn_with_state(
+            database.clone(),
+            auth::optional_authentication,
+        ));
+
+    let private_routes = ApiRouter::new()
         .api_route(
             "/",
             get_with(list::list, list::docs).post_with(upload::upload, upload::docs),
         )
         .route_layer(from_fn_with_state(
             (database, config),
-            auth::require_authentication::<true, true, _>,
+            auth::require_authentication::<true, true, false, _>,
         ))
-        .with_path_items(|op| {
-            op.security_requirement("Authentication token")
-                .tag("Source code management")
-        })
+        .with_path_items(|op| op.security_requirement("Authentication token"));
+
+    ApiRouter::new()
+        .merge(private_routes)
+        .merge(public_routes)
+        .with_path_items(|op| op.tag("Source code management"))
 }
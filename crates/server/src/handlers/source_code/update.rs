@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use db::{
+    source_code::{self, Visibility},
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{auth::AuthenticatedUserId, schema::example_error};
+
+/// Errors that may occur during the source code update request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum SourceCodeUpdateError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Source code was not found or belongs to another user.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "source code not found")]
+    SourceCodeNotFound,
+}
+
+/// JSON request body.
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct SourceCodeUpdateRequest {
+    /// New source code archive visibility to other users.
+    visibility: Visibility,
+}
+
+/// Generate OAPI documentation for the [`update`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Update source code archive of the current user.")
+        .description(
+            r#"Private source code archives, alongside with their build artifacts,
+are only available to their owners, unless a contract with a matching code hash
+was discovered on-chain."#,
+        )
+        .security_requirement("Authentication token")
+        .response::<200, ()>()
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description("Source code archive with the provided identifier was not found.")
+                .example(example_error(SourceCodeUpdateError::SourceCodeNotFound))
+        })
+}
+
+/// Update source code archive of the current authenticated user.
+///
+/// Requests without an authentication token are handled the same way as requests
+/// of other users, to avoid disclosing source code archive existence.
+pub(super) async fn update(
+    Path(id): Path<i64>,
+    current_user: Option<Extension<AuthenticatedUserId>>,
+    State(db): State<Arc<DatabaseConnection>>,
+    Json(request): Json<SourceCodeUpdateRequest>,
+) -> Result<(), SourceCodeUpdateError> {
+    let Some(Extension(current_user)) = current_user else {
+        return Err(SourceCodeUpdateError::SourceCodeNotFound);
+    };
+
+    let result = source_code::Entity::update_many()
+        .filter(source_code::Column::Id.eq(id))
+        .filter(source_code::Column::UserId.eq(current_user.id()))
+        .col_expr(source_code::Column::Visibility, request.visibility.into())
+        .exec(&*db)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(SourceCodeUpdateError::SourceCodeNotFound);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, RequestBodyExt};
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{
+        source_code::{self, Visibility},
+        token, user, ActiveValue, DatabaseConnection, EntityTrait,
+    };
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    /// Create a user with an authentication token, returning its identifier and the token.
+    async fn create_user(db: &DatabaseConnection) -> (i64, String) {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let (model, token) = token::generate_token(user.id);
+
+        token::Entity::insert(model)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert token");
+
+        (user.id, token)
+    }
+
+    /// Update source code visibility, optionally using the provided authentication token.
+    async fn request(
+        db: Arc<DatabaseConnection>,
+        id: i64,
+        token: Option<&str>,
+        visibility: &str,
+    ) -> StatusCode {
+        let mut request = Request::builder()
+            .method("PATCH")
+            .uri(format!("/sourceCode/{id}"))
+            .header("Content-Type", "application/json");
+
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+
+        crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(
+                request
+                    .body(Body::from_json(json!({ "visibility": visibility })))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    /// Get the stored visibility of a source code archive.
+    async fn visibility(db: &DatabaseConnection, id: i64) -> Visibility {
+        source_code::Entity::find_by_id(id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap()
+            .visibility
+    }
+
+    #[tokio::test]
+    async fn toggle_visibility() {
+        let db = Arc::new(create_database().await);
+
+        let (owner_id, owner_token) = create_user(&db).await;
+        let (_, token) = create_user(&db).await;
+
+        let id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(owner_id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            ..Default::default()
+        })
+        .exec_with_returning(&*db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        assert_eq!(visibility(&db, id).await, Visibility::Public);
+
+        for token in [Some(token.as_str()), None] {
+            let status = request(db.clone(), id, token, "private").await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(visibility(&db, id).await, Visibility::Public);
+        }
+
+        let status = request(db.clone(), id, Some(&owner_token), "private").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(visibility(&db, id).await, Visibility::Private);
+
+        let status = request(db.clone(), id, Some(&owner_token), "public").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(visibility(&db, id).await, Visibility::Public);
+
+        let status = request(db.clone(), id + 1, Some(&owner_token), "private").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
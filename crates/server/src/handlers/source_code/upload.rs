@@ -1,23 +1,30 @@
 use std::sync::Arc;
 
 use aide::{transform::TransformOperation, OperationIo};
+use api_types::source_code::SourceCodeUploadResponse;
 use axum::{
     extract::{multipart::MultipartError, Multipart, State},
     http::StatusCode,
     Extension, Json,
 };
 use axum_derive_error::ErrorResponse;
-use common::{config::Config, hash, s3};
+use common::{
+    config::Config,
+    hash,
+    s3::{ConfiguredClient, ObjectStorage, UploadError},
+};
 use db::{
     sea_query::OnConflict, source_code, user, ActiveValue, ColumnTrait, DatabaseConnection, DbErr,
     EntityTrait, QueryFilter, QuerySelect, SelectExt, TransactionErrorExt, TransactionTrait,
 };
 use derive_more::{Display, Error, From};
-use schemars::JsonSchema;
-use serde::Serialize;
 use serde_json::Value;
+use tracing::warn;
 
-use crate::{auth::AuthenticatedUserId, schema::example_error};
+use crate::{
+    auth::AuthenticatedUserId,
+    schema::{example_database_identifier, example_error, example_multipart_error},
+};
 
 /// Errors that may occur during the source code upload process.
 #[derive(ErrorResponse, Display, From, Error, OperationIo)]
@@ -26,8 +33,8 @@ pub(super) enum SourceCodeUploadError {
     /// Database-related error.
     DatabaseError(DbErr),
 
-    /// AWS S3-related error.
-    S3Error(s3::Error),
+    /// Unable to upload the source code archive to S3.
+    UploadError(UploadError),
 
     /// `multipart/form-data` request handling error.
     #[status(StatusCode::BAD_REQUEST)]
@@ -49,20 +56,18 @@ pub(super) enum SourceCodeUploadError {
     NonExistentUser,
 }
 
-/// Source code identifier response.
-#[derive(Serialize, JsonSchema)]
-pub(super) struct SourceCodeUploadResponse {
-    /// Source code identifier.
-    #[schemars(example = "crate::schema::example_database_identifier")]
-    id: i64,
-}
-
 /// Generate OAPI documentation for the [`upload`] handler.
 pub(super) fn docs(op: TransformOperation) -> TransformOperation {
     op.summary("Upload a new source code archive.")
-        .response::<200, Json<SourceCodeUploadResponse>>()
+        .response_with::<200, Json<SourceCodeUploadResponse>, _>(|op| {
+            op.description("Source code archive upload response.")
+                .example(SourceCodeUploadResponse {
+                    id: example_database_identifier(),
+                })
+        })
         .response_with::<400, Json<Value>, _>(|op| {
             op.description("Incorrect multipart/form-data request.")
+                .example(example_multipart_error())
         })
         .response_with::<422, Json<Value>, _>(|op| {
             op.description("Incorrect file upload.")
@@ -118,10 +123,8 @@ pub(super) async fn upload(
                 let id = if let Some(id) = existing_source_code {
                     id
                 } else {
-                    s3::ConfiguredClient::new(&config.storage)
-                        .await
-                        .upload_source_code(&archive_hash[..], archive)
-                        .await?;
+                    let client = ConfiguredClient::new(&config.storage).await;
+                    upload_with_retry(&client, &archive_hash, &archive).await?;
 
                     let model = source_code::Entity::insert(source_code::ActiveModel {
                         user_id: ActiveValue::Set(Some(current_user.id())),
@@ -148,3 +151,108 @@ pub(super) async fn upload(
     .await
     .into_raw_result()
 }
+
+/// Upload the source code archive, retrying once if the uploaded object
+/// fails the integrity verification.
+async fn upload_with_retry<S: ObjectStorage>(
+    client: &ConfiguredClient<'_, S>,
+    hash: &[u8],
+    archive: &[u8],
+) -> Result<(), UploadError> {
+    match client.upload_source_code(hash, archive).await {
+        Err(UploadError::Integrity(err)) => {
+            warn!(%err, "source code archive integrity verification failed, retrying");
+
+            client.upload_source_code(hash, archive).await
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::async_trait;
+    use common::{
+        config::Config,
+        s3::{
+            Checksums, ConfiguredClient, Error, IntegrityError, ObjectStorage, PutObject,
+            PutObjectOutput, UploadError,
+        },
+    };
+
+    use super::upload_with_retry;
+
+    /// In-memory [`ObjectStorage`], which corrupts a predefined count of uploads.
+    struct CorruptingStorage {
+        /// Count of uploads, that are reported with a mismatching checksum.
+        corrupted: Mutex<usize>,
+
+        /// Checksums received with each upload request.
+        checksums: Arc<Mutex<Vec<Option<Checksums>>>>,
+    }
+
+    impl CorruptingStorage {
+        /// Create a storage, that corrupts the provided count of uploads
+        /// and records received checksums into the provided list.
+        fn new(corrupted: usize, checksums: Arc<Mutex<Vec<Option<Checksums>>>>) -> Self {
+            Self {
+                corrupted: Mutex::new(corrupted),
+                checksums,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStorage for CorruptingStorage {
+        async fn put_object(&self, request: PutObject) -> Result<PutObjectOutput, Error> {
+            let mut corrupted = self.corrupted.lock().unwrap();
+
+            let checksum_sha256 = if *corrupted > 0 {
+                *corrupted -= 1;
+                Checksums::new(b"corrupted").sha256
+            } else {
+                Checksums::new(&request.body).sha256
+            };
+
+            self.checksums.lock().unwrap().push(request.checksums);
+
+            Ok(PutObjectOutput {
+                checksum_sha256: Some(checksum_sha256),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn integrity_retry() {
+        let config = Config::for_tests();
+        let checksums = Arc::default();
+        let storage = CorruptingStorage::new(1, Arc::clone(&checksums));
+        let client = ConfiguredClient::with_storage(&config.storage, storage);
+
+        upload_with_retry(&client, &[1; 32], b"archive")
+            .await
+            .expect("retried upload failed");
+
+        assert_eq!(
+            *checksums.lock().unwrap(),
+            vec![Some(Checksums::new(b"archive")); 2]
+        );
+    }
+
+    #[tokio::test]
+    async fn persistent_integrity_failure() {
+        let config = Config::for_tests();
+        let checksums = Arc::default();
+        let storage = CorruptingStorage::new(2, Arc::clone(&checksums));
+        let client = ConfiguredClient::with_storage(&config.storage, storage);
+
+        assert!(matches!(
+            upload_with_retry(&client, &[1; 32], b"archive").await,
+            Err(UploadError::Integrity(IntegrityError::Checksum { .. }))
+        ));
+        assert_eq!(checksums.lock().unwrap().len(), 2);
+    }
+}
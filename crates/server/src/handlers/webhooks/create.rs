@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use axum_derive_error::ErrorResponse;
+use common::webhook_url::{self, WebhookUrlError};
+use db::{
+    user, webhook, ActiveValue, DatabaseConnection, DbErr, EntityTrait, QuerySelect, SelectExt,
+    TransactionErrorExt, TransactionTrait,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use validator::Validate;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    schema::{example_database_identifier, example_error},
+    validation::ValidatedJson,
+};
+
+/// Errors that may occur during the webhook creation process.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum WebhookCreateError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// User was already deleted at the time the request was being executed.
+    #[status(StatusCode::FORBIDDEN)]
+    #[display(fmt = "non-existent user")]
+    NonExistentUser,
+
+    /// Provided webhook URL is not allowed.
+    #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+    ForbiddenUrl(WebhookUrlError),
+}
+
+/// JSON request body.
+#[derive(Deserialize, Validate, JsonSchema)]
+pub(super) struct WebhookCreateRequest {
+    /// URL that will receive build session notifications.
+    ///
+    /// Only HTTPS URLs with hosts that resolve to public addresses are allowed.
+    #[validate(url, length(max = 2048))]
+    #[schemars(example = "crate::schema::example_webhook_url")]
+    url: String,
+
+    /// Secret value used to sign webhook request bodies.
+    ///
+    /// Each request contains an `X-Patron-Timestamp` header with the UNIX timestamp
+    /// (in seconds) of the delivery attempt, and an `X-Patron-Signature` header with
+    /// a hex-encoded HMAC-SHA256 signature of the timestamp, followed by a `.` character
+    /// and the request body. Receivers should reject requests with stale timestamps
+    /// to prevent captured deliveries from being replayed.
+    #[validate(length(min = 16, max = 128))]
+    #[schemars(example = "crate::schema::example_token")]
+    secret: String,
+}
+
+/// JSON response body.
+#[derive(Serialize, JsonSchema)]
+pub(super) struct WebhookCreateResponse {
+    /// Webhook identifier.
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    id: i64,
+}
+
+/// Generate OAPI documentation for the [`create`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Create new webhook.")
+        .description(
+            r#"Created webhook will be notified with a `POST` request
+each time a build session of the current user finishes its execution.
+
+Webhook URLs must use the HTTPS scheme, and their hosts must not resolve
+to loopback, private, link-local or unspecified addresses. Redirects
+returned by webhook URLs are not followed."#,
+        )
+        .response_with::<200, Json<WebhookCreateResponse>, _>(|op| {
+            op.description("Created webhook response.")
+                .example(WebhookCreateResponse {
+                    id: example_database_identifier(),
+                })
+        })
+        .response_with::<403, Json<Value>, _>(|op| {
+            op.description("Current user was deleted.")
+                .example(example_error(WebhookCreateError::NonExistentUser))
+        })
+        .response_with::<422, Json<Value>, _>(|op| {
+            op.description("Provided webhook URL is not allowed.")
+                .example(example_error(WebhookCreateError::ForbiddenUrl(
+                    WebhookUrlError::InsecureScheme,
+                )))
+        })
+}
+
+/// Webhook creation handler.
+pub(super) async fn create(
+    Extension(current_user): Extension<AuthenticatedUserId>,
+    State(db): State<Arc<DatabaseConnection>>,
+    ValidatedJson(request): ValidatedJson<WebhookCreateRequest>,
+) -> Result<Json<WebhookCreateResponse>, WebhookCreateError> {
+    webhook_url::validate(&request.url).await?;
+
+    db.transaction(|txn| {
+        Box::pin(async move {
+            let user_exists = user::Entity::find_by_id(current_user.id())
+                .select_only()
+                .exists(txn)
+                .await?;
+
+            if !user_exists {
+                return Err(WebhookCreateError::NonExistentUser);
+            }
+
+            let model = webhook::Entity::insert(webhook::ActiveModel {
+                user_id: ActiveValue::Set(current_user.id()),
+                url: ActiveValue::Set(request.url),
+                secret: ActiveValue::Set(request.secret),
+                ..Default::default()
+            })
+            .exec_with_returning(txn)
+            .await?;
+
+            Ok(Json(WebhookCreateResponse { id: model.id }))
+        })
+    })
+    .await
+    .into_raw_result()
+}
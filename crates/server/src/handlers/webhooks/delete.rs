@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use axum_derive_error::ErrorResponse;
+use db::{webhook, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use derive_more::{Display, Error, From};
+use serde_json::Value;
+
+use crate::{auth::AuthenticatedUserId, schema::example_error};
+
+/// Errors that may occur during the webhook deletion request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum WebhookDeletionError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+
+    /// Webhook was not found or belongs to another user.
+    #[status(StatusCode::NOT_FOUND)]
+    #[display(fmt = "webhook not found")]
+    WebhookNotFound,
+}
+
+/// Generate OAPI documentation for the [`delete`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("Delete webhook of the current user.")
+        .response::<200, ()>()
+        .response_with::<404, Json<Value>, _>(|op| {
+            op.description("Webhook with the provided identifier was not found.")
+                .example(example_error(WebhookDeletionError::WebhookNotFound))
+        })
+}
+
+/// Delete webhook of the current authenticated user.
+pub(super) async fn delete(
+    Path(id): Path<i64>,
+    Extension(current_user): Extension<AuthenticatedUserId>,
+    State(db): State<Arc<DatabaseConnection>>,
+) -> Result<(), WebhookDeletionError> {
+    let result = webhook::Entity::delete_many()
+        .filter(webhook::Column::Id.eq(id))
+        .filter(webhook::Column::UserId.eq(current_user.id()))
+        .exec(&*db)
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(WebhookDeletionError::WebhookNotFound);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::testing::{create_database, RequestBodyExt, ResponseBodyExt};
+
+    use assert_json::{assert_json, validators};
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{token, user, webhook, DatabaseConnection, EntityTrait};
+    use serde_json::json;
+    use tower::Service;
+
+    /// Create a user with an authentication token.
+    async fn create_user(db: &DatabaseConnection) -> String {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let (model, token) = token::generate_token(user.id);
+
+        token::Entity::insert(model)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert token");
+
+        token
+    }
+
+    /// Create a webhook request with the provided method and URI.
+    fn request(method: &str, uri: &str, token: &str, body: Body) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {token}"))
+            .header("Content-Type", "application/json")
+            .body(body)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_list_and_delete() {
+        let db = Arc::new(create_database().await);
+
+        let token = create_user(&db).await;
+
+        let mut service = crate::app_router(db.clone(), Arc::new(Config::for_tests()));
+
+        let response = service
+            .call(request(
+                "POST",
+                "/webhooks",
+                &token,
+                Body::from_json(json!({
+                    "url": "https://203.0.113.10/webhook",
+                    "secret": "0123456789abcdef",
+                })),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_json!(response.json().await, {
+            "id": 1,
+        });
+
+        let stored = webhook::Entity::find_by_id(1)
+            .one(&*db)
+            .await
+            .unwrap()
+            .expect("webhook was not stored");
+        assert_eq!(stored.secret, "0123456789abcdef");
+
+        let response = service
+            .call(request("GET", "/webhooks", &token, Body::empty()))
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, [
+            {
+                "id": 1,
+                "url": "https://203.0.113.10/webhook",
+                "created_at": validators::i64(|_| Ok(())),
+            }
+        ]);
+
+        let response = service
+            .call(request("DELETE", "/webhooks/1", &token, Body::empty()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = service
+            .call(request("GET", "/webhooks", &token, Body::empty()))
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, []);
+    }
+
+    #[tokio::test]
+    async fn invalid_request() {
+        let db = Arc::new(create_database().await);
+
+        let token = create_user(&db).await;
+
+        let mut service = crate::app_router(db, Arc::new(Config::for_tests()));
+
+        for body in [
+            json!({ "url": "not a url", "secret": "0123456789abcdef" }),
+            json!({ "url": "https://203.0.113.10/webhook", "secret": "short" }),
+            json!({ "url": "http://203.0.113.10/webhook", "secret": "0123456789abcdef" }),
+            json!({ "url": "https://127.0.0.1/webhook", "secret": "0123456789abcdef" }),
+            json!({ "url": "https://localhost/webhook", "secret": "0123456789abcdef" }),
+            json!({ "url": "https://10.0.0.1/webhook", "secret": "0123456789abcdef" }),
+        ] {
+            let response = service
+                .call(request("POST", "/webhooks", &token, Body::from_json(body)))
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
+    #[tokio::test]
+    async fn foreign_webhook() {
+        let db = Arc::new(create_database().await);
+
+        let owner_token = create_user(&db).await;
+        let token = create_user(&db).await;
+
+        let mut service = crate::app_router(db, Arc::new(Config::for_tests()));
+
+        service
+            .call(request(
+                "POST",
+                "/webhooks",
+                &owner_token,
+                Body::from_json(json!({
+                    "url": "https://203.0.113.10/webhook",
+                    "secret": "0123456789abcdef",
+                })),
+            ))
+            .await
+            .unwrap();
+
+        let response = service
+            .call(request("GET", "/webhooks", &token, Body::empty()))
+            .await
+            .unwrap();
+
+        assert_json!(response.json().await, []);
+
+        let response = service
+            .call(request("DELETE", "/webhooks/1", &token, Body::empty()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_json!(response.json().await, {
+            "code": 404,
+            "error": "webhook not found",
+        });
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use aide::{transform::TransformOperation, OperationIo};
+use axum::{extract::State, Extension, Json};
+use axum_derive_error::ErrorResponse;
+use db::{
+    webhook, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PrimitiveDateTime, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+use derive_more::{Display, Error, From};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::{
+    auth::AuthenticatedUserId,
+    pagination::Pagination,
+    schema::{example_database_identifier, example_timestamp, example_webhook_url},
+};
+
+/// A single webhook data.
+///
+/// Webhook secrets are never returned after creation.
+#[derive(Serialize, JsonSchema)]
+pub struct WebhookData {
+    /// Webhook identifier.
+    #[schemars(example = "crate::schema::example_database_identifier")]
+    pub id: i64,
+
+    /// URL that receives build session notifications.
+    #[schemars(example = "crate::schema::example_webhook_url")]
+    pub url: String,
+
+    /// Webhook creation timestamp.
+    #[schemars(example = "crate::schema::example_timestamp")]
+    pub created_at: i64,
+}
+
+/// Errors that may occur during the webhook list request handling.
+#[derive(ErrorResponse, Display, From, Error, OperationIo)]
+#[aide(output)]
+pub(super) enum WebhookListError {
+    /// Database-related error.
+    DatabaseError(DbErr),
+}
+
+/// Generate OAPI documentation for the [`list`] handler.
+pub(super) fn docs(op: TransformOperation) -> TransformOperation {
+    op.summary("List webhooks of the current user.")
+        .response_with::<200, Json<Vec<WebhookData>>, _>(|op| {
+            op.description("Webhook list.").example(vec![WebhookData {
+                id: example_database_identifier(),
+                url: example_webhook_url(),
+                created_at: example_timestamp(),
+            }])
+        })
+}
+
+/// List webhooks of the current authenticated user.
+pub(super) async fn list(
+    Extension(current_user): Extension<AuthenticatedUserId>,
+    State(db): State<Arc<DatabaseConnection>>,
+    pagination: Pagination,
+) -> Result<Json<Vec<WebhookData>>, WebhookListError> {
+    let webhooks = webhook::Entity::find()
+        .select_only()
+        .columns([
+            webhook::Column::Id,
+            webhook::Column::Url,
+            webhook::Column::CreatedAt,
+        ])
+        .filter(webhook::Column::UserId.eq(current_user.id()))
+        .order_by_asc(webhook::Column::Id)
+        .limit(pagination.limit())
+        .offset(pagination.offset())
+        .into_tuple::<(i64, String, PrimitiveDateTime)>()
+        .all(&*db)
+        .await?
+        .into_iter()
+        .map(|(id, url, created_at)| WebhookData {
+            id,
+            url,
+            created_at: created_at.assume_utc().unix_timestamp(),
+        })
+        .collect();
+
+    Ok(Json(webhooks))
+}
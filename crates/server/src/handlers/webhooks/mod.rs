@@ -0,0 +1,27 @@
+/// Webhook creation route.
+mod create;
+
+/// Webhook deletion route.
+mod delete;
+
+/// Webhook list route.
+mod list;
+
+use std::sync::Arc;
+
+use aide::axum::{
+    routing::{delete_with, get_with},
+    ApiRouter,
+};
+use db::DatabaseConnection;
+
+/// Create an [`ApiRouter`] that provides an API server with webhook management routes.
+pub(crate) fn routes() -> ApiRouter<Arc<DatabaseConnection>> {
+    ApiRouter::new()
+        .api_route(
+            "/",
+            get_with(list::list, list::docs).post_with(create::create, create::docs),
+        )
+        .api_route("/:id", delete_with(delete::delete, delete::docs))
+        .with_path_items(|op| op.tag("Webhooks"))
+}
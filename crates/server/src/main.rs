@@ -14,6 +14,15 @@
 /// API authentication middleware and helpers.
 mod auth;
 
+/// Substrate node RPC client used to dry-run contract calls.
+mod contract_rpc;
+
+/// Line-based text diff.
+mod diff;
+
+/// Build session event broadcasting.
+mod events;
+
 /// Route handlers.
 mod handlers;
 
@@ -23,9 +32,18 @@ mod hex_hash;
 /// Resource pagination structs.
 mod pagination;
 
+/// Per-user request rate limiting.
+mod rate_limit;
+
 /// Validated JSON bodies.
 mod validation;
 
+/// Account ownership proofs.
+mod signing;
+
+/// Source code and build artifact visibility checks.
+mod visibility;
+
 /// [`schemars`] crate helper functions.
 mod schema;
 
@@ -41,7 +59,10 @@ use aide::{
 };
 use axum::{middleware::from_fn_with_state, Extension, Server};
 use common::{config::Config, logging};
+use contract_rpc::{ContractRpc, NodeRpc};
 use db::{Database, DatabaseConnection};
+use events::BuildSessionEvents;
+use rate_limit::RateLimiter;
 use tracing::info;
 
 /// API server entrypoint.
@@ -77,6 +98,16 @@ async fn main() -> Result<(), anyhow::Error> {
 
 /// Construct a [`ApiRouter`] with API server endpoints.
 fn app_router(database: Arc<DatabaseConnection>, config: Arc<Config>) -> ApiRouter {
+    app_router_with_rpc(database, config, Arc::new(NodeRpc))
+}
+
+/// Construct a [`ApiRouter`] with API server endpoints, that use the provided
+/// [`ContractRpc`] implementation to dry-run contract calls.
+fn app_router_with_rpc(
+    database: Arc<DatabaseConnection>,
+    config: Arc<Config>,
+    rpc: Arc<dyn ContractRpc>,
+) -> ApiRouter {
     let mixed_routes = ApiRouter::new()
         .nest(
             "/sourceCode",
@@ -89,9 +120,10 @@ fn app_router(database: Arc<DatabaseConnection>, config: Arc<Config>) -> ApiRout
 
     let protected_routes = ApiRouter::new()
         .nest("/keys", handlers::keys::routes())
+        .nest("/webhooks", handlers::webhooks::routes())
         .route_layer(from_fn_with_state(
             (database.clone(), config.clone()),
-            auth::require_authentication::<false, false, _>,
+            auth::require_authentication::<false, false, true, _>,
         ))
         .with_path_items(|op| op.security_requirement("Authentication token"));
 
@@ -99,7 +131,7 @@ fn app_router(database: Arc<DatabaseConnection>, config: Arc<Config>) -> ApiRout
         .nest("/payment", handlers::payment::routes())
         .route_layer(from_fn_with_state(
             (database.clone(), config.clone()),
-            auth::require_authentication::<true, false, _>,
+            auth::require_authentication::<true, false, true, _>,
         ))
         .with_path_items(|op| op.security_requirement("Authentication token"));
 
@@ -108,10 +140,20 @@ fn app_router(database: Arc<DatabaseConnection>, config: Arc<Config>) -> ApiRout
         .merge(protected_routes)
         .merge(payment_routes)
         .nest("/auth", handlers::auth::routes())
-        .nest("/contracts", handlers::contracts::routes())
-        .nest("/files", handlers::files::routes())
+        .nest("/codes", handlers::codes::routes(database.clone()))
+        .nest(
+            "/contracts",
+            handlers::contracts::routes(database.clone(), config.clone()),
+        )
+        .nest("/files", handlers::files::routes(database.clone()))
+        .nest("/metadata", handlers::metadata::routes(database.clone()))
+        .nest("/nodes", handlers::nodes::routes())
         .nest("/docs", handlers::docs::routes())
+        .nest("/internal", handlers::internal::routes())
         .layer(Extension(config))
+        .layer(Extension(BuildSessionEvents::default()))
+        .layer(Extension(RateLimiter::default()))
+        .layer(Extension(rpc))
         .with_state(database)
 }
 
@@ -127,6 +169,10 @@ fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
             name: "Build session management".into(),
             ..Default::default()
         })
+        .tag(Tag {
+            name: "Code management".into(),
+            ..Default::default()
+        })
         .tag(Tag {
             name: "Contract management".into(),
             ..Default::default()
@@ -135,6 +181,14 @@ fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
             name: "File uploads".into(),
             ..Default::default()
         })
+        .tag(Tag {
+            name: "Metadata search".into(),
+            ..Default::default()
+        })
+        .tag(Tag {
+            name: "Node information".into(),
+            ..Default::default()
+        })
         .tag(Tag {
             name: "Public key verification".into(),
             ..Default::default()
@@ -147,12 +201,23 @@ fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
             name: "Source code management".into(),
             ..Default::default()
         })
+        .tag(Tag {
+            name: "Webhooks".into(),
+            ..Default::default()
+        })
         .security_scheme(
             "Authentication token",
             SecurityScheme::Http {
                 scheme: String::from("bearer"),
                 bearer_format: None,
-                description: None,
+                description: Some(String::from(
+                    "Authentication tokens have one of the following scopes:\n\n\
+                    - `full` allows access to all routes.\n\
+                    - `ci` allows access to build session and source code management routes only, \
+                    while key management, webhook and payment routes are rejected.\n\n\
+                    Token scope can be requested with the `scope` query parameter \
+                    of the `/auth/login` route.",
+                )),
                 extensions: Default::default(),
             },
         )
@@ -1,7 +1,21 @@
-use std::num::NonZeroU64;
+use std::{num::NonZeroU64, sync::Arc};
 
+use aide::{
+    gen::GenContext,
+    openapi::{Operation, Response},
+    OperationInput,
+};
+use axum::{
+    async_trait,
+    extract::{rejection::QueryRejection, FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+};
+use axum_derive_error::ErrorResponse;
+use common::config::{default_max_page_size, Config};
+use derive_more::{Display, Error};
 use schemars::JsonSchema;
 use serde::Deserialize;
+use validator::{ValidationError, ValidationErrors};
 
 /// Count of items per page.
 pub const PER_PAGE: u64 = 25;
@@ -9,14 +23,42 @@ pub const PER_PAGE: u64 = 25;
 /// Total page limit.
 pub const MAX_PAGES: u64 = 10000;
 
-/// Pagination helper for the [`Query`] extractor.
-///
-/// [`Query`]: axum::extract::Query
+/// Errors related to pagination query string parsing.
+#[derive(ErrorResponse, Display, Error)]
+pub enum PaginationRejection {
+    /// Unable to parse a query string.
+    #[status(StatusCode::BAD_REQUEST)]
+    QueryParsingError(QueryRejection),
+
+    /// Provided page size is out of the allowed bounds.
+    #[status(StatusCode::UNPROCESSABLE_ENTITY)]
+    ValidationError(ValidationErrors),
+}
+
+/// Offset pagination query string.
 #[derive(Deserialize, JsonSchema)]
-pub struct Pagination {
+struct PaginationQuery {
     /// Current page value.
     #[serde(default = "default_page")]
     page: NonZeroU64,
+
+    /// Count of items per page.
+    ///
+    /// Must not exceed the maximum page size configured on the server (100 by default).
+    #[serde(default)]
+    #[schemars(range(min = 1))]
+    limit: Option<u64>,
+}
+
+/// Offset pagination extractor.
+///
+/// Page size is validated against the `server.max_page_size` configuration value.
+pub struct Pagination {
+    /// Current page value.
+    page: NonZeroU64,
+
+    /// Count of items per page.
+    limit: u64,
 }
 
 /// Default page value used when user didn't provide one.
@@ -28,11 +70,222 @@ fn default_page() -> NonZeroU64 {
 impl Pagination {
     /// Get `LIMIT` value for a SQL query.
     pub fn limit(&self) -> u64 {
-        PER_PAGE
+        self.limit
     }
 
     /// Get `OFFSET` value for a SQL query.
     pub fn offset(&self) -> u64 {
-        (self.page.get().min(MAX_PAGES) - 1) * PER_PAGE
+        (self.page.get().min(MAX_PAGES) - 1) * self.limit
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Pagination {
+    type Rejection = PaginationRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<PaginationQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(PaginationRejection::QueryParsingError)?;
+
+        Ok(Self {
+            page: query.page,
+            limit: validate_limit(query.limit, parts)?,
+        })
+    }
+}
+
+impl OperationInput for Pagination {
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        <Query<PaginationQuery> as OperationInput>::operation_input(ctx, operation)
+    }
+
+    fn inferred_early_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        <Query<PaginationQuery> as OperationInput>::inferred_early_responses(ctx, operation)
+    }
+}
+
+/// Cursor-based pagination query string.
+#[derive(Deserialize, JsonSchema)]
+struct CursorPaginationQuery {
+    /// Identifier of the last seen item.
+    ///
+    /// If provided, only those items that follow the item with
+    /// the provided identifier will be returned.
+    #[serde(default)]
+    after: Option<i64>,
+
+    /// Maximum count of items per page.
+    ///
+    /// Must not exceed the maximum page size configured on the server (100 by default).
+    #[serde(default)]
+    #[schemars(range(min = 1))]
+    limit: Option<u64>,
+}
+
+/// Cursor-based pagination extractor.
+///
+/// Unlike [`Pagination`], cursor-based pagination doesn't degrade with the page number,
+/// since the next page is located using the identifier of the last seen item.
+pub struct CursorPagination {
+    /// Identifier of the last seen item.
+    after: Option<i64>,
+
+    /// Maximum count of items per page.
+    limit: u64,
+}
+
+impl CursorPagination {
+    /// Get identifier of the last seen item.
+    pub fn after(&self) -> Option<i64> {
+        self.after
+    }
+
+    /// Get `LIMIT` value for a SQL query.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Get cursor of the next page from the current page items.
+    ///
+    /// Returns [`None`] if the current page is not full, meaning that there are no more items.
+    pub fn next_cursor<T>(&self, items: &[T], id: impl Fn(&T) -> i64) -> Option<i64> {
+        if items.len() as u64 == self.limit() {
+            items.last().map(id)
+        } else {
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CursorPagination {
+    type Rejection = PaginationRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<CursorPaginationQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(PaginationRejection::QueryParsingError)?;
+
+        Ok(Self {
+            after: query.after,
+            limit: validate_limit(query.limit, parts)?,
+        })
+    }
+}
+
+impl OperationInput for CursorPagination {
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        <Query<CursorPaginationQuery> as OperationInput>::operation_input(ctx, operation)
+    }
+
+    fn inferred_early_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        <Query<CursorPaginationQuery> as OperationInput>::inferred_early_responses(ctx, operation)
+    }
+}
+
+/// Validate the page size provided by user, falling back to [`PER_PAGE`] if none was provided.
+///
+/// Maximum page size is retrieved from the [`Config`] request extension.
+fn validate_limit(limit: Option<u64>, parts: &Parts) -> Result<u64, PaginationRejection> {
+    let max_page_size = parts
+        .extensions
+        .get::<Arc<Config>>()
+        .and_then(|config| config.server.as_ref())
+        .map_or_else(default_max_page_size, |server| server.max_page_size);
+
+    let Some(limit) = limit else {
+        return Ok(PER_PAGE.min(max_page_size));
+    };
+
+    if (1..=max_page_size).contains(&limit) {
+        return Ok(limit);
+    }
+
+    let mut error = ValidationError::new("range");
+    error.add_param("min".into(), &1);
+    error.add_param("max".into(), &max_page_size);
+    error.add_param("value".into(), &limit);
+
+    let mut errors = ValidationErrors::new();
+    errors.add("limit", error);
+
+    Err(PaginationRejection::ValidationError(errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::FromRequestParts, http::Request};
+    use common::config::Config;
+
+    use super::{CursorPagination, Pagination, PaginationRejection, PER_PAGE};
+
+    /// Extract [`Pagination`] from the provided query string using the provided maximum page size.
+    async fn pagination(
+        query: &str,
+        max_page_size: u64,
+    ) -> Result<Pagination, PaginationRejection> {
+        let mut config = Config::for_tests();
+        config.server.as_mut().unwrap().max_page_size = max_page_size;
+
+        let (mut parts, _) = Request::builder()
+            .uri(format!("/?{query}"))
+            .extension(Arc::new(config))
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        Pagination::from_request_parts(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn limit_bounds() {
+        assert_eq!(pagination("", 100).await.unwrap().limit(), PER_PAGE);
+        assert_eq!(pagination("", 10).await.unwrap().limit(), 10);
+        assert_eq!(pagination("limit=1", 100).await.unwrap().limit(), 1);
+        assert_eq!(pagination("limit=100", 100).await.unwrap().limit(), 100);
+
+        assert!(matches!(
+            pagination("limit=0", 100).await,
+            Err(PaginationRejection::ValidationError(_))
+        ));
+        assert!(matches!(
+            pagination("limit=101", 100).await,
+            Err(PaginationRejection::ValidationError(_))
+        ));
+        assert!(matches!(
+            pagination("limit=-1", 100).await,
+            Err(PaginationRejection::QueryParsingError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn offset() {
+        let pagination = pagination("page=3&limit=10", 100).await.unwrap();
+
+        assert_eq!(pagination.offset(), 20);
+    }
+
+    #[tokio::test]
+    async fn cursor_limit_bounds() {
+        let (mut parts, _) = Request::builder()
+            .uri("/?after=5&limit=101")
+            .extension(Arc::new(Config::for_tests()))
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        assert!(matches!(
+            CursorPagination::from_request_parts(&mut parts, &()).await,
+            Err(PaginationRejection::ValidationError(_))
+        ));
     }
 }
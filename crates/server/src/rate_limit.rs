@@ -0,0 +1,80 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Duration of a single rate limiting window.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Request counter of a single rate limiting window.
+struct Window {
+    /// Time at which the window was started.
+    started_at: Instant,
+
+    /// Count of requests accepted during the window.
+    count: u32,
+}
+
+/// Fixed-window rate limiter keyed by user identifiers, shared between all request handlers.
+#[derive(Clone, Default)]
+pub(crate) struct RateLimiter(Arc<Mutex<HashMap<i64, Window>>>);
+
+impl RateLimiter {
+    /// Attempt to accept a new request of the provided user, allowing at most
+    /// `limit` requests per minute.
+    ///
+    /// Returns `false` if the request has to be rejected.
+    pub(crate) fn try_acquire(&self, user_id: i64, limit: u32) -> bool {
+        self.try_acquire_at(user_id, limit, Instant::now())
+    }
+
+    /// Attempt to accept a new request of the provided user at the provided time.
+    fn try_acquire_at(&self, user_id: i64, limit: u32, now: Instant) -> bool {
+        let mut windows = self.0.lock().expect("rate limiter lock is poisoned");
+
+        if !windows.contains_key(&user_id) {
+            // Discard expired windows of other users to keep memory usage bounded.
+            windows.retain(|_, window| now.duration_since(window.started_at) < WINDOW);
+        }
+
+        let window = windows.entry(user_id).or_insert(Window {
+            started_at: now,
+            count: 0,
+        });
+
+        if now.duration_since(window.started_at) >= WINDOW {
+            window.started_at = now;
+            window.count = 0;
+        }
+
+        if window.count >= limit {
+            return false;
+        }
+
+        window.count += 1;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::RateLimiter;
+
+    #[test]
+    fn fixed_window() {
+        let limiter = RateLimiter::default();
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(1, 2, now));
+        assert!(limiter.try_acquire_at(1, 2, now + Duration::from_secs(1)));
+        assert!(!limiter.try_acquire_at(1, 2, now + Duration::from_secs(2)));
+
+        assert!(limiter.try_acquire_at(2, 2, now + Duration::from_secs(2)));
+
+        assert!(limiter.try_acquire_at(1, 2, now + Duration::from_secs(60)));
+    }
+}
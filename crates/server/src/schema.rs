@@ -1,15 +1,22 @@
 use std::fmt::Display;
 
+use api_types::build_sessions::ArtifactSignature;
 use axum::response::IntoResponse;
-use common::rpc::sp_core::{
-    crypto::{AccountId32, Ss58Codec},
-    sr25519::{Pair, Public, Signature},
-    Pair as _,
+use common::{
+    hash::HexBytes,
+    rpc::sp_core::{
+        crypto::{AccountId32, Ss58Codec},
+        sr25519::{Pair, Public, Signature},
+        Pair as _,
+    },
 };
-use db::{build_session, diagnostic, event::EventBody};
+use db::{build_session, build_session_stage::Stage, diagnostic, event::EventBody};
 use serde_json::{json, Value};
 
-use crate::hex_hash::HexHash;
+use crate::{
+    diff::{LineChange, LineChangeKind},
+    hex_hash::HexHash,
+};
 
 /// Generate example values for OAPI documentation.
 macro_rules! generate_examples {
@@ -40,16 +47,21 @@ pub(crate) fn example_error<E: Display + IntoResponse>(err: E) -> Value {
 
 generate_examples!(
     database_identifier, i64, 1;
-    hex_hash, HexHash, HexHash([200; 32]);
+    hex_hash, HexHash, HexHash::from([200; 32]);
     cargo_contract_version, String, String::from("4.0.0-alpha");
     build_session_status, build_session::Status, build_session::Status::Completed;
     log_position, Option<i64>, Some(40);
     log_entry, String, String::from("Compiling futures-util v0.3.28");
+    log_repeat_count, i64, 1;
     timestamp, i64, 1672531200;
+    block_number, Option<i64>, Some(4200000);
     account, AccountId32, AccountId32::from_ss58check("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY").unwrap();
     public_key, Public, Public(example_account().into());
     signature, Signature, Pair::from_seed(&[0; 32]).sign(b"test message");
     token, String, String::from("UYEIngStyH6Bxu1hLFIIwBxLgyMBhMQv4SVR1KzzbvzIDCSMcwwF8ApXagqyuWbh");
+    signing_domain, String, String::from("patron.works");
+    login_message_template, String, String::from("<Bytes>patron:patron.works:login:{address}</Bytes>");
+    verify_message_template, String, String::from("<Bytes>patron:patron.works:verify:{address}</Bytes>");
     event_body, EventBody, EventBody::CodeHashUpdate {
         new_code_hash: hex::encode([200; 32]),
     };
@@ -61,8 +73,56 @@ generate_examples!(
     ];
     folder, Option<String>, Some(String::from("contracts/test_contract"));
     node, String, String::from("alephzero");
+    call_data, String, String::from("0x633aa551");
+    node_display_name, Option<String>, Some(String::from("Aleph Zero"));
+    explorer_url_template, Option<String>, Some(String::from("https://alephzero.subscan.io/account/{address}"));
+    token_symbol, Option<String>, Some(String::from("AZERO"));
+    token_decimals, Option<i16>, Some(12);
+    last_block_seen_at, Option<i64>, Some(1672531200);
+    node_last_error, Option<String>, None;
+    node_healthy, bool, true;
+    ss58_prefix, Option<i16>, Some(42);
     diagnostic_level, diagnostic::Level, diagnostic::Level::Error;
     diagnostic_start, i64, 0;
     diagnostic_end, i64, 1;
-    diagnostic_message, String, String::from("test")
+    diagnostic_start_line, Option<i64>, Some(1);
+    diagnostic_start_col, Option<i64>, Some(1);
+    diagnostic_end_line, Option<i64>, Some(1);
+    diagnostic_end_col, Option<i64>, Some(2);
+    diagnostic_message, String, String::from("test");
+    webhook_url, String, String::from("https://example.com/webhook");
+    stage_duration, Option<i64>, Some(15000);
+    image_digest, Option<String>, Some(format!("sha256:{}", hex::encode([200; 32])));
+    build_image, Option<String>, Some(format!("paritytech/contracts-verifiable@sha256:{}", hex::encode([200; 32])));
+    builder_hostname, Option<String>, Some(String::from("builder-1"));
+    signing_key_id, String, String::from("1f9c2e4b7a0d3c58");
+    signing_public_key, HexBytes<32>, HexBytes([212; 32]);
+    artifact_signature, Option<ArtifactSignature>, Some(ArtifactSignature {
+        key_id: example_signing_key_id(),
+        public_key: example_signing_public_key(),
+        signature: HexBytes([100; 64]),
+    });
+    contract_name, Option<String>, Some(String::from("flipper"));
+    queue_position, Option<i64>, Some(2);
+    build_session_stage, Option<Stage>, Some(Stage::Building);
+    artifact_kind, Option<build_session::ArtifactKind>, Some(build_session::ArtifactKind::Wasm);
+    message_name, String, String::from("flip");
+    code_size, i64, 16384;
+    file_text, String, String::from("#[ink::contract]\nmod flipper {}");
+    contract_event_body, String, serde_json::to_string(&example_event_body()).unwrap();
+    line_change, LineChange, LineChange {
+        kind: LineChangeKind::Added,
+        line: 2,
+        text: String::from("mod flipper {}"),
+    };
+    abi_spec, Value, json!({
+        "constructors": [],
+        "messages": [{ "label": "flip", "selector": "0x633aa551" }],
+        "events": [],
+    });
+    abi_types, Value, json!([]);
+    multipart_error, Value, json!({
+        "code": 400,
+        "error": "Error parsing `multipart/form-data` request",
+    })
 );
@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+
+use common::{
+    config::{default_signing_domain, Config},
+    rpc::sp_core::{
+        sr25519::{Pair, Public, Signature},
+        Pair as _,
+    },
+};
+use derive_more::Display;
+
+/// Placeholder of the account address inside of signed message templates.
+pub(crate) const ADDRESS_PLACEHOLDER: &str = "{address}";
+
+/// Purpose of a signed account ownership proof.
+///
+/// Purpose is included into the signed message, so that ownership proofs
+/// created for one route cannot be used with another one.
+#[derive(Clone, Copy, Display)]
+pub(crate) enum Purpose {
+    /// User authentication.
+    #[display(fmt = "login")]
+    Login,
+
+    /// Public key verification.
+    #[display(fmt = "verify")]
+    Verify,
+}
+
+/// Get the domain string included into signed messages.
+pub(crate) fn signing_domain(config: &Config) -> Cow<'_, str> {
+    config.server.as_ref().map_or_else(
+        || Cow::Owned(default_signing_domain()),
+        |server| Cow::Borrowed(&server.signing_domain),
+    )
+}
+
+/// Check if deprecated ownership proofs, that contain only the account address, are accepted.
+pub(crate) fn legacy_signatures(config: &Config) -> bool {
+    matches!(&config.server, Some(server) if server.legacy_signatures)
+}
+
+/// Create a message, that has to be signed with an account to prove its ownership.
+///
+/// Use [`ADDRESS_PLACEHOLDER`] as an address to create a message template.
+pub(crate) fn message(config: &Config, purpose: Purpose, address: &str) -> String {
+    format!(
+        "<Bytes>patron:{}:{purpose}:{address}</Bytes>",
+        signing_domain(config)
+    )
+}
+
+/// Verify the provided account ownership proof.
+///
+/// Deprecated ownership proofs are accepted only if enabled with the server configuration.
+pub(crate) fn verify(
+    config: &Config,
+    purpose: Purpose,
+    signature: &Signature,
+    account: &Public,
+) -> bool {
+    let address = account.to_string();
+
+    Pair::verify(signature, message(config, purpose, &address), account)
+        || (legacy_signatures(config)
+            && Pair::verify(signature, format!("<Bytes>{address}</Bytes>"), account))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{
+        config::Config,
+        rpc::sp_core::{sr25519::Pair, Pair as _},
+    };
+
+    use super::{message, verify, Purpose, ADDRESS_PLACEHOLDER};
+
+    #[test]
+    fn message_format() {
+        let mut config = Config::for_tests();
+        config.server.as_mut().unwrap().signing_domain = String::from("example.com");
+
+        assert_eq!(
+            message(&config, Purpose::Verify, ADDRESS_PLACEHOLDER),
+            "<Bytes>patron:example.com:verify:{address}</Bytes>"
+        );
+    }
+
+    #[test]
+    fn purpose_binding() {
+        let config = Config::for_tests();
+        let pair = Pair::from_seed(&[1; 32]);
+        let account = pair.public();
+
+        let signature =
+            pair.sign(message(&config, Purpose::Login, &account.to_string()).as_bytes());
+
+        assert!(verify(&config, Purpose::Login, &signature, &account));
+        assert!(!verify(&config, Purpose::Verify, &signature, &account));
+    }
+
+    #[test]
+    fn legacy_format() {
+        let mut config = Config::for_tests();
+        let pair = Pair::from_seed(&[1; 32]);
+        let account = pair.public();
+
+        let signature = pair.sign(format!("<Bytes>{account}</Bytes>").as_bytes());
+
+        assert!(!verify(&config, Purpose::Login, &signature, &account));
+
+        config.server.as_mut().unwrap().legacy_signatures = true;
+
+        assert!(verify(&config, Purpose::Login, &signature, &account));
+    }
+}
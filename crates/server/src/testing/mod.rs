@@ -1,21 +1,22 @@
 use std::error::Error;
 
 use axum::async_trait;
-use db::{Database, DatabaseConnection};
+use db::{
+    source_code::{self, Visibility},
+    DatabaseConnection, EntityTrait,
+};
 use hyper::body::{self, Bytes, HttpBody};
-use migration::MigratorTrait;
 use serde::Serialize;
 
-pub(crate) async fn create_database() -> DatabaseConnection {
-    let db = Database::connect("sqlite::memory:")
-        .await
-        .expect("unable to create test database");
+pub(crate) use migration::testing::create_database;
 
-    migration::Migrator::up(&db, None)
+/// Make all stored source code archives private.
+pub(crate) async fn make_source_code_private(db: &DatabaseConnection) {
+    source_code::Entity::update_many()
+        .col_expr(source_code::Column::Visibility, Visibility::Private.into())
+        .exec(db)
         .await
-        .expect("unable to run migrations");
-
-    db
+        .expect("unable to update source code");
 }
 
 pub(crate) trait RequestBodyExt: Sized {
@@ -0,0 +1,367 @@
+use db::{
+    build_session, contract,
+    sea_orm::Condition,
+    source_code::{self, Visibility},
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect, QueryTrait,
+    SelectExt,
+};
+
+use crate::auth::AuthenticatedUserId;
+
+/// Check whether the provided source code archive owner is the current user.
+pub(crate) fn owned(user_id: Option<i64>, current_user: Option<AuthenticatedUserId>) -> bool {
+    match (user_id, current_user) {
+        (Some(user_id), Some(current_user)) => user_id == current_user.id(),
+        _ => false,
+    }
+}
+
+/// Condition that matches source code archives that are either public
+/// or owned by the current user.
+fn public_or_owned(current_user: Option<AuthenticatedUserId>) -> Condition {
+    let condition = Condition::any().add(source_code::Column::Visibility.eq(Visibility::Public));
+
+    match current_user {
+        Some(current_user) => condition.add(source_code::Column::UserId.eq(current_user.id())),
+        None => condition,
+    }
+}
+
+/// Check whether any completed build session of the provided source code archive
+/// produced a code hash of a contract discovered on-chain.
+pub(crate) async fn source_code_deployed<C: ConnectionTrait + Send>(
+    db: &C,
+    source_code_id: i64,
+) -> Result<bool, DbErr> {
+    let code_hashes = build_session::Entity::find()
+        .select_only()
+        .column(build_session::Column::CodeHash)
+        .filter(build_session::Column::SourceCodeId.eq(source_code_id))
+        .filter(build_session::Column::Status.eq(build_session::Status::Completed))
+        .into_query();
+
+    contract::Entity::find()
+        .select_only()
+        .filter(contract::Column::CodeHash.in_subquery(code_hashes))
+        .exists(db)
+        .await
+}
+
+/// Check whether the source code archive with the provided identifier
+/// is visible to the current user.
+///
+/// Public source code archives are visible to everyone, while private ones are visible
+/// only to their owners, unless they were deployed on-chain.
+pub(crate) async fn source_code_visible<C: ConnectionTrait + Send>(
+    db: &C,
+    source_code_id: i64,
+    current_user: Option<AuthenticatedUserId>,
+) -> Result<bool, DbErr> {
+    let Some((user_id, visibility)) = source_code::Entity::find_by_id(source_code_id)
+        .select_only()
+        .columns([source_code::Column::UserId, source_code::Column::Visibility])
+        .into_tuple::<(Option<i64>, Visibility)>()
+        .one(db)
+        .await?
+    else {
+        return Ok(false);
+    };
+
+    if visibility == Visibility::Public || owned(user_id, current_user) {
+        return Ok(true);
+    }
+
+    source_code_deployed(db, source_code_id).await
+}
+
+/// Condition that matches source code archives visible to the current user.
+///
+/// This is a query counterpart of [`source_code_visible`], suitable for filtering lists.
+pub(crate) fn source_code_visible_condition(
+    current_user: Option<AuthenticatedUserId>,
+) -> Condition {
+    let deployed_code_hashes = contract::Entity::find()
+        .select_only()
+        .column(contract::Column::CodeHash)
+        .into_query();
+
+    let deployed_source_codes = build_session::Entity::find()
+        .select_only()
+        .column(build_session::Column::SourceCodeId)
+        .filter(build_session::Column::Status.eq(build_session::Status::Completed))
+        .filter(build_session::Column::CodeHash.in_subquery(deployed_code_hashes))
+        .into_query();
+
+    public_or_owned(current_user).add(source_code::Column::Id.in_subquery(deployed_source_codes))
+}
+
+/// Check whether the build session with the provided identifier is visible to the current user.
+///
+/// Build sessions share the visibility of their source code archives.
+pub(crate) async fn build_session_visible<C: ConnectionTrait + Send>(
+    db: &C,
+    build_session_id: i64,
+    current_user: Option<AuthenticatedUserId>,
+) -> Result<bool, DbErr> {
+    let Some(source_code_id) = build_session::Entity::find_by_id(build_session_id)
+        .select_only()
+        .column(build_session::Column::SourceCodeId)
+        .into_tuple::<i64>()
+        .one(db)
+        .await?
+    else {
+        return Ok(false);
+    };
+
+    source_code_visible(db, source_code_id, current_user).await
+}
+
+/// Check whether build artifacts with the provided code hash are visible to the current user.
+///
+/// Build artifacts are visible if a contract with the same code hash was discovered on-chain,
+/// or if at least one build session with the same code hash has a visible source code archive.
+///
+/// Code blobs without any related build sessions were discovered on-chain,
+/// and are always visible.
+pub(crate) async fn code_hash_visible<C: ConnectionTrait + Send>(
+    db: &C,
+    code_hash: &[u8],
+    current_user: Option<AuthenticatedUserId>,
+) -> Result<bool, DbErr> {
+    let deployed = contract::Entity::find()
+        .select_only()
+        .filter(contract::Column::CodeHash.eq(code_hash))
+        .exists(db)
+        .await?;
+
+    if deployed {
+        return Ok(true);
+    }
+
+    let visible = build_session::Entity::find()
+        .select_only()
+        .inner_join(source_code::Entity)
+        .filter(build_session::Column::CodeHash.eq(code_hash))
+        .filter(public_or_owned(current_user))
+        .exists(db)
+        .await?;
+
+    if visible {
+        return Ok(true);
+    }
+
+    let built = build_session::Entity::find()
+        .select_only()
+        .filter(build_session::Column::CodeHash.eq(code_hash))
+        .exists(db)
+        .await?;
+
+    Ok(!built)
+}
+
+/// Condition that matches rows with code hashes in the provided column
+/// visible to the current user.
+///
+/// This is a query counterpart of [`code_hash_visible`], suitable for filtering lists.
+pub(crate) fn code_hash_visible_condition<T: ColumnTrait>(
+    column: T,
+    current_user: Option<AuthenticatedUserId>,
+) -> Condition {
+    let deployed = contract::Entity::find()
+        .select_only()
+        .column(contract::Column::CodeHash)
+        .into_query();
+
+    let visible = build_session::Entity::find()
+        .select_only()
+        .column(build_session::Column::CodeHash)
+        .inner_join(source_code::Entity)
+        .filter(build_session::Column::CodeHash.is_not_null())
+        .filter(public_or_owned(current_user))
+        .into_query();
+
+    let built = build_session::Entity::find()
+        .select_only()
+        .column(build_session::Column::CodeHash)
+        .filter(build_session::Column::CodeHash.is_not_null())
+        .into_query();
+
+    Condition::any()
+        .add(column.in_subquery(deployed))
+        .add(column.in_subquery(visible))
+        .add(column.not_in_subquery(built))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use common::config::Config;
+    use db::{
+        build_session, code, contract, node,
+        source_code::{self, Visibility},
+        token, user, ActiveValue, DatabaseConnection, EntityTrait,
+    };
+    use tower::ServiceExt;
+
+    use crate::testing::create_database;
+
+    /// Create a user with an authentication token.
+    async fn create_user(db: &DatabaseConnection) -> (i64, String) {
+        let user = user::Entity::insert(user::ActiveModel::default())
+            .exec_with_returning(db)
+            .await
+            .expect("unable to create user");
+
+        let (model, token) = token::generate_token(user.id);
+
+        token::Entity::insert(model)
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert token");
+
+        (user.id, token)
+    }
+
+    /// Create a completed build session of a source code archive with the provided visibility.
+    async fn create_build_session(db: &DatabaseConnection, user_id: i64, visibility: Visibility) {
+        let source_code_id = source_code::Entity::insert(source_code::ActiveModel {
+            user_id: ActiveValue::Set(Some(user_id)),
+            archive_hash: ActiveValue::Set(vec![0; 32]),
+            visibility: ActiveValue::Set(visibility),
+            ..Default::default()
+        })
+        .exec_with_returning(db)
+        .await
+        .expect("unable to create source code")
+        .id;
+
+        build_session::Entity::insert(build_session::ActiveModel {
+            user_id: ActiveValue::Set(Some(user_id)),
+            source_code_id: ActiveValue::Set(source_code_id),
+            status: ActiveValue::Set(build_session::Status::Completed),
+            cargo_contract_version: ActiveValue::Set(String::from("3.0.0")),
+            code_hash: ActiveValue::Set(Some(vec![0; 32])),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert build session");
+    }
+
+    /// Create a contract blob, optionally discovered on-chain.
+    async fn create_code(db: &DatabaseConnection, deployed: bool) {
+        code::Entity::insert(code::ActiveModel {
+            hash: ActiveValue::Set(vec![0; 32]),
+            code: ActiveValue::Set(vec![1, 2, 3]),
+        })
+        .exec_without_returning(db)
+        .await
+        .expect("unable to insert code");
+
+        if deployed {
+            let node = node::Entity::insert(node::ActiveModel {
+                name: ActiveValue::Set(String::from("test")),
+                url: ActiveValue::Set(String::from("ws://localhost:9944")),
+                confirmed_block: ActiveValue::Set(0),
+                ..Default::default()
+            })
+            .exec_with_returning(db)
+            .await
+            .expect("unable to insert node");
+
+            contract::Entity::insert(contract::ActiveModel {
+                node_id: ActiveValue::Set(node.id),
+                code_hash: ActiveValue::Set(vec![0; 32]),
+                address: ActiveValue::Set(vec![1; 32]),
+                ..Default::default()
+            })
+            .exec_without_returning(db)
+            .await
+            .expect("unable to insert contract");
+        }
+    }
+
+    /// Request build artifact and details routes, returning their response statuses.
+    async fn request(db: Arc<DatabaseConnection>, token: Option<&str>) -> [StatusCode; 2] {
+        let mut statuses = [StatusCode::OK; 2];
+
+        for (status, route) in statuses.iter_mut().zip(["artifact", "details"]) {
+            let mut request = Request::builder()
+                .method("GET")
+                .uri(format!("/buildSessions/{route}/{}", hex::encode([0; 32])));
+
+            if let Some(token) = token {
+                request = request.header("Authorization", format!("Bearer {token}"));
+            }
+
+            *status = crate::app_router(db.clone(), Arc::new(Config::for_tests()))
+                .oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap()
+                .status();
+        }
+
+        statuses
+    }
+
+    #[tokio::test]
+    async fn visibility_combinations() {
+        for (visibility, deployed, owner, other, anonymous) in [
+            (Visibility::Public, false, true, true, true),
+            (Visibility::Public, true, true, true, true),
+            (Visibility::Private, false, true, false, false),
+            (Visibility::Private, true, true, true, true),
+        ] {
+            let db = Arc::new(create_database().await);
+
+            let (owner_id, owner_token) = create_user(&db).await;
+            let (_, token) = create_user(&db).await;
+
+            create_code(&db, deployed).await;
+            create_build_session(&db, owner_id, visibility).await;
+
+            for (token, visible) in [
+                (Some(owner_token.as_str()), owner),
+                (Some(token.as_str()), other),
+                (None, anonymous),
+            ] {
+                let expected = if visible {
+                    StatusCode::OK
+                } else {
+                    StatusCode::NOT_FOUND
+                };
+
+                assert_eq!(
+                    request(db.clone(), token).await,
+                    [expected; 2],
+                    "{visibility:?}, deployed: {deployed}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn code_without_build_sessions() {
+        let db = Arc::new(create_database().await);
+
+        create_code(&db, false).await;
+
+        let response = crate::app_router(db, Arc::new(Config::for_tests()))
+            .oneshot(
+                Request::builder()
+                    .method("GET")
+                    .uri(format!("/buildSessions/wasm/{}", hex::encode([0; 32])))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
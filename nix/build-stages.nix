@@ -37,8 +37,8 @@ in {
 
     ${unzip} $dst
 
-    shopt -s globstar
-    for i in **/*.rs; do
+    shopt -s globstar nullglob
+    for i in **/*.rs **/Cargo.toml; do
       ${curl} -f "$API_SERVER_URL"/files/upload/"$BUILD_SESSION_TOKEN" \
         -F "$i"="@$i"
     done
@@ -47,15 +47,14 @@ in {
       -X POST
   '');
 
-  move = mkStageImage "move" (let
-    mkMove = extension: ''
-      find /contract/target/ink \
-        -maxdepth 2 \
-        -type f \
-        -name "*.${extension}" \
-        -not -path "*/.*" \
-        -exec mv {} /contract/target/ink/main.${extension} \;
-    '';
-  in
-    pkgs.lib.concatStringsSep "\n" (map mkMove ["wasm" "json"]));
+  # List build artifacts of all contracts, which are then selected by the builder
+  # according to the build session project directory.
+  move = mkStageImage "move" ''
+    find /contract \
+      -path "*/target/ink/*" \
+      -type f \
+      \( -name "*.wasm" -o -name "*.polkavm" -o -name "*.json" \) \
+      -not -path "*/.*" \
+      > /contract/.artifacts
+  '';
 }